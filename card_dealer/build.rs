@@ -0,0 +1,10 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Generate the gRPC stubs only when the server is enabled; protoc is
+    // vendored so contributors don't need it installed.
+    #[cfg(feature = "grpc")]
+    {
+        std::env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path()?);
+        tonic_build::compile_protos("proto/dealer.proto")?;
+    }
+    Ok(())
+}
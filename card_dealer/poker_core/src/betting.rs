@@ -0,0 +1,383 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::bounty::Elimination;
+use crate::card_dealer::Card;
+use crate::chips::Chips;
+use crate::events::GameEvent;
+use crate::player::PlayerAction;
+use crate::player_id::PlayerId;
+use crate::variant::GameVariant;
+
+/// The betting rounds of a hand, in order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(rename_all = "snake_case"))]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub enum Street {
+    Preflop,
+    Flop,
+    Turn,
+    River,
+}
+
+impl Street {
+    /// Community cards showing during this street.
+    pub fn board_cards(self) -> usize {
+        match self {
+            Street::Preflop => 0,
+            Street::Flop => 3,
+            Street::Turn => 4,
+            Street::River => 5,
+        }
+    }
+
+    pub fn next(self) -> Option<Street> {
+        match self {
+            Street::Preflop => Some(Street::Flop),
+            Street::Flop => Some(Street::Turn),
+            Street::Turn => Some(Street::River),
+            Street::River => None,
+        }
+    }
+}
+
+/// Where the betting of the current hand stands.
+///
+/// Owned by `GameController`, which updates it as players act; read it to
+/// show a player what they owe and what they may do.
+#[derive(Debug, Clone)]
+pub struct BettingState {
+    pub street: Street,
+    pub seats: Vec<PlayerId>,               // Player ids dealt in, button first
+    pub small_blind: Chips,
+    pub big_blind: Chips,
+    pub current_bet: Chips,                 // Street total every player has to match
+    pub min_raise: Chips,                   // Smallest raise allowed on top of `current_bet`
+    pub straddle: Option<PlayerId>,         // Under the gun, if they posted a straddle; acts last preflop
+    pub(crate) street_bets: HashMap<PlayerId, Chips>,
+    pub(crate) streets: Vec<StreetBets>,    // Everything put in, street by street, the current street last
+    pub(crate) antes: HashMap<PlayerId, Chips>, // Dead money in the main pot, not counted towards anyone's bets
+    pub(crate) to_act: VecDeque<PlayerId>,  // Players still to act this street, next first
+    pub(crate) runout_vote: Option<RunoutVote>, // Open while the players all in pick how often to run the board
+    pub(crate) runs: Option<usize>,         // Times the rest of the board is dealt, once agreed
+    pub(crate) insurance_priced: bool,      // Whether the all-in was looked at for insurance, whoever it was offered to
+    pub(crate) insurance: Option<InsuranceOffer>,
+}
+
+impl BettingState {
+    pub(crate) fn new(seats: Vec<PlayerId>, small_blind: Chips, big_blind: Chips) -> Self {
+        Self {
+            street: Street::Preflop,
+            seats,
+            small_blind,
+            big_blind,
+            current_bet: Chips::ZERO,
+            min_raise: big_blind,
+            straddle: None,
+            street_bets: HashMap::new(),
+            streets: vec![StreetBets::new(Street::Preflop)],
+            antes: HashMap::new(),
+            to_act: VecDeque::new(),
+            runout_vote: None,
+            runs: None,
+            insurance_priced: false,
+            insurance: None,
+        }
+    }
+
+    /// The player whose turn it is, if betting is open.
+    pub fn to_act(&self) -> Option<&str> {
+        self.to_act.front().map(PlayerId::as_str)
+    }
+
+    /// Chips `player_id` has put in on this street.
+    pub fn street_bet(&self, player_id: &str) -> Chips {
+        self.street_bets.get(player_id).copied().unwrap_or_default()
+    }
+
+    /// What was put in on each street so far, preflop first. Blinds and
+    /// the ante count towards preflop.
+    pub fn streets(&self) -> &[StreetBets] {
+        &self.streets
+    }
+
+    /// Adds `amount` to what `player_id` put in on the current street.
+    pub(crate) fn add_to_street(&mut self, player_id: &PlayerId, amount: Chips) {
+        if let Some(street) = self.streets.last_mut() {
+            street.add(player_id, amount);
+        }
+    }
+
+    /// Chips `player_id` must add to stay in, before capping at their stack.
+    pub fn owed(&self, player_id: &str) -> Chips {
+        self.current_bet.saturating_sub(self.street_bet(player_id))
+    }
+
+    /// Smallest street total a raise may go to.
+    pub fn min_raise_to(&self) -> Chips {
+        self.current_bet + self.min_raise
+    }
+
+    /// True when only two players were dealt in. Heads-up the button posts
+    /// the small blind and acts first preflop, but still acts last after the
+    /// flop.
+    pub fn is_heads_up(&self) -> bool {
+        self.seats.len() == 2
+    }
+
+    /// The players posting the small and big blinds.
+    pub fn blinds(&self) -> (&PlayerId, &PlayerId) {
+        if self.is_heads_up() {
+            (&self.seats[0], &self.seats[1])
+        } else {
+            (&self.seats[1], &self.seats[2])
+        }
+    }
+
+    /// Seats in preflop acting order: after the big blind, big blind last,
+    /// or after the straddle, straddle last. Heads-up that starts with the
+    /// button.
+    pub(crate) fn preflop_order(&self) -> impl Iterator<Item = &PlayerId> {
+        let (_, big) = self.blinds();
+        let last = self.straddle.as_ref().unwrap_or(big);
+        self.after(last).chain(Some(last))
+    }
+
+    /// The player under the gun, first to act preflop unless they straddle.
+    /// `None` heads-up, where the button acts first.
+    pub fn under_the_gun(&self) -> Option<&PlayerId> {
+        if self.is_heads_up() {
+            return None;
+        }
+        let (_, big) = self.blinds();
+        self.after(big).next()
+    }
+
+    /// Seats in acting order after the flop: after the button, button last.
+    /// Heads-up that starts with the big blind.
+    pub(crate) fn after_button(&self) -> impl Iterator<Item = &PlayerId> {
+        self.seats.iter().skip(1).chain(self.seats.first())
+    }
+
+    /// Seats in order after `player_id`, ending just before them.
+    pub(crate) fn after(&self, player_id: &str) -> impl Iterator<Item = &PlayerId> {
+        let index = self.seats.iter().position(|seat| seat == player_id).unwrap_or(0);
+        self.seats.iter().skip(index + 1).chain(self.seats.iter().take(index))
+    }
+}
+
+/// The chips put into a hand on one street.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreetBets {
+    pub street: Street,
+    pub bets: Vec<(PlayerId, Chips)>, // What each player put in on the street; players who put in nothing are left out
+}
+
+impl StreetBets {
+    pub fn new(street: Street) -> Self {
+        Self { street, bets: Vec::new() }
+    }
+
+    /// Chips everyone put in on the street together.
+    pub fn total(&self) -> Chips {
+        self.bets.iter().map(|(_, amount)| *amount).sum()
+    }
+
+    /// Chips `player_id` put in on the street.
+    pub fn bet(&self, player_id: &str) -> Chips {
+        self.bets.iter().find(|(id, _)| id == player_id).map_or(Chips::ZERO, |(_, amount)| *amount)
+    }
+
+    pub(crate) fn add(&mut self, player_id: &PlayerId, amount: Chips) {
+        if amount.is_zero() {
+            return;
+        }
+        match self.bets.iter_mut().find(|(id, _)| id == player_id) {
+            Some((_, total)) => *total += amount,
+            None => self.bets.push((player_id.clone(), amount)),
+        }
+    }
+}
+
+/// The players left in a hand that is all in before the river, each
+/// choosing how many times to run the rest of the board.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunoutVote {
+    pub max_runs: usize,
+    pub choices: Vec<(PlayerId, Option<usize>)>, // Each player's choice, in seat order; `None` until they choose
+}
+
+impl RunoutVote {
+    pub(crate) fn new(players: Vec<PlayerId>, max_runs: usize) -> Self {
+        Self { max_runs, choices: players.into_iter().map(|player_id| (player_id, None)).collect() }
+    }
+
+    /// Times the board runs once everyone has chosen: the fewest anyone
+    /// asked for, as running it more than once needs every player's consent.
+    pub fn agreed(&self) -> Option<usize> {
+        self.choices.iter().map(|(_, runs)| *runs).collect::<Option<Vec<usize>>>()?.into_iter().min()
+    }
+}
+
+/// All-in insurance offered to the favourite: they pay `premium`, and are
+/// paid back `coverage` if they lose the pots they are in against someone
+/// else, or the part of it they don't win on a split.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InsuranceOffer {
+    pub player_id: PlayerId,
+    pub equity: f64,          // Exact share of those pots the player wins, over every way the board can come
+    pub coverage: Chips,      // The pots insured
+    pub premium: Chips,       // What the insurance pays out on average, rounded up
+    pub bought: Option<bool>, // `None` until the player decides
+}
+
+/// One action taken in a hand.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoggedAction {
+    pub street: Street,
+    pub player_id: PlayerId,
+    pub action: PlayerAction,   // Calls costing nothing are logged as checks, sitting out as a fold
+    pub hand_total: Chips,      // Chips the player had put into the hand once the action was taken
+}
+
+/// What happened in a hand started with `GameController::start_hand`, in
+/// order. Kept after the hand ends until the next one starts.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HandLog {
+    pub hand_number: u64,
+    pub variant: GameVariant,
+    pub seats: Vec<PlayerId>,       // Player ids dealt in, button first
+    pub players: Vec<LoggedPlayer>, // The same players as they sat down to the hand
+    pub small_blind: Chips,
+    pub big_blind: Chips,
+    pub ante: Chips,                // Big blind ante, posted by the big blind once the blind is covered; zero when none
+    pub straddle: Option<(PlayerId, Chips)>, // Under the gun and the straddle they posted, if any
+    pub hole_cards: HashMap<PlayerId, Vec<Card>>, // Cards dealt to each player; private to them until shown
+    pub actions: Vec<LoggedAction>, // Every action after the blinds were posted
+    pub board: Vec<Card>,
+    pub extra_boards: Vec<Vec<Card>>, // Boards after the first in multi-board variants
+    pub eliminations: Vec<Elimination>, // Players dealt in who lost every chip, once the hand has finished
+    pub all_in: Vec<AllInShare>,    // Each live player's stake, if the betting ended all-in before the river
+    pub payouts: Vec<(PlayerId, Chips)>, // What the pots paid, main pot first, once the hand has finished
+    pub rake: Chips,                // Kept by the house out of the pots, once the hand has finished
+    pub streets: Vec<StreetBets>,   // What was put in on each street dealt, once the hand has finished
+    pub showdown: Vec<PlayerId>,    // Players who showed their cards, if more than one was left at the end
+    pub shown: Vec<(PlayerId, Vec<Card>)>, // Cards a winner without a showdown chose to show afterwards
+    pub finished: bool,
+}
+
+impl HandLog {
+    /// Replays the hand as the public events a table would have published:
+    /// the start, each action, the board as each street is dealt and, once
+    /// finished, who won. Pots and busted players aren't reconstructed.
+    pub fn events(&self) -> Vec<GameEvent> {
+        let hand_number = self.hand_number;
+        let mut events = vec![GameEvent::HandStarted { hand_number, players: self.seats.clone() }];
+        let mut street = Street::Preflop;
+        let mut deal_until = |until: Street, events: &mut Vec<GameEvent>| {
+            while let Some(next) = street.next().filter(|next| *next <= until && next.board_cards() <= self.board.len()) {
+                events.push(GameEvent::BoardDealt { hand_number, cards: self.board[..next.board_cards()].to_vec() });
+                street = next;
+            }
+        };
+        for action in &self.actions {
+            deal_until(action.street, &mut events);
+            events.push(GameEvent::PlayerActed { hand_number, player_id: action.player_id.clone(), action: action.action.clone() });
+        }
+        deal_until(Street::River, &mut events); // Streets run out once everyone was all-in
+        if self.finished {
+            let mut winners: Vec<PlayerId> = Vec::new();
+            for (player_id, _) in &self.payouts {
+                if !winners.contains(player_id) {
+                    winners.push(player_id.clone());
+                }
+            }
+            events.push(GameEvent::HandFinished { hand_number, winners });
+            for (player_id, cards) in &self.shown {
+                events.push(GameEvent::CardsShown { hand_number, player_id: player_id.clone(), cards: cards.clone() });
+            }
+        }
+        events
+    }
+}
+
+/// A player dealt into a logged hand, before the blinds were posted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoggedPlayer {
+    pub player_id: PlayerId,
+    pub display_name: String,
+    pub table_position: usize,
+    pub stack: Chips,
+}
+
+/// A player's stake in the pots once nobody could bet any more before the
+/// river, priced on equity.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AllInShare {
+    pub player_id: PlayerId,
+    pub equity: f64,        // Expected share of all the pots, in `0..=1`
+    pub expected: f64,      // Chips the pots would pay the player on average
+    pub won: Chips,         // Chips the pots did pay, once the hand has finished
+}
+
+/// Splits each player's chips for the hand into a main pot and side pots.
+///
+/// Takes `(player_id, chips, still_live)` and returns `(amount, eligible
+/// player ids)` per pot, main pot first. Folded players' chips count towards
+/// the pots but they are eligible for none.
+pub fn side_pots(contributions: &[(PlayerId, Chips, bool)]) -> Vec<(Chips, Vec<PlayerId>)> {
+    let mut remaining = contributions.to_vec();
+    let mut pots: Vec<(Chips, Vec<PlayerId>)> = Vec::new();
+    while let Some(level) = remaining.iter().filter(|(_, chips, live)| *live && !chips.is_zero()).map(|(_, chips, _)| *chips).min() {
+        let eligible = remaining.iter().filter(|(_, chips, live)| *live && *chips >= level).map(|(id, _, _)| id.clone()).collect();
+        let mut amount = Chips::ZERO;
+        for (_, chips, _) in &mut remaining {
+            let taken = (*chips).min(level);
+            *chips -= taken;
+            amount += taken;
+        }
+        pots.push((amount, eligible));
+    }
+    // Folded chips above every live player's contribution go to the last pot
+    let leftover: Chips = remaining.iter().map(|(_, chips, _)| chips).sum();
+    if let Some((amount, _)) = pots.last_mut() {
+        *amount += leftover;
+    }
+    pots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_side_pots() {
+        let contributions = vec![
+            (PlayerId::from("short"), Chips::new(50), true),
+            (PlayerId::from("deep"), Chips::new(200), true),
+            (PlayerId::from("caller"), Chips::new(200), true),
+            (PlayerId::from("folded"), Chips::new(20), false),
+        ];
+        let pots = side_pots(&contributions);
+        assert_eq!(pots[0], (Chips::new(170), vec![PlayerId::from("short"), "deep".into(), "caller".into()]));
+        assert_eq!(pots[1], (Chips::new(300), vec![PlayerId::from("deep"), "caller".into()]));
+        assert_eq!(pots.len(), 2);
+    }
+
+    #[test]
+    fn test_acting_order() {
+        let state = BettingState::new(vec!["a".into(), "b".into(), "c".into()], Chips::new(5), Chips::new(10));
+        assert_eq!(state.after_button().cloned().collect::<Vec<_>>(), ["b", "c", "a"]);
+        assert_eq!(state.after("b").cloned().collect::<Vec<_>>(), ["c", "a"]);
+        assert_eq!(state.preflop_order().cloned().collect::<Vec<_>>(), ["a", "b", "c"]);
+        assert!(!state.is_heads_up());
+
+        // Heads-up the button is the small blind, first in preflop and last after
+        let state = BettingState::new(vec!["a".into(), "b".into()], Chips::new(5), Chips::new(10));
+        assert!(state.is_heads_up());
+        assert_eq!(state.blinds(), (&PlayerId::from("a"), &PlayerId::from("b")));
+        assert_eq!(state.preflop_order().cloned().collect::<Vec<_>>(), ["a", "b"]);
+        assert_eq!(state.after_button().cloned().collect::<Vec<_>>(), ["b", "a"]);
+        assert_eq!(Street::Turn.next(), Some(Street::River));
+        assert_eq!(Street::Flop.board_cards(), 3);
+    }
+}
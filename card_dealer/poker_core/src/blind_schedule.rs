@@ -0,0 +1,110 @@
+//! Tournament blind levels on a clock: each level lasts a fixed time and may
+//! be followed by a break. Only the arithmetic lives here; whoever runs the
+//! clock tells the `GameController` when the blinds go up.
+
+use std::time::Duration;
+
+use crate::chips::Chips;
+
+/// One level of a blind schedule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct BlindLevel {
+    pub small_blind: Chips,
+    pub big_blind: Chips,
+    pub duration_secs: u64,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub break_secs: u64,        // Break after this level; none when zero
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub ante: Chips,            // Big blind ante, posted by the big blind for the whole table; none when zero
+}
+
+/// Blind levels in the order they are played. The last level lasts until
+/// the tournament ends.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct BlindSchedule {
+    pub levels: Vec<BlindLevel>,
+}
+
+/// Where the clock stands at some moment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClockState {
+    pub level: usize,                   // Starts at 1
+    pub small_blind: Chips,
+    pub big_blind: Chips,
+    pub ante: Chips,                    // Big blind ante; zero when the level has none
+    pub on_break: bool,                 // The break after `level` is being taken
+    pub remaining: Option<Duration>,    // Until the level or break ends; None on the last level
+}
+
+impl BlindSchedule {
+    /// The clock `elapsed` after the first level started, or `None` for an
+    /// empty schedule.
+    pub fn at(&self, elapsed: Duration) -> Option<ClockState> {
+        let last = self.levels.len().checked_sub(1)?;
+        let mut start = Duration::ZERO;
+        for (index, level) in self.levels.iter().enumerate() {
+            let state = |on_break, remaining| ClockState {
+                level: index + 1,
+                small_blind: level.small_blind,
+                big_blind: level.big_blind,
+                ante: level.ante,
+                on_break,
+                remaining,
+            };
+            if index == last {
+                return Some(state(false, None));
+            }
+            let level_end = start + Duration::from_secs(level.duration_secs);
+            if elapsed < level_end {
+                return Some(state(false, Some(level_end - elapsed)));
+            }
+            let break_end = level_end + Duration::from_secs(level.break_secs);
+            if elapsed < break_end {
+                return Some(state(true, Some(break_end - elapsed)));
+            }
+            start = break_end;
+        }
+        None
+    }
+
+    /// Checks every level has blinds a hand can be started with.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.levels.is_empty() {
+            return Err("A blind schedule needs at least one level".to_string());
+        }
+        for (index, level) in self.levels.iter().enumerate() {
+            if level.small_blind.is_zero() || level.big_blind < level.small_blind {
+                return Err(format!("Blind level {} must have positive blinds with the big blind at least the small blind", index + 1));
+            }
+            if level.duration_secs == 0 && index + 1 < self.levels.len() {
+                return Err(format!("Blind level {} must last at least a second", index + 1));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn level(small_blind: u64, duration_secs: u64, break_secs: u64) -> BlindLevel {
+        BlindLevel { small_blind: Chips::new(small_blind), big_blind: Chips::new(small_blind * 2), duration_secs, break_secs, ante: Chips::new(small_blind * 2) }
+    }
+
+    #[test]
+    fn test_levels_and_breaks() {
+        let schedule = BlindSchedule { levels: vec![level(5, 600, 0), level(10, 600, 300), level(25, 600, 0)] };
+        let at = |secs| schedule.at(Duration::from_secs(secs)).unwrap();
+        assert_eq!((at(0).level, at(0).remaining), (1, Some(Duration::from_secs(600))));
+        assert_eq!((at(650).big_blind, at(650).ante, at(650).on_break), (Chips::new(20), Chips::new(20), false));
+        assert_eq!((at(1250).level, at(1250).on_break, at(1250).remaining), (2, true, Some(Duration::from_secs(250))));
+        assert_eq!((at(1500).level, at(1500).small_blind, at(1500).remaining), (3, Chips::new(25), None)); // The last level never ends
+        assert!(BlindSchedule::default().at(Duration::ZERO).is_none());
+        assert!(BlindSchedule { levels: vec![level(5, 0, 0), level(10, 600, 0)] }.validate().is_err());
+    }
+}
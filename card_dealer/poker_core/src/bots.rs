@@ -0,0 +1,435 @@
+//! Computer players. A `BotStrategy` seated with `GameController::seat_bot`
+//! is asked for its action whenever it is that seat's turn.
+
+use std::cmp::Ordering;
+
+use rand::seq::SliceRandom;
+use rand::{Rng, RngCore};
+
+use crate::betting::Street;
+use crate::card_dealer::{Card, Rank};
+use crate::card_set::CardSet;
+use crate::chips::Chips;
+use crate::player_id::PlayerId;
+use crate::player::PlayerAction;
+use crate::poker_hand::{hand_value, HandRank};
+use crate::preflop_chart::{ChartAdvice, Position, PreflopChart};
+
+/// What a bot can see when it is asked to act: its own cards and the public
+/// state of the hand.
+#[derive(Debug, Clone)]
+pub struct ActionContext {
+    pub player_id: PlayerId,
+    pub hole_cards: Vec<Card>,
+    pub board: Vec<Card>,
+    pub street: Street,
+    pub stack: Chips,        // Chips behind, not counting this street's bet
+    pub to_call: Chips,      // Chips owed to stay in, capped at the stack
+    pub street_bet: Chips,   // Chips already put in on this street
+    pub current_bet: Chips,  // Street total to match
+    pub min_raise_to: Chips, // Smallest street total a raise may go to
+    pub big_blind: Chips,
+    pub pot: Chips,          // Every chip committed to the hand so far
+    pub effective_stack: Chips, // Chips behind that can still be won or lost: the stack, or the biggest opponent's if smaller
+    pub opponents: usize,    // Other players still holding cards
+    pub players_behind: usize, // Players in the hand acting after this one from the flop on
+    pub position: Position,  // Where the seat acts from preflop
+}
+
+impl ActionContext {
+    /// The bet or raise taking this street's total to `total`, clamped to what
+    /// the rules and the stack allow. Falls back to a call when the player
+    /// can't raise at all.
+    pub fn raise_to(&self, total: Chips) -> PlayerAction {
+        let all_in = self.street_bet + self.stack;
+        if all_in <= self.current_bet {
+            return PlayerAction::Call;
+        }
+        let minimum = if self.current_bet.is_zero() { self.big_blind } else { self.min_raise_to };
+        let total = total.max(minimum).min(all_in);
+        if self.current_bet.is_zero() { PlayerAction::Bet(total) } else { PlayerAction::Raise(total - self.current_bet) }
+    }
+
+    /// The share of the pot after a call that the call pays for: calling is
+    /// break-even with at least this much equity. Zero when nothing is owed.
+    pub fn pot_odds(&self) -> f64 {
+        if self.to_call.is_zero() {
+            return 0.0;
+        }
+        self.to_call.get() as f64 / (self.pot + self.to_call).get() as f64
+    }
+
+    /// True preflop once someone has raised the big blind.
+    pub fn facing_raise(&self) -> bool {
+        self.current_bet > self.big_blind
+    }
+
+    /// Checks when that's free, folds otherwise.
+    pub fn check_or_fold(&self) -> PlayerAction {
+        if self.to_call.is_zero() { PlayerAction::Check } else { PlayerAction::Fold }
+    }
+}
+
+/// Decides how a computer player acts.
+pub trait BotStrategy: Send {
+    /// Short name shown for the bot, e.g. `"calling_station"`.
+    fn name(&self) -> &'static str;
+
+    /// Picks an action for the player described by `context`. An action the
+    /// engine rejects is replaced by a check, or a fold when facing a bet.
+    fn decide(&mut self, context: &ActionContext) -> PlayerAction;
+}
+
+/// Calls every bet and never raises.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CallingStation;
+
+impl BotStrategy for CallingStation {
+    fn name(&self) -> &'static str {
+        "calling_station"
+    }
+
+    fn decide(&mut self, _context: &ActionContext) -> PlayerAction {
+        PlayerAction::Call
+    }
+}
+
+/// A loose bot acting at random: mostly calls, folds a fifth of the time it
+/// faces a bet and raises now and then.
+pub struct RandomBot {
+    rng: Box<dyn RngCore + Send>,
+}
+
+impl RandomBot {
+    pub fn new(rng: impl RngCore + Send + 'static) -> Self {
+        Self { rng: Box::new(rng) }
+    }
+}
+
+impl BotStrategy for RandomBot {
+    fn name(&self) -> &'static str {
+        "random"
+    }
+
+    fn decide(&mut self, context: &ActionContext) -> PlayerAction {
+        let roll: f64 = self.rng.gen();
+        match () {
+            _ if !context.to_call.is_zero() && roll < 0.2 => PlayerAction::Fold,
+            _ if roll > 0.85 => {
+                // Raise between the minimum and three times it
+                let minimum = context.min_raise_to.max(context.big_blind);
+                context.raise_to(Chips::new(self.rng.gen_range(minimum.get()..=minimum.get() * 3)))
+            }
+            _ => PlayerAction::Call,
+        }
+    }
+}
+
+/// Plays few hands but bets them hard: preflop it follows the built-in
+/// `PreflopChart` for its position, and after the flop only continues with
+/// a pair using a hole card or better.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TightAggressive;
+
+impl BotStrategy for TightAggressive {
+    fn name(&self) -> &'static str {
+        "tight_aggressive"
+    }
+
+    fn decide(&mut self, context: &ActionContext) -> PlayerAction {
+        if context.street == Street::Preflop {
+            return match PreflopChart::builtin().advise(context.position, &context.hole_cards, context.facing_raise()) {
+                ChartAdvice::Raise => context.raise_to(context.current_bet.max(context.big_blind) * 3),
+                ChartAdvice::Call => PlayerAction::Call,
+                ChartAdvice::Fold => context.check_or_fold(),
+            };
+        }
+        match made_hand(&context.hole_cards, &context.board) {
+            rank if rank >= HandRank::TwoPair => context.raise_to(context.current_bet + context.pot * 2 / 3),
+            HandRank::OnePair if context.to_call <= context.pot / 2 => PlayerAction::Call,
+            _ => context.check_or_fold(),
+        }
+    }
+}
+
+/// How strong a `RuleBasedBot` plays.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "snake_case"))]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Medium,
+    Hard,
+}
+
+impl Difficulty {
+    /// The tuning this preset stands for.
+    pub fn config(self) -> RuleBasedConfig {
+        match self {
+            // Rough strength estimates, no notion of price or position, and frequent loose calls
+            Difficulty::Easy => RuleBasedConfig {
+                trials: 30,
+                raise_strength: 2.0,
+                call_margin: 0.0,
+                use_pot_odds: false,
+                position_bonus: 0.0,
+                bluff_frequency: 0.0,
+                mistake_rate: 0.15,
+            },
+            Difficulty::Medium => RuleBasedConfig {
+                trials: 150,
+                raise_strength: 1.6,
+                call_margin: 0.03,
+                use_pot_odds: true,
+                position_bonus: 0.0,
+                bluff_frequency: 0.03,
+                mistake_rate: 0.03,
+            },
+            Difficulty::Hard => RuleBasedConfig {
+                trials: 400,
+                raise_strength: 1.4,
+                call_margin: 0.0,
+                use_pot_odds: true,
+                position_bonus: 0.2,
+                bluff_frequency: 0.08,
+                mistake_rate: 0.0,
+            },
+        }
+    }
+}
+
+/// Tuning of a `RuleBasedBot`.
+///
+/// Strengths are equity relative to an even share of the pot: 1.0 means
+/// winning as often as an average hand against this many opponents.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleBasedConfig {
+    pub trials: u32,            // Run-outs simulated per decision to estimate equity
+    pub raise_strength: f64,    // Relative strength needed to bet or raise
+    pub call_margin: f64,       // Equity above the pot odds needed to call
+    pub use_pot_odds: bool,     // Without it, calls whenever equity is at least an even share
+    pub position_bonus: f64,    // Added to relative strength when acting last after the flop
+    pub bluff_frequency: f64,   // Chance of betting a weak hand when nobody has bet
+    pub mistake_rate: f64,      // Chance of just calling, whatever the hand
+}
+
+/// Bets and calls on estimated equity: raises strong hands, calls when the
+/// pot odds justify it and folds the rest. Tuned by a `RuleBasedConfig`,
+/// usually one of the `Difficulty` presets.
+pub struct RuleBasedBot {
+    config: RuleBasedConfig,
+    rng: Box<dyn RngCore + Send>,
+}
+
+impl RuleBasedBot {
+    pub fn new(config: RuleBasedConfig, rng: impl RngCore + Send + 'static) -> Self {
+        Self { config, rng: Box::new(rng) }
+    }
+
+    pub fn with_difficulty(difficulty: Difficulty, rng: impl RngCore + Send + 'static) -> Self {
+        Self::new(difficulty.config(), rng)
+    }
+
+    pub fn config(&self) -> &RuleBasedConfig {
+        &self.config
+    }
+}
+
+impl BotStrategy for RuleBasedBot {
+    fn name(&self) -> &'static str {
+        "rule_based"
+    }
+
+    fn decide(&mut self, context: &ActionContext) -> PlayerAction {
+        let config = &self.config;
+        if self.rng.gen_bool(config.mistake_rate.clamp(0.0, 1.0)) {
+            return PlayerAction::Call;
+        }
+        let equity = hand_strength(&context.hole_cards, &context.board, context.opponents, config.trials, &mut self.rng);
+        let even_share = 1.0 / (context.opponents + 1) as f64;
+        let mut strength = equity / even_share;
+        if context.players_behind == 0 {
+            strength += config.position_bonus;
+        }
+
+        if strength >= config.raise_strength {
+            // Bet between half the pot and the whole pot, more with stronger hands
+            let size = context.pot.get() as f64 * equity.clamp(0.5, 1.0);
+            return context.raise_to(context.current_bet + Chips::new(size as u64));
+        }
+        if context.to_call.is_zero() {
+            if self.rng.gen_bool(config.bluff_frequency.clamp(0.0, 1.0)) {
+                return context.raise_to(context.current_bet + context.pot / 2);
+            }
+            return PlayerAction::Check;
+        }
+        let needed = if config.use_pot_odds {
+            context.pot_odds() + config.call_margin
+        } else {
+            even_share
+        };
+        if equity >= needed { PlayerAction::Call } else { PlayerAction::Fold }
+    }
+}
+
+/// Estimates how often `hole_cards` win against `opponents` random hands by
+/// dealing out the rest of the board `trials` times. Split pots count as a
+/// fraction of a win. Returns a value in `0..=1`.
+pub fn hand_strength<R: Rng + ?Sized>(hole_cards: &[Card], board: &[Card], opponents: usize, trials: u32, rng: &mut R) -> f64 {
+    let ours_hole: CardSet = hole_cards.iter().copied().collect();
+    let board: CardSet = board.iter().copied().collect();
+    let mut deck: Vec<Card> = (CardSet::full() - ours_hole - board).iter().collect();
+    let runout = 5usize.saturating_sub(board.len());
+    let dealt = runout + 2 * opponents.max(1);
+    if trials == 0 || hole_cards.len() != 2 || dealt > deck.len() {
+        return 0.0;
+    }
+    let mut wins = 0.0;
+    for _ in 0..trials {
+        let (drawn, _) = deck.partial_shuffle(rng, dealt);
+        let (rest_of_board, opponent_cards) = drawn.split_at(runout);
+        let full_board = board | rest_of_board.iter().copied().collect();
+        let ours = hand_value(ours_hole | full_board);
+        let mut tied = 1;
+        let mut beaten = false;
+        for hand in opponent_cards.chunks(2) {
+            match hand_value(full_board | hand.iter().copied().collect()).cmp(&ours) {
+                Ordering::Greater => {
+                    beaten = true;
+                    break;
+                }
+                Ordering::Equal => tied += 1,
+                Ordering::Less => {}
+            }
+        }
+        if !beaten {
+            wins += 1.0 / f64::from(tied);
+        }
+    }
+    wins / f64::from(trials)
+}
+
+/// Scores two hole cards on the Chen formula, from about -1 (7-2 offsuit) to
+/// 20 (aces).
+pub fn preflop_strength(hole_cards: &[Card]) -> i32 {
+    let [first, second] = hole_cards else { return 0 };
+    let (high, low) = if first.rank >= second.rank { (first, second) } else { (second, first) };
+    // Scores are kept doubled so half points stay exact
+    let points = |rank: Rank| match rank {
+        Rank::Ace => 20,
+        Rank::King => 16,
+        Rank::Queen => 14,
+        Rank::Jack => 12,
+        rank => rank as i32 + 2, // Ten is 5 points, down to 1 for a deuce
+    };
+    let mut doubled = points(high.rank);
+    if high.rank == low.rank {
+        return (doubled * 2).max(10) / 2;
+    }
+    if high.suit == low.suit {
+        doubled += 4;
+    }
+    let gap = high.rank as i32 - low.rank as i32 - 1;
+    doubled -= match gap {
+        0 => 0,
+        1 => 2,
+        2 => 4,
+        3 => 8,
+        _ => 10,
+    };
+    if gap <= 1 && high.rank < Rank::Queen {
+        doubled += 2;
+    }
+    (doubled + 1).div_euclid(2) // Round half points up
+}
+
+/// The rank of the best hand, not counting a pair that is only on the board.
+fn made_hand(hole_cards: &[Card], board: &[Card]) -> HandRank {
+    let Some(value) = hand_value(hole_cards.iter().chain(board).copied().collect()) else {
+        return HandRank::HighCard;
+    };
+    let rank = value.rank();
+    if rank == HandRank::OnePair {
+        let paired = hole_cards.iter().any(|hole| hole_cards.iter().chain(board).filter(|card| card.rank == hole.rank).count() >= 2);
+        if !paired {
+            return HandRank::HighCard;
+        }
+    }
+    rank
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card_dealer::parse_cards;
+
+    fn context(hole: &str, board: &str, to_call: u64) -> ActionContext {
+        let to_call = Chips::new(to_call);
+        ActionContext {
+            player_id: "bot".into(),
+            hole_cards: parse_cards(hole).unwrap(),
+            board: parse_cards(board).unwrap(),
+            street: if board.is_empty() { Street::Preflop } else { Street::Flop },
+            stack: Chips::new(1000),
+            to_call,
+            street_bet: Chips::ZERO,
+            current_bet: to_call,
+            min_raise_to: to_call * 2,
+            big_blind: Chips::new(10),
+            pot: Chips::new(30),
+            effective_stack: Chips::new(1000),
+            opponents: 2,
+            players_behind: 1,
+            position: Position::Button,
+        }
+    }
+
+    #[test]
+    fn test_preflop_strength() {
+        assert_eq!(preflop_strength(&parse_cards("As Ad").unwrap()), 20);
+        assert_eq!(preflop_strength(&parse_cards("Ks Qs").unwrap()), 10);
+        assert_eq!(preflop_strength(&parse_cards("5h 5c").unwrap()), 5);
+        assert_eq!(preflop_strength(&parse_cards("7c 2d").unwrap()), -1);
+    }
+
+    #[test]
+    fn test_tight_aggressive() {
+        let mut bot = TightAggressive;
+        assert_eq!(bot.decide(&context("Ah Ac", "", 10)), PlayerAction::Raise(Chips::new(20)));
+        assert!(matches!(bot.decide(&context("7c 2d", "", 10)), PlayerAction::Fold));
+        assert!(matches!(bot.decide(&context("7c 2d", "", 0)), PlayerAction::Check));
+        // Facing a raise, a hand worth opening may only be worth a call
+        assert!(matches!(bot.decide(&context("Kh 6h", "", 10)), PlayerAction::Raise(_)));
+        assert!(matches!(bot.decide(&context("9h 9c", "", 30)), PlayerAction::Call));
+        assert!(matches!(bot.decide(&context("Kh 6h", "", 30)), PlayerAction::Fold));
+        // A pair only on the board is no reason to call
+        assert!(matches!(bot.decide(&context("Ac Kd", "9s 9h 4c", 10)), PlayerAction::Fold));
+        assert!(matches!(bot.decide(&context("9c Kd", "9s Th 4c", 10)), PlayerAction::Call));
+        assert_eq!(bot.decide(&context("9c 4d", "9s Th 4c", 0)), PlayerAction::Bet(Chips::new(20)));
+    }
+
+    #[test]
+    fn test_raise_to_is_clamped() {
+        let mut context = context("Ah Ac", "", 10);
+        assert_eq!(context.raise_to(Chips::new(15)), PlayerAction::Raise(Chips::new(10)));
+        context.stack = Chips::new(5);
+        assert!(matches!(context.raise_to(Chips::new(100)), PlayerAction::Call));
+    }
+
+    #[test]
+    fn test_rule_based_bot() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+        let mut rng = StdRng::seed_from_u64(5);
+        let aces = hand_strength(&parse_cards("As Ah").unwrap(), &[], 1, 400, &mut rng);
+        assert!((0.75..0.95).contains(&aces), "{aces}");
+
+        let mut bot = RuleBasedBot::with_difficulty(Difficulty::Hard, rng);
+        assert!(matches!(bot.decide(&context("Ah Ac", "", 10)), PlayerAction::Raise(_)));
+        // Far too expensive a call for seven-deuce
+        let mut facing_raise = context("7c 2d", "", 100);
+        facing_raise.opponents = 1;
+        assert!(matches!(bot.decide(&facing_raise), PlayerAction::Fold));
+    }
+}
@@ -0,0 +1,72 @@
+//! Knockout tournaments: every entrant carries a bounty, paid to whoever
+//! eliminates them.
+
+use crate::chips::Chips;
+use crate::player_id::PlayerId;
+
+/// How a collected bounty is paid out.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "snake_case"))]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub enum BountyFormat {
+    #[default]
+    Knockout,      // The eliminator is paid the whole bounty
+    Progressive,   // Half is paid, half goes onto the eliminator's own bounty
+}
+
+/// The bounty each entrant starts with and how it is paid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct BountyRules {
+    pub bounty: Chips,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub format: BountyFormat,
+}
+
+/// A player knocked out in a hand and who gets their bounty.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Elimination {
+    pub player_id: PlayerId,
+    pub eliminated_by: Vec<PlayerId>,   // Winners of the pot holding the player's last chips
+}
+
+impl BountyRules {
+    /// Splits `bounty` between `eliminators` and returns, for each of them,
+    /// `(paid out, added to their own bounty)`. Odd chips go to the first
+    /// eliminators.
+    pub fn split(&self, bounty: Chips, eliminators: usize) -> Vec<(Chips, Chips)> {
+        if eliminators == 0 {
+            return Vec::new();
+        }
+        let share = bounty / eliminators as u64;
+        let odd = (bounty % eliminators as u64).get();
+        (0..eliminators as u64)
+            .map(|index| share + Chips::from(u32::from(index < odd)))
+            .map(|amount| match self.format {
+                BountyFormat::Knockout => (amount, Chips::ZERO),
+                BountyFormat::Progressive => {
+                    let kept = amount / 2; // The odd chip is paid out
+                    (amount - kept, kept)
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bounty_split() {
+        let knockout = BountyRules { bounty: Chips::new(100), format: BountyFormat::Knockout };
+        assert_eq!(knockout.split(Chips::new(100), 1), vec![(Chips::new(100), Chips::ZERO)]);
+        assert_eq!(knockout.split(Chips::new(101), 2), vec![(Chips::new(51), Chips::ZERO), (Chips::new(50), Chips::ZERO)]);
+
+        let progressive = BountyRules { format: BountyFormat::Progressive, ..knockout };
+        assert_eq!(progressive.split(Chips::new(150), 1), vec![(Chips::new(75), Chips::new(75))]);
+        assert_eq!(progressive.split(Chips::new(75), 1), vec![(Chips::new(38), Chips::new(37))]);
+        assert!(progressive.split(Chips::new(75), 0).is_empty());
+    }
+}
@@ -0,0 +1,116 @@
+//! The rules for bringing chips to a table: a buy-in or top-up between
+//! hands, a rebuy once busted, and a tournament add-on.
+
+use crate::chips::Chips;
+use crate::game_error::GameError;
+use crate::player::Player;
+
+/// How a player is adding chips.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "snake_case"))]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub enum BuyInKind {
+    BuyIn,   // Buys in or tops up between hands
+    Rebuy,   // Buys back in after losing every chip
+    AddOn,   // The one-off tournament add-on
+}
+
+/// A tournament add-on: a fixed amount each player may buy once, until the
+/// window closes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct AddOn {
+    pub amount: Chips,
+    pub until_hand: u64,   // Last hand number the add-on can be bought in
+}
+
+/// Limits on what players may buy in for at a table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct BuyInRules {
+    pub min_buy_in: Chips,          // Smallest stack a buy-in or rebuy may leave a player with
+    pub max_buy_in: Chips,          // Largest stack a buy-in or rebuy may leave a player with
+    pub max_rebuys: Option<u32>,    // Rebuys each player may make; unlimited when unset
+    pub add_on: Option<AddOn>,      // No add-on is offered when unset
+}
+
+impl Default for BuyInRules {
+    fn default() -> Self {
+        Self { min_buy_in: Chips::new(200), max_buy_in: Chips::new(2000), max_rebuys: None, add_on: None }
+    }
+}
+
+impl BuyInRules {
+    /// Checks that `player` may add `amount` chips as `kind` during hand
+    /// `hand_number`. Whether a hand is in progress is up to the caller.
+    pub fn check(&self, kind: BuyInKind, player: &Player, amount: Chips, hand_number: u64) -> Result<(), GameError> {
+        if amount.is_zero() {
+            return Err(GameError::InvalidRequest("buy in for at least one chip".to_string()));
+        }
+        let stack = player.chip_stack.checked_add(amount)
+            .ok_or_else(|| GameError::InvalidRequest("stack would overflow".to_string()))?;
+        match kind {
+            BuyInKind::BuyIn | BuyInKind::Rebuy => {
+                if kind == BuyInKind::Rebuy {
+                    if !player.chip_stack.is_zero() {
+                        return Err(GameError::NotAllowed("only a player with no chips left can rebuy".to_string()));
+                    }
+                    if self.max_rebuys.is_some_and(|max| player.rebuys >= max) {
+                        return Err(GameError::NotAllowed("no rebuys left".to_string()));
+                    }
+                }
+                if stack < self.min_buy_in || stack > self.max_buy_in {
+                    return Err(GameError::InvalidRequest(format!(
+                        "stack after buying in must be between {} and {}", self.min_buy_in, self.max_buy_in
+                    )));
+                }
+            }
+            BuyInKind::AddOn => {
+                let add_on = self.add_on.ok_or_else(|| GameError::NotAllowed("this table has no add-on".to_string()))?;
+                if hand_number > add_on.until_hand {
+                    return Err(GameError::NotAllowed(format!("the add-on closed after hand {}", add_on.until_hand)));
+                }
+                if player.took_add_on {
+                    return Err(GameError::NotAllowed("the add-on can only be taken once".to_string()));
+                }
+                if amount != add_on.amount {
+                    return Err(GameError::InvalidRequest(format!("the add-on is {} chips", add_on.amount)));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn player(chip_stack: u64) -> Player {
+        Player::new("1".into(), "Alice".to_string(), 0, Chips::new(chip_stack))
+    }
+
+    #[test]
+    fn test_buy_in_limits() {
+        let rules = BuyInRules { max_rebuys: Some(1), add_on: Some(AddOn { amount: Chips::new(500), until_hand: 10 }), ..BuyInRules::default() };
+        assert!(rules.check(BuyInKind::BuyIn, &player(0), Chips::new(1000), 0).is_ok());
+        assert!(rules.check(BuyInKind::BuyIn, &player(0), Chips::new(100), 0).is_err()); // Below the minimum
+        assert!(rules.check(BuyInKind::BuyIn, &player(1500), Chips::new(1000), 0).is_err()); // Above the maximum
+        assert!(rules.check(BuyInKind::BuyIn, &player(1500), Chips::new(500), 0).is_ok()); // Topping up to the maximum
+
+        assert!(matches!(rules.check(BuyInKind::Rebuy, &player(10), Chips::new(1000), 0), Err(GameError::NotAllowed(_))));
+        let mut busted = player(0);
+        assert!(rules.check(BuyInKind::Rebuy, &busted, Chips::new(1000), 0).is_ok());
+        busted.rebuys = 1;
+        assert!(matches!(rules.check(BuyInKind::Rebuy, &busted, Chips::new(1000), 0), Err(GameError::NotAllowed(_))));
+
+        assert!(rules.check(BuyInKind::AddOn, &player(1500), Chips::new(500), 10).is_ok()); // Not capped by the maximum
+        assert!(rules.check(BuyInKind::AddOn, &player(1500), Chips::new(400), 10).is_err());
+        assert!(rules.check(BuyInKind::AddOn, &player(1500), Chips::new(500), 11).is_err());
+        let mut added = player(2000);
+        added.took_add_on = true;
+        assert!(rules.check(BuyInKind::AddOn, &added, Chips::new(500), 10).is_err());
+    }
+}
@@ -0,0 +1,456 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::card_set::CardSet;
+use crate::game_error::GameError;
+
+// Card enums
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub enum Suit {
+    Hearts,
+    Diamonds,
+    Clubs,
+    Spades,
+    Joker,      // Only `Card::JOKER` has it; not one of the four suits in `Suit::ALL`
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub enum Rank {
+    Two,
+    Three,
+    Four,
+    Five,
+    Six,
+    Seven,
+    Eight,
+    Nine,
+    Ten,
+    Jack,
+    Queen,
+    King,
+    Ace,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct Card {
+    pub rank: Rank,
+    pub suit: Suit,
+}
+
+impl Card {
+    /// The joker, written `Jk`. Only dealt with wild-card rules that add it
+    /// to the deck.
+    pub const JOKER: Card = Card { rank: Rank::Jack, suit: Suit::Joker };
+
+    pub fn is_joker(self) -> bool {
+        self.suit == Suit::Joker
+    }
+}
+
+impl Suit {
+    pub const ALL: [Suit; 4] = [Suit::Hearts, Suit::Diamonds, Suit::Clubs, Suit::Spades];
+
+    /// Lowercase letter used in card notation, e.g. `h` for hearts.
+    fn letter(self) -> char {
+        match self {
+            Suit::Hearts => 'h',
+            Suit::Diamonds => 'd',
+            Suit::Clubs => 'c',
+            Suit::Spades => 's',
+            Suit::Joker => 'k',
+        }
+    }
+}
+
+impl Rank {
+    /// Every rank, lowest first.
+    pub const ALL: [Rank; 13] = [
+        Rank::Two,
+        Rank::Three,
+        Rank::Four,
+        Rank::Five,
+        Rank::Six,
+        Rank::Seven,
+        Rank::Eight,
+        Rank::Nine,
+        Rank::Ten,
+        Rank::Jack,
+        Rank::Queen,
+        Rank::King,
+        Rank::Ace,
+    ];
+
+    /// Character used in card notation, e.g. `T` for ten.
+    pub(crate) fn letter(self) -> char {
+        match self {
+            Rank::Two => '2',
+            Rank::Three => '3',
+            Rank::Four => '4',
+            Rank::Five => '5',
+            Rank::Six => '6',
+            Rank::Seven => '7',
+            Rank::Eight => '8',
+            Rank::Nine => '9',
+            Rank::Ten => 'T',
+            Rank::Jack => 'J',
+            Rank::Queen => 'Q',
+            Rank::King => 'K',
+            Rank::Ace => 'A',
+        }
+    }
+
+    /// The rank written `letter`, in either case, e.g. `t` for ten.
+    pub(crate) fn from_letter(letter: char) -> Option<Rank> {
+        Rank::ALL.into_iter().find(|rank| rank.letter() == letter.to_ascii_uppercase())
+    }
+}
+
+/// Formats a card in the usual two-character notation, e.g. `Ah` or `Td`.
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.rank.letter(), self.suit.letter())
+    }
+}
+
+/// Parses two-character notation such as `Ah`, `td` or `9C`, or `Jk` for
+/// the joker.
+impl FromStr for Card {
+    type Err = GameError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        if text.eq_ignore_ascii_case("jk") {
+            return Ok(Card::JOKER);
+        }
+        let invalid = || GameError::InvalidRequest(format!("invalid card {text:?}"));
+        let mut chars = text.chars();
+        let (Some(rank), Some(suit), None) = (chars.next(), chars.next(), chars.next()) else {
+            return Err(invalid());
+        };
+        let rank = Rank::from_letter(rank).ok_or_else(invalid)?;
+        let suit = Suit::ALL
+            .into_iter()
+            .find(|candidate| candidate.letter() == suit.to_ascii_lowercase())
+            .ok_or_else(invalid)?;
+        Ok(Card { rank, suit })
+    }
+}
+
+/// Parses cards separated by whitespace or commas, e.g. `"Ah Kd, 7c"`.
+pub fn parse_cards(text: &str) -> Result<Vec<Card>, GameError> {
+    text.split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|part| !part.is_empty())
+        .map(str::parse)
+        .collect()
+}
+
+#[derive(Debug)]
+pub struct Deck {
+    cards: Vec<Card>,
+    decks: usize,        // Standard decks shuffled together
+    continuous: bool,    // Dealt cards are shuffled back in on reset instead of starting afresh
+    joker: bool,         // Each deck has a joker as well as its 52 cards
+}
+
+impl Deck {
+    /// Create a new deck of cards, shuffled with the thread-local RNG
+    #[cfg(feature = "os-rng")]
+    pub fn new() -> Self {
+        Self::shuffled(&mut rand::thread_rng())
+    }
+
+    /// Create a shoe of `decks` standard decks shuffled together, with the
+    /// thread-local RNG
+    #[cfg(feature = "os-rng")]
+    pub fn new_multi(decks: usize) -> Self {
+        Self::shuffled_multi(decks, &mut rand::thread_rng())
+    }
+
+    /// Create a new deck of cards shuffled with `rng`
+    pub fn shuffled<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        Self::shuffled_multi(1, rng)
+    }
+
+    /// Create a shoe of `decks` standard decks (at least one) shuffled
+    /// together with `rng`
+    pub fn shuffled_multi<R: Rng + ?Sized>(decks: usize, rng: &mut R) -> Self {
+        let mut deck = Self { cards: Vec::new(), decks: decks.max(1), continuous: false, joker: false };
+        deck.cards = deck.full_shoe();
+        deck.cards.shuffle(rng);
+        deck
+    }
+
+    /// A deck dealing exactly `cards`, first card first, e.g. to replay a
+    /// disputed hand. Fails with `DuplicateCard` if a card appears twice.
+    pub fn from_cards(cards: Vec<Card>) -> Result<Self, GameError> {
+        CardSet::try_from_cards(&cards).map_err(GameError::DuplicateCard)?;
+        Ok(Self { cards, decks: 1, continuous: false, joker: false })
+    }
+
+    /// A shoe of `decks` decks dealing exactly `cards`, first card first.
+    /// Fails with `DuplicateCard` if a card appears more often than there
+    /// are decks.
+    pub fn from_shoe(cards: Vec<Card>, decks: usize) -> Result<Self, GameError> {
+        let decks = decks.max(1);
+        let mut copies: HashMap<Card, usize> = HashMap::new();
+        for &card in &cards {
+            let count = copies.entry(card).or_default();
+            *count += 1;
+            if *count > decks {
+                return Err(GameError::DuplicateCard(card));
+            }
+        }
+        Ok(Self { cards, decks, continuous: false, joker: false })
+    }
+
+    /// Every card once, in suit then rank order
+    pub fn all_cards() -> Vec<Card> {
+        Suit::ALL
+            .into_iter()
+            .flat_map(|suit| Rank::ALL.into_iter().map(move |rank| Card { rank, suit }))
+            .collect()
+    }
+
+    /// Every card in the deck before any is dealt, unshuffled
+    fn full_shoe(&self) -> Vec<Card> {
+        let deck = Self::all_cards().into_iter().chain(self.joker.then_some(Card::JOKER));
+        (0..self.decks).flat_map(|_| deck.clone()).collect()
+    }
+
+    /// Deals `count` cards from the deck
+    pub fn deal(&mut self, count: usize) -> Option<Vec<Card>> {
+        if count > self.cards.len() {
+            return None; // Not enough cards remaining
+        }
+        Some(self.cards.drain(0..count).collect()) // Return the cards
+    }
+
+    /// The cards left, next to be dealt first.
+    pub(crate) fn cards(&self) -> &[Card] {
+        &self.cards
+    }
+
+    /// Returns the number of remaining cards
+    pub fn remaining(&self) -> usize {
+        self.cards.len()
+    }
+
+    /// Standard decks in this one; each card appears this many times
+    pub fn decks(&self) -> usize {
+        self.decks
+    }
+
+    pub fn is_continuous_shuffle(&self) -> bool {
+        self.continuous
+    }
+
+    pub fn has_joker(&self) -> bool {
+        self.joker
+    }
+
+    /// Adds a joker to each deck, or takes it out, from the next
+    /// `reset_with` on.
+    pub fn set_joker(&mut self, joker: bool) {
+        self.joker = joker;
+    }
+
+    /// Makes `reset_with` shuffle the dealt cards back into the rest of the
+    /// deck, like a continuous shuffling machine, rather than starting over
+    /// with a freshly shuffled deck.
+    pub fn set_continuous_shuffle(&mut self, continuous: bool) {
+        self.continuous = continuous;
+    }
+
+    /// Keeps `other`'s number of decks, jokers and shuffle mode, e.g. for a
+    /// deck stacked in place of it.
+    pub(crate) fn with_settings_of(mut self, other: &Deck) -> Self {
+        self.decks = other.decks;
+        self.continuous = other.continuous;
+        self.joker = other.joker;
+        self
+    }
+
+    /// Resets the deck to a full shuffled state
+    #[cfg(feature = "os-rng")]
+    pub fn reset(&mut self) {
+        self.reset_with(&mut rand::thread_rng());
+    }
+
+    /// Resets the deck to a full state shuffled with `rng`. In continuous
+    /// shuffle mode each card dealt since is put back at a random place in
+    /// what's left of the deck instead.
+    pub fn reset_with<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        if !self.continuous {
+            self.cards = self.full_shoe();
+            self.cards.shuffle(rng);
+            return;
+        }
+        let mut missing = self.full_shoe();
+        for card in &self.cards {
+            if let Some(index) = missing.iter().position(|candidate| candidate == card) {
+                missing.swap_remove(index);
+            }
+        }
+        for card in missing {
+            let index = rng.gen_range(0..=self.cards.len());
+            self.cards.insert(index, card);
+        }
+    }
+}
+
+#[cfg(feature = "os-rng")]
+impl Default for Deck {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_card_notation() {
+        let card: Card = "Td".parse().unwrap();
+        assert_eq!(card, Card { rank: Rank::Ten, suit: Suit::Diamonds });
+        assert_eq!(card.to_string(), "Td");
+        assert_eq!("aH".parse::<Card>().unwrap().to_string(), "Ah");
+        assert!("1h".parse::<Card>().is_err());
+        assert!("Ahh".parse::<Card>().is_err());
+        assert_eq!(parse_cards("Ah Kd,7c").unwrap().len(), 3);
+        assert_eq!("JK".parse::<Card>().unwrap(), Card::JOKER);
+        assert_eq!(Card::JOKER.to_string(), "Jk");
+        assert!("Ak".parse::<Card>().is_err());
+    }
+
+    #[test]
+    fn test_deck_from_cards() {
+        let mut deck = Deck::from_cards(parse_cards("Ah Kd 7c").unwrap()).unwrap();
+        assert_eq!(deck.deal(2).unwrap(), parse_cards("Ah Kd").unwrap());
+        assert_eq!(deck.remaining(), 1);
+        let duplicate = "Ah".parse().unwrap();
+        assert_eq!(Deck::from_cards(parse_cards("Ah Kd ah").unwrap()).err(), Some(GameError::DuplicateCard(duplicate)));
+    }
+
+    #[test]
+    fn test_seeded_shuffle_is_repeatable() {
+        use rand::SeedableRng;
+        let first = Deck::shuffled(&mut rand::rngs::StdRng::seed_from_u64(7)).deal(52).unwrap();
+        let second = Deck::shuffled(&mut rand::rngs::StdRng::seed_from_u64(7)).deal(52).unwrap();
+        assert_eq!(first, second);
+        assert_ne!(first, Deck::all_cards());
+    }
+
+    #[test]
+    fn test_multi_deck_and_continuous_shuffle() {
+        use rand::SeedableRng;
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let mut shoe = Deck::shuffled_multi(6, &mut rng);
+        assert_eq!((shoe.remaining(), shoe.decks()), (312, 6));
+        let ace = "Ah".parse().unwrap();
+        assert_eq!(shoe.cards().iter().filter(|&&card| card == ace).count(), 6);
+
+        shoe.set_continuous_shuffle(true);
+        shoe.deal(20).unwrap();
+        let rest = shoe.cards().to_vec();
+        shoe.reset_with(&mut rng);
+        assert_eq!(shoe.remaining(), 312);
+        let mut undealt = rest.iter().peekable();
+        for card in shoe.cards() {
+            undealt.next_if_eq(&card);
+        }
+        assert_eq!(undealt.next(), None); // The undealt cards kept their order
+        assert_eq!(shoe.cards().iter().filter(|&&card| card == ace).count(), 6);
+
+        shoe.set_joker(true);
+        shoe.reset_with(&mut rng);
+        assert_eq!(shoe.remaining(), 318);
+        assert_eq!(shoe.cards().iter().filter(|card| card.is_joker()).count(), 6);
+    }
+
+    #[test]
+    #[cfg(feature = "os-rng")]
+    fn test_deck_initialization() {
+        let deck = Deck::new();
+        assert_eq!(deck.remaining(), 52); // A new deck should have 52 cards
+    }
+
+    #[test]
+    #[cfg(feature = "os-rng")]
+    fn test_dealing_cards() {
+        let mut deck = Deck::new();
+        let dealt = deck.deal(5).unwrap(); // Deal 5 cards
+        assert_eq!(dealt.len(), 5);        // Check that 5 cards were dealt
+        assert_eq!(deck.remaining(), 47); // Remaining cards should be 47
+    }
+
+    #[test]
+    #[cfg(feature = "os-rng")]
+    fn test_not_enough_cards() {
+        let mut deck = Deck::new();
+        let _ = deck.deal(50);           // Deal most of the deck
+        assert!(deck.deal(5).is_none()); // Not enough cards to deal 5 more
+    }
+
+    #[test]
+    #[cfg(feature = "os-rng")]
+    fn test_deck_reset() {
+        let mut deck = Deck::new();
+        let _ = deck.deal(10);      // Deal 10 cards
+        deck.reset();               // Reset the deck
+        assert_eq!(deck.remaining(), 52); // Deck should be full again
+    }
+
+    #[test]
+    #[cfg(feature = "os-rng")]
+    fn test_deal_zero() {
+        let mut deck = Deck::new();
+        let dealt = deck.deal(0).unwrap();
+        assert_eq!(dealt.len(), 0);
+        assert_eq!(deck.remaining(), 52);
+    }
+
+    #[test]
+    #[cfg(feature = "os-rng")]
+    fn test_deal_more_than_remaining() {
+        let mut deck = Deck::new();
+        let dealt = deck.deal(53);
+        assert!(dealt.is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "os-rng")]
+    fn test_multiple_deals() {
+        let mut deck = Deck::new(); // Initialize the deck
+
+        // First deal: 10 cards
+        let first_deal = deck.deal(10).unwrap();
+        assert_eq!(first_deal.len(), 10); // Verify 10 cards were dealt
+        assert_eq!(deck.remaining(), 42); // 52 - 10 = 42
+
+        // Second deal: 15 cards
+        let second_deal = deck.deal(15).unwrap();
+        assert_eq!(second_deal.len(), 15); // Verify 15 cards were dealt
+        assert_eq!(deck.remaining(), 27); // 42 - 15 = 27
+
+        // Third deal: 20 cards
+        let third_deal = deck.deal(20).unwrap();
+        assert_eq!(third_deal.len(), 20); // Verify 20 cards were dealt
+        assert_eq!(deck.remaining(), 7); // 27 - 20 = 7
+
+        // Final check: Remaining cards
+        let remaining_cards = deck.deal(7).unwrap();
+        assert_eq!(remaining_cards.len(), 7); // All remaining cards dealt
+        assert_eq!(deck.remaining(), 0); // No cards left
+    }
+}
\ No newline at end of file
@@ -0,0 +1,278 @@
+//! A set of cards packed into one `u64`, one bit per card. Membership,
+//! union and intersection are single instructions, so the evaluator and the
+//! equity loops use it instead of cloning and scanning `Vec<Card>`.
+
+use std::fmt;
+use std::ops::{BitAnd, BitOr, BitOrAssign, Sub};
+
+use crate::card_dealer::{Card, Rank, Suit};
+
+/// Bits used by a full deck.
+const FULL: u64 = (1 << 52) - 1;
+
+/// The joker's bit, just past the four suits. `full` leaves it out.
+const JOKER: u64 = 1 << (4 * SUIT_BITS + Card::JOKER.rank as u32);
+
+/// Ranks in one suit occupy 13 consecutive bits.
+const SUIT_BITS: u32 = 13;
+
+/// Mask covering the 13 ranks of a single suit.
+const RANK_MASK: u64 = (1 << SUIT_BITS) - 1;
+
+/// A set of distinct cards. Bit `suit * 13 + rank` is set when the card is
+/// present, so iteration yields cards in the same suit-then-rank order as
+/// `Deck::all_cards`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct CardSet(u64);
+
+impl CardSet {
+    /// The empty set.
+    pub const EMPTY: CardSet = CardSet(0);
+
+    /// Every card in a 52-card deck.
+    pub const fn full() -> Self {
+        CardSet(FULL)
+    }
+
+    /// The raw mask: 52 bits for the cards, and one more for the joker.
+    pub const fn bits(self) -> u64 {
+        self.0
+    }
+
+    /// Builds a set from a raw mask, dropping any bits that aren't a card.
+    pub const fn from_bits(bits: u64) -> Self {
+        CardSet(bits & (FULL | JOKER))
+    }
+
+    /// Adds a card, returning `false` if it was already present.
+    pub fn insert(&mut self, card: Card) -> bool {
+        let bit = bit(card);
+        let added = self.0 & bit == 0;
+        self.0 |= bit;
+        added
+    }
+
+    /// Removes a card, returning `false` if it was not present.
+    pub fn remove(&mut self, card: Card) -> bool {
+        let bit = bit(card);
+        let removed = self.0 & bit != 0;
+        self.0 &= !bit;
+        removed
+    }
+
+    pub fn contains(self, card: Card) -> bool {
+        self.0 & bit(card) != 0
+    }
+
+    pub fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn union(self, other: Self) -> Self {
+        CardSet(self.0 | other.0)
+    }
+
+    pub fn intersection(self, other: Self) -> Self {
+        CardSet(self.0 & other.0)
+    }
+
+    /// Cards in `self` that are not in `other`.
+    pub fn difference(self, other: Self) -> Self {
+        CardSet(self.0 & !other.0)
+    }
+
+    /// True if the two sets share no card.
+    pub fn is_disjoint(self, other: Self) -> bool {
+        self.0 & other.0 == 0
+    }
+
+    /// Ranks present in one suit, as a 13-bit mask with twos in bit 0.
+    pub fn suit_ranks(self, suit: Suit) -> u16 {
+        ((self.0 >> (suit as u32 * SUIT_BITS)) & RANK_MASK) as u16
+    }
+
+    /// Ranks present in any suit, as a 13-bit mask with twos in bit 0.
+    pub fn ranks(self) -> u16 {
+        Suit::ALL.into_iter().fold(0, |ranks, suit| ranks | self.suit_ranks(suit))
+    }
+
+    /// Iterates the cards in suit-then-rank order.
+    pub fn iter(self) -> Cards {
+        Cards(self.0)
+    }
+
+    /// Builds a set from `cards`, or returns the first card that appears
+    /// twice.
+    pub fn try_from_cards(cards: &[Card]) -> Result<Self, Card> {
+        let mut set = CardSet::EMPTY;
+        for &card in cards {
+            if !set.insert(card) {
+                return Err(card);
+            }
+        }
+        Ok(set)
+    }
+}
+
+fn bit(card: Card) -> u64 {
+    1 << (card.suit as u32 * SUIT_BITS + card.rank as u32)
+}
+
+fn card_at(index: u32) -> Card {
+    if 1 << index == JOKER {
+        return Card::JOKER;
+    }
+    Card {
+        rank: Rank::ALL[(index % SUIT_BITS) as usize],
+        suit: Suit::ALL[(index / SUIT_BITS) as usize],
+    }
+}
+
+/// Iterator over the cards of a `CardSet`, lowest bit first.
+#[derive(Debug, Clone)]
+pub struct Cards(u64);
+
+impl Iterator for Cards {
+    type Item = Card;
+
+    fn next(&mut self) -> Option<Card> {
+        if self.0 == 0 {
+            return None;
+        }
+        let index = self.0.trailing_zeros();
+        self.0 &= self.0 - 1; // Clear the lowest set bit
+        Some(card_at(index))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.0.count_ones() as usize;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for Cards {}
+
+impl IntoIterator for CardSet {
+    type Item = Card;
+    type IntoIter = Cards;
+
+    fn into_iter(self) -> Cards {
+        self.iter()
+    }
+}
+
+/// Collects cards into a set; duplicates are merged.
+impl FromIterator<Card> for CardSet {
+    fn from_iter<I: IntoIterator<Item = Card>>(cards: I) -> Self {
+        let mut set = CardSet::EMPTY;
+        set.extend(cards);
+        set
+    }
+}
+
+impl Extend<Card> for CardSet {
+    fn extend<I: IntoIterator<Item = Card>>(&mut self, cards: I) {
+        for card in cards {
+            self.insert(card);
+        }
+    }
+}
+
+impl From<Card> for CardSet {
+    fn from(card: Card) -> Self {
+        CardSet(bit(card))
+    }
+}
+
+impl BitOr for CardSet {
+    type Output = CardSet;
+
+    fn bitor(self, other: Self) -> Self {
+        self.union(other)
+    }
+}
+
+impl BitOrAssign for CardSet {
+    fn bitor_assign(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+}
+
+impl BitAnd for CardSet {
+    type Output = CardSet;
+
+    fn bitand(self, other: Self) -> Self {
+        self.intersection(other)
+    }
+}
+
+impl Sub for CardSet {
+    type Output = CardSet;
+
+    fn sub(self, other: Self) -> Self {
+        self.difference(other)
+    }
+}
+
+/// Formats the cards space-separated, e.g. `2h Ah Kd`.
+impl fmt::Display for CardSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, card) in self.iter().enumerate() {
+            if index > 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{card}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card_dealer::{parse_cards, Deck};
+
+    #[test]
+    fn test_full_set_matches_the_deck_order() {
+        let cards: Vec<Card> = CardSet::full().iter().collect();
+        assert_eq!(cards, Deck::all_cards());
+        assert_eq!(CardSet::full().len(), 52);
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a: CardSet = parse_cards("Ah Kh 2c").unwrap().into_iter().collect();
+        let b: CardSet = parse_cards("Kh 2c 9s").unwrap().into_iter().collect();
+        assert_eq!((a & b).to_string(), "Kh 2c");
+        assert_eq!((a | b).len(), 4);
+        assert_eq!((a - b).to_string(), "Ah");
+        assert!(!a.is_disjoint(b));
+        assert_eq!(a.ranks(), 1 << Rank::Ace as u16 | 1 << Rank::King as u16 | 1 << Rank::Two as u16);
+        assert_eq!(a.suit_ranks(Suit::Clubs), 1);
+    }
+
+    #[test]
+    fn test_duplicates_are_reported() {
+        let cards = parse_cards("Ah Kd Ah").unwrap();
+        assert_eq!(CardSet::try_from_cards(&cards), Err(cards[0]));
+        let mut set = CardSet::from(cards[0]);
+        assert!(!set.insert(cards[0]));
+        assert!(set.remove(cards[0]));
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    fn test_joker_has_its_own_bit() {
+        let cards = parse_cards("Jk Js Ah").unwrap();
+        let set: CardSet = cards.iter().copied().collect();
+        assert_eq!(set.len(), 3);
+        assert_eq!(set.to_string(), "Ah Js Jk");
+        assert_eq!(CardSet::from_bits(set.bits()), set);
+        assert_eq!(set.ranks(), 1 << Rank::Ace as u16 | 1 << Rank::Jack as u16); // The joker has no rank
+        assert!(!CardSet::full().contains(Card::JOKER));
+    }
+}
@@ -0,0 +1,190 @@
+//! An amount of chips. Stacks, bets, pots and blinds are all `Chips`, so
+//! a chip count can't be mixed up with a seat number or a hand count, and
+//! arithmetic that would overflow or go negative panics instead of wrapping.
+
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Div, Mul, Rem, Sub, SubAssign};
+use std::str::FromStr;
+
+use crate::game_error::GameError;
+
+/// A non-negative number of chips.
+///
+/// `+` and `-` panic on overflow or underflow, in release builds too: a
+/// wrapped chip count is always a bug. Use `checked_add`/`checked_sub` where
+/// the amount comes from outside the engine. Serializes as a plain number.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema), schema(value_type = u64))]
+pub struct Chips(u64);
+
+impl Chips {
+    pub const ZERO: Chips = Chips(0);
+
+    pub const fn new(amount: u64) -> Self {
+        Chips(amount)
+    }
+
+    /// The amount as a plain number.
+    pub const fn get(self) -> u64 {
+        self.0
+    }
+
+    pub const fn is_zero(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn checked_add(self, other: Chips) -> Option<Chips> {
+        self.0.checked_add(other.0).map(Chips)
+    }
+
+    pub fn checked_sub(self, other: Chips) -> Option<Chips> {
+        self.0.checked_sub(other.0).map(Chips)
+    }
+
+    /// Adds a signed adjustment, failing if the result would be negative.
+    pub fn checked_add_signed(self, delta: i64) -> Option<Chips> {
+        self.0.checked_add_signed(delta).map(Chips)
+    }
+
+    /// `self - other`, or zero if `other` is larger.
+    pub fn saturating_sub(self, other: Chips) -> Chips {
+        Chips(self.0.saturating_sub(other.0))
+    }
+
+    /// Like `checked_sub`, reporting a shortfall as `InsufficientChips`.
+    pub fn try_sub(self, other: Chips) -> Result<Chips, GameError> {
+        self.checked_sub(other).ok_or(GameError::InsufficientChips)
+    }
+}
+
+impl From<u32> for Chips {
+    fn from(amount: u32) -> Self {
+        Chips(u64::from(amount))
+    }
+}
+
+impl From<u64> for Chips {
+    fn from(amount: u64) -> Self {
+        Chips(amount)
+    }
+}
+
+impl From<Chips> for u64 {
+    fn from(chips: Chips) -> Self {
+        chips.0
+    }
+}
+
+impl Add for Chips {
+    type Output = Chips;
+
+    fn add(self, other: Chips) -> Chips {
+        self.checked_add(other).expect("chip count overflowed")
+    }
+}
+
+impl AddAssign for Chips {
+    fn add_assign(&mut self, other: Chips) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Chips {
+    type Output = Chips;
+
+    fn sub(self, other: Chips) -> Chips {
+        self.checked_sub(other).expect("chip count went negative")
+    }
+}
+
+impl SubAssign for Chips {
+    fn sub_assign(&mut self, other: Chips) {
+        *self = *self - other;
+    }
+}
+
+/// Scales an amount, e.g. a stack per player.
+impl Mul<u64> for Chips {
+    type Output = Chips;
+
+    fn mul(self, times: u64) -> Chips {
+        Chips(self.0.checked_mul(times).expect("chip count overflowed"))
+    }
+}
+
+/// Splits an amount into equal shares, rounding down.
+impl Div<u64> for Chips {
+    type Output = Chips;
+
+    fn div(self, shares: u64) -> Chips {
+        Chips(self.0 / shares)
+    }
+}
+
+/// What is left over after splitting into equal shares.
+impl Rem<u64> for Chips {
+    type Output = Chips;
+
+    fn rem(self, shares: u64) -> Chips {
+        Chips(self.0 % shares)
+    }
+}
+
+impl Sum for Chips {
+    fn sum<I: Iterator<Item = Chips>>(amounts: I) -> Chips {
+        amounts.fold(Chips::ZERO, Add::add)
+    }
+}
+
+impl<'a> Sum<&'a Chips> for Chips {
+    fn sum<I: Iterator<Item = &'a Chips>>(amounts: I) -> Chips {
+        amounts.copied().sum()
+    }
+}
+
+impl fmt::Display for Chips {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+/// Parses a plain non-negative number, e.g. from a command-line flag.
+impl FromStr for Chips {
+    type Err = GameError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        text.trim()
+            .parse()
+            .map(Chips)
+            .map_err(|_| GameError::InvalidRequest(format!("invalid chip amount {text:?}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checked_arithmetic() {
+        let stack = Chips::new(100);
+        assert_eq!(stack + Chips::new(50), Chips::new(150));
+        assert_eq!(stack.checked_sub(Chips::new(101)), None);
+        assert_eq!(stack.try_sub(Chips::new(101)), Err(GameError::InsufficientChips));
+        assert_eq!(stack.saturating_sub(Chips::new(101)), Chips::ZERO);
+        assert_eq!(stack.checked_add_signed(-100), Some(Chips::ZERO));
+        assert_eq!(stack.checked_add_signed(-101), None);
+        assert_eq!(Chips::new(u64::MAX).checked_add(stack), None);
+        assert_eq!((Chips::new(7) / 2, Chips::new(7) % 2), (Chips::new(3), Chips::new(1)));
+        assert_eq!([stack, stack].iter().sum::<Chips>(), Chips::new(200));
+        assert_eq!("250".parse::<Chips>().unwrap().to_string(), "250");
+        assert!("-5".parse::<Chips>().is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "chip count went negative")]
+    fn test_underflow_panics() {
+        let _ = Chips::new(5) - Chips::new(6);
+    }
+}
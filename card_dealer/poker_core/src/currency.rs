@@ -0,0 +1,27 @@
+//! What a table's chips are worth. Play-money chips are free, so players
+//! rebuy as often as they like. A tracked table's chips come out of each
+//! player's balance at a `Bank` when they sit down or buy more, and go back
+//! into it when they leave.
+
+use crate::chips::Chips;
+use crate::game_error::GameError;
+
+/// Where a table's chips come from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "snake_case"))]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub enum CurrencyMode {
+    #[default]
+    PlayMoney,  // Free chips; rebuys are unlimited
+    Tracked,    // Chips are drawn from, and paid back into, the players' bank balances
+}
+
+/// The players' balances outside any table, shared by every tracked table.
+pub trait Bank: Send + Sync {
+    /// Takes `amount` out of the balance of `player_id`, refusing with
+    /// `InsufficientChips` when it doesn't cover it.
+    fn withdraw(&self, player_id: &str, amount: Chips) -> Result<(), GameError>;
+
+    /// Pays `amount` into the balance of `player_id`.
+    fn deposit(&self, player_id: &str, amount: Chips);
+}
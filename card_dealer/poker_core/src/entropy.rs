@@ -0,0 +1,264 @@
+//! Where the randomness that shuffles the decks comes from.
+
+#[cfg(feature = "os-rng")]
+use std::fs::File;
+#[cfg(feature = "os-rng")]
+use std::io::{self, Read};
+#[cfg(feature = "os-rng")]
+use std::path::{Path, PathBuf};
+
+use rand::rngs::StdRng;
+use rand::{Error, RngCore, SeedableRng};
+use tracing::info;
+
+/// A source of randomness for shuffling. Pick one with
+/// `GameController::with_entropy`.
+pub trait EntropySource: RngCore + Send {
+    /// Short name for logs, e.g. `"os"`.
+    fn name(&self) -> &'static str;
+
+    /// Called before each deck is shuffled. Sources that reseed for every
+    /// deck do it here.
+    fn begin_shuffle(&mut self) {}
+}
+
+impl<S: EntropySource + ?Sized> EntropySource for Box<S> {
+    fn name(&self) -> &'static str {
+        (**self).name()
+    }
+
+    fn begin_shuffle(&mut self) {
+        (**self).begin_shuffle()
+    }
+}
+
+/// Wraps any RNG, e.g. a seeded one for replays, as an `EntropySource`.
+pub struct RngSource<R>(pub R);
+
+impl<R: RngCore> RngCore for RngSource<R> {
+    fn next_u32(&mut self) -> u32 {
+        self.0.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.0.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.0.try_fill_bytes(dest)
+    }
+}
+
+impl<R: RngCore + Send> EntropySource for RngSource<R> {
+    fn name(&self) -> &'static str {
+        "rng"
+    }
+}
+
+/// The operating system's RNG, read for every shuffle.
+#[cfg(feature = "os-rng")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OsSource;
+
+#[cfg(feature = "os-rng")]
+impl RngCore for OsSource {
+    fn next_u32(&mut self) -> u32 {
+        rand::rngs::OsRng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        rand::rngs::OsRng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        rand::rngs::OsRng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        rand::rngs::OsRng.try_fill_bytes(dest)
+    }
+}
+
+#[cfg(feature = "os-rng")]
+impl EntropySource for OsSource {
+    fn name(&self) -> &'static str {
+        "os"
+    }
+}
+
+/// The thread-local RNG of whichever thread is shuffling: a CSPRNG that
+/// reseeds itself from the OS.
+#[cfg(feature = "os-rng")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ThreadSource;
+
+#[cfg(feature = "os-rng")]
+impl RngCore for ThreadSource {
+    fn next_u32(&mut self) -> u32 {
+        rand::thread_rng().next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        rand::thread_rng().next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        rand::thread_rng().fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        rand::thread_rng().try_fill_bytes(dest)
+    }
+}
+
+#[cfg(feature = "os-rng")]
+impl EntropySource for ThreadSource {
+    fn name(&self) -> &'static str {
+        "thread"
+    }
+}
+
+/// A ChaCha CSPRNG given a fresh seed for every deck and logging it, so any
+/// shuffle can be reproduced from the logs with `StdRng::from_seed`. Whoever
+/// reads the logs can do the same, so keep them private.
+pub struct LoggedSeedSource {
+    seeds: Box<dyn RngCore + Send>, // Where each deck's seed comes from
+    rng: StdRng,
+}
+
+impl LoggedSeedSource {
+    /// Seeds every deck from the operating system's RNG.
+    #[cfg(feature = "os-rng")]
+    pub fn new() -> Self {
+        Self::with_seeds(rand::rngs::OsRng)
+    }
+
+    /// Seeds every deck from `seeds`.
+    pub fn with_seeds(seeds: impl RngCore + Send + 'static) -> Self {
+        Self { seeds: Box::new(seeds), rng: StdRng::from_seed([0; 32]) }
+    }
+}
+
+#[cfg(feature = "os-rng")]
+impl Default for LoggedSeedSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RngCore for LoggedSeedSource {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.rng.try_fill_bytes(dest)
+    }
+}
+
+impl EntropySource for LoggedSeedSource {
+    fn name(&self) -> &'static str {
+        "chacha"
+    }
+
+    fn begin_shuffle(&mut self) {
+        let mut seed = [0u8; 32];
+        self.seeds.fill_bytes(&mut seed);
+        let hex: String = seed.iter().map(|byte| format!("{byte:02x}")).collect();
+        info!(seed = %hex, "deck seeded");
+        self.rng = StdRng::from_seed(seed);
+    }
+}
+
+/// Bytes read from a file: a hardware RNG device such as `/dev/hwrng`, or a
+/// pipe fed by a remote entropy service. A read failure panics rather than
+/// shuffling with anything weaker.
+#[cfg(feature = "os-rng")]
+pub struct FileSource {
+    path: PathBuf,
+    file: File,
+}
+
+#[cfg(feature = "os-rng")]
+impl FileSource {
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file = File::open(&path)?;
+        Ok(Self { path, file })
+    }
+}
+
+#[cfg(feature = "os-rng")]
+impl RngCore for FileSource {
+    fn next_u32(&mut self) -> u32 {
+        let mut bytes = [0; 4];
+        self.fill_bytes(&mut bytes);
+        u32::from_le_bytes(bytes)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut bytes = [0; 8];
+        self.fill_bytes(&mut bytes);
+        u64::from_le_bytes(bytes)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        if let Err(err) = self.try_fill_bytes(dest) {
+            panic!("could not read entropy from {}: {err}", self.path.display());
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.file.read_exact(dest).map_err(Error::new)
+    }
+}
+
+#[cfg(feature = "os-rng")]
+impl EntropySource for FileSource {
+    fn name(&self) -> &'static str {
+        "file"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card_dealer::Deck;
+
+    #[test]
+    fn test_logged_seeds_reproduce_the_deck() {
+        let mut source = LoggedSeedSource::with_seeds(StdRng::seed_from_u64(9));
+        source.begin_shuffle();
+        let dealt = Deck::shuffled(&mut source).deal(52).unwrap();
+
+        let mut seed = [0u8; 32];
+        StdRng::seed_from_u64(9).fill_bytes(&mut seed); // What the log shows
+        assert_eq!(Deck::shuffled(&mut StdRng::from_seed(seed)).deal(52).unwrap(), dealt);
+
+        source.begin_shuffle();
+        assert_ne!(Deck::shuffled(&mut source).deal(52).unwrap(), dealt);
+    }
+
+    #[test]
+    #[cfg(feature = "os-rng")]
+    fn test_file_source_reads_the_file() {
+        let path = std::env::temp_dir().join(format!("poker_core_entropy_{}", std::process::id()));
+        std::fs::write(&path, [1, 0, 0, 0, 2, 0, 0, 0]).unwrap();
+        let mut source = FileSource::open(&path).unwrap();
+        assert_eq!((source.next_u32(), source.next_u32()), (1, 2));
+        assert!(source.try_fill_bytes(&mut [0]).is_err()); // The file is used up
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,315 @@
+use std::cmp::Ordering;
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::card_dealer::Card;
+use crate::card_set::CardSet;
+use crate::game_error::GameError;
+use crate::poker_hand::hand_value;
+use crate::range::Range;
+
+/// Cards on a complete board.
+const BOARD_SIZE: usize = 5;
+
+/// Trials per independently seeded batch in `estimate_equity_seeded`.
+const BATCH: u32 = 1_000;
+
+/// Estimates each player's equity: their expected share of the pot if the
+/// hand were run to showdown.
+///
+/// The rest of the board is dealt at random `trials` times; a split pot
+/// counts as an equal fraction for each winner. Returns one value in `0..=1`
+/// per entry of `hole_cards`, in the same order.
+pub fn estimate_equity<R: Rng + ?Sized>(
+    hole_cards: &[Vec<Card>],
+    board: &[Card],
+    trials: u32,
+    rng: &mut R,
+) -> Result<Vec<f64>, GameError> {
+    let runouts = Runouts::new(hole_cards, board, trials)?;
+    let shares = runouts.run(trials, rng);
+    Ok(shares.into_iter().map(|share| share / f64::from(trials)).collect())
+}
+
+/// Like `estimate_equity`, but the trials are split into batches that each
+/// get their own RNG seeded from `seed`. With the `parallel` feature the
+/// batches run on rayon's thread pool; either way the same seed gives the
+/// same result.
+pub fn estimate_equity_seeded(hole_cards: &[Vec<Card>], board: &[Card], trials: u32, seed: u64) -> Result<Vec<f64>, GameError> {
+    let runouts = Runouts::new(hole_cards, board, trials)?;
+    let batch = |index: u32| {
+        let size = BATCH.min(trials - index * BATCH);
+        runouts.run(size, &mut StdRng::seed_from_u64(seed.wrapping_add(u64::from(index))))
+    };
+    let batches = trials.div_ceil(BATCH);
+    #[cfg(feature = "parallel")]
+    let results: Vec<Vec<f64>> = (0..batches).into_par_iter().map(batch).collect();
+    #[cfg(not(feature = "parallel"))]
+    let results: Vec<Vec<f64>> = (0..batches).map(batch).collect();
+
+    // Summed in batch order so the floating-point result doesn't depend on scheduling
+    let mut shares = vec![0.0; hole_cards.len()];
+    for result in results {
+        for (total, share) in shares.iter_mut().zip(result) {
+            *total += share;
+        }
+    }
+    Ok(shares.into_iter().map(|share| share / f64::from(trials)).collect())
+}
+
+/// Each player's exact equity, from every way the rest of the board can
+/// come rather than a sample of them. Needs at least the flop: before it
+/// there are too many boards to go through them all.
+pub fn exact_equity(hole_cards: &[Vec<Card>], board: &[Card]) -> Result<Vec<f64>, GameError> {
+    if board.len() < 3 {
+        return Err(GameError::InvalidRequest("exact equity needs at least the flop".to_string()));
+    }
+    let runouts = Runouts::new(hole_cards, board, 1)?;
+    let (shares, boards) = runouts.exhaust();
+    Ok(shares.into_iter().map(|share| share / boards as f64).collect())
+}
+
+/// Where a made hand ranks against every holding an opponent could have on
+/// `board`: the share of those it beats, a tie counting half. `0.87` reads
+/// as "beats 87% of hands".
+///
+/// Needs two hole cards and a flop, turn or river. Only the cards showing
+/// count; nothing is dealt to come.
+pub fn hand_percentile(hole_cards: &[Card], board: &[Card]) -> Result<f64, GameError> {
+    if hole_cards.len() != 2 || !(3..=BOARD_SIZE).contains(&board.len()) {
+        return Err(GameError::InvalidRequest("a percentile needs two hole cards and a flop, turn or river".to_string()));
+    }
+    let known = CardSet::try_from_cards(&[hole_cards, board].concat()).map_err(GameError::DuplicateCard)?;
+    if known.contains(Card::JOKER) {
+        return Err(GameError::InvalidRequest("a percentile can't be worked out with a joker".to_string()));
+    }
+    let board: CardSet = board.iter().copied().collect();
+    let ours = hand_value(known).expect("at least five cards");
+    let unseen: Vec<Card> = (CardSet::full() - known).iter().collect();
+    let (mut score, mut holdings) = (0.0, 0);
+    for (index, &first) in unseen.iter().enumerate() {
+        for &second in &unseen[index + 1..] {
+            let theirs = hand_value(board | first.into() | second.into()).expect("at least five cards");
+            score += match ours.cmp(&theirs) {
+                Ordering::Greater => 1.0,
+                Ordering::Equal => 0.5,
+                Ordering::Less => 0.0,
+            };
+            holdings += 1;
+        }
+    }
+    Ok(score / f64::from(holdings))
+}
+
+/// The hero's equity against one hand of a range.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct ComboEquity {
+    pub cards: [Card; 2],
+    pub equity: f64,
+}
+
+/// What `equity_vs_range` found.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct RangeEquity {
+    pub equity: f64,                // Against the whole range, each combo equally likely
+    pub combos: Vec<ComboEquity>,   // In the range's order, without those the known cards block
+}
+
+/// Estimates the equity of `hero` against a villain holding any hand of
+/// `range`, each combination of cards as likely as the next.
+///
+/// Combinations that share a card with the hero or the board can't be dealt
+/// and are left out. Each of the rest gets `trials` runouts, seeded from
+/// `seed` as in `estimate_equity_seeded`, so the same seed gives the same
+/// result.
+pub fn equity_vs_range(hero: &[Card], range: &Range, board: &[Card], trials: u32, seed: u64) -> Result<RangeEquity, GameError> {
+    let dead = CardSet::try_from_cards(&[hero, board].concat()).map_err(GameError::DuplicateCard)?;
+    let live: Vec<[Card; 2]> = range.live(dead).collect();
+    if live.is_empty() {
+        return Err(GameError::InvalidRequest("every hand in the range shares a card with the hero or the board".to_string()));
+    }
+    let combo = |cards: &[Card; 2]| {
+        let equity = estimate_equity_seeded(&[hero.to_vec(), cards.to_vec()], board, trials, seed)?;
+        Ok(ComboEquity { cards: *cards, equity: equity[0] })
+    };
+    #[cfg(feature = "parallel")]
+    let combos: Result<Vec<ComboEquity>, GameError> = live.par_iter().map(combo).collect();
+    #[cfg(not(feature = "parallel"))]
+    let combos: Result<Vec<ComboEquity>, GameError> = live.iter().map(combo).collect();
+
+    let combos = combos?;
+    let equity = combos.iter().map(|combo| combo.equity).sum::<f64>() / combos.len() as f64;
+    Ok(RangeEquity { equity, combos })
+}
+
+/// A validated equity question: each player's hole cards, the known board
+/// and the cards left to deal from.
+struct Runouts {
+    holes: Vec<CardSet>,
+    board: CardSet,
+    stub: Vec<Card>,
+    missing: usize,
+}
+
+impl Runouts {
+    fn new(hole_cards: &[Vec<Card>], board: &[Card], trials: u32) -> Result<Self, GameError> {
+        if hole_cards.len() < 2 {
+            return Err(GameError::InvalidRequest("equity needs at least two hands".to_string()));
+        }
+        if let Some(hand) = hole_cards.iter().find(|cards| cards.len() != 2) {
+            return Err(GameError::InvalidRequest(format!("expected two hole cards, got {}", hand.len())));
+        }
+        if board.len() > BOARD_SIZE {
+            return Err(GameError::InvalidRequest(format!("a board has at most {BOARD_SIZE} cards")));
+        }
+        if trials == 0 {
+            return Err(GameError::InvalidRequest("trials must be positive".to_string()));
+        }
+
+        let mut known = CardSet::EMPTY;
+        for &card in hole_cards.iter().flatten().chain(board) {
+            if !known.insert(card) {
+                return Err(GameError::DuplicateCard(card));
+            }
+        }
+        Ok(Self {
+            holes: hole_cards.iter().map(|hole| hole.iter().copied().collect()).collect(),
+            board: board.iter().copied().collect(),
+            stub: (CardSet::full() - known).iter().collect(),
+            missing: BOARD_SIZE - board.len(),
+        })
+    }
+
+    /// Deals `trials` runouts and returns each player's summed share of the
+    /// pots won.
+    fn run<R: Rng + ?Sized>(&self, trials: u32, rng: &mut R) -> Vec<f64> {
+        let mut stub = self.stub.clone();
+        let mut shares = vec![0.0; self.holes.len()];
+        for _ in 0..trials {
+            let (runout, _) = stub.partial_shuffle(rng, self.missing);
+            self.award(self.board | runout.iter().copied().collect(), &mut shares);
+        }
+        shares
+    }
+
+    /// Deals every possible runout once and returns each player's summed
+    /// share of the pots won, with the number of runouts.
+    fn exhaust(&self) -> (Vec<f64>, usize) {
+        fn deal(runouts: &Runouts, board: CardSet, from: usize, missing: usize, shares: &mut [f64]) -> usize {
+            if missing == 0 {
+                runouts.award(board, shares);
+                return 1;
+            }
+            (from..runouts.stub.len()).map(|index| deal(runouts, board | runouts.stub[index].into(), index + 1, missing - 1, shares)).sum()
+        }
+        let mut shares = vec![0.0; self.holes.len()];
+        let boards = deal(self, self.board, 0, self.missing, &mut shares);
+        (shares, boards)
+    }
+
+    /// Adds each player's share of the pot on `full_board` to `shares`.
+    fn award(&self, full_board: CardSet, shares: &mut [f64]) {
+        let hands: Vec<_> = self.holes.iter().map(|&hole| hand_value(hole | full_board)).collect();
+        let mut winners = vec![0];
+        for index in 1..hands.len() {
+            match hands[index].cmp(&hands[winners[0]]) {
+                Ordering::Greater => winners = vec![index],
+                Ordering::Equal => winners.push(index),
+                Ordering::Less => {}
+            }
+        }
+        let share = 1.0 / winners.len() as f64;
+        for winner in winners {
+            shares[winner] += share;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card_dealer::{parse_cards, Rank};
+
+    #[test]
+    fn test_equity_sums_to_one_and_favours_the_better_hand() {
+        let hands = vec![parse_cards("Ah As").unwrap(), parse_cards("7c 2d").unwrap()];
+        let equity = estimate_equity(&hands, &[], 2_000, &mut StdRng::seed_from_u64(3)).unwrap();
+        assert!((equity.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        assert!(equity[0] > 0.75, "{equity:?}");
+    }
+
+    #[test]
+    fn test_complete_board_is_exact() {
+        let hands = vec![parse_cards("Ah Kh").unwrap(), parse_cards("Qc Qd").unwrap()];
+        let board = parse_cards("2h 7h 9h Qs 3c").unwrap();
+        let equity = estimate_equity(&hands, &board, 10, &mut StdRng::seed_from_u64(3)).unwrap();
+        assert_eq!(equity, vec![1.0, 0.0]); // Flush beats trips
+    }
+
+    #[test]
+    fn test_duplicate_cards_rejected() {
+        let hands = vec![parse_cards("Ah Kh").unwrap(), parse_cards("Ah Qd").unwrap()];
+        let duplicate = hands[0][0];
+        assert_eq!(estimate_equity(&hands, &[], 10, &mut StdRng::seed_from_u64(3)), Err(GameError::DuplicateCard(duplicate)));
+    }
+
+    #[test]
+    fn test_seeded_equity_is_repeatable() {
+        let hands = vec![parse_cards("Ah As").unwrap(), parse_cards("Kd Kc").unwrap(), parse_cards("7c 2d").unwrap()];
+        let equity = estimate_equity_seeded(&hands, &[], 2_500, 7).unwrap();
+        assert_eq!(equity, estimate_equity_seeded(&hands, &[], 2_500, 7).unwrap());
+        assert!((equity.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        assert!(equity[0] > equity[1] && equity[1] > equity[2], "{equity:?}");
+        assert!(estimate_equity_seeded(&hands, &[], 0, 7).is_err());
+    }
+
+    #[test]
+    fn test_exact_equity_counts_every_runout() {
+        let hands = vec![parse_cards("Ah Kh").unwrap(), parse_cards("Qc Qd").unwrap()];
+        let turn = parse_cards("2h 7h 3c Qs").unwrap();
+        // Seven of the 44 cards left make the flush; the three and queen of hearts fill the queens up
+        assert_eq!(exact_equity(&hands, &turn).unwrap(), vec![7.0 / 44.0, 37.0 / 44.0]);
+        let flop = exact_equity(&hands, &turn[..3]).unwrap();
+        assert!((flop.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        assert_eq!(flop[0], 557.0 / 990.0); // The nut flush draw with two overcards is ahead of the queens
+        assert!(matches!(exact_equity(&hands, &[]), Err(GameError::InvalidRequest(_))));
+    }
+
+    #[test]
+    fn test_hand_percentile() {
+        let percentile = |hole_cards: &str, board: &str| hand_percentile(&parse_cards(hole_cards).unwrap(), &parse_cards(board).unwrap());
+        assert_eq!(percentile("Ah Kh", "Qh Jh Th 2c 3d"), Ok(1.0)); // Nothing beats or ties a royal flush
+        let weak = percentile("7c 2d", "Ah Kd Qs 9h 4c").unwrap();
+        assert!(weak < 0.2, "{weak}"); // Only worse high cards lose to it
+        let two_pair = percentile("Ah Kd", "As Kc 7h").unwrap();
+        assert!(two_pair > 0.9 && two_pair < 1.0, "{two_pair}"); // Only sets beat top two pair, and the other ace-kings tie
+
+        assert!(matches!(percentile("Ah Kd", "As Kc"), Err(GameError::InvalidRequest(_))));
+        assert!(matches!(percentile("Ah Kd", "Ah Kc 7h"), Err(GameError::DuplicateCard(_))));
+    }
+
+    #[test]
+    fn test_equity_against_a_range() {
+        let hero = parse_cards("Ah Kh").unwrap();
+        let board = parse_cards("Ad 7c 2s 9d Tc").unwrap();
+        let range: Range = "AA, KK, QQ".parse().unwrap();
+        let result = equity_vs_range(&hero, &range, &board, 1, 7).unwrap();
+        // One pair of aces is left, which makes trips; the kings and queens lose to top pair
+        assert_eq!(result.combos.len(), 1 + 3 + 6);
+        assert!(result.combos.iter().all(|combo| combo.equity == if combo.cards[0].rank == Rank::Ace { 0.0 } else { 1.0 }));
+        assert!((result.equity - 0.9).abs() < 1e-9, "{result:?}");
+
+        let flop = &board[..3];
+        assert_eq!(equity_vs_range(&hero, &range, flop, 500, 7), equity_vs_range(&hero, &range, flop, 500, 7));
+        let blocked: Range = "AhAd".parse().unwrap();
+        assert!(matches!(equity_vs_range(&hero, &blocked, &board, 1, 7), Err(GameError::InvalidRequest(_))));
+    }
+}
@@ -0,0 +1,156 @@
+use crate::buy_in::BuyInKind;
+use crate::card_dealer::Card;
+use crate::chips::Chips;
+use crate::player::PlayerAction;
+use crate::poker_hand::HandRank;
+use crate::player_id::PlayerId;
+
+/// Something that happened at the table.
+///
+/// Events are public: they never carry hole cards, so they can be fanned out
+/// to spectators and external consumers as-is.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type", rename_all = "snake_case"))]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub enum GameEvent {
+    HandStarted { hand_number: u64, players: Vec<PlayerId> },
+    BoardDealt { hand_number: u64, cards: Vec<Card> },
+    ExtraBoardDealt { hand_number: u64, board: usize, cards: Vec<Card> }, // `board` counts from 1, so the second board is 2
+    PlayerActed { hand_number: u64, player_id: PlayerId, action: PlayerAction },
+    ActionRequired { hand_number: u64, player_id: PlayerId, to_call: Chips, pot: Chips, pot_odds: f64, effective_stack: Chips, min_raise_to: Chips }, // The turn passed to `player_id`
+    ActionTimedOut { hand_number: u64, player_id: PlayerId, action: PlayerAction }, // The table acted for `player_id` when their time ran out
+    AllInEquity { hand_number: u64, equities: Vec<(PlayerId, f64)> }, // Each live player's expected share of the pots, once betting ends before the river
+    RunoutVoteOpened { hand_number: u64, players: Vec<PlayerId>, max_runs: usize }, // Each of `players` chooses how many times to run the board
+    RunsChosen { hand_number: u64, player_id: PlayerId, runs: usize },
+    RunsAgreed { hand_number: u64, runs: usize },
+    RunoutResults { hand_number: u64, runouts: Vec<Runout> }, // At showdown of a board run more than once, one entry per run
+    InsuranceOffered { hand_number: u64, player_id: PlayerId, equity: f64, coverage: Chips, premium: Chips },
+    InsuranceDecided { hand_number: u64, player_id: PlayerId, bought: bool },
+    InsuranceSettled { hand_number: u64, player_id: PlayerId, payout: Chips },
+    RabbitHunted { hand_number: u64, player_id: PlayerId, cards: Vec<Card> }, // Shown after the hand, never dealt; play isn't affected
+    HandFinished { hand_number: u64, winners: Vec<PlayerId> },
+    CardsShown { hand_number: u64, player_id: PlayerId, cards: Vec<Card> }, // Shown by choice by a winner who wasn't called
+    PotAwarded { hand_number: u64, pot: usize, amount: Chips, winners: Vec<PlayerId> },
+    PlayerBusted { player_id: PlayerId },
+    BountyAwarded { hand_number: u64, player_id: PlayerId, eliminated_by: Vec<PlayerId>, amount: Chips },
+    SevenDeuceBonus { hand_number: u64, player_id: PlayerId, amount: Chips }, // `amount` is the total collected from the table
+    BadBeatJackpot { hand_number: u64, losing_hand: HandRank, payouts: Vec<(PlayerId, Chips)>, pool: Chips }, // `pool` is what's left to seed the next jackpot
+    HandCancelled { hand_number: u64 },
+    PlayerSatOut { player_id: PlayerId },
+    PlayerRemoved { player_id: PlayerId },
+    ChipsAdjusted { player_id: PlayerId, chip_stack: Chips },
+    ChipsBought { player_id: PlayerId, kind: BuyInKind, amount: Chips, chip_stack: Chips },
+    WaitlistJoined { player_id: PlayerId, position: usize },
+    SeatOpened { table_position: usize },
+    SeatReserved { player_id: PlayerId, invited_by: PlayerId }, // A seat is held for `player_id` until they join or it is released
+    SeatReleased { player_id: PlayerId },
+    PlayerSeated { player_id: PlayerId, table_position: usize, chip_stack: Chips },
+    TournamentEntry { player_id: PlayerId, entry: u32, prize_pool: Chips },
+    BlindsChanged { small_blind: Chips, big_blind: Chips },
+    BreakStarted,
+    BreakEnded,
+    HandForHandStarted,
+    HandForHandEnded,
+    TablePaused,
+    TableResumed,
+}
+
+impl GameEvent {
+    /// The event's `type` tag, e.g. `"hand_finished"`.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            GameEvent::HandStarted { .. } => "hand_started",
+            GameEvent::BoardDealt { .. } => "board_dealt",
+            GameEvent::ExtraBoardDealt { .. } => "extra_board_dealt",
+            GameEvent::PlayerActed { .. } => "player_acted",
+            GameEvent::ActionRequired { .. } => "action_required",
+            GameEvent::ActionTimedOut { .. } => "action_timed_out",
+            GameEvent::AllInEquity { .. } => "all_in_equity",
+            GameEvent::RunoutVoteOpened { .. } => "runout_vote_opened",
+            GameEvent::RunsChosen { .. } => "runs_chosen",
+            GameEvent::RunsAgreed { .. } => "runs_agreed",
+            GameEvent::RunoutResults { .. } => "runout_results",
+            GameEvent::InsuranceOffered { .. } => "insurance_offered",
+            GameEvent::InsuranceDecided { .. } => "insurance_decided",
+            GameEvent::InsuranceSettled { .. } => "insurance_settled",
+            GameEvent::RabbitHunted { .. } => "rabbit_hunted",
+            GameEvent::CardsShown { .. } => "cards_shown",
+            GameEvent::HandFinished { .. } => "hand_finished",
+            GameEvent::PotAwarded { .. } => "pot_awarded",
+            GameEvent::PlayerBusted { .. } => "player_busted",
+            GameEvent::BountyAwarded { .. } => "bounty_awarded",
+            GameEvent::BadBeatJackpot { .. } => "bad_beat_jackpot",
+            GameEvent::SevenDeuceBonus { .. } => "seven_deuce_bonus",
+            GameEvent::HandCancelled { .. } => "hand_cancelled",
+            GameEvent::PlayerSatOut { .. } => "player_sat_out",
+            GameEvent::PlayerRemoved { .. } => "player_removed",
+            GameEvent::ChipsAdjusted { .. } => "chips_adjusted",
+            GameEvent::ChipsBought { .. } => "chips_bought",
+            GameEvent::WaitlistJoined { .. } => "waitlist_joined",
+            GameEvent::SeatOpened { .. } => "seat_opened",
+            GameEvent::SeatReserved { .. } => "seat_reserved",
+            GameEvent::SeatReleased { .. } => "seat_released",
+            GameEvent::PlayerSeated { .. } => "player_seated",
+            GameEvent::TournamentEntry { .. } => "tournament_entry",
+            GameEvent::BlindsChanged { .. } => "blinds_changed",
+            GameEvent::BreakStarted => "break_started",
+            GameEvent::BreakEnded => "break_ended",
+            GameEvent::HandForHandStarted => "hand_for_hand_started",
+            GameEvent::HandForHandEnded => "hand_for_hand_ended",
+            GameEvent::TablePaused => "table_paused",
+            GameEvent::TableResumed => "table_resumed",
+        }
+    }
+
+    /// The hand the event belongs to, for events of a hand.
+    pub fn hand_number(&self) -> Option<u64> {
+        match self {
+            GameEvent::HandStarted { hand_number, .. }
+            | GameEvent::BoardDealt { hand_number, .. }
+            | GameEvent::ExtraBoardDealt { hand_number, .. }
+            | GameEvent::PlayerActed { hand_number, .. }
+            | GameEvent::ActionRequired { hand_number, .. }
+            | GameEvent::ActionTimedOut { hand_number, .. }
+            | GameEvent::AllInEquity { hand_number, .. }
+            | GameEvent::RunoutVoteOpened { hand_number, .. }
+            | GameEvent::RunsChosen { hand_number, .. }
+            | GameEvent::RunsAgreed { hand_number, .. }
+            | GameEvent::RunoutResults { hand_number, .. }
+            | GameEvent::InsuranceOffered { hand_number, .. }
+            | GameEvent::InsuranceDecided { hand_number, .. }
+            | GameEvent::InsuranceSettled { hand_number, .. }
+            | GameEvent::RabbitHunted { hand_number, .. }
+            | GameEvent::CardsShown { hand_number, .. }
+            | GameEvent::HandFinished { hand_number, .. }
+            | GameEvent::PotAwarded { hand_number, .. }
+            | GameEvent::BountyAwarded { hand_number, .. }
+            | GameEvent::SevenDeuceBonus { hand_number, .. }
+            | GameEvent::BadBeatJackpot { hand_number, .. }
+            | GameEvent::HandCancelled { hand_number } => Some(*hand_number),
+            _ => None,
+        }
+    }
+}
+
+/// One run of the board and what it won.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct Runout {
+    pub board: Vec<Card>,                // All five cards, the ones dealt before the all-in included
+    pub winners: Vec<Vec<PlayerId>>,     // Winners of each pot on this board, main pot first
+    pub payouts: Vec<(PlayerId, Chips)>, // Each pot's share for this run, split between its winners
+}
+
+/// A card leaving the deck, for the operator's deal audit trail.
+///
+/// Unlike events these carry hole cards, so they are never fanned out to
+/// players or spectators.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "type", rename_all = "snake_case"))]
+pub enum DealRecord {
+    DeckShuffled { hand_number: u64, deck: Vec<Card> }, // The whole deck, in the order it will be dealt
+    HoleCards { hand_number: u64, player_id: PlayerId, cards: Vec<Card> },
+    CommunityCards { hand_number: u64, board: usize, cards: Vec<Card> }, // `board` counts from 1; only the newly dealt cards
+    RabbitHunt { hand_number: u64, player_id: PlayerId, cards: Vec<Card> }, // Looked at after the hand, left in the deck
+}
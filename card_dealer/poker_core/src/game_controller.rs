@@ -0,0 +1,3404 @@
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+#[cfg(feature = "metrics")]
+use std::time::Instant;
+
+use rand::rngs::StdRng;
+use rand::{RngCore, SeedableRng};
+use tracing::{debug, info, info_span, Span};
+
+use crate::betting::{side_pots, AllInShare, BettingState, HandLog, InsuranceOffer, LoggedAction, LoggedPlayer, RunoutVote, Street, StreetBets};
+use crate::bots::{ActionContext, BotStrategy};
+use crate::bounty::{BountyRules, Elimination};
+use crate::buy_in::{BuyInKind, BuyInRules};
+use crate::card_dealer::{Card, Deck, Rank};
+use crate::chips::Chips;
+use crate::currency::{Bank, CurrencyMode};
+use crate::entropy::{EntropySource, RngSource};
+use crate::equity::{estimate_equity_seeded, exact_equity};
+use crate::events::{DealRecord, GameEvent, Runout};
+use crate::game_error::GameError;
+use crate::hints::{self, Hint};
+use crate::jackpot::{Jackpot, JackpotRules};
+use crate::ledger::{Account, Ledger, TransactionKind};
+#[cfg(feature = "metrics")]
+use crate::metrics::METRICS;
+use crate::snapshot::{GameSnapshot, PlayerSnapshot};
+use crate::player::{Player, PlayerAction};
+use crate::player_id::PlayerId;
+use crate::player_registry::PlayerRegistry;
+use crate::poker_hand::{Hand, HandRank};
+use crate::preflop_chart::{Position, PreflopChart};
+use crate::rake::RakeRules;
+use crate::table::{Pot, Table}; // Import Table
+use crate::table_config::TableConfig;
+use crate::tournament::{Tournament, TournamentRules};
+use crate::variant::GameVariant;
+use crate::wild_cards::WildCards;
+use crate::waitlist::{Seating, WaitingList, WaitingPlayer};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Seats at a table unless `set_max_seats` says otherwise.
+pub const DEFAULT_MAX_SEATS: usize = 9;
+
+/// Most times players all in before the river may run the rest of the board.
+pub const MAX_RUNS: usize = 3;
+
+/// Runouts dealt to price the pots when the betting ends all-in before the river.
+const ALL_IN_TRIALS: u32 = 2_000;
+
+pub struct GameController {
+    rng: Box<dyn EntropySource>,      // Shuffles every deck dealt at this table
+    deck: Deck,
+    community_cards: Vec<Card>,       // Shared cards on the table
+    players: PlayerRegistry,          // All players in the game, indexed by id
+    table: Table,                     // The game table
+    hand_number: u64,                 // Incremented each time hole cards are dealt
+    hand_in_progress: bool,           // Hole cards are out but the hand hasn't reached showdown
+    accepting_hands: bool,            // Cleared during shutdown so no new hand starts
+    paused: bool,                     // Set by an admin to hold the table between hands
+    on_break: bool,                   // Set by the tournament clock during a scheduled break
+    blinds: Option<(Chips, Chips)>,   // Small and big blind `start_next_hand` posts
+    big_blind_ante: Chips,            // Ante the big blind posts for the whole table from the next hand on
+    hand_for_hand: bool,              // Set on the money bubble: one hand at a time across the tournament's tables
+    next_hand_released: bool,         // During hand-for-hand, whether one more hand may start here
+    events: Vec<GameEvent>,           // Public events not yet collected with `take_events`
+    deal_records: Vec<DealRecord>,    // Cards dealt, not yet collected with `take_deal_records`
+    betting: Option<BettingState>,    // Set while a hand started with `start_hand` is being bet
+    button: Option<usize>,            // Index of the player who had the button last hand
+    bots: HashMap<PlayerId, Box<dyn BotStrategy>>, // Seats played by the engine itself
+    hand_log: Option<HandLog>,        // The latest hand started with `start_hand`
+    finished_hands: Vec<HandLog>,     // Hands finished, not yet collected with `take_finished_hands`
+    unseen_board: Option<(u64, Vec<Card>)>, // The rest of the board had the last hand gone on, by hand number
+    rabbit_hunts: HashMap<PlayerId, u64>, // Hand each player last hunted the rabbit on
+    deck_stacked: bool,               // The deck was set with `stack_deck` and not dealt from yet
+    chips_in_play: Chips,             // Chips seated players should hold between them, stacks plus bets
+    ledger: Ledger,                   // Every chip movement, reconciled against the stacks by `verify_invariants`
+    currency: CurrencyMode,           // Whether chips are free or drawn from `bank`
+    bank: Option<Arc<dyn Bank>>,      // The players' balances a tracked table draws from
+    buy_in_rules: BuyInRules,         // What players may buy in, rebuy or add on for
+    max_seats: usize,                 // Players who can sit at once; more go on the waiting list
+    waitlist: WaitingList,            // Players waiting for a seat, next in line first
+    reserved_seats: Vec<PlayerId>,    // Players a seat is held for, e.g. after an invitation; counted as taken
+    bounty_rules: Option<BountyRules>, // Set for knockout tournaments
+    tournament: Option<Tournament>,   // Entries and prize pool, for tournament tables
+    variant: GameVariant,             // The game dealt from the next hand on
+    wild_cards: WildCards,            // Cards that stand for whatever makes the best hand
+    jackpot: Option<Jackpot>,         // Bad beat jackpot pool, when the table runs one
+    seven_deuce_bonus: Option<Chips>, // Paid by everyone dealt in to a player who wins a pot with 7-2 offsuit
+    training_mode: bool,              // Players may ask for hints on their turn
+    max_runs: usize,                  // Most times players all in before the river may run the board; 1 always runs it once
+    insurance: bool,                  // Offers the favourite of a heads-up all-in insurance on their share of the pot
+    rake: RakeRules,                  // The house's share of each hand's pots
+    straddles: bool,                  // Under the gun may straddle, posting twice the big blind
+    straddle_requests: HashSet<PlayerId>, // Players who asked to straddle the next hand
+    action_timeout_secs: Option<u64>, // Seconds a player has to act, for the host to enforce; no limit when unset
+    preflop_chart: PreflopChart,      // The starting hands hints recommend
+}
+
+impl GameController {
+    /// Creates a controller whose decks are shuffled from OS entropy
+    #[cfg(feature = "os-rng")]
+    pub fn new() -> Self {
+        Self::with_rng(StdRng::from_entropy())
+    }
+
+    /// Creates a controller whose decks are shuffled with `rng`, e.g. a seeded RNG for replays
+    pub fn with_rng(rng: impl RngCore + Send + 'static) -> Self {
+        Self::with_entropy(RngSource(rng))
+    }
+
+    /// Creates a controller whose decks are shuffled from `source`
+    pub fn with_entropy(source: impl EntropySource + 'static) -> Self {
+        let mut rng: Box<dyn EntropySource> = Box::new(source);
+        debug!(source = rng.name(), "entropy source chosen");
+        rng.begin_shuffle();
+        Self {
+            deck: Deck::shuffled(&mut rng),
+            rng,
+            community_cards: Vec::new(),
+            players: PlayerRegistry::default(),
+            table: Table::new(), // Initialize the table
+            hand_number: 0,
+            hand_in_progress: false,
+            accepting_hands: true,
+            paused: false,
+            on_break: false,
+            blinds: None,
+            big_blind_ante: Chips::ZERO,
+            hand_for_hand: false,
+            next_hand_released: false,
+            events: Vec::new(),
+            deal_records: Vec::new(),
+            betting: None,
+            button: None,
+            bots: HashMap::new(),
+            hand_log: None,
+            finished_hands: Vec::new(),
+            unseen_board: None,
+            rabbit_hunts: HashMap::new(),
+            deck_stacked: false,
+            chips_in_play: Chips::ZERO,
+            ledger: Ledger::new(),
+            currency: CurrencyMode::default(),
+            bank: None,
+            buy_in_rules: BuyInRules::default(),
+            max_seats: DEFAULT_MAX_SEATS,
+            waitlist: WaitingList::default(),
+            reserved_seats: Vec::new(),
+            bounty_rules: None,
+            tournament: None,
+            variant: GameVariant::default(),
+            wild_cards: WildCards::default(),
+            jackpot: None,
+            seven_deuce_bonus: None,
+            training_mode: false,
+            max_runs: 1,
+            insurance: false,
+            rake: RakeRules::default(),
+            straddles: false,
+            straddle_requests: HashSet::new(),
+            action_timeout_secs: None,
+            preflop_chart: PreflopChart::builtin().clone(),
+        }
+    }
+
+    /// Initializes players with their names and other parameters
+    pub fn initialize_players(
+        &mut self,
+        player_data: Vec<(impl Into<PlayerId>, String, usize, Chips)>, // (player_id, display_name, table_position, chip_stack)
+    ) {
+        self.players = PlayerRegistry::new(
+            player_data
+                .into_iter()
+                .map(|(player_id, display_name, table_position, chip_stack)| {
+                    Player::new(player_id.into(), display_name, table_position, chip_stack)
+                })
+                .collect(),
+        );
+        self.chips_in_play = self.players.iter().map(|player| player.chip_stack).sum();
+        self.ledger = Ledger::new();
+        for player in &self.players {
+            let account = Account::Player(player.player_id.clone());
+            self.ledger.record(self.hand_number, TransactionKind::Seated, Account::Cashier, account, player.chip_stack);
+        }
+        let bounty = self.starting_bounty();
+        for player in &mut self.players {
+            player.bounty = bounty;
+        }
+    }
+
+    /// Captures the seats and stacks so the table can be reopened later
+    pub fn snapshot(&self) -> GameSnapshot {
+        GameSnapshot {
+            hand_number: self.hand_number,
+            players: self
+                .players
+                .iter()
+                .map(|player| PlayerSnapshot {
+                    player_id: player.player_id.clone(),
+                    display_name: player.display_name.clone(),
+                    table_position: player.table_position,
+                    chip_stack: player.chip_stack,
+                })
+                .collect(),
+        }
+    }
+
+    /// Reseats the players from a snapshot taken by `snapshot`
+    pub fn restore(&mut self, snapshot: GameSnapshot) {
+        self.hand_number = snapshot.hand_number;
+        self.initialize_players(
+            snapshot
+                .players
+                .into_iter()
+                .map(|p| (p.player_id, p.display_name, p.table_position, p.chip_stack))
+                .collect(),
+        );
+    }
+
+    /// Span covering everything that happens during the current hand
+    fn hand_span(&self) -> Span {
+        info_span!("hand", hand_id = self.hand_number)
+    }
+
+    /// Number of the hand currently in progress (0 before the first deal)
+    pub fn hand_number(&self) -> u64 {
+        self.hand_number
+    }
+
+    /// Deals hole cards to each player
+    pub fn deal_hole_cards(&mut self) -> Result<(), GameError> {
+        self.begin_hand()?;
+        self.deal_hole_cards_where(|_| true)
+    }
+
+    /// Opens the next hand and numbers it. Refused while the server is
+    /// shutting down or the table is paused, before anything is dealt or bet.
+    fn begin_hand(&mut self) -> Result<(), GameError> {
+        if !self.accepting_hands {
+            return Err(GameError::InvalidState("the server is shutting down and not starting new hands".to_string()));
+        }
+        if self.paused {
+            return Err(GameError::InvalidState("the table is paused".to_string()));
+        }
+        if self.on_break {
+            return Err(GameError::InvalidState("the tournament is on a break".to_string()));
+        }
+        if self.hand_for_hand && !std::mem::take(&mut self.next_hand_released) {
+            return Err(GameError::InvalidState("playing hand-for-hand: waiting for the other tables to finish their hand".to_string()));
+        }
+        self.hand_number += 1;
+        Ok(())
+    }
+
+    /// Deals hole cards to the players `dealt_in` accepts
+    fn deal_hole_cards_where(&mut self, dealt_in: fn(&Player) -> bool) -> Result<(), GameError> {
+        self.deck_stacked = false;
+        let _span = self.hand_span().entered();
+
+        let count = self.variant.hole_cards();
+        self.deal_records.push(DealRecord::DeckShuffled { hand_number: self.hand_number, deck: self.deck.cards().to_vec() });
+        for player in self.players.iter_mut().filter(|player| dealt_in(player)) {
+            if let Some(cards) = self.deck.deal(count) {
+                self.deal_records.push(DealRecord::HoleCards {
+                    hand_number: self.hand_number,
+                    player_id: player.player_id.clone(),
+                    cards: cards.clone(),
+                });
+                player.hole_cards = cards;
+            } else {
+                return Err(GameError::NotEnoughCards("hole cards"));
+            }
+        }
+        self.check_cards()?;
+        #[cfg(feature = "metrics")]
+        METRICS.hands_dealt.inc();
+        self.hand_in_progress = true;
+        let players: Vec<PlayerId> = self.players.iter()
+            .filter(|player| dealt_in(player))
+            .map(|player| player.player_id.clone())
+            .collect();
+        info!(players = players.len(), "hole cards dealt");
+        self.events.push(GameEvent::HandStarted { hand_number: self.hand_number, players });
+        Ok(())
+    }
+
+    /// Deals community cards
+    pub fn deal_community_cards(&mut self) -> Result<(), GameError> {
+        let _span = self.hand_span().entered();
+        if let Some(cards) = self.deck.deal(5) {
+            self.deal_records.push(DealRecord::CommunityCards { hand_number: self.hand_number, board: 1, cards: cards.clone() });
+            self.community_cards = cards;
+            self.check_cards()?;
+            info!(board = ?self.community_cards, "community cards dealt");
+            self.events.push(GameEvent::BoardDealt {
+                hand_number: self.hand_number,
+                cards: self.community_cards.clone(),
+            });
+            self.evaluate_player_hands(); // Evaluate hands after dealing community cards
+            self.hand_in_progress = false;
+
+            let in_play: Vec<PlayerId> = self.players.iter()
+                .filter(|player| player.is_in_play)
+                .map(|player| player.player_id.clone())
+                .collect();
+            self.events.push(GameEvent::HandFinished {
+                hand_number: self.hand_number,
+                winners: self.get_winners(&in_play).unwrap_or_default(),
+            });
+            for player in self.players.iter().filter(|player| player.chip_stack.is_zero()) {
+                self.events.push(GameEvent::PlayerBusted { player_id: player.player_id.clone() });
+            }
+            Ok(())
+        } else {
+            Err(GameError::NotEnoughCards("community cards"))
+        }
+    }
+
+    /// Clears everything left from the last hand: the deck, the board, the
+    /// bets and pots, and each player's cards, evaluation and actions.
+    pub fn reset_for_new_hand(&mut self) {
+        self.table.reset_for_new_round();
+        self.reset_deck();
+    }
+
+    /// Resets the deck and clears all players' hole cards
+    pub fn reset_deck(&mut self) {
+        let _span = self.hand_span().entered();
+        if std::mem::take(&mut self.deck_stacked) {
+            info!("deck reset, keeping the stacked deck");
+        } else {
+            info!("deck reset");
+            self.rng.begin_shuffle();
+            self.deck.reset_with(&mut self.rng);
+        }
+        self.community_cards.clear();
+        self.hand_in_progress = false;
+        self.betting = None;
+        for player in &mut self.players {
+            player.reset_for_new_hand();
+        }
+    }
+
+    /// Makes the next hand deal `cards` in order, followed by the rest of the
+    /// deck unshuffled. Each player dealt in gets all their hole cards in turn,
+    /// in seat order, then the board is dealt with no burn cards. A card may
+    /// be stacked once for each deck in the shoe. For tests and for
+    /// reproducing disputed hands.
+    pub fn stack_deck(&mut self, mut cards: Vec<Card>) -> Result<(), GameError> {
+        if self.hand_in_progress {
+            return Err(GameError::InvalidState("a hand is in progress".to_string()));
+        }
+        let mut rest: Vec<Card> = (0..self.deck.decks()).flat_map(|_| Deck::all_cards()).collect();
+        for card in &cards {
+            if let Some(position) = rest.iter().position(|left| left == card) {
+                rest.remove(position);
+            }
+        }
+        cards.extend(rest);
+        self.deck = Deck::from_shoe(cards, self.deck.decks())?.with_settings_of(&self.deck);
+        self.deck_stacked = true;
+        info!("deck stacked");
+        Ok(())
+    }
+
+    /// Stops new hands from being dealt; the current hand may still finish
+    pub fn stop_new_hands(&mut self) {
+        self.accepting_hands = false;
+    }
+
+    /// Holds the table: no new hand is dealt until `resume`. The hand in
+    /// progress plays on.
+    pub fn pause(&mut self) {
+        if self.paused {
+            return;
+        }
+        self.paused = true;
+        info!("table paused");
+        self.events.push(GameEvent::TablePaused);
+    }
+
+    pub fn resume(&mut self) {
+        if !self.paused {
+            return;
+        }
+        self.paused = false;
+        info!("table resumed");
+        self.events.push(GameEvent::TableResumed);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Starts or ends a scheduled tournament break. Like a pause, a break only
+    /// holds the next hand; the hand in progress plays on.
+    pub fn set_on_break(&mut self, on_break: bool) {
+        if self.on_break == on_break {
+            return;
+        }
+        self.on_break = on_break;
+        info!(on_break, "tournament break");
+        self.events.push(if on_break { GameEvent::BreakStarted } else { GameEvent::BreakEnded });
+    }
+
+    pub fn is_on_break(&self) -> bool {
+        self.on_break
+    }
+
+    /// Starts or ends hand-for-hand play. While it lasts each hand has to be
+    /// released with `release_next_hand`, which the tournament coordinator
+    /// does once every table has finished the hand before.
+    pub fn set_hand_for_hand(&mut self, hand_for_hand: bool) {
+        if self.hand_for_hand == hand_for_hand {
+            return;
+        }
+        self.hand_for_hand = hand_for_hand;
+        self.next_hand_released = false;
+        info!(hand_for_hand, "hand-for-hand play");
+        self.events.push(if hand_for_hand { GameEvent::HandForHandStarted } else { GameEvent::HandForHandEnded });
+    }
+
+    pub fn is_hand_for_hand(&self) -> bool {
+        self.hand_for_hand
+    }
+
+    /// Lets one more hand start during hand-for-hand play.
+    pub fn release_next_hand(&mut self) {
+        self.next_hand_released = true;
+    }
+
+    /// Players still in the tournament here: seated with chips, or waiting for a seat.
+    pub fn players_remaining(&self) -> usize {
+        self.players.iter().filter(|player| !player.chip_stack.is_zero()).count() + self.waitlist.len()
+    }
+
+    /// The small and big blind the next hand started with `start_next_hand` posts.
+    pub fn blinds(&self) -> Option<(Chips, Chips)> {
+        self.blinds
+    }
+
+    /// Sets the table up with `config` from the next hand on. Nothing
+    /// changes unless every setting is valid; refused during a hand.
+    pub fn configure(&mut self, config: &TableConfig) -> Result<(), GameError> {
+        config.validate()?;
+        if self.hand_in_progress {
+            return Err(GameError::InvalidState("the table can't be set up again during a hand".to_string()));
+        }
+        self.set_currency_mode(config.currency)?;
+        self.set_variant(config.variant)?;
+        self.set_blinds(config.small_blind, config.big_blind)?;
+        self.set_buy_in_rules(config.buy_in.clone());
+        self.set_max_seats(config.max_seats);
+        self.set_wild_cards(config.wild_cards)?;
+        if config.decks != self.deck.decks() {
+            self.set_decks(config.decks)?;
+        }
+        self.set_continuous_shuffle(config.continuous_shuffle);
+        self.set_training_mode(config.training_mode);
+        self.set_max_runs(config.max_runs)?;
+        self.set_insurance(config.insurance);
+        self.set_big_blind_ante(config.big_blind_ante);
+        self.set_rake(config.rake.clone())?;
+        self.set_straddles(config.straddles);
+        self.set_action_timeout(config.action_timeout_secs);
+        Ok(())
+    }
+
+    /// Sets the blinds for the next hand; a hand in progress keeps the blinds
+    /// it was started with.
+    pub fn set_blinds(&mut self, small_blind: Chips, big_blind: Chips) -> Result<(), GameError> {
+        check_blinds(small_blind, big_blind)?;
+        if self.blinds == Some((small_blind, big_blind)) {
+            return Ok(());
+        }
+        self.blinds = Some((small_blind, big_blind));
+        self.table.min_bet = big_blind;
+        info!(%small_blind, %big_blind, "blinds set for the next hand");
+        self.events.push(GameEvent::BlindsChanged { small_blind, big_blind });
+        Ok(())
+    }
+
+    pub fn big_blind_ante(&self) -> Chips {
+        self.big_blind_ante
+    }
+
+    /// Has the big blind post `ante` for the whole table from the next hand
+    /// on, as dead money in the main pot; zero for no ante.
+    pub fn set_big_blind_ante(&mut self, ante: Chips) {
+        if ante != self.big_blind_ante {
+            info!(%ante, "big blind ante set for the next hand");
+            self.big_blind_ante = ante;
+        }
+    }
+
+    /// Hands over the events recorded since the last call, oldest first.
+    ///
+    /// The engine only records what happened; whoever drives it decides how
+    /// the events are numbered and delivered.
+    pub fn take_events(&mut self) -> Vec<GameEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// Returns and clears every card dealt since the last call, hole cards
+    /// included, for an audit trail of the deals.
+    pub fn take_deal_records(&mut self) -> Vec<DealRecord> {
+        std::mem::take(&mut self.deal_records)
+    }
+
+    /// Returns and clears the logs of the hands started with `start_hand`
+    /// that have finished since the last call, for a hand history.
+    pub fn take_finished_hands(&mut self) -> Vec<HandLog> {
+        std::mem::take(&mut self.finished_hands)
+    }
+
+    /// True while hole cards are out and the hand hasn't been shown down
+    pub fn is_hand_in_progress(&self) -> bool {
+        self.hand_in_progress
+    }
+
+    /// Abandons the current hand, returning every chip committed to it
+    pub fn cancel_hand(&mut self) {
+        let _span = self.hand_span().entered();
+        for player in &mut self.players {
+            let committed = self.table.committed(&player.player_id);
+            if !committed.is_zero() {
+                player.add_chips(committed);
+                self.ledger.record(self.hand_number, TransactionKind::Refund, Account::Pot, Account::Player(player.player_id.clone()), committed);
+                info!(player_id = %player.player_id, refund = %committed, "bet refunded");
+            }
+        }
+        self.table.reset_for_new_round();
+        self.reset_deck();
+        info!("hand cancelled");
+        self.events.push(GameEvent::HandCancelled { hand_number: self.hand_number });
+    }
+
+    /// Evaluates the best hand for each player
+    pub fn evaluate_player_hands(&mut self) {
+        #[cfg(feature = "metrics")]
+        let started = Instant::now();
+        #[cfg(feature = "parallel")]
+        self.players.as_mut_slice().par_iter_mut().for_each(|player| player.evaluate_hand_with_wilds(self.variant, self.wild_cards, &self.community_cards));
+        #[cfg(not(feature = "parallel"))]
+        for player in &mut self.players {
+            player.evaluate_hand_with_wilds(self.variant, self.wild_cards, &self.community_cards);
+        }
+        #[cfg(feature = "metrics")]
+        METRICS.hand_evaluation.observe(started.elapsed());
+        for player in &self.players {
+            debug!(player_id = %player.player_id, hand = ?player.hand_strength, "hand evaluated");
+        }
+
+        self.resolve_pots();
+    }
+
+    pub fn get_players(&self) -> &Vec<Player> {
+        self.players.players()
+    }
+
+    /// Looks up a seated player by id.
+    pub fn find_player(&self, player_id: &str) -> Result<&Player, GameError> {
+        self.players.get(player_id).ok_or_else(|| GameError::PlayerNotFound(player_id.to_string()))
+    }
+
+    fn find_player_mut(&mut self, player_id: &str) -> Result<&mut Player, GameError> {
+        self.players.get_mut(player_id).ok_or_else(|| GameError::PlayerNotFound(player_id.to_string()))
+    }
+
+    /// The seated player's id, shared with the registry instead of copied.
+    fn player_id(&self, player_id: &str) -> Result<PlayerId, GameError> {
+        self.players.id(player_id).ok_or_else(|| GameError::PlayerNotFound(player_id.to_string()))
+    }
+
+    /// Unseats a player. Refused while they are playing a hand, since their
+    /// chips may already be in the pot.
+    pub fn remove_player(&mut self, player_id: &str) -> Result<Player, GameError> {
+        if self.hand_in_progress && self.find_player(player_id)?.is_in_play {
+            return Err(GameError::InvalidState(format!("player {} is in the current hand", player_id)));
+        }
+        let player = self.players.remove(player_id).ok_or_else(|| GameError::PlayerNotFound(player_id.to_string()))?;
+        self.bots.remove(player_id);
+        info!(player_id, "player removed");
+        self.events.push(GameEvent::PlayerRemoved { player_id: player.player_id.clone() });
+        self.chips_in_play -= player.chip_stack;
+        self.record(TransactionKind::CashOut, Account::Player(player.player_id.clone()), Account::Cashier, player.chip_stack);
+        if let Some(bank) = self.tracked_bank() {
+            bank.deposit(player_id, player.chip_stack);
+        }
+        if self.has_free_seat() {
+            self.events.push(GameEvent::SeatOpened { table_position: player.table_position });
+            self.seat_from_waitlist();
+        }
+        Ok(player)
+    }
+
+    /// Seats a bot in the lowest free table position. It acts on its own
+    /// whenever it is its turn in a hand started with `start_hand`. Bots
+    /// have no balance to draw on, so they only sit at play-money tables.
+    pub fn seat_bot(&mut self, player_id: &str, display_name: &str, chip_stack: Chips, strategy: Box<dyn BotStrategy>) -> Result<(), GameError> {
+        if self.players.contains(player_id) {
+            return Err(GameError::InvalidRequest(format!("player {player_id} is already seated")));
+        }
+        if !self.has_free_seat() {
+            return Err(GameError::NotAllowed(format!("all {} seats are taken", self.max_seats)));
+        }
+        if self.currency == CurrencyMode::Tracked {
+            return Err(GameError::NotAllowed("bots can't sit at a tracked-currency table".to_string()));
+        }
+        self.seat(player_id.into(), display_name.to_string(), chip_stack);
+        self.set_bot(player_id, strategy)
+    }
+
+    /// Seats a player in the lowest free table position and returns it. A
+    /// player seated during a hand is dealt in from the next one.
+    fn seat(&mut self, player_id: PlayerId, display_name: String, chip_stack: Chips) -> usize {
+        let position = (0..).find(|position| self.players.iter().all(|player| player.table_position != *position)).unwrap_or(0);
+        let mut player = Player::new(player_id.clone(), display_name, position, chip_stack);
+        player.is_in_play = !self.hand_in_progress;
+        player.bounty = self.starting_bounty();
+        self.players.push(player);
+        self.chips_in_play += chip_stack;
+        self.record(TransactionKind::Seated, Account::Cashier, Account::Player(player_id.clone()), chip_stack);
+        info!(%player_id, position, %chip_stack, "player seated");
+        self.events.push(GameEvent::PlayerSeated { player_id, table_position: position, chip_stack });
+        position
+    }
+
+    pub fn max_seats(&self) -> usize {
+        self.max_seats
+    }
+
+    /// Sets how many players can sit at once. Nobody is unseated when it
+    /// shrinks; waiting players take any seats it adds.
+    pub fn set_max_seats(&mut self, max_seats: usize) {
+        self.max_seats = max_seats;
+        self.seat_from_waitlist();
+    }
+
+    pub fn bounty_rules(&self) -> Option<&BountyRules> {
+        self.bounty_rules.as_ref()
+    }
+
+    /// Makes the table a knockout tournament, or a regular one with `None`.
+    /// Every seated player's bounty starts over at the new amount.
+    pub fn set_bounty_rules(&mut self, rules: Option<BountyRules>) {
+        self.bounty_rules = rules;
+        let bounty = self.starting_bounty();
+        for player in &mut self.players {
+            player.bounty = bounty;
+        }
+    }
+
+    pub fn jackpot(&self) -> Option<&Jackpot> {
+        self.jackpot.as_ref()
+    }
+
+    /// Every chip movement at the table since the players were first seated.
+    pub fn ledger(&self) -> &Ledger {
+        &self.ledger
+    }
+
+    /// Checks the ledger against the stacks, the chips bet in the current
+    /// hand and the chips in play.
+    pub fn reconcile_ledger(&self) -> Result<(), Vec<String>> {
+        let pot = if self.hand_in_progress { self.table.total_committed() } else { Chips::ZERO };
+        let stacks = self.players.iter().map(|player| (&player.player_id, player.chip_stack));
+        self.ledger.reconcile(stacks, pot, self.chips_in_play)
+    }
+
+    pub fn currency_mode(&self) -> CurrencyMode {
+        self.currency
+    }
+
+    /// Sets where the balances of a tracked table are kept.
+    pub fn set_bank(&mut self, bank: Arc<dyn Bank>) {
+        self.bank = Some(bank);
+    }
+
+    /// Plays for free chips, or for chips drawn from the players' bank
+    /// balances on joining and buying in and paid back when they leave.
+    /// Chips seated with `initialize_players` or `restore` are taken as
+    /// already paid for. Only an empty table can change modes, and a tracked
+    /// one needs a bank.
+    pub fn set_currency_mode(&mut self, mode: CurrencyMode) -> Result<(), GameError> {
+        if mode == self.currency {
+            return Ok(());
+        }
+        if mode == CurrencyMode::Tracked && self.bank.is_none() {
+            return Err(GameError::InvalidState("a tracked table needs a bank to draw chips from".to_string()));
+        }
+        if !self.players.is_empty() || !self.waitlist.is_empty() {
+            return Err(GameError::NotAllowed("the currency can only change at an empty table".to_string()));
+        }
+        info!(?mode, "currency mode set");
+        self.currency = mode;
+        Ok(())
+    }
+
+    /// The bank chips are drawn from and paid back into, at a tracked table.
+    fn tracked_bank(&self) -> Option<&dyn Bank> {
+        self.bank.as_deref().filter(|_| self.currency == CurrencyMode::Tracked)
+    }
+
+    /// Records a chip movement during the current hand, or after the last one.
+    fn record(&mut self, kind: TransactionKind, from: Account, to: Account, amount: Chips) {
+        self.ledger.record(self.hand_number, kind, from, to, amount);
+    }
+
+    /// Runs a bad beat jackpot at this table, or stops with `None`. Changing
+    /// the rules keeps the chips already in the pool.
+    pub fn set_jackpot_rules(&mut self, rules: Option<JackpotRules>) -> Result<(), GameError> {
+        let Some(rules) = rules else {
+            self.jackpot = None;
+            return Ok(());
+        };
+        rules.validate().map_err(GameError::InvalidRequest)?;
+        match &mut self.jackpot {
+            Some(jackpot) => jackpot.rules = rules,
+            None => self.jackpot = Some(Jackpot::new(rules)),
+        }
+        Ok(())
+    }
+
+    pub fn seven_deuce_bonus(&self) -> Option<Chips> {
+        self.seven_deuce_bonus
+    }
+
+    /// Plays the seven-deuce game: whoever wins a pot holding 7-2 offsuit is
+    /// paid `bonus` by every other player dealt in. `None` turns it off.
+    pub fn set_seven_deuce_bonus(&mut self, bonus: Option<Chips>) {
+        self.seven_deuce_bonus = bonus.filter(|bonus| !bonus.is_zero());
+    }
+
+    pub fn is_training_mode(&self) -> bool {
+        self.training_mode
+    }
+
+    /// Lets players ask for a hint on their turn with `hint`.
+    pub fn set_training_mode(&mut self, training: bool) {
+        self.training_mode = training;
+    }
+
+    pub fn max_runs(&self) -> usize {
+        self.max_runs
+    }
+
+    /// Lets players all in before the river agree to run the rest of the
+    /// board up to `runs` times, each run winning an even share of the pots.
+    /// Takes effect at the next all-in; 1 turns it off.
+    pub fn set_max_runs(&mut self, runs: usize) -> Result<(), GameError> {
+        if !(1..=MAX_RUNS).contains(&runs) {
+            return Err(GameError::InvalidRequest(format!("the board can be run 1 to {MAX_RUNS} times")));
+        }
+        self.max_runs = runs;
+        Ok(())
+    }
+
+    pub fn insurance(&self) -> bool {
+        self.insurance
+    }
+
+    /// Offers all-in insurance from the next all-in on; see `buy_insurance`.
+    pub fn set_insurance(&mut self, insurance: bool) {
+        self.insurance = insurance;
+    }
+
+    pub fn rake(&self) -> &RakeRules {
+        &self.rake
+    }
+
+    /// Has the house keep a share of each hand's pots from the next hand on.
+    pub fn set_rake(&mut self, rake: RakeRules) -> Result<(), GameError> {
+        rake.validate().map_err(GameError::InvalidRequest)?;
+        self.rake = rake;
+        Ok(())
+    }
+
+    pub fn straddles(&self) -> bool {
+        self.straddles
+    }
+
+    /// Lets the player under the gun straddle from the next hand on; see
+    /// `request_straddle`.
+    pub fn set_straddles(&mut self, straddles: bool) {
+        self.straddles = straddles;
+        if !straddles {
+            self.straddle_requests.clear();
+        }
+    }
+
+    /// Seconds a player has to act before the host acts for them, if the
+    /// table has a limit. The engine doesn't keep time itself.
+    pub fn action_timeout_secs(&self) -> Option<u64> {
+        self.action_timeout_secs
+    }
+
+    pub fn set_action_timeout(&mut self, secs: Option<u64>) {
+        self.action_timeout_secs = secs;
+    }
+
+    /// Has `player_id` straddle the next hand dealt: if they are under the
+    /// gun in it, with three or more players dealt in, they post twice the
+    /// big blind before the cards and act last preflop. Ignored in a hand
+    /// where they aren't under the gun or can't cover the straddle.
+    pub fn request_straddle(&mut self, player_id: &str) -> Result<(), GameError> {
+        if !self.straddles {
+            return Err(GameError::NotAllowed("this table doesn't allow straddles".to_string()));
+        }
+        let player_id = self.find_player(player_id)?.player_id.clone();
+        info!(%player_id, "straddle requested for the next hand");
+        self.straddle_requests.insert(player_id);
+        Ok(())
+    }
+
+    pub fn preflop_chart(&self) -> &PreflopChart {
+        &self.preflop_chart
+    }
+
+    /// Replaces the starting-hand chart hints recommend preflop.
+    pub fn set_preflop_chart(&mut self, chart: PreflopChart) {
+        self.preflop_chart = chart;
+    }
+
+    /// The bounty a player sits down with.
+    fn starting_bounty(&self) -> Chips {
+        self.bounty_rules.map_or(Chips::ZERO, |rules| rules.bounty)
+    }
+
+    /// Players waiting for a seat, next in line first.
+    pub fn waitlist(&self) -> &WaitingList {
+        &self.waitlist
+    }
+
+    /// Sits a player down with `chip_stack`, within the table's buy-in
+    /// limits. When every seat is taken, or others are already waiting, they
+    /// join the end of the waiting list instead and are seated as seats open.
+    pub fn join_table(&mut self, player_id: &str, display_name: &str, chip_stack: Chips) -> Result<Seating, GameError> {
+        if self.players.contains(player_id) {
+            return Err(GameError::InvalidRequest(format!("player {player_id} is already seated")));
+        }
+        if self.waitlist.contains(player_id) {
+            return Err(GameError::InvalidRequest(format!("player {player_id} is already on the waiting list")));
+        }
+        let newcomer = Player::new(player_id.into(), display_name.to_string(), 0, Chips::ZERO);
+        self.buy_in_rules.check(BuyInKind::BuyIn, &newcomer, chip_stack, self.hand_number)?;
+        if let Some(bank) = self.tracked_bank() {
+            bank.withdraw(player_id, chip_stack)?;
+        }
+
+        if let Some(index) = self.reserved_seats.iter().position(|reserved| **reserved == *player_id) {
+            self.reserved_seats.remove(index);
+            let table_position = self.seat(newcomer.player_id, newcomer.display_name, chip_stack);
+            return Ok(Seating::Seated { table_position });
+        }
+        Ok(self.seat_or_queue(newcomer.player_id, newcomer.display_name, chip_stack))
+    }
+
+    /// Holds a free seat for `player_id`, e.g. while they answer an
+    /// invitation from `invited_by`. The seat counts as taken until they join
+    /// with `join_table` or it is released with `release_seat`.
+    pub fn reserve_seat(&mut self, player_id: &str, invited_by: &str) -> Result<(), GameError> {
+        if self.players.contains(player_id) {
+            return Err(GameError::InvalidRequest(format!("player {player_id} is already seated")));
+        }
+        if self.reserved_seats.iter().any(|reserved| **reserved == *player_id) {
+            return Err(GameError::InvalidState(format!("a seat is already held for player {player_id}")));
+        }
+        if !self.has_free_seat() || !self.waitlist.is_empty() {
+            return Err(GameError::NotAllowed(format!("all {} seats are taken", self.max_seats)));
+        }
+        let player_id = PlayerId::from(player_id);
+        info!(%player_id, invited_by, "seat reserved");
+        self.reserved_seats.push(player_id.clone());
+        self.events.push(GameEvent::SeatReserved { player_id, invited_by: invited_by.into() });
+        Ok(())
+    }
+
+    /// Gives up the seat held for `player_id`, seating the next waiting
+    /// player in it.
+    pub fn release_seat(&mut self, player_id: &str) -> Result<(), GameError> {
+        let index = self.reserved_seats
+            .iter()
+            .position(|reserved| **reserved == *player_id)
+            .ok_or_else(|| GameError::PlayerNotFound(player_id.to_string()))?;
+        let player_id = self.reserved_seats.remove(index);
+        info!(%player_id, "seat released");
+        self.events.push(GameEvent::SeatReleased { player_id });
+        self.seat_from_waitlist();
+        Ok(())
+    }
+
+    /// Whether a seat is neither taken nor held for anyone.
+    fn has_free_seat(&self) -> bool {
+        self.players.len() + self.reserved_seats.len() < self.max_seats
+    }
+
+    /// Seats a player if a seat is free and nobody is waiting for one, and
+    /// puts them at the end of the waiting list otherwise.
+    fn seat_or_queue(&mut self, player_id: PlayerId, display_name: String, chip_stack: Chips) -> Seating {
+        if self.has_free_seat() && self.waitlist.is_empty() {
+            let table_position = self.seat(player_id, display_name, chip_stack);
+            return Seating::Seated { table_position };
+        }
+        let position = self.waitlist.join(WaitingPlayer { player_id: player_id.clone(), display_name, chip_stack });
+        info!(%player_id, position, "joined the waiting list");
+        self.events.push(GameEvent::WaitlistJoined { player_id, position });
+        Seating::Waiting { position }
+    }
+
+    /// Takes a player off the waiting list.
+    pub fn leave_waitlist(&mut self, player_id: &str) -> Result<WaitingPlayer, GameError> {
+        let player = self.waitlist.leave(player_id).ok_or_else(|| GameError::PlayerNotFound(player_id.to_string()))?;
+        if let Some(bank) = self.tracked_bank() {
+            bank.deposit(player_id, player.chip_stack);
+        }
+        info!(player_id, "left the waiting list");
+        Ok(player)
+    }
+
+    /// Seats waiting players, longest waiting first, while there are free seats.
+    fn seat_from_waitlist(&mut self) {
+        while self.has_free_seat() {
+            let Some(waiting) = self.waitlist.next() else { break };
+            self.seat(waiting.player_id, waiting.display_name, waiting.chip_stack);
+        }
+    }
+
+    /// Hands a seated player's decisions over to `strategy`.
+    pub fn set_bot(&mut self, player_id: &str, strategy: Box<dyn BotStrategy>) -> Result<(), GameError> {
+        let player_id = self.player_id(player_id)?;
+        info!(%player_id, strategy = strategy.name(), "bot seated");
+        self.bots.insert(player_id, strategy);
+        Ok(())
+    }
+
+    /// The strategy playing `player_id`, if the seat is a bot.
+    pub fn bot(&self, player_id: &str) -> Option<&dyn BotStrategy> {
+        self.bots.get(player_id).map(Box::as_ref)
+    }
+
+    /// Makes a player sit out; if they hold cards in the current hand, they fold.
+    pub fn sit_out_player(&mut self, player_id: &str) -> Result<(), GameError> {
+        let player = self.find_player_mut(player_id)?;
+        player.sit_out();
+        let player_id = player.player_id.clone();
+        self.events.push(GameEvent::PlayerSatOut { player_id });
+        Ok(())
+    }
+
+    /// Lets a player who sat out be dealt into the next hand.
+    pub fn sit_in_player(&mut self, player_id: &str) -> Result<(), GameError> {
+        self.find_player_mut(player_id)?.is_sitting_out = false;
+        info!(player_id, "player sat in");
+        Ok(())
+    }
+
+    /// Adds `delta` chips to a player's stack (removes them when negative) and
+    /// returns the new stack.
+    pub fn adjust_chips(&mut self, player_id: &str, delta: i64) -> Result<Chips, GameError> {
+        let player = self.find_player_mut(player_id)?;
+        player.chip_stack = player.chip_stack.checked_add_signed(delta)
+            .ok_or_else(|| GameError::InvalidRequest(format!("stack would become {}", i128::from(player.chip_stack.get()) + i128::from(delta))))?;
+        let (player_id, chip_stack) = (player.player_id.clone(), player.chip_stack);
+        info!(%player_id, delta, stack = %chip_stack, "chips adjusted");
+        self.chips_in_play = self.chips_in_play.checked_add_signed(delta).unwrap_or_default();
+        let (amount, account) = (Chips::new(delta.unsigned_abs()), Account::Player(player_id.clone()));
+        let (from, to) = if delta < 0 { (account, Account::Cashier) } else { (Account::Cashier, account) };
+        self.record(TransactionKind::Adjustment, from, to, amount);
+        self.events.push(GameEvent::ChipsAdjusted { player_id, chip_stack });
+        Ok(chip_stack)
+    }
+
+    /// Gives a player chips from outside the game, e.g. a rebuy.
+    pub fn award_chips(&mut self, player_id: &str, amount: Chips) -> Result<(), GameError> {
+        let player = self.find_player_mut(player_id)?;
+        player.add_chips(amount);
+        let account = Account::Player(player.player_id.clone());
+        self.chips_in_play += amount;
+        self.record(TransactionKind::Award, Account::Cashier, account, amount);
+        info!(player_id, %amount, "chips awarded");
+        Ok(())
+    }
+
+    pub fn buy_in_rules(&self) -> &BuyInRules {
+        &self.buy_in_rules
+    }
+
+    pub fn set_buy_in_rules(&mut self, rules: BuyInRules) {
+        self.buy_in_rules = rules;
+    }
+
+    /// Adds chips a player pays for, within the table's `BuyInRules`, and
+    /// returns the new stack. Refused while they are playing a hand.
+    pub fn buy_chips(&mut self, player_id: &str, kind: BuyInKind, amount: Chips) -> Result<Chips, GameError> {
+        let player = self.find_player(player_id)?;
+        if self.hand_in_progress && player.is_in_play {
+            return Err(GameError::InvalidState(format!("player {player_id} is in the current hand")));
+        }
+        self.buy_in_rules.check(kind, player, amount, self.entry_hand_number())?;
+        if let Some(bank) = self.tracked_bank() {
+            bank.withdraw(player_id, amount)?;
+        }
+
+        let player = self.find_player_mut(player_id)?;
+        player.add_chips(amount);
+        match kind {
+            BuyInKind::Rebuy => player.rebuys += 1,
+            BuyInKind::AddOn => player.took_add_on = true,
+            BuyInKind::BuyIn => {}
+        }
+        let (player_id, chip_stack) = (player.player_id.clone(), player.chip_stack);
+        self.chips_in_play += amount;
+        let transaction = match kind {
+            BuyInKind::BuyIn => TransactionKind::BuyIn,
+            BuyInKind::Rebuy => TransactionKind::Rebuy,
+            BuyInKind::AddOn => TransactionKind::AddOn,
+        };
+        self.record(transaction, Account::Cashier, Account::Player(player_id.clone()), amount);
+        info!(%player_id, ?kind, %amount, stack = %chip_stack, "chips bought");
+        self.events.push(GameEvent::ChipsBought { player_id, kind, amount, chip_stack });
+        Ok(chip_stack)
+    }
+
+    /// The hand a buy-in or entry made now counts towards: the current hand,
+    /// or the next one between hands.
+    fn entry_hand_number(&self) -> u64 {
+        self.hand_number + u64::from(!self.hand_in_progress)
+    }
+
+    pub fn tournament(&self) -> Option<&Tournament> {
+        self.tournament.as_ref()
+    }
+
+    /// True while a tournament table takes entries and re-entries.
+    pub fn registration_open(&self) -> bool {
+        self.tournament.as_ref().is_some_and(|tournament| tournament.registration_open(self.entry_hand_number()))
+    }
+
+    /// Makes the table a tournament entered with `register`, or a cash table
+    /// with `None`. Entries made under earlier rules are forgotten.
+    pub fn set_tournament_rules(&mut self, rules: Option<TournamentRules>) {
+        self.tournament = rules.map(Tournament::new);
+    }
+
+    pub fn variant(&self) -> GameVariant {
+        self.variant
+    }
+
+    /// Sets the game dealt from the next hand on.
+    pub fn set_variant(&mut self, variant: GameVariant) -> Result<(), GameError> {
+        if self.hand_in_progress {
+            return Err(GameError::InvalidState("the game can't change during a hand".to_string()));
+        }
+        self.variant = variant;
+        Ok(())
+    }
+
+    /// Deals from a shoe of `decks` standard decks from the next hand on,
+    /// e.g. for stress tests. A card dealt twice plays once for each copy,
+    /// so a hand can pair the same card.
+    pub fn set_decks(&mut self, decks: usize) -> Result<(), GameError> {
+        if decks == 0 {
+            return Err(GameError::InvalidRequest("at least one deck is needed".to_string()));
+        }
+        if self.hand_in_progress {
+            return Err(GameError::InvalidState("the deck can't change during a hand".to_string()));
+        }
+        self.rebuild_deck(decks, self.deck.has_joker());
+        Ok(())
+    }
+
+    pub fn wild_cards(&self) -> WildCards {
+        self.wild_cards
+    }
+
+    /// Makes `wild_cards` wild from the next hand on, adding a joker to the
+    /// deck or taking it out as the rules need.
+    pub fn set_wild_cards(&mut self, wild_cards: WildCards) -> Result<(), GameError> {
+        if self.hand_in_progress {
+            return Err(GameError::InvalidState("wild cards can't change during a hand".to_string()));
+        }
+        self.wild_cards = wild_cards;
+        if self.deck.has_joker() != wild_cards.adds_joker() {
+            self.rebuild_deck(self.deck.decks(), wild_cards.adds_joker());
+        }
+        Ok(())
+    }
+
+    /// Starts over with a freshly shuffled shoe of `decks` decks, with or
+    /// without jokers, keeping the shuffle mode.
+    fn rebuild_deck(&mut self, decks: usize, joker: bool) {
+        self.rng.begin_shuffle();
+        let mut deck = Deck::shuffled_multi(decks, &mut self.rng);
+        deck.set_joker(joker);
+        if joker {
+            deck.reset_with(&mut self.rng); // Shuffles the jokers in
+        }
+        deck.set_continuous_shuffle(self.deck.is_continuous_shuffle());
+        self.deck = deck;
+        self.deck_stacked = false;
+    }
+
+    /// Shuffles the cards dealt in each hand back into the rest of the deck,
+    /// rather than dealing every hand from a freshly shuffled one.
+    pub fn set_continuous_shuffle(&mut self, continuous: bool) {
+        self.deck.set_continuous_shuffle(continuous);
+    }
+
+    /// Enters a player in the tournament with its starting stack, while
+    /// registration is open. They are seated, or wait for a seat like anyone
+    /// joining a full table.
+    pub fn register(&mut self, player_id: &str, display_name: &str) -> Result<Seating, GameError> {
+        let tournament = self.tournament.as_ref().ok_or_else(not_a_tournament)?;
+        if self.players.contains(player_id) || self.waitlist.contains(player_id) {
+            return Err(GameError::InvalidRequest(format!("player {player_id} is already at the table")));
+        }
+        tournament.check_entry(player_id, false, self.entry_hand_number())?;
+        self.enter_tournament(player_id.into(), display_name.to_string())
+    }
+
+    /// Enters a busted player again, while registration is open: they give up
+    /// their seat and take the next free one, or a place on the waiting
+    /// list, with a fresh starting stack and bounty.
+    pub fn re_enter(&mut self, player_id: &str) -> Result<Seating, GameError> {
+        let tournament = self.tournament.as_ref().ok_or_else(not_a_tournament)?;
+        tournament.check_entry(player_id, true, self.entry_hand_number())?;
+        if self.waitlist.contains(player_id) {
+            return Err(GameError::InvalidRequest(format!("player {player_id} is already waiting for a seat")));
+        }
+        let display_name = match self.players.get(player_id) {
+            Some(player) if !player.chip_stack.is_zero() => {
+                return Err(GameError::NotAllowed("only a player with no chips left can re-enter".to_string()));
+            }
+            Some(_) => self.remove_player(player_id)?.display_name, // Frees the seat for whoever is next in line
+            None => player_id.to_string(), // Already unseated after busting
+        };
+        self.enter_tournament(player_id.into(), display_name)
+    }
+
+    /// Records a tournament entry and sits the player down with the starting stack.
+    fn enter_tournament(&mut self, player_id: PlayerId, display_name: String) -> Result<Seating, GameError> {
+        let tournament = self.tournament.as_mut().ok_or_else(not_a_tournament)?;
+        let entry = tournament.enter(player_id.clone());
+        let (chip_stack, prize_pool) = (tournament.rules.starting_stack, tournament.prize_pool());
+        info!(%player_id, entry, %prize_pool, "tournament entry");
+        self.events.push(GameEvent::TournamentEntry { player_id: player_id.clone(), entry, prize_pool });
+        Ok(self.seat_or_queue(player_id, display_name, chip_stack))
+    }
+
+    /// Starts a hand with betting: moves the button, deals hole cards to every
+    /// player with chips who isn't sitting out and posts the blinds. Drive the
+    /// hand with `act` while `next_to_act` names a player; the board is dealt
+    /// and the pots paid out as the betting closes.
+    pub fn start_hand(&mut self, small_blind: Chips, big_blind: Chips) -> Result<(), GameError> {
+        if self.hand_in_progress {
+            return Err(GameError::InvalidState("a hand is already in progress".to_string()));
+        }
+        check_blinds(small_blind, big_blind)?;
+        // A hand runs reset, blinds, deal, then betting street by street
+        self.reset_for_new_hand();
+        let dealt: Vec<usize> = (0..self.players.len()).filter(|&index| self.players[index].is_in_play).collect();
+        if dealt.len() < 2 {
+            return Err(GameError::InvalidState("at least two players with chips are needed".to_string()));
+        }
+        self.begin_hand()?;
+        let _span = self.hand_span().entered();
+
+        // The button moves to the next player dealt in
+        let button = self.button.and_then(|last| dealt.iter().position(|&index| index > last)).unwrap_or(0);
+        self.button = Some(dealt[button]);
+        let seats: Vec<PlayerId> = dealt[button..].iter().chain(&dealt[..button])
+            .map(|&index| self.players[index].player_id.clone())
+            .collect();
+        info!(button = %seats[0], "button moved");
+        let players = seats.iter()
+            .filter_map(|id| self.players.get(id))
+            .map(|player| LoggedPlayer {
+                player_id: player.player_id.clone(),
+                display_name: player.display_name.clone(),
+                table_position: player.table_position,
+                stack: player.chip_stack,
+            })
+            .collect();
+        self.post_blinds(seats.clone(), small_blind, big_blind)?;
+        self.deal_hole_cards_where(|player| player.is_in_play)?;
+        self.hand_log = Some(HandLog {
+            hand_number: self.hand_number,
+            variant: self.variant,
+            seats,
+            players,
+            small_blind,
+            big_blind,
+            ante: self.big_blind_ante,
+            straddle: self.betting.as_ref()
+                .and_then(|betting| betting.straddle.clone())
+                .map(|player_id| (player_id, big_blind * 2)),
+            hole_cards: self.players.iter()
+                .filter(|player| player.is_in_play)
+                .map(|player| (player.player_id.clone(), player.hole_cards.clone()))
+                .collect(),
+            actions: Vec::new(),
+            board: Vec::new(),
+            extra_boards: Vec::new(),
+            eliminations: Vec::new(),
+            all_in: Vec::new(),
+            payouts: Vec::new(),
+            rake: Chips::ZERO,
+            streets: Vec::new(),
+            showdown: Vec::new(),
+            shown: Vec::new(),
+            finished: false,
+        });
+        let exposed = self.variant.preflop_board_cards();
+        if exposed > 0 {
+            self.deal_board(exposed)?; // Courchevel shows the first flop card before anyone acts
+        }
+        self.advance()?;
+        self.play_bots()?;
+        self.prompt_next_to_act();
+        self.debug_verify();
+        Ok(())
+    }
+
+    /// Starts a hand like `start_hand`, with the blinds set by `set_blinds`.
+    pub fn start_next_hand(&mut self) -> Result<(), GameError> {
+        let (small_blind, big_blind) = self.blinds
+            .ok_or_else(|| GameError::InvalidState("no blinds have been set for this table".to_string()))?;
+        self.start_hand(small_blind, big_blind)
+    }
+
+    /// Opens the preflop betting for `seats` (button first) and posts the
+    /// blinds; the player after the big blind is first to act.
+    fn post_blinds(&mut self, seats: Vec<PlayerId>, small_blind: Chips, big_blind: Chips) -> Result<(), GameError> {
+        let betting = BettingState::new(seats, small_blind, big_blind);
+        let (small, big) = betting.blinds();
+        let (small, big) = (small.clone(), big.clone());
+        let order: Vec<PlayerId> = betting.preflop_order().cloned().collect();
+        if betting.is_heads_up() {
+            debug!(button = %small, "heads-up: the button posts the small blind");
+        }
+        self.betting = Some(betting);
+
+        self.commit(&small, small_blind)?;
+        self.find_player_mut(&small)?.record_action(PlayerAction::Bet(small_blind));
+        self.commit(&big, big_blind)?;
+        self.find_player_mut(&big)?.record_action(PlayerAction::Bet(big_blind));
+        self.post_ante(&big, self.big_blind_ante)?;
+        let straddle = self.post_straddle(big_blind)?;
+        let players = &self.players;
+        if let Some(betting) = &mut self.betting {
+            betting.current_bet = big_blind.max(straddle);
+            betting.min_raise = big_blind.max(straddle);
+            let order: Vec<PlayerId> = if straddle.is_zero() { order } else { betting.preflop_order().cloned().collect() };
+            betting.to_act = order.into_iter().filter(|id| can_act(players, id)).collect();
+        }
+        info!(%small_blind, %big_blind, "blinds posted");
+        Ok(())
+    }
+
+    /// Posts the straddle of the player under the gun, if they asked for one
+    /// and can cover it; returns what was posted. Requests are used up by
+    /// the hand, posted or not.
+    fn post_straddle(&mut self, big_blind: Chips) -> Result<Chips, GameError> {
+        let requests = std::mem::take(&mut self.straddle_requests);
+        let Some(utg) = self.betting.as_ref().and_then(BettingState::under_the_gun).cloned() else { return Ok(Chips::ZERO) };
+        let straddle = big_blind * 2;
+        if !self.straddles || !requests.contains(&utg) || self.find_player(&utg)?.chip_stack <= straddle {
+            return Ok(Chips::ZERO);
+        }
+        self.commit(&utg, straddle)?;
+        self.find_player_mut(&utg)?.record_action(PlayerAction::Bet(straddle));
+        if let Some(betting) = &mut self.betting {
+            betting.straddle = Some(utg.clone());
+        }
+        info!(player_id = %utg, %straddle, "straddle posted");
+        Ok(straddle)
+    }
+
+    /// Puts the big blind ante into the main pot as dead money, which doesn't
+    /// count towards the player's bets or make them eligible for more of the
+    /// side pots. Posted after the blind, so a short stack covers the blind
+    /// first and antes what is left.
+    fn post_ante(&mut self, player_id: &str, ante: Chips) -> Result<(), GameError> {
+        let player = self.players.get_mut(player_id).ok_or_else(|| GameError::PlayerNotFound(player_id.to_string()))?;
+        let paid = ante.min(player.chip_stack);
+        if paid.is_zero() {
+            return Ok(());
+        }
+        self.table.add_bet(player, paid)?;
+        if let Some(betting) = &mut self.betting {
+            betting.antes.insert(player.player_id.clone(), paid);
+            betting.add_to_street(&player.player_id, paid);
+        }
+        self.ledger.record(self.hand_number, TransactionKind::Ante, Account::Player(player.player_id.clone()), Account::Pot, paid);
+        info!(player_id, ante = %paid, "ante posted");
+        self.update_pots();
+        Ok(())
+    }
+
+    /// The betting of the hand in progress, if it was started with `start_hand`.
+    pub fn betting(&self) -> Option<&BettingState> {
+        self.betting.as_ref()
+    }
+
+    /// What was put in on each street of the hand in progress, or of the
+    /// last one played once it has finished.
+    pub fn street_bets(&self) -> &[StreetBets] {
+        match (&self.betting, &self.hand_log) {
+            (Some(betting), _) => betting.streets(),
+            (None, Some(log)) => &log.streets,
+            (None, None) => &[],
+        }
+    }
+
+    /// The record of the hand in progress, or of the last one played once it
+    /// has finished.
+    pub fn hand_log(&self) -> Option<&HandLog> {
+        self.hand_log.as_ref()
+    }
+
+    /// The player whose turn it is to act.
+    pub fn next_to_act(&self) -> Option<&str> {
+        self.betting.as_ref().and_then(BettingState::to_act)
+    }
+
+    /// What `player_id` can see when it's their turn: their cards, the board
+    /// and what they owe.
+    pub fn action_context(&self, player_id: &str) -> Option<ActionContext> {
+        let betting = self.betting.as_ref()?;
+        let player = self.find_player(player_id).ok()?;
+        Some(ActionContext {
+            player_id: player.player_id.clone(),
+            hole_cards: player.hole_cards.clone(),
+            board: self.community_cards.clone(),
+            street: betting.street,
+            stack: player.chip_stack,
+            to_call: betting.owed(player_id).min(player.chip_stack),
+            street_bet: betting.street_bet(player_id),
+            current_bet: betting.current_bet,
+            min_raise_to: betting.min_raise_to(),
+            big_blind: betting.big_blind,
+            pot: self.table.total_committed(),
+            effective_stack: betting.seats.iter()
+                .filter(|id| *id != player_id && in_play(&self.players, id))
+                .filter_map(|id| Some(self.players.get(id)?.chip_stack))
+                .max()
+                .map_or(player.chip_stack, |biggest| player.chip_stack.min(biggest)),
+            opponents: betting.seats.iter().filter(|id| *id != player_id && in_play(&self.players, id)).count(),
+            players_behind: betting.after_button()
+                .skip_while(|id| *id != player_id)
+                .skip(1)
+                .filter(|id| in_play(&self.players, id))
+                .count(),
+            position: Position::of_seat(betting.seats.iter().position(|id| id == player_id).unwrap_or(0), betting.seats.len()),
+        })
+    }
+
+    /// Suggests an action for `player_id` on their turn, with a short
+    /// rationale, at a table in training mode. The same spot always gets the
+    /// same hint.
+    pub fn hint(&self, player_id: &str) -> Result<Hint, GameError> {
+        if !self.training_mode {
+            return Err(GameError::NotAllowed("hints are only given at training tables".to_string()));
+        }
+        if self.variant != GameVariant::HoldEm || self.wild_cards != WildCards::None {
+            return Err(GameError::InvalidState("hints are only given for Hold'em without wild cards".to_string()));
+        }
+        if self.next_to_act() != Some(player_id) {
+            return Err(GameError::InvalidState(format!("it is not player {player_id}'s turn")));
+        }
+        let context = self.action_context(player_id).ok_or_else(|| GameError::PlayerNotFound(player_id.to_string()))?;
+        Ok(hints::suggest(&context, &self.preflop_chart, &mut StdRng::seed_from_u64(self.hand_number)))
+    }
+
+    /// Applies `player_id`'s action, then lets any bots whose turn follows act.
+    /// `Bet` opens a street with that many chips, `Raise` adds that many on
+    /// top of the current bet, and `Call` with nothing owed is a check.
+    /// Betting or raising a whole stack is always allowed, even when short of
+    /// the minimum, but never more than the stack. The amount is stated in
+    /// full with the action and goes in at once: nothing can be added to it
+    /// until the player's next turn.
+    pub fn act(&mut self, player_id: &str, action: PlayerAction) -> Result<(), GameError> {
+        self.apply_action(player_id, action)?;
+        self.play_bots()?;
+        self.prompt_next_to_act();
+        self.debug_verify();
+        Ok(())
+    }
+
+    /// Acts for `player_id` when their time to act ran out: a check when
+    /// nothing is owed, a fold otherwise.
+    pub fn act_on_timeout(&mut self, player_id: &str) -> Result<(), GameError> {
+        let betting = self.betting.as_ref().filter(|betting| betting.to_act() == Some(player_id))
+            .ok_or_else(|| GameError::InvalidState(format!("it is not player {player_id}'s turn")))?;
+        let action = if betting.owed(player_id).is_zero() { PlayerAction::Check } else { PlayerAction::Fold };
+        info!(player_id, ?action, "acting for a player who timed out");
+        self.events.push(GameEvent::ActionTimedOut { hand_number: self.hand_number, player_id: player_id.into(), action: action.clone() });
+        self.act(player_id, action)
+    }
+
+    /// Tells whoever's turn it now is what calling costs, with the pot odds
+    /// and effective stack worked out for them.
+    fn prompt_next_to_act(&mut self) {
+        let Some(context) = self.next_to_act().and_then(|player_id| self.action_context(player_id)) else { return };
+        self.events.push(GameEvent::ActionRequired {
+            hand_number: self.hand_number,
+            pot_odds: context.pot_odds(),
+            player_id: context.player_id,
+            to_call: context.to_call,
+            pot: context.pot,
+            effective_stack: context.effective_stack,
+            min_raise_to: context.min_raise_to,
+        });
+    }
+
+    /// Asks bots for their actions for as long as it is a bot's turn.
+    fn play_bots(&mut self) -> Result<(), GameError> {
+        while let Some(player_id) = self.betting.as_ref().and_then(|betting| betting.to_act.front()).filter(|id| self.bots.contains_key(*id)).cloned() {
+            let Some(context) = self.action_context(&player_id) else { break };
+            let Some(bot) = self.bots.get_mut(&player_id) else { break };
+            let action = bot.decide(&context);
+            if let Err(err) = self.apply_action(&player_id, action) {
+                debug!(%player_id, %err, "bot action rejected");
+                self.apply_action(&player_id, context.check_or_fold())?;
+            }
+        }
+        Ok(())
+    }
+
+    fn apply_action(&mut self, player_id: &str, action: PlayerAction) -> Result<(), GameError> {
+        let betting = self.betting.as_ref().ok_or_else(|| GameError::InvalidState("no betting round is open".to_string()))?;
+        match betting.to_act() {
+            Some(next) if next == player_id => {}
+            Some(next) => return Err(GameError::InvalidState(format!("it is {next}'s turn"))),
+            None => return Err(GameError::InvalidState("no betting round is open".to_string())),
+        }
+        let stack = self.find_player(player_id)?.chip_stack;
+        let street_bet = betting.street_bet(player_id);
+        let owed = betting.owed(player_id);
+        let current_bet = betting.current_bet;
+        let all_in = street_bet + stack;
+
+        // The street total a bet or raise goes to
+        let raise_to = match action {
+            PlayerAction::Check if !owed.is_zero() => {
+                return Err(GameError::InvalidRequest(format!("cannot check facing a bet of {owed}")));
+            }
+            PlayerAction::Bet(_) if !current_bet.is_zero() => {
+                return Err(GameError::InvalidRequest("there is already a bet to raise".to_string()));
+            }
+            PlayerAction::Raise(_) if current_bet.is_zero() => {
+                return Err(GameError::InvalidRequest("there is no bet to raise; bet instead".to_string()));
+            }
+            PlayerAction::Bet(amount) => Some((amount, betting.big_blind)),
+            PlayerAction::Raise(amount) => Some((current_bet + amount, betting.min_raise_to())),
+            PlayerAction::Fold | PlayerAction::Check | PlayerAction::Call | PlayerAction::SitOut => None,
+        };
+        if let Some((total, minimum)) = raise_to {
+            if total > all_in {
+                return Err(GameError::InsufficientChips);
+            }
+            if total < minimum && total < all_in {
+                return Err(GameError::InvalidRequest(format!("bet at least {minimum} or go all in")));
+            }
+        }
+
+        // The id queued to act, so events and logs share it rather than copy `player_id`
+        let Some(player_id) = self.betting.as_mut().and_then(|betting| betting.to_act.pop_front()) else {
+            return Err(GameError::InvalidState("no betting round is open".to_string()));
+        };
+        let player_id = &player_id;
+        let logged = match action {
+            PlayerAction::Fold => {
+                self.find_player_mut(player_id)?.fold();
+                self.events.push(GameEvent::PlayerActed { hand_number: self.hand_number, player_id: player_id.clone(), action });
+                PlayerAction::Fold
+            }
+            PlayerAction::SitOut => {
+                self.sit_out_player(player_id)?;
+                PlayerAction::Fold
+            }
+            PlayerAction::Check | PlayerAction::Call => {
+                let paid = self.commit(player_id, owed)?;
+                let action = if paid.is_zero() { PlayerAction::Check } else { PlayerAction::Call };
+                self.find_player_mut(player_id)?.record_action(action.clone());
+                self.events.push(GameEvent::PlayerActed { hand_number: self.hand_number, player_id: player_id.clone(), action: action.clone() });
+                action
+            }
+            PlayerAction::Bet(_) | PlayerAction::Raise(_) => {
+                let total = raise_to.map_or(Chips::ZERO, |(total, _)| total);
+                self.commit(player_id, total - street_bet)?;
+                self.find_player_mut(player_id)?.record_action(action.clone());
+                self.events.push(GameEvent::PlayerActed { hand_number: self.hand_number, player_id: player_id.clone(), action: action.clone() });
+                let players = &self.players;
+                if let Some(betting) = self.betting.as_mut().filter(|betting| total > betting.current_bet) {
+                    betting.min_raise = betting.min_raise.max(total - betting.current_bet);
+                    betting.current_bet = total;
+                    // Everyone else still able to bet gets to respond
+                    betting.to_act = betting.after(player_id).filter(|id| can_act(players, id)).cloned().collect();
+                }
+                action
+            }
+        };
+        let hand_total = self.table.committed(player_id);
+        if let (Some(log), Some(betting)) = (&mut self.hand_log, &self.betting) {
+            log.actions.push(LoggedAction { street: betting.street, player_id: player_id.clone(), action: logged, hand_total });
+        }
+        self.update_pots();
+        self.advance()
+    }
+
+    /// Moves up to `amount` chips from a player's stack into the pot and returns what was paid.
+    fn commit(&mut self, player_id: &str, amount: Chips) -> Result<Chips, GameError> {
+        let player = self.players.get_mut(player_id).ok_or_else(|| GameError::PlayerNotFound(player_id.to_string()))?;
+        let paid = amount.min(player.chip_stack); // A short stack goes all in
+        self.table.add_bet(player, paid)?;
+        if let Some(betting) = &mut self.betting {
+            *betting.street_bets.entry(player.player_id.clone()).or_default() += paid;
+            betting.add_to_street(&player.player_id, paid);
+        }
+        self.ledger.record(self.hand_number, TransactionKind::Bet, Account::Player(player.player_id.clone()), Account::Pot, paid);
+        self.update_pots();
+        Ok(paid)
+    }
+
+    /// Rebuilds the main and side pots from what each player has put in,
+    /// antes going to the main pot.
+    fn update_pots(&mut self) {
+        let antes = self.betting.as_ref().map(|betting| &betting.antes);
+        let ante = |player_id: &PlayerId| antes.and_then(|antes| antes.get(player_id)).copied().unwrap_or_default();
+        let contributions: Vec<(PlayerId, Chips, bool)> = self.players
+            .iter()
+            .filter_map(|player| {
+                let chips = self.table.player_bets().get(&player.player_id).copied()?;
+                Some((player.player_id.clone(), chips - ante(&player.player_id), player.is_in_play))
+            })
+            .collect();
+        self.table.pots = side_pots(&contributions)
+            .into_iter()
+            .map(|(total, eligible_players)| Pot::new(total, eligible_players))
+            .collect();
+        let dead: Chips = antes.map_or(Chips::ZERO, |antes| antes.values().copied().sum());
+        if let Some(main) = self.table.pots.first_mut() {
+            main.total += dead;
+        }
+    }
+
+    /// Closes finished betting rounds: deals the next street, or ends the hand
+    /// once one player is left or the river has been bet.
+    fn advance(&mut self) -> Result<(), GameError> {
+        loop {
+            let players = &self.players;
+            let Some(betting) = &mut self.betting else { return Ok(()) };
+            let live = betting.seats.iter().filter(|id| in_play(players, id)).count();
+            if live <= 1 {
+                self.take_rake();
+                self.take_jackpot_drop();
+                return self.finish_hand();
+            }
+            betting.to_act.retain(|id| can_act(players, id));
+            let able = betting.seats.iter().filter(|id| can_act(players, id)).count();
+            if able <= 1 && betting.to_act.iter().all(|id| betting.owed(id).is_zero()) {
+                betting.to_act.clear(); // Nobody is left to bet against
+            }
+            if !betting.to_act.is_empty() {
+                return Ok(());
+            }
+
+            let Some(street) = betting.street.next() else {
+                return self.showdown();
+            };
+            if able <= 1 {
+                self.record_all_in_equity();
+                if !self.runouts_agreed() || !self.insurance_decided() {
+                    return Ok(()); // The rest of the board waits for the players to choose
+                }
+            }
+            let players = &self.players;
+            let Some(betting) = &mut self.betting else { return Ok(()) };
+            betting.street = street;
+            betting.street_bets.clear();
+            betting.streets.push(StreetBets::new(street));
+            betting.current_bet = Chips::ZERO;
+            betting.min_raise = betting.big_blind;
+            if able > 1 {
+                betting.to_act = betting.after_button().filter(|id| can_act(players, id)).cloned().collect();
+            }
+            self.deal_board(street.board_cards())?;
+        }
+    }
+
+    /// Prices each live player's stake in the pots once nobody can bet any
+    /// more before the river, so results can be judged on equity as well as
+    /// on the cards that came. Done once a hand, and only for Hold'em on one
+    /// board without wild cards, which the equity engine covers.
+    fn record_all_in_equity(&mut self) {
+        let Some(log) = &mut self.hand_log else { return };
+        if !log.all_in.is_empty() || self.variant != GameVariant::HoldEm || self.wild_cards != WildCards::None {
+            return;
+        }
+        let mut expected: Vec<(PlayerId, f64)> = Vec::new();
+        for pot in &self.table.pots {
+            let holes: Vec<Vec<Card>> = pot.eligible_players.iter()
+                .map(|id| self.players.get(id).map_or_else(Vec::new, |player| player.hole_cards.clone()))
+                .collect();
+            let equities = if holes.len() == 1 {
+                vec![1.0] // Uncalled chips go back to whoever bet them
+            } else {
+                match estimate_equity_seeded(&holes, &self.community_cards, ALL_IN_TRIALS, self.hand_number) {
+                    Ok(equities) => equities,
+                    Err(err) => {
+                        debug!(%err, "all-in equity not recorded"); // e.g. the same card twice from a multi-deck shoe
+                        return;
+                    }
+                }
+            };
+            for (id, equity) in pot.eligible_players.iter().zip(equities) {
+                match expected.iter_mut().find(|(player_id, _)| player_id == id) {
+                    Some((_, chips)) => *chips += equity * pot.total.get() as f64,
+                    None => expected.push((id.clone(), equity * pot.total.get() as f64)),
+                }
+            }
+        }
+        let total = self.table.total_committed().get() as f64;
+        log.all_in = expected.into_iter()
+            .map(|(player_id, expected)| AllInShare { player_id, equity: expected / total, expected, won: Chips::ZERO })
+            .collect();
+        let equities = log.all_in.iter().map(|share| (share.player_id.clone(), share.equity)).collect();
+        info!(?equities, "all in before the river");
+        self.events.push(GameEvent::AllInEquity { hand_number: self.hand_number, equities });
+    }
+
+    /// Whether the players left in a hand that is all in before the river
+    /// have agreed how many times to run the board. The first call opens the
+    /// vote, unless the table runs it once anyway; bots always choose once.
+    /// Once agreed, each run after the first starts from a copy of the board
+    /// so far, and no more runs are dealt than the deck has cards for.
+    fn runouts_agreed(&mut self) -> bool {
+        let players = &self.players;
+        let Some(betting) = &mut self.betting else { return true };
+        if betting.runs.is_some() {
+            return true;
+        }
+        if betting.runout_vote.is_none() {
+            let voters: Vec<PlayerId> = betting.seats.iter().filter(|id| in_play(players, id)).cloned().collect();
+            if self.max_runs < 2 || self.variant.boards() > 1 || voters.iter().all(|id| self.bots.contains_key(id)) {
+                betting.runs = Some(1);
+                return true;
+            }
+            info!(?voters, max_runs = self.max_runs, "runout vote opened");
+            self.events.push(GameEvent::RunoutVoteOpened { hand_number: self.hand_number, players: voters.clone(), max_runs: self.max_runs });
+            let mut vote = RunoutVote::new(voters, self.max_runs);
+            for (player_id, runs) in vote.choices.iter_mut().filter(|(id, _)| self.bots.contains_key(id)) {
+                *runs = Some(1);
+                self.events.push(GameEvent::RunsChosen { hand_number: self.hand_number, player_id: player_id.clone(), runs: 1 });
+            }
+            betting.runout_vote = Some(vote);
+        }
+        let Some(runs) = betting.runout_vote.as_ref().and_then(RunoutVote::agreed) else { return false };
+        let needed = Street::River.board_cards() - self.community_cards.len();
+        let runs = runs.min(self.deck.remaining() / needed.max(1)).max(1);
+        betting.runout_vote = None;
+        betting.runs = Some(runs);
+        self.table.extra_boards = vec![self.community_cards.clone(); runs - 1];
+        self.table.shared_cards = self.community_cards.len();
+        info!(runs, "runs agreed");
+        self.events.push(GameEvent::RunsAgreed { hand_number: self.hand_number, runs });
+        true
+    }
+
+    /// The players still choosing how many times to run the board, while
+    /// the hand waits for them.
+    pub fn runout_vote(&self) -> Option<&RunoutVote> {
+        self.betting.as_ref()?.runout_vote.as_ref()
+    }
+
+    /// Records how many times `player_id` wants the rest of the board run.
+    /// The board runs as few times as anyone chose, once everyone has chosen.
+    pub fn choose_runs(&mut self, player_id: &str, runs: usize) -> Result<(), GameError> {
+        let vote = self.betting.as_mut()
+            .and_then(|betting| betting.runout_vote.as_mut())
+            .ok_or_else(|| GameError::InvalidState("nobody is choosing how many times to run the board".to_string()))?;
+        if !(1..=vote.max_runs).contains(&runs) {
+            return Err(GameError::InvalidRequest(format!("the board can be run 1 to {} times", vote.max_runs)));
+        }
+        let (_, choice) = vote.choices.iter_mut()
+            .find(|(id, _)| id == player_id)
+            .ok_or_else(|| GameError::NotAllowed(format!("player {player_id} is not in the hand")))?;
+        if choice.is_some() {
+            return Err(GameError::InvalidState(format!("player {player_id} has already chosen")));
+        }
+        *choice = Some(runs);
+        info!(player_id, runs, "runs chosen");
+        self.events.push(GameEvent::RunsChosen { hand_number: self.hand_number, player_id: player_id.into(), runs });
+        self.advance()?;
+        self.debug_verify();
+        Ok(())
+    }
+
+    /// Whether the favourite of a heads-up all-in has decided on insurance,
+    /// pricing it the first time it's asked with the flop or turn showing.
+    /// Only offered on Hold'em without wild cards, to a player with better
+    /// odds than a coin flip but not yet a sure winner, and never to a bot.
+    /// Before the flop there are too many boards to price it exactly, so an
+    /// all-in then is offered insurance once the flop is out.
+    fn insurance_decided(&mut self) -> bool {
+        let players = &self.players;
+        let Some(betting) = &mut self.betting else { return true };
+        if let Some(offer) = &betting.insurance {
+            return offer.bought.is_some();
+        }
+        if !self.insurance || betting.insurance_priced || self.community_cards.len() < Street::Flop.board_cards()
+            || self.variant != GameVariant::HoldEm || self.wild_cards != WildCards::None || betting.runs != Some(1) {
+            return true;
+        }
+        betting.insurance_priced = true;
+        let live: Vec<&Player> = betting.seats.iter().filter(|id| in_play(players, id)).filter_map(|id| players.get(id)).collect();
+        if live.len() != 2 {
+            return true;
+        }
+        let holes: Vec<Vec<Card>> = live.iter().map(|player| player.hole_cards.clone()).collect();
+        let equities = match exact_equity(&holes, &self.community_cards) {
+            Ok(equities) => equities,
+            Err(err) => {
+                debug!(%err, "insurance not priced");
+                return true;
+            }
+        };
+        let (favourite, equity) = if equities[0] >= equities[1] { (live[0], equities[0]) } else { (live[1], equities[1]) };
+        if equity <= 0.5 || equity >= 1.0 || self.bots.contains_key(&favourite.player_id) {
+            return true;
+        }
+        let coverage: Chips = self.table.pots.iter().filter(|pot| pot.eligible_players.len() > 1).map(|pot| pot.total).sum();
+        let premium = Chips::new((coverage.get() as f64 * (1.0 - equity) - 1e-9).ceil() as u64); // Rounded up, past float noise
+        let offer = InsuranceOffer { player_id: favourite.player_id.clone(), equity, coverage, premium, bought: None };
+        info!(player_id = %offer.player_id, equity, %coverage, %premium, "insurance offered");
+        self.events.push(GameEvent::InsuranceOffered {
+            hand_number: self.hand_number,
+            player_id: offer.player_id.clone(),
+            equity,
+            coverage,
+            premium,
+        });
+        betting.insurance = Some(offer);
+        false
+    }
+
+    /// The insurance offered on the current hand, while the hand waits for
+    /// the favourite to decide and after.
+    pub fn insurance_offer(&self) -> Option<&InsuranceOffer> {
+        self.betting.as_ref()?.insurance.as_ref()
+    }
+
+    /// Buys or declines the insurance offered to `player_id`, then deals the
+    /// rest of the board. Buying takes the whole premium from the player's
+    /// stack at once, and fails with `InsufficientChips` when it can't; the
+    /// offer then stands until the player declines it.
+    pub fn buy_insurance(&mut self, player_id: &str, buy: bool) -> Result<(), GameError> {
+        let offer = self.insurance_offer()
+            .filter(|offer| offer.bought.is_none())
+            .ok_or_else(|| GameError::InvalidState("no insurance is on offer".to_string()))?;
+        if offer.player_id != player_id {
+            return Err(GameError::NotAllowed(format!("insurance is offered to player {}", offer.player_id)));
+        }
+        if buy {
+            let premium = offer.premium;
+            let player = self.find_player_mut(player_id)?;
+            player.chip_stack = player.chip_stack.try_sub(premium)?;
+            self.chips_in_play -= premium;
+            self.record(TransactionKind::InsurancePremium, Account::Player(player_id.into()), Account::Insurer, premium);
+        }
+        if let Some(offer) = self.betting.as_mut().and_then(|betting| betting.insurance.as_mut()) {
+            offer.bought = Some(buy);
+        }
+        info!(player_id, buy, "insurance decided");
+        self.events.push(GameEvent::InsuranceDecided { hand_number: self.hand_number, player_id: player_id.into(), bought: buy });
+        self.advance()?;
+        self.debug_verify();
+        Ok(())
+    }
+
+    /// Pays out insurance bought on this hand: the insured player gets the
+    /// part of the coverage matching the share of the insured pots they
+    /// lost. The premium was paid when it was bought.
+    fn settle_insurance(&mut self) -> Result<(), GameError> {
+        let Some(offer) = self.betting.as_ref().and_then(|betting| betting.insurance.clone()).filter(|offer| offer.bought == Some(true)) else {
+            return Ok(());
+        };
+        let insured: Vec<&Pot> = self.table.pots.iter().filter(|pot| pot.eligible_players.len() > 1).collect();
+        let total: Chips = insured.iter().map(|pot| pot.total).sum();
+        let won: Chips = insured.iter().flat_map(|pot| pot.payouts()).filter(|(id, _)| *id == offer.player_id).map(|(_, amount)| amount).sum();
+        let lost = u128::from(total.saturating_sub(won).get());
+        let payout = Chips::new((u128::from(offer.coverage.get()) * lost / u128::from(total.get().max(1))) as u64);
+
+        let player_id = offer.player_id;
+        let player = self.find_player_mut(&player_id)?;
+        player.add_chips(payout);
+        self.chips_in_play += payout;
+        self.record(TransactionKind::InsurancePayout, Account::Insurer, Account::Player(player_id.clone()), payout);
+        info!(%player_id, %payout, "insurance settled");
+        self.events.push(GameEvent::InsuranceSettled { hand_number: self.hand_number, player_id, payout });
+        Ok(())
+    }
+
+    /// Shows `player_id` the cards that would have completed the board of
+    /// the last hand, which ended before the river. The cards are only
+    /// looked at, not dealt, so the deck is left as it was. Each player may
+    /// hunt once an orbit: once in as many hands as there are players seated.
+    pub fn rabbit_hunt(&mut self, player_id: &str) -> Result<Vec<Card>, GameError> {
+        self.find_player(player_id)?;
+        if self.hand_in_progress {
+            return Err(GameError::InvalidState("the rabbit can only be hunted once the hand is over".to_string()));
+        }
+        let cards = self.unseen_board.as_ref()
+            .filter(|(hand_number, _)| *hand_number == self.hand_number)
+            .map(|(_, cards)| cards.clone())
+            .ok_or_else(|| GameError::InvalidState("the last hand didn't end before the river".to_string()))?;
+        let orbit = self.players.len() as u64;
+        if let Some(next) = self.rabbit_hunts.get(player_id).map(|last| last + orbit).filter(|next| self.hand_number < *next) {
+            return Err(GameError::NotAllowed(format!("one rabbit hunt per orbit; the next is allowed from hand {next}")));
+        }
+        self.rabbit_hunts.insert(player_id.into(), self.hand_number);
+        info!(player_id, ?cards, "rabbit hunted");
+        self.deal_records.push(DealRecord::RabbitHunt { hand_number: self.hand_number, player_id: player_id.into(), cards: cards.clone() });
+        self.events.push(GameEvent::RabbitHunted { hand_number: self.hand_number, player_id: player_id.into(), cards: cards.clone() });
+        Ok(cards)
+    }
+
+    /// Shows the table `cards` from the hole cards of `player_id`, who won
+    /// the last hand without a showdown. One card or both may be shown, and
+    /// the hand's log is recorded again with them.
+    pub fn show_cards(&mut self, player_id: &str, cards: &[Card]) -> Result<(), GameError> {
+        self.find_player(player_id)?;
+        if self.hand_in_progress {
+            return Err(GameError::InvalidState("cards can only be shown once the hand is over".to_string()));
+        }
+        let log = self.hand_log.as_mut()
+            .filter(|log| log.hand_number == self.hand_number && log.finished && log.showdown.is_empty())
+            .ok_or_else(|| GameError::InvalidState("the last hand went to showdown".to_string()))?;
+        if !log.payouts.iter().any(|(id, _)| id == player_id) {
+            return Err(GameError::NotAllowed("only the winner of the hand may show their cards".to_string()));
+        }
+        let hole_cards = log.hole_cards.get(player_id).map(Vec::as_slice).unwrap_or_default();
+        if cards.is_empty() {
+            return Err(GameError::InvalidRequest("show at least one card".to_string()));
+        }
+        if let Some(card) = cards.iter().find(|card| !hole_cards.contains(card)) {
+            return Err(GameError::InvalidRequest(format!("{card} isn't one of your hole cards")));
+        }
+        let mut shown = log.shown.iter().filter(|(id, _)| id == player_id).flat_map(|(_, cards)| cards);
+        if let Some(card) = shown.find(|card| cards.contains(card)) {
+            return Err(GameError::InvalidState(format!("{card} has already been shown")));
+        }
+        log.shown.push((player_id.into(), cards.to_vec()));
+        self.finished_hands.push(log.clone());
+        info!(player_id, ?cards, "cards shown");
+        self.events.push(GameEvent::CardsShown { hand_number: self.hand_number, player_id: player_id.into(), cards: cards.to_vec() });
+        Ok(())
+    }
+
+    /// Deals community cards until `cards` are showing.
+    fn deal_board(&mut self, cards: usize) -> Result<(), GameError> {
+        let _span = self.hand_span().entered();
+        let more = self.deck.deal(cards - self.community_cards.len()).ok_or(GameError::NotEnoughCards("community cards"))?;
+        self.deal_records.push(DealRecord::CommunityCards { hand_number: self.hand_number, board: 1, cards: more.clone() });
+        self.community_cards.extend(more);
+        let runs = self.betting.as_ref().and_then(|betting| betting.runs).unwrap_or(1);
+        self.table.extra_boards.resize(runs.max(self.variant.boards()) - 1, Vec::new());
+        for (index, board) in self.table.extra_boards.iter_mut().enumerate() {
+            let more = self.deck.deal(cards - board.len()).ok_or(GameError::NotEnoughCards("community cards"))?;
+            self.deal_records.push(DealRecord::CommunityCards { hand_number: self.hand_number, board: index + 2, cards: more.clone() });
+            board.extend(more);
+        }
+        self.check_cards()?;
+        if let Some(log) = &mut self.hand_log {
+            log.board = self.community_cards.clone();
+            log.extra_boards = self.table.extra_boards.clone();
+        }
+        info!(board = ?self.community_cards, extra_boards = ?self.table.extra_boards, "community cards dealt");
+        self.events.push(GameEvent::BoardDealt { hand_number: self.hand_number, cards: self.community_cards.clone() });
+        for (index, board) in self.table.extra_boards.iter().enumerate() {
+            self.events.push(GameEvent::ExtraBoardDealt { hand_number: self.hand_number, board: index + 2, cards: board.clone() });
+        }
+        for player in self.players.iter_mut().filter(|player| player.is_in_play) {
+            player.evaluate_hand_with_wilds(self.variant, self.wild_cards, &self.community_cards); // Keeps showdown to the river's new combinations
+        }
+        Ok(())
+    }
+
+    /// Evaluates the hands still in and pays out every pot.
+    fn showdown(&mut self) -> Result<(), GameError> {
+        self.take_rake();
+        self.take_jackpot_drop();
+        self.evaluate_player_hands(); // Also picks each pot's winners
+        self.resolve_extra_boards();
+        self.record_runouts();
+        self.pay_bad_beat_jackpot()?;
+        self.finish_hand()
+    }
+
+    /// Takes the house's rake out of the pots, main pot first. Only called
+    /// chips are raked: a bet nobody matched goes back whole.
+    fn take_rake(&mut self) {
+        let flopped = self.betting.as_ref().is_some_and(|betting| betting.street != Street::Preflop);
+        if self.rake.percent == 0 || (self.rake.no_flop_no_drop && !flopped) {
+            return;
+        }
+        let mut bets: Vec<Chips> = self.table.player_bets().values().copied().collect();
+        bets.sort_unstable_by(|a, b| b.cmp(a));
+        let uncalled = match bets.as_slice() {
+            [top, next, ..] => *top - *next,
+            _ => bets.first().copied().unwrap_or_default(),
+        };
+        let called: Chips = self.table.pots.iter().map(|pot| pot.total).sum::<Chips>().saturating_sub(uncalled);
+        let rake = self.rake.rake_for(called);
+        let mut left = rake;
+        for pot in &mut self.table.pots {
+            let taken = left.min(pot.total);
+            pot.total -= taken;
+            left -= taken;
+        }
+        let rake = rake - left;
+        if rake.is_zero() {
+            return;
+        }
+        self.chips_in_play -= rake;
+        self.ledger.record(self.hand_number, TransactionKind::Rake, Account::Pot, Account::House, rake);
+        if let Some(log) = &mut self.hand_log {
+            log.rake = rake;
+        }
+        debug!(%rake, "rake taken");
+    }
+
+    /// Moves the jackpot drop out of the main pot into the jackpot pool.
+    fn take_jackpot_drop(&mut self) {
+        let flopped = self.street_bets().len() > 1;
+        let (Some(jackpot), Some(pot)) = (&mut self.jackpot, self.table.pots.first_mut()) else { return };
+        if jackpot.rules.no_flop_no_drop && !flopped {
+            return;
+        }
+        let drop = jackpot.drop_for(pot.total);
+        if drop.is_zero() {
+            return;
+        }
+        pot.total -= drop;
+        jackpot.add(drop);
+        self.chips_in_play -= drop;
+        self.ledger.record(self.hand_number, TransactionKind::Rake, Account::Pot, Account::Jackpot, drop);
+        debug!(%drop, pool = %jackpot.pool(), "jackpot drop taken");
+    }
+
+    /// Pays the jackpot when the best hand to lose the main pot at showdown
+    /// qualifies: shares go to the players holding it, the pot's winners and
+    /// everyone else dealt in.
+    fn pay_bad_beat_jackpot(&mut self) -> Result<(), GameError> {
+        let (Some(jackpot), Some(pot), Some(betting)) = (&self.jackpot, self.table.pots.first(), &self.betting) else { return Ok(()) };
+        let Some(winners) = pot.winners.clone() else { return Ok(()) };
+        let beaten: Vec<PlayerId> = pot.eligible_players.iter().filter(|id| !winners.contains(id)).cloned().collect();
+        let losers = self.get_winners(&beaten).unwrap_or_default();
+        let Some(losing_hand) = losers.first().and_then(|id| self.players.get(id)?.best_hand.as_ref()).map(|hand| hand.rank.clone()) else { return Ok(()) };
+        if !jackpot.qualifies(&losing_hand) {
+            return Ok(());
+        }
+        let table: Vec<PlayerId> = betting.seats.iter().filter(|id| !losers.contains(id) && !winners.contains(id)).cloned().collect();
+
+        let Some(jackpot) = &mut self.jackpot else { return Ok(()) };
+        let payout = jackpot.hit(losers.len(), winners.len(), table.len());
+        let pool = jackpot.pool();
+        let payouts: Vec<(PlayerId, Chips)> = losers.into_iter().map(|id| (id, payout.per_loser))
+            .chain(winners.into_iter().map(|id| (id, payout.per_winner)))
+            .chain(table.into_iter().map(|id| (id, payout.per_table)))
+            .filter(|(_, amount)| !amount.is_zero())
+            .collect();
+        for (player_id, amount) in &payouts {
+            self.find_player_mut(player_id)?.add_chips(*amount);
+            self.chips_in_play += *amount;
+            self.record(TransactionKind::Jackpot, Account::Jackpot, Account::Player(player_id.clone()), *amount);
+            info!(%player_id, %amount, "bad beat jackpot paid");
+        }
+        self.events.push(GameEvent::BadBeatJackpot { hand_number: self.hand_number, losing_hand, payouts, pool });
+        Ok(())
+    }
+
+    /// Picks each pot's winners on every board after the first, from the
+    /// hands of the players eligible for it.
+    fn resolve_extra_boards(&mut self) {
+        let boards = 1 + self.table.extra_boards.len();
+        for (index, board) in self.table.extra_boards.iter().enumerate() {
+            for (pot_index, pot) in self.table.pots.iter_mut().enumerate() {
+                let values: Vec<(&PlayerId, _)> = pot.eligible_players.iter()
+                    .filter_map(|id| {
+                        Some((id, self.wild_cards.hand_value(self.variant, &self.players.get(id)?.hole_cards, board)?))
+                    })
+                    .collect();
+                let best = values.iter().map(|(_, value)| *value).max();
+                let winners: Vec<PlayerId> = values.iter().filter(|(_, value)| Some(*value) == best).map(|(id, _)| (*id).clone()).collect();
+                let amount = Pot::board_shares(pot.total, boards)[index + 1];
+                info!(board = index + 2, pot = %pot.total, ?winners, "pot resolved");
+                self.events.push(GameEvent::PotAwarded { hand_number: self.hand_number, pot: pot_index, amount, winners: winners.clone() });
+                pot.extra_board_winners.push(winners);
+            }
+        }
+    }
+
+    /// Lists each run of a board run more than once with what it won.
+    fn record_runouts(&mut self) {
+        if self.betting.as_ref().and_then(|betting| betting.runs).unwrap_or(1) < 2 {
+            return;
+        }
+        let runouts = std::iter::once(&self.community_cards)
+            .chain(&self.table.extra_boards)
+            .enumerate()
+            .map(|(index, board)| {
+                let pots: Vec<Vec<(PlayerId, Chips)>> = self.table.pots.iter().map(|pot| pot.board_payouts(index)).collect();
+                Runout {
+                    board: board.clone(),
+                    winners: pots.iter().map(|payouts| payouts.iter().map(|(id, _)| id.clone()).collect()).collect(),
+                    payouts: pots.into_iter().flatten().collect(),
+                }
+            })
+            .collect();
+        self.events.push(GameEvent::RunoutResults { hand_number: self.hand_number, runouts });
+    }
+
+    /// Pays out the pots and closes the hand. A pot nobody has won yet goes
+    /// to the last player left in.
+    fn finish_hand(&mut self) -> Result<(), GameError> {
+        let _span = self.hand_span().entered();
+        let in_play: Vec<PlayerId> = self.players.iter()
+            .filter(|player| player.is_in_play)
+            .map(|player| player.player_id.clone())
+            .collect();
+        for (index, pot) in self.table.pots.iter_mut().enumerate() {
+            if pot.winners.is_none() && in_play.len() == 1 {
+                pot.winners = Some(in_play.clone());
+                self.events.push(GameEvent::PotAwarded {
+                    hand_number: self.hand_number,
+                    pot: index,
+                    amount: pot.total,
+                    winners: in_play.clone(),
+                });
+            }
+        }
+        let payouts: Vec<(PlayerId, Chips)> = self.table.pots.iter().flat_map(Pot::payouts).collect();
+        self.record_results(&payouts);
+        for (winner, amount) in payouts.clone() {
+            self.find_player_mut(&winner)?.add_chips(amount);
+            info!(player_id = %winner, %amount, "pot paid");
+            self.record(TransactionKind::PotAward, Account::Pot, Account::Player(winner), amount);
+        }
+        self.pay_seven_deuce_bonus()?;
+        self.settle_insurance()?;
+
+        let showdown = if in_play.len() > 1 { in_play.clone() } else { Vec::new() };
+        let winners = if in_play.len() == 1 { in_play } else { self.get_winners(&in_play).unwrap_or_default() };
+        self.events.push(GameEvent::HandFinished { hand_number: self.hand_number, winners });
+        for player in self.players.iter().filter(|player| player.chip_stack.is_zero()) {
+            self.events.push(GameEvent::PlayerBusted { player_id: player.player_id.clone() });
+        }
+        let eliminations = self.eliminations();
+        for elimination in &eliminations {
+            self.award_bounty(elimination)?;
+        }
+        self.hand_in_progress = false;
+        let streets = self.betting.take().map(|betting| betting.streets).unwrap_or_default();
+        let missing = Street::River.board_cards().saturating_sub(self.community_cards.len());
+        self.unseen_board = (missing > 0 && self.variant.boards() == 1)
+            .then(|| self.deck.cards().get(..missing).map(|cards| (self.hand_number, cards.to_vec())))
+            .flatten();
+        if let Some(log) = self.hand_log.as_mut().filter(|log| log.hand_number == self.hand_number) {
+            log.eliminations = eliminations;
+            log.payouts = payouts;
+            log.streets = streets;
+            log.showdown = showdown;
+            log.finished = true;
+            self.finished_hands.push(log.clone());
+        }
+        info!("hand finished");
+        Ok(())
+    }
+
+    /// Adds what the pots paid each player dealt in, less what they put in,
+    /// to their running totals: in actual chips, and at the equity priced
+    /// for an all-in before the river.
+    fn record_results(&mut self, payouts: &[(PlayerId, Chips)]) {
+        let Some(betting) = &self.betting else { return };
+        for player_id in &betting.seats {
+            let put_in = self.table.player_bets().get(player_id).copied().unwrap_or_default();
+            let won: Chips = payouts.iter().filter(|(winner, _)| winner == player_id).map(|(_, amount)| *amount).sum();
+            let share = self.hand_log.as_mut().and_then(|log| log.all_in.iter_mut().find(|share| share.player_id == *player_id));
+            let expected = match share {
+                Some(share) => {
+                    share.won = won;
+                    share.expected
+                }
+                None => won.get() as f64,
+            };
+            let Some(player) = self.players.get_mut(player_id) else { continue };
+            player.chips_won += won.get() as i64 - put_in.get() as i64;
+            player.ev_chips_won += expected - put_in.get() as f64;
+        }
+    }
+
+    /// Pays the seven-deuce bonus to each pot winner holding 7-2 offsuit,
+    /// from every other player dealt in. A player short of the bonus pays
+    /// what they have.
+    fn pay_seven_deuce_bonus(&mut self) -> Result<(), GameError> {
+        let (Some(bonus), Some(betting)) = (self.seven_deuce_bonus, &self.betting) else { return Ok(()) };
+        let mut winners: Vec<PlayerId> = self.table.pots.iter()
+            .flat_map(|pot| pot.winners.iter().flatten().chain(pot.extra_board_winners.iter().flatten()))
+            .cloned()
+            .collect();
+        let mut seen = HashSet::new();
+        winners.retain(|winner| seen.insert(winner.clone()) && self.players.get(winner).is_some_and(|player| is_seven_deuce(&player.hole_cards)));
+        let seats = betting.seats.clone();
+        for winner in winners {
+            let mut collected = Chips::ZERO;
+            for payer in seats.iter().filter(|id| **id != winner) {
+                let player = self.find_player_mut(payer)?;
+                let paid = bonus.min(player.chip_stack);
+                player.chip_stack -= paid;
+                collected += paid;
+                self.ledger.record(self.hand_number, TransactionKind::Bonus, Account::Player(payer.clone()), Account::Player(winner.clone()), paid);
+            }
+            self.find_player_mut(&winner)?.add_chips(collected);
+            info!(player_id = %winner, %collected, "seven-deuce bonus paid");
+            self.events.push(GameEvent::SevenDeuceBonus { hand_number: self.hand_number, player_id: winner, amount: collected });
+        }
+        Ok(())
+    }
+
+    /// The players dealt into this hand who have lost every chip, each
+    /// credited to the winners of the highest pot they were eligible for: the
+    /// one holding their last chips.
+    fn eliminations(&self) -> Vec<Elimination> {
+        let Some(betting) = &self.betting else { return Vec::new() };
+        betting.seats.iter()
+            .filter(|id| self.players.get(id).is_some_and(|player| player.chip_stack.is_zero()))
+            .filter_map(|id| {
+                let pot = self.table.pots.iter().rev().find(|pot| pot.eligible_players.contains(id))?;
+                let mut eliminated_by: Vec<PlayerId> = pot.winners.clone()?.into_iter().chain(pot.extra_board_winners.concat()).filter(|winner| winner != id).collect();
+                let mut seen = HashSet::new();
+                eliminated_by.retain(|winner| seen.insert(winner.clone())); // A winner on both boards collects once
+                (!eliminated_by.is_empty()).then(|| Elimination { player_id: id.clone(), eliminated_by })
+            })
+            .collect()
+    }
+
+    /// Pays a knocked-out player's bounty to whoever eliminated them, when
+    /// the table has bounties.
+    fn award_bounty(&mut self, elimination: &Elimination) -> Result<(), GameError> {
+        let Some(rules) = self.bounty_rules else { return Ok(()) };
+        let bounty = std::mem::take(&mut self.find_player_mut(&elimination.player_id)?.bounty);
+        for (eliminator, (paid, kept)) in elimination.eliminated_by.iter().zip(rules.split(bounty, elimination.eliminated_by.len())) {
+            let player = self.find_player_mut(eliminator)?;
+            player.bounties_won += paid;
+            player.bounty += kept;
+            info!(player_id = %eliminator, knocked_out = %elimination.player_id, %paid, bounty = %player.bounty, "bounty won");
+        }
+        self.events.push(GameEvent::BountyAwarded {
+            hand_number: self.hand_number,
+            player_id: elimination.player_id.clone(),
+            eliminated_by: elimination.eliminated_by.clone(),
+            amount: bounty,
+        });
+        Ok(())
+    }
+
+    /// Checks that the game state is consistent: no chips created or lost, no
+    /// card in two places, and the betting matching the street being played.
+    /// Returns every broken rule found.
+    ///
+    /// Debug builds run this after every action and panic on a violation.
+    pub fn verify_invariants(&self) -> Result<(), Vec<String>> {
+        let mut violations = Vec::new();
+
+        let stacks: Chips = self.players.iter().map(|player| player.chip_stack).sum();
+        let committed = self.table.total_committed();
+        // Bets of a finished hand have already been paid out to the winners
+        let chips = stacks + if self.hand_in_progress { committed } else { Chips::ZERO };
+        if chips != self.chips_in_play {
+            violations.push(format!("{chips} chips on the table, expected {}", self.chips_in_play));
+        }
+        if let Err(mismatches) = self.reconcile_ledger() {
+            violations.extend(mismatches);
+        }
+        if self.betting.is_some() {
+            let pots: Chips = self.table.pots.iter().map(|pot| pot.total).sum();
+            if pots != committed {
+                violations.push(format!("pots hold {pots} chips but {committed} were bet"));
+            }
+        }
+
+        if let Err(GameError::DuplicateCard(card)) = self.check_cards() {
+            violations.push(format!("card {card} is in play twice"));
+        }
+
+        if let Some(betting) = &self.betting {
+            if !self.hand_in_progress {
+                violations.push("betting is open but no hand is in progress".to_string());
+            }
+            let board_cards = betting.street.board_cards().max(self.variant.preflop_board_cards());
+            if self.community_cards.len() != board_cards {
+                violations.push(format!("{:?} is being bet with {} board cards", betting.street, self.community_cards.len()));
+            }
+            for player_id in &betting.to_act {
+                if !betting.seats.contains(player_id) || !can_act(&self.players, player_id) {
+                    violations.push(format!("{player_id} is waiting to act but cannot"));
+                }
+            }
+            if let Some((player_id, &bet)) = betting.street_bets.iter().find(|(_, &bet)| bet > betting.current_bet) {
+                violations.push(format!("{player_id} bet {bet}, above the current bet of {}", betting.current_bet));
+            }
+            if let Some((player_id, &bet)) = betting.street_bets.iter().find(|(id, &bet)| bet > self.table.committed(id)) {
+                violations.push(format!("{player_id} bet {bet} this street but only {} in the hand", self.table.committed(player_id)));
+            }
+            for (player_id, &committed) in self.table.player_bets() {
+                let by_street: Chips = betting.streets.iter().map(|street| street.bet(player_id)).sum();
+                if by_street != committed {
+                    violations.push(format!("{player_id} put in {by_street} street by street but {committed} in the hand"));
+                }
+            }
+        } else if !matches!(self.community_cards.len(), 0 | 3 | 4 | 5) {
+            violations.push(format!("{} board cards dealt", self.community_cards.len()));
+        }
+
+        if violations.is_empty() { Ok(()) } else { Err(violations) }
+    }
+
+    /// Checks that every physical card is in one place only: the deck, a
+    /// player's hand or the board. Dealing runs this after every deal, so a
+    /// corrupted deck stops the hand rather than dealing a card twice. With
+    /// several decks each card is there once per deck.
+    pub fn check_cards(&self) -> Result<(), GameError> {
+        let held = self.players.iter().flat_map(|player| &player.hole_cards);
+        let boards = self.table.extra_boards.iter().flat_map(|board| &board[self.table.shared_cards.min(board.len())..]);
+        let mut copies: HashMap<Card, usize> = HashMap::new();
+        for &card in self.deck.cards().iter().chain(held).chain(&self.community_cards).chain(boards) {
+            let count = copies.entry(card).or_default();
+            *count += 1;
+            if *count > self.deck.decks() {
+                return Err(GameError::DuplicateCard(card));
+            }
+        }
+        Ok(())
+    }
+
+    /// Panics on a broken invariant in debug builds; does nothing in release.
+    fn debug_verify(&self) {
+        #[cfg(debug_assertions)]
+        if let Err(violations) = self.verify_invariants() {
+            panic!("game invariants broken: {}", violations.join("; "));
+        }
+    }
+
+    pub fn get_community_cards(&self) -> &Vec<Card> {
+        &self.community_cards
+    }
+
+    /// Find the winner(s) amongst the provided player pool
+    /// Returns the indexes of the winning players (more than one in case of a tie)
+    pub fn get_winners(&self, player_pool: &[PlayerId]) -> Option<Vec<PlayerId>> {
+        let mut best_hand_rank = HandRank::HighCard;
+        let mut best_hands: Vec<&Hand> = Vec::new(); // Store best hands for tie-breaking
+        let mut winners: Vec<PlayerId> = Vec::new();
+    
+        for player in player_pool.iter().filter_map(|player_id| self.players.get(player_id)) {
+    
+            if let Some(ref hand) = player.best_hand {
+                if hand.rank > best_hand_rank {
+                    // Found a stronger hand, reset the winner list
+                    best_hand_rank = hand.rank.clone();
+                    best_hands.clear();
+                    winners.clear();
+                    best_hands.push(hand);
+                    winners.push(player.player_id.clone());
+                } else if hand.rank == best_hand_rank {
+                    if best_hands.is_empty() {
+                        // 🚀 Safety check: Prevent out-of-bounds panic
+                        best_hands.push(hand);
+                        winners.push(player.player_id.clone());
+                    } else {
+                        match hand.compare_two_hands(best_hands[0]) {
+                            Ordering::Greater => {
+                                // New best hand found, reset previous winners
+                                best_hands.clear();
+                                winners.clear();
+                                best_hands.push(hand);
+                                winners.push(player.player_id.clone());
+                            }
+                            Ordering::Equal => {
+                                // Exact tie, keep both players as winners
+                                best_hands.push(hand);
+                                winners.push(player.player_id.clone());
+                            }
+                            Ordering::Less => {
+                                // This hand is weaker, do nothing
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    
+        if winners.is_empty() {
+            None // No winners found
+        } else {
+            Some(winners)
+        }
+    }
+    
+    
+
+
+    pub fn resolve_pots(&mut self) {
+    // Step 1: Collect winners for each pot BEFORE mutably borrowing `self.table.pots`
+    let winners_for_pots: Vec<Option<Vec<PlayerId>>> = self.table.pots.iter()
+        .map(|pot| self.get_winners(&pot.eligible_players)) // Get winners for each pot
+        .collect();
+
+    // Step 2: Mutably iterate over `self.table.pots` AFTER winner data is collected
+    let boards = 1 + self.table.extra_boards.len(); // The first board wins its share of each pot
+    for (index, (pot, winners)) in self.table.pots.iter_mut().zip(winners_for_pots).enumerate() {
+        info!(pot = %pot.total, winners = ?winners, "pot resolved");
+        if let Some(winners) = &winners {
+            self.events.push(GameEvent::PotAwarded {
+                hand_number: self.hand_number,
+                pot: index,
+                amount: Pot::board_shares(pot.total, boards)[0],
+                winners: winners.clone(),
+            });
+        }
+        pot.winners = winners; // Assign winners to each pot
+    }
+}
+
+
+
+    pub fn get_table(&self) -> &Table {
+        &self.table
+    }
+
+    pub fn get_table_mut(&mut self) -> &mut Table {
+        &mut self.table
+    }
+    
+}
+
+fn not_a_tournament() -> GameError {
+    GameError::NotAllowed("this table is not a tournament".to_string())
+}
+
+fn check_blinds(small_blind: Chips, big_blind: Chips) -> Result<(), GameError> {
+    if small_blind.is_zero() || big_blind < small_blind {
+        return Err(GameError::InvalidRequest("blinds must be positive and the big blind at least the small blind".to_string()));
+    }
+    Ok(())
+}
+
+/// True for a seven and a deuce of different suits.
+fn is_seven_deuce(hole_cards: &[Card]) -> bool {
+    match hole_cards {
+        [a, b] => a.suit != b.suit && matches!((a.rank, b.rank), (Rank::Seven, Rank::Two) | (Rank::Two, Rank::Seven)),
+        _ => false,
+    }
+}
+
+/// True if `player_id` still holds cards in the current hand.
+fn in_play(players: &PlayerRegistry, player_id: &str) -> bool {
+    players.get(player_id).is_some_and(|player| player.is_in_play)
+}
+
+/// True if `player_id` is in the hand and has chips left to bet.
+fn can_act(players: &PlayerRegistry, player_id: &str) -> bool {
+    players.get(player_id).is_some_and(|player| player.is_in_play && !player.chip_stack.is_zero())
+}
+
+#[cfg(feature = "os-rng")]
+impl Default for GameController {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn controller_with_players() -> GameController {
+        let mut controller = GameController::with_rng(StdRng::seed_from_u64(1));
+        controller.initialize_players(vec![
+            ("1".to_string(), "Alice".to_string(), 0, Chips::new(1000)),
+            ("2".to_string(), "Bob".to_string(), 1, Chips::new(1000)),
+        ]);
+        controller
+    }
+
+    #[test]
+    fn test_no_new_hands_after_stop() {
+        let mut controller = controller_with_players();
+        controller.stop_new_hands();
+        assert!(matches!(controller.deal_hole_cards(), Err(GameError::InvalidState(_))));
+    }
+
+    #[test]
+    fn test_cancel_hand_refunds_bets() {
+        let mut controller = controller_with_players();
+        controller.deal_hole_cards().unwrap();
+        assert!(controller.is_hand_in_progress());
+
+        controller.table.add_bet(&mut controller.players[0], Chips::new(100)).unwrap();
+        assert_eq!(controller.get_players()[0].chip_stack, Chips::new(900));
+
+        controller.cancel_hand();
+        assert!(!controller.is_hand_in_progress());
+        assert_eq!(controller.get_players()[0].chip_stack, Chips::new(1000));
+        assert!(controller.get_table().pots.is_empty());
+    }
+
+    #[test]
+    fn test_admin_moderation() {
+        let mut controller = controller_with_players();
+        assert_eq!(controller.adjust_chips("1", -250), Ok(Chips::new(750)));
+        assert!(controller.adjust_chips("1", -1000).is_err());
+
+        controller.pause();
+        assert!(controller.deal_hole_cards().is_err());
+        controller.resume();
+        controller.deal_hole_cards().unwrap();
+
+        assert!(controller.remove_player("2").is_err()); // Still in the hand
+        controller.sit_out_player("2").unwrap();
+        assert_eq!(controller.remove_player("2").unwrap().display_name, "Bob");
+        assert_eq!(controller.get_players().len(), 1);
+    }
+
+    #[test]
+    fn test_registry_follows_seating_changes() {
+        let mut controller = controller_with_players();
+        controller.seat_bot("3", "Cal", Chips::new(500), Box::new(crate::bots::CallingStation)).unwrap();
+        assert!(controller.seat_bot("3", "Again", Chips::new(500), Box::new(crate::bots::CallingStation)).is_err());
+
+        controller.remove_player("1").unwrap();
+        assert!(matches!(controller.find_player("1"), Err(GameError::PlayerNotFound(_))));
+        // Seats after the removed one are still found once the others move down
+        assert_eq!(controller.find_player("2").unwrap().display_name, "Bob");
+        assert_eq!(controller.find_player("3").unwrap().chip_stack, Chips::new(500));
+        controller.start_hand(Chips::new(5), Chips::new(10)).unwrap();
+        assert_eq!(controller.hand_log().unwrap().seats, vec![PlayerId::from("2"), PlayerId::from("3")]);
+    }
+
+    #[test]
+    fn test_waiting_list_fills_open_seats() {
+        let mut controller = controller_with_players();
+        controller.set_max_seats(3);
+        assert_eq!(controller.join_table("3", "Cal", Chips::new(1000)), Ok(Seating::Seated { table_position: 2 }));
+        assert_eq!(controller.join_table("4", "Dee", Chips::new(1000)), Ok(Seating::Waiting { position: 1 }));
+        assert_eq!(controller.join_table("5", "Eve", Chips::new(800)), Ok(Seating::Waiting { position: 2 }));
+        assert!(controller.join_table("4", "Dee", Chips::new(1000)).is_err());
+        assert!(controller.join_table("6", "Fay", Chips::new(50)).is_err()); // Below the minimum buy-in
+        assert!(matches!(controller.seat_bot("bot", "Robo", Chips::new(500), Box::new(crate::bots::CallingStation)), Err(GameError::NotAllowed(_))));
+
+        controller.take_events();
+        controller.remove_player("1").unwrap();
+        assert_eq!(controller.find_player("4").unwrap().table_position, 0); // First in line takes the open seat
+        assert_eq!(controller.waitlist().position("5"), Some(1));
+        let kinds: Vec<_> = controller.take_events().iter().map(GameEvent::kind).collect();
+        assert_eq!(kinds, vec!["player_removed", "seat_opened", "player_seated"]);
+
+        controller.leave_waitlist("5").unwrap();
+        controller.remove_player("2").unwrap();
+        assert!(controller.waitlist().is_empty());
+        assert_eq!(controller.get_players().len(), 2);
+        assert_eq!(controller.verify_invariants(), Ok(()));
+    }
+
+    #[test]
+    fn test_reserved_seats_count_as_taken() {
+        let mut controller = controller_with_players();
+        controller.set_max_seats(3);
+        controller.reserve_seat("3", "1").unwrap();
+        assert!(matches!(controller.reserve_seat("3", "1"), Err(GameError::InvalidState(_))));
+        assert!(matches!(controller.reserve_seat("4", "1"), Err(GameError::NotAllowed(_))));
+        assert_eq!(controller.join_table("4", "Dee", Chips::new(1000)), Ok(Seating::Waiting { position: 1 }));
+        assert_eq!(controller.join_table("3", "Cal", Chips::new(1000)), Ok(Seating::Seated { table_position: 2 })); // Ahead of the waiting list
+
+        controller.remove_player("3").unwrap();
+        assert_eq!(controller.find_player("4").unwrap().table_position, 2);
+        controller.remove_player("4").unwrap();
+        controller.reserve_seat("5", "2").unwrap();
+        controller.join_table("6", "Fay", Chips::new(1000)).unwrap();
+        controller.take_events();
+        controller.release_seat("5").unwrap();
+        assert_eq!(controller.find_player("6").unwrap().table_position, 2);
+        let kinds: Vec<_> = controller.take_events().iter().map(GameEvent::kind).collect();
+        assert_eq!(kinds, vec!["seat_released", "player_seated"]);
+        assert!(matches!(controller.release_seat("5"), Err(GameError::PlayerNotFound(_))));
+    }
+
+    #[test]
+    fn test_late_registration_and_re_entry() {
+        use crate::card_dealer::parse_cards;
+        let mut controller = controller_with_players();
+        assert!(matches!(controller.register("3", "Cal"), Err(GameError::NotAllowed(_))));
+        controller.set_tournament_rules(Some(TournamentRules { late_registration_until: 2, max_entries: 2, ..TournamentRules::default() }));
+        controller.set_max_seats(3);
+        assert_eq!(controller.register("3", "Cal"), Ok(Seating::Seated { table_position: 2 }));
+        assert_eq!(controller.register("4", "Dee"), Ok(Seating::Waiting { position: 1 }));
+        assert!(controller.register("3", "Cal").is_err());
+        assert_eq!(controller.find_player("3").unwrap().chip_stack, Chips::new(1500));
+
+        // Cal busts in the first hand and re-enters, handing their seat to Dee
+        controller.adjust_chips("1", 1000).unwrap();
+        controller.stack_deck(parse_cards("Ah Ad 7c 2d Kc Kd Qs Jh 9h 8c 3s").unwrap()).unwrap();
+        controller.start_hand(Chips::new(5), Chips::new(10)).unwrap();
+        controller.act("1", PlayerAction::Raise(Chips::new(1990))).unwrap();
+        controller.act("2", PlayerAction::Fold).unwrap();
+        controller.act("3", PlayerAction::Call).unwrap();
+        assert!(controller.find_player("3").unwrap().chip_stack.is_zero());
+        assert!(matches!(controller.re_enter("1"), Err(GameError::InvalidRequest(_)))); // Never registered
+        assert_eq!(controller.re_enter("3"), Ok(Seating::Waiting { position: 1 }));
+        assert_eq!(controller.find_player("4").unwrap().table_position, 2);
+        assert_eq!(controller.waitlist().position("3"), Some(1));
+
+        let tournament = controller.tournament().unwrap();
+        assert_eq!((tournament.entries("3"), tournament.total_entries(), tournament.prize_pool()), (2, 3, Chips::new(300)));
+        // Registration closes after hand 2
+        controller.start_hand(Chips::new(5), Chips::new(10)).unwrap();
+        controller.cancel_hand();
+        assert!(matches!(controller.register("5", "Eve"), Err(GameError::NotAllowed(_))));
+        assert_eq!(controller.verify_invariants(), Ok(()));
+    }
+
+    #[test]
+    fn test_new_blinds_apply_from_the_next_hand() {
+        let mut controller = controller_with_players();
+        assert!(matches!(controller.start_next_hand(), Err(GameError::InvalidState(_))));
+        controller.set_blinds(Chips::new(5), Chips::new(10)).unwrap();
+        controller.start_next_hand().unwrap();
+        assert!(controller.set_blinds(Chips::new(20), Chips::new(10)).is_err());
+        controller.set_blinds(Chips::new(10), Chips::new(20)).unwrap();
+        assert_eq!(controller.betting().unwrap().current_bet, Chips::new(10)); // The hand in progress keeps its blinds
+        controller.cancel_hand();
+
+        controller.set_on_break(true);
+        assert!(matches!(controller.start_next_hand(), Err(GameError::InvalidState(_))));
+        controller.set_on_break(false);
+        controller.start_next_hand().unwrap();
+        assert_eq!(controller.hand_log().map(|log| (log.small_blind, log.big_blind)), Some((Chips::new(10), Chips::new(20))));
+        let kinds: Vec<_> = controller.take_events().iter().map(GameEvent::kind).filter(|kind| kind.starts_with("blinds") || kind.starts_with("break")).collect();
+        assert_eq!(kinds, vec!["blinds_changed", "blinds_changed", "break_started", "break_ended"]);
+    }
+
+    #[test]
+    fn test_configure_sets_up_the_next_hand() {
+        let mut controller = controller_with_players();
+        let config = TableConfig { variant: GameVariant::Omaha, small_blind: Chips::new(25), big_blind: Chips::new(50), max_seats: 6, decks: 2, ..TableConfig::default() };
+        controller.configure(&config).unwrap();
+        assert_eq!(controller.blinds(), Some((Chips::new(25), Chips::new(50))));
+        assert_eq!((controller.variant(), controller.max_seats()), (GameVariant::Omaha, 6));
+        assert_eq!(controller.deck.decks(), 2);
+
+        // A bad setting leaves the table as it was
+        assert!(controller.configure(&TableConfig { decks: 0, ..config.clone() }).is_err());
+        assert_eq!(controller.blinds(), Some((Chips::new(25), Chips::new(50))));
+        controller.start_next_hand().unwrap();
+        assert!(matches!(controller.configure(&config), Err(GameError::InvalidState(_))));
+    }
+
+    #[test]
+    fn test_hand_for_hand_waits_for_a_release() {
+        let mut controller = controller_with_players();
+        controller.start_hand(Chips::new(5), Chips::new(10)).unwrap();
+        controller.set_hand_for_hand(true);
+        controller.act("1", PlayerAction::Fold).unwrap(); // The hand in progress plays on
+        assert!(matches!(controller.start_hand(Chips::new(5), Chips::new(10)), Err(GameError::InvalidState(_))));
+
+        controller.release_next_hand();
+        controller.start_hand(Chips::new(5), Chips::new(10)).unwrap();
+        controller.cancel_hand();
+        assert!(controller.start_hand(Chips::new(5), Chips::new(10)).is_err()); // One hand per release
+        controller.set_hand_for_hand(false);
+        controller.start_hand(Chips::new(5), Chips::new(10)).unwrap();
+        assert_eq!(controller.players_remaining(), 2);
+    }
+
+    #[test]
+    fn test_double_board_splits_the_pot() {
+        use crate::card_dealer::parse_cards;
+        let mut controller = controller_with_players();
+        controller.set_variant(GameVariant::DoubleBoardHoldEm).unwrap();
+        // Each street is dealt to the first board, then to the second
+        controller.stack_deck(parse_cards("Ah Ad Kc Kd Qs Jh 9h Ks 7d 2c 8c 5h 3s 4s").unwrap()).unwrap();
+        controller.start_hand(Chips::new(5), Chips::new(10)).unwrap();
+        assert!(controller.set_variant(GameVariant::HoldEm).is_err());
+        controller.act("1", PlayerAction::Raise(Chips::new(990))).unwrap();
+        controller.act("2", PlayerAction::Call).unwrap();
+
+        let log = controller.hand_log().unwrap();
+        assert_eq!(log.board, parse_cards("Qs Jh 9h 8c 3s").unwrap());
+        assert_eq!(log.extra_boards, vec![parse_cards("Ks 7d 2c 5h 4s").unwrap()]);
+        assert_eq!(stacks(&controller), vec![1000, 1000]); // Aces take the first board, trip kings the second
+        let awarded: Vec<_> = controller.take_events().into_iter()
+            .filter_map(|event| match event {
+                GameEvent::PotAwarded { amount, winners, .. } => Some((amount, winners)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(awarded, vec![(Chips::new(1000), vec!["1".into()]), (Chips::new(1000), vec!["2".into()])]);
+        assert!(controller.hand_log().unwrap().eliminations.is_empty());
+        assert_eq!(controller.verify_invariants(), Ok(()));
+    }
+
+    #[test]
+    fn test_all_in_players_agree_to_run_it_twice() {
+        use crate::card_dealer::parse_cards;
+        let mut controller = controller_with_players();
+        controller.set_max_runs(3).unwrap();
+        // Each street is dealt to the first run, then to the second
+        controller.stack_deck(parse_cards("Ah Ad Kc Kd Qs Jh 9h Ks 7d 2c 8c 5h 3s 4s").unwrap()).unwrap();
+        controller.start_hand(Chips::new(5), Chips::new(10)).unwrap();
+        assert!(matches!(controller.choose_runs("1", 2), Err(GameError::InvalidState(_))));
+        controller.act("1", PlayerAction::Raise(Chips::new(990))).unwrap();
+        controller.act("2", PlayerAction::Call).unwrap();
+
+        assert!(controller.is_hand_in_progress() && controller.get_community_cards().is_empty());
+        assert_eq!(controller.runout_vote().unwrap().max_runs, 3);
+        assert!(matches!(controller.choose_runs("1", 4), Err(GameError::InvalidRequest(_))));
+        controller.choose_runs("1", 3).unwrap();
+        assert!(matches!(controller.choose_runs("1", 2), Err(GameError::InvalidState(_))));
+        controller.choose_runs("2", 2).unwrap(); // The fewest runs anyone chose
+
+        let log = controller.hand_log().unwrap();
+        assert_eq!(log.board, parse_cards("Qs Jh 9h 8c 3s").unwrap());
+        assert_eq!(log.extra_boards, vec![parse_cards("Ks 7d 2c 5h 4s").unwrap()]);
+        assert_eq!(stacks(&controller), vec![1000, 1000]); // Aces take the first run, trip kings the second
+        let Some(GameEvent::RunoutResults { runouts, .. }) = controller.take_events().into_iter().find(|event| event.kind() == "runout_results") else {
+            panic!("no runout results");
+        };
+        assert_eq!(runouts.len(), 2);
+        assert_eq!(runouts[1].board, parse_cards("Ks 7d 2c 5h 4s").unwrap());
+        assert_eq!(runouts[0].winners, vec![vec![PlayerId::from("1")]]);
+        assert_eq!(runouts[1].payouts, vec![("2".into(), Chips::new(1000))]);
+        assert_eq!(controller.verify_invariants(), Ok(()));
+    }
+
+    #[test]
+    fn test_insured_favourite_is_paid_when_outdrawn() {
+        use crate::card_dealer::parse_cards;
+        let mut controller = GameController::with_rng(StdRng::seed_from_u64(1));
+        controller.initialize_players(vec![
+            ("1".to_string(), "Alice".to_string(), 0, Chips::new(1000)),
+            ("2".to_string(), "Bob".to_string(), 1, Chips::new(2000)), // Covers Alice, with chips behind for the premium
+        ]);
+        controller.set_insurance(true);
+        controller.stack_deck(parse_cards("Ah Kh Qc Qd 2h 7h Qs 3c 8h").unwrap()).unwrap();
+        controller.start_hand(Chips::new(5), Chips::new(10)).unwrap();
+        controller.act("1", PlayerAction::Call).unwrap();
+        controller.act("2", PlayerAction::Check).unwrap();
+        controller.act("2", PlayerAction::Bet(Chips::new(990))).unwrap();
+        controller.take_events();
+        controller.act("1", PlayerAction::Call).unwrap();
+
+        // Set of queens against the nut flush draw, priced before the turn
+        let offer = controller.insurance_offer().unwrap().clone();
+        assert_eq!((offer.player_id.as_str(), offer.coverage), ("2", Chips::new(2000)));
+        assert!(offer.equity > 0.5 && offer.equity < 1.0, "{offer:?}");
+        assert_eq!(offer.premium, Chips::new((2000.0 * (1.0 - offer.equity)).ceil() as u64));
+        assert_eq!(controller.get_community_cards().len(), 3);
+        assert!(matches!(controller.buy_insurance("1", true), Err(GameError::NotAllowed(_))));
+        controller.buy_insurance("2", true).unwrap();
+        assert!(matches!(controller.buy_insurance("2", false), Err(GameError::InvalidState(_))));
+
+        // The river makes the flush; the insurance pays the pot back
+        assert_eq!(stacks(&controller), vec![2000, 3000 - offer.premium.get()]);
+        let insurer = controller.ledger().balance(&Account::Insurer);
+        assert_eq!(insurer, i128::from(offer.premium.get()) - 2000);
+        assert!(controller.take_events().iter().any(|event| event.kind() == "insurance_settled"));
+        assert_eq!(controller.verify_invariants(), Ok(()));
+    }
+
+    #[test]
+    fn test_insurance_is_refused_when_the_stack_cannot_pay_the_premium() {
+        use crate::card_dealer::parse_cards;
+        let mut controller = controller_with_players();
+        controller.set_insurance(true);
+        controller.stack_deck(parse_cards("Ah Kh Qc Qd 2h 7h Qs 3c 8h").unwrap()).unwrap();
+        controller.start_hand(Chips::new(5), Chips::new(10)).unwrap();
+        controller.act("1", PlayerAction::Call).unwrap();
+        controller.act("2", PlayerAction::Check).unwrap();
+        controller.act("2", PlayerAction::Bet(Chips::new(990))).unwrap();
+        controller.act("1", PlayerAction::Call).unwrap();
+
+        // Bob is all in, so nothing is left to pay the premium with
+        assert!(controller.insurance_offer().unwrap().premium > Chips::ZERO);
+        assert_eq!(controller.buy_insurance("2", true), Err(GameError::InsufficientChips));
+        assert_eq!(stacks(&controller), vec![0, 0]);
+        assert_eq!(controller.ledger().balance(&Account::Insurer), 0);
+        assert_eq!(controller.insurance_offer().unwrap().bought, None);
+
+        // The offer stands until declined; the river then makes Alice's flush
+        controller.buy_insurance("2", false).unwrap();
+        assert_eq!(stacks(&controller), vec![2000, 0]);
+        assert_eq!(controller.verify_invariants(), Ok(()));
+    }
+
+    #[test]
+    fn test_timed_out_players_check_or_fold() {
+        let mut controller = controller_with_players();
+        controller.start_hand(Chips::new(5), Chips::new(10)).unwrap();
+        assert!(matches!(controller.act_on_timeout("2"), Err(GameError::InvalidState(_))));
+        controller.act("1", PlayerAction::Call).unwrap();
+
+        // Nothing owed, so the big blind checks its option
+        controller.act_on_timeout("2").unwrap();
+        assert_eq!(controller.betting().unwrap().street, Street::Flop);
+        controller.act("2", PlayerAction::Bet(Chips::new(10))).unwrap();
+        controller.act_on_timeout("1").unwrap();
+        assert!(!controller.is_hand_in_progress());
+        assert_eq!(stacks(&controller), vec![990, 1010]);
+        let timed_out: Vec<_> = controller.take_events().into_iter()
+            .filter_map(|event| match event {
+                GameEvent::ActionTimedOut { player_id, action, .. } => Some((player_id, action)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(timed_out, vec![(PlayerId::from("2"), PlayerAction::Check), (PlayerId::from("1"), PlayerAction::Fold)]);
+    }
+
+    #[test]
+    fn test_rabbit_hunt_shows_the_cards_left_in_the_deck() {
+        use crate::card_dealer::parse_cards;
+        let mut controller = controller_with_players();
+        controller.stack_deck(parse_cards("Ah Kh Qc Qd 2h 7h Qs 3c 8h").unwrap()).unwrap();
+        controller.start_hand(Chips::new(5), Chips::new(10)).unwrap();
+        assert!(matches!(controller.rabbit_hunt("1"), Err(GameError::InvalidState(_))));
+        controller.act("1", PlayerAction::Fold).unwrap();
+        controller.take_deal_records();
+
+        assert_eq!(controller.rabbit_hunt("1").unwrap(), parse_cards("2h 7h Qs 3c 8h").unwrap());
+        assert!(matches!(controller.rabbit_hunt("1"), Err(GameError::NotAllowed(_))));
+        assert_eq!(controller.rabbit_hunt("2").unwrap().len(), 5);
+        assert_eq!(controller.take_deal_records().len(), 2); // Recorded as looked at, not dealt
+        assert_eq!(controller.verify_invariants(), Ok(()));
+
+        // Once an orbit: two hands at a table of two
+        for _ in 0..2 {
+            controller.start_hand(Chips::new(5), Chips::new(10)).unwrap();
+            let next = controller.next_to_act().unwrap().to_string();
+            controller.act(&next, PlayerAction::Fold).unwrap();
+            assert_eq!(controller.rabbit_hunt("1").is_ok(), controller.hand_number() == 3);
+        }
+    }
+
+    #[test]
+    fn test_uncalled_winner_may_show_one_card() {
+        use crate::card_dealer::parse_cards;
+        let mut controller = controller_with_players();
+        controller.stack_deck(parse_cards("Ah Kh Qc Qd").unwrap()).unwrap();
+        controller.start_hand(Chips::new(5), Chips::new(10)).unwrap();
+        controller.act("1", PlayerAction::Fold).unwrap();
+        controller.take_finished_hands();
+        controller.take_events();
+
+        assert!(matches!(controller.show_cards("1", &parse_cards("Ah").unwrap()), Err(GameError::NotAllowed(_))));
+        assert!(matches!(controller.show_cards("2", &parse_cards("Ah").unwrap()), Err(GameError::InvalidRequest(_))));
+        controller.show_cards("2", &parse_cards("Qd").unwrap()).unwrap();
+        assert!(matches!(controller.show_cards("2", &parse_cards("Qc Qd").unwrap()), Err(GameError::InvalidState(_))));
+        assert!(controller.take_events().contains(&GameEvent::CardsShown { hand_number: 1, player_id: "2".into(), cards: parse_cards("Qd").unwrap() }));
+        let logs = controller.take_finished_hands();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].shown, vec![(PlayerId::from("2"), parse_cards("Qd").unwrap())]);
+        assert!(matches!(logs[0].events().last(), Some(GameEvent::CardsShown { .. })));
+    }
+
+    #[test]
+    fn test_deal_records_cover_every_card_dealt() {
+        use crate::card_dealer::parse_cards;
+        let mut controller = controller_with_players();
+        controller.stack_deck(parse_cards("Ah Ad Kc Kd Qs Jh 9h").unwrap()).unwrap();
+        controller.start_hand(Chips::new(5), Chips::new(10)).unwrap();
+        controller.act("1", PlayerAction::Call).unwrap();
+        controller.act("2", PlayerAction::Check).unwrap();
+
+        let records = controller.take_deal_records();
+        assert!(matches!(&records[0], DealRecord::DeckShuffled { hand_number: 1, deck } if deck.len() == 52));
+        assert_eq!(records[1..], [
+            DealRecord::HoleCards { hand_number: 1, player_id: "1".into(), cards: parse_cards("Ah Ad").unwrap() },
+            DealRecord::HoleCards { hand_number: 1, player_id: "2".into(), cards: parse_cards("Kc Kd").unwrap() },
+            DealRecord::CommunityCards { hand_number: 1, board: 1, cards: parse_cards("Qs Jh 9h").unwrap() },
+        ]);
+        assert!(controller.take_deal_records().is_empty());
+    }
+
+    #[test]
+    fn test_hands_from_a_continuously_shuffled_shoe() {
+        let mut controller = controller_with_players();
+        controller.set_decks(2).unwrap();
+        controller.set_continuous_shuffle(true);
+        for _ in 0..5 {
+            controller.start_hand(Chips::new(5), Chips::new(10)).unwrap();
+            assert!(controller.set_decks(1).is_err());
+            controller.cancel_hand();
+        }
+        assert_eq!(controller.deck.decks(), 2);
+        assert_eq!(controller.verify_invariants(), Ok(()));
+    }
+
+    #[test]
+    fn test_cards_dealt_twice_from_a_shoe_play_at_showdown() {
+        use crate::card_dealer::parse_cards;
+        let mut controller = controller_with_players();
+        controller.set_decks(2).unwrap();
+        assert!(matches!(controller.stack_deck(parse_cards("Ah Ah Ah").unwrap()), Err(GameError::DuplicateCard(_))));
+
+        // Alice's ace and king are both on the board too: four different
+        // cards, but two pair counting each copy
+        controller.stack_deck(parse_cards("Ah Kd 9c 8c Ah Kd 7s 7s 2c").unwrap()).unwrap();
+        controller.start_hand(Chips::new(5), Chips::new(10)).unwrap();
+        controller.act("1", PlayerAction::Call).unwrap();
+        controller.act("2", PlayerAction::Check).unwrap();
+        for _ in 0..3 {
+            controller.act("2", PlayerAction::Check).unwrap();
+            controller.act("1", PlayerAction::Check).unwrap();
+        }
+        assert_eq!(controller.find_player("1").unwrap().hand_strength, Some(HandRank::TwoPair));
+        assert_eq!(stacks(&controller), vec![1010, 990]);
+        assert_eq!(controller.verify_invariants(), Ok(()));
+
+        // In Omaha her ace of hearts and deuce make a full house with the board's
+        controller.set_variant(GameVariant::Omaha).unwrap();
+        controller.stack_deck(parse_cards("Ah 2d Kc Qc Js Jh 9d 8d Ah Ad 2s 5c 6h").unwrap()).unwrap();
+        controller.start_hand(Chips::new(5), Chips::new(10)).unwrap();
+        controller.act("2", PlayerAction::Call).unwrap();
+        controller.act("1", PlayerAction::Check).unwrap();
+        for _ in 0..3 {
+            controller.act("1", PlayerAction::Check).unwrap();
+            controller.act("2", PlayerAction::Check).unwrap();
+        }
+        assert_eq!(controller.find_player("1").unwrap().hand_strength, Some(HandRank::FullHouse));
+        assert_eq!(stacks(&controller), vec![1020, 980]);
+        assert_eq!(controller.verify_invariants(), Ok(()));
+    }
+
+    #[test]
+    fn test_bad_beat_jackpot() {
+        use crate::card_dealer::parse_cards;
+        let mut controller = controller_with_players();
+        let rules = JackpotRules { drop: Chips::new(100), ..JackpotRules::default() };
+        controller.set_jackpot_rules(Some(rules)).unwrap();
+        controller.stack_deck(parse_cards("Ah Ad Qh Kh As Ac 9h Th Jh").unwrap()).unwrap();
+        controller.start_hand(Chips::new(5), Chips::new(10)).unwrap();
+        controller.act("1", PlayerAction::Raise(Chips::new(990))).unwrap();
+        controller.act("2", PlayerAction::Call).unwrap();
+
+        // Quad aces lose to a straight flush. The drop funds the jackpot the
+        // same hand; heads up, the table's share stays in the pool.
+        assert_eq!(stacks(&controller), vec![50, 1900 + 25]);
+        assert_eq!(controller.jackpot().unwrap().pool(), Chips::new(25));
+        let hit = controller.take_events().into_iter().find(|event| matches!(event, GameEvent::BadBeatJackpot { .. }));
+        assert_eq!(hit, Some(GameEvent::BadBeatJackpot {
+            hand_number: 1,
+            losing_hand: HandRank::FourOfAKind,
+            payouts: vec![("1".into(), Chips::new(50)), ("2".into(), Chips::new(25))],
+            pool: Chips::new(25),
+        }));
+        assert_eq!(controller.verify_invariants(), Ok(()));
+    }
+
+    #[test]
+    fn test_bets_are_tracked_street_by_street() {
+        use crate::card_dealer::parse_cards;
+        let mut controller = controller_with_players();
+        let rules = JackpotRules { drop: Chips::new(5), min_pot: Chips::new(1), no_flop_no_drop: true, ..JackpotRules::default() };
+        controller.set_jackpot_rules(Some(rules)).unwrap();
+        controller.stack_deck(parse_cards("Ah Ad Kc Kd 2c 7h 9s Jd 3c").unwrap()).unwrap();
+        controller.start_hand(Chips::new(5), Chips::new(10)).unwrap();
+        controller.act("1", PlayerAction::Raise(Chips::new(20))).unwrap();
+        controller.act("2", PlayerAction::Call).unwrap();
+        controller.act("2", PlayerAction::Bet(Chips::new(40))).unwrap();
+        controller.act("1", PlayerAction::Call).unwrap();
+        let flop = &controller.street_bets()[1];
+        assert_eq!((flop.street, flop.total(), flop.bet("1")), (Street::Flop, Chips::new(80), Chips::new(40)));
+        assert_eq!(controller.verify_invariants(), Ok(()));
+        controller.act("2", PlayerAction::Bet(Chips::new(100))).unwrap();
+        controller.act("1", PlayerAction::Fold).unwrap();
+
+        let streets = &controller.hand_log().unwrap().streets;
+        assert_eq!(streets.iter().map(|street| (street.street, street.total())).collect::<Vec<_>>(), vec![
+            (Street::Preflop, Chips::new(60)),
+            (Street::Flop, Chips::new(80)),
+            (Street::Turn, Chips::new(100)),
+        ]);
+        assert_eq!(streets[0].bets, vec![("1".into(), Chips::new(30)), ("2".into(), Chips::new(30))]);
+        assert_eq!(controller.jackpot().unwrap().pool(), Chips::new(5)); // Saw a flop
+
+        // No flop, no drop
+        controller.start_hand(Chips::new(5), Chips::new(10)).unwrap();
+        let next = controller.next_to_act().unwrap().to_string();
+        controller.act(&next, PlayerAction::Fold).unwrap();
+        assert_eq!(controller.street_bets().len(), 1);
+        assert_eq!(controller.jackpot().unwrap().pool(), Chips::new(5));
+    }
+
+    #[test]
+    fn test_seven_deuce_bonus() {
+        use crate::card_dealer::parse_cards;
+        let mut controller = controller_with_players();
+        controller.set_seven_deuce_bonus(Some(Chips::new(50)));
+        controller.stack_deck(parse_cards("7h 2c Ah Ad").unwrap()).unwrap();
+        controller.start_hand(Chips::new(5), Chips::new(10)).unwrap();
+        controller.act("1", PlayerAction::Raise(Chips::new(20))).unwrap();
+        controller.act("2", PlayerAction::Fold).unwrap();
+
+        // Bob's big blind, then the bonus
+        assert_eq!(stacks(&controller), vec![1010 + 50, 990 - 50]);
+        let bonus = controller.take_events().into_iter().find(|event| matches!(event, GameEvent::SevenDeuceBonus { .. }));
+        assert_eq!(bonus, Some(GameEvent::SevenDeuceBonus { hand_number: 1, player_id: "1".into(), amount: Chips::new(50) }));
+        assert_eq!(controller.verify_invariants(), Ok(()));
+
+        assert!(is_seven_deuce(&parse_cards("2d 7s").unwrap()));
+        assert!(!is_seven_deuce(&parse_cards("7d 2d").unwrap())); // Suited doesn't count
+    }
+
+    #[test]
+    fn test_joker_wins_with_five_of_a_kind() {
+        use crate::card_dealer::parse_cards;
+        let mut controller = controller_with_players();
+        controller.set_wild_cards(WildCards::Joker).unwrap();
+        assert_eq!(controller.deck.remaining(), 53);
+        controller.stack_deck(parse_cards("Jk Kd Ah As Kc Ks Kh 7d 2c").unwrap()).unwrap();
+        controller.start_hand(Chips::new(5), Chips::new(10)).unwrap();
+        controller.act("1", PlayerAction::Raise(Chips::new(990))).unwrap();
+        controller.act("2", PlayerAction::Call).unwrap();
+
+        let alice = &controller.get_players()[0];
+        assert_eq!(alice.hand_strength, Some(HandRank::FiveOfAKind));
+        assert_eq!(stacks(&controller), vec![2000, 0]);
+        assert_eq!(controller.verify_invariants(), Ok(()));
+
+        controller.set_wild_cards(WildCards::None).unwrap();
+        assert_eq!(controller.deck.remaining(), 52); // The joker is out of the deck again
+    }
+
+    #[test]
+    fn test_courchevel_shows_a_flop_card_and_plays_two_hole_cards() {
+        use crate::card_dealer::parse_cards;
+        let mut controller = controller_with_players();
+        controller.set_variant(GameVariant::Courchevel).unwrap();
+        controller.stack_deck(parse_cards("Ah Kh 2c 3d 4s Qs Qd Jc Jd Tc 9h 5h 8h 7h 6c").unwrap()).unwrap();
+        controller.start_hand(Chips::new(5), Chips::new(10)).unwrap();
+        assert_eq!(controller.get_players()[0].hole_cards.len(), 5);
+        assert_eq!(controller.get_community_cards(), &parse_cards("9h").unwrap()); // Shown before the preflop betting
+        controller.act("1", PlayerAction::Raise(Chips::new(990))).unwrap();
+        controller.act("2", PlayerAction::Call).unwrap();
+
+        // Alice's two hearts make a flush with three from the board, beating Bob's jack-high straight
+        assert_eq!(controller.get_community_cards(), &parse_cards("9h 5h 8h 7h 6c").unwrap());
+        assert_eq!(controller.get_players()[0].hand_strength, Some(HandRank::Flush));
+        assert_eq!(stacks(&controller), vec![2000, 0]);
+        assert_eq!(controller.verify_invariants(), Ok(()));
+    }
+
+    #[test]
+    fn test_events_are_taken_once() {
+        let mut controller = controller_with_players();
+        controller.pause();
+        controller.resume();
+        controller.deal_hole_cards().unwrap();
+
+        let kinds: Vec<_> = controller.take_events().iter().map(GameEvent::kind).collect();
+        assert_eq!(kinds, vec!["table_paused", "table_resumed", "hand_started"]);
+        assert!(controller.take_events().is_empty());
+    }
+
+    fn stacks(controller: &GameController) -> Vec<u64> {
+        controller.get_players().iter().map(|player| player.chip_stack.get()).collect()
+    }
+
+    #[test]
+    fn test_fold_to_the_big_blind() {
+        let mut controller = controller_with_players();
+        controller.start_hand(Chips::new(5), Chips::new(10)).unwrap();
+        // Heads-up the button posts the small blind and acts first
+        assert_eq!(controller.next_to_act(), Some("1"));
+        assert!(matches!(controller.act("2", PlayerAction::Call), Err(GameError::InvalidState(_))));
+        assert!(matches!(controller.act("1", PlayerAction::Check), Err(GameError::InvalidRequest(_))));
+
+        controller.act("1", PlayerAction::Fold).unwrap();
+        assert!(!controller.is_hand_in_progress());
+        assert_eq!(stacks(&controller), vec![995, 1005]);
+
+        // The button moves for the next hand
+        controller.start_hand(Chips::new(5), Chips::new(10)).unwrap();
+        assert_eq!(controller.next_to_act(), Some("2"));
+    }
+
+    #[test]
+    fn test_new_hand_starts_clean() {
+        let mut controller = controller_with_players();
+        controller.start_hand(Chips::new(5), Chips::new(10)).unwrap();
+        controller.act("1", PlayerAction::Call).unwrap();
+        controller.act("2", PlayerAction::Check).unwrap();
+        for _ in 0..3 {
+            controller.act("2", PlayerAction::Check).unwrap();
+            controller.act("1", PlayerAction::Check).unwrap();
+        }
+        assert!(controller.get_players().iter().all(|player| player.best_hand.is_some()));
+
+        // Blinds are posted before the cards come out, and nothing is left from the showdown
+        controller.start_hand(Chips::new(5), Chips::new(10)).unwrap();
+        assert_eq!(controller.get_table().total_committed(), Chips::new(15));
+        for player in controller.get_players() {
+            assert!(player.best_hand.is_none() && player.hand_strength.is_none());
+            assert_eq!(player.hole_cards.len(), 2);
+            assert_eq!(player.action_history.len(), 1); // Just the blind
+        }
+    }
+
+    #[test]
+    fn test_heads_up_once_a_player_leaves() {
+        let mut controller = controller_with_players();
+        controller.seat_bot("3", "Cal", Chips::new(1000), Box::new(crate::bots::CallingStation)).unwrap();
+        controller.start_hand(Chips::new(5), Chips::new(10)).unwrap();
+        assert!(!controller.betting().unwrap().is_heads_up());
+        assert_eq!(controller.next_to_act(), Some("1")); // Three-handed the button is under the gun
+        controller.act("1", PlayerAction::Fold).unwrap();
+        controller.act("2", PlayerAction::Fold).unwrap();
+        controller.remove_player("3").unwrap();
+
+        controller.start_hand(Chips::new(5), Chips::new(10)).unwrap();
+        let betting = controller.betting().unwrap();
+        assert!(betting.is_heads_up());
+        let (small, big) = betting.blinds();
+        let (small, big) = (small.to_string(), big.to_string());
+        assert_eq!(betting.seats[0], small); // The button posts the small blind...
+        assert_eq!(controller.next_to_act(), Some(small.as_str())); // ...and acts first preflop
+        controller.act(&small, PlayerAction::Call).unwrap();
+        controller.act(&big, PlayerAction::Check).unwrap();
+        assert_eq!(controller.next_to_act(), Some(big.as_str())); // But last after the flop
+    }
+
+    #[test]
+    fn test_hand_checked_down_to_showdown() {
+        let mut controller = controller_with_players();
+        controller.start_hand(Chips::new(5), Chips::new(10)).unwrap();
+        controller.act("1", PlayerAction::Call).unwrap();
+        controller.act("2", PlayerAction::Check).unwrap();
+        for street in 1..=3 {
+            assert_eq!(controller.get_community_cards().len(), street + 2);
+            controller.act("2", PlayerAction::Check).unwrap();
+            controller.act("1", PlayerAction::Check).unwrap();
+        }
+        assert_eq!(controller.next_to_act(), None);
+        assert!(!controller.is_hand_in_progress());
+        assert_eq!(stacks(&controller).iter().sum::<u64>(), 2000);
+        let kinds: Vec<_> = controller.take_events().iter().map(GameEvent::kind).collect();
+        assert_eq!(kinds.iter().filter(|kind| **kind == "board_dealt").count(), 3);
+        assert!(kinds.contains(&"pot_awarded"));
+    }
+
+    #[test]
+    fn test_all_in_runs_out_the_board() {
+        let mut controller = controller_with_players();
+        controller.start_hand(Chips::new(5), Chips::new(10)).unwrap();
+        assert!(matches!(controller.act("1", PlayerAction::Raise(Chips::new(2))), Err(GameError::InvalidRequest(_))));
+        assert_eq!(controller.act("1", PlayerAction::Raise(Chips::new(1000))), Err(GameError::InsufficientChips));
+        controller.act("1", PlayerAction::Raise(Chips::new(990))).unwrap();
+        assert_eq!(controller.betting().unwrap().owed("2"), Chips::new(990));
+        controller.act("2", PlayerAction::Call).unwrap();
+
+        assert_eq!(controller.get_community_cards().len(), 5);
+        assert!(!controller.is_hand_in_progress());
+        let stacks = stacks(&controller);
+        assert_eq!(stacks.iter().sum::<u64>(), 2000);
+        assert!(stacks.contains(&2000) || stacks == vec![1000, 1000]);
+    }
+
+    #[test]
+    fn test_all_in_results_count_at_equity() {
+        use crate::card_dealer::parse_cards;
+        let mut controller = controller_with_players();
+        controller.stack_deck(parse_cards("Ah Ad Kc Kd Ks 7d 2c 9s 3h").unwrap()).unwrap();
+        controller.start_hand(Chips::new(5), Chips::new(10)).unwrap();
+        controller.act("1", PlayerAction::Raise(Chips::new(990))).unwrap();
+        controller.take_events();
+        controller.act("2", PlayerAction::Call).unwrap();
+        assert_eq!(stacks(&controller), vec![0, 2000]); // Kings hit a set
+
+        let Some(GameEvent::AllInEquity { equities, .. }) = controller.take_events().into_iter().find(|event| event.kind() == "all_in_equity") else {
+            panic!("no all-in equity recorded");
+        };
+        assert!(equities[0].1 > 0.75 && (equities[0].1 + equities[1].1 - 1.0).abs() < 1e-9, "{equities:?}");
+        let log = controller.hand_log().unwrap();
+        assert_eq!((log.all_in[0].won, log.all_in[1].won), (Chips::ZERO, Chips::new(2000)));
+
+        let (alice, bob) = (controller.find_player("1").unwrap(), controller.find_player("2").unwrap());
+        assert_eq!((alice.chips_won, bob.chips_won), (-1000, 1000));
+        assert!((alice.ev_chips_won - (2000.0 * equities[0].1 - 1000.0)).abs() < 1e-6);
+        assert!((alice.ev_chips_won + bob.ev_chips_won).abs() < 1e-6);
+
+        // Without an all-in the results are the chips themselves
+        let luck = |controller: &GameController| controller.get_players().iter().map(|player| player.ev_chips_won - player.chips_won as f64).collect::<Vec<_>>();
+        let before = luck(&controller);
+        controller.award_chips("1", Chips::new(100)).unwrap();
+        controller.start_hand(Chips::new(5), Chips::new(10)).unwrap();
+        let next = controller.next_to_act().unwrap().to_string();
+        controller.act(&next, PlayerAction::Fold).unwrap();
+        assert_ne!(controller.find_player("2").unwrap().chips_won, 1000);
+        assert_eq!(luck(&controller), before);
+    }
+
+    #[test]
+    fn test_hints_only_at_training_tables() {
+        use crate::card_dealer::parse_cards;
+        let mut controller = controller_with_players();
+        controller.stack_deck(parse_cards("Kh 6h 7c 2d").unwrap()).unwrap();
+        controller.start_hand(Chips::new(5), Chips::new(10)).unwrap();
+        assert!(matches!(controller.hint("1"), Err(GameError::NotAllowed(_))));
+
+        controller.set_training_mode(true);
+        let hint = controller.hint("1").unwrap();
+        assert_eq!(hint.action, PlayerAction::Raise(Chips::new(20))); // K6s opens from the button
+        assert_eq!((hint.position, hint.pot_odds), (Position::Button, 5.0 / 20.0));
+        assert_eq!(controller.hint("1").unwrap(), hint);
+        assert!(matches!(controller.hint("2"), Err(GameError::InvalidState(_))));
+
+        controller.set_preflop_chart("button.open = AA".parse().unwrap());
+        assert_eq!(controller.hint("1").unwrap().action, PlayerAction::Fold);
+    }
+
+    #[test]
+    fn test_invariants_hold_through_a_hand() {
+        let mut controller = controller_with_players();
+        controller.start_hand(Chips::new(5), Chips::new(10)).unwrap();
+        assert_eq!(controller.verify_invariants(), Ok(()));
+        controller.act("1", PlayerAction::Raise(Chips::new(20))).unwrap();
+        controller.act("2", PlayerAction::Call).unwrap();
+        assert_eq!(controller.verify_invariants(), Ok(()));
+
+        // Chips appearing from nowhere are caught
+        controller.players[0].chip_stack += Chips::new(7);
+        let violations = controller.verify_invariants().unwrap_err();
+        assert!(violations[0].contains("expected 2000"), "{violations:?}");
+        controller.players[0].chip_stack -= Chips::new(7);
+        controller.adjust_chips("2", 50).unwrap();
+        assert_eq!(controller.verify_invariants(), Ok(()));
+
+        controller.community_cards.push(controller.players[1].hole_cards[0]);
+        let violations = controller.verify_invariants().unwrap_err();
+        assert!(violations.iter().any(|violation| violation.contains("twice")));
+        assert!(violations.iter().any(|violation| violation.contains("Flop is being bet with 4")));
+    }
+
+    #[test]
+    fn test_ledger_records_every_chip_movement() {
+        let mut controller = controller_with_players();
+        controller.start_hand(Chips::new(5), Chips::new(10)).unwrap();
+        controller.act("1", PlayerAction::Raise(Chips::new(30))).unwrap();
+        controller.act("2", PlayerAction::Fold).unwrap();
+        controller.buy_chips("2", BuyInKind::BuyIn, Chips::new(10)).unwrap();
+        controller.remove_player("1").unwrap();
+
+        let moves: Vec<_> = controller.ledger().transactions()
+            .map(|transaction| (transaction.hand_number, transaction.kind, transaction.amount.get()))
+            .collect();
+        assert_eq!(moves, [
+            (0, TransactionKind::Seated, 1000),
+            (0, TransactionKind::Seated, 1000),
+            (1, TransactionKind::Bet, 5),
+            (1, TransactionKind::Bet, 10),
+            (1, TransactionKind::Bet, 35), // Raised by 30 over the big blind
+            (1, TransactionKind::PotAward, 50),
+            (1, TransactionKind::BuyIn, 10),
+            (1, TransactionKind::CashOut, 1010),
+        ]);
+        assert_eq!(controller.ledger().player_transactions("2").count(), 3);
+        assert_eq!(controller.ledger().balance(&Account::Player("2".into())), 1000);
+        assert_eq!(controller.verify_invariants(), Ok(()));
+
+        // A movement the stacks don't show is caught
+        controller.record(TransactionKind::Award, Account::Cashier, Account::Player("2".into()), Chips::new(7));
+        let violations = controller.verify_invariants().unwrap_err();
+        assert!(violations.iter().any(|violation| violation.contains("ledger of player 2 sums to 1007")), "{violations:?}");
+    }
+
+    #[test]
+    fn test_tracked_table_draws_on_the_bank() {
+        #[derive(Default)]
+        struct TestBank(std::sync::Mutex<HashMap<String, u64>>);
+        impl Bank for TestBank {
+            fn withdraw(&self, player_id: &str, amount: Chips) -> Result<(), GameError> {
+                let mut balances = self.0.lock().unwrap();
+                let balance = balances.entry(player_id.to_string()).or_default();
+                *balance = balance.checked_sub(amount.get()).ok_or(GameError::InsufficientChips)?;
+                Ok(())
+            }
+            fn deposit(&self, player_id: &str, amount: Chips) {
+                *self.0.lock().unwrap().entry(player_id.to_string()).or_default() += amount.get();
+            }
+        }
+
+        let mut controller = GameController::with_rng(StdRng::seed_from_u64(1));
+        assert!(matches!(controller.set_currency_mode(CurrencyMode::Tracked), Err(GameError::InvalidState(_))));
+        let bank = Arc::new(TestBank::default());
+        bank.deposit("1", Chips::new(1500));
+        controller.set_bank(bank.clone());
+        controller.set_currency_mode(CurrencyMode::Tracked).unwrap();
+
+        assert_eq!(controller.join_table("2", "Bob", Chips::new(500)), Err(GameError::InsufficientChips));
+        assert!(matches!(controller.seat_bot("bot", "Robo", Chips::new(500), Box::new(crate::bots::CallingStation)), Err(GameError::NotAllowed(_))));
+        controller.join_table("1", "Alice", Chips::new(1000)).unwrap();
+        assert!(matches!(controller.set_currency_mode(CurrencyMode::PlayMoney), Err(GameError::NotAllowed(_))));
+        controller.adjust_chips("1", -1000).unwrap();
+        assert_eq!(controller.buy_chips("1", BuyInKind::Rebuy, Chips::new(1000)), Err(GameError::InsufficientChips));
+        controller.buy_chips("1", BuyInKind::Rebuy, Chips::new(500)).unwrap();
+        assert_eq!(bank.0.lock().unwrap()["1"], 0);
+        controller.remove_player("1").unwrap();
+        assert_eq!(bank.0.lock().unwrap()["1"], 500);
+    }
+
+    #[test]
+    fn test_stacked_deck_deals_in_order() {
+        use crate::card_dealer::parse_cards;
+        let mut controller = controller_with_players();
+        controller.stack_deck(parse_cards("Ah Ad Kc 2d Qs Jh 9h 8c 3s").unwrap()).unwrap();
+        controller.start_hand(Chips::new(5), Chips::new(10)).unwrap();
+        assert_eq!(controller.get_players()[1].hole_cards, parse_cards("Kc 2d").unwrap());
+        controller.act("1", PlayerAction::Raise(Chips::new(990))).unwrap();
+        controller.act("2", PlayerAction::Call).unwrap();
+        assert_eq!(controller.get_community_cards(), &parse_cards("Qs Jh 9h 8c 3s").unwrap());
+        assert_eq!(stacks(&controller), vec![2000, 0]);
+
+        // Later hands are shuffled again
+        controller.initialize_players(vec![("1".to_string(), "Alice".to_string(), 0, Chips::new(1000)), ("2".to_string(), "Bob".to_string(), 1, Chips::new(1000))]);
+        controller.start_hand(Chips::new(5), Chips::new(10)).unwrap();
+        assert_ne!(controller.get_players()[0].hole_cards, parse_cards("Ah Ad").unwrap());
+    }
+
+    #[test]
+    fn test_knockout_pays_the_bounty() {
+        use crate::bounty::BountyFormat;
+        use crate::card_dealer::parse_cards;
+        let mut controller = controller_with_players();
+        controller.set_bounty_rules(Some(BountyRules { bounty: Chips::new(100), format: BountyFormat::Progressive }));
+        controller.stack_deck(parse_cards("Ah Ad Kc 2d Qs Jh 9h 8c 3s").unwrap()).unwrap();
+        controller.start_hand(Chips::new(5), Chips::new(10)).unwrap();
+        controller.act("1", PlayerAction::Raise(Chips::new(990))).unwrap();
+        controller.act("2", PlayerAction::Call).unwrap();
+
+        let eliminations = &controller.hand_log().unwrap().eliminations;
+        assert_eq!(eliminations, &vec![Elimination { player_id: "2".into(), eliminated_by: vec!["1".into()] }]);
+        // Progressive: half the bounty is paid, half goes onto the winner's head
+        let winner = controller.find_player("1").unwrap();
+        assert_eq!((winner.bounties_won, winner.bounty), (Chips::new(50), Chips::new(150)));
+        assert_eq!(controller.find_player("2").unwrap().bounty, Chips::ZERO);
+        assert!(controller.take_events().iter().any(|event| event.kind() == "bounty_awarded"));
+        assert_eq!(controller.verify_invariants(), Ok(())); // Bounties are prize money, not chips in play
+    }
+
+    #[test]
+    fn test_duplicate_cards_are_caught() {
+        use crate::card_dealer::parse_cards;
+        let mut controller = controller_with_players();
+        let ace = "Ah".parse().unwrap();
+        assert_eq!(controller.stack_deck(parse_cards("Ah Kd Ah").unwrap()), Err(GameError::DuplicateCard(ace)));
+
+        controller.deal_hole_cards().unwrap();
+        assert_eq!(controller.check_cards(), Ok(()));
+        let stolen = controller.deck.cards()[0];
+        controller.players[0].hole_cards[0] = stolen; // A card both in the deck and in a hand
+        assert_eq!(controller.check_cards(), Err(GameError::DuplicateCard(stolen)));
+        assert_eq!(controller.deal_community_cards(), Err(GameError::DuplicateCard(stolen)));
+    }
+
+    #[test]
+    fn test_action_required_carries_the_price() {
+        let mut controller = controller_with_players();
+        controller.start_hand(Chips::new(5), Chips::new(10)).unwrap();
+        let prompt = controller.take_events().pop();
+        assert_eq!(prompt, Some(GameEvent::ActionRequired {
+            hand_number: 1,
+            player_id: "1".into(),
+            to_call: Chips::new(5),
+            pot: Chips::new(15),
+            pot_odds: 0.25,
+            effective_stack: Chips::new(990), // Bob's stack behind his big blind
+            min_raise_to: Chips::new(20),
+        }));
+
+        controller.act("1", PlayerAction::Call).unwrap();
+        let prompt = controller.take_events().pop();
+        assert!(matches!(prompt, Some(GameEvent::ActionRequired { player_id, to_call, pot_odds, .. }) if player_id == "2" && to_call.is_zero() && pot_odds == 0.0));
+    }
+
+    #[test]
+    fn test_bots_act_on_their_turn() {
+        use crate::bots::{CallingStation, TightAggressive};
+        let mut controller = controller_with_players();
+        controller.seat_bot("bot", "Station", Chips::new(1000), Box::new(CallingStation)).unwrap();
+        assert!(controller.seat_bot("bot", "Again", Chips::new(1000), Box::new(TightAggressive)).is_err());
+        assert_eq!(controller.find_player("bot").unwrap().table_position, 2);
+
+        // The button is seat 0; the bot in the big blind checks behind the human players
+        controller.start_hand(Chips::new(5), Chips::new(10)).unwrap();
+        controller.act("1", PlayerAction::Call).unwrap();
+        controller.act("2", PlayerAction::Call).unwrap();
+        assert_eq!(controller.get_community_cards().len(), 3);
+        assert_eq!(controller.next_to_act(), Some("2"));
+        controller.act("2", PlayerAction::Bet(Chips::new(50))).unwrap();
+        assert_eq!(controller.betting().unwrap().street_bet("bot"), Chips::new(50));
+        assert_eq!(controller.next_to_act(), Some("1"));
+        let acted = controller.take_events().into_iter()
+            .filter(|event| matches!(event, GameEvent::PlayerActed { player_id, .. } if player_id == "bot"))
+            .count();
+        assert_eq!(acted, 2);
+    }
+
+    #[test]
+    fn test_big_blind_ante_is_dead_money() {
+        let mut controller = GameController::with_rng(StdRng::seed_from_u64(1));
+        controller.initialize_players(vec![
+            ("1".to_string(), "Alice".to_string(), 0, Chips::new(1000)),
+            ("2".to_string(), "Bob".to_string(), 1, Chips::new(1000)),
+            ("3".to_string(), "Carol".to_string(), 2, Chips::new(15)),
+        ]);
+        controller.set_big_blind_ante(Chips::new(10));
+        controller.start_hand(Chips::new(5), Chips::new(10)).unwrap();
+        // Carol covers the big blind first and antes the 5 chips left
+        assert_eq!(stacks(&controller), [1000, 995, 0]);
+        assert_eq!(controller.betting().unwrap().current_bet, Chips::new(10));
+        controller.act("1", PlayerAction::Raise(Chips::new(90))).unwrap();
+        controller.act("2", PlayerAction::Call).unwrap();
+
+        // The ante plays for the main pot without making Carol eligible for more of it
+        let pots: Vec<_> = controller.get_table().pots.iter().map(|pot| (pot.total.get(), pot.eligible_players.len())).collect();
+        assert_eq!(pots, [(35, 3), (180, 2)]);
+        let ante = controller.ledger().transactions().find(|transaction| transaction.kind == TransactionKind::Ante).unwrap();
+        assert_eq!((ante.from.clone(), ante.amount), (Account::Player("3".into()), Chips::new(5)));
+        assert_eq!(controller.hand_log().unwrap().ante, Chips::new(10));
+        assert_eq!(controller.verify_invariants(), Ok(()));
+    }
+
+    #[test]
+    fn test_rake_is_taken_from_called_chips_only() {
+        use crate::card_dealer::parse_cards;
+        let mut controller = controller_with_players();
+        controller.set_rake(RakeRules { percent: 5, cap: Some(Chips::new(3)), no_flop_no_drop: false }).unwrap();
+        controller.stack_deck(parse_cards("Ah Ad Kc Kd Qs Jh 9h 2c 3d").unwrap()).unwrap();
+        controller.start_hand(Chips::new(5), Chips::new(10)).unwrap();
+        controller.act("1", PlayerAction::Call).unwrap();
+        controller.act("2", PlayerAction::Check).unwrap();
+        controller.act("2", PlayerAction::Bet(Chips::new(40))).unwrap();
+        controller.act("1", PlayerAction::Call).unwrap();
+        for _ in 0..2 {
+            controller.act("2", PlayerAction::Check).unwrap();
+            controller.act("1", PlayerAction::Check).unwrap();
+        }
+
+        // 5% of 100 is 5, capped at 3
+        assert_eq!(stacks(&controller), vec![1047, 950]);
+        assert_eq!(controller.hand_log().unwrap().rake, Chips::new(3));
+        assert_eq!(controller.ledger().balance(&Account::House), 3);
+
+        // Bob's raise goes uncalled: only the 20 chips matched are raked
+        controller.start_hand(Chips::new(5), Chips::new(10)).unwrap();
+        controller.act("2", PlayerAction::Raise(Chips::new(90))).unwrap();
+        controller.act("1", PlayerAction::Fold).unwrap();
+        assert_eq!(controller.hand_log().unwrap().rake, Chips::new(1));
+        assert_eq!(stacks(&controller), vec![1037, 959]);
+        assert_eq!(controller.verify_invariants(), Ok(()));
+    }
+
+    #[test]
+    fn test_straddle_acts_last_preflop() {
+        let mut controller = GameController::with_rng(StdRng::seed_from_u64(1));
+        controller.initialize_players(vec![
+            ("1".to_string(), "Alice".to_string(), 0, Chips::new(1000)),
+            ("2".to_string(), "Bob".to_string(), 1, Chips::new(1000)),
+            ("3".to_string(), "Carol".to_string(), 2, Chips::new(1000)),
+        ]);
+        assert!(matches!(controller.request_straddle("1"), Err(GameError::NotAllowed(_))));
+        controller.set_straddles(true);
+        controller.request_straddle("1").unwrap();
+        controller.start_hand(Chips::new(5), Chips::new(10)).unwrap();
+
+        // Three-handed the button is under the gun
+        assert_eq!(stacks(&controller), vec![980, 995, 990]);
+        let betting = controller.betting().unwrap();
+        assert_eq!((betting.current_bet, betting.min_raise_to()), (Chips::new(20), Chips::new(40)));
+        assert_eq!(controller.next_to_act(), Some("2"));
+        controller.act("2", PlayerAction::Call).unwrap();
+        controller.act("3", PlayerAction::Call).unwrap();
+        assert_eq!(controller.next_to_act(), Some("1")); // The straddle's option
+        controller.act("1", PlayerAction::Check).unwrap();
+        assert_eq!(controller.betting().unwrap().street, Street::Flop);
+        assert_eq!(controller.hand_log().unwrap().straddle, Some((PlayerId::from("1"), Chips::new(20))));
+        assert!(controller.straddle_requests.is_empty()); // Only good for the one hand
+        assert_eq!(controller.verify_invariants(), Ok(()));
+    }
+}
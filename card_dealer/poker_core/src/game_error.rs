@@ -0,0 +1,46 @@
+use thiserror::Error;
+
+use crate::card_dealer::Card;
+
+/// Errors raised by the game engine.
+///
+/// The API layer maps each variant onto an HTTP status code, so pick the
+/// variant by what went wrong rather than by where it happened.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum GameError {
+    #[error("Invalid request: {0}")]
+    InvalidRequest(String),         // Malformed or out-of-range input (400)
+
+    #[error("Unauthorized: {0}")]
+    Unauthorized(String),           // Missing, invalid or expired credentials (401)
+
+    #[error("Action not allowed: {0}")]
+    NotAllowed(String),             // The caller may not perform this action (403)
+
+    #[error("Player {0} not found")]
+    PlayerNotFound(String),         // No player with this id (404)
+
+    #[error("Table {0} not found")]
+    TableNotFound(String),          // No table with this id (404)
+
+    #[error("Hand {0} not found")]
+    HandNotFound(u64),              // No hand with this number in the history kept (404)
+
+    #[error("No winner determined")]
+    NoWinner,                       // Nobody holds an evaluated hand (404)
+
+    #[error("Not enough cards to deal {0}.")]
+    NotEnoughCards(&'static str),   // The deck ran out (409)
+
+    #[error("Card {0} appears more than once")]
+    DuplicateCard(Card),            // The same physical card given or found twice (400)
+
+    #[error("Not enough chips to bet")]
+    InsufficientChips,              // The bet exceeds the player's stack (409)
+
+    #[error("Invalid game state: {0}")]
+    InvalidState(String),           // Action doesn't fit the current state of the hand (409)
+
+    #[error("Internal error: {0}")]
+    Internal(String),               // Server-side failure, e.g. a poisoned lock (500)
+}
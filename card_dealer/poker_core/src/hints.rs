@@ -0,0 +1,177 @@
+//! Hints for beginners: a reasonable action for the player whose turn it is,
+//! and a sentence on why, from the preflop chart before the flop and from
+//! estimated equity against the price of a call after it.
+
+use rand::Rng;
+
+use crate::betting::Street;
+use crate::bots::{hand_strength, ActionContext};
+use crate::card_dealer::Card;
+use crate::player::PlayerAction;
+use crate::preflop_chart::{ChartAdvice, Position, PreflopChart};
+
+/// Runouts dealt to estimate equity for a hint.
+const HINT_TRIALS: u32 = 1_000;
+
+/// Equity relative to an even share of the pot worth betting for value.
+const VALUE_BET_STRENGTH: f64 = 1.5;
+
+/// A suggested action and why.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct Hint {
+    pub action: PlayerAction,
+    pub rationale: String,
+    pub equity: Option<f64>,    // Estimated share of the pot the hand wins, after the flop
+    pub pot_odds: f64,          // Equity a call needs to break even, zero when nothing is owed
+    pub position: Position,
+}
+
+/// Suggests an action for the player described by `context`. Preflop hands
+/// follow `chart`; later streets bet strong hands, and call or fold the rest
+/// on equity against the pot odds.
+pub fn suggest<R: Rng + ?Sized>(context: &ActionContext, chart: &PreflopChart, rng: &mut R) -> Hint {
+    let (action, rationale, equity) = if context.street == Street::Preflop {
+        let (action, rationale) = preflop(context, chart);
+        (action, rationale, None)
+    } else {
+        let equity = hand_strength(&context.hole_cards, &context.board, context.opponents, HINT_TRIALS, rng);
+        let (action, rationale) = postflop(context, equity);
+        (action, rationale, Some(equity))
+    };
+    Hint { action, rationale, equity, pot_odds: context.pot_odds(), position: context.position }
+}
+
+fn preflop(context: &ActionContext, chart: &PreflopChart) -> (PlayerAction, String) {
+    let hand = shorthand(&context.hole_cards);
+    let seat = context.position.name().replace('_', " ");
+    let facing_raise = context.facing_raise();
+    match chart.advise(context.position, &context.hole_cards, facing_raise) {
+        ChartAdvice::Raise if facing_raise => (
+            context.raise_to(context.current_bet * 3),
+            format!("{hand} is strong enough to re-raise from the {seat}."),
+        ),
+        ChartAdvice::Raise => (
+            context.raise_to(context.current_bet.max(context.big_blind) * 3),
+            format!("{hand} is in the {seat}'s opening range: raise rather than limp."),
+        ),
+        ChartAdvice::Call => (
+            PlayerAction::Call,
+            format!("{hand} is good enough to call a raise from the {seat}, but not to re-raise."),
+        ),
+        ChartAdvice::Fold if context.to_call.is_zero() => (
+            PlayerAction::Check,
+            format!("{hand} isn't worth a raise from the {seat}, but checking is free."),
+        ),
+        ChartAdvice::Fold => (
+            PlayerAction::Fold,
+            format!("{hand} is too weak to play from the {seat}{}.", if facing_raise { " against a raise" } else { "" }),
+        ),
+    }
+}
+
+fn postflop(context: &ActionContext, equity: f64) -> (PlayerAction, String) {
+    let even_share = 1.0 / (context.opponents + 1) as f64;
+    let (equity_percent, odds_percent) = (percent(equity), percent(context.pot_odds()));
+    if equity / even_share >= VALUE_BET_STRENGTH {
+        return (
+            context.raise_to(context.current_bet + context.pot * 2 / 3),
+            format!("Your hand wins about {equity_percent}% of the time, well ahead of an even share: bet for value."),
+        );
+    }
+    if context.to_call.is_zero() {
+        return (
+            PlayerAction::Check,
+            format!("Your hand wins about {equity_percent}% of the time, not enough to bet: check and see a free card."),
+        );
+    }
+    if equity >= context.pot_odds() {
+        (
+            PlayerAction::Call,
+            format!("Calling needs {odds_percent}% equity and your hand has about {equity_percent}%: the price is right."),
+        )
+    } else {
+        (
+            PlayerAction::Fold,
+            format!("Calling needs {odds_percent}% equity but your hand has only about {equity_percent}%: fold."),
+        )
+    }
+}
+
+/// Two hole cards the way ranges write them, e.g. `K6s`, `AKo` or `QQ`.
+fn shorthand(hole_cards: &[Card]) -> String {
+    let [first, second] = hole_cards else { return "This hand".to_string() };
+    let (high, low) = if first.rank >= second.rank { (first, second) } else { (second, first) };
+    let suffix = match () {
+        _ if high.rank == low.rank => "",
+        _ if high.suit == low.suit => "s",
+        _ => "o",
+    };
+    format!("{}{}{suffix}", high.rank.letter(), low.rank.letter())
+}
+
+fn percent(share: f64) -> u32 {
+    (share * 100.0).round() as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use crate::card_dealer::parse_cards;
+    use crate::chips::Chips;
+
+    fn context(hole_cards: &str, board: &str, position: Position, current_bet: u64, to_call: u64, pot: u64) -> ActionContext {
+        ActionContext {
+            player_id: "1".into(),
+            hole_cards: parse_cards(hole_cards).unwrap(),
+            board: parse_cards(board).unwrap(),
+            street: if board.is_empty() { Street::Preflop } else { Street::Flop },
+            stack: Chips::new(1000),
+            to_call: Chips::new(to_call),
+            street_bet: Chips::new(current_bet - to_call),
+            current_bet: Chips::new(current_bet),
+            min_raise_to: Chips::new(current_bet * 2),
+            big_blind: Chips::new(10),
+            pot: Chips::new(pot),
+            effective_stack: Chips::new(1000),
+            opponents: 1,
+            players_behind: 0,
+            position,
+        }
+    }
+
+    fn hint(context: &ActionContext) -> Hint {
+        suggest(context, PreflopChart::builtin(), &mut StdRng::seed_from_u64(7))
+    }
+
+    #[test]
+    fn test_preflop_hints_follow_the_chart() {
+        let open = hint(&context("Kh 6h", "", Position::Button, 10, 5, 15));
+        assert_eq!((open.action, open.equity), (PlayerAction::Raise(Chips::new(20)), None));
+        assert!(open.rationale.contains("K6s") && open.rationale.contains("button"), "{}", open.rationale);
+
+        let free = hint(&context("7c 2d", "", Position::BigBlind, 10, 0, 20));
+        assert_eq!(free.action, PlayerAction::Check);
+        assert_eq!(hint(&context("7c 2d", "", Position::BigBlind, 30, 20, 45)).action, PlayerAction::Fold);
+        assert_eq!(hint(&context("9c 9d", "", Position::Cutoff, 30, 30, 45)).action, PlayerAction::Call);
+        assert!(hint(&context("7c 2d", "", Position::BigBlind, 30, 20, 45)).rationale.contains("big blind"));
+    }
+
+    #[test]
+    fn test_postflop_hints_weigh_equity_against_the_price() {
+        let nuts = hint(&context("Ah Kh", "Qh Jh Th", Position::Button, 0, 0, 100));
+        assert_eq!(nuts.action, PlayerAction::Bet(Chips::new(66)));
+        assert!(nuts.equity.unwrap() > 0.95);
+
+        // A gutshot getting a good price calls, and folds to an overbet
+        let cheap = hint(&context("9c 8d", "Qs Jh 2c", Position::Button, 10, 10, 200));
+        assert_eq!(cheap.action, PlayerAction::Call);
+        assert!(cheap.rationale.contains("5%"), "{}", cheap.rationale);
+        assert_eq!(hint(&context("9c 8d", "Qs Jh 2c", Position::Button, 1000, 1000, 200)).action, PlayerAction::Fold);
+        assert_eq!(hint(&context("9c 8d", "Qs Jh 2c", Position::Button, 0, 0, 200)).action, PlayerAction::Check);
+    }
+}
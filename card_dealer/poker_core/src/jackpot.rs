@@ -0,0 +1,123 @@
+//! Bad beat jackpot: a pool built from a drop taken out of every pot, paid
+//! out when a very strong hand loses at showdown.
+
+use crate::chips::Chips;
+use crate::poker_hand::HandRank;
+
+/// How the jackpot is funded and when it is hit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct JackpotRules {
+    pub drop: Chips,                // Taken from the main pot of each hand into the pool
+    pub min_pot: Chips,             // No drop from a main pot smaller than this
+    pub no_flop_no_drop: bool,      // No drop from a hand that ended before the flop
+    pub qualifying_hand: HandRank,  // The losing hand must be at least this strong
+    pub loser_share: u8,            // Percent of the pool paid to the player whose hand lost
+    pub winner_share: u8,           // Percent paid to the winner of the pot
+    pub table_share: u8,            // Percent split between everyone else dealt in; what's left seeds the next jackpot
+}
+
+impl Default for JackpotRules {
+    fn default() -> Self {
+        Self {
+            drop: Chips::new(1),
+            min_pot: Chips::new(20),
+            no_flop_no_drop: false,
+            qualifying_hand: HandRank::FourOfAKind,
+            loser_share: 50,
+            winner_share: 25,
+            table_share: 25,
+        }
+    }
+}
+
+impl JackpotRules {
+    /// Checks the shares add up to no more than the whole pool.
+    pub fn validate(&self) -> Result<(), String> {
+        let total = u32::from(self.loser_share) + u32::from(self.winner_share) + u32::from(self.table_share);
+        if total > 100 {
+            return Err(format!("Jackpot shares add up to {total}%, more than the whole pool"));
+        }
+        Ok(())
+    }
+}
+
+/// The jackpot pool at a table.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Jackpot {
+    pub rules: JackpotRules,
+    pool: Chips,
+}
+
+/// What a hit jackpot pays each player in the three groups it is shared
+/// between.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JackpotPayout {
+    pub per_loser: Chips,
+    pub per_winner: Chips,
+    pub per_table: Chips,
+}
+
+impl Jackpot {
+    pub fn new(rules: JackpotRules) -> Self {
+        Self { rules, pool: Chips::ZERO }
+    }
+
+    pub fn pool(&self) -> Chips {
+        self.pool
+    }
+
+    /// The drop owed by a main pot of `pot` chips.
+    pub fn drop_for(&self, pot: Chips) -> Chips {
+        if pot < self.rules.min_pot { Chips::ZERO } else { self.rules.drop.min(pot) }
+    }
+
+    pub(crate) fn add(&mut self, drop: Chips) {
+        self.pool += drop;
+    }
+
+    /// True if `losing_hand` losing at showdown hits the jackpot.
+    pub fn qualifies(&self, losing_hand: &HandRank) -> bool {
+        *losing_hand >= self.rules.qualifying_hand && !self.pool.is_zero()
+    }
+
+    /// Takes the shares out of the pool, each split evenly between the
+    /// players in its group. Odd chips, and the share of a group with nobody
+    /// in it, stay in the pool for the next jackpot.
+    pub(crate) fn hit(&mut self, losers: usize, winners: usize, table: usize) -> JackpotPayout {
+        let pool = self.pool;
+        let share = |percent: u8, players: usize| if players == 0 { Chips::ZERO } else { pool * u64::from(percent) / 100 / players as u64 };
+        let payout = JackpotPayout {
+            per_loser: share(self.rules.loser_share, losers),
+            per_winner: share(self.rules.winner_share, winners),
+            per_table: share(self.rules.table_share, table),
+        };
+        self.pool -= payout.per_loser * losers as u64 + payout.per_winner * winners as u64 + payout.per_table * table as u64;
+        payout
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drop_and_hit() {
+        let mut jackpot = Jackpot::new(JackpotRules { drop: Chips::new(5), ..JackpotRules::default() });
+        assert_eq!((jackpot.drop_for(Chips::new(19)), jackpot.drop_for(Chips::new(20))), (Chips::ZERO, Chips::new(5)));
+        assert!(!jackpot.qualifies(&HandRank::StraightFlush)); // Nothing to pay yet
+
+        jackpot.add(Chips::new(1001));
+        assert!(!jackpot.qualifies(&HandRank::FullHouse));
+        assert!(jackpot.qualifies(&HandRank::FourOfAKind));
+        let payout = jackpot.hit(1, 1, 3);
+        assert_eq!((payout.per_loser, payout.per_winner, payout.per_table), (Chips::new(500), Chips::new(250), Chips::new(83)));
+        assert_eq!(jackpot.pool(), Chips::new(2)); // Odd chips seed the next jackpot
+
+        let payout = jackpot.hit(1, 1, 0); // Heads up, the table share stays in the pool
+        assert_eq!((payout.per_loser, payout.per_table, jackpot.pool()), (Chips::new(1), Chips::ZERO, Chips::new(1)));
+
+        assert!(JackpotRules { table_share: 26, ..JackpotRules::default() }.validate().is_err());
+    }
+}
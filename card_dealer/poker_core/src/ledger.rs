@@ -0,0 +1,168 @@
+//! Every chip movement at a table, as transactions between accounts: the
+//! players' stacks, the pot, the jackpot pool, the house and the cashier, where chips
+//! come onto and leave the table. Transactions are only ever appended, so
+//! a player's history can be replayed, and `Ledger::reconcile` checks the
+//! balances they add up to against the table itself.
+
+use std::collections::{HashMap, VecDeque};
+
+use crate::chips::Chips;
+use crate::player_id::PlayerId;
+
+/// Transactions a ledger keeps; older ones are dropped, their effect kept in the balances.
+const LEDGER_LEN: usize = 10_000;
+
+/// Where chips are held.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(rename_all = "snake_case"))]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub enum Account {
+    Player(PlayerId), // A seated player's stack
+    Pot,              // Bets of the hand being played
+    Jackpot,          // The bad beat jackpot pool
+    Cashier,          // Outside the table: buy-ins come from here, cash-outs go back
+    Insurer,          // The house, which sells all-in insurance
+    House,            // The house's rake
+}
+
+/// Why chips moved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(rename_all = "snake_case"))]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub enum TransactionKind {
+    Seated,     // The stack a player sat down with
+    BuyIn,      // Bought in or topped up between hands
+    Rebuy,
+    AddOn,
+    Award,      // Given from outside the game, e.g. a fresh stack in a simulation
+    Adjustment, // Added or taken away by an admin
+    Bet,        // Put into the pot, blinds included
+    Ante,       // The big blind ante, dead money in the main pot
+    PotAward,   // Won from the pots
+    Refund,     // Bets handed back when a hand is cancelled
+    Rake,       // Taken out of the pots: the house's rake, or the jackpot drop
+    Jackpot,    // A share of the bad beat jackpot
+    Bonus,      // The seven-deuce bonus, paid by each player dealt in to its winner
+    CashOut,    // The stack a player took when leaving the table
+    InsurancePremium, // Paid for all-in insurance when it is bought
+    InsurancePayout,  // Paid by the insurer to an insured player who lost
+}
+
+/// One chip movement. Never changed once recorded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct Transaction {
+    pub id: u64,            // Counts from 1 in the order recorded
+    pub hand_number: u64,   // The hand being played, or the last one played between hands
+    pub kind: TransactionKind,
+    pub from: Account,
+    pub to: Account,
+    pub amount: Chips,
+}
+
+impl Transaction {
+    /// Whether the transaction moved chips into or out of `player_id`'s stack.
+    pub fn involves(&self, player_id: &str) -> bool {
+        [&self.from, &self.to].into_iter().any(|account| matches!(account, Account::Player(id) if **id == *player_id))
+    }
+}
+
+/// A table's transactions, oldest first, and every account's balance.
+#[derive(Debug, Default)]
+pub struct Ledger {
+    transactions: VecDeque<Transaction>,
+    next_id: u64,
+    balances: HashMap<Account, i128>, // Negative for accounts paying out more than they took in, such as the cashier
+}
+
+impl Ledger {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a transaction moving `amount` chips; moving none records nothing.
+    pub(crate) fn record(&mut self, hand_number: u64, kind: TransactionKind, from: Account, to: Account, amount: Chips) {
+        if amount.is_zero() {
+            return;
+        }
+        *self.balances.entry(from.clone()).or_default() -= i128::from(amount.get());
+        *self.balances.entry(to.clone()).or_default() += i128::from(amount.get());
+        self.next_id += 1;
+        if self.transactions.len() == LEDGER_LEN {
+            self.transactions.pop_front();
+        }
+        self.transactions.push_back(Transaction { id: self.next_id, hand_number, kind, from, to, amount });
+    }
+
+    /// The transactions kept, oldest first.
+    pub fn transactions(&self) -> impl Iterator<Item = &Transaction> {
+        self.transactions.iter()
+    }
+
+    /// The kept transactions moving chips into or out of `player_id`'s stack, oldest first.
+    pub fn player_transactions<'a>(&'a self, player_id: &'a str) -> impl Iterator<Item = &'a Transaction> {
+        self.transactions.iter().filter(move |transaction| transaction.involves(player_id))
+    }
+
+    /// What `account` has taken in less what it has paid out, over every
+    /// transaction ever recorded.
+    pub fn balance(&self, account: &Account) -> i128 {
+        self.balances.get(account).copied().unwrap_or_default()
+    }
+
+    /// Checks the balances against the table: each player's against their
+    /// stack, with nothing left on players no longer seated; the pot's against
+    /// the chips bet; and all of them together against the chips in play.
+    /// Returns every mismatch found.
+    pub fn reconcile<'a>(&self, stacks: impl IntoIterator<Item = (&'a PlayerId, Chips)>, pot: Chips, chips_in_play: Chips) -> Result<(), Vec<String>> {
+        let mut violations = Vec::new();
+        let stacks: HashMap<&PlayerId, Chips> = stacks.into_iter().collect();
+        let mut total = 0;
+        for (account, balance) in &self.balances {
+            let Account::Player(player_id) = account else { continue };
+            total += balance;
+            let stack = stacks.get(player_id).map_or(0, |stack| i128::from(stack.get()));
+            if *balance != stack {
+                violations.push(format!("ledger of player {player_id} sums to {balance}, their stack is {stack}"));
+            }
+        }
+        for (player_id, stack) in &stacks {
+            if !self.balances.contains_key(&Account::Player((*player_id).clone())) && !stack.is_zero() {
+                violations.push(format!("player {player_id} holds {stack} chips the ledger never gave them"));
+            }
+        }
+        let pot_balance = self.balance(&Account::Pot);
+        if pot_balance != i128::from(pot.get()) {
+            violations.push(format!("ledger of the pot sums to {pot_balance}, {pot} chips are bet"));
+        }
+        total += pot_balance;
+        if total != i128::from(chips_in_play.get()) {
+            violations.push(format!("ledgers sum to {total}, the table has {chips_in_play} chips in play"));
+        }
+        if violations.is_empty() { Ok(()) } else { Err(violations) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_balances_and_reconciliation() {
+        let (alice, bob) = (PlayerId::from("1"), PlayerId::from("2"));
+        let mut ledger = Ledger::new();
+        ledger.record(0, TransactionKind::Seated, Account::Cashier, Account::Player(alice.clone()), Chips::new(1000));
+        ledger.record(0, TransactionKind::Seated, Account::Cashier, Account::Player(bob.clone()), Chips::new(1000));
+        ledger.record(1, TransactionKind::Bet, Account::Player(alice.clone()), Account::Pot, Chips::new(100));
+        ledger.record(1, TransactionKind::Bet, Account::Player(bob.clone()), Account::Pot, Chips::ZERO); // Not recorded
+        assert_eq!(ledger.transactions().map(|transaction| transaction.id).collect::<Vec<_>>(), [1, 2, 3]);
+        assert_eq!(ledger.balance(&Account::Cashier), -2000);
+        assert_eq!(ledger.reconcile([(&alice, Chips::new(900)), (&bob, Chips::new(1000))], Chips::new(100), Chips::new(2000)), Ok(()));
+
+        ledger.record(1, TransactionKind::PotAward, Account::Pot, Account::Player(bob.clone()), Chips::new(100));
+        assert_eq!(ledger.player_transactions("2").map(|transaction| transaction.kind).collect::<Vec<_>>(), [TransactionKind::Seated, TransactionKind::PotAward]);
+        let violations = ledger.reconcile([(&alice, Chips::new(900)), (&bob, Chips::new(1000))], Chips::ZERO, Chips::new(2000)).unwrap_err();
+        assert_eq!(violations, ["ledger of player 2 sums to 1100, their stack is 1000".to_string()]);
+    }
+}
@@ -0,0 +1,92 @@
+//! Card dealing, hand evaluation and the game engine behind the card dealer
+//! server, usable without its HTTP stack or async runtime.
+//!
+//! # Features
+//! - `os-rng` (default): `Deck::new` and `GameController::new`, shuffling
+//!   from OS entropy, and the OS, thread-local and file `entropy` sources.
+//!   Without it every deck is shuffled by an RNG the caller passes in, which
+//!   keeps the crate buildable for `wasm32-unknown-unknown`.
+//! - `serde` (default): `Serialize` for hands and events, and
+//!   `Serialize`/`Deserialize` for cards and snapshots.
+//! - `schema` (default): OpenAPI schemas via `utoipa`; implies `serde`.
+//! - `metrics` (default): the process-wide `metrics::METRICS` registry the
+//!   engine records into.
+//! - `parallel`: evaluates players' hands and batches of equity runouts on
+//!   rayon's thread pool. Results are the same as without it.
+//! - `mental-poker`: the `mental_poker` module, a protocol for dealing from
+//!   a deck the players shuffle and encrypt themselves.
+//!
+//! With `default-features = false` only the engine itself is compiled.
+
+pub mod betting;
+pub mod blind_schedule;
+pub mod bots;
+pub mod bounty;
+pub mod buy_in;
+pub mod card_dealer;
+pub mod card_set;
+pub mod chips;
+pub mod currency;
+pub mod entropy;
+pub mod equity;
+pub mod events;
+pub mod game_controller;
+pub mod game_error;
+pub mod hints;
+pub mod jackpot;
+pub mod ledger;
+#[cfg(feature = "mental-poker")]
+pub mod mental_poker;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+pub mod ofc;
+pub mod outs;
+pub mod player;
+pub mod player_id;
+mod player_registry;
+pub mod poker_hand;
+pub mod preflop_chart;
+pub mod rake;
+pub mod range;
+pub mod snapshot;
+pub mod solver;
+pub mod table;
+pub mod table_config;
+pub mod tournament;
+pub mod variant;
+pub mod waitlist;
+pub mod wild_cards;
+
+pub use betting::{AllInShare, BettingState, HandLog, InsuranceOffer, LoggedAction, LoggedPlayer, RunoutVote, Street, StreetBets};
+pub use blind_schedule::{BlindLevel, BlindSchedule, ClockState};
+pub use buy_in::{AddOn, BuyInKind, BuyInRules};
+pub use bounty::{BountyFormat, BountyRules, Elimination};
+pub use bots::{ActionContext, BotStrategy, CallingStation, Difficulty, RandomBot, RuleBasedBot, RuleBasedConfig, TightAggressive};
+pub use card_dealer::{parse_cards, Card, Deck, Rank, Suit};
+pub use card_set::CardSet;
+pub use chips::Chips;
+pub use currency::{Bank, CurrencyMode};
+pub use entropy::EntropySource;
+pub use equity::{equity_vs_range, estimate_equity, estimate_equity_seeded, exact_equity, hand_percentile, ComboEquity, RangeEquity};
+pub use events::{DealRecord, GameEvent, Runout};
+pub use game_controller::GameController;
+pub use game_error::GameError;
+pub use hints::Hint;
+pub use jackpot::{Jackpot, JackpotRules};
+pub use ledger::{Account, Ledger, Transaction, TransactionKind};
+pub use ofc::OfcBoard;
+pub use outs::{analyze_outs, Draw, OutsAnalysis};
+pub use player::Player;
+pub use player_id::PlayerId;
+pub use poker_hand::{find_best_hand, hand_value, Hand, HandError, HandRank, HandValue, PartialEvaluation};
+pub use preflop_chart::{ChartAdvice, Position, PreflopChart};
+pub use rake::RakeRules;
+pub use range::Range;
+pub use snapshot::{GameSnapshot, PlayerSnapshot};
+pub use solver::{ComboStrategy, SolverBackend, SolverSpot, SolverStrategy};
+pub use table::Table;
+pub use table_config::TableConfig;
+pub use tournament::{Tournament, TournamentRules};
+pub use variant::GameVariant;
+pub use waitlist::{Seating, WaitingList, WaitingPlayer};
+pub use wild_cards::WildCards;
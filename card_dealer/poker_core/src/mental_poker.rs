@@ -0,0 +1,350 @@
+//! Mental poker: the players shuffle and encrypt the deck between them, so
+//! the dealer only ever holds ciphertexts and learns a card when every player
+//! has handed over their key for it.
+//!
+//! Cards are encrypted with SRA, a commutative cipher (exponentiation modulo
+//! a shared prime), so encryptions can be added and removed in any order:
+//!
+//! 1. Starting from [`MentalDeal::deck`], each player in turn encrypts every
+//!    card with one key and shuffles the deck ([`MentalPlayer::shuffle`]).
+//! 2. Each player in turn swaps that key for a separate key per deck position
+//!    ([`MentalPlayer::lock`]), so cards can be opened one at a time.
+//! 3. To deal a card to a player, everyone else gives that player their key
+//!    for its position ([`MentalPlayer::card_key`]) through the dealer, who
+//!    keeps them ([`MentalDeal::submit_key`]). A board card, or a hand shown
+//!    down, is opened once the dealer holds every player's key for it.
+//!
+//! The dealer's [`MentalDeal`] only enforces the order of the protocol; the
+//! cryptography happens on the clients' [`MentalPlayer`]s.
+
+use std::collections::HashMap;
+
+use num_bigint::BigUint;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::card_dealer::{Card, Deck};
+use crate::game_error::GameError;
+use crate::player_id::PlayerId;
+
+/// The 2048-bit safe prime of RFC 3526, group 14. Cards are encoded as
+/// quadratic residues, whose subgroup has the prime order `(p - 1) / 2`.
+const PRIME_HEX: &str = "\
+    FFFFFFFFFFFFFFFFC90FDAA22168C234C4C6628B80DC1CD129024E088A67CC74020BBEA63B139B22514A08798E3404DD\
+    EF9519B3CD3A431B302B0A6DF25F14374FE1356D6D51C245E485B576625E7EC6F44C42E9A637ED6B0BFF5CB6F406B7ED\
+    EE386BFB5A899FA5AE9F24117C4B1FE649286651ECE45B3DC2007CB8A163BF0598DA48361C55D39A69163FA8FD24CF5F\
+    83655D23DCA3AD961C62F356208552BB9ED529077096966D670C354E4ABC9804F1746C08CA18217C32905E462E36CE3B\
+    E39E772C180E86039B2783A2EC07A28FB5C55DF06F4C52C9DE2BCBF6955817183995497CEA956AE515D2261898FA0510\
+    15728E5A8AACAA68FFFFFFFFFFFFFFFF";
+
+fn prime() -> BigUint {
+    BigUint::parse_bytes(PRIME_HEX.as_bytes(), 16).expect("the prime is valid hex")
+}
+
+/// The order of the subgroup the encoded cards live in.
+fn order() -> BigUint {
+    (prime() - 1u32) >> 1
+}
+
+/// A card, or a deck position's key, as a number modulo the shared prime.
+/// Serialized as a hex string.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(try_from = "String", into = "String"))]
+pub struct Cipher(BigUint);
+
+/// A card as the players pass it around: encrypted by one or more of them.
+pub type EncryptedCard = Cipher;
+/// A player's key for one deck position, given to whoever opens the card.
+pub type RevealKey = Cipher;
+
+impl TryFrom<String> for Cipher {
+    type Error = GameError;
+
+    fn try_from(hex: String) -> Result<Self, GameError> {
+        BigUint::parse_bytes(hex.as_bytes(), 16)
+            .map(Cipher)
+            .ok_or_else(|| GameError::InvalidRequest(format!("'{hex}' is not a hex number")))
+    }
+}
+
+impl From<Cipher> for String {
+    fn from(cipher: Cipher) -> String {
+        cipher.0.to_str_radix(16)
+    }
+}
+
+/// An SRA key pair: exponents that undo each other on encoded cards.
+#[derive(Debug, Clone)]
+struct SraKey {
+    encrypt: BigUint,
+    decrypt: BigUint,
+}
+
+impl SraKey {
+    fn generate<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        let order = order();
+        loop {
+            let mut bytes = [0u8; 256];
+            rng.fill_bytes(&mut bytes);
+            let encrypt = BigUint::from_bytes_be(&bytes) % &order;
+            if let Some(decrypt) = encrypt.modinv(&order) {
+                return Self { encrypt, decrypt };
+            }
+        }
+    }
+
+    fn encrypt(&self, card: &Cipher) -> Cipher {
+        Cipher(card.0.modpow(&self.encrypt, &prime()))
+    }
+
+    fn decrypt(&self, card: &Cipher) -> Cipher {
+        Cipher(card.0.modpow(&self.decrypt, &prime()))
+    }
+}
+
+/// The unencrypted deck, in `Deck::all_cards` order. Card `i` is `(i + 2)²`,
+/// a quadratic residue, so encrypting it doesn't leak anything through its
+/// Legendre symbol.
+fn encoded_deck() -> Vec<(Card, Cipher)> {
+    Deck::all_cards()
+        .into_iter()
+        .zip(2u32..)
+        .map(|(card, value)| (card, Cipher(BigUint::from(value * value))))
+        .collect()
+}
+
+fn decode(value: &Cipher) -> Result<Card, GameError> {
+    encoded_deck()
+        .into_iter()
+        .find(|(_, encoded)| encoded == value)
+        .map(|(card, _)| card)
+        .ok_or_else(|| GameError::InvalidRequest("the keys don't open this card".to_string()))
+}
+
+/// One player's side of the protocol. It never leaves the player's client.
+#[derive(Debug, Clone)]
+pub struct MentalPlayer {
+    shuffle_key: SraKey,
+    card_keys: Vec<SraKey>, // One per deck position, made by `lock`
+}
+
+impl MentalPlayer {
+    pub fn new<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        Self { shuffle_key: SraKey::generate(rng), card_keys: Vec::new() }
+    }
+
+    /// Step 1: encrypts every card with this player's shuffle key and
+    /// shuffles the result.
+    pub fn shuffle<R: Rng + ?Sized>(&self, deck: &[EncryptedCard], rng: &mut R) -> Vec<EncryptedCard> {
+        let mut deck: Vec<EncryptedCard> = deck.iter().map(|card| self.shuffle_key.encrypt(card)).collect();
+        deck.shuffle(rng);
+        deck
+    }
+
+    /// Step 2: takes the shuffle key off every card and encrypts each with
+    /// a key of its own.
+    pub fn lock<R: Rng + ?Sized>(&mut self, deck: &[EncryptedCard], rng: &mut R) -> Vec<EncryptedCard> {
+        self.card_keys = deck.iter().map(|_| SraKey::generate(rng)).collect();
+        deck.iter()
+            .zip(&self.card_keys)
+            .map(|(card, key)| key.encrypt(&self.shuffle_key.decrypt(card)))
+            .collect()
+    }
+
+    /// This player's key for the card at `position`, to give to whoever may
+    /// see it.
+    pub fn card_key(&self, position: usize) -> Option<RevealKey> {
+        self.card_keys.get(position).map(|key| Cipher(key.decrypt.clone()))
+    }
+
+    /// Opens the card at `position` of the locked deck with every other
+    /// player's key for it.
+    pub fn open(&self, deck: &[EncryptedCard], position: usize, keys: &[RevealKey]) -> Result<Card, GameError> {
+        let own = self.card_key(position).ok_or_else(|| GameError::InvalidState("the deck isn't locked yet".to_string()))?;
+        open_card(deck, position, keys.iter().chain([&own]))
+    }
+}
+
+fn open_card<'a>(deck: &[EncryptedCard], position: usize, keys: impl IntoIterator<Item = &'a RevealKey>) -> Result<Card, GameError> {
+    let card = deck.get(position).ok_or_else(|| GameError::InvalidRequest(format!("no card at position {position}")))?;
+    let prime = prime();
+    let opened = keys.into_iter().fold(card.0.clone(), |value, key| value.modpow(&key.0, &prime));
+    decode(&Cipher(opened))
+}
+
+/// Where a deal is in the protocol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "phase", rename_all = "snake_case"))]
+pub enum MentalPhase {
+    Shuffling { turn: usize },  // Index of the player who shuffles next
+    Locking { turn: usize },    // Index of the player who locks next
+    Dealing,
+}
+
+/// The dealer's side of a deal: the encrypted deck and the keys players
+/// have handed over, never a card it hasn't been given every key for.
+#[derive(Debug, Clone)]
+pub struct MentalDeal {
+    players: Vec<PlayerId>,                              // In the order they shuffle
+    deck: Vec<EncryptedCard>,
+    phase: MentalPhase,
+    owners: Vec<Option<PlayerId>>,                       // Who each dealt position went to; `None` for the board
+    keys: HashMap<usize, HashMap<PlayerId, RevealKey>>,  // Keys handed over, by deck position
+}
+
+impl MentalDeal {
+    /// Starts a deal between `players`, who shuffle in the order given.
+    pub fn new(players: Vec<PlayerId>) -> Result<Self, GameError> {
+        if players.len() < 2 {
+            return Err(GameError::InvalidRequest("mental poker needs at least two players".to_string()));
+        }
+        Ok(Self {
+            players,
+            deck: encoded_deck().into_iter().map(|(_, encoded)| encoded).collect(),
+            phase: MentalPhase::Shuffling { turn: 0 },
+            owners: Vec::new(),
+            keys: HashMap::new(),
+        })
+    }
+
+    pub fn deck(&self) -> &[EncryptedCard] {
+        &self.deck
+    }
+
+    pub fn phase(&self) -> MentalPhase {
+        self.phase
+    }
+
+    /// The player whose shuffle or lock the deal is waiting for.
+    pub fn awaiting(&self) -> Option<&PlayerId> {
+        match self.phase {
+            MentalPhase::Shuffling { turn } | MentalPhase::Locking { turn } => self.players.get(turn),
+            MentalPhase::Dealing => None,
+        }
+    }
+
+    /// Takes the deck back from the player whose turn it is, after their
+    /// shuffle or lock. It must still hold 52 different cards.
+    pub fn submit_deck(&mut self, player_id: &str, deck: Vec<EncryptedCard>) -> Result<(), GameError> {
+        if self.awaiting().is_none_or(|awaiting| awaiting.as_str() != player_id) {
+            return Err(GameError::NotAllowed(format!("it isn't {player_id}'s turn to pass the deck on")));
+        }
+        let mut distinct = deck.clone();
+        distinct.sort_by(|a, b| a.0.cmp(&b.0));
+        distinct.dedup();
+        if deck.len() != self.deck.len() || distinct.len() != deck.len() {
+            return Err(GameError::InvalidRequest(format!("the deck must hold {} different cards", self.deck.len())));
+        }
+        self.deck = deck;
+        let last = self.players.len() - 1;
+        self.phase = match self.phase {
+            MentalPhase::Shuffling { turn } if turn == last => MentalPhase::Locking { turn: 0 },
+            MentalPhase::Shuffling { turn } => MentalPhase::Shuffling { turn: turn + 1 },
+            MentalPhase::Locking { turn } if turn == last => MentalPhase::Dealing,
+            MentalPhase::Locking { turn } => MentalPhase::Locking { turn: turn + 1 },
+            MentalPhase::Dealing => unreachable!("nobody is awaited while dealing"),
+        };
+        Ok(())
+    }
+
+    /// Deals the next position of the deck to `owner`, or to the board with
+    /// `None`, and returns it. Every other player then owes the dealer their
+    /// key for it.
+    pub fn deal(&mut self, owner: Option<PlayerId>) -> Result<usize, GameError> {
+        if self.phase != MentalPhase::Dealing {
+            return Err(GameError::InvalidState("the deck hasn't been shuffled and locked by every player".to_string()));
+        }
+        if owner.as_ref().is_some_and(|owner| !self.players.contains(owner)) {
+            return Err(GameError::PlayerNotFound(owner.map(String::from).unwrap_or_default()));
+        }
+        if self.owners.len() == self.deck.len() {
+            return Err(GameError::NotEnoughCards("the next card"));
+        }
+        self.owners.push(owner);
+        Ok(self.owners.len() - 1)
+    }
+
+    /// Keeps `player_id`'s key for a dealt position.
+    pub fn submit_key(&mut self, player_id: &str, position: usize, key: RevealKey) -> Result<(), GameError> {
+        let player_id = self.players.iter()
+            .find(|id| id.as_str() == player_id)
+            .ok_or_else(|| GameError::PlayerNotFound(player_id.to_string()))?;
+        if position >= self.owners.len() {
+            return Err(GameError::InvalidRequest(format!("position {position} hasn't been dealt")));
+        }
+        self.keys.entry(position).or_default().insert(player_id.clone(), key);
+        Ok(())
+    }
+
+    /// The keys `player_id` needs to open their card at `position`: everyone
+    /// else's, once all have been handed over.
+    pub fn keys_for(&self, player_id: &str, position: usize) -> Result<Vec<RevealKey>, GameError> {
+        if self.owners.get(position).and_then(Option::as_ref).is_none_or(|owner| owner.as_str() != player_id) {
+            return Err(GameError::NotAllowed(format!("position {position} wasn't dealt to {player_id}")));
+        }
+        let keys = self.keys.get(&position);
+        self.players.iter()
+            .filter(|id| id.as_str() != player_id)
+            .map(|id| {
+                keys.and_then(|keys| keys.get(id))
+                    .cloned()
+                    .ok_or_else(|| GameError::InvalidState(format!("{id} hasn't handed over their key yet")))
+            })
+            .collect()
+    }
+
+    /// The card at `position`, once every player's key for it has been
+    /// handed over: a board card, or a hand shown down.
+    pub fn open(&self, position: usize) -> Option<Card> {
+        let keys = self.keys.get(&position)?;
+        if keys.len() < self.players.len() {
+            return None;
+        }
+        open_card(&self.deck, position, keys.values()).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_deal_without_the_dealer_seeing_hole_cards() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(3);
+        let ids: Vec<PlayerId> = vec!["1".into(), "2".into()];
+        let mut players = [MentalPlayer::new(&mut rng), MentalPlayer::new(&mut rng)];
+        let mut deal = MentalDeal::new(ids.clone()).unwrap();
+
+        for (id, player) in ids.iter().zip(&players) {
+            let deck = player.shuffle(deal.deck(), &mut rng);
+            deal.submit_deck(id, deck).unwrap();
+        }
+        assert!(deal.submit_deck("2", deal.deck().to_vec()).is_err()); // Player 1 locks first
+        for (id, player) in ids.iter().zip(&mut players) {
+            let deck = player.lock(deal.deck(), &mut rng);
+            deal.submit_deck(id, deck).unwrap();
+        }
+        assert_eq!(deal.phase(), MentalPhase::Dealing);
+
+        // A hole card for player 1: only player 2's key is handed over
+        let hole = deal.deal(Some(ids[0].clone())).unwrap();
+        deal.submit_key("2", hole, players[1].card_key(hole).unwrap()).unwrap();
+        assert_eq!(deal.open(hole), None);
+        assert!(deal.keys_for("2", hole).is_err());
+        let keys = deal.keys_for("1", hole).unwrap();
+        let card = players[0].open(deal.deck(), hole, &keys).unwrap();
+
+        // A board card is opened by the dealer once both keys are in
+        let board = deal.deal(None).unwrap();
+        for (id, player) in ids.iter().zip(&players) {
+            deal.submit_key(id, board, player.card_key(board).unwrap()).unwrap();
+        }
+        let flop = deal.open(board).unwrap();
+        assert_ne!(card, flop);
+
+        // Showing the hand down reveals the same card to everyone
+        deal.submit_key("1", hole, players[0].card_key(hole).unwrap()).unwrap();
+        assert_eq!(deal.open(hole), Some(card));
+    }
+}
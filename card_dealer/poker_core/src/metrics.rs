@@ -0,0 +1,172 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+
+/// Process-wide metrics registry, rendered at `GET /metrics`.
+pub static METRICS: LazyLock<Metrics> = LazyLock::new(Metrics::new);
+
+/// Upper bounds (in seconds) shared by every histogram.
+const BUCKETS: [f64; 11] = [0.00005, 0.0001, 0.00025, 0.0005, 0.001, 0.0025, 0.005, 0.01, 0.05, 0.25, 1.0];
+
+/// Monotonically increasing count.
+#[derive(Default)]
+pub struct Counter(AtomicU64);
+
+impl Counter {
+    pub fn inc(&self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Value that can go up and down.
+#[derive(Default)]
+pub struct Gauge(AtomicI64);
+
+impl Gauge {
+    pub fn inc(&self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn dec(&self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    pub fn get(&self) -> i64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Cumulative histogram over `BUCKETS`, tracking durations in seconds.
+#[derive(Default)]
+pub struct Histogram {
+    buckets: [AtomicU64; BUCKETS.len()],
+    count: AtomicU64,
+    sum_micros: AtomicU64,
+}
+
+impl Histogram {
+    pub fn observe(&self, elapsed: Duration) {
+        let seconds = elapsed.as_secs_f64();
+        for (bound, bucket) in BUCKETS.iter().zip(&self.buckets) {
+            if seconds <= *bound {
+                bucket.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.sum_micros.fetch_add(elapsed.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    /// Writes the `_bucket`, `_sum` and `_count` series, with optional extra labels.
+    fn render(&self, out: &mut String, name: &str, labels: &str) {
+        let separator = if labels.is_empty() { "" } else { "," };
+        for (bound, bucket) in BUCKETS.iter().zip(&self.buckets) {
+            let _ = writeln!(out, "{name}_bucket{{{labels}{separator}le=\"{bound}\"}} {}", bucket.load(Ordering::Relaxed));
+        }
+        let _ = writeln!(out, "{name}_bucket{{{labels}{separator}le=\"+Inf\"}} {}", self.count());
+        let braces = if labels.is_empty() { String::new() } else { format!("{{{labels}}}") };
+        let sum = self.sum_micros.load(Ordering::Relaxed) as f64 / 1_000_000.0;
+        let _ = writeln!(out, "{name}_sum{braces} {sum}");
+        let _ = writeln!(out, "{name}_count{braces} {}", self.count());
+    }
+}
+
+/// All metrics exported by the server.
+pub struct Metrics {
+    pub hands_dealt: Counter,                           // Completed hole-card deals
+    pub player_actions: Counter,                        // Bets, raises, folds, ... recorded by players
+    pub hand_evaluation: Histogram,                     // Time spent evaluating every player's hand
+    pub websocket_connections: Gauge,                   // Open WebSocket connections
+    route_latency: Mutex<BTreeMap<(String, u16), Histogram>>, // Request latency keyed by (route, status)
+}
+
+impl Metrics {
+    fn new() -> Self {
+        Self {
+            hands_dealt: Counter::default(),
+            player_actions: Counter::default(),
+            hand_evaluation: Histogram::default(),
+            websocket_connections: Gauge::default(),
+            route_latency: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Records how long a request to `route` took to answer with `status`.
+    pub fn observe_route(&self, route: &str, status: u16, elapsed: Duration) {
+        let mut routes = self.route_latency.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        routes
+            .entry((route.to_string(), status))
+            .or_default()
+            .observe(elapsed);
+    }
+
+    /// Renders every metric in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+
+        let _ = writeln!(out, "# HELP poker_hands_dealt_total Hands dealt since the server started.");
+        let _ = writeln!(out, "# TYPE poker_hands_dealt_total counter");
+        let _ = writeln!(out, "poker_hands_dealt_total {}", self.hands_dealt.get());
+
+        let _ = writeln!(out, "# HELP poker_player_actions_total Player actions recorded since the server started.");
+        let _ = writeln!(out, "# TYPE poker_player_actions_total counter");
+        let _ = writeln!(out, "poker_player_actions_total {}", self.player_actions.get());
+
+        let _ = writeln!(out, "# HELP poker_websocket_connections Open WebSocket connections.");
+        let _ = writeln!(out, "# TYPE poker_websocket_connections gauge");
+        let _ = writeln!(out, "poker_websocket_connections {}", self.websocket_connections.get());
+
+        let _ = writeln!(out, "# HELP poker_hand_evaluation_seconds Time taken to evaluate all players' hands.");
+        let _ = writeln!(out, "# TYPE poker_hand_evaluation_seconds histogram");
+        self.hand_evaluation.render(&mut out, "poker_hand_evaluation_seconds", "");
+
+        let _ = writeln!(out, "# HELP poker_http_request_duration_seconds HTTP request latency by route and status.");
+        let _ = writeln!(out, "# TYPE poker_http_request_duration_seconds histogram");
+        let routes = self.route_latency.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        for ((route, status), histogram) in routes.iter() {
+            let labels = format!("route=\"{route}\",status=\"{status}\"");
+            histogram.render(&mut out, "poker_http_request_duration_seconds", &labels);
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histogram_buckets_are_cumulative() {
+        let histogram = Histogram::default();
+        histogram.observe(Duration::from_micros(80));
+        histogram.observe(Duration::from_millis(3));
+
+        let mut out = String::new();
+        histogram.render(&mut out, "test_seconds", "");
+
+        assert!(out.contains("test_seconds_bucket{le=\"0.0001\"} 1"));
+        assert!(out.contains("test_seconds_bucket{le=\"0.005\"} 2"));
+        assert!(out.contains("test_seconds_bucket{le=\"+Inf\"} 2"));
+        assert!(out.contains("test_seconds_count 2"));
+    }
+
+    #[test]
+    fn test_route_latency_rendering() {
+        let metrics = Metrics::new();
+        metrics.observe_route("/deal_hole", 200, Duration::from_millis(1));
+
+        let out = metrics.render();
+        assert!(out.contains("poker_http_request_duration_seconds_count{route=\"/deal_hole\",status=\"200\"} 1"));
+        assert!(out.contains("poker_hands_dealt_total 0"));
+    }
+}
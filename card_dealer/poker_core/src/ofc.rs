@@ -0,0 +1,263 @@
+//! Open-face Chinese poker: each player sets thirteen cards face up into a
+//! 3-card top row and 5-card middle and bottom rows, which must get stronger
+//! from top to bottom. Finished boards are scored row by row against every
+//! opponent, plus royalties for strong rows.
+
+use crate::card_dealer::{Card, Rank};
+use crate::card_set::CardSet;
+use crate::game_error::GameError;
+use crate::poker_hand::{hand_value, HandRank};
+
+/// Points for winning all three rows against an opponent, instead of three.
+const SCOOP_POINTS: i32 = 6;
+
+/// One of the three rows of an OFC board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "snake_case"))]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub enum Row {
+    Top,
+    Middle,
+    Bottom,
+}
+
+impl Row {
+    /// Every row, top first.
+    pub const ALL: [Row; 3] = [Row::Top, Row::Middle, Row::Bottom];
+
+    /// Cards the row holds once it is full.
+    pub fn size(self) -> usize {
+        match self {
+            Row::Top => 3,
+            Row::Middle | Row::Bottom => 5,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Row::Top => "top",
+            Row::Middle => "middle",
+            Row::Bottom => "bottom",
+        }
+    }
+}
+
+/// The cards one player has set so far.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct OfcBoard {
+    top: Vec<Card>,
+    middle: Vec<Card>,
+    bottom: Vec<Card>,
+}
+
+/// A row's strength, comparable between 3- and 5-card rows: the hand rank,
+/// then the ranks that decide ties (paired ranks first, a straight by its
+/// top card).
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct RowStrength {
+    rank: HandRank,
+    ranks: Vec<Rank>,
+}
+
+impl OfcBoard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn row(&self, row: Row) -> &[Card] {
+        match row {
+            Row::Top => &self.top,
+            Row::Middle => &self.middle,
+            Row::Bottom => &self.bottom,
+        }
+    }
+
+    /// Sets `card` into `row`. Cards can't be moved once placed.
+    pub fn place(&mut self, row: Row, card: Card) -> Result<(), GameError> {
+        if Row::ALL.iter().any(|&row| self.row(row).contains(&card)) {
+            return Err(GameError::DuplicateCard(card));
+        }
+        let cards = match row {
+            Row::Top => &mut self.top,
+            Row::Middle => &mut self.middle,
+            Row::Bottom => &mut self.bottom,
+        };
+        if cards.len() == row.size() {
+            return Err(GameError::InvalidRequest(format!("the {} row is full", row.name())));
+        }
+        cards.push(card);
+        Ok(())
+    }
+
+    /// True once all thirteen cards are set.
+    pub fn is_complete(&self) -> bool {
+        Row::ALL.iter().all(|&row| self.row(row).len() == row.size())
+    }
+
+    /// True for a complete board whose rows don't get stronger from top to
+    /// bottom. A fouled board loses every row and earns no royalties.
+    pub fn is_fouled(&self) -> bool {
+        self.is_complete() && !(self.strength(Row::Top) <= self.strength(Row::Middle) && self.strength(Row::Middle) <= self.strength(Row::Bottom))
+    }
+
+    /// Bonus points for strong rows on a complete, unfouled board.
+    pub fn royalties(&self) -> i32 {
+        if !self.is_complete() || self.is_fouled() {
+            return 0;
+        }
+        Row::ALL.iter().map(|&row| royalty(row, &self.strength(row))).sum()
+    }
+
+    /// True when the board earns a Fantasyland hand: queens or better on top
+    /// without fouling.
+    pub fn qualifies_for_fantasyland(&self) -> bool {
+        if !self.is_complete() || self.is_fouled() {
+            return false;
+        }
+        let top = self.strength(Row::Top);
+        match top.rank {
+            HandRank::ThreeOfAKind => true,
+            HandRank::OnePair => top.ranks[0] >= Rank::Queen,
+            _ => false,
+        }
+    }
+
+    fn strength(&self, row: Row) -> RowStrength {
+        row_strength(self.row(row))
+    }
+}
+
+/// Points `board` wins from `opponent`, negative when it pays: one per row
+/// won, six for winning all three, and the difference in royalties. A fouled
+/// board loses every row to an unfouled one; two fouled boards score nothing.
+pub fn score(board: &OfcBoard, opponent: &OfcBoard) -> i32 {
+    match (board.is_fouled(), opponent.is_fouled()) {
+        (true, true) => 0,
+        (true, false) => -(SCOOP_POINTS + opponent.royalties()),
+        (false, true) => SCOOP_POINTS + board.royalties(),
+        (false, false) => {
+            let rows: i32 = Row::ALL.iter()
+                .map(|&row| board.strength(row).cmp(&opponent.strength(row)) as i32)
+                .sum();
+            let rows = if rows.abs() == 3 { rows.signum() * SCOOP_POINTS } else { rows };
+            rows + board.royalties() - opponent.royalties()
+        }
+    }
+}
+
+/// Each player's net points after scoring every board against every other.
+/// The totals add up to zero.
+pub fn settle(boards: &[OfcBoard]) -> Vec<i32> {
+    boards.iter()
+        .enumerate()
+        .map(|(index, board)| {
+            boards.iter().enumerate().filter(|(other, _)| *other != index).map(|(_, opponent)| score(board, opponent)).sum()
+        })
+        .collect()
+}
+
+fn row_strength(cards: &[Card]) -> RowStrength {
+    let set: CardSet = cards.iter().copied().collect();
+    let mut counts: Vec<(usize, Rank)> = Rank::ALL.iter()
+        .map(|&rank| (cards.iter().filter(|card| card.rank == rank).count(), rank))
+        .filter(|(count, _)| *count > 0)
+        .collect();
+    counts.sort_by(|a, b| b.cmp(a)); // Most copies first, then the higher rank
+    let rank = match (hand_value(set), counts.first()) {
+        (Some(value), _) => value.rank(),
+        (None, Some((3, _))) => HandRank::ThreeOfAKind,
+        (None, Some((2, _))) => HandRank::OnePair,
+        (None, _) => HandRank::HighCard,
+    };
+    let ranks = match rank {
+        HandRank::Straight | HandRank::StraightFlush => {
+            let wheel = set.ranks() == 0b1_0000_0000_1111; // A-2-3-4-5 plays as five high
+            vec![if wheel { Rank::Five } else { counts[0].1 }]
+        }
+        _ => counts.into_iter().map(|(_, rank)| rank).collect(),
+    };
+    RowStrength { rank, ranks }
+}
+
+fn royalty(row: Row, strength: &RowStrength) -> i32 {
+    match (row, &strength.rank) {
+        (Row::Top, HandRank::OnePair) => (strength.ranks[0] as i32 - Rank::Five as i32).max(0), // 66 pays 1, AA pays 9
+        (Row::Top, HandRank::ThreeOfAKind) => 10 + strength.ranks[0] as i32, // 222 pays 10, AAA pays 22
+        (Row::Top, _) => 0,
+        (_, rank) => {
+            let royal = *rank == HandRank::StraightFlush && strength.ranks[0] == Rank::Ace;
+            let (middle, bottom) = match rank {
+                _ if royal => (50, 25),
+                HandRank::StraightFlush => (30, 15),
+                HandRank::FourOfAKind => (20, 10),
+                HandRank::FullHouse => (12, 6),
+                HandRank::Flush => (8, 4),
+                HandRank::Straight => (4, 2),
+                HandRank::ThreeOfAKind => (2, 0),
+                _ => (0, 0),
+            };
+            if row == Row::Middle { middle } else { bottom }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card_dealer::parse_cards;
+
+    fn board(top: &str, middle: &str, bottom: &str) -> OfcBoard {
+        let mut board = OfcBoard::new();
+        for (row, cards) in Row::ALL.into_iter().zip([top, middle, bottom]) {
+            for card in parse_cards(cards).unwrap() {
+                board.place(row, card).unwrap();
+            }
+        }
+        board
+    }
+
+    #[test]
+    fn test_placement() {
+        let mut board = board("Ah Ad", "", "");
+        let card = "Ah".parse().unwrap();
+        assert_eq!(board.place(Row::Bottom, card), Err(GameError::DuplicateCard(card)));
+        board.place(Row::Top, "2c".parse().unwrap()).unwrap();
+        assert!(matches!(board.place(Row::Top, "3c".parse().unwrap()), Err(GameError::InvalidRequest(_))));
+        assert!(!board.is_complete() && !board.is_fouled());
+    }
+
+    #[test]
+    fn test_fouling_royalties_and_fantasyland() {
+        let good = board("Qh Qd 2c", "9s 9d 9c 4h 5d", "Ah Kh 8h 6h 3h");
+        assert!(good.is_complete() && !good.is_fouled());
+        assert_eq!(good.royalties(), 7 + 2 + 4); // Queens on top, trips in the middle, a flush on the bottom
+        assert!(good.qualifies_for_fantasyland());
+
+        // Kings on top over a pair of kings with a weaker kicker in the middle
+        let fouled = board("Ks Kc As", "Kd Kh Qc Jd Tc", "2s 2d 2h 7c 7d");
+        assert!(fouled.is_fouled());
+        assert_eq!((fouled.royalties(), fouled.qualifies_for_fantasyland()), (0, false));
+
+        let wheel = board("2s 3c 4d", "As 2h 3s 4c 5s", "6c 7h 8d 9s Td");
+        assert!(!wheel.is_fouled()); // A five-high straight under a ten-high one
+        let royal = board("2s 3c 4d", "Kd Ks 7c 7s 8c", "Ac Kc Qc Jc Tc");
+        assert_eq!(royal.royalties(), 25);
+    }
+
+    #[test]
+    fn test_scoring() {
+        let strong = board("Qh Qd 2c", "9s 9d 9c 4h 5d", "Ah Kh 8h 6h 3h");
+        let weak = board("Jc 4d 3s", "Th Td 6c 7c 8s", "Ks Kc Jd Js 2h");
+        assert_eq!(score(&strong, &weak), 6 + 13); // Scoop plus royalties
+        assert_eq!(score(&weak, &strong), -19);
+
+        let fouled = board("Ks Kc As", "Kd Kh Qc Jd Tc", "2s 2d 2h 7c 7d");
+        assert_eq!(score(&fouled, &weak), -6);
+        assert_eq!(score(&fouled, &fouled), 0);
+        let totals = settle(&[strong, weak, fouled]);
+        assert_eq!(totals, vec![19 + 19, -19 + 6, -19 - 6]);
+        assert_eq!(totals.iter().sum::<i32>(), 0);
+    }
+}
@@ -0,0 +1,105 @@
+//! Draws and outs on the flop or turn: which unseen cards would improve a
+//! Hold'em hand, for bots and training tools.
+
+use crate::card_dealer::{Card, Suit};
+use crate::card_set::CardSet;
+use crate::game_error::GameError;
+use crate::poker_hand::{hand_value, HandRank};
+
+/// A hand one card away from a straight or a flush.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "snake_case"))]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub enum Draw {
+    FlushDraw,      // Four cards of a suit
+    OpenEnded,      // Two ranks make a straight: open-ended, or a double gutshot
+    Gutshot,        // One rank makes a straight
+}
+
+/// An unseen card that would improve the hand, and what it makes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct Out {
+    pub card: Card,
+    pub makes: HandRank,
+}
+
+/// What `analyze_outs` found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct OutsAnalysis {
+    pub hand: HandRank,     // The best hand so far
+    pub draws: Vec<Draw>,
+    pub outs: Vec<Out>,     // In suit-then-rank order
+}
+
+/// Finds the draws in two hole cards with a flop or turn, and every unseen
+/// card that would make a better category of hand on the next street.
+pub fn analyze_outs(hole_cards: &[Card], board: &[Card]) -> Result<OutsAnalysis, GameError> {
+    if hole_cards.len() != 2 || !(3..=4).contains(&board.len()) {
+        return Err(GameError::InvalidRequest("outs need two hole cards and a flop or turn".to_string()));
+    }
+    let cards = CardSet::try_from_cards(&[hole_cards, board].concat()).map_err(GameError::DuplicateCard)?;
+    if cards.contains(Card::JOKER) {
+        return Err(GameError::InvalidRequest("outs can't be counted with a joker".to_string()));
+    }
+    let hand = hand_value(cards).expect("at least five cards").rank();
+    let outs = (CardSet::full() - cards).iter()
+        .map(|card| Out { card, makes: hand_value(cards | card.into()).expect("at least five cards").rank() })
+        .filter(|out| out.makes > hand)
+        .collect();
+    Ok(OutsAnalysis { draws: draws(cards, &hand), hand, outs })
+}
+
+fn draws(cards: CardSet, hand: &HandRank) -> Vec<Draw> {
+    let mut draws = Vec::new();
+    if *hand < HandRank::Flush && Suit::ALL.iter().any(|&suit| cards.suit_ranks(suit).count_ones() == 4) {
+        draws.push(Draw::FlushDraw);
+    }
+    if *hand < HandRank::Straight {
+        let ranks = cards.ranks();
+        let straight = |ranks: u16| ranks & ranks >> 1 & ranks >> 2 & ranks >> 3 & ranks >> 4 != 0;
+        let completing = (0..13).filter(|rank| ranks & 1 << rank == 0 && straight(ranks | 1 << rank)).count();
+        match completing {
+            0 => {}
+            1 => draws.push(Draw::Gutshot),
+            _ => draws.push(Draw::OpenEnded),
+        }
+    }
+    draws
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card_dealer::parse_cards;
+
+    fn analyze(hole_cards: &str, board: &str) -> OutsAnalysis {
+        analyze_outs(&parse_cards(hole_cards).unwrap(), &parse_cards(board).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_draws_and_outs() {
+        let combo = analyze("9h Th", "Jh Qc 2h");
+        assert_eq!(combo.hand, HandRank::HighCard);
+        assert_eq!(combo.draws, vec![Draw::FlushDraw, Draw::OpenEnded]);
+        let straights = combo.outs.iter().filter(|out| out.makes == HandRank::Straight).count();
+        let flushes = combo.outs.iter().filter(|out| out.makes >= HandRank::Flush).count();
+        assert_eq!((straights, flushes), (6, 9)); // 8h and Kh make a flush rather than a straight
+        assert_eq!(combo.outs.len(), 9 + 6 + 14); // And any other card that pairs one already out
+
+        let gutshot = analyze("9h Tc", "Qd Kc 4s 2s");
+        assert_eq!(gutshot.draws, vec![Draw::Gutshot]);
+        assert_eq!(gutshot.outs.iter().filter(|out| out.makes == HandRank::Straight).count(), 4);
+
+        let made = analyze("9h 8h", "Th Jh Qh");
+        assert_eq!((made.hand, made.draws), (HandRank::StraightFlush, vec![]));
+        assert!(made.outs.is_empty());
+
+        let cards = parse_cards("Ah Kd 2c 3c Ah").unwrap();
+        assert!(matches!(analyze_outs(&cards[..2], &cards[2..]), Err(GameError::DuplicateCard(_))));
+        assert!(matches!(analyze_outs(&cards[..2], &cards[2..4]), Err(GameError::InvalidRequest(_))));
+    }
+}
@@ -0,0 +1,154 @@
+use crate::poker_hand::Hand;
+use crate::poker_hand::find_best_shoe_hand;
+use crate::poker_hand::HandRank;
+use crate::poker_hand::PartialEvaluation;
+use crate::card_dealer::Card;
+use crate::card_set::CardSet;
+use crate::chips::Chips;
+use crate::player_id::PlayerId;
+use crate::variant::GameVariant;
+use crate::wild_cards::WildCards;
+#[cfg(feature = "metrics")]
+use crate::metrics::METRICS;
+use tracing::info;
+
+
+pub struct Player {
+    pub player_id: PlayerId,                // Unique identifier, possibly an NFT address
+    pub display_name: String,               // Player's display name for UI
+    pub hole_cards: Vec<Card>,              // Player's hole cards
+    pub hand_strength: Option<HandRank>,    // Best current hand rank
+    pub best_hand: Option<Hand>,       // Best current 5-card hand
+    pub chip_stack: Chips,                  // Player's current chip stack; bets leave it through `Table::add_bet`
+    pub table_position: usize,              // Position at the table
+    pub is_sitting_out: bool,               // Indicates if the player is sitting out
+    pub is_in_play: bool,                   // Indicates if the player is active in the current hand
+    pub action_history: Vec<PlayerAction>,  // Player's action history
+    pub rebuys: u32,                        // Rebuys made at this table
+    pub took_add_on: bool,                  // Has bought the tournament add-on
+    pub bounty: Chips,                      // Paid to whoever knocks the player out, in bounty tournaments
+    pub bounties_won: Chips,                // Bounties paid to the player so far; prize money, not chips in play
+    pub chips_won: i64,                     // Won from pots less chips put in, over the hands played at this table
+    pub ev_chips_won: f64,                  // `chips_won` with every all-in before the river counted at its equity
+    evaluation: PartialEvaluation,          // Best hand so far, extended street by street
+}
+
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "snake_case"))]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub enum PlayerAction {
+    Bet(Chips),     // A bet with the amount
+    Raise(Chips),   // A raise with the amount
+    Fold,           // The player folds
+    Check,          // The player checks
+    Call,           // The player calls
+    SitOut,         // The player sits out
+}
+
+
+impl Player {
+    /// Creates a new player instance
+    pub fn new(player_id: PlayerId, display_name: String, table_position: usize, chip_stack: Chips) -> Self {
+        Self {
+            player_id,
+            display_name,
+            hole_cards: Vec::new(),
+            hand_strength: None,
+            best_hand: None,
+            chip_stack,
+            table_position,
+            is_sitting_out: false,
+            is_in_play: true,
+            action_history: Vec::new(),
+            rebuys: 0,
+            took_add_on: false,
+            bounty: Chips::ZERO,
+            bounties_won: Chips::ZERO,
+            chips_won: 0,
+            ev_chips_won: 0.0,
+            evaluation: PartialEvaluation::default(),
+        }
+    }
+
+    /// Evaluates the player's hand strength based on the community cards.
+    /// It combines the player's hole cards with the community cards,
+    /// and finds the best hand that can be made from them. Cards already
+    /// evaluated on an earlier street are not scored again.
+    pub fn evaluate_hand(&mut self, community_cards: &[Card]) {
+        let cards: Vec<Card> = self.hole_cards.iter().chain(community_cards).copied().collect();
+        match CardSet::try_from_cards(&cards) {
+            Ok(cards) => {
+                self.evaluation.update(cards);
+                self.best_hand = self.evaluation.best_cards().and_then(|cards| Hand::new(cards.iter().collect()).ok()); // None until five cards are known
+            }
+            Err(_) => self.best_hand = find_best_shoe_hand(&self.hole_cards, community_cards, false), // A card dealt twice from a multi-deck shoe
+        }
+        self.hand_strength = self.best_hand.as_ref().map(|hand| hand.rank.clone());     // Use `map()` to extract rank safely without unwrap
+    }
+
+    /// Evaluates the player's best hand under `variant`'s rules; Hold'em
+    /// hands are extended card by card as in `evaluate_hand`.
+    pub fn evaluate_hand_for(&mut self, variant: GameVariant, community_cards: &[Card]) {
+        if !variant.uses_exactly_two() {
+            return self.evaluate_hand(community_cards);
+        }
+        self.best_hand = variant.best_hand(&self.hole_cards, community_cards);
+        self.hand_strength = self.best_hand.as_ref().map(|hand| hand.rank.clone());
+    }
+
+    /// Evaluates the player's best hand under `variant`'s rules with
+    /// `wild_cards` standing for whatever card is best, or as in
+    /// `evaluate_hand_for` when nothing is wild.
+    pub fn evaluate_hand_with_wilds(&mut self, variant: GameVariant, wild_cards: WildCards, community_cards: &[Card]) {
+        if wild_cards == WildCards::None {
+            return self.evaluate_hand_for(variant, community_cards);
+        }
+        self.best_hand = wild_cards.best_hand(variant, &self.hole_cards, community_cards);
+        self.hand_strength = self.best_hand.as_ref().map(|hand| hand.rank.clone());
+    }
+
+    /// Marks the player as folded for the current hand
+    pub fn fold(&mut self) {
+        self.is_in_play = false;
+        self.hole_cards.clear(); // Optional: Reset cards for clarity
+        self.record_action(PlayerAction::Fold);
+    }
+
+    /// Marks the player as sitting out
+    pub fn sit_out(&mut self) {
+        self.is_sitting_out = true;
+        self.is_in_play = false;
+        self.hole_cards.clear(); // Optional: Reset cards for clarity
+        self.record_action(PlayerAction::Fold); // Record as folded for this hand
+    }
+
+    /// Resets the player for a new hand: nothing about the last hand's cards,
+    /// best hand or actions carries over
+    pub fn reset_for_new_hand(&mut self) {
+        self.hole_cards.clear();
+        self.is_in_play = !self.is_sitting_out && !self.chip_stack.is_zero(); // Dealt in unless sitting out or busted
+        self.hand_strength = None;
+        self.best_hand = None;
+        self.evaluation = PartialEvaluation::default();
+        self.clear_action_history();
+    }
+
+    /// Adds chips to the player's stack
+    pub fn add_chips(&mut self, amount: Chips) {
+        self.chip_stack += amount;
+    }
+
+    /// Records a player's action in the action history
+    pub fn record_action(&mut self, action: PlayerAction) {
+        #[cfg(feature = "metrics")]
+        METRICS.player_actions.inc();
+        info!(player_id = %self.player_id, ?action, "player action");
+        self.action_history.push(action);
+    }
+
+    /// Clears the player's action history
+    pub fn clear_action_history(&mut self) {
+        self.action_history.clear();
+    }
+}
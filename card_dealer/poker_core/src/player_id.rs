@@ -0,0 +1,125 @@
+//! Identifies a seated player. Ids are compared on every bet, pot and event,
+//! so they share one allocation: cloning a `PlayerId` is a reference count
+//! bump, not a string copy.
+
+use std::borrow::Borrow;
+use std::fmt;
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// A player's unique id, e.g. a seat number or an NFT address.
+///
+/// Derefs to `str` and borrows as one, so maps keyed by `PlayerId` can be
+/// looked up with a plain `&str`. Serializes as a plain string.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema), schema(value_type = String))]
+pub struct PlayerId(Arc<str>);
+
+impl PlayerId {
+    pub fn new(id: impl Into<Arc<str>>) -> Self {
+        PlayerId(id.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Deref for PlayerId {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for PlayerId {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for PlayerId {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&str> for PlayerId {
+    fn from(id: &str) -> Self {
+        PlayerId(id.into())
+    }
+}
+
+impl From<String> for PlayerId {
+    fn from(id: String) -> Self {
+        PlayerId(id.into())
+    }
+}
+
+impl From<&String> for PlayerId {
+    fn from(id: &String) -> Self {
+        PlayerId(id.as_str().into())
+    }
+}
+
+impl From<PlayerId> for String {
+    fn from(id: PlayerId) -> Self {
+        id.0.to_string()
+    }
+}
+
+impl PartialEq<str> for PlayerId {
+    fn eq(&self, other: &str) -> bool {
+        &*self.0 == other
+    }
+}
+
+impl PartialEq<&str> for PlayerId {
+    fn eq(&self, other: &&str) -> bool {
+        &*self.0 == *other
+    }
+}
+
+impl PartialEq<String> for PlayerId {
+    fn eq(&self, other: &String) -> bool {
+        *self.0 == **other
+    }
+}
+
+impl PartialEq<PlayerId> for str {
+    fn eq(&self, other: &PlayerId) -> bool {
+        self == &*other.0
+    }
+}
+
+impl PartialEq<PlayerId> for &str {
+    fn eq(&self, other: &PlayerId) -> bool {
+        *self == &*other.0
+    }
+}
+
+impl fmt::Display for PlayerId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_lookup_by_str() {
+        let id = PlayerId::from("p1");
+        let shared = id.clone();
+        assert!(Arc::ptr_eq(&id.0, &shared.0)); // Clones share the string
+        assert_eq!(id, "p1");
+        assert_eq!(id.to_string(), "p1");
+
+        let bets = HashMap::from([(id, 50)]);
+        assert_eq!(bets.get("p1"), Some(&50));
+    }
+}
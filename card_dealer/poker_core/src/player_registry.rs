@@ -0,0 +1,121 @@
+//! The players seated at a table, indexed by id so looking one up during a
+//! hand doesn't scan the whole table.
+
+use std::collections::HashMap;
+use std::ops::{Index, IndexMut};
+
+use crate::player::Player;
+use crate::player_id::PlayerId;
+
+/// Seated players in seating order, plus a map from each id to its index.
+///
+/// Players can be changed in place through `get_mut` and `iter_mut`, but
+/// their `player_id` must not be: the index would go stale.
+#[derive(Default)]
+pub(crate) struct PlayerRegistry {
+    players: Vec<Player>,
+    seats: HashMap<PlayerId, usize>, // Index into `players` of each id
+}
+
+impl PlayerRegistry {
+    pub(crate) fn new(players: Vec<Player>) -> Self {
+        let mut registry = Self { players, seats: HashMap::new() };
+        registry.reindex();
+        registry
+    }
+
+    fn reindex(&mut self) {
+        self.seats = self.players.iter().enumerate().map(|(index, player)| (player.player_id.clone(), index)).collect();
+    }
+
+    pub(crate) fn players(&self) -> &Vec<Player> {
+        &self.players
+    }
+
+    pub(crate) fn index_of(&self, player_id: &str) -> Option<usize> {
+        self.seats.get(player_id).copied()
+    }
+
+    pub(crate) fn contains(&self, player_id: &str) -> bool {
+        self.seats.contains_key(player_id)
+    }
+
+    pub(crate) fn get(&self, player_id: &str) -> Option<&Player> {
+        self.index_of(player_id).map(|index| &self.players[index])
+    }
+
+    pub(crate) fn get_mut(&mut self, player_id: &str) -> Option<&mut Player> {
+        self.index_of(player_id).map(|index| &mut self.players[index])
+    }
+
+    /// The id as stored in the registry, so callers can share it rather than
+    /// allocate a new one.
+    pub(crate) fn id(&self, player_id: &str) -> Option<PlayerId> {
+        self.seats.get_key_value(player_id).map(|(id, _)| id.clone())
+    }
+
+    pub(crate) fn push(&mut self, player: Player) {
+        self.seats.insert(player.player_id.clone(), self.players.len());
+        self.players.push(player);
+    }
+
+    pub(crate) fn remove(&mut self, player_id: &str) -> Option<Player> {
+        let index = self.index_of(player_id)?;
+        let player = self.players.remove(index);
+        self.reindex(); // Everyone after the removed seat moved down one
+        Some(player)
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.players.len()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.players.is_empty()
+    }
+
+    pub(crate) fn iter(&self) -> std::slice::Iter<'_, Player> {
+        self.players.iter()
+    }
+
+    pub(crate) fn iter_mut(&mut self) -> std::slice::IterMut<'_, Player> {
+        self.players.iter_mut()
+    }
+
+    #[cfg(feature = "parallel")]
+    pub(crate) fn as_mut_slice(&mut self) -> &mut [Player] {
+        &mut self.players
+    }
+}
+
+impl Index<usize> for PlayerRegistry {
+    type Output = Player;
+
+    fn index(&self, index: usize) -> &Player {
+        &self.players[index]
+    }
+}
+
+impl IndexMut<usize> for PlayerRegistry {
+    fn index_mut(&mut self, index: usize) -> &mut Player {
+        &mut self.players[index]
+    }
+}
+
+impl<'a> IntoIterator for &'a PlayerRegistry {
+    type Item = &'a Player;
+    type IntoIter = std::slice::Iter<'a, Player>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut PlayerRegistry {
+    type Item = &'a mut Player;
+    type IntoIter = std::slice::IterMut<'a, Player>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
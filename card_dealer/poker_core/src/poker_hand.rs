@@ -0,0 +1,583 @@
+use crate::card_dealer::{Card, Suit};
+use crate::card_set::CardSet;
+use crate::game_error::GameError;
+use itertools::Itertools;
+use std::cmp::{Ordering, Reverse};
+use thiserror::Error;
+
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub enum HandRank {
+    HighCard,
+    OnePair,
+    TwoPair,
+    ThreeOfAKind,
+    Straight,
+    Flush,
+    FullHouse,
+    FourOfAKind,
+    StraightFlush,
+    FiveOfAKind,    // Only made with wild cards
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct Hand {
+    pub cards: Vec<Card>, // The cards forming the hand
+    pub rank: HandRank,   // The rank of the hand (e.g., Full House, Flush)
+}
+
+/// Why a set of cards can't be made into a `Hand`.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum HandError {
+    #[error("a hand is exactly five cards, got {0}")]
+    WrongCardCount(usize),      // `Hand::new` was given too few or too many cards
+
+    #[error("need at least five cards to make a hand, got {0}")]
+    TooFewCards(usize),         // `find_best_hand` has nothing to choose from
+
+    #[error("card {0} appears twice")]
+    DuplicateCard(Card),        // The same physical card was passed twice
+}
+
+/// Bad cards are bad input, so a `HandError` surfaces as `InvalidRequest`,
+/// or `DuplicateCard` for a repeated card.
+impl From<HandError> for GameError {
+    fn from(error: HandError) -> Self {
+        match error {
+            HandError::DuplicateCard(card) => GameError::DuplicateCard(card),
+            other => GameError::InvalidRequest(other.to_string()),
+        }
+    }
+}
+
+impl Hand {
+    /// Creates a new `Hand` instance by evaluating the given cards, which
+    /// must be exactly five distinct cards.
+    pub fn new(mut cards: Vec<Card>) -> Result<Self, HandError> {
+        if cards.len() != 5 {
+            return Err(HandError::WrongCardCount(cards.len()));
+        }
+        CardSet::try_from_cards(&cards).map_err(HandError::DuplicateCard)?;
+        cards.sort_by_key(|card| Reverse(card.rank)); // Sort by rank descending
+        let rank = evaluate_hand(&cards[..]);
+        Ok(Self { cards, rank })
+    }
+
+    /// Compares two hands to determine the winner.
+    pub fn compare_two_hands(&self, other: &Self) -> Ordering {
+        match self.rank.cmp(&other.rank) {
+            Ordering::Equal => {
+                // Sort and compare the highest-ranked cards as tie-breakers
+                let mut self_sorted = self.cards.clone();
+                let mut other_sorted = other.cards.clone();
+                self_sorted.sort_by_key(|card| Reverse(card.rank));
+                other_sorted.sort_by_key(|card| Reverse(card.rank));
+    
+                for (card1, card2) in self_sorted.iter().zip(&other_sorted) {
+                    match card1.rank.cmp(&card2.rank) {
+                        Ordering::Equal => continue,
+                        ordering => return ordering,
+                    }
+                }
+                Ordering::Equal
+            }
+            ordering => ordering,
+        }
+    }
+
+    /// The hand's strength as a `HandValue`, counting each copy of a card
+    /// dealt twice from a shoe of several decks.
+    pub fn value(&self) -> HandValue {
+        shoe_card_value(&self.cards)
+    }
+}
+
+/// Finds the best possible hand from a set of cards
+/// by generating all possible 5-card combinations and comparing them.
+/// Returns the best hand found, or an error if there are fewer than five
+/// cards or a card repeats.
+pub fn find_best_hand(cards: &[Card]) -> Result<Hand, HandError> {
+    CardSet::try_from_cards(cards).map_err(HandError::DuplicateCard)?;
+    cards
+        .iter()
+        .copied()
+        .combinations(5) // Generate all 5-card combinations
+        .max_by_key(|combination| five_card_value(combination.iter().copied().collect())) // Score without building a `Hand`
+        .ok_or(HandError::TooFewCards(cards.len()))
+        .and_then(Hand::new)
+}
+
+/// A hand's strength packed into one integer: the `HandRank` in the high
+/// bits, then the five card ranks from highest to lowest. Ordering matches
+/// `Hand::compare_two_hands`, so hot loops can compare values instead of
+/// building `Hand`s.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HandValue(u32);
+
+impl HandValue {
+    /// The category of the hand, e.g. `FullHouse`.
+    pub fn rank(self) -> HandRank {
+        HandRank::ALL[(self.0 >> 20) as usize].clone()
+    }
+}
+
+impl HandRank {
+    /// Every hand rank, weakest first.
+    pub const ALL: [HandRank; 10] = [
+        HandRank::HighCard,
+        HandRank::OnePair,
+        HandRank::TwoPair,
+        HandRank::ThreeOfAKind,
+        HandRank::Straight,
+        HandRank::Flush,
+        HandRank::FullHouse,
+        HandRank::FourOfAKind,
+        HandRank::StraightFlush,
+        HandRank::FiveOfAKind,
+    ];
+}
+
+/// The value of the best 5-card hand within `cards`, or `None` if there are
+/// fewer than five.
+pub fn hand_value(cards: CardSet) -> Option<HandValue> {
+    best_with(cards, CardSet::EMPTY).map(|(value, _)| value)
+}
+
+/// The value of the best Omaha hand: exactly two of `hole_cards` with
+/// exactly three of `board`. `None` with fewer than two hole cards or three
+/// board cards.
+pub fn omaha_hand_value(hole_cards: CardSet, board: CardSet) -> Option<HandValue> {
+    best_omaha(hole_cards, board).map(|(value, _)| value)
+}
+
+/// The best Omaha hand from `hole_cards` and `board`, using exactly two hole
+/// cards and exactly three from the board.
+pub fn find_best_omaha_hand(hole_cards: &[Card], board: &[Card]) -> Result<Hand, HandError> {
+    let hole_set = CardSet::try_from_cards(hole_cards).map_err(HandError::DuplicateCard)?;
+    let board_set = CardSet::try_from_cards(board).map_err(HandError::DuplicateCard)?;
+    if let Some(card) = (hole_set & board_set).iter().next() {
+        return Err(HandError::DuplicateCard(card));
+    }
+    let (_, cards) = best_omaha(hole_set, board_set).ok_or(HandError::TooFewCards(hole_cards.len().min(2) + board.len().min(3)))?;
+    Hand::new(cards.iter().collect())
+}
+
+/// The best hand from cards dealt out of a shoe of several decks, where the
+/// same card can come up twice and each copy plays as a card of its own.
+/// With `exactly_two` the hand is exactly two of `hole_cards` and three of
+/// `board`, as in Omaha. `None` without enough cards to make one.
+pub fn find_best_shoe_hand(hole_cards: &[Card], board: &[Card], exactly_two: bool) -> Option<Hand> {
+    let fives: Vec<Vec<Card>> = if exactly_two {
+        hole_cards.iter()
+            .copied()
+            .combinations(2)
+            .cartesian_product(board.iter().copied().combinations(3).collect::<Vec<_>>())
+            .map(|(hole, board)| [hole, board].concat())
+            .collect()
+    } else {
+        hole_cards.iter().chain(board).copied().combinations(5).collect()
+    };
+    let mut cards = fives.into_iter().max_by_key(|five| shoe_card_value(five))?;
+    cards.sort_by_key(|card| Reverse(card.rank));
+    let rank = shoe_card_value(&cards).rank();
+    Some(Hand { cards, rank })
+}
+
+fn best_omaha(hole_cards: CardSet, board: CardSet) -> Option<(HandValue, CardSet)> {
+    hole_cards
+        .iter()
+        .tuple_combinations()
+        .filter_map(|(first, second)| best_with(board, CardSet::from(first) | CardSet::from(second)))
+        .max_by_key(|(value, _)| *value)
+}
+
+/// The best hand made of `required` plus enough cards from `cards` to make
+/// five, with the five cards it uses.
+fn best_with(cards: CardSet, required: CardSet) -> Option<(HandValue, CardSet)> {
+    let (count, pick) = (cards.len(), 5usize.checked_sub(required.len())?);
+    if count < pick {
+        return None;
+    }
+    let mut positions = [0u8; 52];
+    for (slot, index) in positions.iter_mut().zip(BitIndices(cards.bits())) {
+        *slot = index;
+    }
+    // Walk every `pick`-of-`count` subset of positions in increasing order (Gosper's hack)
+    let mut subset: u64 = (1 << pick) - 1;
+    let mut best: Option<(HandValue, CardSet)> = None;
+    while subset < 1 << count {
+        let bits = BitIndices(subset).fold(required.bits(), |bits, position| bits | 1 << positions[position as usize]);
+        let hand = CardSet::from_bits(bits);
+        let value = five_card_value(hand);
+        if best.is_none_or(|(best, _)| value >= best) {
+            best = Some((value, hand));
+        }
+        if subset == 0 {
+            break; // Nothing to pick: `required` is already five cards
+        }
+        let lowest = subset & subset.wrapping_neg();
+        let ripple = subset + lowest;
+        subset = (((ripple ^ subset) >> 2) / lowest) | ripple;
+    }
+    best
+}
+
+/// The best hand within a set of cards that grows as the board is dealt.
+///
+/// Adding a card only scores the five-card combinations that use it, so a
+/// player's hand is updated on the turn and river instead of being
+/// re-evaluated from scratch.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PartialEvaluation {
+    cards: CardSet,
+    best: Option<(HandValue, CardSet)>,
+}
+
+impl PartialEvaluation {
+    /// Evaluates `cards` from scratch.
+    pub fn new(cards: CardSet) -> Self {
+        Self { cards, best: best_with(cards, CardSet::EMPTY) }
+    }
+
+    /// Every card seen so far.
+    pub fn cards(&self) -> CardSet {
+        self.cards
+    }
+
+    /// The value of the best hand, once there are five cards.
+    pub fn value(&self) -> Option<HandValue> {
+        self.best.map(|(value, _)| value)
+    }
+
+    /// The five cards making up the best hand.
+    pub fn best_cards(&self) -> Option<CardSet> {
+        self.best.map(|(_, cards)| cards)
+    }
+
+    /// Adds one card, scoring only the combinations that include it.
+    pub fn add(&mut self, card: Card) {
+        if self.cards.contains(card) {
+            return;
+        }
+        if let Some((value, hand)) = best_with(self.cards, card.into()) {
+            if self.best.is_none_or(|(best, _)| value >= best) {
+                self.best = Some((value, hand));
+            }
+        }
+        self.cards.insert(card);
+    }
+
+    /// Brings the evaluation up to date with `cards`. Cards already seen are
+    /// not re-scored; if any card has gone (a new hand, or a fold) the
+    /// evaluation starts over.
+    pub fn update(&mut self, cards: CardSet) {
+        if self.cards - cards != CardSet::EMPTY {
+            *self = Self::new(cards);
+            return;
+        }
+        for card in cards - self.cards {
+            self.add(card);
+        }
+    }
+}
+
+/// Positions of the set bits, lowest first.
+struct BitIndices(u64);
+
+impl Iterator for BitIndices {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        if self.0 == 0 {
+            return None;
+        }
+        let index = self.0.trailing_zeros() as u8;
+        self.0 &= self.0 - 1;
+        Some(index)
+    }
+}
+
+/// Scores exactly five cards with the same rules as `evaluate_hand`.
+fn five_card_value(hand: CardSet) -> HandValue {
+    let mut counts = [0u8; 13];
+    let mut flush = false;
+    for suit in Suit::ALL {
+        let ranks = hand.suit_ranks(suit);
+        flush |= ranks.count_ones() == 5;
+        for (rank, count) in counts.iter_mut().enumerate() {
+            *count += (ranks >> rank & 1) as u8;
+        }
+    }
+    value_of(&counts, flush)
+}
+
+/// Scores exactly five cards like `five_card_value`, but from a slice, so a
+/// card that repeats counts once for each copy.
+fn shoe_card_value(hand: &[Card]) -> HandValue {
+    let mut counts = [0u8; 13];
+    for card in hand {
+        counts[card.rank as usize] += 1;
+    }
+    value_of(&counts, check_flush(&count_suits(hand)))
+}
+
+/// Scores five cards given as the copies of each rank, twos first, and
+/// whether they are all one suit. Wild cards can make copies a real deck
+/// can't, so five of a kind is scored too.
+pub(crate) fn value_of(counts: &[u8; 13], flush: bool) -> HandValue {
+    let present = counts.iter().rev().fold(0u16, |ranks, &count| ranks << 1 | u16::from(count > 0));
+    let straight = present & present >> 1 & present >> 2 & present >> 3 & present >> 4 != 0;
+    let has = |wanted: u8| counts.contains(&wanted);
+    let pairs = counts.iter().filter(|&&count| count == 2).count();
+
+    let rank = match (flush, straight) {
+        _ if has(5) => HandRank::FiveOfAKind,
+        (true, true) => HandRank::StraightFlush,
+        _ if has(4) => HandRank::FourOfAKind,
+        _ if has(3) && has(2) => HandRank::FullHouse,
+        (true, false) => HandRank::Flush,
+        (false, true) => HandRank::Straight,
+        _ if has(3) => HandRank::ThreeOfAKind,
+        _ if pairs == 2 => HandRank::TwoPair,
+        _ if has(2) => HandRank::OnePair,
+        _ => HandRank::HighCard,
+    };
+
+    // Tie-breakers: every card's rank, highest first, four bits each
+    let mut value = rank as u32;
+    let mut packed = 0;
+    for (rank, &count) in counts.iter().enumerate().rev() {
+        for _ in 0..count {
+            if packed < 5 {
+                value = value << 4 | rank as u32;
+                packed += 1;
+            }
+        }
+    }
+    for _ in packed..5 {
+        value <<= 4;
+    }
+    HandValue(value)
+}
+
+/// Evaluates the rank of a hand based on the given cards.
+/// The hand is assumed to be sorted by rank in descending order.
+fn evaluate_hand(hand: &[Card]) -> HandRank {
+    let suits = count_suits(hand);
+    let ranks = count_ranks(hand);
+
+    let is_flush = check_flush(&suits);
+    let is_straight = check_straight(&ranks);
+
+    match (is_flush, is_straight) {
+        (true, true) => HandRank::StraightFlush,
+        _ if ranks.contains(&4) => HandRank::FourOfAKind,       // Check if any rank has 4 cards
+        _ if ranks.contains(&3) && ranks.contains(&2) => HandRank::FullHouse,
+        (true, false) => HandRank::Flush,
+        (false, true) => HandRank::Straight,
+        _ if ranks.contains(&3) => HandRank::ThreeOfAKind,
+        _ if ranks.iter().filter(|&&count| count == 2).count() == 2 => HandRank::TwoPair,
+        _ if ranks.contains(&2) => HandRank::OnePair,
+        _ => HandRank::HighCard,
+    }
+}
+
+
+/// Counts the number of occurrences of each suit.
+fn count_suits(hand: &[Card]) -> Vec<usize> {
+    let mut suits = vec![0; 5]; // The joker is a suit of its own, so never makes a flush
+    for card in hand {
+        suits[card.suit as usize] += 1;     // convert the enum to a usize and increment the count
+    }
+    suits
+}
+
+/// Counts the number of occurrences of each rank.
+fn count_ranks(hand: &[Card]) -> Vec<usize> {
+    let mut ranks = vec![0; 13];
+    for card in hand {
+        ranks[card.rank as usize] += 1;    // convert the enum to a usize and increment the count
+    }
+    ranks
+}
+
+/// Checks if the hand is a flush (all cards have the same suit).
+fn check_flush(suits: &[usize]) -> bool {
+    suits.contains(&5)   // return true if any suit count == 5
+}
+
+/// Checks if the hand is a straight (5 consecutive ranks).
+fn check_straight(ranks: &[usize]) -> bool {
+    let mut consecutive = 0;
+    // Each rank has been counted, so a consecutive straight would look like this: 0,1,1,1,1,1,0,0,0,0,0,0,0
+    for &count in ranks {
+        if count > 0 {
+            consecutive += 1;
+            if consecutive == 5 {
+                return true;
+            }
+        } else {
+            consecutive = 0;
+        }
+    }
+    false
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card_dealer::{Card, Rank, Suit};
+
+    /// Helper function to create a card
+    fn create_card(rank: Rank, suit: Suit) -> Card {
+        Card { rank, suit }
+    }
+
+    #[test]
+    fn test_best_hand_scenario() {
+        // Community cards: 9H, JH, 5C, AS, JD
+        let community_cards = vec![
+            create_card(Rank::Four, Suit::Spades),
+            create_card(Rank::Eight, Suit::Diamonds),
+            create_card(Rank::Eight, Suit::Hearts),
+            create_card(Rank::Two, Suit::Clubs),
+            create_card(Rank::Jack, Suit::Clubs),
+        ];
+
+        // Player 1: 5H, 7S
+        let player1_cards = vec![
+            create_card(Rank::Six, Suit::Spades),
+            create_card(Rank::King, Suit::Spades),
+        ];
+
+        // Player 2: KC, 9H
+        let player2_cards = vec![
+            create_card(Rank::Four, Suit::Diamonds),
+            create_card(Rank::Six, Suit::Clubs),
+        ];
+
+        // Player 3: 10D, AH
+        let player3_cards = vec![
+            create_card(Rank::Seven, Suit::Diamonds),
+            create_card(Rank::Jack, Suit::Spades),
+        ];
+
+        // Evaluate the best hand for each player
+        let player1_hand = find_best_hand(&[player1_cards.clone(), community_cards.clone()].concat()).unwrap();
+        let player2_hand = find_best_hand(&[player2_cards.clone(), community_cards.clone()].concat()).unwrap();
+        let player3_hand = find_best_hand(&[player3_cards.clone(), community_cards.clone()].concat()).unwrap();
+
+        // Compare hands to determine the winner
+        let mut best_hand = &player1_hand;
+        if player2_hand.compare_two_hands(best_hand) == Ordering::Greater {
+            best_hand = &player2_hand;
+        }
+        if player3_hand.compare_two_hands(best_hand) == Ordering::Greater {
+            best_hand = &player3_hand;
+        }
+
+        // Print the results for debugging
+        println!("Player 1 Hand: {:?}, Rank: {:?}", player1_hand.cards, player1_hand.rank);
+        println!("Player 2 Hand: {:?}, Rank: {:?}", player2_hand.cards, player2_hand.rank);
+        println!("Player 3 Hand: {:?}, Rank: {:?}", player3_hand.cards, player3_hand.rank);
+        println!("Winning Hand: {:?}, Rank: {:?}", best_hand.cards, best_hand.rank);
+
+        // Assert the correct winner
+        assert_eq!(best_hand.rank, HandRank::TwoPair); // The strongest expected hand
+    }
+
+    #[test]
+    fn test_hand_value_agrees_with_find_best_hand() {
+        use rand::rngs::StdRng;
+        use rand::seq::SliceRandom;
+        use rand::SeedableRng;
+
+        let mut rng = StdRng::seed_from_u64(11);
+        let mut deck = crate::card_dealer::Deck::all_cards();
+        for _ in 0..300 {
+            deck.shuffle(&mut rng);
+            let (first, second) = (&deck[..7], &deck[7..14]);
+            let (a, b) = (find_best_hand(first).unwrap(), find_best_hand(second).unwrap());
+            let (value_a, value_b) = (hand_value(first.iter().copied().collect()).unwrap(), hand_value(second.iter().copied().collect()).unwrap());
+            assert_eq!(value_a.rank(), a.rank);
+            assert_eq!(value_a.cmp(&value_b), a.compare_two_hands(&b), "{first:?} vs {second:?}");
+        }
+        assert_eq!(hand_value(deck[..4].iter().copied().collect()), None);
+    }
+
+    #[test]
+    fn test_partial_evaluation_matches_a_full_evaluation() {
+        use crate::card_dealer::parse_cards;
+
+        let hole: CardSet = parse_cards("9h Th").unwrap().into_iter().collect();
+        let board = parse_cards("2c Jh Qh 9c Kh").unwrap();
+        let mut partial = PartialEvaluation::new(hole);
+        assert_eq!(partial.value(), None);
+        for street in [3, 4, 5] {
+            let cards = hole | board[..street].iter().copied().collect();
+            partial.update(cards);
+            assert_eq!(partial, PartialEvaluation::new(cards), "after {street} board cards");
+        }
+        assert_eq!(partial.value().map(HandValue::rank), Some(HandRank::StraightFlush));
+        partial.update(hole); // The board went away: start over
+        assert_eq!(partial.cards(), hole);
+        assert_eq!(partial.value(), None);
+    }
+
+    #[test]
+    fn test_hand_requires_five_distinct_cards() {
+        use crate::card_dealer::parse_cards;
+
+        let cards = parse_cards("Ah Kh Qh Jh Th 9h").unwrap();
+        assert_eq!(Hand::new(cards[..5].to_vec()).unwrap().rank, HandRank::StraightFlush);
+        assert_eq!(Hand::new(cards.clone()).err(), Some(HandError::WrongCardCount(6)));
+        assert_eq!(Hand::new(cards[..4].to_vec()).err(), Some(HandError::WrongCardCount(4)));
+        let doubled = parse_cards("Ah Kh Qh Jh Ah").unwrap();
+        assert_eq!(Hand::new(doubled.clone()).err(), Some(HandError::DuplicateCard(cards[0])));
+
+        assert_eq!(find_best_hand(&cards[..4]).err(), Some(HandError::TooFewCards(4)));
+        assert_eq!(find_best_hand(&doubled).err(), Some(HandError::DuplicateCard(cards[0])));
+        assert!(matches!(GameError::from(HandError::TooFewCards(4)), GameError::InvalidRequest(_)));
+    }
+
+    #[test]
+    fn test_omaha_uses_exactly_two_hole_cards() {
+        use crate::card_dealer::parse_cards;
+
+        let (hole, board) = (parse_cards("Ah Ks Qd Jc 3s").unwrap(), parse_cards("2h 5h 8h 9h Tc").unwrap());
+        // One heart in hand makes no flush; Q J with T 9 8 is the best straight
+        let hand = find_best_omaha_hand(&hole[..4], &board).unwrap();
+        assert_eq!(hand.rank, HandRank::Straight);
+        assert_eq!(find_best_hand(&[&hole[..4], &board[..]].concat()).unwrap().rank, HandRank::Flush);
+        let to_set = |cards: &[Card]| cards.iter().copied().collect::<CardSet>();
+        assert_eq!(omaha_hand_value(to_set(&hole), to_set(&board)).map(HandValue::rank), Some(HandRank::Straight));
+        assert_eq!(omaha_hand_value(to_set(&hole), to_set(&board[..2])), None);
+        assert_eq!(find_best_omaha_hand(&hole, &hole[..3]).err(), Some(HandError::DuplicateCard(hole[0])));
+    }
+
+    #[test]
+    fn test_shoe_hands_count_each_copy_of_a_card() {
+        use crate::card_dealer::parse_cards;
+
+        // From two decks the ace of hearts in hand pairs the one on the board
+        let (hole, board) = (parse_cards("Ah Kd").unwrap(), parse_cards("Ah Ad 7c 2s 9h").unwrap());
+        let hand = find_best_shoe_hand(&hole, &board, false).unwrap();
+        assert_eq!(hand.rank, HandRank::ThreeOfAKind);
+        assert_eq!(hand.cards, parse_cards("Ah Ah Ad Kd 9h").unwrap());
+        assert_eq!(hand.value().rank(), HandRank::ThreeOfAKind);
+        // Only four different cards, still two pair
+        let repeats = parse_cards("Ah Kd 7s 7s 2c").unwrap();
+        assert_eq!(find_best_shoe_hand(&hole, &repeats, false).unwrap().rank, HandRank::TwoPair);
+        assert!(find_best_shoe_hand(&hole, &board[..2], false).is_none());
+
+        // Omaha still plays exactly two hole cards
+        let hand = find_best_shoe_hand(&parse_cards("Ah 2d Kc Qc").unwrap(), &board, true).unwrap();
+        assert_eq!(hand.rank, HandRank::FullHouse);
+        assert!(find_best_omaha_hand(&parse_cards("Ah 2d Kc Qc").unwrap(), &board).is_err());
+    }
+}
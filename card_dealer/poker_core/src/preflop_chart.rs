@@ -0,0 +1,250 @@
+//! Preflop starting-hand charts: which hands to open, call a raise with or
+//! 3-bet from each position, for bots and training hints.
+//!
+//! Charts are written one range per line, `position.action = range`:
+//!
+//! ```text
+//! # Comments start with a hash
+//! button.open = 22+, A2s+, K9s+, ATo+
+//! button.call = JJ-22, AJs-A2s, KQo
+//! button.three_bet = QQ+, AKs, AKo
+//! ```
+//!
+//! Positions are `early`, `middle`, `cutoff`, `button`, `small_blind` and
+//! `big_blind`; actions are `open`, `call` and `three_bet`. A chart file
+//! only needs the lines it changes: the rest come from the built-in chart.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::LazyLock;
+
+use crate::card_dealer::Card;
+use crate::game_error::GameError;
+use crate::range::Range;
+
+/// The built-in chart, a standard six-handed one, in the chart file format.
+const BUILTIN: &str = "
+early.open = 77+, ATs+, KTs+, QTs+, JTs, AJo+, KQo
+early.call = TT-77, AQs-ATs, KQs, AQo
+early.three_bet = JJ+, AKs, AKo
+middle.open = 55+, A8s+, K9s+, Q9s+, J9s+, T9s, ATo+, KJo+
+middle.call = TT-55, AQs-A9s, KJs+, QJs, JTs, AQo-AJo
+middle.three_bet = JJ+, AKs, AKo
+cutoff.open = 22+, A2s+, K8s+, Q9s+, J9s+, T8s+, 98s, 87s, 76s, A9o+, KTo+, QTo+, JTo
+cutoff.call = JJ-22, AQs-A9s, KJs+, QJs, JTs, T9s, AQo-AJo, KQo
+cutoff.three_bet = QQ+, AKs, A5s-A4s, AKo
+button.open = 22+, A2s+, K5s+, Q7s+, J7s+, T7s+, 97s+, 86s+, 75s+, 65s, 54s, A5o+, K9o+, Q9o+, J9o+, T9o
+button.call = JJ-22, AJs-A2s, K9s+, Q9s+, J9s+, T8s+, 98s, 87s, 76s, AQo-ATo, KJo+, QJo
+button.three_bet = QQ+, AKs, AQs, A5s-A4s, AKo
+small_blind.open = 22+, A2s+, K7s+, Q8s+, J8s+, T8s+, 98s, 87s, 76s, A8o+, KTo+, QTo+, JTo
+small_blind.call = TT-22, AQs-A8s, KTs+, QTs+, JTs, T9s, AJo+, KQo
+small_blind.three_bet = JJ+, AKs, AQs, A5s, AKo
+big_blind.open = TT+, AQs+, AKo
+big_blind.call = 22+, A2s+, K2s+, Q6s+, J7s+, T7s+, 96s+, 85s+, 75s+, 64s+, 54s, A2o+, K8o+, Q9o+, J9o+, T9o, 98o
+big_blind.three_bet = QQ+, AKs, AQs, A5s-A4s, AKo
+";
+
+static BUILTIN_CHART: LazyLock<PreflopChart> = LazyLock::new(|| {
+    let mut chart = PreflopChart { positions: Default::default() };
+    chart.apply(BUILTIN).expect("the built-in chart parses");
+    chart
+});
+
+/// Where a seat acts from, preflop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "snake_case"))]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub enum Position {
+    Early,      // The first seats after the blinds
+    Middle,     // Between early position and the cutoff
+    Cutoff,     // Just before the button
+    Button,     // Heads-up, the small blind as well
+    SmallBlind,
+    BigBlind,
+}
+
+impl Position {
+    pub const ALL: [Position; 6] = [
+        Position::Early,
+        Position::Middle,
+        Position::Cutoff,
+        Position::Button,
+        Position::SmallBlind,
+        Position::BigBlind,
+    ];
+
+    /// The position of the seat `after_button` places after the button,
+    /// which is 0, at a table of `players`.
+    pub fn of_seat(after_button: usize, players: usize) -> Position {
+        if players <= 2 {
+            return if after_button == 0 { Position::Button } else { Position::BigBlind };
+        }
+        match after_button {
+            0 => Position::Button,
+            1 => Position::SmallBlind,
+            2 => Position::BigBlind,
+            seat if seat + 1 == players => Position::Cutoff,
+            seat => {
+                // The seats before the cutoff are split, early ones taking the odd seat
+                let before_cutoff = players - 4;
+                if seat - 3 < before_cutoff.div_ceil(2) { Position::Early } else { Position::Middle }
+            }
+        }
+    }
+
+    /// The name used in chart files, e.g. `small_blind`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Position::Early => "early",
+            Position::Middle => "middle",
+            Position::Cutoff => "cutoff",
+            Position::Button => "button",
+            Position::SmallBlind => "small_blind",
+            Position::BigBlind => "big_blind",
+        }
+    }
+}
+
+/// What a chart says to do with a hand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(rename_all = "snake_case"))]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub enum ChartAdvice {
+    Fold,       // Or check, when that's free
+    Call,
+    Raise,      // Open, or 3-bet facing a raise
+}
+
+/// The hands one position plays.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PositionChart {
+    pub open: Range,        // Raised first in
+    pub call: Range,        // Called facing a raise
+    pub three_bet: Range,   // Re-raised facing a raise
+}
+
+/// A starting-hand chart covering every position.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreflopChart {
+    positions: [PositionChart; 6], // In `Position::ALL` order
+}
+
+impl PreflopChart {
+    /// The built-in chart.
+    pub fn builtin() -> &'static PreflopChart {
+        &BUILTIN_CHART
+    }
+
+    /// Reads a chart file, taking any line it leaves out from the built-in
+    /// chart.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let text = fs::read_to_string(path)?;
+        text.parse().map_err(|err: GameError| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))
+    }
+
+    pub fn position(&self, position: Position) -> &PositionChart {
+        &self.positions[Self::index(position)]
+    }
+
+    /// What to do with `hole_cards` from `position`, first in or facing a raise.
+    pub fn advise(&self, position: Position, hole_cards: &[Card], facing_raise: bool) -> ChartAdvice {
+        let chart = self.position(position);
+        match () {
+            _ if !facing_raise && chart.open.contains(hole_cards) => ChartAdvice::Raise,
+            _ if facing_raise && chart.three_bet.contains(hole_cards) => ChartAdvice::Raise,
+            _ if facing_raise && chart.call.contains(hole_cards) => ChartAdvice::Call,
+            _ => ChartAdvice::Fold,
+        }
+    }
+
+    fn index(position: Position) -> usize {
+        Position::ALL.iter().position(|&candidate| candidate == position).expect("every position is listed")
+    }
+
+    /// Applies the lines of a chart file on top of this chart.
+    fn apply(&mut self, text: &str) -> Result<(), GameError> {
+        for (number, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let invalid = |reason: &str| GameError::InvalidRequest(format!("chart line {}: {reason}", number + 1));
+            let (key, range) = line.split_once('=').ok_or_else(|| invalid("expected `position.action = range`"))?;
+            let (position, action) = key.trim().split_once('.').ok_or_else(|| invalid("expected `position.action`"))?;
+            let position = Position::ALL.into_iter()
+                .find(|candidate| candidate.name() == position)
+                .ok_or_else(|| invalid(&format!("unknown position {position:?}")))?;
+            let range = if range.trim().is_empty() {
+                Range::default() // Plays no hands this way
+            } else {
+                range.parse().map_err(|err: GameError| invalid(&err.to_string()))?
+            };
+            let chart = &mut self.positions[Self::index(position)];
+            match action {
+                "open" => chart.open = range,
+                "call" => chart.call = range,
+                "three_bet" => chart.three_bet = range,
+                _ => return Err(invalid(&format!("unknown action {action:?}"))),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Parses a chart file's contents, taking any line it leaves out from the
+/// built-in chart.
+impl FromStr for PreflopChart {
+    type Err = GameError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut chart = PreflopChart::builtin().clone();
+        chart.apply(text)?;
+        Ok(chart)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card_dealer::parse_cards;
+
+    fn advise(chart: &PreflopChart, position: Position, hole_cards: &str, facing_raise: bool) -> ChartAdvice {
+        chart.advise(position, &parse_cards(hole_cards).unwrap(), facing_raise)
+    }
+
+    #[test]
+    fn test_builtin_chart() {
+        let chart = PreflopChart::builtin();
+        assert_eq!(advise(chart, Position::Button, "Kh 6h", false), ChartAdvice::Raise);
+        assert_eq!(advise(chart, Position::Early, "Kh 6h", false), ChartAdvice::Fold);
+        assert_eq!(advise(chart, Position::Cutoff, "Ah Ad", true), ChartAdvice::Raise);
+        assert_eq!(advise(chart, Position::Cutoff, "9c 9d", true), ChartAdvice::Call);
+        assert_eq!(advise(chart, Position::BigBlind, "7c 2d", true), ChartAdvice::Fold);
+        assert!(chart.position(Position::BigBlind).call.len() > chart.position(Position::Early).call.len());
+
+        assert_eq!(Position::of_seat(0, 2), Position::Button);
+        assert_eq!(Position::of_seat(1, 2), Position::BigBlind);
+        let six_max: Vec<Position> = (0..6).map(|seat| Position::of_seat(seat, 6)).collect();
+        assert_eq!(six_max, [Position::Button, Position::SmallBlind, Position::BigBlind, Position::Early, Position::Middle, Position::Cutoff]);
+        assert_eq!(Position::of_seat(5, 9), Position::Early); // Three early seats and two middle ones at nine-handed
+        assert_eq!(Position::of_seat(6, 9), Position::Middle);
+    }
+
+    #[test]
+    fn test_chart_file_overrides_the_builtin() {
+        let chart: PreflopChart = "# Tighter from the button\nbutton.open = 99+, AQs+\nbig_blind.three_bet =\n".parse().unwrap();
+        assert_eq!(advise(&chart, Position::Button, "Kh 6h", false), ChartAdvice::Fold);
+        assert_eq!(advise(&chart, Position::BigBlind, "Ah Ad", true), ChartAdvice::Call); // Still in the calling range
+        assert_eq!(chart.position(Position::Cutoff), PreflopChart::builtin().position(Position::Cutoff));
+
+        for bad in ["button = AA", "dealer.open = AA", "button.limp = AA", "button.open = AKx"] {
+            assert!(bad.parse::<PreflopChart>().is_err(), "{bad}");
+        }
+        let path = std::env::temp_dir().join(format!("poker_core_chart_{}.txt", std::process::id()));
+        fs::write(&path, "early.open = AA").unwrap();
+        assert_eq!(PreflopChart::load(&path).unwrap().position(Position::Early).open.len(), 6);
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,48 @@
+//! The house's rake: a share of every pot the table keeps, up to a cap per
+//! hand.
+
+use crate::chips::Chips;
+
+/// Largest share of a pot a table may rake, in percent.
+pub const MAX_RAKE_PERCENT: u8 = 10;
+
+/// How much of each hand's pots the house keeps.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct RakeRules {
+    pub percent: u8,           // Share of the called chips taken, rounded down; no rake at zero
+    pub cap: Option<Chips>,    // Most taken from one hand; uncapped when unset
+    pub no_flop_no_drop: bool, // No rake from a hand that ended before the flop
+}
+
+impl RakeRules {
+    /// Checks the share is one a table may take.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.percent > MAX_RAKE_PERCENT {
+            return Err(format!("the rake may be at most {MAX_RAKE_PERCENT}% of the pot"));
+        }
+        Ok(())
+    }
+
+    /// The rake taken from a hand whose pots hold `called` chips that were
+    /// called, uncalled bets left out.
+    pub fn rake_for(&self, called: Chips) -> Chips {
+        let rake = Chips::new((u128::from(called.get()) * u128::from(self.percent) / 100) as u64);
+        self.cap.map_or(rake, |cap| rake.min(cap))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rake_is_capped() {
+        let rules = RakeRules { percent: 5, cap: Some(Chips::new(30)), no_flop_no_drop: false };
+        assert_eq!(rules.rake_for(Chips::new(199)), Chips::new(9));
+        assert_eq!(rules.rake_for(Chips::new(10_000)), Chips::new(30));
+        assert_eq!(RakeRules::default().rake_for(Chips::new(10_000)), Chips::ZERO);
+        assert!(RakeRules { percent: 11, ..rules }.validate().is_err());
+    }
+}
@@ -0,0 +1,207 @@
+//! Hand ranges in the usual shorthand, e.g. `"QQ+, AKs, A5s-A2s, KhQh"`.
+
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use crate::card_dealer::{Card, Rank, Suit};
+use crate::card_set::CardSet;
+use crate::game_error::GameError;
+
+/// Which suit combinations of two ranks a shorthand hand covers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Suitedness {
+    Any,        // `AK`: suited and offsuit
+    Suited,     // `AKs`
+    Offsuit,    // `AKo`
+}
+
+/// A set of two-card starting hands, each combination of cards listed once.
+///
+/// Parsed from comma- or space-separated parts, each one of:
+/// - a pair, `QQ`, or every pair from it up, `QQ+`, or between two, `22-55`;
+/// - two ranks, `AK`, suited only, `AKs`, or offsuit only, `AKo`;
+/// - every kicker up to one below the top card, `ATs+`, or between two,
+///   `A2s-A5s`;
+/// - exact cards, `AhKh`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Range {
+    combos: Vec<[Card; 2]>, // Higher rank first, in the order parsed
+}
+
+impl Range {
+    /// Every combination in the range.
+    pub fn combos(&self) -> &[[Card; 2]] {
+        &self.combos
+    }
+
+    pub fn len(&self) -> usize {
+        self.combos.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.combos.is_empty()
+    }
+
+    /// True if `hole_cards` are one of the combinations, in either order.
+    pub fn contains(&self, hole_cards: &[Card]) -> bool {
+        let [first, second] = hole_cards else { return false };
+        self.combos.iter().any(|combo| *combo == [*first, *second] || *combo == [*second, *first])
+    }
+
+    /// The combinations that don't use any of `dead`, e.g. the hero's hole
+    /// cards and the board.
+    pub fn live(&self, dead: CardSet) -> impl Iterator<Item = [Card; 2]> + '_ {
+        self.combos.iter().copied().filter(move |combo| !dead.contains(combo[0]) && !dead.contains(combo[1]))
+    }
+
+    fn add(&mut self, seen: &mut HashSet<CardSet>, combo: [Card; 2]) {
+        if seen.insert(combo.iter().copied().collect()) {
+            self.combos.push(combo);
+        }
+    }
+}
+
+impl FromStr for Range {
+    type Err = GameError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut range = Range::default();
+        let mut seen = HashSet::new();
+        for part in text.split(|c: char| c.is_whitespace() || c == ',').filter(|part| !part.is_empty()) {
+            for combo in expand(part)? {
+                range.add(&mut seen, combo);
+            }
+        }
+        if range.is_empty() {
+            return Err(GameError::InvalidRequest("a range needs at least one hand".to_string()));
+        }
+        Ok(range)
+    }
+}
+
+/// The combinations one part of a range stands for.
+fn expand(part: &str) -> Result<Vec<[Card; 2]>, GameError> {
+    let invalid = || GameError::InvalidRequest(format!("invalid range part {part:?}"));
+    if let Some((first, second)) = exact_cards(part) {
+        if first == second || first.is_joker() || second.is_joker() {
+            return Err(invalid());
+        }
+        return Ok(vec![if first.rank >= second.rank { [first, second] } else { [second, first] }]);
+    }
+
+    let (from, to) = match part.split_once('-') {
+        Some((low, high)) => {
+            let (low, high) = (shorthand(low).ok_or_else(invalid)?, shorthand(high).ok_or_else(invalid)?);
+            let same_shape = low.2 == high.2 && (low.0 == low.1) == (high.0 == high.1);
+            // Pairs run on both ranks; other hands keep their top card and run on the kicker
+            if !same_shape || (low.0 != low.1 && low.0 != high.0) {
+                return Err(invalid());
+            }
+            (low, high)
+        }
+        None => match part.strip_suffix('+') {
+            Some(base) => {
+                let base = shorthand(base).ok_or_else(invalid)?;
+                let top = if base.0 == base.1 { (Rank::Ace, Rank::Ace, base.2) } else { (base.0, below(base.0), base.2) };
+                (base, top)
+            }
+            None => {
+                let hand = shorthand(part).ok_or_else(invalid)?;
+                (hand, hand)
+            }
+        },
+    };
+
+    let (from, to) = if from.1 <= to.1 { (from, to) } else { (to, from) };
+    Ok(Rank::ALL.into_iter()
+        .filter(|&rank| rank >= from.1 && rank <= to.1)
+        .flat_map(|rank| {
+            let high = if from.0 == from.1 { rank } else { from.0 };
+            combos(high, rank, from.2)
+        })
+        .collect())
+}
+
+/// Four characters naming two exact cards, e.g. `AhKh`.
+fn exact_cards(part: &str) -> Option<(Card, Card)> {
+    if part.len() != 4 || !part.is_char_boundary(2) {
+        return None;
+    }
+    Some((part[..2].parse().ok()?, part[2..].parse().ok()?))
+}
+
+/// Two ranks and an optional `s` or `o`, e.g. `AKs` or `TT`. The higher
+/// rank comes first whichever way round they are written.
+fn shorthand(text: &str) -> Option<(Rank, Rank, Suitedness)> {
+    let mut chars = text.chars();
+    let (first, second) = (Rank::from_letter(chars.next()?)?, Rank::from_letter(chars.next()?)?);
+    let suitedness = match chars.next() {
+        None => Suitedness::Any,
+        Some('s' | 'S') => Suitedness::Suited,
+        Some('o' | 'O') => Suitedness::Offsuit,
+        Some(_) => return None,
+    };
+    if chars.next().is_some() || (first == second && suitedness != Suitedness::Any) {
+        return None;
+    }
+    Some((first.max(second), first.min(second), suitedness))
+}
+
+/// The rank one below `rank`, or `rank` itself for a two.
+fn below(rank: Rank) -> Rank {
+    Rank::ALL.into_iter().rev().find(|&lower| lower < rank).unwrap_or(rank)
+}
+
+fn combos(high: Rank, low: Rank, suitedness: Suitedness) -> Vec<[Card; 2]> {
+    let mut combos = Vec::new();
+    for (index, &first) in Suit::ALL.iter().enumerate() {
+        for &second in &Suit::ALL[if high == low { index + 1 } else { 0 }..] {
+            let wanted = match suitedness {
+                Suitedness::Any => true,
+                Suitedness::Suited => first == second,
+                Suitedness::Offsuit => first != second,
+            };
+            if wanted {
+                combos.push([Card { rank: high, suit: first }, Card { rank: low, suit: second }]);
+            }
+        }
+    }
+    combos
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count(text: &str) -> usize {
+        text.parse::<Range>().unwrap().len()
+    }
+
+    #[test]
+    fn test_shorthand_expands_to_combos() {
+        assert_eq!(count("AA"), 6);
+        assert_eq!(count("AKs"), 4);
+        assert_eq!(count("AKo"), 12);
+        assert_eq!(count("ka"), 16);
+        assert_eq!(count("QQ+"), 18);
+        assert_eq!(count("22-44"), 18);
+        assert_eq!(count("ATs+"), 16);      // ATs, AJs, AQs, AKs
+        assert_eq!(count("A5s-A2s"), 16);
+        assert_eq!(count("KhQh"), 1);
+        assert_eq!(count("AKs, AK, AhKh"), 16); // Overlaps count once
+        assert_eq!(count("22+, A2+, K2+, Q2+, J2+, T2+, 92+, 82+, 72+, 62+, 52+, 42+, 32"), 1326);
+
+        for bad in ["", "AAs", "AKx", "A", "AK-QJ", "AKs-A2o", "AhAh", "Zz+"] {
+            assert!(bad.parse::<Range>().is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn test_dead_cards_block_combos() {
+        let range: Range = "AA, AKs".parse().unwrap();
+        let dead: CardSet = crate::card_dealer::parse_cards("Ah 7c").unwrap().into_iter().collect();
+        assert_eq!(range.live(dead).count(), 3 + 3);
+        assert!(range.contains(&crate::card_dealer::parse_cards("Ks As").unwrap()));
+        assert!(!range.contains(&crate::card_dealer::parse_cards("Ks Ad").unwrap()));
+    }
+}
@@ -0,0 +1,20 @@
+use crate::chips::Chips;
+use crate::player_id::PlayerId;
+
+/// A seated player's durable state.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PlayerSnapshot {
+    pub player_id: PlayerId,
+    pub display_name: String,
+    pub table_position: usize,
+    pub chip_stack: Chips,
+}
+
+/// Everything needed to reopen the table between hands.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GameSnapshot {
+    pub hand_number: u64,
+    pub players: Vec<PlayerSnapshot>,
+}
@@ -0,0 +1,159 @@
+//! An interface to game-theory-optimal solvers. The engine doesn't solve
+//! spots itself: a `SolverBackend` hands a `SolverSpot` to whatever solver
+//! a study tool plugs in, and gets back how often each hand in the range of
+//! the player to act takes each action.
+
+use crate::card_dealer::Card;
+use crate::card_set::CardSet;
+use crate::chips::Chips;
+use crate::game_error::GameError;
+use crate::range::Range;
+
+/// Frequencies of a hand's actions may be this far off summing to one.
+const FREQUENCY_TOLERANCE: f64 = 1e-3;
+
+/// A postflop spot between two players, the one out of position to act.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SolverSpot {
+    pub ranges: [Range; 2],     // Out of position first, then in position
+    pub board: Vec<Card>,       // The flop, turn or river
+    pub pot: Chips,
+    pub effective_stack: Chips, // Chips behind the shorter stack
+    pub bet_sizes: Vec<u32>,    // Bets and raises offered, in percent of the pot
+}
+
+impl SolverSpot {
+    /// Checks the spot is one a solver can be asked about: a flop, turn or
+    /// river board without repeats or jokers, chips in the pot, and hands
+    /// left in both ranges once the board blocks its cards.
+    pub fn validate(&self) -> Result<(), GameError> {
+        if !(3..=5).contains(&self.board.len()) {
+            return Err(GameError::InvalidRequest("a solver needs a flop, turn or river".to_string()));
+        }
+        let board = CardSet::try_from_cards(&self.board).map_err(GameError::DuplicateCard)?;
+        if board.contains(Card::JOKER) {
+            return Err(GameError::InvalidRequest("a solver can't play a joker".to_string()));
+        }
+        if self.pot.is_zero() {
+            return Err(GameError::InvalidRequest("the pot needs chips in it".to_string()));
+        }
+        if self.bet_sizes.contains(&0) {
+            return Err(GameError::InvalidRequest("bet sizes must be above zero".to_string()));
+        }
+        if self.ranges.iter().any(|range| range.live(board).next().is_none()) {
+            return Err(GameError::InvalidRequest("the board blocks every hand in a range".to_string()));
+        }
+        Ok(())
+    }
+
+    /// The hands in `ranges[player]` the board doesn't block.
+    pub fn live_combos(&self, player: usize) -> Vec<[Card; 2]> {
+        self.ranges[player].live(self.board.iter().copied().collect()).collect()
+    }
+}
+
+/// How often one hand takes each action.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct ComboStrategy {
+    pub cards: [Card; 2],
+    pub frequencies: Vec<f64>, // One per action, in the strategy's order, summing to one
+}
+
+/// A solver's strategy for the player to act.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SolverStrategy {
+    actions: Vec<String>,       // As the solver names them, e.g. `check` or `bet 75`
+    combos: Vec<ComboStrategy>,
+}
+
+impl SolverStrategy {
+    /// Checks a solver's answer: every hand needs a frequency for each
+    /// action, and its frequencies must sum to one.
+    pub fn new(actions: Vec<String>, combos: Vec<ComboStrategy>) -> Result<Self, String> {
+        if actions.is_empty() {
+            return Err("the strategy has no actions".to_string());
+        }
+        for combo in &combos {
+            let hand = format!("{}{}", combo.cards[0], combo.cards[1]);
+            if combo.frequencies.len() != actions.len() {
+                return Err(format!("{hand} has {} frequencies for {} actions", combo.frequencies.len(), actions.len()));
+            }
+            let total: f64 = combo.frequencies.iter().sum();
+            if combo.frequencies.iter().any(|frequency| !(0.0..=1.0).contains(frequency)) || (total - 1.0).abs() > FREQUENCY_TOLERANCE {
+                return Err(format!("{hand}'s frequencies don't add up to one"));
+            }
+        }
+        Ok(Self { actions, combos })
+    }
+
+    pub fn actions(&self) -> &[String] {
+        &self.actions
+    }
+
+    pub fn combos(&self) -> &[ComboStrategy] {
+        &self.combos
+    }
+
+    /// How often the whole range takes each action, every hand weighted
+    /// equally.
+    pub fn overall(&self) -> Vec<f64> {
+        let mut totals = vec![0.0; self.actions.len()];
+        for combo in &self.combos {
+            for (total, frequency) in totals.iter_mut().zip(&combo.frequencies) {
+                *total += frequency;
+            }
+        }
+        let hands = self.combos.len().max(1) as f64;
+        totals.into_iter().map(|total| total / hands).collect()
+    }
+}
+
+/// A solver a study tool plugs in, e.g. a wrapper around an external
+/// process. Solving can take a long time, so callers run it off any thread
+/// that has other work to do.
+pub trait SolverBackend: Send + Sync {
+    /// Short name shown with its strategies.
+    fn name(&self) -> &str;
+
+    /// Solves a spot that passed `SolverSpot::validate`, returning the
+    /// strategy of the player out of position.
+    fn solve(&self, spot: &SolverSpot) -> Result<SolverStrategy, GameError>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card_dealer::parse_cards;
+
+    fn combo(cards: &str, frequencies: &[f64]) -> ComboStrategy {
+        let cards = parse_cards(cards).unwrap();
+        ComboStrategy { cards: [cards[0], cards[1]], frequencies: frequencies.to_vec() }
+    }
+
+    #[test]
+    fn test_spot_and_strategy_checks() {
+        let spot = SolverSpot {
+            ranges: ["AA, KK".parse().unwrap(), "AK".parse().unwrap()],
+            board: parse_cards("Ah Kd 2c").unwrap(),
+            pot: Chips::new(100),
+            effective_stack: Chips::new(900),
+            bet_sizes: vec![33, 75],
+        };
+        assert_eq!(spot.validate(), Ok(()));
+        assert_eq!((spot.live_combos(0).len(), spot.live_combos(1).len()), (3 + 3, 9));
+        let blocked = SolverSpot { ranges: ["AhKd".parse().unwrap(), "AK".parse().unwrap()], ..spot.clone() };
+        assert!(blocked.validate().is_err());
+        assert!(SolverSpot { board: parse_cards("Ah Kd").unwrap(), ..spot.clone() }.validate().is_err());
+        assert!(SolverSpot { pot: Chips::ZERO, ..spot }.validate().is_err());
+
+        let actions = vec!["check".to_string(), "bet 75".to_string()];
+        let strategy = SolverStrategy::new(actions.clone(), vec![combo("As Ad", &[0.2, 0.8]), combo("Ks Kh", &[0.6, 0.4])]).unwrap();
+        let overall = strategy.overall();
+        assert!((overall[0] - 0.4).abs() < 1e-9 && (overall[1] - 0.6).abs() < 1e-9, "{overall:?}");
+        assert!(SolverStrategy::new(actions.clone(), vec![combo("As Ad", &[1.0])]).is_err());
+        assert!(SolverStrategy::new(actions, vec![combo("As Ad", &[0.5, 0.2])]).is_err());
+        assert!(SolverStrategy::new(Vec::new(), Vec::new()).is_err());
+    }
+}
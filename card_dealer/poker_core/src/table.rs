@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+use crate::card_dealer::Card; // Import Card from card_dealer.rs
+use crate::chips::Chips;
+use crate::game_error::GameError;
+use crate::player::Player;
+use crate::player_id::PlayerId;
+
+#[derive(Debug)]
+pub struct Table {
+    pub community_cards: Vec<Card>,             // Shared cards on the table
+    pub extra_boards: Vec<Vec<Card>>,           // Boards after the first in multi-board variants, in the order dealt
+    pub shared_cards: usize,                    // Cards every extra board shares with the first, when one board is run more than once
+    pub pots: Vec<Pot>,                         // Multiple pots for the game
+    player_bets: HashMap<PlayerId, Chips>,      // Chips each player has put into the hand; only `add_bet` adds to them
+    pub min_bet: Chips,                         // Minimum bet for the current round
+    pub max_bet: Chips,                         // Current maximum bet
+}
+
+#[derive(Debug)]
+pub struct Pot {
+    pub total: Chips,                           // Total chips in this pot
+    pub eligible_players: Vec<PlayerId>,        // Player IDs eligible to win this pot
+    pub winners: Option<Vec<PlayerId>>,         // Winners of this pot, None if not resolved yet
+    pub extra_board_winners: Vec<Vec<PlayerId>>, // Winners on each extra board, who split the pot with `winners`
+}
+
+impl Pot {
+    pub fn new(total: Chips, eligible_players: Vec<PlayerId>) -> Self {
+        Self { total, eligible_players, winners: None, extra_board_winners: Vec::new() }
+    }
+
+    /// Splits `total` evenly between `boards` boards, odd chips to the first boards.
+    pub fn board_shares(total: Chips, boards: usize) -> Vec<Chips> {
+        split_evenly(total, boards.max(1))
+    }
+
+    /// What each winner is paid: an even share of the pot per board, split
+    /// evenly between that board's winners. Odd chips go to the first boards
+    /// and, within a board, to the first winners. Nothing is paid before the
+    /// first board's winners are known.
+    pub fn payouts(&self) -> Vec<(PlayerId, Chips)> {
+        (0..=self.extra_board_winners.len()).flat_map(|board| self.board_payouts(board)).collect()
+    }
+
+    /// What the winners on one board are paid, counting boards from 0.
+    pub fn board_payouts(&self, board: usize) -> Vec<(PlayerId, Chips)> {
+        let winners = if board == 0 { self.winners.as_ref() } else { self.extra_board_winners.get(board - 1) };
+        let (Some(_), Some(winners)) = (&self.winners, winners) else { return Vec::new() };
+        let share = Self::board_shares(self.total, 1 + self.extra_board_winners.len())[board];
+        winners.iter().cloned().zip(split_evenly(share, winners.len())).collect()
+    }
+}
+
+/// `total` in `parts` shares as even as chips allow, the larger ones first.
+fn split_evenly(total: Chips, parts: usize) -> Vec<Chips> {
+    if parts == 0 {
+        return Vec::new();
+    }
+    let share = total / parts as u64;
+    let odd = (total % parts as u64).get();
+    (0..parts as u64).map(|index| share + Chips::from(u32::from(index < odd))).collect()
+}
+
+impl Table {
+    pub fn new() -> Self {
+        Self {
+            community_cards: Vec::new(),
+            extra_boards: Vec::new(),
+            shared_cards: 0,
+            pots: Vec::new(),
+            player_bets: HashMap::new(),
+            min_bet: Chips::ZERO,
+            max_bet: Chips::ZERO,
+        }
+    }
+
+    /// Moves `amount` chips from `player`'s stack into the hand.
+    ///
+    /// Table stakes: only chips in front of the player can be wagered, so a
+    /// bet larger than their stack is refused rather than capped, and a player
+    /// who isn't in the hand can't bet at all. The whole amount moves in one
+    /// step and can't be taken back; the pots are rebuilt from these bets by
+    /// whoever runs the hand.
+    pub fn add_bet(&mut self, player: &mut Player, amount: Chips) -> Result<(), GameError> {
+        if !player.is_in_play {
+            return Err(GameError::InvalidState(format!("player {} is not in the hand", player.player_id)));
+        }
+        player.chip_stack = player.chip_stack.try_sub(amount)?;
+        *self.player_bets.entry(player.player_id.clone()).or_default() += amount;
+        Ok(())
+    }
+
+    /// Chips `player_id` has put into the current hand.
+    pub fn committed(&self, player_id: &str) -> Chips {
+        self.player_bets.get(player_id).copied().unwrap_or_default()
+    }
+
+    /// Chips every player has put into the current hand, by player id.
+    pub fn player_bets(&self) -> &HashMap<PlayerId, Chips> {
+        &self.player_bets
+    }
+
+    /// Chips put into the current hand by everyone together.
+    pub fn total_committed(&self) -> Chips {
+        self.player_bets.values().sum()
+    }
+
+    /// Clears the table for a new round
+    pub fn reset_for_new_round(&mut self) {
+        self.community_cards.clear();
+        self.extra_boards.clear();
+        self.shared_cards = 0;
+        self.pots.clear();
+        self.player_bets.clear();
+        self.min_bet = Chips::ZERO;
+        self.max_bet = Chips::ZERO;
+    }
+}
+
+impl Default for Table {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_stakes() {
+        let mut table = Table::new();
+        let mut player = Player::new("1".into(), "Alice".to_string(), 0, Chips::new(100));
+        assert_eq!(table.add_bet(&mut player, Chips::new(101)), Err(GameError::InsufficientChips));
+        assert_eq!((player.chip_stack, table.committed("1")), (Chips::new(100), Chips::ZERO)); // Nothing moved
+
+        table.add_bet(&mut player, Chips::new(60)).unwrap();
+        table.add_bet(&mut player, Chips::new(40)).unwrap();
+        assert_eq!((player.chip_stack, table.committed("1")), (Chips::ZERO, Chips::new(100)));
+
+        player.fold();
+        assert!(matches!(table.add_bet(&mut player, Chips::ZERO), Err(GameError::InvalidState(_))));
+        assert_eq!(table.total_committed(), Chips::new(100)); // Folding leaves the chips in the pot
+    }
+
+    #[test]
+    fn test_pot_payouts_split_between_boards() {
+        let mut pot = Pot::new(Chips::new(101), vec!["1".into(), "2".into(), "3".into()]);
+        assert!(pot.payouts().is_empty()); // Not resolved yet
+        pot.winners = Some(vec!["1".into()]);
+        assert_eq!(pot.payouts(), vec![("1".into(), Chips::new(101))]);
+
+        pot.extra_board_winners = vec![vec!["2".into(), "3".into()]];
+        assert_eq!(pot.payouts(), vec![("1".into(), Chips::new(51)), ("2".into(), Chips::new(25)), ("3".into(), Chips::new(25))]);
+    }
+}
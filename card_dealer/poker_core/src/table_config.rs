@@ -0,0 +1,117 @@
+//! Everything a table is set up with when it opens: the game, the blinds
+//! and antes, the buy-in limits, the seats, the rake and the clock, in one
+//! value a host can send or store.
+
+use crate::buy_in::BuyInRules;
+use crate::chips::Chips;
+use crate::currency::CurrencyMode;
+use crate::game_controller::{DEFAULT_MAX_SEATS, MAX_RUNS};
+use crate::game_error::GameError;
+use crate::rake::RakeRules;
+use crate::variant::GameVariant;
+use crate::wild_cards::WildCards;
+
+/// The settings of one table. Missing fields take the defaults a
+/// `GameController` starts with, apart from the blinds, which default to 5/10.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct TableConfig {
+    pub variant: GameVariant,     // The game dealt at the table
+    pub small_blind: Chips,
+    pub big_blind: Chips,
+    pub big_blind_ante: Chips,    // Posted by the big blind for the whole table; zero for no ante
+    pub buy_in: BuyInRules,       // Buy-in limits, rebuys and add-on
+    pub max_seats: usize,         // Further players join the waiting list
+    pub wild_cards: WildCards,
+    pub decks: usize,             // Standard decks shuffled together into the shoe
+    pub continuous_shuffle: bool, // Shuffle each hand's cards back into the shoe instead of a fresh deck per hand
+    pub training_mode: bool,      // Players may ask for hints on their turn
+    pub currency: CurrencyMode,   // Free play money, or chips drawn from the players' balances
+    pub max_runs: usize,          // Most times players all in before the river may agree to run the board
+    pub insurance: bool,          // The favourite of a heads-up all-in may insure their share of the pot
+    pub rake: RakeRules,          // The house's share of each pot; none by default
+    pub straddles: bool,          // The player under the gun may straddle
+    pub action_timeout_secs: Option<u64>, // Seconds a player has to act before the table acts for them; no limit when unset
+}
+
+impl Default for TableConfig {
+    fn default() -> Self {
+        Self {
+            variant: GameVariant::default(),
+            small_blind: Chips::new(5),
+            big_blind: Chips::new(10),
+            big_blind_ante: Chips::ZERO,
+            buy_in: BuyInRules::default(),
+            max_seats: DEFAULT_MAX_SEATS,
+            wild_cards: WildCards::default(),
+            decks: 1,
+            continuous_shuffle: false,
+            training_mode: false,
+            currency: CurrencyMode::default(),
+            max_runs: 1,
+            insurance: false,
+            rake: RakeRules::default(),
+            straddles: false,
+            action_timeout_secs: None,
+        }
+    }
+}
+
+impl TableConfig {
+    /// Checks the settings make a playable table.
+    pub fn validate(&self) -> Result<(), GameError> {
+        if self.small_blind.is_zero() || self.big_blind < self.small_blind {
+            return Err(GameError::InvalidRequest("blinds must be positive and the big blind at least the small blind".to_string()));
+        }
+        if self.big_blind_ante > self.big_blind {
+            return Err(GameError::InvalidRequest("the big blind ante may be at most the big blind".to_string()));
+        }
+        if self.buy_in.min_buy_in > self.buy_in.max_buy_in {
+            return Err(GameError::InvalidRequest("the minimum buy-in is above the maximum".to_string()));
+        }
+        if self.buy_in.max_buy_in < self.big_blind {
+            return Err(GameError::InvalidRequest("the maximum buy-in must cover at least one big blind".to_string()));
+        }
+        if self.max_seats < 2 {
+            return Err(GameError::InvalidRequest("a table needs at least two seats".to_string()));
+        }
+        if self.decks == 0 {
+            return Err(GameError::InvalidRequest("at least one deck is needed".to_string()));
+        }
+        if self.currency == CurrencyMode::PlayMoney && self.buy_in.max_rebuys.is_some() {
+            return Err(GameError::InvalidRequest("rebuys are unlimited with play money".to_string()));
+        }
+        if !(1..=MAX_RUNS).contains(&self.max_runs) {
+            return Err(GameError::InvalidRequest(format!("the board can be run 1 to {MAX_RUNS} times")));
+        }
+        self.rake.validate().map_err(GameError::InvalidRequest)?;
+        if self.action_timeout_secs == Some(0) {
+            return Err(GameError::InvalidRequest("the action timeout must be at least a second".to_string()));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(TableConfig::default().validate(), Ok(()));
+        let config = TableConfig { small_blind: Chips::new(20), ..TableConfig::default() };
+        assert!(matches!(config.validate(), Err(GameError::InvalidRequest(_))));
+        let buy_in = BuyInRules { min_buy_in: Chips::new(500), max_buy_in: Chips::new(400), ..BuyInRules::default() };
+        assert!(TableConfig { buy_in, ..TableConfig::default() }.validate().is_err());
+        assert!(TableConfig { max_seats: 1, ..TableConfig::default() }.validate().is_err());
+        assert!(TableConfig { decks: 0, ..TableConfig::default() }.validate().is_err());
+        assert!(TableConfig { max_runs: 4, ..TableConfig::default() }.validate().is_err());
+        assert!(TableConfig { big_blind_ante: Chips::new(20), ..TableConfig::default() }.validate().is_err());
+        assert!(TableConfig { rake: RakeRules { percent: 15, ..RakeRules::default() }, ..TableConfig::default() }.validate().is_err());
+        assert!(TableConfig { action_timeout_secs: Some(0), ..TableConfig::default() }.validate().is_err());
+        let buy_in = BuyInRules { max_rebuys: Some(2), ..BuyInRules::default() };
+        assert!(TableConfig { buy_in: buy_in.clone(), ..TableConfig::default() }.validate().is_err());
+        assert_eq!(TableConfig { buy_in, currency: CurrencyMode::Tracked, ..TableConfig::default() }.validate(), Ok(()));
+    }
+}
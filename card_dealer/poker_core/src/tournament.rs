@@ -0,0 +1,118 @@
+//! Tournament entries: registering, late registration and re-entering after
+//! busting, and the prize pool the entry fees add up to.
+
+use std::collections::HashMap;
+
+use crate::chips::Chips;
+use crate::game_error::GameError;
+use crate::player_id::PlayerId;
+
+/// How players enter a tournament table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct TournamentRules {
+    pub entry_fee: Chips,                  // Paid into the prize pool by every entry and re-entry
+    pub starting_stack: Chips,             // Chips each entry sits down with
+    pub late_registration_until: u64,      // Last hand number an entry or re-entry counts towards
+    pub max_entries: u32,                  // Entries each player may make, the first one included; 1 is a freezeout
+    pub paid_places: u32,                  // Finishers in the money; play goes hand-for-hand on the bubble. 0 turns that off
+}
+
+impl Default for TournamentRules {
+    fn default() -> Self {
+        Self { entry_fee: Chips::new(100), starting_stack: Chips::new(1500), late_registration_until: 0, max_entries: 1, paid_places: 0 }
+    }
+}
+
+impl TournamentRules {
+    /// True when one more elimination puts everyone left in the money, with
+    /// `players_remaining` across every table of the tournament.
+    pub fn on_bubble(&self, players_remaining: usize) -> bool {
+        self.paid_places > 0 && players_remaining == self.paid_places as usize + 1
+    }
+}
+
+/// The entries made so far at a tournament table.
+#[derive(Debug, Clone, Default)]
+pub struct Tournament {
+    pub rules: TournamentRules,
+    entries: HashMap<PlayerId, u32>,   // Entries each player has made
+}
+
+impl Tournament {
+    pub fn new(rules: TournamentRules) -> Self {
+        Self { rules, entries: HashMap::new() }
+    }
+
+    /// Entries `player_id` has made.
+    pub fn entries(&self, player_id: &str) -> u32 {
+        self.entries.get(player_id).copied().unwrap_or(0)
+    }
+
+    /// Entries made by everyone.
+    pub fn total_entries(&self) -> u32 {
+        self.entries.values().sum()
+    }
+
+    /// Every entry fee paid so far, recalculated from the entries.
+    pub fn prize_pool(&self) -> Chips {
+        self.rules.entry_fee * u64::from(self.total_entries())
+    }
+
+    /// True while entries are taken for hand `hand_number`.
+    pub fn registration_open(&self, hand_number: u64) -> bool {
+        hand_number <= self.rules.late_registration_until
+    }
+
+    /// Checks that `player_id` may make another entry counting from hand
+    /// `hand_number`; `re_entry` says whether they mean to enter again.
+    pub(crate) fn check_entry(&self, player_id: &str, re_entry: bool, hand_number: u64) -> Result<(), GameError> {
+        if !self.registration_open(hand_number) {
+            return Err(GameError::NotAllowed(format!("registration closed after hand {}", self.rules.late_registration_until)));
+        }
+        match (self.entries(player_id), re_entry) {
+            (0, true) => Err(GameError::InvalidRequest(format!("player {player_id} has not entered yet"))),
+            (0, false) => Ok(()),
+            (_, false) => Err(GameError::InvalidRequest(format!("player {player_id} is already registered; re-enter instead"))),
+            (entries, true) if entries >= self.rules.max_entries => {
+                Err(GameError::NotAllowed(format!("no more than {} entries per player", self.rules.max_entries)))
+            }
+            (_, true) => Ok(()),
+        }
+    }
+
+    /// Records an entry and returns how many `player_id` has now made.
+    pub(crate) fn enter(&mut self, player_id: PlayerId) -> u32 {
+        let entries = self.entries.entry(player_id).or_default();
+        *entries += 1;
+        *entries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entries_and_prize_pool() {
+        let mut tournament = Tournament::new(TournamentRules { late_registration_until: 5, max_entries: 2, ..TournamentRules::default() });
+        assert!(tournament.check_entry("1", true, 1).is_err()); // Nothing to re-enter
+        assert!(tournament.check_entry("1", false, 1).is_ok());
+        tournament.enter("1".into());
+        tournament.enter("2".into());
+        assert!(tournament.check_entry("1", false, 1).is_err());
+        assert!(tournament.check_entry("1", true, 5).is_ok());
+        assert!(matches!(tournament.check_entry("1", true, 6), Err(GameError::NotAllowed(_)))); // Late registration is over
+        assert_eq!(tournament.enter("1".into()), 2);
+        assert!(matches!(tournament.check_entry("1", true, 3), Err(GameError::NotAllowed(_))));
+        assert_eq!((tournament.total_entries(), tournament.prize_pool()), (3, Chips::new(300)));
+        assert!(!tournament.rules.on_bubble(2)); // No places paid
+    }
+
+    #[test]
+    fn test_money_bubble() {
+        let rules = TournamentRules { paid_places: 3, ..TournamentRules::default() };
+        assert_eq!((rules.on_bubble(5), rules.on_bubble(4), rules.on_bubble(3)), (false, true, false));
+    }
+}
@@ -0,0 +1,84 @@
+//! The poker variants a table can deal.
+
+use crate::card_dealer::Card;
+use crate::card_set::CardSet;
+use crate::poker_hand::{find_best_hand, find_best_omaha_hand, find_best_shoe_hand, hand_value, omaha_hand_value, Hand, HandValue};
+
+/// Which game is dealt at a table.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "snake_case"))]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub enum GameVariant {
+    #[default]
+    HoldEm,
+    DoubleBoardHoldEm,   // Two boards; each pot is split between the best hand on either
+    Omaha,               // Four hole cards, of which a hand uses exactly two
+    FiveCardOmaha,       // Omaha with five hole cards
+    Courchevel,          // Five-card Omaha with the first flop card shown before the preflop betting
+}
+
+impl GameVariant {
+    /// Community boards dealt each hand.
+    pub fn boards(self) -> usize {
+        match self {
+            GameVariant::DoubleBoardHoldEm => 2,
+            _ => 1,
+        }
+    }
+
+    /// Hole cards dealt to each player.
+    pub fn hole_cards(self) -> usize {
+        match self {
+            GameVariant::HoldEm | GameVariant::DoubleBoardHoldEm => 2,
+            GameVariant::Omaha => 4,
+            GameVariant::FiveCardOmaha | GameVariant::Courchevel => 5,
+        }
+    }
+
+    /// Board cards shown before the preflop betting.
+    pub fn preflop_board_cards(self) -> usize {
+        match self {
+            GameVariant::Courchevel => 1,
+            _ => 0,
+        }
+    }
+
+    /// True for the Omaha games, where a hand is exactly two hole cards and
+    /// three from the board.
+    pub fn uses_exactly_two(self) -> bool {
+        matches!(self, GameVariant::Omaha | GameVariant::FiveCardOmaha | GameVariant::Courchevel)
+    }
+
+    /// The value of the best hand `hole_cards` make with `board` in this game.
+    pub fn hand_value(self, hole_cards: CardSet, board: CardSet) -> Option<HandValue> {
+        if self.uses_exactly_two() {
+            omaha_hand_value(hole_cards, board)
+        } else {
+            hand_value(hole_cards | board)
+        }
+    }
+
+    /// The value of the best hand `hole_cards` make with `board` in this
+    /// game, like `hand_value` but counting each copy of a card dealt twice
+    /// from a shoe of several decks.
+    pub fn shoe_hand_value(self, hole_cards: &[Card], board: &[Card]) -> Option<HandValue> {
+        if CardSet::try_from_cards(&[hole_cards, board].concat()).is_err() {
+            return self.best_hand(hole_cards, board).map(|hand| hand.value());
+        }
+        self.hand_value(hole_cards.iter().copied().collect(), board.iter().copied().collect())
+    }
+
+    /// The best hand `hole_cards` make with `board` in this game, once there
+    /// are enough cards to make one. A card dealt twice from a shoe of
+    /// several decks plays once for each copy.
+    pub fn best_hand(self, hole_cards: &[Card], board: &[Card]) -> Option<Hand> {
+        if CardSet::try_from_cards(&[hole_cards, board].concat()).is_err() {
+            return find_best_shoe_hand(hole_cards, board, self.uses_exactly_two());
+        }
+        if self.uses_exactly_two() {
+            find_best_omaha_hand(hole_cards, board).ok()
+        } else {
+            find_best_hand(&[hole_cards, board].concat()).ok()
+        }
+    }
+}
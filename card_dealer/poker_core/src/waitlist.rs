@@ -0,0 +1,96 @@
+//! Players waiting for a seat at a full table, seated first come, first
+//! served as seats open up.
+
+use std::collections::VecDeque;
+
+use crate::chips::Chips;
+use crate::player_id::PlayerId;
+
+/// A player queued for a seat, with the stack they'll sit down with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct WaitingPlayer {
+    pub player_id: PlayerId,
+    pub display_name: String,
+    pub chip_stack: Chips,
+}
+
+/// Where a player ended up after asking to join a table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "status", rename_all = "snake_case"))]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub enum Seating {
+    Seated { table_position: usize },
+    Waiting { position: usize },     // 1 for the next player to be seated
+}
+
+/// A first-in, first-out queue of players waiting for a seat.
+#[derive(Debug, Clone, Default)]
+pub struct WaitingList {
+    players: VecDeque<WaitingPlayer>,
+}
+
+impl WaitingList {
+    /// Queues a player and returns their place in line, starting at 1.
+    pub(crate) fn join(&mut self, player: WaitingPlayer) -> usize {
+        self.players.push_back(player);
+        self.players.len()
+    }
+
+    /// Takes a player out of the queue wherever they are in it.
+    pub(crate) fn leave(&mut self, player_id: &str) -> Option<WaitingPlayer> {
+        let index = self.players.iter().position(|player| player.player_id == player_id)?;
+        self.players.remove(index)
+    }
+
+    /// Takes the player who has waited longest.
+    pub(crate) fn next(&mut self) -> Option<WaitingPlayer> {
+        self.players.pop_front()
+    }
+
+    /// `player_id`'s place in line, starting at 1.
+    pub fn position(&self, player_id: &str) -> Option<usize> {
+        self.players.iter().position(|player| player.player_id == player_id).map(|index| index + 1)
+    }
+
+    pub fn contains(&self, player_id: &str) -> bool {
+        self.position(player_id).is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.players.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.players.is_empty()
+    }
+
+    /// Waiting players, next to be seated first.
+    pub fn iter(&self) -> impl Iterator<Item = &WaitingPlayer> {
+        self.players.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn waiting(player_id: &str) -> WaitingPlayer {
+        WaitingPlayer { player_id: player_id.into(), display_name: player_id.to_uppercase(), chip_stack: Chips::new(500) }
+    }
+
+    #[test]
+    fn test_first_in_first_out() {
+        let mut list = WaitingList::default();
+        assert_eq!(list.join(waiting("a")), 1);
+        assert_eq!(list.join(waiting("b")), 2);
+        assert_eq!(list.join(waiting("c")), 3);
+
+        assert_eq!(list.leave("b").map(|player| player.display_name), Some("B".to_string()));
+        assert_eq!(list.position("c"), Some(2)); // Moves up when someone ahead leaves
+        assert_eq!(list.next(), Some(waiting("a")));
+        assert_eq!(list.next(), Some(waiting("c")));
+        assert!(list.is_empty() && list.next().is_none());
+    }
+}
@@ -0,0 +1,132 @@
+//! Wild cards for home games: a joker shuffled into each deck, or every
+//! deuce, stands for whichever card makes the best hand. Two wild cards can
+//! stand for the same card, so five of a kind beats a straight flush.
+
+use std::cmp::Reverse;
+
+use itertools::Itertools;
+
+use crate::card_dealer::{Card, Rank, Suit};
+use crate::poker_hand::{value_of, Hand, HandValue};
+use crate::variant::GameVariant;
+
+/// Which cards are wild at a table.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "snake_case"))]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub enum WildCards {
+    #[default]
+    None,
+    Joker,      // A joker is added to each deck
+    Deuces,     // Every two
+}
+
+impl WildCards {
+    pub fn is_wild(self, card: Card) -> bool {
+        match self {
+            WildCards::None => false,
+            WildCards::Joker => card.is_joker(),
+            WildCards::Deuces => card.rank == Rank::Two,
+        }
+    }
+
+    /// True if the deck needs a joker for these rules.
+    pub fn adds_joker(self) -> bool {
+        self == WildCards::Joker
+    }
+
+    /// The value of the best hand `hole_cards` make with `board` in
+    /// `variant`, each wild card standing for whatever is best.
+    pub fn hand_value(self, variant: GameVariant, hole_cards: &[Card], board: &[Card]) -> Option<HandValue> {
+        if self == WildCards::None {
+            return variant.shoe_hand_value(hole_cards, board);
+        }
+        self.best(variant, hole_cards, board).map(|(value, _)| value)
+    }
+
+    /// The best hand `hole_cards` make with `board` in `variant`, with each
+    /// wild card replaced by the card it stands for.
+    pub fn best_hand(self, variant: GameVariant, hole_cards: &[Card], board: &[Card]) -> Option<Hand> {
+        if self == WildCards::None {
+            return variant.best_hand(hole_cards, board);
+        }
+        let (value, mut cards) = self.best(variant, hole_cards, board)?;
+        cards.sort_by_key(|card| Reverse(card.rank));
+        Some(Hand { cards, rank: value.rank() })
+    }
+
+    fn best(self, variant: GameVariant, hole_cards: &[Card], board: &[Card]) -> Option<(HandValue, Vec<Card>)> {
+        let fives: Vec<Vec<Card>> = if variant.uses_exactly_two() {
+            hole_cards.iter()
+                .copied()
+                .combinations(2)
+                .cartesian_product(board.iter().copied().combinations(3).collect::<Vec<_>>())
+                .map(|(hole, board)| [hole, board].concat())
+                .collect()
+        } else {
+            hole_cards.iter().chain(board).copied().combinations(5).collect()
+        };
+        fives.iter().map(|five| self.best_five(five)).max_by_key(|(value, _)| *value)
+    }
+
+    /// Tries every rank for each wild card among exactly five cards. The
+    /// wild cards take the suit of the natural ones, which makes a flush
+    /// when those are suited and no rank repeats.
+    fn best_five(self, cards: &[Card]) -> (HandValue, Vec<Card>) {
+        let (wilds, naturals): (Vec<Card>, Vec<Card>) = cards.iter().partition(|&&card| self.is_wild(card));
+        let mut counts = [0u8; 13];
+        for card in &naturals {
+            counts[card.rank as usize] += 1;
+        }
+        let suit = naturals.first().map_or(Suit::Spades, |card| card.suit);
+        let suited = naturals.iter().all(|card| card.suit == suit);
+        Rank::ALL.into_iter()
+            .combinations_with_replacement(wilds.len())
+            .map(|ranks| {
+                let mut counts = counts;
+                for &rank in &ranks {
+                    counts[rank as usize] += 1;
+                }
+                let flush = suited && counts.iter().all(|&count| count <= 1);
+                (value_of(&counts, flush), ranks)
+            })
+            .max_by_key(|(value, _)| *value)
+            .map(|(value, ranks)| (value, naturals.iter().copied().chain(ranks.into_iter().map(|rank| Card { rank, suit })).collect()))
+            .expect("there is always at least one way to play the wild cards")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card_dealer::parse_cards;
+    use crate::poker_hand::HandRank;
+
+    fn best(wild_cards: WildCards, hole_cards: &str, board: &str) -> Hand {
+        let (hole_cards, board) = (parse_cards(hole_cards).unwrap(), parse_cards(board).unwrap());
+        wild_cards.best_hand(GameVariant::HoldEm, &hole_cards, &board).unwrap()
+    }
+
+    #[test]
+    fn test_wild_cards_complete_the_best_hand() {
+        let five_aces = best(WildCards::Joker, "Jk Ah", "Ad Ac As 7d 2c");
+        assert_eq!(five_aces.rank, HandRank::FiveOfAKind);
+        assert!(five_aces.cards.iter().all(|card| card.rank == Rank::Ace));
+
+        let royal = best(WildCards::Joker, "Jk Kh", "Qh Jh Th 4c 4d");
+        assert_eq!((royal.rank, royal.cards[0]), (HandRank::StraightFlush, "Ah".parse().unwrap()));
+        // Paired naturals can't be a flush, so the joker makes quads instead
+        assert_eq!(best(WildCards::Joker, "Jk Kh", "Kc 9h 5h 2h Ks").rank, HandRank::FourOfAKind);
+        // Without wild cards the joker is just a dead card
+        assert_eq!(best(WildCards::None, "Jk Kh", "Kc 9h 5h 2h Ks").rank, HandRank::ThreeOfAKind);
+
+        let deuces = best(WildCards::Deuces, "2c 2d", "9s 9h 4c 6d Jh");
+        assert_eq!(deuces.rank, HandRank::FourOfAKind);
+        let value = |wild_cards: WildCards, hole: &str| {
+            wild_cards.hand_value(GameVariant::Omaha, &parse_cards(hole).unwrap(), &parse_cards("Ah Kh 7c 7d 3s").unwrap()).unwrap()
+        };
+        // Omaha still plays exactly two hole cards, the joker being one of them
+        assert_eq!(value(WildCards::Joker, "Jk 7h Qs 8c").rank(), HandRank::FourOfAKind);
+        assert_eq!(value(WildCards::None, "Jk 7h Qs 8c").rank(), HandRank::ThreeOfAKind);
+    }
+}
@@ -0,0 +1,55 @@
+#ifndef POKER_FFI_H
+#define POKER_FFI_H
+
+/* Generated by cbindgen from poker_ffi/src/lib.rs. Do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Outcome of an FFI call; anything but `Ok` leaves the output untouched.
+enum PokerStatus {
+  POKER_STATUS_OK = 0,
+  POKER_STATUS_NULL_POINTER = 1,
+  POKER_STATUS_INVALID_CARD = 2,
+  POKER_STATUS_INVALID_LENGTH = 3,
+  POKER_STATUS_DUPLICATE_CARD = 4,
+};
+typedef uint32_t PokerStatus;
+
+// The best five-card hand found among the cards passed in.
+typedef struct PokerHand {
+  // Hand category, from 0 (high card) to 8 (straight flush).
+  uint8_t rank;
+  // The five cards making the hand, highest rank first.
+  uint8_t cards[5];
+} PokerHand;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Evaluates the best hand among `len` cards (five to seven).
+//
+// # Safety
+// `cards` must point to `len` readable bytes and `out` to a writable `PokerHand`.
+PokerStatus poker_evaluate(const uint8_t *cards, uintptr_t len, struct PokerHand *out);
+
+// Compares the best hands in `a` and `b`, writing -1, 0 or 1 to `out` as `a`
+// loses, ties or wins.
+//
+// # Safety
+// `a` and `b` must point to `a_len` and `b_len` readable bytes and `out` to a
+// writable `int32_t`.
+PokerStatus poker_compare(const uint8_t *a,
+                          uintptr_t a_len,
+                          const uint8_t *b,
+                          uintptr_t b_len,
+                          int32_t *out);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* POKER_FFI_H */
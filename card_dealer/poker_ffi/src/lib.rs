@@ -0,0 +1,160 @@
+//! C interface to the hand evaluator, for engines written in C, C++ or C#
+//! that want to embed it directly. The declarations are in
+//! `include/poker_ffi.h`.
+//!
+//! A card is one byte, `rank * 4 + suit`: ranks run from 0 (two) to 12
+//! (ace) and suits are 0 hearts, 1 diamonds, 2 clubs, 3 spades. So `0` is
+//! the two of hearts and `51` the ace of spades.
+
+use std::cmp::Ordering;
+use std::slice;
+
+use poker_core::card_dealer::{Card, Rank, Suit};
+use poker_core::poker_hand::{find_best_hand, Hand, HandError};
+
+/// Outcome of an FFI call; anything but `Ok` leaves the output untouched.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PokerStatus {
+    Ok = 0,
+    NullPointer = 1,   // A required pointer was null
+    InvalidCard = 2,   // A byte was 52 or above
+    InvalidLength = 3, // Fewer than five or more than seven cards
+    DuplicateCard = 4, // The same card was passed twice
+}
+
+/// The best five-card hand found among the cards passed in.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PokerHand {
+    /// Hand category, from 0 (high card) to 8 (straight flush).
+    pub rank: u8,
+    /// The five cards making the hand, highest rank first.
+    pub cards: [u8; 5],
+}
+
+/// Evaluates the best hand among `len` cards (five to seven).
+///
+/// # Safety
+/// `cards` must point to `len` readable bytes and `out` to a writable `PokerHand`.
+#[no_mangle]
+pub unsafe extern "C" fn poker_evaluate(cards: *const u8, len: usize, out: *mut PokerHand) -> PokerStatus {
+    if out.is_null() {
+        return PokerStatus::NullPointer;
+    }
+    match best_hand(cards, len) {
+        Ok(hand) => {
+            let mut bytes = [0; 5];
+            for (byte, card) in bytes.iter_mut().zip(&hand.cards) {
+                *byte = card_to_byte(card);
+            }
+            *out = PokerHand { rank: hand.rank as u8, cards: bytes };
+            PokerStatus::Ok
+        }
+        Err(status) => status,
+    }
+}
+
+/// Compares the best hands in `a` and `b`, writing -1, 0 or 1 to `out` as `a`
+/// loses, ties or wins.
+///
+/// # Safety
+/// `a` and `b` must point to `a_len` and `b_len` readable bytes and `out` to a
+/// writable `int32_t`.
+#[no_mangle]
+pub unsafe extern "C" fn poker_compare(
+    a: *const u8,
+    a_len: usize,
+    b: *const u8,
+    b_len: usize,
+    out: *mut i32,
+) -> PokerStatus {
+    if out.is_null() {
+        return PokerStatus::NullPointer;
+    }
+    let hands = best_hand(a, a_len).and_then(|a| Ok((a, best_hand(b, b_len)?)));
+    match hands {
+        Ok((a, b)) => {
+            *out = match a.compare_two_hands(&b) {
+                Ordering::Less => -1,
+                Ordering::Equal => 0,
+                Ordering::Greater => 1,
+            };
+            PokerStatus::Ok
+        }
+        Err(status) => status,
+    }
+}
+
+/// Reads and validates `len` card bytes, then finds the best hand among them.
+unsafe fn best_hand(cards: *const u8, len: usize) -> Result<Hand, PokerStatus> {
+    if cards.is_null() {
+        return Err(PokerStatus::NullPointer);
+    }
+    if !(5..=7).contains(&len) {
+        return Err(PokerStatus::InvalidLength);
+    }
+    let bytes = slice::from_raw_parts(cards, len);
+    let cards = bytes.iter().map(|&byte| card_from_byte(byte)).collect::<Result<Vec<_>, _>>()?;
+    find_best_hand(&cards).map_err(|error| match error {
+        HandError::DuplicateCard(_) => PokerStatus::DuplicateCard,
+        HandError::WrongCardCount(_) | HandError::TooFewCards(_) => PokerStatus::InvalidLength,
+    })
+}
+
+fn card_from_byte(byte: u8) -> Result<Card, PokerStatus> {
+    let rank = *Rank::ALL.get(usize::from(byte / 4)).ok_or(PokerStatus::InvalidCard)?;
+    Ok(Card { rank, suit: Suit::ALL[usize::from(byte % 4)] })
+}
+
+fn card_to_byte(card: &Card) -> u8 {
+    card.rank as u8 * 4 + card.suit as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use poker_core::card_dealer::parse_cards;
+
+    fn bytes(text: &str) -> Vec<u8> {
+        parse_cards(text).unwrap().iter().map(card_to_byte).collect()
+    }
+
+    #[test]
+    fn test_evaluate_seven_cards() {
+        let cards = bytes("Kd 7c Kh 2s Ks 7d 3h");
+        let mut hand = PokerHand { rank: 0, cards: [0; 5] };
+        let status = unsafe { poker_evaluate(cards.as_ptr(), cards.len(), &mut hand) };
+        assert_eq!(status, PokerStatus::Ok);
+        assert_eq!(hand.rank, 6); // Full house
+        assert!(hand.cards[..3].iter().all(|&card| card / 4 == Rank::King as u8));
+        assert_eq!(card_from_byte(51), Ok(Card { rank: Rank::Ace, suit: Suit::Spades }));
+    }
+
+    #[test]
+    fn test_invalid_input_is_reported() {
+        let mut hand = PokerHand { rank: 0, cards: [0; 5] };
+        let short = bytes("Ah Kh");
+        let bad = [0, 1, 2, 3, 52];
+        let duplicate = [0, 1, 2, 3, 3];
+        unsafe {
+            assert_eq!(poker_evaluate(short.as_ptr(), short.len(), &mut hand), PokerStatus::InvalidLength);
+            assert_eq!(poker_evaluate(bad.as_ptr(), bad.len(), &mut hand), PokerStatus::InvalidCard);
+            assert_eq!(poker_evaluate(duplicate.as_ptr(), duplicate.len(), &mut hand), PokerStatus::DuplicateCard);
+            assert_eq!(poker_evaluate(std::ptr::null(), 5, &mut hand), PokerStatus::NullPointer);
+        }
+    }
+
+    #[test]
+    fn test_compare_hands() {
+        let flush = bytes("Ah Kh 9h 4h 2h");
+        let straight = bytes("9c Td Jh Qs Kc");
+        let mut ordering = 0;
+        unsafe {
+            assert_eq!(poker_compare(flush.as_ptr(), 5, straight.as_ptr(), 5, &mut ordering), PokerStatus::Ok);
+            assert_eq!(ordering, 1);
+            poker_compare(straight.as_ptr(), 5, flush.as_ptr(), 5, &mut ordering);
+            assert_eq!(ordering, -1);
+        }
+    }
+}
@@ -0,0 +1,107 @@
+//! WebAssembly bindings for the hand evaluator, so web clients can evaluate
+//! hands and estimate equities without a round trip to the server.
+//!
+//! Build with `wasm-pack build poker_wasm --target web`. Cards use the
+//! two-character notation of `poker_core` (`Ah`, `Td`, ...), separated by
+//! spaces or commas.
+
+use poker_core::card_dealer::parse_cards;
+use poker_core::equity::estimate_equity;
+use poker_core::game_error::GameError;
+use poker_core::poker_hand::{find_best_hand, HandRank};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use wasm_bindgen::prelude::*;
+
+/// The best five-card hand found among the given cards.
+#[wasm_bindgen]
+pub struct EvaluatedHand {
+    rank: String,  // Hand category, e.g. "Full House"
+    cards: String, // The five cards making the hand, e.g. "Kd Kh Ks 7c 7d"
+}
+
+#[wasm_bindgen]
+impl EvaluatedHand {
+    #[wasm_bindgen(getter)]
+    pub fn rank(&self) -> String {
+        self.rank.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn cards(&self) -> String {
+        self.cards.clone()
+    }
+}
+
+/// Evaluates the best hand among five to seven cards, e.g. `"Ah Kh Qh Jh Th 2c 3d"`.
+#[wasm_bindgen(js_name = evaluateHand)]
+pub fn evaluate_hand(cards: &str) -> Result<EvaluatedHand, JsError> {
+    evaluate(cards).map_err(js_error)
+}
+
+/// Estimates each hand's share of the pot over `trials` random run-outs.
+///
+/// `hands` holds two hole cards per player; `board` zero to five community
+/// cards. The same `seed` always gives the same estimate.
+#[wasm_bindgen]
+pub fn equity(hands: Vec<String>, board: &str, trials: u32, seed: u32) -> Result<Vec<f64>, JsError> {
+    equity_of(&hands, board, trials, seed).map_err(js_error)
+}
+
+fn evaluate(cards: &str) -> Result<EvaluatedHand, GameError> {
+    let cards = parse_cards(cards)?;
+    if !(5..=7).contains(&cards.len()) {
+        return Err(GameError::InvalidRequest(format!("expected 5 to 7 cards, got {}", cards.len())));
+    }
+    let hand = find_best_hand(&cards)?;
+    Ok(EvaluatedHand {
+        rank: rank_name(&hand.rank).to_string(),
+        cards: hand.cards.iter().map(ToString::to_string).collect::<Vec<_>>().join(" "),
+    })
+}
+
+fn equity_of(hands: &[String], board: &str, trials: u32, seed: u32) -> Result<Vec<f64>, GameError> {
+    let hands = hands.iter().map(|hand| parse_cards(hand)).collect::<Result<Vec<_>, _>>()?;
+    let board = parse_cards(board)?;
+    estimate_equity(&hands, &board, trials, &mut StdRng::seed_from_u64(u64::from(seed)))
+}
+
+fn rank_name(rank: &HandRank) -> &'static str {
+    match rank {
+        HandRank::HighCard => "High Card",
+        HandRank::OnePair => "One Pair",
+        HandRank::TwoPair => "Two Pair",
+        HandRank::ThreeOfAKind => "Three of a Kind",
+        HandRank::Straight => "Straight",
+        HandRank::Flush => "Flush",
+        HandRank::FullHouse => "Full House",
+        HandRank::FourOfAKind => "Four of a Kind",
+        HandRank::StraightFlush => "Straight Flush",
+        HandRank::FiveOfAKind => "Five of a Kind",
+    }
+}
+
+fn js_error(error: GameError) -> JsError {
+    JsError::new(&error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evaluate_seven_cards() {
+        let hand = evaluate("Kd 7c Kh 2s Ks 7d 3h").unwrap();
+        assert_eq!(hand.rank, "Full House");
+        assert_eq!(hand.cards.split(' ').count(), 5);
+        assert!(evaluate("Kd 7c").is_err());
+    }
+
+    #[test]
+    fn test_equity_is_seeded() {
+        let hands = vec!["Ah As".to_string(), "Kc Kd".to_string()];
+        let first = equity_of(&hands, "", 500, 9).unwrap();
+        assert_eq!(first, equity_of(&hands, "", 500, 9).unwrap());
+        assert!(first[0] > first[1]);
+    }
+}
@@ -0,0 +1,314 @@
+//! The Annual Computer Poker Competition (ACPC) dealer protocol, so research
+//! bots written for the competition's dealer can play at the default table.
+//!
+//! A client connects over TCP, sends `VERSION:2.0.0` and takes the next free
+//! seat from `acpc.seats`. It is sent a `MATCHSTATE` line whenever the hand
+//! changes, and answers the states where it is to act with the same line
+//! followed by `:` and its action: `f`, `c`, or `r<chips>`, a raise to that
+//! many chips committed to the hand in total (no-limit). Once every seat is
+//! taken the adapter deals hands back to back.
+
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::sync::{Arc, Mutex};
+
+use poker_core::bots::ActionContext;
+use poker_core::chips::Chips;
+use poker_core::game_controller::GameController;
+use poker_core::game_error::GameError;
+use poker_core::player::PlayerAction;
+use poker_core::player_id::PlayerId;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{debug, info, warn};
+
+use crate::config::AcpcConfig;
+use crate::table_registry::TableHandle;
+
+/// Protocol versions this adapter speaks.
+const VERSION_PREFIX: &str = "VERSION:2.";
+
+/// Accepts ACPC clients until the listener fails.
+pub async fn serve(config: AcpcConfig, table: Arc<TableHandle>) -> io::Result<()> {
+    let Some(addr) = config.listen_addr else { return Ok(()) };
+    let listener = TcpListener::bind(addr).await?;
+    info!(%addr, seats = ?config.seats, "ACPC listening");
+    let dealer = Arc::new(AcpcDealer::new(table, config.seats));
+    loop {
+        let (stream, peer) = listener.accept().await?;
+        let dealer = dealer.clone();
+        tokio::spawn(async move {
+            if let Err(err) = dealer.run_session(stream).await {
+                warn!(%peer, %err, "ACPC session failed");
+            }
+        });
+    }
+}
+
+/// Seats ACPC clients at a table and relays between them and its `GameController`.
+pub struct AcpcDealer {
+    table: Arc<TableHandle>,
+    seats: Vec<String>,
+    connected: Mutex<HashSet<String>>,
+    shown_result: Mutex<HashMap<String, u64>>, // Last hand whose final state each seat has been sent
+}
+
+impl AcpcDealer {
+    pub fn new(table: Arc<TableHandle>, seats: Vec<String>) -> Self {
+        Self { table, seats, connected: Mutex::default(), shown_result: Mutex::default() }
+    }
+
+    /// Plays one client's seat until it disconnects.
+    pub async fn run_session<S: AsyncRead + AsyncWrite>(&self, stream: S) -> io::Result<()> {
+        let (reader, mut writer) = tokio::io::split(stream);
+        let mut lines = BufReader::new(reader).lines();
+        let version = lines.next_line().await?.unwrap_or_default();
+        if !version.trim().starts_with(VERSION_PREFIX) {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, format!("unsupported protocol {version:?}")));
+        }
+        let seat = self.claim_seat().ok_or_else(|| io::Error::other("every ACPC seat is taken"))?;
+        info!(seat, "ACPC client seated");
+        let result = self.play_seat(&seat, &mut lines, &mut writer).await;
+        self.leave(&seat).await;
+        result
+    }
+
+    async fn play_seat<R, W>(&self, seat: &str, lines: &mut tokio::io::Lines<R>, writer: &mut W) -> io::Result<()>
+    where
+        R: tokio::io::AsyncBufRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
+        let mut events = self.table.subscribe();
+        let mut last_sent = String::new();
+        loop {
+            let player_id = seat.to_string();
+            let (state, to_act, finished) = self.table.call(move |controller| {
+                let finished = controller.hand_log().filter(|log| log.finished).map(|log| log.hand_number);
+                (match_state(controller, &player_id), controller.next_to_act() == Some(player_id.as_str()), finished)
+            }).await.map_err(io::Error::other)?;
+            if let Some(state) = state.filter(|state| *state != last_sent) {
+                writer.write_all(format!("{state}\r\n").as_bytes()).await?;
+                writer.flush().await?;
+                last_sent = state;
+            }
+            if let Some(hand_number) = finished {
+                self.lock_shown().insert(seat.to_string(), hand_number);
+            }
+            if self.deal_if_ready().await {
+                continue;
+            }
+
+            if to_act {
+                let Some(line) = lines.next_line().await? else { return Ok(()) };
+                let code = line.trim().rsplit(':').next().unwrap_or_default().to_string();
+                let player_id = seat.to_string();
+                self.table.try_call(move |controller| act(controller, &player_id, &code)).await.map_err(io::Error::other)?;
+                continue;
+            }
+            match events.recv().await {
+                Ok(_) | Err(RecvError::Lagged(_)) => {}
+                Err(RecvError::Closed) => return Ok(()),
+            }
+        }
+    }
+
+    /// Starts the next hand once every seat is taken and has seen how the last
+    /// one ended. Returns whether a hand was started.
+    async fn deal_if_ready(&self) -> bool {
+        if self.lock_connected().len() < self.seats.len() {
+            return false;
+        }
+        let seen_by_all = {
+            let shown = self.lock_shown();
+            self.seats.iter().map(|seat| shown.get(seat).copied().unwrap_or(0)).min().unwrap_or(0)
+        };
+        let started = self.table.try_call(move |controller| {
+            let last_hand = controller.hand_log().map_or(0, |log| log.hand_number);
+            if controller.is_hand_in_progress() || last_hand > seen_by_all {
+                return Ok(false);
+            }
+            controller.start_next_hand().map(|()| true)
+        }).await;
+        started.unwrap_or_else(|err| {
+            debug!(%err, "ACPC hand not started");
+            false
+        })
+    }
+
+    fn claim_seat(&self) -> Option<String> {
+        let mut connected = self.lock_connected();
+        let seat = self.seats.iter().find(|seat| !connected.contains(*seat))?.clone();
+        connected.insert(seat.clone());
+        Some(seat)
+    }
+
+    /// Frees a seat, folding its hand so the table isn't left waiting on it.
+    async fn leave(&self, seat: &str) {
+        self.lock_connected().remove(seat);
+        let player_id = seat.to_string();
+        let left = self.table.try_call(move |controller| {
+            if controller.next_to_act() == Some(player_id.as_str()) {
+                controller.act(&player_id, PlayerAction::SitOut)?;
+            } else {
+                controller.sit_out_player(&player_id)?;
+            }
+            controller.sit_in_player(&player_id) // Dealt in again once a client reconnects
+        }).await;
+        if let Err(err) = left {
+            warn!(seat, %err, "could not fold a disconnected ACPC seat");
+        }
+        info!(seat, "ACPC client left");
+    }
+
+    fn lock_connected(&self) -> std::sync::MutexGuard<'_, HashSet<String>> {
+        self.connected.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn lock_shown(&self) -> std::sync::MutexGuard<'_, HashMap<String, u64>> {
+        self.shown_result.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Applies an ACPC action code for `player_id`. Codes the engine can't take
+/// become a check, or a fold when facing a bet, as at the competition dealer.
+fn act(controller: &mut GameController, player_id: &str, code: &str) -> Result<(), GameError> {
+    let context = controller
+        .action_context(player_id)
+        .ok_or_else(|| GameError::InvalidState("no betting round is open".to_string()))?;
+    let committed = controller.get_table().committed(player_id);
+    let action = parse_action(code, &context, committed).unwrap_or_else(|| context.check_or_fold());
+    if let Err(err) = controller.act(player_id, action) {
+        debug!(player_id, code, %err, "ACPC action rejected");
+        controller.act(player_id, context.check_or_fold())?;
+    }
+    Ok(())
+}
+
+/// Reads `f`, `c` or `r<chips>`; `committed` is what the player has put into
+/// the hand so far, since ACPC raise sizes count the whole hand.
+pub fn parse_action(code: &str, context: &ActionContext, committed: Chips) -> Option<PlayerAction> {
+    match code {
+        "f" => Some(PlayerAction::Fold),
+        "c" => Some(PlayerAction::Call),
+        "r" => Some(context.raise_to(context.min_raise_to)),
+        _ => {
+            let hand_total: Chips = code.strip_prefix('r')?.parse().ok()?;
+            let before_street = committed - context.street_bet;
+            Some(context.raise_to(hand_total.saturating_sub(before_street)))
+        }
+    }
+}
+
+/// The `MATCHSTATE` line `player_id` is shown for the latest hand, or `None`
+/// if they were not dealt into it.
+///
+/// Positions count from the first player to act after the flop, so heads-up
+/// the big blind is position 0. Other players' hole cards appear only once
+/// they are shown down.
+pub fn match_state(controller: &GameController, player_id: &str) -> Option<String> {
+    let log = controller.hand_log()?;
+    let order: Vec<&PlayerId> = log.seats.iter().skip(1).chain(log.seats.first()).collect();
+    let position = order.iter().position(|id| *id == player_id)?;
+
+    let streets_dealt = match log.board.len() {
+        0 => 1,
+        3 => 2,
+        4 => 3,
+        _ => 4,
+    };
+    let mut rounds = vec![String::new(); streets_dealt];
+    for logged in &log.actions {
+        let round = &mut rounds[(logged.street as usize).min(streets_dealt - 1)];
+        match logged.action {
+            PlayerAction::Fold | PlayerAction::SitOut => round.push('f'),
+            PlayerAction::Check | PlayerAction::Call => round.push('c'),
+            PlayerAction::Bet(_) | PlayerAction::Raise(_) => round.push_str(&format!("r{}", logged.hand_total)),
+        }
+    }
+
+    let still_in = |id: &str| controller.find_player(id).is_ok_and(|player| player.is_in_play);
+    let showdown = log.finished && order.iter().filter(|id| still_in(id)).count() > 1;
+    let holes: Vec<String> = order
+        .iter()
+        .map(|id| match log.hole_cards.get(id.as_str()) {
+            Some(cards) if *id == player_id || (showdown && still_in(id)) => cards.iter().map(ToString::to_string).collect(),
+            _ => String::new(),
+        })
+        .collect();
+    let mut cards = holes.join("|");
+    for segment in [&log.board[..log.board.len().min(3)], log.board.get(3..4).unwrap_or_default(), log.board.get(4..5).unwrap_or_default()] {
+        if !segment.is_empty() {
+            cards.push('/');
+            cards.extend(segment.iter().map(ToString::to_string));
+        }
+    }
+    Some(format!("MATCHSTATE:{position}:{}:{}:{cards}", log.hand_number, rounds.join("/")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use poker_core::bots::CallingStation;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn heads_up() -> GameController {
+        let mut controller = GameController::with_rng(StdRng::seed_from_u64(2));
+        controller.initialize_players(vec![("1".to_string(), "Alice".to_string(), 0, Chips::new(1000))]);
+        controller.seat_bot("2", "Station", Chips::new(1000), Box::new(CallingStation)).unwrap();
+        controller.set_blinds(Chips::new(50), Chips::new(100)).unwrap();
+        controller
+    }
+
+    #[test]
+    fn test_match_state_and_raise_sizes() {
+        let mut controller = heads_up();
+        controller.start_hand(Chips::new(50), Chips::new(100)).unwrap();
+        // The button posts the small blind and is position 1 heads-up
+        let state = match_state(&controller, "1").unwrap();
+        assert!(state.starts_with("MATCHSTATE:1:1::|"), "{state}");
+        assert_eq!(state.len(), "MATCHSTATE:1:1::|AhKd".len());
+
+        act(&mut controller, "1", "r300").unwrap(); // The bot calls
+        assert_eq!(controller.get_table().committed("2"), Chips::new(300));
+        let state = match_state(&controller, "1").unwrap();
+        assert!(state.starts_with("MATCHSTATE:1:1:r300c/c:|"), "{state}"); // The bot checked the flop
+
+        // Raising to 900 puts 600 more in
+        act(&mut controller, "1", "r900").unwrap();
+        assert_eq!(controller.get_table().committed("1"), Chips::new(900));
+        act(&mut controller, "1", "c").unwrap();
+        act(&mut controller, "1", "f").unwrap();
+        let state = match_state(&controller, "2").unwrap();
+        assert!(state.starts_with("MATCHSTATE:0:1:r300c/cr900c/cc/cf:"), "{state}");
+        assert!(state.split(':').nth(4).unwrap().split('/').next().unwrap().ends_with('|')); // Folded cards stay hidden
+    }
+
+    #[tokio::test]
+    async fn test_session_over_the_wire() {
+        let table = TableHandle::new("acpc", heads_up());
+        let dealer = Arc::new(AcpcDealer::new(table, vec!["1".to_string()]));
+        let (client, server) = tokio::io::duplex(1024);
+        let session = tokio::spawn({
+            let dealer = dealer.clone();
+            async move { dealer.run_session(server).await }
+        });
+
+        let (reader, mut writer) = tokio::io::split(client);
+        let mut lines = BufReader::new(reader).lines();
+        writer.write_all(b"VERSION:2.0.0\r\n").await.unwrap();
+        let state = lines.next_line().await.unwrap().unwrap();
+        assert!(state.starts_with("MATCHSTATE:1:1::"), "{state}");
+        writer.write_all(format!("{state}:f\r\n").as_bytes()).await.unwrap();
+        let result = lines.next_line().await.unwrap().unwrap();
+        assert!(result.starts_with("MATCHSTATE:1:1:f:"), "{result}");
+        // The next hand is dealt straight away; the bot now has the button and limps
+        let next = lines.next_line().await.unwrap().unwrap();
+        assert!(next.starts_with("MATCHSTATE:0:2:c:"), "{next}");
+
+        drop(writer);
+        drop(lines);
+        session.await.unwrap().unwrap();
+    }
+}
@@ -0,0 +1,492 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use tracing::info;
+use warp::{Filter, Rejection};
+
+use poker_core::bots::RuleBasedBot;
+use poker_core::game_controller::GameController;
+use poker_core::game_error::GameError;
+use crate::auto_dealer::spawn_auto_dealer;
+use crate::hand_history::collusion::{analyze, default_checks};
+use crate::table_registry::{TableHandle, DEFAULT_TABLE_ID};
+use super::auth::{admin, Role, Session};
+use super::rejections::reject;
+use super::requests::{AddBotRequest, AdminLoginRequest, ChipAdjustmentRequest, CreateTableRequest, LedgerQuery};
+use super::responses::{AdminActionResponse, AuditLogResponse, CollusionReportResponse, ErrorResponse, LedgerResponse, PlayerStateResponse, SessionResponse, TableCreatedResponse};
+use super::service::player_state;
+use super::warp_routes::{with_state, AppState};
+use super::validation::json_body;
+
+/// Player id recorded on admin sessions.
+const ADMIN_ID: &str = "admin";
+
+/// API route exchanging the configured admin token for an admin session.
+///
+/// # Endpoint
+/// `POST /v1/admin/login`
+///
+/// # Response
+/// - **Success**: Returns a session token carrying the admin role.
+/// - **Failure**: `401 Unauthorized` for a wrong token, `403 Forbidden` when no admin token is configured.
+#[utoipa::path(
+    post,
+    path = "/v1/admin/login",
+    tag = "admin",
+    request_body = AdminLoginRequest,
+    responses(
+        (status = 200, description = "A new admin session token", body = SessionResponse),
+        (status = 401, description = "Wrong admin token", body = ErrorResponse),
+        (status = 403, description = "Admin login is disabled", body = ErrorResponse),
+    )
+)]
+fn admin_login_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("admin" / "login")
+        .and(warp::post())
+        .and(json_body())
+        .and(with_state(state))
+        .and_then(|request: AdminLoginRequest, state: Arc<AppState>| async move {
+            state.authenticator.check_admin_token(&request.admin_token).map_err(reject)?;
+
+            let (token, session) = state.authenticator.issue(ADMIN_ID, DEFAULT_TABLE_ID, Role::Admin);
+            info!("admin logged in");
+            Ok::<_, Rejection>(warp::reply::json(&SessionResponse::new(token, session)))
+        })
+}
+
+/// API route removing a player from the table.
+///
+/// # Endpoint
+/// `POST /v1/admin/players/{player_id}/kick`
+///
+/// # Response
+/// - **Success**: Confirms the player was unseated.
+/// - **Failure**: `404 Not Found` for an unknown player, `409 Conflict` while they are in a hand.
+#[utoipa::path(
+    post,
+    path = "/v1/admin/players/{player_id}/kick",
+    tag = "admin",
+    params(("player_id" = String, Path, description = "Player to remove")),
+    responses(
+        (status = 200, description = "The player was removed", body = AdminActionResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse),
+        (status = 404, description = "No player with this id", body = ErrorResponse),
+        (status = 409, description = "The player is in the current hand", body = ErrorResponse),
+    )
+)]
+fn kick_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("admin" / "players" / String / "kick")
+        .and(warp::post())
+        .and(admin(state.clone()))
+        .and(with_state(state))
+        .and_then(|player_id: String, _: Session, state: Arc<AppState>| async move {
+            let player = state.tables.default_table()
+                .try_call(move |controller| controller.remove_player(&player_id))
+                .await
+                .map_err(reject)?;
+            let message = format!("{} removed from the table", player.display_name);
+            Ok::<_, Rejection>(warp::reply::json(&AdminActionResponse::new(message)))
+        })
+}
+
+/// API route forcing a player to sit out, folding any hand they hold.
+///
+/// # Endpoint
+/// `POST /v1/admin/players/{player_id}/sit_out`
+///
+/// # Response
+/// - **Success**: Confirms the player is sitting out.
+/// - **Failure**: `404 Not Found` for an unknown player.
+#[utoipa::path(
+    post,
+    path = "/v1/admin/players/{player_id}/sit_out",
+    tag = "admin",
+    params(("player_id" = String, Path, description = "Player to sit out")),
+    responses(
+        (status = 200, description = "The player is sitting out", body = AdminActionResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse),
+        (status = 404, description = "No player with this id", body = ErrorResponse),
+    )
+)]
+fn sit_out_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("admin" / "players" / String / "sit_out")
+        .and(warp::post())
+        .and(admin(state.clone()))
+        .and(with_state(state))
+        .and_then(|player_id: String, _: Session, state: Arc<AppState>| async move {
+            let seat = player_id.clone();
+            state.tables.default_table()
+                .try_call(move |controller| controller.sit_out_player(&seat))
+                .await
+                .map_err(reject)?;
+            let message = format!("Player {} is sitting out", player_id);
+            Ok::<_, Rejection>(warp::reply::json(&AdminActionResponse::new(message)))
+        })
+}
+
+/// API route adding chips to, or removing chips from, a player's stack.
+///
+/// # Endpoint
+/// `POST /v1/admin/players/{player_id}/chips`
+///
+/// # Response
+/// - **Success**: Returns the player's seat with the new stack.
+/// - **Failure**: `400 Bad Request` if the stack would go negative, `404 Not Found` for an unknown player.
+#[utoipa::path(
+    post,
+    path = "/v1/admin/players/{player_id}/chips",
+    tag = "admin",
+    params(("player_id" = String, Path, description = "Player whose stack changes")),
+    request_body = ChipAdjustmentRequest,
+    responses(
+        (status = 200, description = "The player's updated seat", body = PlayerStateResponse),
+        (status = 400, description = "The stack would go negative", body = ErrorResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse),
+        (status = 404, description = "No player with this id", body = ErrorResponse),
+    )
+)]
+fn adjust_chips_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("admin" / "players" / String / "chips")
+        .and(warp::post())
+        .and(admin(state.clone()))
+        .and(json_body())
+        .and(with_state(state))
+        .and_then(|player_id: String, _: Session, request: ChipAdjustmentRequest, state: Arc<AppState>| async move {
+            let table = state.tables.default_table();
+            let adjusted = player_id.clone();
+            table.try_call(move |controller| controller.adjust_chips(&adjusted, request.delta)).await.map_err(reject)?;
+            let player = player_state(&table, player_id).await.map_err(reject)?;
+            Ok::<_, Rejection>(warp::reply::json(&player))
+        })
+}
+
+/// API route seating a computer player at the next free position.
+///
+/// # Endpoint
+/// `POST /v1/add_bot`
+///
+/// # Response
+/// - **Success**: Returns the bot's seat. It acts on its own whenever it is its turn.
+/// - **Failure**: `400 Bad Request` if the player id is already seated,
+///   `403 Forbidden` when every seat is taken or the table plays for a
+///   tracked currency, which bots have no balance in.
+#[utoipa::path(
+    post,
+    path = "/v1/add_bot",
+    tag = "admin",
+    request_body = AddBotRequest,
+    responses(
+        (status = 200, description = "The bot's seat", body = PlayerStateResponse),
+        (status = 400, description = "The player id is taken", body = ErrorResponse),
+        (status = 403, description = "Admin role required, the table is full or it plays for a tracked currency", body = ErrorResponse),
+    )
+)]
+fn add_bot_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("add_bot")
+        .and(warp::post())
+        .and(admin(state.clone()))
+        .and(json_body())
+        .and(with_state(state))
+        .and_then(|_: Session, request: AddBotRequest, state: Arc<AppState>| async move {
+            let difficulty = request.difficulty;
+            let table = state.tables.default_table();
+            let player_id = request.player_id.clone();
+            table.try_call(move |controller| {
+                let bot = RuleBasedBot::with_difficulty(difficulty, StdRng::from_entropy());
+                controller.seat_bot(&request.player_id, &request.display_name, request.chip_stack, Box::new(bot))
+            }).await.map_err(reject)?;
+            let player = player_state(&table, player_id).await.map_err(reject)?;
+            info!(player_id = %player.player_id, ?difficulty, "bot added");
+            Ok::<_, Rejection>(warp::reply::json(&player))
+        })
+}
+
+/// API route opening a new, empty table with the given settings. Players
+/// take a seat through `POST /v1/tables/{table_id}/join`.
+///
+/// # Endpoint
+/// `POST /v1/tables`
+///
+/// # Response
+/// - **Success**: Returns the table's id and its settings, defaults filled in.
+/// - **Failure**: `400 Bad Request` for settings that don't make a playable table, `409 Conflict` if the id is taken.
+#[utoipa::path(
+    post,
+    path = "/v1/tables",
+    tag = "admin",
+    request_body = CreateTableRequest,
+    responses(
+        (status = 200, description = "The table is open", body = TableCreatedResponse),
+        (status = 400, description = "Invalid table settings", body = ErrorResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse),
+        (status = 409, description = "A table with this id already exists", body = ErrorResponse),
+    )
+)]
+fn create_table_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("tables")
+        .and(warp::post())
+        .and(admin(state.clone()))
+        .and(json_body())
+        .and(with_state(state))
+        .and_then(|_: Session, request: CreateTableRequest, state: Arc<AppState>| async move {
+            let mut controller = GameController::new();
+            controller.set_bank(state.bankroll.clone());
+            controller.configure(&request.config).map_err(reject)?;
+            let table = TableHandle::new(request.table_id.clone(), controller);
+            state.tables.insert(table.clone()).map_err(reject)?;
+            if let Some(pause_secs) = request.auto_deal_pause_secs {
+                spawn_auto_dealer(&table, Duration::from_secs(pause_secs));
+            }
+            info!(table_id = %table.id, variant = ?request.config.variant, "table opened");
+            Ok::<_, Rejection>(warp::reply::json(&TableCreatedResponse::new(request.table_id, request.config)))
+        })
+}
+
+/// Filter matching `admin/tables/{table_id}/<action>`, and `admin/<action>`
+/// as an alias acting on the default table; extracts the table id.
+fn table_action(action: &'static str) -> impl Filter<Extract = (String,), Error = warp::Rejection> + Clone {
+    let scoped = warp::path!("admin" / "tables" / String / ..).and(warp::path(action)).and(warp::path::end());
+    let default = warp::path("admin").and(warp::path(action)).and(warp::path::end()).map(|| DEFAULT_TABLE_ID.to_string());
+    scoped.or(default).unify()
+}
+
+/// API route pausing a table for a break, a dispute or maintenance: no new
+/// hand is dealt there until it is resumed. `POST /v1/admin/pause` pauses
+/// the default table.
+///
+/// # Endpoint
+/// `POST /v1/admin/tables/{table_id}/pause`
+///
+/// # Response
+/// - **Success**: Confirms the table is paused; a hand in progress may still finish.
+/// - **Failure**: `404 Not Found` for an unknown table.
+#[utoipa::path(
+    post,
+    path = "/v1/admin/tables/{table_id}/pause",
+    tag = "admin",
+    params(("table_id" = String, Path, description = "Table to pause")),
+    responses(
+        (status = 200, description = "The table is paused", body = AdminActionResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse),
+        (status = 404, description = "No table with this id", body = ErrorResponse),
+    )
+)]
+fn pause_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    table_action("pause")
+        .and(warp::post())
+        .and(admin(state.clone()))
+        .and(with_state(state))
+        .and_then(|table_id: String, _: Session, state: Arc<AppState>| async move {
+            let table = state.tables.get(&table_id).map_err(reject)?;
+            table.call(|controller| controller.pause()).await.map_err(reject)?;
+            info!(%table_id, "table paused by admin");
+            Ok::<_, Rejection>(warp::reply::json(&AdminActionResponse::new("Table paused")))
+        })
+}
+
+/// API route letting a paused table deal again. `POST /v1/admin/resume`
+/// resumes the default table.
+///
+/// # Endpoint
+/// `POST /v1/admin/tables/{table_id}/resume`
+///
+/// # Response
+/// - **Success**: Confirms the table is accepting new hands.
+/// - **Failure**: `404 Not Found` for an unknown table.
+#[utoipa::path(
+    post,
+    path = "/v1/admin/tables/{table_id}/resume",
+    tag = "admin",
+    params(("table_id" = String, Path, description = "Table to resume")),
+    responses(
+        (status = 200, description = "The table is resumed", body = AdminActionResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse),
+        (status = 404, description = "No table with this id", body = ErrorResponse),
+    )
+)]
+fn resume_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    table_action("resume")
+        .and(warp::post())
+        .and(admin(state.clone()))
+        .and(with_state(state))
+        .and_then(|table_id: String, _: Session, state: Arc<AppState>| async move {
+            let table = state.tables.get(&table_id).map_err(reject)?;
+            table.call(|controller| controller.resume()).await.map_err(reject)?;
+            info!(%table_id, "table resumed by admin");
+            Ok::<_, Rejection>(warp::reply::json(&AdminActionResponse::new("Table resumed")))
+        })
+}
+
+/// API route ending the current hand and refunding every bet.
+///
+/// # Endpoint
+/// `POST /v1/admin/end_hand`
+///
+/// # Response
+/// - **Success**: Confirms the hand was cancelled.
+/// - **Failure**: `409 Conflict` if no hand is in progress.
+#[utoipa::path(
+    post,
+    path = "/v1/admin/end_hand",
+    tag = "admin",
+    responses(
+        (status = 200, description = "The hand was cancelled and bets refunded", body = AdminActionResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse),
+        (status = 409, description = "No hand is in progress", body = ErrorResponse),
+    )
+)]
+fn end_hand_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("admin" / "end_hand")
+        .and(warp::post())
+        .and(admin(state.clone()))
+        .and(with_state(state))
+        .and_then(|_: Session, state: Arc<AppState>| async move {
+            state.tables.default_table().try_call(|controller| {
+                if !controller.is_hand_in_progress() {
+                    return Err(GameError::InvalidState("no hand is in progress".to_string()));
+                }
+                controller.cancel_hand();
+                Ok(())
+            }).await.map_err(reject)?;
+            Ok::<_, Rejection>(warp::reply::json(&AdminActionResponse::new("Hand cancelled and bets refunded")))
+        })
+}
+
+/// API route exporting a table's deal audit log: every card dealt there,
+/// hash-chained so any later edit to the log shows.
+///
+/// # Endpoint
+/// `GET /v1/admin/tables/{table_id}/audit`
+///
+/// # Response
+/// - **Success**: Every entry, oldest first, and whether the chain is intact.
+/// - **Failure**: `404 Not Found` for an unknown table.
+#[utoipa::path(
+    get,
+    path = "/v1/admin/tables/{table_id}/audit",
+    tag = "admin",
+    params(("table_id" = String, Path, description = "Table whose deals to export")),
+    responses(
+        (status = 200, description = "The table's deal audit log", body = AuditLogResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse),
+        (status = 404, description = "No table with this id", body = ErrorResponse),
+    )
+)]
+fn audit_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("admin" / "tables" / String / "audit")
+        .and(warp::get())
+        .and(admin(state.clone()))
+        .and(with_state(state))
+        .and_then(|table_id: String, _: Session, state: Arc<AppState>| async move {
+            let table = state.tables.get(&table_id).map_err(reject)?;
+            let audit = table.audit.clone();
+            // Read on the table's task, so the cards dealt by every earlier command are in
+            let entries = table
+                .call(move |_| audit.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).entries().to_vec())
+                .await
+                .map_err(reject)?;
+            Ok::<_, Rejection>(warp::reply::json(&AuditLogResponse::new(table_id, entries)))
+        })
+}
+
+/// API route listing a table's chip transactions: buy-ins, bets, pots
+/// won, the jackpot drop, bonuses and cash-outs, each between two accounts.
+///
+/// # Endpoint
+/// `GET /v1/admin/tables/{table_id}/ledger?player_id=1`
+///
+/// # Response
+/// - **Success**: The transactions, oldest first, and whether the ledger
+///   reconciles with the stacks and the pot.
+/// - **Failure**: `404 Not Found` for an unknown table.
+#[utoipa::path(
+    get,
+    path = "/v1/admin/tables/{table_id}/ledger",
+    tag = "admin",
+    params(("table_id" = String, Path, description = "Table whose ledger to list"), LedgerQuery),
+    responses(
+        (status = 200, description = "The table's chip transactions", body = LedgerResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse),
+        (status = 404, description = "No table with this id", body = ErrorResponse),
+    )
+)]
+fn ledger_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("admin" / "tables" / String / "ledger")
+        .and(warp::get())
+        .and(warp::query::<LedgerQuery>())
+        .and(admin(state.clone()))
+        .and(with_state(state))
+        .and_then(|table_id: String, query: LedgerQuery, _: Session, state: Arc<AppState>| async move {
+            let table = state.tables.get(&table_id).map_err(reject)?;
+            let (reconciled, transactions) = table
+                .call(move |controller| {
+                    let ledger = controller.ledger();
+                    let transactions = match &query.player_id {
+                        Some(player_id) => ledger.player_transactions(player_id).cloned().collect(),
+                        None => ledger.transactions().cloned().collect(),
+                    };
+                    (controller.reconcile_ledger().is_ok(), transactions)
+                })
+                .await
+                .map_err(reject)?;
+            Ok::<_, Rejection>(warp::reply::json(&LedgerResponse::new(table_id, reconciled, transactions)))
+        })
+}
+
+/// API route running the collusion checks over a table's finished hands:
+/// chip dumping, folding to one opponent far more than to anyone else, and
+/// players dealt in together who logged in from the same address.
+///
+/// # Endpoint
+/// `GET /v1/admin/tables/{table_id}/collusion`
+///
+/// # Response
+/// - **Success**: Every flag raised, check by check; none for a clean table.
+/// - **Failure**: `404 Not Found` for an unknown table.
+#[utoipa::path(
+    get,
+    path = "/v1/admin/tables/{table_id}/collusion",
+    tag = "admin",
+    params(("table_id" = String, Path, description = "Table whose hands to analyze")),
+    responses(
+        (status = 200, description = "The table's collusion report", body = CollusionReportResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse),
+        (status = 404, description = "No table with this id", body = ErrorResponse),
+    )
+)]
+fn collusion_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("admin" / "tables" / String / "collusion")
+        .and(warp::get())
+        .and(admin(state.clone()))
+        .and(with_state(state))
+        .and_then(|table_id: String, _: Session, state: Arc<AppState>| async move {
+            let table = state.tables.get(&table_id).map_err(reject)?;
+            let (history, addresses) = (table.history.clone(), state.logins.snapshot());
+            let (hands_analyzed, flags) = table
+                .call(move |_| analyze(&history.lock().unwrap_or_else(|poisoned| poisoned.into_inner()), &addresses, &default_checks()))
+                .await
+                .map_err(reject)?;
+            if !flags.is_empty() {
+                info!(%table_id, flags = flags.len(), "collusion flags raised");
+            }
+            Ok::<_, Rejection>(warp::reply::json(&CollusionReportResponse::new(table_id, hands_analyzed, flags)))
+        })
+}
+
+/// All admin routes, mounted inside each API version.
+pub fn admin_routes(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    admin_login_route(state.clone())
+        .or(kick_route(state.clone()))
+        .or(sit_out_route(state.clone()))
+        .or(adjust_chips_route(state.clone()))
+        .or(add_bot_route(state.clone()))
+        .or(create_table_route(state.clone()))
+        .or(pause_route(state.clone()))
+        .or(resume_route(state.clone()))
+        .or(end_hand_route(state.clone()))
+        .or(audit_route(state.clone()))
+        .or(ledger_route(state.clone()))
+        .or(collusion_route(state))
+}
@@ -0,0 +1,175 @@
+use std::sync::Arc;
+
+use poker_core::card_dealer::parse_cards;
+use poker_core::equity::equity_vs_range;
+use poker_core::game_error::GameError;
+use poker_core::outs::analyze_outs;
+use poker_core::range::Range;
+use poker_core::solver::SolverSpot;
+use warp::{Filter, Rejection};
+
+use super::i18n::{locale, Locale};
+use super::rate_limit::rate_limited;
+use super::rejections::reject;
+use super::requests::{EquityVsRangeRequest, OutsQuery, SolveRequest};
+use super::responses::{EquityVsRangeResponse, ErrorResponse, OutsResponse, SolveResponse};
+use super::warp_routes::{with_state, AppState};
+use super::validation::json_body;
+
+/// Runouts per villain hand when the request doesn't say.
+const DEFAULT_RANGE_TRIALS: u32 = 1_000;
+
+/// Most runouts per villain hand a request may ask for; a full range at
+/// this many takes a few seconds of CPU.
+const MAX_RANGE_TRIALS: u32 = 10_000;
+
+/// API route listing the draws in a Hold'em hand on the flop or turn, and
+/// every unseen card that would improve it.
+///
+/// Works on the cards given, not on any table, so bots and training UIs can
+/// ask about hands of their own.
+///
+/// # Endpoint
+/// `GET /v1/analysis/outs?hole_cards=9h,Th&board=Jh,Qc,2h`
+///
+/// # Response
+/// - **Success**: Returns the hand so far, its draws and its outs.
+/// - **Failure**: `400 Bad Request` for unreadable or repeated cards, or
+///   anything but two hole cards with a flop or turn.
+#[utoipa::path(
+    get,
+    path = "/v1/analysis/outs",
+    tag = "analysis",
+    params(OutsQuery),
+    responses(
+        (status = 200, description = "Draws and outs", body = OutsResponse),
+        (status = 400, description = "Bad or repeated cards, or the wrong number of them", body = ErrorResponse),
+        (status = 429, description = "Too many requests from this client", body = ErrorResponse),
+    )
+)]
+fn outs_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("analysis" / "outs")
+        .and(warp::get())
+        .and(rate_limited(state))
+        .and(warp::query::<OutsQuery>())
+        .and(locale())
+        .and_then(|query: OutsQuery, locale: Locale| async move {
+            let analysis = parse_cards(&query.hole_cards)
+                .and_then(|hole_cards| analyze_outs(&hole_cards, &parse_cards(&query.board)?))
+                .map_err(reject)?;
+            Ok::<_, Rejection>(warp::reply::json(&OutsResponse::new(analysis, locale)))
+        })
+}
+
+/// API route estimating a hand's equity against a range of villain hands,
+/// and against each hand in it.
+///
+/// Every combination of the range not blocked by the hero's cards or the
+/// board is equally likely. The runouts are seeded the same way for every
+/// request, so asking again gives the same answer.
+///
+/// # Endpoint
+/// `POST /v1/equity_vs_range`
+///
+/// # Response
+/// - **Success**: Returns the hero's equity and the breakdown per villain hand.
+/// - **Failure**: `400 Bad Request` for unreadable or repeated cards, a bad
+///   range or one the known cards block entirely, or too many trials.
+#[utoipa::path(
+    post,
+    path = "/v1/equity_vs_range",
+    tag = "analysis",
+    request_body = EquityVsRangeRequest,
+    responses(
+        (status = 200, description = "Equity against the range and each hand in it", body = EquityVsRangeResponse),
+        (status = 400, description = "Bad cards, range or trial count", body = ErrorResponse),
+        (status = 429, description = "Too many requests from this client", body = ErrorResponse),
+    )
+)]
+fn equity_vs_range_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("equity_vs_range")
+        .and(warp::post())
+        .and(rate_limited(state))
+        .and(json_body())
+        .and_then(|request: EquityVsRangeRequest| async move {
+            let trials = request.trials.unwrap_or(DEFAULT_RANGE_TRIALS);
+            if trials > MAX_RANGE_TRIALS {
+                return Err(reject(GameError::InvalidRequest(format!("at most {MAX_RANGE_TRIALS} trials per hand"))));
+            }
+            let hero = parse_cards(&request.hero).map_err(reject)?;
+            let board = parse_cards(&request.board).map_err(reject)?;
+            let range: Range = request.range.parse().map_err(reject)?;
+            // Thousands of runouts per hand would hold up the other requests on this thread
+            let result = tokio::task::spawn_blocking(move || equity_vs_range(&hero, &range, &board, trials, 0))
+                .await
+                .expect("the equity runs don't panic")
+                .map_err(reject)?;
+            Ok::<_, Rejection>(warp::reply::json(&EquityVsRangeResponse::new(result)))
+        })
+}
+
+/// API route asking the configured solver for the strategy of the player
+/// out of position in a two-player postflop spot.
+///
+/// The solver is whatever `SolverBackend` the server was started with,
+/// usually an external process; see `solver.rs` for what it is sent and
+/// must answer.
+///
+/// # Endpoint
+/// `POST /v1/analysis/solve`
+///
+/// # Response
+/// - **Success**: Returns how often the range, and each hand in it, takes
+///   each action.
+/// - **Failure**: `400 Bad Request` for unreadable cards or ranges, or a
+///   spot a solver can't be asked about, `403 Forbidden` when no solver is
+///   configured, `500 Internal Server Error` when the solver fails.
+#[utoipa::path(
+    post,
+    path = "/v1/analysis/solve",
+    tag = "analysis",
+    request_body = SolveRequest,
+    responses(
+        (status = 200, description = "The solver's strategy", body = SolveResponse),
+        (status = 400, description = "Bad cards, ranges or spot", body = ErrorResponse),
+        (status = 403, description = "No solver is configured", body = ErrorResponse),
+        (status = 429, description = "Too many requests from this client", body = ErrorResponse),
+        (status = 500, description = "The solver failed or gave a bad answer", body = ErrorResponse),
+    )
+)]
+fn solve_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("analysis" / "solve")
+        .and(warp::post())
+        .and(rate_limited(state.clone()))
+        .and(json_body())
+        .and(with_state(state))
+        .and_then(|request: SolveRequest, state: Arc<AppState>| async move {
+            let solver = state.solver.clone()
+                .ok_or_else(|| reject(GameError::NotAllowed("no solver is configured".to_string())))?;
+            let [out_of_position, in_position] = request.ranges.as_slice() else {
+                return Err(reject(GameError::InvalidRequest("a spot needs two ranges".to_string())));
+            };
+            let spot = SolverSpot {
+                ranges: [out_of_position.parse().map_err(reject)?, in_position.parse().map_err(reject)?],
+                board: parse_cards(&request.board).map_err(reject)?,
+                pot: request.pot,
+                effective_stack: request.effective_stack,
+                bet_sizes: request.bet_sizes,
+            };
+            spot.validate().map_err(reject)?;
+            // Solvers can take minutes; keep them off the threads serving other requests
+            let strategy = tokio::task::spawn_blocking({
+                let solver = solver.clone();
+                move || solver.solve(&spot)
+            })
+                .await
+                .expect("the solver doesn't panic")
+                .map_err(reject)?;
+            Ok::<_, Rejection>(warp::reply::json(&SolveResponse::new(solver.name(), strategy)))
+        })
+}
+
+/// All hand analysis routes.
+pub fn analysis_routes(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    outs_route(state.clone()).or(equity_vs_range_route(state.clone())).or(solve_route(state))
+}
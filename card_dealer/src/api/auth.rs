@@ -0,0 +1,242 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha2::Sha256;
+use warp::{Filter, Rejection};
+
+use crate::config::AuthConfig;
+use poker_core::game_error::GameError;
+use super::rejections::reject;
+use super::warp_routes::AppState;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// What a session is allowed to do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    Player, // Acts only on its own seat
+    Admin,  // Moderates the table through the `/v1/admin` routes
+}
+
+impl Role {
+    fn as_str(self) -> &'static str {
+        match self {
+            Role::Player => "player",
+            Role::Admin => "admin",
+        }
+    }
+
+    fn parse(role: &str) -> Option<Self> {
+        match role {
+            "player" => Some(Role::Player),
+            "admin" => Some(Role::Admin),
+            _ => None,
+        }
+    }
+}
+
+/// An authenticated caller, recovered from a session token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Session {
+    pub player_id: String, // Player the token was issued to ("admin" for admin logins)
+    pub table_id: String,  // Table the player's seat-scoped requests go to
+    pub role: Role,        // Player or admin
+    pub expires_at: u64,   // Unix time (seconds) after which the token is rejected
+}
+
+/// Issues and verifies session tokens.
+///
+/// A token has the form `<player_id>.<table_id>.<role>.<expires_at>.<signature>`,
+/// where the signature is a hex HMAC-SHA256 over the first four parts. Tokens are
+/// stateless, so any server sharing the secret accepts them.
+pub struct Authenticator {
+    key: Vec<u8>,
+    ttl_secs: u64,
+    passwords: HashMap<String, String>,
+    open_login: bool,
+    admin_token: Option<String>,
+}
+
+impl Authenticator {
+    /// Uses the configured secret, or a random one (sessions then don't survive a restart).
+    pub fn new(config: &AuthConfig) -> Self {
+        let key = match &config.secret {
+            Some(secret) => secret.as_bytes().to_vec(),
+            None => {
+                let mut key = vec![0u8; 32];
+                rand::thread_rng().fill_bytes(&mut key);
+                key
+            }
+        };
+        Self {
+            key,
+            ttl_secs: config.session_ttl_secs,
+            passwords: config.passwords.clone(),
+            open_login: config.open_login,
+            admin_token: config.admin_token.clone(),
+        }
+    }
+
+    /// Checks a login attempt against the player's configured password.
+    /// Players without one are refused, unless open login lets them in by
+    /// id alone.
+    pub fn check_password(&self, player_id: &str, password: Option<&str>) -> Result<(), GameError> {
+        match (self.passwords.get(player_id), password) {
+            (Some(expected), Some(password)) if self.secrets_match(expected, password) => Ok(()),
+            (None, _) if self.open_login => Ok(()),
+            _ => Err(GameError::Unauthorized("invalid player id or password".to_string())),
+        }
+    }
+
+    /// Checks the admin token. Admin login is disabled while none is configured.
+    pub fn check_admin_token(&self, token: &str) -> Result<(), GameError> {
+        match &self.admin_token {
+            None => Err(GameError::NotAllowed("admin login is disabled".to_string())),
+            Some(expected) if self.secrets_match(expected, token) => Ok(()),
+            Some(_) => Err(GameError::Unauthorized("invalid admin token".to_string())),
+        }
+    }
+
+    /// Compares a secret with the one given, in time that depends on neither:
+    /// both are signed and the signatures compared in constant time.
+    fn secrets_match(&self, expected: &str, given: &str) -> bool {
+        let given = self.sign(given).finalize().into_bytes();
+        self.sign(expected).verify_slice(&given).is_ok()
+    }
+
+    /// Issues a fresh token for `player_id` acting as `role` at `table_id`.
+    pub fn issue(&self, player_id: &str, table_id: &str, role: Role) -> (String, Session) {
+        self.issue_at(player_id, table_id, role, now_unix())
+    }
+
+    fn issue_at(&self, player_id: &str, table_id: &str, role: Role, now: u64) -> (String, Session) {
+        let session = Session {
+            player_id: player_id.to_string(),
+            table_id: table_id.to_string(),
+            role,
+            expires_at: now + self.ttl_secs,
+        };
+        let payload = format!("{}.{}.{}.{}", session.player_id, session.table_id, role.as_str(), session.expires_at);
+        let token = format!("{}.{}", payload, hex::encode(self.sign(&payload).finalize().into_bytes()));
+        (token, session)
+    }
+
+    /// Checks a token's signature and expiry.
+    pub fn verify(&self, token: &str) -> Result<Session, GameError> {
+        self.verify_at(token, now_unix())
+    }
+
+    fn verify_at(&self, token: &str, now: u64) -> Result<Session, GameError> {
+        let invalid = || GameError::Unauthorized("invalid session token".to_string());
+
+        let (payload, signature) = token.rsplit_once('.').ok_or_else(invalid)?;
+        let signature = hex::decode(signature).map_err(|_| invalid())?;
+        self.sign(payload).verify_slice(&signature).map_err(|_| invalid())?;
+
+        let (rest, expires_at) = payload.rsplit_once('.').ok_or_else(invalid)?;
+        let (rest, role) = rest.rsplit_once('.').ok_or_else(invalid)?;
+        let (player_id, table_id) = rest.rsplit_once('.').ok_or_else(invalid)?; // Table ids have no dots; player ids may
+        let role = Role::parse(role).ok_or_else(invalid)?;
+        let expires_at: u64 = expires_at.parse().map_err(|_| invalid())?;
+        if expires_at <= now {
+            return Err(GameError::Unauthorized("session expired".to_string()));
+        }
+
+        Ok(Session { player_id: player_id.to_string(), table_id: table_id.to_string(), role, expires_at })
+    }
+
+    fn sign(&self, payload: &str) -> HmacSha256 {
+        let mut mac = HmacSha256::new_from_slice(&self.key).expect("HMAC accepts keys of any length");
+        mac.update(payload.as_bytes());
+        mac
+    }
+}
+
+fn now_unix() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|elapsed| elapsed.as_secs()).unwrap_or(0)
+}
+
+/// Filter requiring a valid `Authorization: Bearer <token>` header.
+///
+/// Every player-scoped route chains this and acts only on behalf of the
+/// returned `Session`; failures are rejected with a 401.
+pub fn authenticated(state: Arc<AppState>) -> impl Filter<Extract = (Session,), Error = Rejection> + Clone {
+    warp::header::optional::<String>("authorization").and_then(move |header: Option<String>| {
+        let state = state.clone();
+        async move {
+            let header = header
+                .ok_or_else(|| GameError::Unauthorized("missing Authorization header".to_string()))
+                .map_err(reject)?;
+            let token = header
+                .strip_prefix("Bearer ")
+                .ok_or_else(|| GameError::Unauthorized("expected a Bearer token".to_string()))
+                .map_err(reject)?;
+            state.authenticator.verify(token.trim()).map_err(reject)
+        }
+    })
+}
+
+/// Filter requiring a valid session with the `Admin` role; player sessions get a 403.
+pub fn admin(state: Arc<AppState>) -> impl Filter<Extract = (Session,), Error = Rejection> + Clone {
+    authenticated(state).and_then(|session: Session| async move {
+        if session.role == Role::Admin {
+            Ok(session)
+        } else {
+            Err(reject(GameError::NotAllowed("admin role required".to_string())))
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn authenticator() -> Authenticator {
+        Authenticator::new(&AuthConfig {
+            secret: Some("test-secret".to_string()),
+            session_ttl_secs: 60,
+            passwords: HashMap::from([("1".to_string(), "hunter2".to_string())]),
+            open_login: false,
+            admin_token: Some("host-token".to_string()),
+        })
+    }
+
+    #[test]
+    fn test_token_round_trip_and_expiry() {
+        let auth = authenticator();
+        let (token, session) = auth.issue_at("1", "main", Role::Player, 1_000);
+        assert_eq!(session.expires_at, 1_060);
+        assert_eq!(auth.verify_at(&token, 1_030), Ok(session));
+        assert!(auth.verify_at(&token, 1_060).is_err());
+    }
+
+    #[test]
+    fn test_tampered_token_rejected() {
+        let auth = authenticator();
+        let (token, _) = auth.issue_at("1", "main", Role::Player, 1_000);
+        let forged = token.replacen("1.", "2.", 1);
+        assert!(auth.verify_at(&forged, 1_000).is_err());
+        let moved = token.replacen(".main.", ".side.", 1);
+        assert!(auth.verify_at(&moved, 1_000).is_err());
+        let promoted = token.replacen(".player.", ".admin.", 1);
+        assert!(auth.verify_at(&promoted, 1_000).is_err());
+    }
+
+    #[test]
+    fn test_password_check() {
+        let auth = authenticator();
+        assert!(auth.check_password("1", Some("hunter2")).is_ok());
+        assert!(auth.check_password("1", None).is_err());
+        assert!(auth.check_password("1", Some("hunter")).is_err());
+        assert!(auth.check_password("2", None).is_err()); // Unknown players are refused
+        assert!(auth.check_admin_token("host-token").is_ok());
+        assert!(auth.check_admin_token("guess").is_err());
+
+        let open = Authenticator { open_login: true, ..auth };
+        assert!(open.check_password("2", None).is_ok());
+        assert!(open.check_password("1", None).is_err()); // A configured password is still needed
+    }
+}
@@ -0,0 +1,78 @@
+use std::sync::Arc;
+
+use tracing::info;
+use warp::{Filter, Rejection};
+
+use super::auth::{admin, authenticated, Session};
+use super::rejections::reject;
+use super::requests::ChipAdjustmentRequest;
+use super::responses::{BalanceResponse, ErrorResponse};
+use super::warp_routes::{with_state, AppState};
+use super::validation::json_body;
+
+/// API route showing the caller's balance, the chips tracked-currency
+/// tables draw on when they sit down or buy in.
+///
+/// # Endpoint
+/// `GET /v1/balance`
+///
+/// # Response
+/// - **Success**: Returns the balance, `0` for a player never credited.
+/// - **Failure**: `401 Unauthorized` without a valid session.
+#[utoipa::path(
+    get,
+    path = "/v1/balance",
+    tag = "bankroll",
+    responses(
+        (status = 200, description = "The caller's balance", body = BalanceResponse),
+        (status = 401, description = "Missing or invalid session token", body = ErrorResponse),
+    )
+)]
+fn balance_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("balance")
+        .and(warp::get())
+        .and(authenticated(state.clone()))
+        .and(with_state(state))
+        .and_then(|session: Session, state: Arc<AppState>| async move {
+            let balance = state.bankroll.balance(&session.player_id);
+            Ok::<_, Rejection>(warp::reply::json(&BalanceResponse::new(session.player_id, balance)))
+        })
+}
+
+/// API route crediting chips to, or debiting chips from, a player's balance.
+///
+/// # Endpoint
+/// `POST /v1/admin/players/{player_id}/balance`
+///
+/// # Response
+/// - **Success**: Returns the new balance.
+/// - **Failure**: `400 Bad Request` if the balance would go negative.
+#[utoipa::path(
+    post,
+    path = "/v1/admin/players/{player_id}/balance",
+    tag = "bankroll",
+    params(("player_id" = String, Path, description = "Player whose balance changes")),
+    request_body = ChipAdjustmentRequest,
+    responses(
+        (status = 200, description = "The player's new balance", body = BalanceResponse),
+        (status = 400, description = "The balance would go negative", body = ErrorResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse),
+    )
+)]
+fn adjust_balance_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("admin" / "players" / String / "balance")
+        .and(warp::post())
+        .and(admin(state.clone()))
+        .and(json_body())
+        .and(with_state(state))
+        .and_then(|player_id: String, _: Session, request: ChipAdjustmentRequest, state: Arc<AppState>| async move {
+            let balance = state.bankroll.adjust(&player_id, request.delta).map_err(reject)?;
+            info!(%player_id, delta = request.delta, %balance, "balance adjusted");
+            Ok::<_, Rejection>(warp::reply::json(&BalanceResponse::new(player_id, balance)))
+        })
+}
+
+/// All player balance routes.
+pub fn bankroll_routes(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    balance_route(state.clone()).or(adjust_balance_route(state))
+}
@@ -0,0 +1,69 @@
+//! Routes for integration tests and QA, compiled only with the
+//! `debug-routes` feature. Never enable it on a table with real stakes.
+//! Both require an admin session.
+
+use std::sync::Arc;
+
+use tracing::info;
+use warp::{Filter, Rejection};
+
+use poker_core::card_dealer::parse_cards;
+use super::auth::{admin, Session};
+use super::rejections::reject;
+use super::requests::StackDeckRequest;
+use super::responses::{AdminActionResponse, InvariantsResponse};
+use super::warp_routes::{with_state, AppState};
+use super::validation::json_body;
+
+/// API route fixing the cards of the next hand.
+///
+/// # Endpoint
+/// `POST /v1/debug/set_deck`
+///
+/// # Response
+/// - **Success**: Confirms the deck is stacked; the next hand deals `cards` in order.
+/// - **Failure**: `400 Bad Request` for invalid or repeated cards, `409 Conflict` while a hand is in progress.
+fn set_deck_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("debug" / "set_deck")
+        .and(warp::post())
+        .and(admin(state.clone()))
+        .and(json_body())
+        .and(with_state(state))
+        .and_then(|_: Session, request: StackDeckRequest, state: Arc<AppState>| async move {
+            let cards = parse_cards(&request.cards).map_err(reject)?;
+            let count = cards.len();
+            state.tables.default_table()
+                .try_call(move |controller| controller.stack_deck(cards))
+                .await
+                .map_err(reject)?;
+            info!(cards = count, "deck stacked for the next hand");
+            Ok::<_, Rejection>(warp::reply::json(&AdminActionResponse::new(format!("Next hand deals {count} stacked cards first"))))
+        })
+}
+
+/// API route checking the table's game state for inconsistencies.
+///
+/// # Endpoint
+/// `GET /v1/debug/invariants`
+///
+/// # Response
+/// - **Success**: Lists every broken invariant; `ok` is true when there are none.
+fn invariants_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("debug" / "invariants")
+        .and(warp::get())
+        .and(admin(state.clone()))
+        .and(with_state(state))
+        .and_then(|_: Session, state: Arc<AppState>| async move {
+            let violations = state.tables.default_table()
+                .call(|controller| controller.verify_invariants().err().unwrap_or_default())
+                .await
+                .map_err(reject)?;
+            Ok::<_, Rejection>(warp::reply::json(&InvariantsResponse::new(violations)))
+        })
+}
+
+/// All debug routes, mounted inside each API version.
+pub fn debug_routes(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    set_deck_route(state.clone())
+        .or(invariants_route(state))
+}
@@ -0,0 +1,154 @@
+use std::io::Write;
+
+use flate2::write::GzEncoder;
+use sha2::{Digest, Sha256};
+use warp::http::header::{HeaderValue, CONTENT_ENCODING, CONTENT_LENGTH, ETAG, VARY};
+use warp::http::{Method, StatusCode};
+use warp::hyper::body::{to_bytes, Body, HttpBody};
+use warp::reply::Response;
+
+/// Bodies shorter than this are sent as they are; compressing them saves
+/// less than it costs.
+const MIN_COMPRESSED_LEN: usize = 1024;
+
+/// Brotli quality, traded against CPU time per response (0 to 11).
+const BROTLI_QUALITY: u32 = 5;
+
+/// Brotli window size as a power of two.
+const BROTLI_WINDOW_BITS: u32 = 22;
+
+/// Content codings the server can apply, most preferred first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Encoding {
+    Brotli,
+    Gzip,
+}
+
+impl Encoding {
+    fn name(self) -> &'static str {
+        match self {
+            Encoding::Brotli => "br",
+            Encoding::Gzip => "gzip",
+        }
+    }
+
+    fn compress(self, bytes: &[u8]) -> std::io::Result<Vec<u8>> {
+        match self {
+            Encoding::Brotli => {
+                let mut writer = brotli::CompressorWriter::new(Vec::new(), 4096, BROTLI_QUALITY, BROTLI_WINDOW_BITS);
+                writer.write_all(bytes)?;
+                Ok(writer.into_inner())
+            }
+            Encoding::Gzip => {
+                let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(bytes)?;
+                encoder.finish()
+            }
+        }
+    }
+}
+
+/// The coding to use for a client sending `accept_encoding`: whichever of
+/// ours it weights highest, Brotli on a tie, and none it refuses with `q=0`.
+fn preferred_encoding(accept_encoding: &str) -> Option<Encoding> {
+    let weight = |encoding: Encoding| -> f32 {
+        let mut wildcard = 0.0;
+        for entry in accept_encoding.split(',') {
+            let mut parts = entry.split(';').map(str::trim);
+            let name = parts.next().unwrap_or_default();
+            let q = parts
+                .find_map(|param| param.strip_prefix("q="))
+                .map_or(1.0, |q| q.parse().unwrap_or(0.0));
+            if name.eq_ignore_ascii_case(encoding.name()) {
+                return q;
+            }
+            if name == "*" {
+                wildcard = q;
+            }
+        }
+        wildcard
+    };
+    [Encoding::Brotli, Encoding::Gzip]
+        .into_iter()
+        .map(|encoding| (encoding, weight(encoding)))
+        .filter(|(_, q)| *q > 0.0)
+        .fold(None, |best: Option<(Encoding, f32)>, (encoding, q)| match best {
+            Some((_, best_q)) if best_q >= q => best,
+            _ => Some((encoding, q)),
+        })
+        .map(|(encoding, _)| encoding)
+}
+
+/// Whether `if_none_match` lists `etag`, or is `*`.
+fn etag_matches(if_none_match: &str, etag: &str) -> bool {
+    if_none_match.split(',').map(str::trim).any(|candidate| candidate == "*" || candidate == etag)
+}
+
+/// Finishes a successful response with a complete body: tags `GET`
+/// responses with an `ETag` and answers `304 Not Modified` when the client
+/// already has that version, then compresses large bodies in the client's
+/// preferred coding.
+///
+/// Streams such as Server-Sent Events, and responses that are already
+/// encoded, pass through as they are.
+pub async fn finish(method: &Method, accept_encoding: Option<&str>, if_none_match: Option<&str>, response: Response) -> Response {
+    let complete = response.body().size_hint().exact().is_some();
+    if response.status() != StatusCode::OK || !complete || response.headers().contains_key(CONTENT_ENCODING) {
+        return response;
+    }
+    let (mut parts, body) = response.into_parts();
+    let Ok(bytes) = to_bytes(body).await else {
+        return Response::new(Body::empty()); // A complete body can't fail to read
+    };
+
+    if method == Method::GET {
+        // Weak, as the same version may be sent compressed or not
+        let etag = format!("W/\"{}\"", hex::encode(&Sha256::digest(&bytes)[..16]));
+        let etag = HeaderValue::from_str(&etag).expect("hex digits make a valid header value");
+        if if_none_match.is_some_and(|if_none_match| etag_matches(if_none_match, etag.to_str().unwrap_or_default())) {
+            parts.status = StatusCode::NOT_MODIFIED;
+            parts.headers.remove(CONTENT_LENGTH);
+            parts.headers.insert(ETAG, etag);
+            return Response::from_parts(parts, Body::empty());
+        }
+        parts.headers.insert(ETAG, etag);
+    }
+
+    if bytes.len() < MIN_COMPRESSED_LEN {
+        return Response::from_parts(parts, Body::from(bytes));
+    }
+    parts.headers.append(VARY, HeaderValue::from_static("accept-encoding"));
+    match accept_encoding.and_then(preferred_encoding) {
+        Some(encoding) => match encoding.compress(&bytes) {
+            Ok(compressed) => {
+                parts.headers.remove(CONTENT_LENGTH);
+                parts.headers.insert(CONTENT_ENCODING, HeaderValue::from_static(encoding.name()));
+                Response::from_parts(parts, Body::from(compressed))
+            }
+            Err(_) => Response::from_parts(parts, Body::from(bytes)),
+        },
+        None => Response::from_parts(parts, Body::from(bytes)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preferred_encoding() {
+        assert_eq!(preferred_encoding("gzip, deflate, br"), Some(Encoding::Brotli));
+        assert_eq!(preferred_encoding("gzip"), Some(Encoding::Gzip));
+        assert_eq!(preferred_encoding("br;q=0.5, gzip;q=0.8"), Some(Encoding::Gzip));
+        assert_eq!(preferred_encoding("*;q=0.1, br;q=0"), Some(Encoding::Gzip));
+        assert_eq!(preferred_encoding("identity"), None);
+        assert_eq!(preferred_encoding("gzip;q=0"), None);
+    }
+
+    #[test]
+    fn test_etag_matches() {
+        assert!(etag_matches("W/\"ab\", W/\"cd\"", "W/\"cd\""));
+        assert!(etag_matches("*", "W/\"cd\""));
+        assert!(!etag_matches("W/\"ab\"", "W/\"cd\""));
+    }
+}
@@ -0,0 +1,241 @@
+use std::convert::Infallible;
+use std::sync::Arc;
+
+use async_graphql::http::GraphiQLSource;
+use async_graphql::{Context, EmptySubscription, Enum, Object, Schema, SimpleObject};
+use async_graphql_warp::GraphQLResponse;
+use warp::Filter;
+
+use poker_core::card_dealer::{self, Card};
+use poker_core::player::Player;
+use poker_core::player_id::PlayerId;
+use poker_core::poker_hand;
+use poker_core::table::Pot;
+use super::rate_limit::rate_limited;
+use super::AppState;
+
+pub type DealerSchema = Schema<QueryRoot, MutationRoot, EmptySubscription>;
+
+// GraphQL mirrors of the card enums, converted to/from the engine types
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+#[graphql(name = "Suit", remote = "card_dealer::Suit")]
+pub enum SuitValue {
+    Hearts,
+    Diamonds,
+    Clubs,
+    Spades,
+    Joker,
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+#[graphql(name = "Rank", remote = "card_dealer::Rank")]
+pub enum RankValue {
+    Two,
+    Three,
+    Four,
+    Five,
+    Six,
+    Seven,
+    Eight,
+    Nine,
+    Ten,
+    Jack,
+    Queen,
+    King,
+    Ace,
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+#[graphql(name = "HandRank", remote = "poker_hand::HandRank")]
+pub enum HandRankValue {
+    HighCard,
+    OnePair,
+    TwoPair,
+    ThreeOfAKind,
+    Straight,
+    Flush,
+    FullHouse,
+    FourOfAKind,
+    StraightFlush,
+    FiveOfAKind,
+}
+
+#[derive(SimpleObject)]
+#[graphql(name = "Card")]
+pub struct CardObject {
+    rank: RankValue,
+    suit: SuitValue,
+}
+
+impl From<&Card> for CardObject {
+    fn from(card: &Card) -> Self {
+        Self { rank: card.rank.into(), suit: card.suit.into() }
+    }
+}
+
+#[derive(SimpleObject)]
+#[graphql(name = "Player")]
+pub struct PlayerObject {
+    player_id: String,
+    display_name: String,
+    chip_stack: u64,
+    table_position: usize,
+    is_sitting_out: bool,
+    is_in_play: bool,
+    hole_cards: Vec<CardObject>,
+    hand_strength: Option<HandRankValue>,
+    best_hand: Option<Vec<CardObject>>,
+}
+
+impl From<&Player> for PlayerObject {
+    fn from(player: &Player) -> Self {
+        Self {
+            player_id: player.player_id.to_string(),
+            display_name: player.display_name.clone(),
+            chip_stack: player.chip_stack.get(),
+            table_position: player.table_position,
+            is_sitting_out: player.is_sitting_out,
+            is_in_play: player.is_in_play,
+            hole_cards: player.hole_cards.iter().map(CardObject::from).collect(),
+            hand_strength: player.hand_strength.clone().map(HandRankValue::from),
+            best_hand: player.best_hand.as_ref().map(|hand| hand.cards.iter().map(CardObject::from).collect()),
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+#[graphql(name = "Pot")]
+pub struct PotObject {
+    total: u64,
+    eligible_players: Vec<String>,
+    winners: Option<Vec<String>>,
+}
+
+impl From<&Pot> for PotObject {
+    fn from(pot: &Pot) -> Self {
+        Self {
+            total: pot.total.get(),
+            eligible_players: pot.eligible_players.iter().map(ToString::to_string).collect(),
+            winners: pot.winners.as_ref().map(|winners| winners.iter().map(ToString::to_string).collect()),
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+#[graphql(name = "Table")]
+pub struct TableObject {
+    community_cards: Vec<CardObject>,
+    pots: Vec<PotObject>,
+    players: Vec<PlayerObject>,
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// Current board, pots and seated players
+    async fn table(&self, ctx: &Context<'_>) -> async_graphql::Result<TableObject> {
+        let state = ctx.data_unchecked::<Arc<AppState>>();
+        let table = state.tables.default_table().call(|controller| TableObject {
+            community_cards: controller.get_community_cards().iter().map(CardObject::from).collect(),
+            pots: controller.get_table().pots.iter().map(PotObject::from).collect(),
+            players: controller.get_players().iter().map(PlayerObject::from).collect(),
+        }).await?;
+        Ok(table)
+    }
+
+    /// All players at the table
+    async fn players(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<PlayerObject>> {
+        let state = ctx.data_unchecked::<Arc<AppState>>();
+        let players = state.tables.default_table().call(|controller| {
+            controller.get_players().iter().map(PlayerObject::from).collect()
+        }).await?;
+        Ok(players)
+    }
+
+    /// A single player looked up by id
+    async fn player(&self, ctx: &Context<'_>, player_id: String) -> async_graphql::Result<Option<PlayerObject>> {
+        let state = ctx.data_unchecked::<Arc<AppState>>();
+        let player = state.tables.default_table().call(move |controller| {
+            controller.get_players().iter().find(|p| p.player_id == player_id).map(PlayerObject::from)
+        }).await?;
+        Ok(player)
+    }
+
+    /// The winning player(s) among everyone at the table
+    async fn winners(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<PlayerObject>> {
+        let state = ctx.data_unchecked::<Arc<AppState>>();
+        let winners = state.tables.default_table().call(|controller| {
+            let all_players: Vec<PlayerId> = controller.get_players().iter().map(|p| p.player_id.clone()).collect();
+            controller
+                .get_winners(&all_players)
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|player_id| controller.find_player(player_id).ok())
+                .map(PlayerObject::from)
+                .collect()
+        }).await?;
+        Ok(winners)
+    }
+}
+
+pub struct MutationRoot;
+
+#[Object]
+impl MutationRoot {
+    /// Deals two hole cards to every player
+    async fn deal_hole_cards(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<PlayerObject>> {
+        let state = ctx.data_unchecked::<Arc<AppState>>();
+        let players = state.tables.default_table().try_call(|controller| {
+            controller.deal_hole_cards()?;
+            Ok(controller.get_players().iter().map(PlayerObject::from).collect())
+        }).await?;
+        Ok(players)
+    }
+
+    /// Deals the five community cards and evaluates every hand
+    async fn deal_community_cards(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<CardObject>> {
+        let state = ctx.data_unchecked::<Arc<AppState>>();
+        let cards = state.tables.default_table().try_call(|controller| {
+            controller.deal_community_cards()?;
+            Ok(controller.get_community_cards().iter().map(CardObject::from).collect())
+        }).await?;
+        Ok(cards)
+    }
+
+    /// Resets the deck and clears every player's hand
+    async fn reset(&self, ctx: &Context<'_>) -> async_graphql::Result<bool> {
+        let state = ctx.data_unchecked::<Arc<AppState>>();
+        state.tables.default_table().call(|controller| controller.reset_deck()).await?;
+        Ok(true)
+    }
+}
+
+/// Builds the schema with the shared application state attached.
+pub fn build_schema(state: Arc<AppState>) -> DealerSchema {
+    Schema::build(QueryRoot, MutationRoot, EmptySubscription)
+        .data(state)
+        .finish()
+}
+
+/// GraphQL routes.
+///
+/// # Endpoints
+/// - `POST /graphql` executes a query or mutation.
+/// - `GET /graphiql` serves the GraphiQL explorer.
+pub fn graphql_routes(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    let graphql_post = warp::path("graphql")
+        .and(warp::path::end())
+        .and(rate_limited(state.clone()))
+        .and(async_graphql_warp::graphql(build_schema(state)))
+        .and_then(|(schema, request): (DealerSchema, async_graphql::Request)| async move {
+            Ok::<_, Infallible>(GraphQLResponse::from(schema.execute(request).await))
+        });
+
+    let graphiql = warp::path("graphiql")
+        .and(warp::path::end())
+        .and(warp::get())
+        .map(|| warp::reply::html(GraphiQLSource::build().endpoint("/graphql").finish()));
+
+    graphql_post.or(graphiql)
+}
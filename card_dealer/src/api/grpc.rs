@@ -0,0 +1,138 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use tonic::{Request, Response, Status};
+
+use poker_core::card_dealer::Card;
+use poker_core::game_controller::GameController;
+use poker_core::game_error::GameError;
+use poker_core::player::Player;
+use poker_core::player_id::PlayerId;
+use super::AppState;
+
+/// Messages and service stubs generated from `proto/dealer.proto`.
+pub mod proto {
+    tonic::include_proto!("dealer");
+}
+
+use proto::dealer_server::{Dealer, DealerServer};
+
+// The proto enums are declared in the same order as the Rust enums,
+// so the discriminants can be cast straight across.
+impl From<&Card> for proto::Card {
+    fn from(card: &Card) -> Self {
+        Self {
+            rank: card.rank as i32,
+            suit: card.suit as i32,
+        }
+    }
+}
+
+impl From<&Player> for proto::Player {
+    fn from(player: &Player) -> Self {
+        Self {
+            player_id: player.player_id.to_string(),
+            display_name: player.display_name.clone(),
+            chip_stack: player.chip_stack.get(),
+            table_position: player.table_position as u32,
+            is_sitting_out: player.is_sitting_out,
+            is_in_play: player.is_in_play,
+            hole_cards: player.hole_cards.iter().map(proto::Card::from).collect(),
+            hand_strength: player.hand_strength.clone().map(|rank| rank as i32),
+            best_hand: player
+                .best_hand
+                .as_ref()
+                .map(|hand| hand.cards.iter().map(proto::Card::from).collect())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Maps a `GameError` onto the gRPC status reported to the client.
+fn status(error: GameError) -> Status {
+    let message = error.to_string();
+    match error {
+        GameError::InvalidRequest(_) | GameError::DuplicateCard(_) => Status::invalid_argument(message),
+        GameError::Unauthorized(_) => Status::unauthenticated(message),
+        GameError::NotAllowed(_) => Status::permission_denied(message),
+        GameError::PlayerNotFound(_) | GameError::TableNotFound(_) | GameError::HandNotFound(_) | GameError::NoWinner => Status::not_found(message),
+        GameError::NotEnoughCards(_)
+        | GameError::InsufficientChips
+        | GameError::InvalidState(_) => Status::failed_precondition(message),
+        GameError::Internal(_) => Status::internal(message),
+    }
+}
+
+/// Snapshot of the board and every player's state
+fn game_state(controller: &GameController) -> proto::GameState {
+    proto::GameState {
+        community_cards: controller.get_community_cards().iter().map(proto::Card::from).collect(),
+        players: controller.get_players().iter().map(proto::Player::from).collect(),
+    }
+}
+
+/// gRPC implementation of the dealer operations, sharing state with the warp routes.
+pub struct DealerService {
+    state: Arc<AppState>,
+}
+
+#[tonic::async_trait]
+impl Dealer for DealerService {
+    async fn deal_hole(&self, _request: Request<proto::Empty>) -> Result<Response<proto::GameState>, Status> {
+        let state = self.state.tables.default_table().try_call(|controller| {
+            controller.deal_hole_cards()?;
+            Ok(game_state(controller))
+        }).await.map_err(status)?;
+        Ok(Response::new(state))
+    }
+
+    async fn deal_community(&self, _request: Request<proto::Empty>) -> Result<Response<proto::GameState>, Status> {
+        let state = self.state.tables.default_table().try_call(|controller| {
+            controller.deal_community_cards()?;
+            Ok(game_state(controller))
+        }).await.map_err(status)?;
+        Ok(Response::new(state))
+    }
+
+    async fn reset(&self, _request: Request<proto::Empty>) -> Result<Response<proto::GameState>, Status> {
+        let state = self.state.tables.default_table().call(|controller| {
+            controller.reset_deck();
+            game_state(controller)
+        }).await.map_err(status)?;
+        Ok(Response::new(state))
+    }
+
+    async fn evaluate(&self, _request: Request<proto::Empty>) -> Result<Response<proto::GameState>, Status> {
+        let state = self.state.tables.default_table().call(|controller| game_state(controller)).await.map_err(status)?;
+        Ok(Response::new(state))
+    }
+
+    async fn get_winners(&self, _request: Request<proto::Empty>) -> Result<Response<proto::Winners>, Status> {
+        let players = self.state.tables.default_table().try_call(|controller| {
+            let all_players: Vec<PlayerId> = controller.get_players().iter().map(|p| p.player_id.clone()).collect();
+            let winners = controller
+                .get_winners(&all_players)
+                .ok_or(GameError::NoWinner)?;
+
+            Ok(controller
+                .get_players()
+                .iter()
+                .filter(|p| winners.contains(&p.player_id))
+                .map(proto::Player::from)
+                .collect())
+        }).await.map_err(status)?;
+        Ok(Response::new(proto::Winners { players }))
+    }
+}
+
+/// Runs the gRPC server until it fails.
+///
+/// # Arguments
+/// * `state` - The shared game state (`Arc<AppState>`).
+/// * `addr` - The address to listen on.
+pub async fn serve_grpc(state: Arc<AppState>, addr: SocketAddr) -> Result<(), tonic::transport::Error> {
+    tonic::transport::Server::builder()
+        .add_service(DealerServer::new(DealerService { state }))
+        .serve(addr)
+        .await
+}
@@ -0,0 +1,264 @@
+use std::sync::Arc;
+
+use poker_core::game_error::GameError;
+use warp::{Filter, Rejection};
+
+use crate::events::parse_hand_id;
+use crate::hand_history::csv::to_csv;
+use crate::hand_history::phh::{parse_phh, to_phh};
+use crate::hand_history::query::{search, DEFAULT_PAGE_LEN};
+use crate::hand_history::sessions::sessions;
+use crate::hand_history::{parse_hand_histories, to_pokerstars};
+use super::auth::{authenticated, Session};
+use super::rate_limit::rate_limited;
+use super::rejections::reject;
+use super::requests::{CsvExportQuery, ExportQuery, HistoryFormat, HistoryQuery, ImportQuery};
+use super::responses::{ErrorResponse, HandHistoryResponse, HandImportResponse, SessionsResponse};
+use super::warp_routes::{with_state, AppState};
+
+/// Largest hand history file `POST /v1/history/import` reads, in bytes.
+const MAX_IMPORT_BYTES: u64 = 8 * 1024 * 1024;
+
+/// API route listing the table's finished hands, newest first, a page at a
+/// time.
+///
+/// Hands can be narrowed down by player, the hand rank shown down, pot size,
+/// when they finished and variant. Each page but the last has a
+/// `next_cursor`; passing it as `cursor` fetches the next one. Only hole
+/// cards shown at showdown are listed.
+///
+/// # Endpoint
+/// `GET /v1/history?player_id=1&min_hand_rank=Flush&min_pot=100&limit=20`
+///
+/// # Response
+/// - **Success**: Returns a page of hand summaries.
+/// - **Failure**: `400 Bad Request` for a limit above 200 or a pot range
+///   that can't match.
+#[utoipa::path(
+    get,
+    path = "/v1/history",
+    tag = "history",
+    params(HistoryQuery),
+    responses(
+        (status = 200, description = "A page of finished hands", body = HandHistoryResponse),
+        (status = 400, description = "Invalid filter or page size", body = ErrorResponse),
+        (status = 401, description = "Missing, invalid or expired token", body = ErrorResponse),
+    )
+)]
+fn list_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("history")
+        .and(warp::get())
+        .and(authenticated(state.clone()))
+        .and(warp::query::<HistoryQuery>())
+        .and(with_state(state))
+        .and_then(|session: Session, query: HistoryQuery, state: Arc<AppState>| async move {
+            let table = state.tables.get(&session.table_id).map_err(reject)?;
+            let history = table.history.clone();
+            let table_id = table.id.clone();
+            let response = table
+                .try_call(move |_| {
+                    let history = history.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+                    let page = search(&history, &query.filter(), query.cursor, query.limit.unwrap_or(DEFAULT_PAGE_LEN))?;
+                    Ok(HandHistoryResponse::new(&table_id, page))
+                })
+                .await
+                .map_err(reject)?;
+            Ok::<_, Rejection>(warp::reply::json(&response))
+        })
+}
+
+/// API route exporting a finished hand for hand tracking software.
+///
+/// The text is written from the caller's seat: their own hole cards are
+/// dealt to them, and other players' cards only show at showdown. The hand
+/// is named by its id, e.g. `main:42`, or just its number.
+///
+/// # Endpoint
+/// `GET /v1/history/{hand_id}/export?format=stars` or `?format=phh`
+///
+/// # Response
+/// - **Success**: Returns the hand as PokerStars hand history text, which
+///   PokerTracker and Hold'em Manager import, or as a PHH document.
+/// - **Failure**: `400 Bad Request` for a hand the format can't describe,
+///   such as a double-board hand, or any but Hold'em in PHH, `404 Not Found`
+///   for a hand the table no longer keeps.
+#[utoipa::path(
+    get,
+    path = "/v1/history/{hand_id}/export",
+    tag = "history",
+    params(("hand_id" = String, Path, description = "Id or number of the hand to export", example = "main:42"), ExportQuery),
+    responses(
+        (status = 200, description = "The hand history; PHH is served as application/toml", content_type = "text/plain", body = String),
+        (status = 400, description = "A hand the format can't describe, or an id from another table", body = ErrorResponse),
+        (status = 401, description = "Missing, invalid or expired token", body = ErrorResponse),
+        (status = 404, description = "No such hand in the table's history", body = ErrorResponse),
+    )
+)]
+fn export_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("history" / String / "export")
+        .and(warp::get())
+        .and(authenticated(state.clone()))
+        .and(warp::query::<ExportQuery>())
+        .and(with_state(state))
+        .and_then(|hand_id: String, session: Session, query: ExportQuery, state: Arc<AppState>| async move {
+            let table = state.tables.get(&session.table_id).map_err(reject)?;
+            let history = table.history.clone();
+            let table_id = table.id.clone();
+            let hand_number = parse_hand_id(&table_id, &hand_id).map_err(reject)?;
+            // Read on the table's task, so a hand finished by an earlier command is in
+            let (text, content_type) = table
+                .try_call(move |_| {
+                    let history = history.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+                    let hand = history.get(hand_number)?;
+                    let viewer = Some(session.player_id.as_str());
+                    Ok(match query.format {
+                        HistoryFormat::Stars => (to_pokerstars(hand, &table_id, viewer)?, "text/plain; charset=utf-8"),
+                        HistoryFormat::Phh => (to_phh(hand, &table_id, viewer)?, "application/toml; charset=utf-8"),
+                    })
+                })
+                .await
+                .map_err(reject)?;
+            Ok::<_, Rejection>(warp::reply::with_header(text, "content-type", content_type))
+        })
+}
+
+/// API route exporting the table's finished hands as CSV, for analysis in
+/// pandas or a spreadsheet.
+///
+/// `dataset` picks what a row describes: a hand, an action, or a player's
+/// result in a hand. Hole cards only appear where they were shown down.
+///
+/// # Endpoint
+/// `GET /v1/history/export?dataset=results&columns=hand_number,player_id,net&from=1714570000000`
+///
+/// # Response
+/// - **Success**: Returns the CSV with a header row, as an attachment.
+/// - **Failure**: `400 Bad Request` for a column the dataset doesn't have.
+#[utoipa::path(
+    get,
+    path = "/v1/history/export",
+    tag = "history",
+    params(CsvExportQuery),
+    responses(
+        (status = 200, description = "The hands as CSV", content_type = "text/csv", body = String),
+        (status = 400, description = "Unknown column", body = ErrorResponse),
+        (status = 401, description = "Missing, invalid or expired token", body = ErrorResponse),
+    )
+)]
+fn csv_export_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("history" / "export")
+        .and(warp::get())
+        .and(authenticated(state.clone()))
+        .and(warp::query::<CsvExportQuery>())
+        .and(with_state(state))
+        .and_then(|session: Session, query: CsvExportQuery, state: Arc<AppState>| async move {
+            let table = state.tables.get(&session.table_id).map_err(reject)?;
+            let history = table.history.clone();
+            let columns: Option<Vec<String>> = query.columns
+                .map(|columns| columns.split(',').map(str::trim).filter(|column| !column.is_empty()).map(str::to_string).collect());
+            let text = table
+                .try_call(move |_| {
+                    let history = history.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+                    to_csv(history.between(query.from, query.to), query.dataset, columns.as_deref())
+                })
+                .await
+                .map_err(reject)?;
+            let disposition = format!("attachment; filename=\"{}.csv\"", query.dataset.name());
+            let reply = warp::reply::with_header(text, "content-type", "text/csv; charset=utf-8");
+            Ok::<_, Rejection>(warp::reply::with_header(reply, "content-disposition", disposition))
+        })
+}
+
+/// API route reading hand histories played on PokerStars or GGPoker, or
+/// written in PHH, so they can be analysed like hands played here.
+///
+/// The body is the history file as the site wrote it, any number of hands.
+/// Nothing is stored: the hands come back replayed as table events.
+///
+/// # Endpoint
+/// `POST /v1/history/import`, or `POST /v1/history/import?format=phh`
+///
+/// # Response
+/// - **Success**: Returns each hand's board, the hole cards it shows and its events.
+/// - **Failure**: `400 Bad Request` for a line that can't be read or a game
+///   the engine doesn't play, `413 Payload Too Large` for files over 8 MiB.
+#[utoipa::path(
+    post,
+    path = "/v1/history/import",
+    tag = "history",
+    params(ImportQuery),
+    request_body(content = String, content_type = "text/plain", description = "PokerStars or GGPoker hand history text, or a PHH document"),
+    responses(
+        (status = 200, description = "The hands, replayed", body = HandImportResponse),
+        (status = 400, description = "Unreadable history or unsupported game", body = ErrorResponse),
+        (status = 429, description = "Too many requests from this client", body = ErrorResponse),
+    )
+)]
+fn import_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("history" / "import")
+        .and(warp::post())
+        .and(rate_limited(state))
+        .and(warp::query::<ImportQuery>())
+        .and(warp::body::content_length_limit(MAX_IMPORT_BYTES))
+        .and(warp::body::bytes())
+        .and_then(|query: ImportQuery, body: warp::hyper::body::Bytes| async move {
+            let text = std::str::from_utf8(&body)
+                .map_err(|_| reject(GameError::InvalidRequest("hand histories must be UTF-8 text".to_string())))?;
+            let hands = match query.format {
+                HistoryFormat::Stars => parse_hand_histories(text),
+                HistoryFormat::Phh => parse_phh(text),
+            }
+            .map_err(reject)?;
+            Ok::<_, Rejection>(warp::reply::json(&HandImportResponse::new(&hands)))
+        })
+}
+
+/// API route summarizing a player's sessions at the table: hands played,
+/// net result, biggest pots and how often they won at showdown.
+///
+/// A session runs from sitting down to leaving the table, and also ends
+/// after 30 minutes without a hand. Only hands the table still keeps count.
+///
+/// # Endpoint
+/// `GET /v1/sessions/{player_id}`
+///
+/// # Response
+/// - **Success**: Returns the player's sessions, oldest first; none for a
+///   player without hands on record.
+#[utoipa::path(
+    get,
+    path = "/v1/sessions/{player_id}",
+    tag = "history",
+    params(("player_id" = String, Path, description = "Player whose sessions to summarize")),
+    responses(
+        (status = 200, description = "The player's sessions", body = SessionsResponse),
+        (status = 401, description = "Missing, invalid or expired token", body = ErrorResponse),
+    )
+)]
+fn sessions_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("sessions" / String)
+        .and(warp::get())
+        .and(authenticated(state.clone()))
+        .and(with_state(state))
+        .and_then(|player_id: String, session: Session, state: Arc<AppState>| async move {
+            let table = state.tables.get(&session.table_id).map_err(reject)?;
+            let history = table.history.clone();
+            let (player_id, summaries) = table
+                .call(move |_| {
+                    let summaries = sessions(&history.lock().unwrap_or_else(|poisoned| poisoned.into_inner()), &player_id);
+                    (player_id, summaries)
+                })
+                .await
+                .map_err(reject)?;
+            Ok::<_, Rejection>(warp::reply::json(&SessionsResponse::new(player_id, summaries)))
+        })
+}
+
+/// All hand history routes, mounted inside each API version.
+pub fn history_routes(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    list_route(state.clone())
+        .or(export_route(state.clone()))
+        .or(csv_export_route(state.clone()))
+        .or(sessions_route(state.clone()))
+        .or(import_route(state))
+}
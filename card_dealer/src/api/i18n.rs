@@ -0,0 +1,229 @@
+//! Translations of hand names and error messages, picked from the client's
+//! `Accept-Language` header.
+//!
+//! Messages are kept in plain match tables rather than resource files: there
+//! are only a handful, and a missing translation is then a compile error.
+//! Free-text details inside an error (e.g. which card was invalid) stay in
+//! English.
+
+use std::convert::Infallible;
+
+use poker_core::game_error::GameError;
+use poker_core::poker_hand::HandRank;
+use warp::Filter;
+
+/// A language the API can answer in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Locale {
+    #[default]
+    En,
+    Es,
+    Fr,
+    De,
+}
+
+impl Locale {
+    /// The language tag sent back in `Content-Language`.
+    pub fn tag(self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Es => "es",
+            Locale::Fr => "fr",
+            Locale::De => "de",
+        }
+    }
+
+    /// Matches a language tag such as `fr-CA` on its primary subtag.
+    fn from_tag(tag: &str) -> Option<Self> {
+        let primary = tag.split('-').next()?.trim();
+        [Locale::En, Locale::Es, Locale::Fr, Locale::De]
+            .into_iter()
+            .find(|locale| primary.eq_ignore_ascii_case(locale.tag()))
+    }
+
+    /// Picks the supported language the client prefers most, e.g. from
+    /// `de-CH, fr;q=0.9, en;q=0.5`. Falls back to English.
+    pub fn from_accept_language(header: &str) -> Self {
+        let mut choices: Vec<(f32, &str)> = header
+            .split(',')
+            .filter_map(|entry| {
+                let mut parts = entry.split(';');
+                let tag = parts.next()?.trim();
+                let quality = parts
+                    .find_map(|param| param.trim().strip_prefix("q="))
+                    .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())?;
+                (quality > 0.0).then_some((quality, tag))
+            })
+            .collect();
+        choices.sort_by(|a, b| b.0.total_cmp(&a.0)); // Stable, so equal weights keep the client's order
+        choices.into_iter().find_map(|(_, tag)| Self::from_tag(tag)).unwrap_or_default()
+    }
+}
+
+/// Extracts the caller's `Locale` from `Accept-Language`. Never rejects: a
+/// missing or unreadable header means English.
+pub fn locale() -> impl Filter<Extract = (Locale,), Error = Infallible> + Clone {
+    warp::header::headers_cloned().map(|headers: warp::http::HeaderMap| {
+        headers
+            .get(warp::http::header::ACCEPT_LANGUAGE)
+            .and_then(|value| value.to_str().ok())
+            .map(Locale::from_accept_language)
+            .unwrap_or_default()
+    })
+}
+
+/// The name of a hand category, e.g. "Full house".
+pub fn hand_name(rank: &HandRank, locale: Locale) -> &'static str {
+    use HandRank::*;
+    match locale {
+        Locale::En => match rank {
+            HighCard => "High card",
+            OnePair => "One pair",
+            TwoPair => "Two pair",
+            ThreeOfAKind => "Three of a kind",
+            Straight => "Straight",
+            Flush => "Flush",
+            FullHouse => "Full house",
+            FourOfAKind => "Four of a kind",
+            StraightFlush => "Straight flush",
+            FiveOfAKind => "Five of a kind",
+        },
+        Locale::Es => match rank {
+            HighCard => "Carta alta",
+            OnePair => "Pareja",
+            TwoPair => "Doble pareja",
+            ThreeOfAKind => "Trío",
+            Straight => "Escalera",
+            Flush => "Color",
+            FullHouse => "Full",
+            FourOfAKind => "Póker",
+            StraightFlush => "Escalera de color",
+            FiveOfAKind => "Repóker",
+        },
+        Locale::Fr => match rank {
+            HighCard => "Carte haute",
+            OnePair => "Paire",
+            TwoPair => "Double paire",
+            ThreeOfAKind => "Brelan",
+            Straight => "Quinte",
+            Flush => "Couleur",
+            FullHouse => "Full",
+            FourOfAKind => "Carré",
+            StraightFlush => "Quinte flush",
+            FiveOfAKind => "Quintuplé",
+        },
+        Locale::De => match rank {
+            HighCard => "Höchste Karte",
+            OnePair => "Ein Paar",
+            TwoPair => "Zwei Paare",
+            ThreeOfAKind => "Drilling",
+            Straight => "Straße",
+            Flush => "Flush",
+            FullHouse => "Full House",
+            FourOfAKind => "Vierling",
+            StraightFlush => "Straight Flush",
+            FiveOfAKind => "Fünfling",
+        },
+    }
+}
+
+/// A `GameError` as shown to the client. English matches its `Display`.
+pub fn error_message(error: &GameError, locale: Locale) -> String {
+    use GameError::*;
+    match (locale, error) {
+        (Locale::En, _) => error.to_string(),
+
+        (Locale::Es, InvalidRequest(detail)) => format!("Solicitud no válida: {detail}"),
+        (Locale::Es, Unauthorized(detail)) => format!("No autorizado: {detail}"),
+        (Locale::Es, NotAllowed(detail)) => format!("Acción no permitida: {detail}"),
+        (Locale::Es, PlayerNotFound(id)) => format!("Jugador {id} no encontrado"),
+        (Locale::Es, TableNotFound(id)) => format!("Mesa {id} no encontrada"),
+        (Locale::Es, HandNotFound(number)) => format!("Mano {number} no encontrada"),
+        (Locale::Es, NoWinner) => "No se determinó ningún ganador".to_string(),
+        (Locale::Es, NotEnoughCards(what)) => format!("No hay suficientes cartas para repartir {what}."),
+        (Locale::Es, DuplicateCard(card)) => format!("La carta {card} aparece más de una vez"),
+        (Locale::Es, InsufficientChips) => "No hay suficientes fichas para apostar".to_string(),
+        (Locale::Es, InvalidState(detail)) => format!("Estado de juego no válido: {detail}"),
+        (Locale::Es, Internal(detail)) => format!("Error interno: {detail}"),
+
+        (Locale::Fr, InvalidRequest(detail)) => format!("Requête invalide : {detail}"),
+        (Locale::Fr, Unauthorized(detail)) => format!("Non autorisé : {detail}"),
+        (Locale::Fr, NotAllowed(detail)) => format!("Action non autorisée : {detail}"),
+        (Locale::Fr, PlayerNotFound(id)) => format!("Joueur {id} introuvable"),
+        (Locale::Fr, TableNotFound(id)) => format!("Table {id} introuvable"),
+        (Locale::Fr, HandNotFound(number)) => format!("Main {number} introuvable"),
+        (Locale::Fr, NoWinner) => "Aucun gagnant déterminé".to_string(),
+        (Locale::Fr, NotEnoughCards(what)) => format!("Pas assez de cartes pour distribuer {what}."),
+        (Locale::Fr, DuplicateCard(card)) => format!("La carte {card} apparaît plus d'une fois"),
+        (Locale::Fr, InsufficientChips) => "Pas assez de jetons pour miser".to_string(),
+        (Locale::Fr, InvalidState(detail)) => format!("État de jeu invalide : {detail}"),
+        (Locale::Fr, Internal(detail)) => format!("Erreur interne : {detail}"),
+
+        (Locale::De, InvalidRequest(detail)) => format!("Ungültige Anfrage: {detail}"),
+        (Locale::De, Unauthorized(detail)) => format!("Nicht autorisiert: {detail}"),
+        (Locale::De, NotAllowed(detail)) => format!("Aktion nicht erlaubt: {detail}"),
+        (Locale::De, PlayerNotFound(id)) => format!("Spieler {id} nicht gefunden"),
+        (Locale::De, TableNotFound(id)) => format!("Tisch {id} nicht gefunden"),
+        (Locale::De, HandNotFound(number)) => format!("Hand {number} nicht gefunden"),
+        (Locale::De, NoWinner) => "Kein Gewinner ermittelt".to_string(),
+        (Locale::De, NotEnoughCards(what)) => format!("Nicht genug Karten, um {what} auszuteilen."),
+        (Locale::De, DuplicateCard(card)) => format!("Die Karte {card} kommt mehrfach vor"),
+        (Locale::De, InsufficientChips) => "Nicht genug Chips zum Setzen".to_string(),
+        (Locale::De, InvalidState(detail)) => format!("Ungültiger Spielzustand: {detail}"),
+        (Locale::De, Internal(detail)) => format!("Interner Fehler: {detail}"),
+    }
+}
+
+/// Fixed messages for rejections that don't come from the game engine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Message {
+    RouteNotFound,
+    TooManyRequests,
+    MethodNotAllowed,
+    InvalidBody,
+}
+
+impl Message {
+    pub fn text(self, locale: Locale) -> &'static str {
+        match (self, locale) {
+            (Message::RouteNotFound, Locale::En) => "Route not found",
+            (Message::RouteNotFound, Locale::Es) => "Ruta no encontrada",
+            (Message::RouteNotFound, Locale::Fr) => "Route introuvable",
+            (Message::RouteNotFound, Locale::De) => "Route nicht gefunden",
+            (Message::TooManyRequests, Locale::En) => "Too many requests, slow down",
+            (Message::TooManyRequests, Locale::Es) => "Demasiadas solicitudes, más despacio",
+            (Message::TooManyRequests, Locale::Fr) => "Trop de requêtes, ralentissez",
+            (Message::TooManyRequests, Locale::De) => "Zu viele Anfragen, bitte langsamer",
+            (Message::MethodNotAllowed, Locale::En) => "Method not allowed",
+            (Message::MethodNotAllowed, Locale::Es) => "Método no permitido",
+            (Message::MethodNotAllowed, Locale::Fr) => "Méthode non autorisée",
+            (Message::MethodNotAllowed, Locale::De) => "Methode nicht erlaubt",
+            (Message::InvalidBody, Locale::En) => "Invalid request body",
+            (Message::InvalidBody, Locale::Es) => "Cuerpo de la solicitud no válido",
+            (Message::InvalidBody, Locale::Fr) => "Corps de requête invalide",
+            (Message::InvalidBody, Locale::De) => "Ungültiger Anfragetext",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accept_language_negotiation() {
+        assert_eq!(Locale::from_accept_language("fr-CA"), Locale::Fr);
+        assert_eq!(Locale::from_accept_language("ja, de;q=0.8, es;q=0.9"), Locale::Es);
+        assert_eq!(Locale::from_accept_language("es;q=0, de"), Locale::De);
+        assert_eq!(Locale::from_accept_language("ja, *;q=0.5"), Locale::En);
+        assert_eq!(Locale::from_accept_language(""), Locale::En);
+    }
+
+    #[test]
+    fn test_translations() {
+        assert_eq!(hand_name(&HandRank::FullHouse, Locale::De), "Full House");
+        assert_eq!(error_message(&GameError::NoWinner, Locale::En), GameError::NoWinner.to_string());
+        assert_eq!(error_message(&GameError::PlayerNotFound("7".to_string()), Locale::Es), "Jugador 7 no encontrado");
+        assert_eq!(Message::RouteNotFound.text(Locale::Fr), "Route introuvable");
+    }
+}
@@ -0,0 +1,138 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use poker_core::game_error::GameError;
+
+/// How long the outcome of a request is kept for retries with its key.
+const KEY_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Above this many stored keys, expired ones are dropped on the next request.
+const MAX_TRACKED_KEYS: usize = 10_000;
+
+/// Longest `Idempotency-Key` accepted.
+const MAX_KEY_LEN: usize = 255;
+
+/// The outcome of a request as sent back to its retries: the response body,
+/// or the error it failed with.
+pub type Outcome = Result<serde_json::Value, GameError>;
+
+enum Entry {
+    Pending { request: String },
+    Done { request: String, outcome: Outcome, stored_at: Instant },
+}
+
+/// What to do with a request carrying an idempotency key.
+pub enum Claim<'a> {
+    Run(Reservation<'a>), // First time the key is seen; run the request and `complete` it
+    Replay(Outcome),      // Answered before; send this again
+}
+
+/// Outcomes of requests sent with an `Idempotency-Key` header, so a client
+/// retrying after a network blip gets the first answer again instead of
+/// acting twice.
+///
+/// Keys are scoped to the player sending them. Reusing a key for a different
+/// request, or while its first request is still running, is an error.
+#[derive(Default)]
+pub struct IdempotencyCache {
+    entries: Mutex<HashMap<(String, String), Entry>>, // By player id and key; only locked briefly
+}
+
+impl IdempotencyCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Looks up `key` for `player_id`; `request` identifies what was asked,
+    /// to catch a key reused for something else.
+    pub fn claim(&self, player_id: &str, key: &str, request: String) -> Result<Claim<'_>, GameError> {
+        self.claim_at(player_id, key, request, Instant::now())
+    }
+
+    fn claim_at(&self, player_id: &str, key: &str, request: String, now: Instant) -> Result<Claim<'_>, GameError> {
+        if key.is_empty() || key.len() > MAX_KEY_LEN {
+            return Err(GameError::InvalidRequest(format!("Idempotency-Key must be 1 to {MAX_KEY_LEN} characters")));
+        }
+        let mut entries = self.entries.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if entries.len() > MAX_TRACKED_KEYS {
+            entries.retain(|_, entry| !matches!(entry, Entry::Done { stored_at, .. } if now.duration_since(*stored_at) >= KEY_TTL));
+        }
+        let id = (player_id.to_string(), key.to_string());
+        match entries.get(&id) {
+            Some(Entry::Pending { .. }) => Err(GameError::InvalidState("a request with this Idempotency-Key is still running".to_string())),
+            Some(Entry::Done { request: previous, outcome, stored_at }) if now.duration_since(*stored_at) < KEY_TTL => {
+                if *previous != request {
+                    return Err(GameError::InvalidRequest("Idempotency-Key was already used for a different request".to_string()));
+                }
+                Ok(Claim::Replay(outcome.clone()))
+            }
+            _ => {
+                entries.insert(id.clone(), Entry::Pending { request });
+                Ok(Claim::Run(Reservation { cache: self, id: Some(id) }))
+            }
+        }
+    }
+}
+
+/// A key whose first request is running. Dropping it without `complete`,
+/// e.g. when the client goes away, frees the key for a retry.
+pub struct Reservation<'a> {
+    cache: &'a IdempotencyCache,
+    id: Option<(String, String)>,
+}
+
+impl Reservation<'_> {
+    /// Stores the request's outcome for later retries. Internal errors
+    /// aren't stored, so a retry runs the request again.
+    pub fn complete(mut self, outcome: &Outcome) {
+        let Some(id) = self.id.take() else { return };
+        let mut entries = self.cache.entries.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let Some(Entry::Pending { request }) = entries.remove(&id) else { return };
+        if !matches!(outcome, Err(GameError::Internal(_))) {
+            entries.insert(id, Entry::Done { request, outcome: outcome.clone(), stored_at: Instant::now() });
+        }
+    }
+}
+
+impl Drop for Reservation<'_> {
+    fn drop(&mut self) {
+        if let Some(id) = self.id.take() {
+            self.cache.entries.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).remove(&id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retries_replay_the_first_outcome() {
+        let cache = IdempotencyCache::new();
+        let start = Instant::now();
+        let Ok(Claim::Run(reservation)) = cache.claim_at("1", "abc", "fold".to_string(), start) else { panic!("expected a new key") };
+        assert!(matches!(cache.claim_at("1", "abc", "fold".to_string(), start), Err(GameError::InvalidState(_))));
+        reservation.complete(&Ok(serde_json::json!({"chip_stack": 990})));
+
+        let Ok(Claim::Replay(outcome)) = cache.claim_at("1", "abc", "fold".to_string(), start) else { panic!("expected a replay") };
+        assert_eq!(outcome, Ok(serde_json::json!({"chip_stack": 990})));
+        assert!(matches!(cache.claim_at("1", "abc", "call".to_string(), start), Err(GameError::InvalidRequest(_))));
+        assert!(matches!(cache.claim_at("2", "abc", "call".to_string(), start), Ok(Claim::Run(_)))); // Keys are per player
+        assert!(matches!(cache.claim_at("1", "abc", "call".to_string(), start + 2 * KEY_TTL), Ok(Claim::Run(_))));
+        assert!(matches!(cache.claim_at("1", "", "call".to_string(), start), Err(GameError::InvalidRequest(_))));
+    }
+
+    #[test]
+    fn test_abandoned_and_failed_requests_can_be_retried() {
+        let cache = IdempotencyCache::new();
+        drop(cache.claim("1", "abc", "fold".to_string()).unwrap());
+        let Ok(Claim::Run(reservation)) = cache.claim("1", "abc", "fold".to_string()) else { panic!("expected a new key") };
+        reservation.complete(&Err(GameError::Internal("table stopped".to_string())));
+        assert!(matches!(cache.claim("1", "abc", "fold".to_string()), Ok(Claim::Run(_))));
+
+        let Ok(Claim::Run(reservation)) = cache.claim("1", "def", "fold".to_string()) else { panic!("expected a new key") };
+        reservation.complete(&Err(GameError::InsufficientChips));
+        assert!(matches!(cache.claim("1", "def", "fold".to_string()), Ok(Claim::Replay(Err(GameError::InsufficientChips)))));
+    }
+}
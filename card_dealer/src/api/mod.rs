@@ -1,3 +1,34 @@
 mod warp_routes; // Import warp_routes.rs
+mod service;     // Route logic as plain async functions, independent of warp
+mod openapi;     // OpenAPI document and Swagger UI page
+mod rejections;  // GameError -> HTTP status mapping
+mod encoding;    // Compression and ETags for finished responses
+mod i18n;        // Accept-Language negotiation and message tables
+mod rate_limit;  // Per-IP token buckets guarding the game routes
+mod idempotency; // Replaying the outcome of retried requests
+mod auth;        // Signed session tokens for player-scoped routes
+mod admin_routes; // Table moderation for admin sessions
+#[cfg(feature = "debug-routes")]
+mod debug_routes; // Test-only routes such as stacking the deck
+mod spectate;    // Read-only table view and event WebSocket
+mod waitlist;    // Joining a table and its waiting list
+mod tournament;  // Tournament registration and re-entry
+mod analysis;    // Draws, outs, equity and solver strategies for hands given in the request
+mod history;     // Exporting finished hands for tracking software
+mod profiles;    // Player profiles, kept across tables and sessions
+mod social;      // Friend lists and table invitations
+mod bankroll;    // Balances tracked-currency tables draw chips from
+mod requests;    // Typed request bodies
+mod validation;  // Field checks on request bodies before handlers run
+mod responses;   // Typed response bodies shared by every route
+#[cfg(feature = "graphql")]
+mod graphql;     // GraphQL schema served alongside the REST routes
+#[cfg(feature = "grpc")]
+mod grpc;        // tonic gRPC service mirroring the REST routes
 
 pub use warp_routes::*; // Re-export for easy access in main.rs
+pub use rate_limit::RateLimiter;
+pub use auth::Authenticator;
+pub use idempotency::IdempotencyCache;
+#[cfg(feature = "grpc")]
+pub use grpc::serve_grpc;
@@ -0,0 +1,153 @@
+use utoipa::OpenApi;
+use poker_core::card_dealer::{Card, Rank, Suit};
+use crate::audit_log::{AuditEntry, AuditEvent};
+use crate::events::SequencedEvent;
+use crate::hand_history::collusion::CollusionFlag;
+use crate::hand_history::csv::Dataset;
+use crate::hand_history::sessions::{SessionPot, SessionSummary};
+use crate::presence::Presence;
+use crate::profiles::PlayerProfile;
+use crate::social::{Friend, Invitation};
+use poker_core::betting::Street;
+use poker_core::bots::Difficulty;
+use poker_core::events::{GameEvent, Runout};
+use poker_core::equity::ComboEquity;
+use poker_core::ledger::{Account, Transaction, TransactionKind};
+use poker_core::outs::{Draw, Out};
+use poker_core::player::PlayerAction;
+use poker_core::poker_hand::{Hand, HandRank};
+use poker_core::preflop_chart::Position;
+use poker_core::rake::RakeRules;
+use poker_core::solver::ComboStrategy;
+use poker_core::buy_in::{AddOn, BuyInKind, BuyInRules};
+use poker_core::currency::CurrencyMode;
+use poker_core::table_config::TableConfig;
+use poker_core::tournament::TournamentRules;
+use poker_core::variant::GameVariant;
+use poker_core::waitlist::{Seating, WaitingPlayer};
+use poker_core::wild_cards::WildCards;
+use super::requests::{ActionRequest, AddBotRequest, AdminLoginRequest, BuyInRequest, ChipAdjustmentRequest, CreateTableRequest, EquityVsRangeRequest, JoinTableRequest, LoginRequest, RegisterRequest, RunItRequest, InsuranceRequest, ShowRequest, SolveRequest, HistoryFormat, InviteRequest, AcceptInvitationRequest};
+use super::responses::*;
+use super::validation::FieldError;
+
+/// OpenAPI document for the dealer API.
+///
+/// Paths are collected from the `#[utoipa::path]` annotations on the route
+/// functions in `warp_routes.rs`, so new routes only need to be listed here.
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Card Dealer API", description = "Texas Hold'em dealing and hand evaluation"),
+    paths(
+        super::warp_routes::deal_hole_route,
+        super::warp_routes::deal_community_route,
+        super::warp_routes::reset_route,
+        super::warp_routes::evaluate_route,
+        super::warp_routes::test_winners_route,
+        super::warp_routes::login_route,
+        super::warp_routes::refresh_route,
+        super::warp_routes::me_route,
+        super::warp_routes::buy_in_route,
+        super::warp_routes::action_route,
+        super::warp_routes::run_it_route,
+        super::warp_routes::insurance_route,
+        super::warp_routes::rabbit_hunt_route,
+        super::warp_routes::show_route,
+        super::warp_routes::straddle_route,
+        super::warp_routes::hint_route,
+        super::admin_routes::admin_login_route,
+        super::admin_routes::kick_route,
+        super::admin_routes::sit_out_route,
+        super::admin_routes::adjust_chips_route,
+        super::admin_routes::add_bot_route,
+        super::admin_routes::create_table_route,
+        super::admin_routes::pause_route,
+        super::admin_routes::resume_route,
+        super::admin_routes::end_hand_route,
+        super::admin_routes::audit_route,
+        super::admin_routes::ledger_route,
+        super::admin_routes::collusion_route,
+        super::spectate::spectate_route,
+        super::spectate::events_route,
+        super::spectate::poll_route,
+        super::waitlist::join_route,
+        super::waitlist::waitlist_route,
+        super::waitlist::leave_waitlist_route,
+        super::tournament::tournament_route,
+        super::tournament::register_route,
+        super::tournament::re_enter_route,
+        super::analysis::outs_route,
+        super::analysis::equity_vs_range_route,
+        super::analysis::solve_route,
+        super::history::list_route,
+        super::history::export_route,
+        super::history::csv_export_route,
+        super::history::import_route,
+        super::history::sessions_route,
+        super::profiles::get_profile_route,
+        super::profiles::put_profile_route,
+        super::profiles::delete_profile_route,
+        super::social::friends_route,
+        super::social::add_friend_route,
+        super::social::remove_friend_route,
+        super::social::invite_route,
+        super::social::invitations_route,
+        super::social::accept_route,
+        super::social::decline_route,
+        super::bankroll::balance_route,
+        super::bankroll::adjust_balance_route,
+    ),
+    components(schemas(
+        Card, Rank, Suit, Hand, HandRank,
+        PlayerHoleCards, DealHoleResponse, CommunityResponse, ResetResponse,
+        PlayerEvaluation, EvaluationResponse, WinnerSummary, TestWinnersResponse, ErrorResponse, FieldError,
+        LoginRequest, SessionResponse, PlayerStateResponse, ActionPrompt, ActionRequest, RunItRequest, InsuranceRequest, InsurancePrompt, RabbitHuntResponse, ShowRequest, BuyInRequest, BuyInKind,
+        AdminLoginRequest, ChipAdjustmentRequest, AdminActionResponse, AddBotRequest, Difficulty,
+        AuditLogResponse, AuditEntry, AuditEvent, LedgerResponse, Transaction, TransactionKind, Account, CollusionReportResponse, CollusionFlag,
+        CreateTableRequest, TableConfig, CurrencyMode, RakeRules, BuyInRules, AddOn, WildCards, TableCreatedResponse,
+        SpectatorSeat, Presence, SpectatorViewResponse, StreetPot, StreetBet, Street, PollResponse, BlindClockView, GameEvent, Runout, PlayerAction, SequencedEvent,
+        JoinTableRequest, JoinTableResponse, Seating, WaitlistResponse, WaitingPlayer,
+        RegisterRequest, TournamentRules, TournamentResponse, TournamentEntryResponse,
+        OutsResponse, Draw, Out,
+        EquityVsRangeRequest, EquityVsRangeResponse, ComboEquity,
+        HintResponse, Position,
+        SolveRequest, SolveResponse, ActionFrequency, ComboStrategy,
+        HistoryFormat, Dataset, GameVariant, HandImportResponse, ImportedHand, HandHistoryResponse, HandSummary, ShownHand, SessionsResponse, SessionSummary, SessionPot,
+        PlayerProfile, ProfileResponse,
+        Friend, FriendsResponse, Invitation, InviteRequest, InvitationResponse, InvitationsResponse, AcceptInvitationRequest,
+        BalanceResponse,
+    )),
+    tags(
+        (name = "dealer", description = "Dealing, evaluation and winner determination"),
+        (name = "auth", description = "Player login and session tokens"),
+        (name = "admin", description = "Table moderation; requires an admin session"),
+        (name = "spectate", description = "Read-only table view; events stream over /v1/tables/{table_id}/spectate/ws"),
+        (name = "waitlist", description = "Taking a seat, or a place in line when the table is full"),
+        (name = "tournament", description = "Registering for a tournament table and re-entering after busting"),
+        (name = "analysis", description = "Draws, outs, equity and solver strategies for any hand, independent of the tables"),
+        (name = "profiles", description = "Player profiles: display name, avatar, country and client preferences, kept across tables and sessions"),
+        (name = "social", description = "Friends, and inviting them to a table with a seat held while they answer; invitations arrive over /v1/invitations/ws"),
+        (name = "bankroll", description = "Player balances that tracked-currency tables draw chips from and pay them back into"),
+        (name = "history", description = "Finished hands, searched, exported for hand tracking software or analysis, and hands played elsewhere read back in"),
+    )
+)]
+pub struct ApiDoc;
+
+/// Minimal Swagger UI page; the assets are loaded from a CDN so the binary stays small.
+pub const SWAGGER_UI_HTML: &str = r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="utf-8" />
+    <title>Card Dealer API</title>
+    <link rel="stylesheet" href="https://unpkg.com/swagger-ui-dist@5/swagger-ui.css" />
+</head>
+<body>
+    <div id="swagger-ui"></div>
+    <script src="https://unpkg.com/swagger-ui-dist@5/swagger-ui-bundle.js"></script>
+    <script>
+        window.onload = () => {
+            window.ui = SwaggerUIBundle({ url: "/v1/openapi.json", dom_id: "#swagger-ui" });
+        };
+    </script>
+</body>
+</html>
+"##;
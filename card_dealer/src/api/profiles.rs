@@ -0,0 +1,125 @@
+use std::sync::Arc;
+
+use poker_core::game_error::GameError;
+use tracing::info;
+use warp::{Filter, Rejection};
+
+use crate::profiles::PlayerProfile;
+use super::auth::{authenticated, Role, Session};
+use super::rejections::reject;
+use super::responses::{ErrorResponse, ProfileResponse};
+use super::warp_routes::{with_state, AppState};
+use super::validation::json_body;
+
+/// Only the player themselves, or an admin, may change a profile.
+fn check_owner(session: &Session, player_id: &str) -> Result<(), GameError> {
+    if session.role == Role::Admin || session.player_id == player_id {
+        Ok(())
+    } else {
+        Err(GameError::NotAllowed("only the player or an admin may change a profile".to_string()))
+    }
+}
+
+/// API route showing a player's profile.
+///
+/// # Endpoint
+/// `GET /v1/players/{player_id}/profile`
+///
+/// # Response
+/// - **Success**: Returns the profile.
+/// - **Failure**: `404 Not Found` if the player has no profile.
+#[utoipa::path(
+    get,
+    path = "/v1/players/{player_id}/profile",
+    tag = "profiles",
+    params(("player_id" = String, Path, description = "Player whose profile to show")),
+    responses(
+        (status = 200, description = "The player's profile", body = ProfileResponse),
+        (status = 404, description = "No profile for this player", body = ErrorResponse),
+    )
+)]
+fn get_profile_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("players" / String / "profile")
+        .and(warp::get())
+        .and(with_state(state))
+        .and_then(|player_id: String, state: Arc<AppState>| async move {
+            let profile = state.profiles.get(&player_id).map_err(reject)?;
+            Ok::<_, Rejection>(warp::reply::json(&ProfileResponse::new(player_id, profile)))
+        })
+}
+
+/// API route creating or replacing a player's profile.
+///
+/// # Endpoint
+/// `PUT /v1/players/{player_id}/profile`
+///
+/// # Response
+/// - **Success**: Returns the profile as stored.
+/// - **Failure**: `400 Bad Request` for an invalid field, `403 Forbidden` for
+///   another player's profile.
+#[utoipa::path(
+    put,
+    path = "/v1/players/{player_id}/profile",
+    tag = "profiles",
+    params(("player_id" = String, Path, description = "Player whose profile to set")),
+    request_body = PlayerProfile,
+    responses(
+        (status = 200, description = "The stored profile", body = ProfileResponse),
+        (status = 400, description = "Invalid profile field", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid session token", body = ErrorResponse),
+        (status = 403, description = "Another player's profile", body = ErrorResponse),
+    )
+)]
+fn put_profile_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("players" / String / "profile")
+        .and(warp::put())
+        .and(authenticated(state.clone()))
+        .and(json_body())
+        .and(with_state(state))
+        .and_then(|player_id: String, session: Session, profile: PlayerProfile, state: Arc<AppState>| async move {
+            check_owner(&session, &player_id).map_err(reject)?;
+            let created = state.profiles.put(&player_id, profile.clone()).map_err(reject)?;
+            info!(%player_id, created, "profile saved");
+            Ok::<_, Rejection>(warp::reply::json(&ProfileResponse::new(player_id, profile)))
+        })
+}
+
+/// API route deleting a player's profile.
+///
+/// # Endpoint
+/// `DELETE /v1/players/{player_id}/profile`
+///
+/// # Response
+/// - **Success**: Returns the profile that was deleted.
+/// - **Failure**: `403 Forbidden` for another player's profile, `404 Not Found` if there is none.
+#[utoipa::path(
+    delete,
+    path = "/v1/players/{player_id}/profile",
+    tag = "profiles",
+    params(("player_id" = String, Path, description = "Player whose profile to delete")),
+    responses(
+        (status = 200, description = "The deleted profile", body = ProfileResponse),
+        (status = 401, description = "Missing or invalid session token", body = ErrorResponse),
+        (status = 403, description = "Another player's profile", body = ErrorResponse),
+        (status = 404, description = "No profile for this player", body = ErrorResponse),
+    )
+)]
+fn delete_profile_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("players" / String / "profile")
+        .and(warp::delete())
+        .and(authenticated(state.clone()))
+        .and(with_state(state))
+        .and_then(|player_id: String, session: Session, state: Arc<AppState>| async move {
+            check_owner(&session, &player_id).map_err(reject)?;
+            let profile = state.profiles.remove(&player_id).map_err(reject)?;
+            info!(%player_id, "profile deleted");
+            Ok::<_, Rejection>(warp::reply::json(&ProfileResponse::new(player_id, profile)))
+        })
+}
+
+/// All player profile routes.
+pub fn profile_routes(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    get_profile_route(state.clone())
+        .or(put_profile_route(state.clone()))
+        .or(delete_profile_route(state))
+}
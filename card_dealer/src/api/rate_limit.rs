@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use warp::{Filter, Rejection};
+
+use crate::config::RateLimitConfig;
+use super::warp_routes::AppState;
+
+/// Above this many tracked clients, idle buckets are dropped on the next check.
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+/// Rejection raised when a client has used up its request budget.
+#[derive(Debug)]
+pub struct RateLimited {
+    pub retry_after: Duration, // Time until the client's next token is available
+}
+
+impl warp::reject::Reject for RateLimited {}
+
+/// One client's token bucket.
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// Token-bucket rate limiter keyed by client IP.
+///
+/// Each client may burst up to `burst` requests, after which tokens refill
+/// at `requests_per_sec`. A rate of zero turns limiting off.
+pub struct RateLimiter {
+    capacity: f64,
+    refill_per_sec: f64,
+    buckets: Mutex<HashMap<IpAddr, Bucket>>,
+}
+
+impl RateLimiter {
+    pub fn new(config: &RateLimitConfig) -> Self {
+        Self {
+            capacity: f64::from(config.burst),
+            refill_per_sec: config.requests_per_sec,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.refill_per_sec > 0.0
+    }
+
+    /// Takes a token for `client`, or returns how long it must wait for one.
+    pub fn check(&self, client: IpAddr) -> Result<(), Duration> {
+        self.check_at(client, Instant::now())
+    }
+
+    fn check_at(&self, client: IpAddr, now: Instant) -> Result<(), Duration> {
+        if !self.is_enabled() {
+            return Ok(());
+        }
+
+        let mut buckets = self.buckets.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if buckets.len() > MAX_TRACKED_CLIENTS {
+            let refill_time = self.capacity / self.refill_per_sec;
+            buckets.retain(|_, bucket| now.duration_since(bucket.updated).as_secs_f64() < refill_time);
+        }
+
+        let bucket = buckets.entry(client).or_insert(Bucket { tokens: self.capacity, updated: now });
+        let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / self.refill_per_sec))
+        }
+    }
+}
+
+/// Filter charging one token to the calling IP, rejecting with `RateLimited`
+/// once its budget is spent.
+///
+/// Chain it after a route's path so only requests to that route are charged.
+/// Requests without a known remote address (e.g. in-process tests) pass through.
+pub fn rate_limited(state: Arc<AppState>) -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    warp::addr::remote()
+        .and_then(move |remote: Option<SocketAddr>| {
+            let state = state.clone();
+            async move {
+                if let Some(addr) = remote {
+                    state
+                        .rate_limiter
+                        .check(addr.ip())
+                        .map_err(|retry_after| warp::reject::custom(RateLimited { retry_after }))?;
+                }
+                Ok::<_, Rejection>(())
+            }
+        })
+        .untuple_one()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bucket_drains_and_refills() {
+        let limiter = RateLimiter::new(&RateLimitConfig { requests_per_sec: 2.0, burst: 3 });
+        let client: IpAddr = [10, 0, 0, 1].into();
+        let other: IpAddr = [10, 0, 0, 2].into();
+        let start = Instant::now();
+
+        for _ in 0..3 {
+            assert!(limiter.check_at(client, start).is_ok());
+        }
+        let retry_after = limiter.check_at(client, start).unwrap_err();
+        assert_eq!(retry_after, Duration::from_millis(500));
+
+        assert!(limiter.check_at(other, start).is_ok()); // Buckets are per client
+        assert!(limiter.check_at(client, start + Duration::from_millis(500)).is_ok());
+    }
+}
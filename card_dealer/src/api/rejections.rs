@@ -0,0 +1,119 @@
+use std::convert::Infallible;
+use tracing::{error, warn};
+use warp::http::StatusCode;
+use warp::{Rejection, Reply};
+use poker_core::game_error::GameError;
+use super::i18n::{error_message, Locale, Message};
+use super::rate_limit::RateLimited;
+use super::responses::ErrorResponse;
+use super::validation::{FieldError, ValidationFailed};
+
+/// A `GameError` carried through warp's rejection machinery.
+#[derive(Debug)]
+pub struct ApiError(pub GameError);
+
+impl warp::reject::Reject for ApiError {}
+
+/// Rejects the request with `error`, answered with the status from `status_for`.
+pub fn reject(error: GameError) -> Rejection {
+    warp::reject::custom(ApiError(error))
+}
+
+/// Maps a `GameError` onto the HTTP status code reported to the client.
+pub fn status_for(error: &GameError) -> StatusCode {
+    match error {
+        GameError::InvalidRequest(_) | GameError::DuplicateCard(_) => StatusCode::BAD_REQUEST,
+        GameError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+        GameError::NotAllowed(_) => StatusCode::FORBIDDEN,
+        GameError::PlayerNotFound(_) | GameError::TableNotFound(_) | GameError::HandNotFound(_) | GameError::NoWinner => StatusCode::NOT_FOUND,
+        GameError::NotEnoughCards(_)
+        | GameError::InsufficientChips
+        | GameError::InvalidState(_) => StatusCode::CONFLICT,
+        GameError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// Recovery handler turning every rejection into a JSON error body.
+///
+/// # Response
+/// An `ErrorResponse` body with the matching HTTP status code, its message
+/// in the caller's `locale`.
+pub async fn handle_rejection(err: Rejection, locale: Locale) -> Result<impl Reply, Infallible> {
+    let mut retry_after = None;
+    let mut errors: Vec<FieldError> = Vec::new();
+    let (status, message) = if let Some(ApiError(game_error)) = err.find::<ApiError>() {
+        (status_for(game_error), error_message(game_error, locale))
+    } else if err.is_not_found() {
+        (StatusCode::NOT_FOUND, Message::RouteNotFound.text(locale).to_string())
+    } else if let Some(invalid) = err.find::<warp::reject::InvalidQuery>() {
+        (StatusCode::BAD_REQUEST, invalid.to_string())
+    } else if let Some(ValidationFailed(fields)) = err.find::<ValidationFailed>() {
+        errors = fields.clone();
+        (StatusCode::BAD_REQUEST, Message::InvalidBody.text(locale).to_string())
+    } else if let Some(invalid) = err.find::<warp::filters::body::BodyDeserializeError>() {
+        (StatusCode::BAD_REQUEST, invalid.to_string())
+    } else if let Some(too_large) = err.find::<warp::reject::PayloadTooLarge>() {
+        (StatusCode::PAYLOAD_TOO_LARGE, too_large.to_string())
+    } else if let Some(limited) = err.find::<RateLimited>() {
+        retry_after = Some((limited.retry_after.as_secs_f64().ceil() as u64).max(1));
+        (StatusCode::TOO_MANY_REQUESTS, Message::TooManyRequests.text(locale).to_string())
+    } else if let Some(forbidden) = err.find::<warp::cors::CorsForbidden>() {
+        (StatusCode::FORBIDDEN, forbidden.to_string())
+    } else if err.find::<warp::reject::MethodNotAllowed>().is_some() {
+        (StatusCode::METHOD_NOT_ALLOWED, Message::MethodNotAllowed.text(locale).to_string())
+    } else {
+        (StatusCode::INTERNAL_SERVER_ERROR, format!("Unhandled rejection: {:?}", err))
+    };
+
+    if status.is_server_error() {
+        error!(status = status.as_u16(), %message, "request failed");
+    } else {
+        warn!(status = status.as_u16(), %message, "request rejected");
+    }
+
+    let body = warp::reply::json(&ErrorResponse { errors, ..ErrorResponse::new(status.as_u16(), message) });
+    let mut response = warp::reply::with_status(body, status).into_response();
+    response.headers_mut().insert(warp::http::header::CONTENT_LANGUAGE, warp::http::HeaderValue::from_static(locale.tag()));
+    if let Some(secs) = retry_after {
+        response.headers_mut().insert(warp::http::header::RETRY_AFTER, secs.into());
+    }
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_codes() {
+        assert_eq!(status_for(&GameError::InvalidRequest("bad".to_string())), StatusCode::BAD_REQUEST);
+        assert_eq!(status_for(&GameError::NotAllowed("nope".to_string())), StatusCode::FORBIDDEN);
+        assert_eq!(status_for(&GameError::NoWinner), StatusCode::NOT_FOUND);
+        assert_eq!(status_for(&GameError::NotEnoughCards("hole cards")), StatusCode::CONFLICT);
+        assert_eq!(status_for(&GameError::Internal("poisoned".to_string())), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[tokio::test]
+    async fn test_game_error_rejection_body() {
+        let reply = handle_rejection(reject(GameError::NoWinner), Locale::En).await.unwrap();
+        let response = reply.into_response();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let body = warp::hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["type"], "error");
+        assert_eq!(json["status"], 404);
+        assert_eq!(json["message"], "No winner determined");
+    }
+
+    #[tokio::test]
+    async fn test_rejection_is_translated() {
+        let reply = handle_rejection(warp::reject::not_found(), Locale::De).await.unwrap();
+        let response = reply.into_response();
+        assert_eq!(response.headers()["content-language"], "de");
+
+        let body = warp::hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["message"], "Route nicht gefunden");
+    }
+}
@@ -0,0 +1,303 @@
+use poker_core::bots::Difficulty;
+use poker_core::buy_in::BuyInKind;
+use poker_core::chips::Chips;
+use poker_core::player::PlayerAction;
+use poker_core::poker_hand::HandRank;
+use poker_core::table_config::TableConfig;
+use poker_core::variant::GameVariant;
+use serde::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::hand_history::csv::Dataset;
+use crate::hand_history::query::HistoryFilter;
+use crate::table_registry::DEFAULT_TABLE_ID;
+
+/// Request body of `POST /v1/login`.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct LoginRequest {
+    #[schema(example = "1")]
+    pub player_id: String,
+    pub password: Option<String>, // Needed unless the server allows open login and none is configured
+    #[serde(default = "default_login_table")]
+    #[schema(example = "main")]
+    pub table_id: String, // Table the session acts at; the default table unless given
+}
+
+fn default_login_table() -> String {
+    DEFAULT_TABLE_ID.to_string()
+}
+
+/// Request body of `POST /v1/admin/login`.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct AdminLoginRequest {
+    pub admin_token: String,
+}
+
+/// Request body of `POST /v1/admin/players/{player_id}/chips`.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ChipAdjustmentRequest {
+    #[schema(example = -200)]
+    pub delta: i64, // Chips to add; negative to remove
+}
+
+/// Request body of `POST /v1/action`.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ActionRequest {
+    #[schema(example = json!({"raise": 40}))]
+    pub action: PlayerAction, // "fold", "check", "call", or {"bet": amount} / {"raise": amount}
+    #[schema(example = 42)]
+    pub state_version: u64,   // `state_version` of the seat the action was decided on
+}
+
+/// Request body of `POST /v1/run_it`.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RunItRequest {
+    #[schema(example = 2)]
+    pub runs: usize, // Times to run the rest of the board; the fewest anyone in the hand chose is dealt
+}
+
+/// Request body of `POST /v1/insurance`.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct InsuranceRequest {
+    pub buy: bool, // False declines the offer
+}
+
+/// Request body of `POST /v1/show`.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ShowRequest {
+    #[schema(example = "Ah")]
+    pub cards: String, // One or more of the caller's hole cards
+}
+
+/// Request body of `POST /v1/buyin`.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BuyInRequest {
+    #[serde(default = "default_buy_in_kind")]
+    pub kind: BuyInKind, // A plain buy-in unless given
+    #[schema(example = 1000)]
+    pub amount: Chips,
+}
+
+fn default_buy_in_kind() -> BuyInKind {
+    BuyInKind::BuyIn
+}
+
+/// Request body of `POST /v1/tables`: the new table's id and settings.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateTableRequest {
+    #[schema(example = "high-stakes")]
+    pub table_id: String,
+    #[serde(flatten)]
+    pub config: TableConfig,             // Settings not given take the engine's defaults
+    pub auto_deal_pause_secs: Option<u64>, // Deal hands one after another, this long apart; hands are only started by hand when unset
+}
+
+/// Request body of `POST /v1/tables/{table_id}/join`. The player joining
+/// is the caller; a body naming anyone else is refused.
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct JoinTableRequest {
+    #[schema(example = "Dana")]
+    pub display_name: String,
+    #[schema(example = 1000)]
+    pub chip_stack: Chips, // Stack to sit down with, within the table's buy-in limits
+}
+
+/// Request body of `POST /v1/invite`.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct InviteRequest {
+    #[schema(example = "2")]
+    pub friend_id: String,
+    #[schema(example = "main")]
+    pub table_id: String,
+}
+
+/// Request body of `POST /v1/invitations/{id}/accept`.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct AcceptInvitationRequest {
+    #[schema(example = "Dana")]
+    pub display_name: String,
+    #[schema(example = 1000)]
+    pub chip_stack: Chips,         // Stack to sit down with, within the table's buy-in limits
+}
+
+/// Query string of `GET /v1/invitations/ws`.
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct NotificationsQuery {
+    pub token: String, // The player's session token; browsers can't set headers on WebSockets
+}
+
+/// Request body of `POST /v1/tables/{table_id}/register`.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RegisterRequest {
+    #[schema(example = "4")]
+    pub player_id: String,
+    #[schema(example = "Dana")]
+    pub display_name: String,
+    pub password: Option<String>,  // Needed unless the server allows open login and none is configured
+}
+
+/// Query string of `GET /v1/tables/{table_id}/spectate/ws`.
+#[derive(Debug, Default, Deserialize, IntoParams)]
+pub struct SpectateQuery {
+    pub last_seq: Option<u64>, // Sequence number of the last event received before reconnecting
+    pub token: Option<String>, // A player's session token, to show them as connected; browsers can't set headers on WebSockets
+}
+
+/// Query string of `GET /v1/admin/tables/{table_id}/ledger`.
+#[derive(Debug, Default, Deserialize, IntoParams)]
+pub struct LedgerQuery {
+    pub player_id: Option<String>, // Only the transactions moving this player's chips
+}
+
+/// Query string of `GET /v1/poll`.
+#[derive(Debug, Default, Deserialize, IntoParams)]
+pub struct PollQuery {
+    #[serde(default)]
+    pub since_seq: u64,               // `last_seq` of the previous poll; 0 for everything the server still has
+    pub timeout_secs: Option<u64>,    // How long to wait for an event, 25 seconds unless given, at most 60
+}
+
+/// Query string of `GET /v1/evaluate`.
+#[derive(Debug, Default, Deserialize, IntoParams)]
+pub struct EvaluateQuery {
+    #[serde(default)]
+    pub percentile: bool, // Also rank each hand against every holding an opponent could have
+}
+
+/// Query string of `GET /v1/hint`.
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct HintQuery {
+    #[param(example = "1")]
+    pub player_id: String, // The caller's own id
+}
+
+/// Hand history formats written by `GET /v1/history/{hand_id}/export` and
+/// read by `POST /v1/history/import`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryFormat {
+    #[default]
+    Stars,  // PokerStars text, which PokerTracker and Hold'em Manager import; GGPoker's is read too
+    Phh,    // The open Poker Hand History format, one TOML document per hand
+}
+
+/// Query string of `GET /v1/history/{hand_id}/export`.
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ExportQuery {
+    #[param(example = "stars")]
+    pub format: HistoryFormat,
+}
+
+/// Query string of `POST /v1/history/import`.
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ImportQuery {
+    #[serde(default)]
+    pub format: HistoryFormat, // PokerStars text unless given
+}
+
+/// Query string of `GET /v1/history`. Every filter is optional.
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct HistoryQuery {
+    #[param(example = "1")]
+    pub player_id: Option<String>,          // Hands the player was dealt into
+    #[param(value_type = Option<String>, example = "Flush")]
+    pub min_hand_rank: Option<HandRank>,    // Hands where the player, or anyone without `player_id`, showed down at least this
+    #[param(value_type = Option<u64>)]
+    pub min_pot: Option<Chips>,
+    #[param(value_type = Option<u64>)]
+    pub max_pot: Option<Chips>,
+    pub from: Option<u64>,                  // Hands finished at or after, in milliseconds since the Unix epoch
+    pub to: Option<u64>,                    // Hands finished before
+    #[param(value_type = Option<String>, example = "hold_em")]
+    pub variant: Option<GameVariant>,
+    pub cursor: Option<u64>,                // `next_cursor` of the previous page
+    pub limit: Option<usize>,               // Hands per page, 50 unless given, at most 200
+}
+
+impl HistoryQuery {
+    pub fn filter(&self) -> HistoryFilter {
+        HistoryFilter {
+            player_id: self.player_id.clone(),
+            min_hand_rank: self.min_hand_rank.clone(),
+            min_pot: self.min_pot,
+            max_pot: self.max_pot,
+            from: self.from,
+            to: self.to,
+            variant: self.variant,
+        }
+    }
+}
+
+/// Query string of `GET /v1/history/export`.
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct CsvExportQuery {
+    #[param(example = "results")]
+    pub dataset: Dataset,
+    #[param(example = "hand_number,player_id,net")]
+    pub columns: Option<String>, // Column names separated by commas; every column when left out
+    pub from: Option<u64>,       // Hands finished at or after, in milliseconds since the Unix epoch
+    pub to: Option<u64>,         // Hands finished before, in milliseconds since the Unix epoch
+}
+
+/// Query string of `GET /v1/analysis/outs`.
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct OutsQuery {
+    #[param(example = "9h,Th")]
+    pub hole_cards: String, // Two cards, separated by commas or spaces
+    #[param(example = "Jh,Qc,2h")]
+    pub board: String,      // The flop, or the flop and turn
+}
+
+/// Request body of `POST /v1/equity_vs_range`.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct EquityVsRangeRequest {
+    #[schema(example = "Ah Kh")]
+    pub hero: String,               // Two cards, separated by commas or spaces
+    #[schema(example = "QQ+, AKs, A5s-A2s")]
+    pub range: String,              // The villain's possible hands
+    #[serde(default)]
+    #[schema(example = "Jh Qc 2h")]
+    pub board: String,              // Up to five cards; empty preflop
+    pub trials: Option<u32>,        // Runouts per villain hand; 1000 unless given
+}
+
+/// Request body of `POST /v1/add_bot`.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct AddBotRequest {
+    #[schema(example = "bot1")]
+    pub player_id: String,
+    #[schema(example = "Robo")]
+    pub display_name: String,
+    #[schema(example = 1000)]
+    pub chip_stack: Chips,
+    #[serde(default)]
+    pub difficulty: Difficulty, // Medium unless given
+}
+
+/// Request body of `POST /v1/debug/set_deck` (debug-routes feature).
+#[cfg(feature = "debug-routes")]
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct StackDeckRequest {
+    #[schema(example = "Ah Ad Kc Kd 7s 7h 2c Qd 9s")]
+    pub cards: String, // Dealt first: hole cards seat by seat, then the board
+}
+
+/// Request body of `POST /v1/analysis/solve`.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SolveRequest {
+    #[schema(example = json!(["JJ-22, AQs-A2s, KQs", "QQ+, AKs, AKo"]))]
+    pub ranges: Vec<String>,        // Two ranges: the player out of position, to act, then the one in position
+    #[schema(example = "Ah Kd 2c")]
+    pub board: String,              // The flop, turn or river
+    #[schema(example = 100)]
+    pub pot: Chips,
+    #[schema(example = 900)]
+    pub effective_stack: Chips,     // Chips behind the shorter stack
+    #[serde(default = "default_bet_sizes")]
+    pub bet_sizes: Vec<u32>,        // In percent of the pot; a third and three quarters unless given
+}
+
+fn default_bet_sizes() -> Vec<u32> {
+    vec![33, 75]
+}
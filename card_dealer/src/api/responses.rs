@@ -0,0 +1,979 @@
+use serde::Serialize;
+use utoipa::ToSchema;
+use poker_core::betting::{InsuranceOffer, Street, StreetBets};
+use poker_core::blind_schedule::ClockState;
+use poker_core::bots::ActionContext;
+use poker_core::card_dealer::Card;
+use poker_core::chips::Chips;
+use poker_core::events::GameEvent;
+use poker_core::equity::{hand_percentile, ComboEquity, RangeEquity};
+use poker_core::game_controller::GameController;
+use poker_core::hints::Hint;
+use poker_core::ledger::Transaction;
+use poker_core::outs::{Draw, Out, OutsAnalysis};
+use poker_core::player::{Player, PlayerAction};
+use poker_core::preflop_chart::Position;
+use poker_core::solver::{ComboStrategy, SolverStrategy};
+use poker_core::player_id::PlayerId;
+use poker_core::poker_hand::{Hand, HandRank};
+use poker_core::table_config::TableConfig;
+use poker_core::tournament::{Tournament, TournamentRules};
+use poker_core::variant::GameVariant;
+use poker_core::waitlist::{Seating, WaitingPlayer};
+use crate::audit_log::{verify_chain, AuditEntry};
+use crate::events::{hand_id, EventBus, SequencedEvent};
+use crate::hand_history::query::{shown_hands, HistoryPage};
+use crate::hand_history::collusion::CollusionFlag;
+use crate::hand_history::sessions::SessionSummary;
+use crate::hand_history::RecordedHand;
+use crate::presence::Presence;
+use crate::profiles::PlayerProfile;
+use crate::social::{Friend, Invitation};
+use super::auth::Session;
+use super::i18n::{hand_name, Locale};
+use super::validation::FieldError;
+
+// Every response carries a `type` field naming its shape, so clients can
+// dispatch on it without knowing which route produced the body.
+
+/// A player's hole cards as returned by `/v1/deal_hole`.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PlayerHoleCards {
+    pub name: String,
+    pub hole_cards: Vec<Card>,
+}
+
+impl From<&Player> for PlayerHoleCards {
+    fn from(player: &Player) -> Self {
+        Self {
+            name: player.display_name.clone(),
+            hole_cards: player.hole_cards.clone(),
+        }
+    }
+}
+
+/// Response body of `GET /v1/deal_hole`.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DealHoleResponse {
+    #[serde(rename = "type")]
+    #[schema(example = "hole")]
+    pub response_type: &'static str,
+    pub players: Vec<PlayerHoleCards>,
+}
+
+impl DealHoleResponse {
+    pub fn new(players: Vec<PlayerHoleCards>) -> Self {
+        Self { response_type: "hole", players }
+    }
+}
+
+/// Response body of `GET /v1/deal_community`.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CommunityResponse {
+    #[serde(rename = "type")]
+    #[schema(example = "community")]
+    pub response_type: &'static str,
+    pub cards: Vec<Card>,
+}
+
+impl CommunityResponse {
+    pub fn new(cards: Vec<Card>) -> Self {
+        Self { response_type: "community", cards }
+    }
+}
+
+/// Response body of `GET /v1/reset`.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ResetResponse {
+    #[serde(rename = "type")]
+    #[schema(example = "reset")]
+    pub response_type: &'static str,
+    pub message: String,
+}
+
+impl ResetResponse {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self { response_type: "reset", message: message.into() }
+    }
+}
+
+/// A player's evaluated hand as returned by `/v1/evaluate`.
+#[derive(Serialize, ToSchema)]
+pub struct PlayerEvaluation {
+    pub name: String,
+    pub hand_strength: Option<HandRank>,
+    pub hand_description: Option<String>, // `hand_strength` in the caller's language
+    pub best_hand: Option<Hand>,
+    pub hole_cards: Vec<Card>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = 0.87)]
+    pub percentile: Option<f64>, // Share of opposing holdings the hand beats on the board; only when asked for
+}
+
+impl PlayerEvaluation {
+    pub fn new(player: &Player, locale: Locale) -> Self {
+        Self {
+            name: player.display_name.clone(),
+            hand_strength: player.hand_strength.clone(),
+            hand_description: player.hand_strength.as_ref().map(|rank| hand_name(rank, locale).to_string()),
+            best_hand: player.best_hand.clone(),
+            hole_cards: player.hole_cards.clone(),
+            percentile: None,
+        }
+    }
+
+    /// Adds where the hand ranks on `board`, if it has two hole cards and
+    /// there is a flop to rank it on.
+    pub fn with_percentile(mut self, board: &[Card]) -> Self {
+        self.percentile = hand_percentile(&self.hole_cards, board).ok();
+        self
+    }
+}
+
+/// Response body of `GET /v1/evaluate`.
+#[derive(Serialize, ToSchema)]
+pub struct EvaluationResponse {
+    #[serde(rename = "type")]
+    #[schema(example = "evaluation")]
+    pub response_type: &'static str,
+    pub players: Vec<PlayerEvaluation>,
+    pub community_cards: Vec<Card>,
+}
+
+impl EvaluationResponse {
+    pub fn new(players: Vec<PlayerEvaluation>, community_cards: Vec<Card>) -> Self {
+        Self { response_type: "evaluation", players, community_cards }
+    }
+}
+
+/// A winning player as returned by `/v1/test_winners`.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WinnerSummary {
+    pub player_id: PlayerId,
+    pub name: String,
+    pub hand_strength: Option<HandRank>,
+    pub hand_description: Option<String>, // `hand_strength` in the caller's language
+    pub best_hand: Option<Vec<Card>>,
+}
+
+impl WinnerSummary {
+    pub fn new(player: &Player, locale: Locale) -> Self {
+        Self {
+            player_id: player.player_id.clone(),
+            name: player.display_name.clone(),
+            hand_strength: player.hand_strength.clone(),
+            hand_description: player.hand_strength.as_ref().map(|rank| hand_name(rank, locale).to_string()),
+            best_hand: player.best_hand.as_ref().map(|hand| hand.cards.clone()),
+        }
+    }
+}
+
+/// Response body of `GET /v1/test_winners`.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TestWinnersResponse {
+    #[serde(rename = "type")]
+    #[schema(example = "test_winners")]
+    pub response_type: &'static str,
+    pub players: Vec<WinnerSummary>,
+}
+
+impl TestWinnersResponse {
+    pub fn new(players: Vec<WinnerSummary>) -> Self {
+        Self { response_type: "test_winners", players }
+    }
+}
+
+/// Response body of `POST /v1/login` and `POST /v1/refresh`.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SessionResponse {
+    #[serde(rename = "type")]
+    #[schema(example = "session")]
+    pub response_type: &'static str,
+    pub player_id: String,
+    pub table_id: String,  // Table the session's seat-scoped requests go to
+    pub token: String,     // Send back as `Authorization: Bearer <token>`
+    pub expires_at: u64,   // Unix time (seconds)
+}
+
+impl SessionResponse {
+    pub fn new(token: String, session: Session) -> Self {
+        Self {
+            response_type: "session",
+            player_id: session.player_id,
+            table_id: session.table_id,
+            token,
+            expires_at: session.expires_at,
+        }
+    }
+}
+
+/// Response body of `GET /v1/me`: the caller's own seat.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PlayerStateResponse {
+    #[serde(rename = "type")]
+    #[schema(example = "player")]
+    pub response_type: &'static str,
+    pub table_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = "main:42")]
+    pub hand_id: Option<String>,      // The hand in progress, or the last one dealt; absent before the first
+    pub player_id: PlayerId,
+    pub name: String,
+    pub chip_stack: Chips,
+    pub hole_cards: Vec<Card>,
+    pub chips_won: i64,               // Won from pots less chips put in, this session
+    pub ev_chips_won: f64,            // The same with each all-in before the river counted at equity
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action: Option<ActionPrompt>, // Only while it's this player's turn
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub runs_to_choose: Option<usize>, // Most runs the player may ask for, while the hand waits for them on `POST /v1/run_it`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub insurance: Option<InsurancePrompt>, // Offered to this player, while the hand waits for them on `POST /v1/insurance`
+    pub state_version: u64,           // The table's latest event; echoed on `POST /v1/action`
+}
+
+impl PlayerStateResponse {
+    /// The player's seat at `controller`, with the price of acting when it's
+    /// their turn.
+    pub fn new(player: &Player, controller: &GameController, events: &EventBus) -> Self {
+        let action = (controller.next_to_act() == Some(player.player_id.as_str()))
+            .then(|| controller.action_context(&player.player_id).map(ActionPrompt::from))
+            .flatten();
+        let runs_to_choose = controller.runout_vote()
+            .filter(|vote| vote.choices.iter().any(|(id, runs)| *id == player.player_id && runs.is_none()))
+            .map(|vote| vote.max_runs);
+        let insurance = controller.insurance_offer()
+            .filter(|offer| offer.player_id == player.player_id && offer.bought.is_none())
+            .map(InsurancePrompt::from);
+        Self {
+            response_type: "player",
+            table_id: events.table_id().to_string(),
+            hand_id: (controller.hand_number() > 0).then(|| hand_id(events.table_id(), controller.hand_number())),
+            player_id: player.player_id.clone(),
+            name: player.display_name.clone(),
+            chip_stack: player.chip_stack,
+            hole_cards: player.hole_cards.clone(),
+            chips_won: player.chips_won,
+            ev_chips_won: player.ev_chips_won,
+            action,
+            runs_to_choose,
+            insurance,
+            state_version: events.last_seq(),
+        }
+    }
+}
+
+/// All-in insurance on offer to the favourite.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct InsurancePrompt {
+    #[schema(example = 0.8)]
+    pub equity: f64,        // Exact share of the insured pots the player wins
+    pub coverage: Chips,    // Paid back if the player loses them
+    pub premium: Chips,     // Taken from the stack when bought
+}
+
+impl From<&InsuranceOffer> for InsurancePrompt {
+    fn from(offer: &InsuranceOffer) -> Self {
+        Self { equity: offer.equity, coverage: offer.coverage, premium: offer.premium }
+    }
+}
+
+/// What acting costs the player whose turn it is, worked out so clients
+/// don't have to.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ActionPrompt {
+    pub to_call: Chips,          // Zero when checking is free
+    pub pot: Chips,              // Every chip committed to the hand so far
+    #[schema(example = 0.25)]
+    pub pot_odds: f64,           // Equity a call needs to break even
+    pub effective_stack: Chips,  // The smaller of the player's stack and the biggest opponent's
+    pub min_raise_to: Chips,     // Smallest street total a raise may go to
+}
+
+impl From<ActionContext> for ActionPrompt {
+    fn from(context: ActionContext) -> Self {
+        Self {
+            to_call: context.to_call,
+            pot: context.pot,
+            pot_odds: context.pot_odds(),
+            effective_stack: context.effective_stack,
+            min_raise_to: context.min_raise_to,
+        }
+    }
+}
+
+/// Response body of the `/v1/admin` moderation routes.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AdminActionResponse {
+    #[serde(rename = "type")]
+    #[schema(example = "admin")]
+    pub response_type: &'static str,
+    #[schema(example = "Player 2 removed")]
+    pub message: String,
+}
+
+impl AdminActionResponse {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self { response_type: "admin", message: message.into() }
+    }
+}
+
+/// Response body of `GET /v1/admin/tables/{table_id}/audit`.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AuditLogResponse {
+    #[serde(rename = "type")]
+    #[schema(example = "audit_log")]
+    pub response_type: &'static str,
+    pub table_id: String,
+    pub intact: bool,                // Every entry's hash checks out and links to the one before
+    pub entries: Vec<AuditEntry>,    // Oldest first
+}
+
+impl AuditLogResponse {
+    pub fn new(table_id: String, entries: Vec<AuditEntry>) -> Self {
+        Self { response_type: "audit_log", table_id, intact: verify_chain(&entries).is_ok(), entries }
+    }
+}
+
+/// Response body of `GET /v1/admin/tables/{table_id}/ledger`.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LedgerResponse {
+    #[serde(rename = "type")]
+    #[schema(example = "ledger")]
+    pub response_type: &'static str,
+    pub table_id: String,
+    pub reconciled: bool,                // Every balance matches the stacks, the pot and the chips in play
+    pub transactions: Vec<Transaction>,  // Oldest first; only the most recent are kept
+}
+
+impl LedgerResponse {
+    pub fn new(table_id: String, reconciled: bool, transactions: Vec<Transaction>) -> Self {
+        Self { response_type: "ledger", table_id, reconciled, transactions }
+    }
+}
+
+/// Response body of `GET /v1/admin/tables/{table_id}/collusion`.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CollusionReportResponse {
+    #[serde(rename = "type")]
+    #[schema(example = "collusion_report")]
+    pub response_type: &'static str,
+    pub table_id: String,
+    pub hands_analyzed: usize,       // Finished hands the table still keeps
+    pub flags: Vec<CollusionFlag>,
+}
+
+impl CollusionReportResponse {
+    pub fn new(table_id: String, hands_analyzed: usize, flags: Vec<CollusionFlag>) -> Self {
+        Self { response_type: "collusion_report", table_id, hands_analyzed, flags }
+    }
+}
+
+/// Response body of `GET /v1/analysis/outs`.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct OutsResponse {
+    #[serde(rename = "type")]
+    #[schema(example = "outs")]
+    pub response_type: &'static str,
+    pub hand: HandRank,
+    pub hand_description: String, // `hand` in the caller's language
+    pub draws: Vec<Draw>,
+    pub out_count: usize,
+    pub outs: Vec<Out>,
+}
+
+impl OutsResponse {
+    pub fn new(analysis: OutsAnalysis, locale: Locale) -> Self {
+        Self {
+            response_type: "outs",
+            hand_description: hand_name(&analysis.hand, locale).to_string(),
+            hand: analysis.hand,
+            draws: analysis.draws,
+            out_count: analysis.outs.len(),
+            outs: analysis.outs,
+        }
+    }
+}
+
+/// Response body of `POST /v1/equity_vs_range`.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct EquityVsRangeResponse {
+    #[serde(rename = "type")]
+    #[schema(example = "equity_vs_range")]
+    pub response_type: &'static str,
+    pub equity: f64,                // Against the whole range, in `0..=1`
+    pub combo_count: usize,         // Villain hands the known cards don't block
+    pub combos: Vec<ComboEquity>,
+}
+
+impl EquityVsRangeResponse {
+    pub fn new(result: RangeEquity) -> Self {
+        Self { response_type: "equity_vs_range", equity: result.equity, combo_count: result.combos.len(), combos: result.combos }
+    }
+}
+
+/// How often a range takes one action.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ActionFrequency {
+    #[schema(example = "bet 75")]
+    pub action: String,
+    pub frequency: f64,
+}
+
+/// Response body of `POST /v1/analysis/solve`.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SolveResponse {
+    #[serde(rename = "type")]
+    #[schema(example = "solver_strategy")]
+    pub response_type: &'static str,
+    pub solver: String,                 // The backend's name
+    pub actions: Vec<ActionFrequency>,  // The whole range, each hand weighted equally
+    pub combos: Vec<ComboStrategy>,     // Frequencies in the order of `actions`
+}
+
+impl SolveResponse {
+    pub fn new(solver: &str, strategy: SolverStrategy) -> Self {
+        let actions = strategy.actions().iter()
+            .zip(strategy.overall())
+            .map(|(action, frequency)| ActionFrequency { action: action.clone(), frequency })
+            .collect();
+        Self { response_type: "solver_strategy", solver: solver.to_string(), actions, combos: strategy.combos().to_vec() }
+    }
+}
+
+/// A hand read by `POST /v1/history/import`.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ImportedHand {
+    pub hand_number: u64,
+    pub played_at: u64,                     // Milliseconds since the Unix epoch, 0 if the history had no date
+    pub board: Vec<Card>,
+    pub hole_cards: Vec<PlayerHoleCards>,   // Only the cards the history shows, by screen name
+    pub events: Vec<GameEvent>,             // The hand replayed as table events, players known by screen name
+}
+
+impl From<&RecordedHand> for ImportedHand {
+    fn from(hand: &RecordedHand) -> Self {
+        let log = &hand.log;
+        let hole_cards = log.players.iter()
+            .filter_map(|player| Some(PlayerHoleCards { name: player.display_name.clone(), hole_cards: log.hole_cards.get(&player.player_id)?.clone() }))
+            .collect();
+        Self { hand_number: log.hand_number, played_at: hand.finished_at, board: log.board.clone(), hole_cards, events: log.events() }
+    }
+}
+
+/// A hand a player showed down, as listed by `GET /v1/history`.
+#[derive(Serialize, ToSchema)]
+pub struct ShownHand {
+    pub player_id: PlayerId,
+    pub hole_cards: Vec<Card>,
+    pub hand: Hand,             // Their best five cards on the first board
+}
+
+/// What was put into a hand on one street, blinds and antes counting
+/// towards preflop.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StreetPot {
+    pub street: Street,
+    pub total: Chips,
+    pub bets: Vec<StreetBet>,   // Players who put nothing in are left out
+}
+
+/// What one player put in on a street.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StreetBet {
+    pub player_id: PlayerId,
+    pub amount: Chips,
+}
+
+impl From<&StreetBets> for StreetPot {
+    fn from(street: &StreetBets) -> Self {
+        Self {
+            street: street.street,
+            total: street.total(),
+            bets: street.bets.iter().map(|(player_id, amount)| StreetBet { player_id: player_id.clone(), amount: *amount }).collect(),
+        }
+    }
+}
+
+/// A finished hand as listed by `GET /v1/history`.
+#[derive(Serialize, ToSchema)]
+pub struct HandSummary {
+    #[schema(example = "main:42")]
+    pub hand_id: String,
+    pub hand_number: u64,
+    pub played_at: u64,             // When the hand finished, in milliseconds since the Unix epoch
+    pub variant: GameVariant,
+    pub players: Vec<PlayerId>,     // Dealt in, button first
+    pub pot: Chips,                 // Without any bet nobody called
+    pub streets: Vec<StreetPot>,    // What went in on each street dealt, including any bet nobody called
+    pub board: Vec<Card>,
+    pub winners: Vec<PlayerId>,
+    pub shown: Vec<ShownHand>,      // Only hands shown at showdown
+}
+
+impl HandSummary {
+    pub fn new(table_id: &str, hand: &RecordedHand) -> Self {
+        let log = &hand.log;
+        let shown = shown_hands(hand).into_iter()
+            .map(|(id, best)| ShownHand { player_id: id.clone(), hole_cards: log.hole_cards[id].clone(), hand: best })
+            .collect();
+        Self {
+            hand_id: hand_id(table_id, log.hand_number),
+            hand_number: log.hand_number,
+            played_at: hand.finished_at,
+            variant: log.variant,
+            players: log.seats.clone(),
+            pot: hand.pot(),
+            streets: log.streets.iter().map(StreetPot::from).collect(),
+            board: log.board.clone(),
+            winners: hand.winners().into_iter().cloned().collect(),
+            shown,
+        }
+    }
+}
+
+/// Response body of `GET /v1/history`.
+#[derive(Serialize, ToSchema)]
+pub struct HandHistoryResponse {
+    #[serde(rename = "type")]
+    #[schema(example = "hand_history")]
+    pub response_type: &'static str,
+    pub hands: Vec<HandSummary>,    // Newest first
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<u64>,   // Pass as `cursor` for the next page; absent on the last one
+}
+
+impl HandHistoryResponse {
+    pub fn new(table_id: &str, page: HistoryPage) -> Self {
+        let hands = page.hands.into_iter().map(|hand| HandSummary::new(table_id, hand)).collect();
+        Self { response_type: "hand_history", hands, next_cursor: page.next_cursor }
+    }
+}
+
+/// Response body of `POST /v1/history/import`.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct HandImportResponse {
+    #[serde(rename = "type")]
+    #[schema(example = "hand_history_import")]
+    pub response_type: &'static str,
+    pub hands: Vec<ImportedHand>,
+}
+
+impl HandImportResponse {
+    pub fn new(hands: &[RecordedHand]) -> Self {
+        Self { response_type: "hand_history_import", hands: hands.iter().map(ImportedHand::from).collect() }
+    }
+}
+
+/// Response body of `GET /v1/sessions/{player_id}`.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SessionsResponse {
+    #[serde(rename = "type")]
+    #[schema(example = "sessions")]
+    pub response_type: &'static str,
+    pub player_id: String,
+    pub sessions: Vec<SessionSummary>, // Oldest first
+}
+
+impl SessionsResponse {
+    pub fn new(player_id: String, sessions: Vec<SessionSummary>) -> Self {
+        Self { response_type: "sessions", player_id, sessions }
+    }
+}
+
+/// Response body of `GET /v1/hint`.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct HintResponse {
+    #[serde(rename = "type")]
+    #[schema(example = "hint")]
+    pub response_type: &'static str,
+    pub action: PlayerAction,
+    #[schema(example = "K6s is in the button's opening range: raise rather than limp.")]
+    pub rationale: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub equity: Option<f64>,        // Estimated share of the pot the hand wins; from the flop on
+    pub pot_odds: f64,              // Equity a call needs to break even
+    pub position: Position,
+}
+
+impl HintResponse {
+    pub fn new(hint: Hint) -> Self {
+        Self {
+            response_type: "hint",
+            action: hint.action,
+            rationale: hint.rationale,
+            equity: hint.equity,
+            pot_odds: hint.pot_odds,
+            position: hint.position,
+        }
+    }
+}
+
+/// Response body of `GET /v1/rabbit_hunt`.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RabbitHuntResponse {
+    #[serde(rename = "type")]
+    #[schema(example = "rabbit_hunt")]
+    pub response_type: &'static str,
+    #[schema(example = "main:42")]
+    pub hand_id: String,
+    pub board: Vec<Card>, // The cards dealt before the hand ended
+    pub cards: Vec<Card>, // The cards that would have come next, in order
+}
+
+impl RabbitHuntResponse {
+    pub fn new(hand_id: String, board: Vec<Card>, cards: Vec<Card>) -> Self {
+        Self { response_type: "rabbit_hunt", hand_id, board, cards }
+    }
+}
+
+/// A seat as shown to spectators: public information only, never hole cards.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SpectatorSeat {
+    pub player_id: PlayerId,
+    pub name: String,
+    pub table_position: usize,
+    pub chip_stack: Chips,
+    pub is_in_play: bool,
+    pub is_sitting_out: bool,
+    pub has_cards: bool, // Holds hole cards in the current hand
+    pub bounty: Chips,   // Paid for knocking this player out; zero outside bounty tournaments
+    pub bounties_won: Chips,
+    pub presence: Presence, // Whether the player's client is connected to the table
+}
+
+impl From<&Player> for SpectatorSeat {
+    fn from(player: &Player) -> Self {
+        Self {
+            player_id: player.player_id.clone(),
+            name: player.display_name.clone(),
+            table_position: player.table_position,
+            chip_stack: player.chip_stack,
+            is_in_play: player.is_in_play,
+            is_sitting_out: player.is_sitting_out,
+            has_cards: !player.hole_cards.is_empty(),
+            bounty: player.bounty,
+            bounties_won: player.bounties_won,
+            presence: Presence::Disconnected, // Filled in from the table's tracker
+        }
+    }
+}
+
+/// Response body of `GET /v1/tables/{table_id}/spectate`.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SpectatorViewResponse {
+    #[serde(rename = "type")]
+    #[schema(example = "spectate")]
+    pub response_type: &'static str,
+    pub table_id: String,
+    pub hand_number: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hand_id: Option<String>, // Of `hand_number`; absent before the first hand
+    pub hand_in_progress: bool,
+    pub paused: bool,
+    pub hand_for_hand: bool,   // On the money bubble, waiting for every table between hands
+    pub community_cards: Vec<Card>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub extra_boards: Vec<Vec<Card>>, // Boards after the first, in double-board games
+    pub pots: Vec<Chips>,      // Total of each pot, main pot first
+    pub streets: Vec<StreetPot>, // What went in on each street of the hand in progress, or of the last hand
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jackpot: Option<Chips>, // Bad beat jackpot pool, when the table runs one
+    pub players: Vec<SpectatorSeat>,
+    pub spectators: usize,     // Open spectator WebSocket connections
+    pub last_event_seq: u64,   // Events after this one arrive on the spectator WebSocket
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub clock: Option<BlindClockView>, // Tournament tables with a blind schedule only
+}
+
+/// Response body of `GET /v1/poll`.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PollResponse {
+    #[serde(rename = "type")]
+    #[schema(example = "poll")]
+    pub response_type: &'static str,
+    pub events: Vec<SequencedEvent>, // Oldest first; empty when the wait timed out
+    pub last_seq: u64,               // Pass as `since_seq` on the next poll
+    pub resync: bool,                // Events were lost; fetch the table state again before carrying on
+}
+
+impl PollResponse {
+    pub fn new(events: Vec<SequencedEvent>, since_seq: u64, resync: bool) -> Self {
+        let last_seq = events.last().map_or(since_seq, |event| event.seq);
+        Self { response_type: "poll", events, last_seq, resync }
+    }
+}
+
+/// The tournament clock as a spectator sees it.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BlindClockView {
+    pub level: usize,                   // Starts at 1
+    pub small_blind: Chips,             // Blinds of the current level, posted from the next hand
+    pub big_blind: Chips,
+    pub ante: Chips,                    // Posted by the big blind for the whole table; zero when none
+    pub on_break: bool,
+    pub seconds_remaining: Option<u64>, // In the level or break; absent on the last level
+}
+
+impl From<ClockState> for BlindClockView {
+    fn from(state: ClockState) -> Self {
+        Self {
+            level: state.level,
+            small_blind: state.small_blind,
+            big_blind: state.big_blind,
+            ante: state.ante,
+            on_break: state.on_break,
+            seconds_remaining: state.remaining.map(|remaining| remaining.as_secs_f64().ceil() as u64),
+        }
+    }
+}
+
+/// Response body of the `/v1/players/{player_id}/profile` routes.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ProfileResponse {
+    #[serde(rename = "type")]
+    #[schema(example = "profile")]
+    pub response_type: &'static str,
+    pub player_id: String,
+    #[serde(flatten)]
+    pub profile: PlayerProfile,
+}
+
+impl ProfileResponse {
+    pub fn new(player_id: String, profile: PlayerProfile) -> Self {
+        Self { response_type: "profile", player_id, profile }
+    }
+}
+
+/// Response body of `GET /v1/balance` and `POST /v1/admin/players/{player_id}/balance`.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BalanceResponse {
+    #[serde(rename = "type")]
+    #[schema(example = "balance")]
+    pub response_type: &'static str,
+    pub player_id: String,
+    pub balance: Chips,           // Chips held away from the tables
+}
+
+impl BalanceResponse {
+    pub fn new(player_id: String, balance: Chips) -> Self {
+        Self { response_type: "balance", player_id, balance }
+    }
+}
+
+/// Response body of the `/v1/friends` routes.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FriendsResponse {
+    #[serde(rename = "type")]
+    #[schema(example = "friends")]
+    pub response_type: &'static str,
+    pub player_id: String,
+    pub friends: Vec<Friend>,      // In id order
+}
+
+impl FriendsResponse {
+    pub fn new(player_id: String, friends: Vec<Friend>) -> Self {
+        Self { response_type: "friends", player_id, friends }
+    }
+}
+
+/// Response body of `POST /v1/invite` and `POST /v1/invitations/{id}/decline`,
+/// and each frame of `GET /v1/invitations/ws`.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct InvitationResponse {
+    #[serde(rename = "type")]
+    #[schema(example = "invitation")]
+    pub response_type: &'static str,
+    #[serde(flatten)]
+    pub invitation: Invitation,
+}
+
+impl InvitationResponse {
+    pub fn new(invitation: Invitation) -> Self {
+        Self { response_type: "invitation", invitation }
+    }
+}
+
+/// Response body of `GET /v1/invitations`.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct InvitationsResponse {
+    #[serde(rename = "type")]
+    #[schema(example = "invitations")]
+    pub response_type: &'static str,
+    pub invitations: Vec<Invitation>, // Oldest first
+}
+
+impl InvitationsResponse {
+    pub fn new(invitations: Vec<Invitation>) -> Self {
+        Self { response_type: "invitations", invitations }
+    }
+}
+
+/// Response body of `POST /v1/tables`.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TableCreatedResponse {
+    #[serde(rename = "type")]
+    #[schema(example = "table_created")]
+    pub response_type: &'static str,
+    pub table_id: String,
+    #[serde(flatten)]
+    pub config: TableConfig,  // The settings in force, defaults filled in
+}
+
+impl TableCreatedResponse {
+    pub fn new(table_id: String, config: TableConfig) -> Self {
+        Self { response_type: "table_created", table_id, config }
+    }
+}
+
+/// Response body of `POST /v1/tables/{table_id}/join`.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JoinTableResponse {
+    #[serde(rename = "type")]
+    #[schema(example = "join")]
+    pub response_type: &'static str,
+    pub table_id: String,
+    pub player_id: String,
+    #[serde(flatten)]
+    pub seating: Seating,     // `status` is "seated" with a table position, or "waiting" with a place in line
+}
+
+impl JoinTableResponse {
+    pub fn new(table_id: String, player_id: String, seating: Seating) -> Self {
+        Self { response_type: "join", table_id, player_id, seating }
+    }
+}
+
+/// Response body of the `/v1/tables/{table_id}/waitlist` routes.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WaitlistResponse {
+    #[serde(rename = "type")]
+    #[schema(example = "waitlist")]
+    pub response_type: &'static str,
+    pub table_id: String,
+    pub seats_taken: usize,
+    pub max_seats: usize,
+    pub players: Vec<WaitingPlayer>,    // Next to be seated first
+}
+
+impl WaitlistResponse {
+    pub fn new(table_id: String, controller: &GameController) -> Self {
+        Self {
+            response_type: "waitlist",
+            table_id,
+            seats_taken: controller.get_players().len(),
+            max_seats: controller.max_seats(),
+            players: controller.waitlist().iter().cloned().collect(),
+        }
+    }
+}
+
+/// Response body of `GET /v1/tables/{table_id}/tournament`.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TournamentResponse {
+    #[serde(rename = "type")]
+    #[schema(example = "tournament")]
+    pub response_type: &'static str,
+    pub table_id: String,
+    pub rules: TournamentRules,
+    pub registration_open: bool,   // New entries and re-entries are taken for the next hand
+    pub total_entries: u32,
+    pub prize_pool: Chips,         // Entry fees paid so far
+}
+
+impl TournamentResponse {
+    pub fn new(table_id: String, tournament: &Tournament, registration_open: bool) -> Self {
+        Self {
+            response_type: "tournament",
+            table_id,
+            rules: tournament.rules,
+            registration_open,
+            total_entries: tournament.total_entries(),
+            prize_pool: tournament.prize_pool(),
+        }
+    }
+}
+
+/// Response body of `POST /v1/tables/{table_id}/register` and `/reenter`.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TournamentEntryResponse {
+    #[serde(rename = "type")]
+    #[schema(example = "entry")]
+    pub response_type: &'static str,
+    pub table_id: String,
+    pub player_id: String,
+    pub entries: u32,              // Entries the player has made, this one included
+    pub prize_pool: Chips,
+    #[serde(flatten)]
+    pub seating: Seating,
+}
+
+/// Body of every non-2xx response.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ErrorResponse {
+    #[serde(rename = "type")]
+    #[schema(example = "error")]
+    pub response_type: &'static str,
+    #[schema(example = 409)]
+    pub status: u16,
+    #[schema(example = "Not enough cards to deal hole cards.")]
+    pub message: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<FieldError>, // Each invalid field of the request body
+}
+
+impl ErrorResponse {
+    pub fn new(status: u16, message: impl Into<String>) -> Self {
+        Self { response_type: "error", status, message: message.into(), errors: Vec::new() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use poker_core::card_dealer::{Rank, Suit};
+    use serde_json::json;
+
+    #[test]
+    fn test_deal_hole_response_shape() {
+        let response = DealHoleResponse::new(vec![PlayerHoleCards {
+            name: "Alice".to_string(),
+            hole_cards: vec![Card { rank: Rank::Ace, suit: Suit::Spades }],
+        }]);
+
+        assert_eq!(
+            serde_json::to_value(&response).unwrap(),
+            json!({
+                "type": "hole",
+                "players": [{"name": "Alice", "hole_cards": [{"rank": "Ace", "suit": "Spades"}]}]
+            })
+        );
+    }
+
+    #[test]
+    fn test_error_response_shape() {
+        let response = ErrorResponse::new(404, "No winner determined");
+        assert_eq!(
+            serde_json::to_value(&response).unwrap(),
+            json!({"type": "error", "status": 404, "message": "No winner determined"})
+        );
+    }
+}
+
+/// Response body of `GET /v1/debug/invariants` (debug-routes feature).
+#[cfg(feature = "debug-routes")]
+#[derive(Debug, Serialize, ToSchema)]
+pub struct InvariantsResponse {
+    #[serde(rename = "type")]
+    #[schema(example = "invariants")]
+    pub response_type: &'static str,
+    pub ok: bool,
+    pub violations: Vec<String>, // Each broken rule, empty when the table is consistent
+}
+
+#[cfg(feature = "debug-routes")]
+impl InvariantsResponse {
+    pub fn new(violations: Vec<String>) -> Self {
+        Self { response_type: "invariants", ok: violations.is_empty(), violations }
+    }
+}
@@ -0,0 +1,380 @@
+//! What the core game routes do, as plain async functions with no tie to
+//! warp. The filters in `warp_routes` only pull the inputs out of the
+//! request, call `ApiService` and turn the result into a reply, so another
+//! HTTP framework could serve the same API, and the logic can be tested
+//! without building a request.
+
+use std::future::Future;
+use std::net::IpAddr;
+
+use poker_core::card_dealer::parse_cards;
+use poker_core::game_error::GameError;
+use poker_core::player_id::PlayerId;
+use tracing::info;
+
+use crate::events::hand_id;
+use crate::table_registry::TableHandle;
+use super::auth::{Role, Session};
+use super::i18n::Locale;
+use super::idempotency::Claim;
+use super::requests::{ActionRequest, BuyInRequest, EvaluateQuery, HintQuery, InsuranceRequest, LoginRequest, RunItRequest, ShowRequest};
+use super::responses::{
+    CommunityResponse, DealHoleResponse, EvaluationResponse, HintResponse, PlayerEvaluation, PlayerHoleCards, PlayerStateResponse,
+    RabbitHuntResponse, ResetResponse, SessionResponse, TestWinnersResponse, WinnerSummary,
+};
+use super::warp_routes::AppState;
+
+/// The dealer, session and player operations of the API, one method per
+/// route. Sessions come in already checked; errors map to statuses as in
+/// `rejections`.
+pub trait ApiService: Send + Sync {
+    /// `GET /v1/deal_hole`: deals two hole cards to every player.
+    fn deal_hole(&self) -> impl Future<Output = Result<DealHoleResponse, GameError>> + Send;
+
+    /// `GET /v1/deal_community`: deals the five community cards.
+    fn deal_community(&self) -> impl Future<Output = Result<CommunityResponse, GameError>> + Send;
+
+    /// `GET /v1/reset`: puts the cards back in the deck.
+    fn reset(&self) -> impl Future<Output = Result<ResetResponse, GameError>> + Send;
+
+    /// `GET /v1/evaluate`: every player's best hand.
+    fn evaluate(&self, query: EvaluateQuery, locale: Locale) -> impl Future<Output = Result<EvaluationResponse, GameError>> + Send;
+
+    /// `GET /v1/test_winners`: the player(s) holding the best hand.
+    fn test_winners(&self, locale: Locale) -> impl Future<Output = Result<TestWinnersResponse, GameError>> + Send;
+
+    /// `POST /v1/login`: a session token bound to the requested table, so
+    /// the caller's seat-scoped requests go there. The address it came
+    /// from, if known, is kept for collusion checks.
+    fn login(&self, request: LoginRequest, client: Option<IpAddr>) -> impl Future<Output = Result<SessionResponse, GameError>> + Send;
+
+    /// `POST /v1/refresh`: a new token for `session`, with a fresh expiry.
+    fn refresh(&self, session: Session) -> impl Future<Output = Result<SessionResponse, GameError>> + Send;
+
+    /// `GET /v1/me`: the caller's seat.
+    fn me(&self, session: Session) -> impl Future<Output = Result<PlayerStateResponse, GameError>> + Send;
+
+    /// `POST /v1/buyin`: adds the chips the caller buys.
+    fn buy_in(&self, session: Session, request: BuyInRequest) -> impl Future<Output = Result<PlayerStateResponse, GameError>> + Send;
+
+    /// `POST /v1/action`: the caller's action on their turn. The seat comes
+    /// back as JSON, as a retry with the same `idempotency_key` gets the
+    /// stored body of the first request.
+    fn action(
+        &self,
+        session: Session,
+        idempotency_key: Option<String>,
+        request: ActionRequest,
+    ) -> impl Future<Output = Result<serde_json::Value, GameError>> + Send;
+
+    /// `POST /v1/run_it`: how many times the caller, all in before the
+    /// river, wants the rest of the board run.
+    fn run_it(&self, session: Session, request: RunItRequest) -> impl Future<Output = Result<PlayerStateResponse, GameError>> + Send;
+
+    /// `POST /v1/straddle`: has the caller straddle the next hand when they
+    /// are under the gun in it.
+    fn straddle(&self, session: Session) -> impl Future<Output = Result<PlayerStateResponse, GameError>> + Send;
+
+    /// `POST /v1/insurance`: buys or declines the insurance offered to the
+    /// caller on an all-in.
+    fn insurance(&self, session: Session, request: InsuranceRequest) -> impl Future<Output = Result<PlayerStateResponse, GameError>> + Send;
+
+    /// `GET /v1/rabbit_hunt`: the cards that would have completed the board
+    /// of the last hand, once it has ended before the river.
+    fn rabbit_hunt(&self, session: Session) -> impl Future<Output = Result<RabbitHuntResponse, GameError>> + Send;
+
+    /// `POST /v1/show`: shows the table some of the caller's hole cards,
+    /// after they won the last hand without a showdown.
+    fn show(&self, session: Session, request: ShowRequest) -> impl Future<Output = Result<PlayerStateResponse, GameError>> + Send;
+
+    /// `GET /v1/hint`: a suggested action for the caller's own seat.
+    fn hint(&self, session: Session, query: HintQuery) -> impl Future<Output = Result<HintResponse, GameError>> + Send;
+}
+
+impl ApiService for AppState {
+    async fn deal_hole(&self) -> Result<DealHoleResponse, GameError> {
+        let players = self.tables.default_table().try_call(|controller| {
+            controller.deal_hole_cards()?;
+            Ok(controller.get_players().iter().map(PlayerHoleCards::from).collect())
+        }).await?;
+        Ok(DealHoleResponse::new(players))
+    }
+
+    async fn deal_community(&self) -> Result<CommunityResponse, GameError> {
+        let community_cards = self.tables.default_table().try_call(|controller| {
+            controller.deal_community_cards()?;
+            Ok(controller.get_community_cards().clone())
+        }).await?;
+        Ok(CommunityResponse::new(community_cards))
+    }
+
+    async fn reset(&self) -> Result<ResetResponse, GameError> {
+        self.tables.default_table().call(|controller| controller.reset_deck()).await?;
+        Ok(ResetResponse::new("Game Reset Successfully"))
+    }
+
+    async fn evaluate(&self, query: EvaluateQuery, locale: Locale) -> Result<EvaluationResponse, GameError> {
+        self.tables.default_table().call(move |controller| {
+            let board = controller.get_community_cards();
+            let players = controller.get_players()
+                .iter()
+                .map(|player| PlayerEvaluation::new(player, locale))
+                .map(|evaluation| if query.percentile { evaluation.with_percentile(board) } else { evaluation })
+                .collect();
+            EvaluationResponse::new(players, controller.get_community_cards().clone())
+        }).await
+    }
+
+    async fn test_winners(&self, locale: Locale) -> Result<TestWinnersResponse, GameError> {
+        let winner_list = self.tables.default_table().try_call(move |controller| {
+            let all_players: Vec<PlayerId> = controller.get_players()
+                .iter()
+                .map(|p| p.player_id.clone())
+                .collect();
+
+            let winners = controller
+                .get_winners(&all_players)
+                .ok_or(GameError::NoWinner)?;
+
+            winners.iter().map(|player_id| {
+                controller.find_player(player_id).map(|player| WinnerSummary::new(player, locale))
+            }).collect::<Result<_, GameError>>()
+        }).await?;
+        Ok(TestWinnersResponse::new(winner_list))
+    }
+
+    async fn login(&self, request: LoginRequest, client: Option<IpAddr>) -> Result<SessionResponse, GameError> {
+        // The player need not be seated yet: a session is what joining a table takes
+        let table = self.tables.get(&request.table_id)?;
+        self.authenticator.check_password(&request.player_id, request.password.as_deref())?;
+
+        let (token, session) = self.authenticator.issue(&request.player_id, &table.id, Role::Player);
+        if let Some(client) = client {
+            self.logins.record(&session.player_id, client);
+        }
+        info!(player_id = %session.player_id, table_id = %session.table_id, "player logged in");
+        Ok(SessionResponse::new(token, session))
+    }
+
+    async fn refresh(&self, session: Session) -> Result<SessionResponse, GameError> {
+        let (token, session) = self.authenticator.issue(&session.player_id, &session.table_id, session.role);
+        Ok(SessionResponse::new(token, session))
+    }
+
+    async fn me(&self, session: Session) -> Result<PlayerStateResponse, GameError> {
+        let table = self.tables.get(&session.table_id)?;
+        player_state(&table, session.player_id).await
+    }
+
+    async fn buy_in(&self, session: Session, request: BuyInRequest) -> Result<PlayerStateResponse, GameError> {
+        let table = self.tables.get(&session.table_id)?;
+        let player_id = session.player_id.clone();
+        table.try_call(move |controller| controller.buy_chips(&player_id, request.kind, request.amount)).await?;
+        let player = player_state(&table, session.player_id).await?;
+        info!(player_id = %player.player_id, kind = ?request.kind, stack = %player.chip_stack, "bought in");
+        Ok(player)
+    }
+
+    async fn action(&self, session: Session, idempotency_key: Option<String>, request: ActionRequest) -> Result<serde_json::Value, GameError> {
+        let reservation = match idempotency_key {
+            None => None,
+            Some(key) => match self.idempotency.claim(&session.player_id, &key, format!("{request:?}"))? {
+                Claim::Replay(outcome) => return outcome,
+                Claim::Run(reservation) => Some(reservation),
+            },
+        };
+        let table = self.tables.get(&session.table_id)?;
+        let player_id = session.player_id.clone();
+        let acted = table.call_with_events(move |controller, events| {
+            if request.state_version != events.last_seq() {
+                return Err(GameError::InvalidState(format!(
+                    "state version {} is stale, the table is at {}; fetch the state again",
+                    request.state_version,
+                    events.last_seq()
+                )));
+            }
+            controller.act(&player_id, request.action)
+        }).await;
+        let outcome = match acted.and_then(|result| result) {
+            Ok(()) => player_state(&table, session.player_id).await
+                .and_then(|player| serde_json::to_value(&player).map_err(|err| GameError::Internal(err.to_string()))),
+            Err(err) => Err(err),
+        };
+        if let Some(reservation) = reservation {
+            reservation.complete(&outcome);
+        }
+        outcome
+    }
+
+    async fn run_it(&self, session: Session, request: RunItRequest) -> Result<PlayerStateResponse, GameError> {
+        let table = self.tables.get(&session.table_id)?;
+        let player_id = session.player_id.clone();
+        table.try_call(move |controller| controller.choose_runs(&player_id, request.runs)).await?;
+        info!(player_id = %session.player_id, runs = request.runs, "runs chosen");
+        player_state(&table, session.player_id).await
+    }
+
+    async fn insurance(&self, session: Session, request: InsuranceRequest) -> Result<PlayerStateResponse, GameError> {
+        let table = self.tables.get(&session.table_id)?;
+        let player_id = session.player_id.clone();
+        table.try_call(move |controller| controller.buy_insurance(&player_id, request.buy)).await?;
+        info!(player_id = %session.player_id, buy = request.buy, "insurance decided");
+        player_state(&table, session.player_id).await
+    }
+
+    async fn rabbit_hunt(&self, session: Session) -> Result<RabbitHuntResponse, GameError> {
+        self.tables.get(&session.table_id)?.call_with_events(move |controller, events| {
+            let cards = controller.rabbit_hunt(&session.player_id)?;
+            let hand_id = hand_id(events.table_id(), controller.hand_number());
+            Ok(RabbitHuntResponse::new(hand_id, controller.get_community_cards().clone(), cards))
+        }).await?
+    }
+
+    async fn show(&self, session: Session, request: ShowRequest) -> Result<PlayerStateResponse, GameError> {
+        let table = self.tables.get(&session.table_id)?;
+        let cards = parse_cards(&request.cards)?;
+        let player_id = session.player_id.clone();
+        table.try_call(move |controller| controller.show_cards(&player_id, &cards)).await?;
+        info!(player_id = %session.player_id, cards = %request.cards, "cards shown");
+        player_state(&table, session.player_id).await
+    }
+
+    async fn straddle(&self, session: Session) -> Result<PlayerStateResponse, GameError> {
+        let table = self.tables.get(&session.table_id)?;
+        let player_id = session.player_id.clone();
+        table.try_call(move |controller| controller.request_straddle(&player_id)).await?;
+        info!(player_id = %session.player_id, "straddle requested");
+        player_state(&table, session.player_id).await
+    }
+
+    async fn hint(&self, session: Session, query: HintQuery) -> Result<HintResponse, GameError> {
+        if session.player_id != query.player_id {
+            return Err(GameError::NotAllowed("hints are only given for your own seat".to_string()));
+        }
+        let hint = self.tables.get(&session.table_id)?.try_call(move |controller| controller.hint(&query.player_id)).await?;
+        Ok(HintResponse::new(hint))
+    }
+}
+
+/// The seat of `player_id` at `table` as it stands now, with the state
+/// version to act against.
+pub(super) async fn player_state(table: &TableHandle, player_id: String) -> Result<PlayerStateResponse, GameError> {
+    table.call_with_events(move |controller, events| {
+        controller.find_player(&player_id).map(|player| PlayerStateResponse::new(player, controller, events))
+    }).await?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use crate::config::{AuthConfig, RateLimitConfig};
+    use crate::hand_history::collusion::LoginAddresses;
+    use crate::bankroll::Bankroll;
+    use crate::profiles::ProfileStore;
+    use crate::social::Social;
+    use crate::table_registry::{TableRegistry, DEFAULT_TABLE_ID};
+    use super::super::{Authenticator, IdempotencyCache, RateLimiter};
+    use poker_core::chips::Chips;
+    use poker_core::game_controller::GameController;
+    use poker_core::player::PlayerAction;
+
+    fn service() -> AppState {
+        let mut controller = GameController::new();
+        controller.initialize_players(vec![
+            ("1".to_string(), "Alice".to_string(), 0, Chips::new(1000)),
+            ("2".to_string(), "Bob".to_string(), 1, Chips::new(1000)),
+        ]);
+        AppState {
+            tables: TableRegistry::new(TableHandle::new(DEFAULT_TABLE_ID, controller)),
+            rate_limiter: RateLimiter::new(&RateLimitConfig::default()),
+            authenticator: Authenticator::new(&AuthConfig { open_login: true, ..AuthConfig::default() }),
+            idempotency: IdempotencyCache::new(),
+            profiles: ProfileStore::new(),
+            bankroll: Arc::new(Bankroll::new()),
+            social: Social::new(),
+            logins: LoginAddresses::new(),
+            solver: None,
+        }
+    }
+
+    fn session(player_id: &str) -> Session {
+        Session { player_id: player_id.to_string(), table_id: DEFAULT_TABLE_ID.to_string(), role: Role::Player, expires_at: u64::MAX }
+    }
+
+    #[tokio::test]
+    async fn test_dealing_without_http() {
+        let service = service();
+        let dealt = service.deal_hole().await.unwrap();
+        assert_eq!(dealt.players.len(), 2);
+        assert_eq!(dealt.players[0].hole_cards.len(), 2);
+        assert_eq!(service.deal_community().await.unwrap().cards.len(), 5);
+
+        let login = service.login(login_request("1", DEFAULT_TABLE_ID), Some([10, 0, 0, 1].into())).await.unwrap();
+        assert_eq!(login.player_id, "1");
+        assert_eq!(service.logins.snapshot()["1"].len(), 1);
+        assert!(matches!(service.login(login_request("1", "nowhere"), None).await, Err(GameError::TableNotFound(_))));
+    }
+
+    fn login_request(player_id: &str, table_id: &str) -> LoginRequest {
+        LoginRequest { player_id: player_id.to_string(), password: None, table_id: table_id.to_string() }
+    }
+
+    #[tokio::test]
+    async fn test_sessions_act_at_the_table_they_logged_in_at() {
+        let service = service();
+        let mut controller = GameController::new();
+        controller.initialize_players(vec![("9".to_string(), "Ivy".to_string(), 0, Chips::new(500))]);
+        service.tables.insert(TableHandle::new("side", controller)).unwrap();
+
+        let login = service.login(login_request("9", "side"), None).await.unwrap();
+        assert_eq!(login.table_id, "side");
+        let session = service.authenticator.verify(&login.token).unwrap();
+        assert_eq!(service.me(session.clone()).await.unwrap().chip_stack, Chips::new(500));
+        assert_eq!(service.refresh(session).await.unwrap().table_id, "side");
+
+        // Not seated at the default table, so a session there finds no seat
+        let main = service.authenticator.verify(&service.login(login_request("9", DEFAULT_TABLE_ID), None).await.unwrap().token).unwrap();
+        assert!(matches!(service.me(main).await, Err(GameError::PlayerNotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_actions_check_the_state_version() {
+        let service = service();
+        let me = service.me(session("1")).await.unwrap();
+        let stale = ActionRequest { action: PlayerAction::Fold, state_version: me.state_version + 1 };
+        assert!(matches!(service.action(session("1"), None, stale).await, Err(GameError::InvalidState(_))));
+        assert!(matches!(
+            service.hint(session("1"), HintQuery { player_id: "2".to_string() }).await,
+            Err(GameError::NotAllowed(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_players_all_in_choose_the_runs() {
+        let service = service();
+        let runs = |runs| RunItRequest { runs };
+        assert!(matches!(service.run_it(session("1"), runs(2)).await, Err(GameError::InvalidState(_))));
+        service.tables.default_table().try_call(|controller| {
+            controller.set_max_runs(2)?;
+            controller.start_hand(Chips::new(5), Chips::new(10))?;
+            controller.act("1", PlayerAction::Raise(Chips::new(990)))?;
+            controller.act("2", PlayerAction::Call)
+        }).await.unwrap();
+
+        assert_eq!(service.me(session("1")).await.unwrap().runs_to_choose, Some(2));
+        assert_eq!(service.run_it(session("1"), runs(2)).await.unwrap().runs_to_choose, None);
+        service.run_it(session("2"), runs(1)).await.unwrap();
+        let hand_over = service.tables.default_table().call(|controller| !controller.is_hand_in_progress() && controller.get_table().extra_boards.is_empty()).await.unwrap();
+        assert!(hand_over);
+    }
+
+    #[tokio::test]
+    async fn test_straddles_need_a_table_that_allows_them() {
+        let service = service();
+        assert!(matches!(service.straddle(session("1")).await, Err(GameError::NotAllowed(_))));
+        service.tables.default_table().call(|controller| controller.set_straddles(true)).await.unwrap();
+        assert_eq!(service.straddle(session("1")).await.unwrap().player_id, "1");
+        assert!(matches!(service.straddle(session("3")).await, Err(GameError::PlayerNotFound(_))));
+    }
+}
@@ -0,0 +1,336 @@
+use std::sync::Arc;
+
+use futures_util::{SinkExt, StreamExt};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::{debug, info, warn};
+use warp::ws::{Message, WebSocket};
+use warp::{Filter, Rejection};
+
+use poker_core::game_error::GameError;
+use crate::social::{Invitation, INVITATION_TTL};
+use super::auth::{authenticated, Session};
+use super::rejections::reject;
+use super::requests::{AcceptInvitationRequest, InviteRequest, NotificationsQuery};
+use super::responses::{ErrorResponse, FriendsResponse, InvitationResponse, InvitationsResponse, JoinTableResponse};
+use super::warp_routes::{with_state, AppState};
+use super::validation::json_body;
+
+/// API route listing the caller's friends.
+///
+/// # Endpoint
+/// `GET /v1/friends`
+///
+/// # Response
+/// - **Success**: Returns the friends, each marked `mutual` once they have added the caller back.
+/// - **Failure**: `401 Unauthorized` without a valid session.
+#[utoipa::path(
+    get,
+    path = "/v1/friends",
+    tag = "social",
+    responses(
+        (status = 200, description = "The caller's friends", body = FriendsResponse),
+        (status = 401, description = "Missing or invalid session token", body = ErrorResponse),
+    )
+)]
+fn friends_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("friends")
+        .and(warp::get())
+        .and(authenticated(state.clone()))
+        .and(with_state(state))
+        .and_then(|session: Session, state: Arc<AppState>| async move {
+            let friends = state.social.friends(&session.player_id);
+            Ok::<_, Rejection>(warp::reply::json(&FriendsResponse::new(session.player_id, friends)))
+        })
+}
+
+/// API route adding a player to the caller's friends.
+///
+/// # Endpoint
+/// `PUT /v1/friends/{friend_id}`
+///
+/// # Response
+/// - **Success**: Returns the caller's friends.
+/// - **Failure**: `400 Bad Request` for the caller's own id.
+#[utoipa::path(
+    put,
+    path = "/v1/friends/{friend_id}",
+    tag = "social",
+    params(("friend_id" = String, Path, description = "Player to add")),
+    responses(
+        (status = 200, description = "The caller's friends", body = FriendsResponse),
+        (status = 400, description = "The caller's own id", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid session token", body = ErrorResponse),
+    )
+)]
+fn add_friend_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("friends" / String)
+        .and(warp::put())
+        .and(authenticated(state.clone()))
+        .and(with_state(state))
+        .and_then(|friend_id: String, session: Session, state: Arc<AppState>| async move {
+            if state.social.add_friend(&session.player_id, &friend_id).map_err(reject)? {
+                info!(player_id = %session.player_id, %friend_id, "friend added");
+            }
+            let friends = state.social.friends(&session.player_id);
+            Ok::<_, Rejection>(warp::reply::json(&FriendsResponse::new(session.player_id, friends)))
+        })
+}
+
+/// API route removing a player from the caller's friends.
+///
+/// # Endpoint
+/// `DELETE /v1/friends/{friend_id}`
+///
+/// # Response
+/// - **Success**: Returns the caller's remaining friends.
+/// - **Failure**: `404 Not Found` if the player isn't among them.
+#[utoipa::path(
+    delete,
+    path = "/v1/friends/{friend_id}",
+    tag = "social",
+    params(("friend_id" = String, Path, description = "Player to remove")),
+    responses(
+        (status = 200, description = "The caller's friends", body = FriendsResponse),
+        (status = 401, description = "Missing or invalid session token", body = ErrorResponse),
+        (status = 404, description = "Not one of the caller's friends", body = ErrorResponse),
+    )
+)]
+fn remove_friend_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("friends" / String)
+        .and(warp::delete())
+        .and(authenticated(state.clone()))
+        .and(with_state(state))
+        .and_then(|friend_id: String, session: Session, state: Arc<AppState>| async move {
+            state.social.remove_friend(&session.player_id, &friend_id).map_err(reject)?;
+            info!(player_id = %session.player_id, %friend_id, "friend removed");
+            let friends = state.social.friends(&session.player_id);
+            Ok::<_, Rejection>(warp::reply::json(&FriendsResponse::new(session.player_id, friends)))
+        })
+}
+
+/// API route inviting a friend to a table. A seat is held for them there
+/// for a minute, or until they accept or decline.
+///
+/// The friend is notified on `GET /v1/invitations/ws`, and the table's
+/// event stream, webhooks included, reports `seat_reserved`.
+///
+/// # Endpoint
+/// `POST /v1/invite`
+///
+/// # Response
+/// - **Success**: Returns the invitation.
+/// - **Failure**: `403 Forbidden` unless the caller and the friend have added
+///   each other, or when the table has no free seat; `404 Not Found` for an
+///   unknown table; `409 Conflict` if the friend is already invited there.
+#[utoipa::path(
+    post,
+    path = "/v1/invite",
+    tag = "social",
+    request_body = InviteRequest,
+    responses(
+        (status = 200, description = "The invitation sent", body = InvitationResponse),
+        (status = 400, description = "The friend is already seated", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid session token", body = ErrorResponse),
+        (status = 403, description = "Not mutual friends, or no free seat", body = ErrorResponse),
+        (status = 404, description = "No table with this id", body = ErrorResponse),
+        (status = 409, description = "Already invited to this table", body = ErrorResponse),
+    )
+)]
+fn invite_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("invite")
+        .and(warp::post())
+        .and(authenticated(state.clone()))
+        .and(json_body())
+        .and(with_state(state))
+        .and_then(|session: Session, request: InviteRequest, state: Arc<AppState>| async move {
+            let table = state.tables.get(&request.table_id).map_err(reject)?;
+            state.social.check_invite(&session.player_id, &request.friend_id, &table.id).map_err(reject)?;
+            let (friend_id, inviter) = (request.friend_id.clone(), session.player_id.clone());
+            table.try_call(move |controller| controller.reserve_seat(&friend_id, &inviter)).await.map_err(reject)?;
+            let invitation = state.social.invite(&session.player_id, &request.friend_id, &table.id);
+            info!(id = invitation.id, from = %invitation.from, to = %invitation.to, table_id = %table.id, "invitation sent");
+
+            // Give the seat up if the invitation goes unanswered
+            let (id, expiring) = (invitation.id, state.clone());
+            tokio::spawn(async move {
+                tokio::time::sleep(INVITATION_TTL).await;
+                if let Some(invitation) = expiring.social.close(id) {
+                    debug!(id, "invitation lapsed");
+                    let _ = table.try_call(move |controller| controller.release_seat(&invitation.to)).await;
+                }
+            });
+            Ok::<_, Rejection>(warp::reply::json(&InvitationResponse::new(invitation)))
+        })
+}
+
+/// API route listing the invitations waiting for the caller's answer.
+///
+/// # Endpoint
+/// `GET /v1/invitations`
+///
+/// # Response
+/// - **Success**: Returns the open invitations, oldest first.
+/// - **Failure**: `401 Unauthorized` without a valid session.
+#[utoipa::path(
+    get,
+    path = "/v1/invitations",
+    tag = "social",
+    responses(
+        (status = 200, description = "The caller's open invitations", body = InvitationsResponse),
+        (status = 401, description = "Missing or invalid session token", body = ErrorResponse),
+    )
+)]
+fn invitations_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("invitations")
+        .and(warp::get())
+        .and(authenticated(state.clone()))
+        .and(with_state(state))
+        .and_then(|session: Session, state: Arc<AppState>| async move {
+            let invitations = state.social.invitations(&session.player_id);
+            Ok::<_, Rejection>(warp::reply::json(&InvitationsResponse::new(invitations)))
+        })
+}
+
+/// API route accepting an invitation: the caller takes the seat held for
+/// them, buying in like anyone joining the table.
+///
+/// # Endpoint
+/// `POST /v1/invitations/{id}/accept`
+///
+/// # Response
+/// - **Success**: Returns the seat taken.
+/// - **Failure**: `400 Bad Request` for an invitation no longer open or a
+///   stack outside the buy-in limits, `403 Forbidden` for another player's
+///   invitation.
+#[utoipa::path(
+    post,
+    path = "/v1/invitations/{id}/accept",
+    tag = "social",
+    params(("id" = u64, Path, description = "Invitation to accept")),
+    request_body = AcceptInvitationRequest,
+    responses(
+        (status = 200, description = "Seated at the table", body = JoinTableResponse),
+        (status = 400, description = "No such open invitation, or a stack outside the buy-in limits", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid session token", body = ErrorResponse),
+        (status = 403, description = "Another player's invitation", body = ErrorResponse),
+    )
+)]
+fn accept_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("invitations" / u64 / "accept")
+        .and(warp::post())
+        .and(authenticated(state.clone()))
+        .and(json_body())
+        .and(with_state(state))
+        .and_then(|id: u64, session: Session, request: AcceptInvitationRequest, state: Arc<AppState>| async move {
+            let invitation = state.social.invitation(id, &session.player_id).map_err(reject)?;
+            let table = state.tables.get(&invitation.table_id).map_err(reject)?;
+            // Left open until the join succeeds, so a rejected buy-in can be retried
+            let player_id = session.player_id.clone();
+            let seating = table
+                .try_call(move |controller| controller.join_table(&player_id, &request.display_name, request.chip_stack))
+                .await
+                .map_err(reject)?;
+            state.social.close(id);
+            info!(id, player_id = %session.player_id, table_id = %table.id, ?seating, "invitation accepted");
+            Ok::<_, Rejection>(warp::reply::json(&JoinTableResponse::new(invitation.table_id, session.player_id, seating)))
+        })
+}
+
+/// API route declining an invitation, giving up the seat held for the caller.
+///
+/// # Endpoint
+/// `POST /v1/invitations/{id}/decline`
+///
+/// # Response
+/// - **Success**: Returns the declined invitation.
+/// - **Failure**: `400 Bad Request` for an invitation no longer open,
+///   `403 Forbidden` for another player's invitation.
+#[utoipa::path(
+    post,
+    path = "/v1/invitations/{id}/decline",
+    tag = "social",
+    params(("id" = u64, Path, description = "Invitation to decline")),
+    responses(
+        (status = 200, description = "The declined invitation", body = InvitationResponse),
+        (status = 400, description = "No such open invitation", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid session token", body = ErrorResponse),
+        (status = 403, description = "Another player's invitation", body = ErrorResponse),
+    )
+)]
+fn decline_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("invitations" / u64 / "decline")
+        .and(warp::post())
+        .and(authenticated(state.clone()))
+        .and(with_state(state))
+        .and_then(|id: u64, session: Session, state: Arc<AppState>| async move {
+            state.social.invitation(id, &session.player_id).map_err(reject)?;
+            let Some(invitation) = state.social.close(id) else {
+                return Err(reject(GameError::InvalidRequest(format!("no open invitation {id}"))));
+            };
+            let table = state.tables.get(&invitation.table_id).map_err(reject)?;
+            let player_id = session.player_id.clone();
+            // The seat may already be gone, e.g. with the table's seats cut since
+            let _ = table.try_call(move |controller| controller.release_seat(&player_id)).await;
+            info!(id, player_id = %session.player_id, "invitation declined");
+            Ok::<_, Rejection>(warp::reply::json(&InvitationResponse::new(invitation)))
+        })
+}
+
+/// API route upgrading to a WebSocket that sends the caller each
+/// invitation as it arrives, as an `InvitationResponse` frame. Browsers
+/// can't set headers on WebSockets, so the session token is passed as `token`.
+///
+/// # Endpoint
+/// `GET /v1/invitations/ws?token=...`
+///
+/// # Response
+/// - **Success**: Switches protocols.
+/// - **Failure**: `401 Unauthorized` for an invalid token.
+fn notifications_ws_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("invitations" / "ws")
+        .and(warp::ws())
+        .and(warp::query::<NotificationsQuery>())
+        .and(with_state(state))
+        .and_then(|ws: warp::ws::Ws, query: NotificationsQuery, state: Arc<AppState>| async move {
+            let session = state.authenticator.verify(&query.token).map_err(reject)?;
+            let invitations = state.social.subscribe();
+            Ok::<_, Rejection>(ws.on_upgrade(move |socket| stream_invitations(socket, session.player_id, invitations)))
+        })
+}
+
+/// Forwards the invitations sent to `player_id` until either side goes away.
+async fn stream_invitations(socket: WebSocket, player_id: String, mut invitations: broadcast::Receiver<Arc<Invitation>>) {
+    let (mut outgoing, mut incoming) = socket.split();
+    loop {
+        tokio::select! {
+            invitation = invitations.recv() => match invitation {
+                Ok(invitation) if *invitation.to == *player_id => {
+                    let Ok(text) = serde_json::to_string(&InvitationResponse::new(Invitation::clone(&invitation))) else { continue };
+                    if outgoing.send(Message::text(text)).await.is_err() {
+                        break;
+                    }
+                }
+                Ok(_) => {}
+                Err(RecvError::Lagged(missed)) => warn!(missed, "invitation listener fell behind, invitations skipped"),
+                Err(RecvError::Closed) => break,
+            },
+            message = incoming.next() => match message {
+                Some(Ok(message)) if !message.is_close() => {}
+                _ => break,
+            },
+        }
+    }
+    debug!(%player_id, "invitation listener disconnected");
+}
+
+/// All friend and invitation routes.
+pub fn social_routes(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    friends_route(state.clone())
+        .or(add_friend_route(state.clone()))
+        .or(remove_friend_route(state.clone()))
+        .or(invite_route(state.clone()))
+        .or(invitations_route(state.clone()))
+        .or(accept_route(state.clone()))
+        .or(decline_route(state.clone()))
+        .or(notifications_ws_route(state))
+}
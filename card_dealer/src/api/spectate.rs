@@ -0,0 +1,362 @@
+use std::convert::Infallible;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures_util::{stream, SinkExt, Stream, StreamExt};
+use serde::Serialize;
+use tokio::sync::broadcast::{self, error::{RecvError, TryRecvError}};
+use tracing::{debug, warn};
+use warp::ws::{Message, WebSocket};
+use warp::{Filter, Rejection};
+
+use crate::events::{hand_id, SequencedEvent};
+use poker_core::game_error::GameError;
+#[cfg(feature = "metrics")]
+use poker_core::metrics::METRICS;
+use crate::blind_clock::BlindClock;
+use crate::presence::{HEARTBEAT_INTERVAL, HEARTBEAT_TIMEOUT};
+use crate::table_registry::TableHandle;
+use super::auth::Role;
+use super::rejections::reject;
+use super::requests::{PollQuery, SpectateQuery};
+use super::responses::{BlindClockView, ErrorResponse, PollResponse, SpectatorSeat, SpectatorViewResponse, StreetPot};
+use super::warp_routes::{with_state, AppState};
+
+/// How long a poll waits for an event when the client doesn't say.
+const DEFAULT_POLL_TIMEOUT: Duration = Duration::from_secs(25);
+
+/// Longest a poll may wait, kept under common proxy idle timeouts.
+const MAX_POLL_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// First frame to a client resuming from a sequence number the server no
+/// longer has the events after; it should fetch the table state again.
+#[derive(Debug, Serialize)]
+struct ResyncNotice {
+    #[serde(rename = "type")]
+    response_type: &'static str,
+    last_seq: u64, // The live events that follow start after this one
+}
+
+/// Counts a spectator connection, and the player's presence when a player
+/// opened it, for as long as it is alive.
+struct SpectatorGuard {
+    table: Arc<TableHandle>,
+    player_id: Option<String>,
+}
+
+impl SpectatorGuard {
+    fn new(table: Arc<TableHandle>, player_id: Option<String>) -> Self {
+        table.spectators.fetch_add(1, Ordering::Relaxed);
+        if let Some(player_id) = &player_id {
+            table.presence.connect(player_id);
+        }
+        #[cfg(feature = "metrics")]
+        METRICS.websocket_connections.inc();
+        Self { table, player_id }
+    }
+
+    /// Notes that the client answered.
+    fn seen(&self) {
+        if let Some(player_id) = &self.player_id {
+            self.table.presence.touch(player_id);
+        }
+    }
+}
+
+impl Drop for SpectatorGuard {
+    fn drop(&mut self) {
+        self.table.spectators.fetch_sub(1, Ordering::Relaxed);
+        if let Some(player_id) = &self.player_id {
+            self.table.presence.disconnect(player_id);
+        }
+        #[cfg(feature = "metrics")]
+        METRICS.websocket_connections.dec();
+    }
+}
+
+/// API route returning the public view of a table.
+///
+/// # Endpoint
+/// `GET /v1/tables/{table_id}/spectate`
+///
+/// # Response
+/// - **Success**: Returns seats, stacks, board and pots, whether each
+///   player's client is connected, and the blind clock on tables that run
+///   one; hole cards are never included.
+/// - **Failure**: `404 Not Found` for an unknown table.
+#[utoipa::path(
+    get,
+    path = "/v1/tables/{table_id}/spectate",
+    tag = "spectate",
+    params(("table_id" = String, Path, description = "Table to watch")),
+    responses(
+        (status = 200, description = "The public table state", body = SpectatorViewResponse),
+        (status = 404, description = "No table with this id", body = ErrorResponse),
+    )
+)]
+fn spectate_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("tables" / String / "spectate")
+        .and(warp::get())
+        .and(with_state(state))
+        .and_then(|table_id: String, state: Arc<AppState>| async move {
+            let table = state.tables.get(&table_id).map_err(reject)?;
+            let spectators = table.spectators.load(Ordering::Relaxed);
+            let clock = table.clock.get().and_then(BlindClock::state).map(BlindClockView::from);
+            let mut view = table.call_with_events(move |controller, events| SpectatorViewResponse {
+                response_type: "spectate",
+                table_id,
+                hand_number: controller.hand_number(),
+                hand_id: (controller.hand_number() > 0).then(|| hand_id(events.table_id(), controller.hand_number())),
+                hand_in_progress: controller.is_hand_in_progress(),
+                paused: controller.is_paused(),
+                hand_for_hand: controller.is_hand_for_hand(),
+                community_cards: controller.get_community_cards().clone(),
+                extra_boards: controller.get_table().extra_boards.clone(),
+                pots: controller.get_table().pots.iter().map(|pot| pot.total).collect(),
+                streets: controller.street_bets().iter().map(StreetPot::from).collect(),
+                jackpot: controller.jackpot().map(|jackpot| jackpot.pool()),
+                players: controller.get_players().iter().map(SpectatorSeat::from).collect(),
+                spectators,
+                last_event_seq: events.last_seq(),
+                clock,
+            }).await.map_err(reject)?;
+            for seat in &mut view.players {
+                seat.presence = table.presence.status(&seat.player_id);
+            }
+            Ok::<_, Rejection>(warp::reply::json(&view))
+        })
+}
+
+/// API route upgrading to a WebSocket that streams the table's public events.
+///
+/// A client reconnecting with `last_seq`, the `seq` of the last event it
+/// received, first gets exactly the events it missed. If the server no
+/// longer has them all, it gets a `resync` frame instead and should fetch
+/// the table state again.
+///
+/// A player passing their session token as `token` is shown as connected
+/// while the socket is open. The server pings every client every 15 seconds
+/// and closes connections that stay silent for a minute.
+///
+/// # Endpoint
+/// `GET /v1/tables/{table_id}/spectate/ws?last_seq=41`
+///
+/// # Response
+/// - **Success**: Switches protocols; each text frame is one `SequencedEvent`
+///   as JSON, possibly preceded by `{"type": "resync", "last_seq": ...}`.
+/// - **Failure**: `404 Not Found` for an unknown table, `401 Unauthorized`
+///   for an invalid token.
+fn spectate_ws_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("tables" / String / "spectate" / "ws")
+        .and(warp::ws())
+        .and(warp::query::<SpectateQuery>())
+        .and(with_state(state))
+        .and_then(|table_id: String, ws: warp::ws::Ws, query: SpectateQuery, state: Arc<AppState>| async move {
+            let table = state.tables.get(&table_id).map_err(reject)?;
+            let session = query.token.as_deref().map(|token| state.authenticator.verify(token)).transpose().map_err(reject)?;
+            let player_id = session
+                .filter(|session| session.role == Role::Player && session.table_id == table_id)
+                .map(|session| session.player_id);
+            // Subscribe before upgrading so nothing published in between is lost
+            let (backlog, events) = match query.last_seq {
+                None => (Vec::new(), table.subscribe()),
+                Some(seq) => table.call_with_events(move |_, events| {
+                    let (missed, receiver) = events.subscribe_after(seq);
+                    let backlog = if events.can_resume_from(seq) {
+                        missed.iter().filter_map(|event| serde_json::to_string(&**event).ok()).collect()
+                    } else {
+                        let notice = ResyncNotice { response_type: "resync", last_seq: events.last_seq() };
+                        serde_json::to_string(&notice).into_iter().collect()
+                    };
+                    (backlog, receiver)
+                }).await.map_err(reject)?,
+            };
+            let guard = SpectatorGuard::new(table, player_id);
+            Ok::<_, Rejection>(ws.on_upgrade(move |socket| stream_events(socket, backlog, events, guard)))
+        })
+}
+
+/// Sends `backlog`, then forwards events to a spectator until either side
+/// goes away or the client stops answering pings.
+async fn stream_events(socket: WebSocket, backlog: Vec<String>, mut events: broadcast::Receiver<Arc<SequencedEvent>>, guard: SpectatorGuard) {
+    let (mut outgoing, mut incoming) = socket.split();
+    for text in backlog {
+        if outgoing.send(Message::text(text)).await.is_err() {
+            return;
+        }
+    }
+    let mut heartbeat = tokio::time::interval_at(tokio::time::Instant::now() + HEARTBEAT_INTERVAL, HEARTBEAT_INTERVAL);
+    let mut last_seen = tokio::time::Instant::now();
+    loop {
+        tokio::select! {
+            event = events.recv() => match event {
+                Ok(event) => {
+                    let Ok(text) = serde_json::to_string(&*event) else { continue };
+                    if outgoing.send(Message::text(text)).await.is_err() {
+                        break;
+                    }
+                }
+                Err(RecvError::Lagged(missed)) => warn!(missed, "spectator fell behind, events skipped"),
+                Err(RecvError::Closed) => break,
+            },
+            message = incoming.next() => match message {
+                // Spectators are read-only, but anything they send, pongs included, shows they're there
+                Some(Ok(message)) if !message.is_close() => {
+                    last_seen = tokio::time::Instant::now();
+                    guard.seen();
+                }
+                _ => break,
+            },
+            _ = heartbeat.tick() => {
+                if last_seen.elapsed() > HEARTBEAT_TIMEOUT {
+                    debug!("spectator stopped answering pings");
+                    break;
+                }
+                if outgoing.send(Message::ping(Vec::new())).await.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+    debug!("spectator disconnected");
+}
+
+/// API route streaming the table's public events as Server-Sent Events.
+///
+/// An alternative to the spectator WebSocket for clients behind proxies that
+/// handle WebSockets badly. Each event's SSE `id` is its sequence number, so
+/// a reconnecting client sending `Last-Event-ID` first receives the events it
+/// missed (as far back as the server's history reaches).
+///
+/// # Endpoint
+/// `GET /v1/events`
+///
+/// # Response
+/// - **Success**: A `text/event-stream` where each message's `event` is the
+///   event type and `data` the `SequencedEvent` as JSON.
+#[utoipa::path(
+    get,
+    path = "/v1/events",
+    tag = "spectate",
+    params(("Last-Event-ID" = Option<u64>, Header, description = "Resume after this sequence number")),
+    responses(
+        (status = 200, description = "Stream of game events", content_type = "text/event-stream", body = SequencedEvent),
+    )
+)]
+fn events_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path("events")
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(warp::header::optional::<u64>("last-event-id"))
+        .and(with_state(state))
+        .and_then(|last_event_id: Option<u64>, state: Arc<AppState>| async move {
+            let table = state.tables.default_table();
+            let (missed, receiver) = match last_event_id {
+                Some(seq) => table.subscribe_after(seq).await.map_err(reject)?,
+                None => (Vec::new(), table.subscribe()),
+            };
+
+            let events = stream::iter(missed).chain(live_events(receiver)).map(|event| {
+                let sse = warp::sse::Event::default()
+                    .id(event.seq.to_string())
+                    .event(event.event.kind())
+                    .json_data(&*event);
+                // Serializing plain data can't fail; send an empty message rather than end the stream
+                Ok::<_, Infallible>(sse.unwrap_or_default())
+            });
+            Ok::<_, Rejection>(warp::sse::reply(warp::sse::keep_alive().stream(events)))
+        })
+}
+
+/// API route waiting for the table's next events, for clients that can use
+/// neither WebSockets nor Server-Sent Events.
+///
+/// Answers at once with the events after `since_seq` the server still has,
+/// or else waits until the next ones are published or the timeout passes.
+/// Each answer's `last_seq` is the `since_seq` of the next poll. When
+/// events after `since_seq` were lost, `resync` is set and the client should
+/// fetch the table state again.
+///
+/// # Endpoint
+/// `GET /v1/poll?since_seq=41&timeout_secs=25`
+///
+/// # Response
+/// - **Success**: Returns the new events, oldest first, possibly none.
+/// - **Failure**: `400 Bad Request` for a timeout over 60 seconds.
+#[utoipa::path(
+    get,
+    path = "/v1/poll",
+    tag = "spectate",
+    params(PollQuery),
+    responses(
+        (status = 200, description = "Events after `since_seq`", body = PollResponse),
+        (status = 400, description = "Timeout too long", body = ErrorResponse),
+    )
+)]
+fn poll_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path("poll")
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(warp::query::<PollQuery>())
+        .and(with_state(state))
+        .and_then(|query: PollQuery, state: Arc<AppState>| async move {
+            let timeout = query.timeout_secs.map_or(DEFAULT_POLL_TIMEOUT, Duration::from_secs);
+            if timeout > MAX_POLL_TIMEOUT {
+                return Err(reject(GameError::InvalidRequest(format!("timeout_secs may be at most {}", MAX_POLL_TIMEOUT.as_secs()))));
+            }
+            let since = query.since_seq;
+            let (missed, mut receiver, resumable, last_seq) = state.tables.default_table()
+                .call_with_events(move |_, events| {
+                    let (missed, receiver) = events.subscribe_after(since);
+                    (missed, receiver, events.can_resume_from(since), events.last_seq())
+                })
+                .await
+                .map_err(reject)?;
+            if !resumable {
+                return Ok(warp::reply::json(&PollResponse::new(Vec::new(), last_seq, true)));
+            }
+            if !missed.is_empty() {
+                let events = missed.iter().map(|event| SequencedEvent::clone(event)).collect();
+                return Ok(warp::reply::json(&PollResponse::new(events, since, false)));
+            }
+
+            // Wait for the first event, then take whatever else has already arrived with it
+            let mut events = Vec::new();
+            let mut resync = false;
+            match tokio::time::timeout(timeout, receiver.recv()).await {
+                Ok(Ok(event)) => events.push(SequencedEvent::clone(&event)),
+                Ok(Err(RecvError::Lagged(_))) => resync = true,
+                Ok(Err(RecvError::Closed)) | Err(_) => {}
+            }
+            loop {
+                match receiver.try_recv() {
+                    Ok(event) => events.push(SequencedEvent::clone(&event)),
+                    Err(TryRecvError::Lagged(_)) => resync = true,
+                    Err(TryRecvError::Empty | TryRecvError::Closed) => break,
+                }
+            }
+            Ok::<_, Rejection>(warp::reply::json(&PollResponse::new(events, since, resync)))
+        })
+}
+
+/// Turns a broadcast receiver into a stream, skipping over lag.
+fn live_events(receiver: broadcast::Receiver<Arc<SequencedEvent>>) -> impl Stream<Item = Arc<SequencedEvent>> {
+    stream::unfold(receiver, |mut receiver| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(event) => return Some((event, receiver)),
+                Err(RecvError::Lagged(missed)) => warn!(missed, "event stream fell behind, events skipped"),
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    })
+}
+
+/// All spectator routes.
+pub fn spectate_routes(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    spectate_ws_route(state.clone())
+        .or(spectate_route(state.clone()))
+        .or(events_route(state.clone()))
+        .or(poll_route(state))
+}
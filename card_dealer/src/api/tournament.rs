@@ -0,0 +1,167 @@
+use std::sync::Arc;
+
+use poker_core::game_controller::GameController;
+use poker_core::game_error::GameError;
+use poker_core::waitlist::Seating;
+use tracing::info;
+use warp::{Filter, Rejection};
+
+use super::auth::{authenticated, Session};
+use super::rate_limit::rate_limited;
+use super::rejections::reject;
+use super::requests::RegisterRequest;
+use super::responses::{ErrorResponse, TournamentEntryResponse, TournamentResponse};
+use super::warp_routes::{with_state, AppState};
+use super::validation::json_body;
+
+/// The entry just made, as returned to the player who made it.
+fn entry_response(controller: &GameController, table_id: String, player_id: String, seating: Seating) -> Result<TournamentEntryResponse, GameError> {
+    let tournament = controller.tournament().ok_or_else(|| GameError::NotAllowed("this table is not a tournament".to_string()))?;
+    Ok(TournamentEntryResponse {
+        response_type: "entry",
+        entries: tournament.entries(&player_id),
+        prize_pool: tournament.prize_pool(),
+        table_id,
+        player_id,
+        seating,
+    })
+}
+
+/// API route showing a tournament table's entry rules and prize pool.
+///
+/// # Endpoint
+/// `GET /v1/tables/{table_id}/tournament`
+///
+/// # Response
+/// - **Success**: Returns the rules, whether registration is open, and the prize pool so far.
+/// - **Failure**: `403 Forbidden` for a cash table, `404 Not Found` for an unknown table.
+#[utoipa::path(
+    get,
+    path = "/v1/tables/{table_id}/tournament",
+    tag = "tournament",
+    params(("table_id" = String, Path, description = "Tournament table")),
+    responses(
+        (status = 200, description = "The tournament's entries and prize pool", body = TournamentResponse),
+        (status = 403, description = "The table is not a tournament", body = ErrorResponse),
+        (status = 404, description = "No table with this id", body = ErrorResponse),
+    )
+)]
+fn tournament_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("tables" / String / "tournament")
+        .and(warp::get())
+        .and(with_state(state))
+        .and_then(|table_id: String, state: Arc<AppState>| async move {
+            let table = state.tables.get(&table_id).map_err(reject)?;
+            let tournament = table
+                .try_call(move |controller| {
+                    let tournament = controller.tournament().ok_or_else(|| GameError::NotAllowed("this table is not a tournament".to_string()))?;
+                    Ok(TournamentResponse::new(table_id, tournament, controller.registration_open()))
+                })
+                .await
+                .map_err(reject)?;
+            Ok::<_, Rejection>(warp::reply::json(&tournament))
+        })
+}
+
+/// API route entering a player in a tournament while registration is open.
+///
+/// The player sits down with the tournament's starting stack, or waits for a
+/// seat when the table is full; the entry fee goes into the prize pool.
+///
+/// # Endpoint
+/// `POST /v1/tables/{table_id}/register`
+///
+/// # Response
+/// - **Success**: Returns the entry, the new prize pool and the seat or place in line.
+/// - **Failure**: `400 Bad Request` for a player already at the table, `401
+///   Unauthorized` for a wrong password, `403 Forbidden` once registration
+///   has closed or for a cash table, `404 Not Found` for an unknown table.
+#[utoipa::path(
+    post,
+    path = "/v1/tables/{table_id}/register",
+    tag = "tournament",
+    params(("table_id" = String, Path, description = "Tournament table")),
+    request_body = RegisterRequest,
+    responses(
+        (status = 200, description = "The player is entered", body = TournamentEntryResponse),
+        (status = 400, description = "Already at the table", body = ErrorResponse),
+        (status = 401, description = "Wrong password", body = ErrorResponse),
+        (status = 403, description = "Registration is closed, or the table is not a tournament", body = ErrorResponse),
+        (status = 404, description = "No table with this id", body = ErrorResponse),
+    )
+)]
+fn register_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("tables" / String / "register")
+        .and(warp::post())
+        .and(rate_limited(state.clone()))
+        .and(json_body())
+        .and(with_state(state))
+        .and_then(|table_id: String, request: RegisterRequest, state: Arc<AppState>| async move {
+            let table = state.tables.get(&table_id).map_err(reject)?;
+            state
+                .authenticator
+                .check_password(&request.player_id, request.password.as_deref())
+                .map_err(reject)?;
+
+            let entry = table
+                .try_call(move |controller| {
+                    let seating = controller.register(&request.player_id, &request.display_name)?;
+                    entry_response(controller, table_id, request.player_id, seating)
+                })
+                .await
+                .map_err(reject)?;
+            info!(player_id = %entry.player_id, prize_pool = %entry.prize_pool, "registered for the tournament");
+            Ok::<_, Rejection>(warp::reply::json(&entry))
+        })
+}
+
+/// API route entering the authenticated player again after they busted.
+///
+/// Their old seat is given up; they take the next free one, or a place on
+/// the waiting list, with a fresh starting stack.
+///
+/// # Endpoint
+/// `POST /v1/tables/{table_id}/reenter`
+///
+/// # Response
+/// - **Success**: Returns the entry, the new prize pool and the seat or place in line.
+/// - **Failure**: `401 Unauthorized` without a valid session token, `403
+///   Forbidden` for a player with chips left, out of entries or after
+///   registration has closed, `409 Conflict` while they are in a hand.
+#[utoipa::path(
+    post,
+    path = "/v1/tables/{table_id}/reenter",
+    tag = "tournament",
+    params(("table_id" = String, Path, description = "Tournament table")),
+    responses(
+        (status = 200, description = "The player is entered again", body = TournamentEntryResponse),
+        (status = 401, description = "Missing, invalid or expired token", body = ErrorResponse),
+        (status = 403, description = "Re-entry not allowed", body = ErrorResponse),
+        (status = 409, description = "The caller is playing a hand", body = ErrorResponse),
+    )
+)]
+fn re_enter_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("tables" / String / "reenter")
+        .and(warp::post())
+        .and(authenticated(state.clone()))
+        .and(with_state(state))
+        .and_then(|table_id: String, session: Session, state: Arc<AppState>| async move {
+            let table = state.tables.get(&table_id).map_err(reject)?;
+            let entry = table
+                .try_call(move |controller| {
+                    let seating = controller.re_enter(&session.player_id)?;
+                    entry_response(controller, table_id, session.player_id, seating)
+                })
+                .await
+                .map_err(reject)?;
+            info!(player_id = %entry.player_id, entries = entry.entries, "re-entered the tournament");
+            Ok::<_, Rejection>(warp::reply::json(&entry))
+        })
+}
+
+/// All tournament routes.
+pub fn tournament_routes(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    tournament_route(state.clone())
+        .or(register_route(state.clone()))
+        .or(re_enter_route(state))
+}
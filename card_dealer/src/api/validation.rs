@@ -0,0 +1,369 @@
+//! Checks on JSON request bodies before any handler sees them. Every
+//! problem is reported at once, by field, in the `errors` of a `400 Bad
+//! Request`, rather than the handler failing on the first one or acting on
+//! a nonsensical value.
+
+use std::collections::HashSet;
+use std::ops::RangeInclusive;
+
+use poker_core::card_dealer::{parse_cards, Card};
+use poker_core::chips::Chips;
+use poker_core::player::PlayerAction;
+use poker_core::range::Range;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use utoipa::ToSchema;
+use warp::{Filter, Rejection};
+
+use crate::profiles::PlayerProfile;
+
+use super::requests::{
+    AcceptInvitationRequest, ActionRequest, AddBotRequest, AdminLoginRequest, BuyInRequest, ChipAdjustmentRequest, CreateTableRequest,
+    EquityVsRangeRequest, InsuranceRequest, InviteRequest, JoinTableRequest, LoginRequest, RegisterRequest, RunItRequest, ShowRequest, SolveRequest,
+};
+#[cfg(feature = "debug-routes")]
+use super::requests::StackDeckRequest;
+
+/// Longest player id accepted.
+const MAX_PLAYER_ID_LEN: usize = 64;
+
+/// Longest display name accepted, in characters.
+const MAX_DISPLAY_NAME_LEN: usize = 32;
+
+/// Longest avatar URL accepted.
+const MAX_AVATAR_URL_LEN: usize = 512;
+
+/// Most a profile's preferences may take up, as JSON.
+const MAX_PREFERENCES_LEN: usize = 4096;
+
+/// Longest table id accepted.
+const MAX_TABLE_ID_LEN: usize = 32;
+
+/// One problem with one field of a request body.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct FieldError {
+    #[schema(example = "amount")]
+    pub field: String,      // Path to the field, e.g. `ranges[1]`
+    #[schema(example = "must be more than zero")]
+    pub message: String,
+}
+
+/// Rejection raised when a request body fails validation.
+#[derive(Debug)]
+pub struct ValidationFailed(pub Vec<FieldError>);
+
+impl warp::reject::Reject for ValidationFailed {}
+
+/// The problems found in a request body so far.
+#[derive(Debug, Default)]
+pub struct FieldErrors(Vec<FieldError>);
+
+impl FieldErrors {
+    pub fn add(&mut self, field: impl Into<String>, message: impl Into<String>) {
+        self.0.push(FieldError { field: field.into(), message: message.into() });
+    }
+
+    /// A player id: 1 to 64 characters, none of them whitespace or control
+    /// characters.
+    pub fn player_id(&mut self, field: &str, id: &str) {
+        if id.is_empty() || id.len() > MAX_PLAYER_ID_LEN {
+            self.add(field, format!("must be 1 to {MAX_PLAYER_ID_LEN} bytes long"));
+        } else if id.chars().any(|c| c.is_whitespace() || c.is_control()) {
+            self.add(field, "may not contain whitespace or control characters");
+        }
+    }
+
+    /// A name shown to other players: not blank, at most 32 characters, no
+    /// control characters.
+    pub fn display_name(&mut self, field: &str, name: &str) {
+        if name.trim().is_empty() || name.chars().count() > MAX_DISPLAY_NAME_LEN {
+            self.add(field, format!("must be 1 to {MAX_DISPLAY_NAME_LEN} characters long, not counting surrounding spaces"));
+        } else if name.chars().any(char::is_control) {
+            self.add(field, "may not contain control characters");
+        }
+    }
+
+    /// A table id: 1 to 32 ASCII letters, digits, `-` or `_`, as it appears
+    /// in URLs and in the ids of the table's hands.
+    pub fn table_id(&mut self, field: &str, id: &str) {
+        if id.is_empty() || id.len() > MAX_TABLE_ID_LEN {
+            self.add(field, format!("must be 1 to {MAX_TABLE_ID_LEN} characters long"));
+        } else if !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+            self.add(field, "may only contain ASCII letters, digits, '-' and '_'");
+        }
+    }
+
+    pub fn positive(&mut self, field: &str, amount: Chips) {
+        if amount.is_zero() {
+            self.add(field, "must be more than zero");
+        }
+    }
+
+    /// Cards such as `"Ah Kd"`, `count` of them, none repeated. Returns them
+    /// when they parse, for checks across fields.
+    pub fn cards(&mut self, field: &str, text: &str, count: RangeInclusive<usize>) -> Option<Vec<Card>> {
+        let cards = match parse_cards(text) {
+            Ok(cards) => cards,
+            Err(err) => {
+                self.add(field, err.to_string());
+                return None;
+            }
+        };
+        if !count.contains(&cards.len()) {
+            let expected = if count.start() == count.end() { count.start().to_string() } else { format!("{} to {}", count.start(), count.end()) };
+            self.add(field, format!("needs {expected} cards, not {}", cards.len()));
+        } else if cards.iter().collect::<HashSet<_>>().len() < cards.len() {
+            self.add(field, "repeats a card");
+        } else {
+            return Some(cards);
+        }
+        None
+    }
+
+    /// A range of starting hands such as `"QQ+, AKs"`, holding at least one hand.
+    pub fn range(&mut self, field: &str, text: &str) {
+        match text.parse::<Range>() {
+            Ok(range) if range.is_empty() => self.add(field, "holds no hands"),
+            Ok(_) => {}
+            Err(err) => self.add(field, err.to_string()),
+        }
+    }
+}
+
+/// A request body that can check its own fields.
+pub trait Validate {
+    /// Notes every problem with the body in `errors`.
+    fn validate(&self, errors: &mut FieldErrors);
+}
+
+/// Filter reading a JSON body and rejecting it with `ValidationFailed`
+/// unless it passes `Validate`.
+///
+/// Use it in place of `warp::body::json()` on every route taking a body.
+pub fn json_body<T>() -> impl Filter<Extract = (T,), Error = Rejection> + Clone
+where
+    T: DeserializeOwned + Validate + Send,
+{
+    warp::body::json().and_then(|body: T| async move {
+        let mut errors = FieldErrors::default();
+        body.validate(&mut errors);
+        if errors.0.is_empty() {
+            Ok(body)
+        } else {
+            Err(warp::reject::custom(ValidationFailed(errors.0)))
+        }
+    })
+}
+
+impl Validate for LoginRequest {
+    fn validate(&self, errors: &mut FieldErrors) {
+        errors.player_id("player_id", &self.player_id);
+        errors.table_id("table_id", &self.table_id);
+    }
+}
+
+impl Validate for AdminLoginRequest {
+    fn validate(&self, errors: &mut FieldErrors) {
+        if self.admin_token.is_empty() {
+            errors.add("admin_token", "may not be empty");
+        }
+    }
+}
+
+impl Validate for ChipAdjustmentRequest {
+    fn validate(&self, errors: &mut FieldErrors) {
+        if self.delta == 0 {
+            errors.add("delta", "may not be zero");
+        }
+    }
+}
+
+impl Validate for ActionRequest {
+    fn validate(&self, errors: &mut FieldErrors) {
+        match self.action {
+            PlayerAction::Bet(amount) => errors.positive("action.bet", amount),
+            PlayerAction::Raise(amount) => errors.positive("action.raise", amount),
+            PlayerAction::Fold | PlayerAction::Check | PlayerAction::Call | PlayerAction::SitOut => {}
+        }
+    }
+}
+
+impl Validate for RunItRequest {
+    fn validate(&self, errors: &mut FieldErrors) {
+        if self.runs == 0 {
+            errors.add("runs", "must be at least 1");
+        }
+    }
+}
+
+impl Validate for InsuranceRequest {
+    fn validate(&self, _errors: &mut FieldErrors) {} // Either answer is valid
+}
+
+impl Validate for ShowRequest {
+    fn validate(&self, errors: &mut FieldErrors) {
+        errors.cards("cards", &self.cards, 1..=5); // Whether they were dealt is up to the table
+    }
+}
+
+impl Validate for BuyInRequest {
+    fn validate(&self, errors: &mut FieldErrors) {
+        errors.positive("amount", self.amount);
+    }
+}
+
+impl Validate for JoinTableRequest {
+    fn validate(&self, errors: &mut FieldErrors) {
+        errors.display_name("display_name", &self.display_name);
+        errors.positive("chip_stack", self.chip_stack);
+    }
+}
+
+impl Validate for CreateTableRequest {
+    fn validate(&self, errors: &mut FieldErrors) {
+        // The settings themselves are checked by the table, which knows how they fit together
+        errors.table_id("table_id", &self.table_id);
+    }
+}
+
+impl Validate for PlayerProfile {
+    fn validate(&self, errors: &mut FieldErrors) {
+        errors.display_name("display_name", &self.display_name);
+        if let Some(url) = &self.avatar_url {
+            if !url.starts_with("https://") || url.len() > MAX_AVATAR_URL_LEN {
+                errors.add("avatar_url", format!("must be an https:// URL of at most {MAX_AVATAR_URL_LEN} bytes"));
+            }
+        }
+        if let Some(country) = &self.country {
+            if country.len() != 2 || !country.chars().all(|c| c.is_ascii_uppercase()) {
+                errors.add("country", "must be a two-letter ISO 3166-1 code such as \"DE\"");
+            }
+        }
+        if serde_json::to_string(&self.preferences).map_or(true, |json| json.len() > MAX_PREFERENCES_LEN) {
+            errors.add("preferences", format!("may take up at most {MAX_PREFERENCES_LEN} bytes as JSON"));
+        }
+    }
+}
+
+impl Validate for InviteRequest {
+    fn validate(&self, errors: &mut FieldErrors) {
+        errors.player_id("friend_id", &self.friend_id);
+        errors.table_id("table_id", &self.table_id);
+    }
+}
+
+impl Validate for AcceptInvitationRequest {
+    fn validate(&self, errors: &mut FieldErrors) {
+        errors.display_name("display_name", &self.display_name);
+        errors.positive("chip_stack", self.chip_stack);
+    }
+}
+
+impl Validate for RegisterRequest {
+    fn validate(&self, errors: &mut FieldErrors) {
+        errors.player_id("player_id", &self.player_id);
+        errors.display_name("display_name", &self.display_name);
+    }
+}
+
+impl Validate for AddBotRequest {
+    fn validate(&self, errors: &mut FieldErrors) {
+        errors.player_id("player_id", &self.player_id);
+        errors.display_name("display_name", &self.display_name);
+        errors.positive("chip_stack", self.chip_stack);
+    }
+}
+
+impl Validate for EquityVsRangeRequest {
+    fn validate(&self, errors: &mut FieldErrors) {
+        let hero = errors.cards("hero", &self.hero, 2..=2);
+        let board = errors.cards("board", &self.board, 0..=5);
+        if let (Some(hero), Some(board)) = (hero, board) {
+            if board.iter().any(|card| hero.contains(card)) {
+                errors.add("board", "repeats one of the hero's cards");
+            }
+        }
+        errors.range("range", &self.range);
+        if self.trials == Some(0) {
+            errors.add("trials", "must be at least 1");
+        }
+    }
+}
+
+impl Validate for SolveRequest {
+    fn validate(&self, errors: &mut FieldErrors) {
+        if self.ranges.len() != 2 {
+            errors.add("ranges", format!("needs two ranges, not {}", self.ranges.len()));
+        }
+        for (index, range) in self.ranges.iter().enumerate() {
+            errors.range(&format!("ranges[{index}]"), range);
+        }
+        errors.cards("board", &self.board, 3..=5);
+        errors.positive("pot", self.pot);
+        errors.positive("effective_stack", self.effective_stack);
+        for (index, size) in self.bet_sizes.iter().enumerate() {
+            if *size == 0 {
+                errors.add(format!("bet_sizes[{index}]"), "must be more than zero");
+            }
+        }
+    }
+}
+
+#[cfg(feature = "debug-routes")]
+impl Validate for StackDeckRequest {
+    fn validate(&self, errors: &mut FieldErrors) {
+        // A shoe of several decks may deal the same card twice, so repeats are left to the table
+        if let Err(err) = parse_cards(&self.cards) {
+            errors.add("cards", err.to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(body: &impl Validate) -> Vec<(String, String)> {
+        let mut errors = FieldErrors::default();
+        body.validate(&mut errors);
+        errors.0.into_iter().map(|error| (error.field, error.message)).collect()
+    }
+
+    #[test]
+    fn test_every_problem_is_reported() {
+        let request = SolveRequest {
+            ranges: vec!["QQ+".to_string(), "XX".to_string()],
+            board: "Ah Kd".to_string(),
+            pot: Chips::ZERO,
+            effective_stack: Chips::new(900),
+            bet_sizes: vec![33, 0],
+        };
+        let fields: Vec<String> = check(&request).into_iter().map(|(field, _)| field).collect();
+        assert_eq!(fields, ["ranges[1]", "board", "pot", "bet_sizes[1]"]);
+
+        let request = EquityVsRangeRequest {
+            hero: "Ah Ah".to_string(),
+            range: "".to_string(),
+            board: "".to_string(),
+            trials: Some(0),
+        };
+        assert_eq!(check(&request), [
+            ("hero".to_string(), "repeats a card".to_string()),
+            ("range".to_string(), "Invalid request: a range needs at least one hand".to_string()),
+            ("trials".to_string(), "must be at least 1".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn test_player_ids_and_names() {
+        let login = LoginRequest { player_id: "two words".to_string(), password: None, table_id: "a.b".to_string() };
+        assert_eq!(check(&login).len(), 2);
+        let login = LoginRequest { player_id: "0x71C7656E".to_string(), table_id: "main".to_string(), ..login };
+        assert!(check(&login).is_empty());
+        let request = JoinTableRequest { display_name: "   ".to_string(), chip_stack: Chips::new(1000) };
+        assert_eq!(check(&request).len(), 1);
+        let request = JoinTableRequest { display_name: "Dana".to_string(), ..request };
+        assert!(check(&request).is_empty());
+        assert_eq!(check(&ActionRequest { action: PlayerAction::Raise(Chips::ZERO), state_version: 1 })[0].0, "action.raise");
+    }
+}
@@ -0,0 +1,143 @@
+use std::sync::Arc;
+
+use poker_core::game_error::GameError;
+use tracing::info;
+use warp::{Filter, Rejection};
+
+use super::auth::{authenticated, Session};
+use super::rate_limit::rate_limited;
+use super::rejections::reject;
+use super::requests::JoinTableRequest;
+use super::responses::{ErrorResponse, JoinTableResponse, WaitlistResponse};
+use super::warp_routes::{with_state, AppState};
+use super::validation::json_body;
+
+/// API route seating the authenticated player at a table, or putting them
+/// on its waiting list when every seat is taken. The session must have been
+/// issued for this table, so the caller's later requests go to it.
+///
+/// Waiting players are seated in the order they joined as seats open; the
+/// table's event stream reports `seat_opened` and then `player_seated`.
+///
+/// # Endpoint
+/// `POST /v1/tables/{table_id}/join`
+///
+/// # Response
+/// - **Success**: Returns the seat taken or the place in line.
+/// - **Failure**: `400 Bad Request` for a stack outside the buy-in limits or a
+///   player already seated or waiting, `401 Unauthorized` without a valid
+///   session token, `403 Forbidden` for a session issued for another table,
+///   `404 Not Found` for an unknown table.
+#[utoipa::path(
+    post,
+    path = "/v1/tables/{table_id}/join",
+    tag = "waitlist",
+    params(("table_id" = String, Path, description = "Table to join")),
+    request_body = JoinTableRequest,
+    responses(
+        (status = 200, description = "Seated, or waiting for a seat", body = JoinTableResponse),
+        (status = 400, description = "Stack outside the buy-in limits, or already seated or waiting", body = ErrorResponse),
+        (status = 401, description = "Missing, invalid or expired token", body = ErrorResponse),
+        (status = 403, description = "Session issued for another table", body = ErrorResponse),
+        (status = 404, description = "No table with this id", body = ErrorResponse),
+    )
+)]
+fn join_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("tables" / String / "join")
+        .and(warp::post())
+        .and(rate_limited(state.clone()))
+        .and(authenticated(state.clone()))
+        .and(json_body())
+        .and(with_state(state))
+        .and_then(|table_id: String, session: Session, request: JoinTableRequest, state: Arc<AppState>| async move {
+            let table = state.tables.get(&table_id).map_err(reject)?;
+            if session.table_id != table.id {
+                return Err(reject(GameError::NotAllowed(format!("log in at table {table_id} to join it"))));
+            }
+
+            let player_id = session.player_id.clone();
+            let seating = table
+                .try_call(move |controller| controller.join_table(&session.player_id, &request.display_name, request.chip_stack))
+                .await
+                .map_err(reject)?;
+            info!(%table_id, %player_id, ?seating, "player joined");
+            Ok::<_, Rejection>(warp::reply::json(&JoinTableResponse::new(table_id, player_id, seating)))
+        })
+}
+
+/// API route listing the players waiting for a seat at a table.
+///
+/// # Endpoint
+/// `GET /v1/tables/{table_id}/waitlist`
+///
+/// # Response
+/// - **Success**: Returns the waiting players, next in line first, and how many seats are taken.
+/// - **Failure**: `404 Not Found` for an unknown table.
+#[utoipa::path(
+    get,
+    path = "/v1/tables/{table_id}/waitlist",
+    tag = "waitlist",
+    params(("table_id" = String, Path, description = "Table whose waiting list to show")),
+    responses(
+        (status = 200, description = "The waiting list", body = WaitlistResponse),
+        (status = 404, description = "No table with this id", body = ErrorResponse),
+    )
+)]
+fn waitlist_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("tables" / String / "waitlist")
+        .and(warp::get())
+        .and(with_state(state))
+        .and_then(|table_id: String, state: Arc<AppState>| async move {
+            let table = state.tables.get(&table_id).map_err(reject)?;
+            let waitlist = table
+                .call(move |controller| WaitlistResponse::new(table_id, controller))
+                .await
+                .map_err(reject)?;
+            Ok::<_, Rejection>(warp::reply::json(&waitlist))
+        })
+}
+
+/// API route taking the authenticated player off a table's waiting list.
+///
+/// # Endpoint
+/// `DELETE /v1/tables/{table_id}/waitlist`
+///
+/// # Response
+/// - **Success**: Returns the waiting list without the caller.
+/// - **Failure**: `401 Unauthorized` without a valid session token, `404 Not
+///   Found` for an unknown table or a caller who isn't waiting.
+#[utoipa::path(
+    delete,
+    path = "/v1/tables/{table_id}/waitlist",
+    tag = "waitlist",
+    params(("table_id" = String, Path, description = "Table whose waiting list to leave")),
+    responses(
+        (status = 200, description = "The waiting list after leaving it", body = WaitlistResponse),
+        (status = 401, description = "Missing, invalid or expired token", body = ErrorResponse),
+        (status = 404, description = "No such table, or the caller isn't waiting", body = ErrorResponse),
+    )
+)]
+fn leave_waitlist_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("tables" / String / "waitlist")
+        .and(warp::delete())
+        .and(authenticated(state.clone()))
+        .and(with_state(state))
+        .and_then(|table_id: String, session: Session, state: Arc<AppState>| async move {
+            let table = state.tables.get(&table_id).map_err(reject)?;
+            let waitlist = table
+                .try_call(move |controller| {
+                    controller.leave_waitlist(&session.player_id)?;
+                    Ok(WaitlistResponse::new(table_id, controller))
+                })
+                .await
+                .map_err(reject)?;
+            Ok::<_, Rejection>(warp::reply::json(&waitlist))
+        })
+}
+
+/// All waiting list routes.
+pub fn waitlist_routes(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    join_route(state.clone())
+        .or(waitlist_route(state.clone()))
+        .or(leave_waitlist_route(state))
+}
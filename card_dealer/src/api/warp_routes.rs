@@ -1,11 +1,56 @@
-use warp::Filter;
-use std::{sync::{Arc, Mutex}};
-use crate::game_controller::GameController;
+use warp::{Filter, Rejection};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use crate::config::CorsConfig;
+use poker_core::game_error::GameError;
+use poker_core::solver::SolverBackend;
+#[cfg(feature = "metrics")]
+use poker_core::metrics::METRICS;
+use crate::bankroll::Bankroll;
+use crate::profiles::ProfileStore;
+use crate::hand_history::collusion::LoginAddresses;
+use crate::social::Social;
+use crate::table_registry::TableRegistry;
+use super::admin_routes::admin_routes;
+use super::spectate::spectate_routes;
+use super::waitlist::waitlist_routes;
+use super::tournament::tournament_routes;
+use super::analysis::analysis_routes;
+use super::history::history_routes;
+use super::profiles::profile_routes;
+use super::social::social_routes;
+use super::bankroll::bankroll_routes;
+use super::auth::{authenticated, Authenticator, Session};
+use super::idempotency::IdempotencyCache;
+use super::rate_limit::{rate_limited, RateLimiter};
+use super::requests::{ActionRequest, BuyInRequest, EvaluateQuery, HintQuery, InsuranceRequest, LoginRequest, RunItRequest, ShowRequest};
+use super::openapi::{ApiDoc, SWAGGER_UI_HTML};
+use super::i18n::{locale, Locale};
+use super::rejections::{handle_rejection, reject};
+use super::encoding;
+use super::service::ApiService;
+use super::validation::json_body;
+use super::responses::{
+    CommunityResponse, DealHoleResponse, ErrorResponse, EvaluationResponse, HintResponse, PlayerStateResponse, RabbitHuntResponse, ResetResponse,
+    SessionResponse, TestWinnersResponse,
+};
+use serde::Serialize;
+use utoipa::OpenApi;
+use warp::http::header::{HeaderMap, HeaderValue, ACCEPT_ENCODING, IF_NONE_MATCH};
+use warp::http::Method;
 
 /// Struct representing the shared state of the application.
-/// Contains a `GameController` wrapped in a `Mutex` for thread safety.
+/// Each table's `GameController` sits behind its own async lock in `tables`.
 pub struct AppState {
-    pub game_controller: Mutex<GameController>,
+    pub tables: TableRegistry,         // Every hosted table; unscoped routes use the default one
+    pub rate_limiter: RateLimiter,     // Per-IP request budget for the game routes
+    pub authenticator: Authenticator, // Issues and checks session tokens
+    pub idempotency: IdempotencyCache, // Outcomes of actions sent with an Idempotency-Key, for retries
+    pub profiles: ProfileStore,        // Player profiles, independent of any table
+    pub bankroll: Arc<Bankroll>,       // Balances tracked-currency tables draw chips from
+    pub social: Social,                // Friend lists and open table invitations
+    pub logins: LoginAddresses,        // Addresses players logged in from, for the collusion report
+    pub solver: Option<Arc<dyn SolverBackend>>, // Answers /v1/analysis/solve; unset when no solver is configured
 }
 
 /// Helper function to create a Warp filter for sharing the application state.
@@ -17,7 +62,7 @@ pub struct AppState {
 ///
 /// # Returns
 /// A `warp::Filter` that provides the shared state.
-fn with_state(
+pub(super) fn with_state(
     state: Arc<AppState>,
 ) -> impl Filter<Extract = (Arc<AppState>,), Error = std::convert::Infallible> + Clone {
     warp::any().map(move || state.clone())
@@ -28,38 +73,26 @@ fn with_state(
 /// This endpoint assigns two hole cards to each player.
 ///
 /// # Endpoint
-/// `GET /deal_hole`
+/// `GET /v1/deal_hole`
 ///
 /// # Response
 /// - **Success**: Returns a JSON object containing each player's hole cards.
-/// - **Failure**: Returns an error message if cards cannot be dealt.
+/// - **Failure**: `409 Conflict` if the deck cannot cover the deal.
+#[utoipa::path(
+    get,
+    path = "/v1/deal_hole",
+    tag = "dealer",
+    responses(
+        (status = 200, description = "Hole cards for every player", body = DealHoleResponse),
+        (status = 409, description = "The deck ran out of cards", body = ErrorResponse),
+        (status = 429, description = "Too many requests from this client", body = ErrorResponse),
+    )
+)]
 fn deal_hole_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
     warp::path("deal_hole")
+        .and(rate_limited(state.clone()))
         .and(with_state(state))
-        .map(|state: Arc<AppState>| {
-            let mut controller = state.game_controller.lock().unwrap();
-            match controller.deal_hole_cards() {
-                Ok(_) => {
-                    let player_cards: Vec<_> = controller
-                        .get_players()
-                        .iter()
-                        .map(|player| serde_json::json!({
-                            "name": player.display_name,
-                            "hole_cards": player.hole_cards
-                        }))
-                        .collect();
-
-                    warp::reply::json(&serde_json::json!({
-                        "type": "hole",
-                        "players": player_cards
-                    }))
-                }
-                Err(err) => warp::reply::json(&serde_json::json!({
-                    "type": "error",
-                    "message": err
-                })),
-            }
-        })
+        .and_then(|state: Arc<AppState>| async move { json_reply(state.deal_hole().await) })
 }
 
 /// API route to deal community cards.
@@ -67,27 +100,26 @@ fn deal_hole_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Re
 /// This endpoint assigns five community cards to the table.
 ///
 /// # Endpoint
-/// `GET /deal_community`
+/// `GET /v1/deal_community`
 ///
 /// # Response
 /// - **Success**: Returns a JSON object with the community cards.
-/// - **Failure**: Returns an error message if cards cannot be dealt.
+/// - **Failure**: `409 Conflict` if the deck cannot cover the deal.
+#[utoipa::path(
+    get,
+    path = "/v1/deal_community",
+    tag = "dealer",
+    responses(
+        (status = 200, description = "The five community cards", body = CommunityResponse),
+        (status = 409, description = "The deck ran out of cards", body = ErrorResponse),
+        (status = 429, description = "Too many requests from this client", body = ErrorResponse),
+    )
+)]
 fn deal_community_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
     warp::path("deal_community")
+        .and(rate_limited(state.clone()))
         .and(with_state(state))
-        .map(|state: Arc<AppState>| {
-            let mut controller = state.game_controller.lock().unwrap();
-            match controller.deal_community_cards() {
-                Ok(_) => warp::reply::json(&serde_json::json!({
-                    "type": "community",
-                    "cards": controller.get_community_cards()
-                })),
-                Err(err) => warp::reply::json(&serde_json::json!({
-                    "type": "error",
-                    "message": err
-                })),
-            }
-        })
+        .and_then(|state: Arc<AppState>| async move { json_reply(state.deal_community().await) })
 }
 
 /// API route to reset the game state.
@@ -95,21 +127,23 @@ fn deal_community_route(state: Arc<AppState>) -> impl Filter<Extract = (impl war
 /// This endpoint resets the deck and clears all game state.
 ///
 /// # Endpoint
-/// `GET /reset`
+/// `GET /v1/reset`
 ///
 /// # Response
 /// - **Success**: Returns a confirmation message.
+#[utoipa::path(
+    get,
+    path = "/v1/reset",
+    tag = "dealer",
+    responses(
+        (status = 200, description = "Confirmation that the game was reset", body = ResetResponse),
+    )
+)]
 fn reset_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
     warp::path("reset")
+        .and(rate_limited(state.clone()))
         .and(with_state(state))
-        .map(|state: Arc<AppState>| {
-            let mut controller = state.game_controller.lock().unwrap();
-            controller.reset_deck();
-            warp::reply::json(&serde_json::json!({
-                "type": "reset",
-                "message": "Game Reset Successfully"
-            }))
-        })
+        .and_then(|state: Arc<AppState>| async move { json_reply(state.reset().await) })
 }
 
 /// API route to evaluate all player hands.
@@ -117,30 +151,29 @@ fn reset_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,
 /// This endpoint calculates the best possible hand for each player.
 ///
 /// # Endpoint
-/// `GET /evaluate`
+/// `GET /v1/evaluate`
+///
+/// With `?percentile=true` each hand also says what share of the holdings
+/// an opponent could have it beats on the board.
 ///
 /// # Response
 /// - **Success**: Returns each player's best hand and strength.
+#[utoipa::path(
+    get,
+    path = "/v1/evaluate",
+    tag = "dealer",
+    params(EvaluateQuery),
+    responses(
+        (status = 200, description = "Each player's best hand alongside the board", body = EvaluationResponse),
+    )
+)]
 fn evaluate_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
     warp::path("evaluate")
+        .and(rate_limited(state.clone()))
         .and(with_state(state))
-        .map(|state: Arc<AppState>| {
-            let controller = state.game_controller.lock().unwrap();
-            let player_hands: Vec<_> = controller.get_players().iter().map(|player| {
-                serde_json::json!({
-                    "name": player.display_name,
-                    "hand_strength": player.hand_strength,
-                    "best_hand": player.best_hand,
-                    "hole_cards": player.hole_cards
-                })
-            }).collect();
-
-            warp::reply::json(&serde_json::json!({
-                "type": "evaluation",
-                "players": player_hands,
-                "community_cards": controller.get_community_cards()
-            }))
-        })
+        .and(warp::query::<EvaluateQuery>())
+        .and(locale())
+        .and_then(|state: Arc<AppState>, query: EvaluateQuery, locale: Locale| async move { json_reply(state.evaluate(query, locale).await) })
 }
 
 /// API route to determine the winner(s).
@@ -148,62 +181,1612 @@ fn evaluate_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Rep
 /// This endpoint identifies the best hand(s) among all players.
 ///
 /// # Endpoint
-/// `GET /test_winners`
+/// `GET /v1/test_winners`
 ///
 /// # Response
 /// - **Success**: Returns the winning player(s) and their best hand.
-/// - **Failure**: Returns an error message if no winner is found.
+/// - **Failure**: `404 Not Found` if no winner can be determined.
+#[utoipa::path(
+    get,
+    path = "/v1/test_winners",
+    tag = "dealer",
+    responses(
+        (status = 200, description = "The winning player(s)", body = TestWinnersResponse),
+        (status = 404, description = "No player holds an evaluated hand", body = ErrorResponse),
+    )
+)]
 fn test_winners_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
     warp::path("test_winners")
+        .and(rate_limited(state.clone()))
         .and(with_state(state))
-        .map(|state: Arc<AppState>| {
-            let controller = state.game_controller.lock().unwrap();
-            let all_players: Vec<String> = controller.get_players()
-                .iter()
-                .map(|p| p.player_id.clone())
-                .collect();
-
-            match controller.get_winners(&all_players) {
-                Some(winners) => {
-                    let winner_list: Vec<_> = winners.iter().map(|player_id| {
-                        let player = controller.get_players().iter()
-                            .find(|p| &p.player_id == player_id)
-                            .unwrap();
-                        serde_json::json!({
-                            "player_id": player_id,
-                            "name": player.display_name,
-                            "hand_strength": player.hand_strength,
-                            "best_hand": player.best_hand.as_ref().map(|h| h.cards.clone())
-                        })
-                    }).collect();
-
-                    warp::reply::json(&serde_json::json!({
-                        "type": "test_winners",
-                        "players": winner_list
-                    }))
-                }
-                None => warp::reply::json(&serde_json::json!({
-                    "type": "error",
-                    "message": "No winner determined"
-                })),
-            }
+        .and(locale())
+        .and_then(|state: Arc<AppState>, locale: Locale| async move { json_reply(state.test_winners(locale).await) })
+}
+
+/// API route exchanging a player id and password for a session token.
+/// Players without a configured password can only log in while the server
+/// allows open login.
+///
+/// The session is bound to `table_id`, the default table unless given:
+/// `/v1/me`, actions, buy-ins and hand history all go to that table.
+///
+/// # Endpoint
+/// `POST /v1/login`
+///
+/// # Response
+/// - **Success**: Returns a signed token to send as `Authorization: Bearer <token>`.
+/// - **Failure**: `401 Unauthorized` for a wrong or missing password, `404 Not Found` for an unknown table.
+#[utoipa::path(
+    post,
+    path = "/v1/login",
+    tag = "auth",
+    request_body = LoginRequest,
+    responses(
+        (status = 200, description = "A new session token", body = SessionResponse),
+        (status = 401, description = "Wrong or missing password", body = ErrorResponse),
+        (status = 404, description = "No table with this id", body = ErrorResponse),
+    )
+)]
+fn login_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path("login")
+        .and(warp::path::end())
+        .and(warp::post())
+        .and(rate_limited(state.clone()))
+        .and(warp::addr::remote())
+        .and(json_body())
+        .and(with_state(state))
+        .and_then(|remote: Option<SocketAddr>, request: LoginRequest, state: Arc<AppState>| async move {
+            json_reply(state.login(request, remote.map(|addr| addr.ip())).await)
+        })
+}
+
+/// API route trading a still-valid session token for one with a fresh expiry.
+///
+/// # Endpoint
+/// `POST /v1/refresh`
+///
+/// # Response
+/// - **Success**: Returns a new token for the same player.
+/// - **Failure**: `401 Unauthorized` if the current token is missing, invalid or expired.
+#[utoipa::path(
+    post,
+    path = "/v1/refresh",
+    tag = "auth",
+    responses(
+        (status = 200, description = "A renewed session token", body = SessionResponse),
+        (status = 401, description = "Missing, invalid or expired token", body = ErrorResponse),
+    )
+)]
+fn refresh_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path("refresh")
+        .and(warp::path::end())
+        .and(warp::post())
+        .and(authenticated(state.clone()))
+        .and(with_state(state))
+        .and_then(|session: Session, state: Arc<AppState>| async move { json_reply(state.refresh(session).await) })
+}
+
+/// API route returning the authenticated player's own seat and hole cards.
+///
+/// # Endpoint
+/// `GET /v1/me`
+///
+/// # Response
+/// - **Success**: Returns the caller's stack and hole cards, their results
+///   this session in chips and in all-in equity, and the price of acting
+///   while it's their turn.
+/// - **Failure**: `401 Unauthorized` without a valid session token.
+#[utoipa::path(
+    get,
+    path = "/v1/me",
+    tag = "auth",
+    responses(
+        (status = 200, description = "The caller's seat", body = PlayerStateResponse),
+        (status = 401, description = "Missing, invalid or expired token", body = ErrorResponse),
+    )
+)]
+fn me_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path("me")
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(authenticated(state.clone()))
+        .and(with_state(state))
+        .and_then(|session: Session, state: Arc<AppState>| async move { json_reply(state.me(session).await) })
+}
+
+/// API route adding chips the authenticated player buys, within the table's
+/// buy-in limits.
+///
+/// # Endpoint
+/// `POST /v1/buyin`
+///
+/// # Response
+/// - **Success**: Returns the caller's seat with the new stack.
+/// - **Failure**: `400 Bad Request` for an amount outside the limits, `403
+///   Forbidden` when a rebuy or the add-on isn't available, `409 Conflict`
+///   while the caller is in a hand.
+#[utoipa::path(
+    post,
+    path = "/v1/buyin",
+    tag = "auth",
+    request_body = BuyInRequest,
+    responses(
+        (status = 200, description = "The caller's seat after buying in", body = PlayerStateResponse),
+        (status = 400, description = "Amount outside the table's limits", body = ErrorResponse),
+        (status = 401, description = "Missing, invalid or expired token", body = ErrorResponse),
+        (status = 403, description = "No rebuy or add-on available", body = ErrorResponse),
+        (status = 409, description = "The caller is playing a hand", body = ErrorResponse),
+    )
+)]
+fn buy_in_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path("buyin")
+        .and(warp::path::end())
+        .and(warp::post())
+        .and(authenticated(state.clone()))
+        .and(json_body())
+        .and(with_state(state))
+        .and_then(|session: Session, request: BuyInRequest, state: Arc<AppState>| async move {
+            json_reply(state.buy_in(session, request).await)
+        })
+}
+
+/// API route taking the authenticated player's action on their turn.
+///
+/// The request carries the `state_version` of the state the action was
+/// decided on, as returned by `GET /v1/me`; once anything has happened at
+/// the table since, the action is refused so a slow client can't act on
+/// outdated information.
+///
+/// A client sending an `Idempotency-Key` header gets the first outcome back
+/// when it retries with the same key, for ten minutes, instead of acting
+/// twice. Keys are per player and may not be reused for another action.
+///
+/// # Endpoint
+/// `POST /v1/action`
+///
+/// # Response
+/// - **Success**: Returns the caller's seat after acting.
+/// - **Failure**: `400 Bad Request` for an invalid amount or a key reused for
+///   another action, `409 Conflict` when it isn't the caller's turn, the
+///   state version is stale or the key's first request is still running.
+#[utoipa::path(
+    post,
+    path = "/v1/action",
+    tag = "auth",
+    request_body = ActionRequest,
+    params(("Idempotency-Key" = Option<String>, Header, description = "Retries with the same key get the first outcome again")),
+    responses(
+        (status = 200, description = "The caller's seat after acting", body = PlayerStateResponse),
+        (status = 400, description = "Invalid action, or a reused key", body = ErrorResponse),
+        (status = 401, description = "Missing, invalid or expired token", body = ErrorResponse),
+        (status = 409, description = "Not the caller's turn, a stale state version, or the key's first request is still running", body = ErrorResponse),
+    )
+)]
+fn action_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path("action")
+        .and(warp::path::end())
+        .and(warp::post())
+        .and(authenticated(state.clone()))
+        .and(warp::header::optional::<String>("idempotency-key"))
+        .and(json_body())
+        .and(with_state(state))
+        .and_then(|session: Session, key: Option<String>, request: ActionRequest, state: Arc<AppState>| async move {
+            json_reply(state.action(session, key, request).await)
+        })
+}
+
+/// API route choosing how many times to run the rest of the board, once
+/// the authenticated player is all in before the river at a table that
+/// allows it.
+///
+/// Every player left in the hand chooses; the board is run as few times as
+/// anyone asked for, and each run wins an even share of every pot.
+///
+/// # Endpoint
+/// `POST /v1/run_it`
+///
+/// # Response
+/// - **Success**: Returns the caller's seat after choosing.
+/// - **Failure**: `400 Bad Request` for more runs than the table allows,
+///   `403 Forbidden` for a player not in the hand, `409 Conflict` when
+///   nobody is choosing or the caller already chose.
+#[utoipa::path(
+    post,
+    path = "/v1/run_it",
+    tag = "auth",
+    request_body = RunItRequest,
+    responses(
+        (status = 200, description = "The caller's seat after choosing", body = PlayerStateResponse),
+        (status = 400, description = "More runs than the table allows", body = ErrorResponse),
+        (status = 401, description = "Missing, invalid or expired token", body = ErrorResponse),
+        (status = 403, description = "The caller isn't in the hand", body = ErrorResponse),
+        (status = 409, description = "Nobody is choosing, or the caller already chose", body = ErrorResponse),
+    )
+)]
+fn run_it_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path("run_it")
+        .and(warp::path::end())
+        .and(warp::post())
+        .and(authenticated(state.clone()))
+        .and(json_body())
+        .and(with_state(state))
+        .and_then(|session: Session, request: RunItRequest, state: Arc<AppState>| async move {
+            json_reply(state.run_it(session, request).await)
+        })
+}
+
+/// API route buying or declining the all-in insurance offered to the
+/// authenticated player.
+///
+/// At tables that sell it, the favourite of a heads-up all-in before the
+/// river is offered insurance on their share of the pot once the flop is
+/// out, priced from their exact equity. The rest of the board waits for
+/// the answer. Buying takes the premium from the caller's stack at once;
+/// the coverage is paid back for whatever share of the pot they lose.
+///
+/// # Endpoint
+/// `POST /v1/insurance`
+///
+/// # Response
+/// - **Success**: Returns the caller's seat after deciding.
+/// - **Failure**: `403 Forbidden` when the offer is another player's,
+///   `409 Conflict` when nothing is on offer or the stack can't cover the
+///   premium.
+#[utoipa::path(
+    post,
+    path = "/v1/insurance",
+    tag = "auth",
+    request_body = InsuranceRequest,
+    responses(
+        (status = 200, description = "The caller's seat after deciding", body = PlayerStateResponse),
+        (status = 401, description = "Missing, invalid or expired token", body = ErrorResponse),
+        (status = 403, description = "The insurance is offered to another player", body = ErrorResponse),
+        (status = 409, description = "No insurance is on offer, or the stack can't cover the premium", body = ErrorResponse),
+    )
+)]
+fn insurance_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path("insurance")
+        .and(warp::path::end())
+        .and(warp::post())
+        .and(authenticated(state.clone()))
+        .and(json_body())
+        .and(with_state(state))
+        .and_then(|session: Session, request: InsuranceRequest, state: Arc<AppState>| async move {
+            json_reply(state.insurance(session, request).await)
+        })
+}
+
+/// API route showing the authenticated player the cards that would have
+/// completed the board of the last hand, when it ended before the river.
+///
+/// The cards are only looked at, never dealt, and shown to the whole table
+/// as a `rabbit_hunted` event. Each player may hunt once an orbit.
+///
+/// # Endpoint
+/// `GET /v1/rabbit_hunt`
+///
+/// # Response
+/// - **Success**: Returns the board and the cards that would have come.
+/// - **Failure**: `403 Forbidden` when the caller already hunted this
+///   orbit, `409 Conflict` during a hand or when the last hand reached
+///   the river.
+#[utoipa::path(
+    get,
+    path = "/v1/rabbit_hunt",
+    tag = "auth",
+    responses(
+        (status = 200, description = "The cards that would have come", body = RabbitHuntResponse),
+        (status = 401, description = "Missing, invalid or expired token", body = ErrorResponse),
+        (status = 403, description = "Already hunted this orbit", body = ErrorResponse),
+        (status = 409, description = "A hand is in progress, or the last one reached the river", body = ErrorResponse),
+    )
+)]
+fn rabbit_hunt_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path("rabbit_hunt")
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(authenticated(state.clone()))
+        .and(with_state(state))
+        .and_then(|session: Session, state: Arc<AppState>| async move {
+            json_reply(state.rabbit_hunt(session).await)
+        })
+}
+
+/// API route showing the table some of the authenticated player's hole
+/// cards, after they won the last hand without being called.
+///
+/// One card or both may be shown, and more later. The cards go out to the
+/// table as a `cards_shown` event and into the hand's history.
+///
+/// # Endpoint
+/// `POST /v1/show`
+///
+/// # Response
+/// - **Success**: Returns the caller's seat after showing.
+/// - **Failure**: `400 Bad Request` for cards the caller wasn't dealt,
+///   `403 Forbidden` when the caller didn't win the hand, `409 Conflict`
+///   during a hand, after a showdown or for a card already shown.
+#[utoipa::path(
+    post,
+    path = "/v1/show",
+    tag = "auth",
+    request_body = ShowRequest,
+    responses(
+        (status = 200, description = "The caller's seat after showing", body = PlayerStateResponse),
+        (status = 400, description = "Cards the caller wasn't dealt", body = ErrorResponse),
+        (status = 401, description = "Missing, invalid or expired token", body = ErrorResponse),
+        (status = 403, description = "The caller didn't win the hand", body = ErrorResponse),
+        (status = 409, description = "A hand is in progress, it went to showdown, or a card was already shown", body = ErrorResponse),
+    )
+)]
+fn show_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path("show")
+        .and(warp::path::end())
+        .and(warp::post())
+        .and(authenticated(state.clone()))
+        .and(json_body())
+        .and(with_state(state))
+        .and_then(|session: Session, request: ShowRequest, state: Arc<AppState>| async move {
+            json_reply(state.show(session, request).await)
         })
 }
 
+/// API route having the authenticated player straddle the next hand: when
+/// they are under the gun in it they post twice the big blind before the
+/// cards and act last preflop.
+///
+/// Only tables that allow straddles take the request. It is dropped after
+/// the next hand, and ignored when the caller isn't under the gun in it or
+/// can't cover the straddle.
+///
+/// # Endpoint
+/// `POST /v1/straddle`
+///
+/// # Response
+/// - **Success**: Returns the caller's seat.
+/// - **Failure**: `403 Forbidden` when the table doesn't allow straddles,
+///   `404 Not Found` when the caller has no seat.
+#[utoipa::path(
+    post,
+    path = "/v1/straddle",
+    tag = "auth",
+    responses(
+        (status = 200, description = "The caller's seat", body = PlayerStateResponse),
+        (status = 401, description = "Missing, invalid or expired token", body = ErrorResponse),
+        (status = 403, description = "The table doesn't allow straddles", body = ErrorResponse),
+        (status = 404, description = "The caller has no seat", body = ErrorResponse),
+    )
+)]
+fn straddle_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path("straddle")
+        .and(warp::path::end())
+        .and(warp::post())
+        .and(authenticated(state.clone()))
+        .and(with_state(state))
+        .and_then(|session: Session, state: Arc<AppState>| async move {
+            json_reply(state.straddle(session).await)
+        })
+}
+
+/// API route suggesting an action to a beginner on their turn, from the
+/// preflop chart before the flop and from hand strength against the pot odds
+/// after it.
+///
+/// Only tables in training mode give hints, and only to the player asking
+/// about their own seat.
+///
+/// # Endpoint
+/// `GET /v1/hint?player_id=1`
+///
+/// # Response
+/// - **Success**: Returns the suggested action and why.
+/// - **Failure**: `403 Forbidden` outside training mode or for another
+///   player's seat, `409 Conflict` when it isn't the player's turn.
+#[utoipa::path(
+    get,
+    path = "/v1/hint",
+    tag = "auth",
+    params(HintQuery),
+    responses(
+        (status = 200, description = "A suggested action with its rationale", body = HintResponse),
+        (status = 401, description = "Missing, invalid or expired token", body = ErrorResponse),
+        (status = 403, description = "Not a training table, or another player's seat", body = ErrorResponse),
+        (status = 409, description = "Not the player's turn", body = ErrorResponse),
+    )
+)]
+fn hint_route(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path("hint")
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(authenticated(state.clone()))
+        .and(warp::query::<HintQuery>())
+        .and(with_state(state))
+        .and_then(|session: Session, query: HintQuery, state: Arc<AppState>| async move { json_reply(state.hint(session, query).await) })
+}
+
+/// Sends the result of an `ApiService` call as JSON, or the error as a rejection.
+fn json_reply<T: Serialize>(result: Result<T, GameError>) -> Result<warp::reply::Json, Rejection> {
+    result.map(|body| warp::reply::json(&body)).map_err(reject)
+}
+
+/// API route exposing server metrics.
+///
+/// # Endpoint
+/// `GET /metrics`
+///
+/// # Response
+/// - **Success**: Returns counters and histograms in the Prometheus text format.
+#[cfg(feature = "metrics")]
+fn metrics_route() -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path("metrics")
+        .and(warp::path::end())
+        .map(|| {
+            warp::reply::with_header(
+                METRICS.render(),
+                "content-type",
+                "text/plain; version=0.0.4",
+            )
+        })
+}
+
+/// Records per-route request latency for `/metrics`.
+///
+/// Unmatched paths are grouped under a single label so random URLs can't
+/// blow up the number of series.
+#[cfg(feature = "metrics")]
+fn record_latency(info: warp::log::Info) {
+    let status = info.status().as_u16();
+    let route = if status == 404 { "unmatched" } else { info.path() };
+    METRICS.observe_route(route, status, info.elapsed());
+}
+
+/// API route serving the OpenAPI specification.
+///
+/// # Endpoint
+/// `GET /v1/openapi.json`
+///
+/// # Response
+/// - **Success**: Returns the OpenAPI document describing every route.
+fn openapi_route() -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path("openapi.json")
+        .and(warp::path::end())
+        .map(|| warp::reply::json(&ApiDoc::openapi()))
+}
+
+/// API route serving a Swagger UI page for browsing the specification.
+///
+/// # Endpoint
+/// `GET /docs`
+///
+/// # Response
+/// - **Success**: Returns an HTML page that loads `/v1/openapi.json`.
+fn docs_route() -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path("docs")
+        .and(warp::path::end())
+        .map(|| warp::reply::html(SWAGGER_UI_HTML))
+}
+
+/// Builds the CORS wrapper from the configured policy, or `None` when CORS is off.
+fn cors(config: &CorsConfig) -> Option<warp::cors::Builder> {
+    if !config.is_enabled() {
+        return None;
+    }
+
+    let builder = warp::cors()
+        .allow_methods(config.allowed_methods.iter().map(String::as_str))
+        .allow_headers(config.allowed_headers.iter().map(String::as_str))
+        .max_age(std::time::Duration::from_secs(config.max_age_secs));
+
+    Some(if config.allows_any_origin() {
+        builder.allow_any_origin()
+    } else {
+        builder.allow_origins(config.allowed_origins.iter().map(String::as_str))
+    })
+}
+
+/// Erases the reply type so filters with and without optional wrappers line up.
+fn boxed_reply<R: warp::Reply + 'static>(reply: R) -> Box<dyn warp::Reply> {
+    Box::new(reply)
+}
+
+/// Routes making up version 1 of the API.
+///
+/// Breaking changes to paths or response shapes go into a new version
+/// mounted next to this one, so existing clients keep working.
+fn v1_routes(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    let routes = deal_hole_route(state.clone())
+        .or(deal_community_route(state.clone()))
+        .or(reset_route(state.clone()))
+        .or(evaluate_route(state.clone()))
+        .or(test_winners_route(state.clone()))
+        .or(login_route(state.clone()))
+        .or(refresh_route(state.clone()))
+        .or(me_route(state.clone()))
+        .or(hint_route(state.clone()))
+        .or(buy_in_route(state.clone()))
+        .or(action_route(state.clone()))
+        .or(run_it_route(state.clone()))
+        .or(insurance_route(state.clone()))
+        .or(rabbit_hunt_route(state.clone()))
+        .or(show_route(state.clone()))
+        .or(straddle_route(state.clone()))
+        .or(admin_routes(state.clone()))
+        .or(spectate_routes(state.clone()))
+        .or(waitlist_routes(state.clone()))
+        .or(tournament_routes(state.clone()))
+        .or(analysis_routes(state.clone()))
+        .or(history_routes(state.clone()))
+        .or(profile_routes(state.clone()))
+        .or(social_routes(state.clone()))
+        .or(bankroll_routes(state.clone()))
+        .or(openapi_route());
+
+    #[cfg(feature = "debug-routes")]
+    let routes = routes.or(super::debug_routes::debug_routes(state));
+
+    routes
+}
+
 /// Combines all API routes into a single filter.
 ///
 /// This function collects all endpoints and allows them to be served
-/// from the main application.
+/// from the main application. Each API version lives under its own prefix
+/// (`/v1/...`); the unprefixed paths that predate versioning remain as
+/// aliases of v1. Operational endpoints (`/metrics`, `/docs`) are unversioned.
 ///
 /// # Arguments
 /// * `state` - The shared game state (`Arc<AppState>`).
+/// * `cors_config` - Which browser origins may call the API.
 ///
 /// # Returns
-/// A `warp::Filter` containing all defined routes.
-pub fn get_routes(state: Arc<AppState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
-    deal_hole_route(state.clone())
-        .or(deal_community_route(state.clone()))
-        .or(reset_route(state.clone()))
-        .or(evaluate_route(state.clone()))
-        .or(test_winners_route(state.clone()))
+/// A `warp::Filter` containing all defined routes, with rejections
+/// converted into JSON error responses by `handle_rejection` (in the
+/// language asked for by `Accept-Language`), large bodies compressed,
+/// `GET` responses tagged for `If-None-Match`, and each
+/// request wrapped in its own tracing span.
+pub fn get_routes(state: Arc<AppState>, cors_config: &CorsConfig) -> impl Filter<Extract = (impl warp::Reply,), Error = std::convert::Infallible> + Clone {
+    let routes = warp::path("v1")
+        .and(v1_routes(state.clone()))
+        .or(v1_routes(state.clone()))
+        .or(docs_route());
+
+    #[cfg(feature = "metrics")]
+    let routes = routes.or(metrics_route());
+
+    #[cfg(feature = "graphql")]
+    let routes = routes.or(super::graphql::graphql_routes(state.clone()));
+
+    let routes = match cors(cors_config) {
+        Some(cors) => routes.with(cors).map(boxed_reply).boxed(),
+        None => routes.map(boxed_reply).boxed(),
+    };
+
+    // Like `recover`, but with the caller's language at hand for the error body
+    let routes = locale()
+        .and(routes.map(Ok).or_else(|rejection| async move { Ok::<_, std::convert::Infallible>((Err(rejection),)) }))
+        .and_then(|locale: Locale, result: Result<Box<dyn warp::Reply>, Rejection>| async move {
+            match result {
+                Ok(reply) => Ok::<_, std::convert::Infallible>(reply),
+                Err(rejection) => handle_rejection(rejection, locale).await.map(boxed_reply),
+            }
+        });
+
+    // Once the body is final, compress it and answer conditional GETs
+    let routes = warp::method()
+        .and(warp::header::headers_cloned())
+        .and(routes)
+        .then(|method: Method, headers: HeaderMap, reply: Box<dyn warp::Reply>| async move {
+            let header = |name| headers.get(name).and_then(|value: &HeaderValue| value.to_str().ok());
+            encoding::finish(&method, header(ACCEPT_ENCODING), header(IF_NONE_MATCH), warp::Reply::into_response(reply)).await
+        });
+
+    #[cfg(feature = "metrics")]
+    let routes = routes.with(warp::log::custom(record_latency));
+
+    routes.with(warp::trace::request())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{AuthConfig, RateLimitConfig};
+    use poker_core::chips::Chips;
+    use poker_core::game_controller::GameController;
+    use poker_core::player::PlayerAction;
+    use poker_core::variant::GameVariant;
+    use poker_core::solver::{ComboStrategy, SolverSpot, SolverStrategy};
+    use crate::table_registry::{TableHandle, DEFAULT_TABLE_ID};
+
+    fn test_state() -> Arc<AppState> {
+        let mut controller = GameController::new();
+        controller.initialize_players(vec![
+            ("1".to_string(), "Alice".to_string(), 0, Chips::new(1000)),
+            ("2".to_string(), "Bob".to_string(), 1, Chips::new(1000)),
+        ]);
+        Arc::new(AppState {
+            tables: TableRegistry::new(TableHandle::new(DEFAULT_TABLE_ID, controller)),
+            rate_limiter: RateLimiter::new(&RateLimitConfig::default()),
+            authenticator: Authenticator::new(&AuthConfig {
+                open_login: true,
+                admin_token: Some("host-token".to_string()),
+                ..AuthConfig::default()
+            }),
+            idempotency: IdempotencyCache::new(),
+            profiles: ProfileStore::new(),
+            bankroll: Arc::new(Bankroll::new()),
+            social: Social::new(),
+            logins: LoginAddresses::new(),
+            solver: None,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_versioned_and_legacy_paths() {
+        let routes = get_routes(test_state(), &CorsConfig::default());
+
+        for path in ["/v1/reset", "/reset", "/v1/openapi.json"] {
+            let response = warp::test::request().path(path).reply(&routes).await;
+            assert_eq!(response.status(), 200, "{path}");
+        }
+
+        let response = warp::test::request().path("/v2/reset").reply(&routes).await;
+        assert_eq!(response.status(), 404);
+    }
+
+    #[tokio::test]
+    async fn test_accept_language() {
+        let routes = get_routes(test_state(), &CorsConfig::default());
+
+        let response = warp::test::request().path("/v2/reset").header("accept-language", "es-MX, en;q=0.5").reply(&routes).await;
+        assert_eq!(response.status(), 404);
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(body["message"], "Ruta no encontrada");
+
+        warp::test::request().path("/v1/deal_hole").reply(&routes).await;
+        warp::test::request().path("/v1/deal_community").reply(&routes).await;
+        let response = warp::test::request().path("/v1/evaluate").header("accept-language", "fr").reply(&routes).await;
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        let french = ["Carte haute", "Paire", "Double paire", "Brelan", "Quinte", "Couleur", "Full", "Carré", "Quinte flush"];
+        let description = body["players"][0]["hand_description"].as_str().unwrap();
+        assert!(french.contains(&description), "{body}");
+    }
+
+    #[tokio::test]
+    async fn test_evaluation_with_percentile() {
+        let routes = get_routes(test_state(), &CorsConfig::default());
+        warp::test::request().path("/v1/deal_hole").reply(&routes).await;
+        warp::test::request().path("/v1/deal_community").reply(&routes).await;
+
+        let response = warp::test::request().path("/v1/evaluate").reply(&routes).await;
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert!(body["players"][0].get("percentile").is_none());
+
+        let response = warp::test::request().path("/v1/evaluate?percentile=true").reply(&routes).await;
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        let percentile = body["players"][0]["percentile"].as_f64().unwrap();
+        assert!((0.0..=1.0).contains(&percentile), "{body}");
+    }
+
+    /// Posts `body` to a login route and returns the issued token.
+    async fn login<F>(routes: &F, path: &str, body: serde_json::Value) -> String
+    where
+        F: Filter + Clone + 'static,
+        F::Extract: warp::Reply + Send,
+    {
+        let response = warp::test::request().method("POST").path(path).json(&body).reply(routes).await;
+        assert_eq!(response.status(), 200);
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        body["token"].as_str().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn test_retried_action_is_taken_once() {
+        let state = test_state();
+        let routes = get_routes(state.clone(), &CorsConfig::default());
+        let table = state.tables.default_table();
+        let first = table.try_call(|controller| {
+            controller.start_hand(Chips::new(5), Chips::new(10))?;
+            Ok(controller.next_to_act().map(str::to_string))
+        }).await.unwrap().unwrap();
+        let token = login(&routes, "/v1/login", serde_json::json!({"player_id": first})).await;
+        let response = warp::test::request().path("/v1/me").header("authorization", format!("Bearer {token}")).reply(&routes).await;
+        let version = serde_json::from_slice::<serde_json::Value>(response.body()).unwrap()["state_version"].clone();
+        let act = |key: &str, action: serde_json::Value| {
+            warp::test::request()
+                .method("POST")
+                .path("/v1/action")
+                .header("authorization", format!("Bearer {token}"))
+                .header("idempotency-key", key)
+                .json(&serde_json::json!({"action": action, "state_version": version}))
+        };
+
+        // The retry of the call gets the same answer instead of a second call out of turn
+        let response = act("retry-me", serde_json::json!("call")).reply(&routes).await;
+        assert_eq!(response.status(), 200);
+        let retried = act("retry-me", serde_json::json!("call")).reply(&routes).await;
+        assert_eq!((retried.status(), retried.body()), (response.status(), response.body()));
+        let calls = table.call(|controller| controller.hand_log().map(|log| log.actions.len())).await.unwrap();
+        assert_eq!(calls, Some(1));
+
+        assert_eq!(act("retry-me", serde_json::json!({"raise": 20})).reply(&routes).await.status(), 400);
+        assert_eq!(act("fresh", serde_json::json!("call")).reply(&routes).await.status(), 409); // Acting on the state before the call
+    }
+
+    #[tokio::test]
+    async fn test_action_on_stale_state_is_refused() {
+        let state = test_state();
+        let routes = get_routes(state.clone(), &CorsConfig::default());
+        let table = state.tables.default_table();
+        let first = table.try_call(|controller| {
+            controller.start_hand(Chips::new(5), Chips::new(10))?;
+            Ok(controller.next_to_act().map(str::to_string))
+        }).await.unwrap().unwrap();
+        let token = login(&routes, "/v1/login", serde_json::json!({"player_id": first})).await;
+        let response = warp::test::request().path("/v1/me").header("authorization", format!("Bearer {token}")).reply(&routes).await;
+        let seat: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert!(seat["action"].is_object());
+        assert_eq!((&seat["table_id"], &seat["hand_id"]), (&serde_json::json!("main"), &serde_json::json!("main:1")));
+        let act = |version: &serde_json::Value| {
+            warp::test::request()
+                .method("POST")
+                .path("/v1/action")
+                .header("authorization", format!("Bearer {token}"))
+                .json(&serde_json::json!({"action": "call", "state_version": version}))
+        };
+
+        // Something happened at the table after the seat was fetched
+        table.call(|controller| controller.pause()).await.unwrap();
+        let response = act(&seat["state_version"]).reply(&routes).await;
+        assert_eq!(response.status(), 409);
+        let response = warp::test::request().path("/v1/me").header("authorization", format!("Bearer {token}")).reply(&routes).await;
+        let fresh: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(fresh["state_version"], seat["state_version"].as_u64().unwrap() + 1);
+        let response = act(&fresh["state_version"]).reply(&routes).await;
+        assert_eq!(response.status(), 200);
+        let after: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert!(after["state_version"].as_u64() > fresh["state_version"].as_u64());
+    }
+
+    #[tokio::test]
+    async fn test_admin_routes_require_admin_role() {
+        let routes = get_routes(test_state(), &CorsConfig::default());
+
+        let player_token = login(&routes, "/v1/login", serde_json::json!({"player_id": "1"})).await;
+        let response = warp::test::request()
+            .method("POST")
+            .path("/v1/admin/pause")
+            .header("authorization", format!("Bearer {player_token}"))
+            .reply(&routes)
+            .await;
+        assert_eq!(response.status(), 403);
+
+        let admin_token = login(&routes, "/v1/admin/login", serde_json::json!({"admin_token": "host-token"})).await;
+        let response = warp::test::request()
+            .method("POST")
+            .path("/v1/admin/players/2/chips")
+            .header("authorization", format!("Bearer {admin_token}"))
+            .json(&serde_json::json!({"delta": 500}))
+            .reply(&routes)
+            .await;
+        assert_eq!(response.status(), 200);
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(body["chip_stack"], 1500);
+    }
+
+    #[tokio::test]
+    async fn test_pause_and_resume_a_table() {
+        let state = test_state();
+        let routes = get_routes(state.clone(), &CorsConfig::default());
+        let admin_token = login(&routes, "/v1/admin/login", serde_json::json!({"admin_token": "host-token"})).await;
+        let post = |path: &str| warp::test::request().method("POST").path(path).header("authorization", format!("Bearer {admin_token}"));
+        let paused = || async {
+            let response = warp::test::request().path("/v1/tables/main/spectate").reply(&routes).await;
+            serde_json::from_slice::<serde_json::Value>(response.body()).unwrap()["paused"].clone()
+        };
+
+        assert_eq!(post("/v1/admin/tables/main/pause").reply(&routes).await.status(), 200);
+        assert_eq!(paused().await, true);
+        let dealt = state.tables.default_table().try_call(|controller| controller.start_hand(Chips::new(5), Chips::new(10))).await;
+        assert!(matches!(dealt, Err(GameError::InvalidState(_))));
+        assert_eq!(post("/v1/admin/tables/side/pause").reply(&routes).await.status(), 404);
+
+        // The unscoped path acts on the default table
+        assert_eq!(post("/v1/admin/resume").reply(&routes).await.status(), 200);
+        assert_eq!(paused().await, false);
+    }
+
+    #[tokio::test]
+    async fn test_create_a_table() {
+        let state = test_state();
+        let routes = get_routes(state.clone(), &CorsConfig::default());
+        let admin_token = login(&routes, "/v1/admin/login", serde_json::json!({"admin_token": "host-token"})).await;
+        let create = |body: serde_json::Value| {
+            warp::test::request().method("POST").path("/v1/tables").header("authorization", format!("Bearer {admin_token}")).json(&body)
+        };
+
+        let response = create(serde_json::json!({
+            "table_id": "omaha-1",
+            "variant": "omaha",
+            "small_blind": 25,
+            "big_blind": 50,
+            "buy_in": {"min_buy_in": 1000, "max_buy_in": 5000},
+            "max_seats": 6,
+        })).reply(&routes).await;
+        assert_eq!(response.status(), 200);
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!((body["type"].as_str(), body["max_seats"].as_u64(), body["decks"].as_u64()), (Some("table_created"), Some(6), Some(1)));
+
+        let table = state.tables.get("omaha-1").unwrap();
+        let (variant, blinds) = table.call(|controller| (controller.variant(), controller.blinds())).await.unwrap();
+        assert_eq!((variant, blinds), (GameVariant::Omaha, Some((Chips::new(25), Chips::new(50)))));
+        let dana = login(&routes, "/v1/login", serde_json::json!({"player_id": "4", "table_id": "omaha-1"})).await;
+        let join = warp::test::request()
+            .method("POST")
+            .path("/v1/tables/omaha-1/join")
+            .header("authorization", format!("Bearer {dana}"))
+            .json(&serde_json::json!({"display_name": "Dana", "chip_stack": 500}))
+            .reply(&routes)
+            .await;
+        assert_eq!(join.status(), 400); // Below the table's minimum buy-in
+
+        assert_eq!(create(serde_json::json!({"table_id": "omaha-1"})).reply(&routes).await.status(), 409);
+        assert_eq!(create(serde_json::json!({"table_id": "bad:id"})).reply(&routes).await.status(), 400);
+        assert_eq!(create(serde_json::json!({"table_id": "tiny", "max_seats": 1})).reply(&routes).await.status(), 400);
+        assert!(state.tables.get("tiny").is_err());
+        let anonymous = warp::test::request().method("POST").path("/v1/tables").json(&serde_json::json!({"table_id": "x"})).reply(&routes).await;
+        assert_eq!(anonymous.status(), 401);
+    }
+
+    #[tokio::test]
+    async fn test_profile_crud() {
+        let routes = get_routes(test_state(), &CorsConfig::default());
+        let alice = login(&routes, "/v1/login", serde_json::json!({"player_id": "1"})).await;
+        let bob = login(&routes, "/v1/login", serde_json::json!({"player_id": "2"})).await;
+        let request = |method: &str, token: &str| {
+            warp::test::request().method(method).path("/v1/players/1/profile").header("authorization", format!("Bearer {token}"))
+        };
+        let profile = serde_json::json!({"display_name": "Alice", "country": "NZ", "preferences": {"four_color_deck": true}});
+
+        assert_eq!(warp::test::request().path("/v1/players/1/profile").reply(&routes).await.status(), 404);
+        assert_eq!(request("PUT", &bob).json(&profile).reply(&routes).await.status(), 403);
+        assert_eq!(request("PUT", &alice).json(&profile).reply(&routes).await.status(), 200);
+        let invalid = request("PUT", &alice).json(&serde_json::json!({"display_name": "Alice", "avatar_url": "ftp://x", "country": "nz"})).reply(&routes).await;
+        assert_eq!(invalid.status(), 400);
+
+        let response = warp::test::request().path("/v1/players/1/profile").reply(&routes).await;
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!((body["type"].as_str(), body["country"].as_str()), (Some("profile"), Some("NZ")));
+        assert_eq!(body["preferences"]["four_color_deck"], true);
+
+        assert_eq!(request("DELETE", &alice).reply(&routes).await.status(), 200);
+        assert_eq!(request("DELETE", &alice).reply(&routes).await.status(), 404);
+    }
+
+    #[tokio::test]
+    async fn test_tracked_table_draws_on_the_balance() {
+        let routes = get_routes(test_state(), &CorsConfig::default());
+        let admin_token = login(&routes, "/v1/admin/login", serde_json::json!({"admin_token": "host-token"})).await;
+        let admin = |path: &str, body: serde_json::Value| {
+            warp::test::request().method("POST").path(path).header("authorization", format!("Bearer {admin_token}")).json(&body)
+        };
+        let config = serde_json::json!({"table_id": "cash", "currency": "tracked"});
+        assert_eq!(admin("/v1/tables", config).reply(&routes).await.status(), 200);
+        assert_eq!(admin("/v1/admin/players/1/balance", serde_json::json!({"delta": 500})).reply(&routes).await.status(), 200);
+
+        let alice = login(&routes, "/v1/login", serde_json::json!({"player_id": "1", "table_id": "cash"})).await;
+        let join = |chip_stack: u64| {
+            warp::test::request()
+                .method("POST")
+                .path("/v1/tables/cash/join")
+                .header("authorization", format!("Bearer {alice}"))
+                .json(&serde_json::json!({"display_name": "Alice", "chip_stack": chip_stack}))
+        };
+        assert_eq!(join(600).reply(&routes).await.status(), 409);
+        assert_eq!(join(300).reply(&routes).await.status(), 200);
+
+        let response = warp::test::request().path("/v1/balance").header("authorization", format!("Bearer {alice}")).reply(&routes).await;
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!((body["type"].as_str(), body["balance"].as_u64()), (Some("balance"), Some(200)));
+        let response = warp::test::request().path("/v1/me").header("authorization", format!("Bearer {alice}")).reply(&routes).await;
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(body["chip_stack"], 300); // The session acts at the table it was issued for
+    }
+
+    #[tokio::test]
+    async fn test_only_the_caller_can_spend_their_balance_on_a_seat() {
+        let state = test_state();
+        let routes = get_routes(state.clone(), &CorsConfig::default());
+        let admin_token = login(&routes, "/v1/admin/login", serde_json::json!({"admin_token": "host-token"})).await;
+        let admin = |path: &str, body: serde_json::Value| {
+            warp::test::request().method("POST").path(path).header("authorization", format!("Bearer {admin_token}")).json(&body)
+        };
+        assert_eq!(admin("/v1/tables", serde_json::json!({"table_id": "cash", "currency": "tracked"})).reply(&routes).await.status(), 200);
+        assert_eq!(admin("/v1/admin/players/1/balance", serde_json::json!({"delta": 500})).reply(&routes).await.status(), 200);
+        let join = || warp::test::request().method("POST").path("/v1/tables/cash/join");
+
+        let anonymous = join().json(&serde_json::json!({"display_name": "Alice", "chip_stack": 300})).reply(&routes).await;
+        assert_eq!(anonymous.status(), 401);
+        let bob = login(&routes, "/v1/login", serde_json::json!({"player_id": "2", "table_id": "cash"})).await;
+        let for_alice = join()
+            .header("authorization", format!("Bearer {bob}"))
+            .json(&serde_json::json!({"player_id": "1", "display_name": "Alice", "chip_stack": 300}))
+            .reply(&routes)
+            .await;
+        assert_eq!(for_alice.status(), 400);
+        let elsewhere = login(&routes, "/v1/login", serde_json::json!({"player_id": "1"})).await;
+        let wrong_table = join()
+            .header("authorization", format!("Bearer {elsewhere}"))
+            .json(&serde_json::json!({"display_name": "Alice", "chip_stack": 300}))
+            .reply(&routes)
+            .await;
+        assert_eq!(wrong_table.status(), 403);
+
+        assert_eq!(state.bankroll.balance("1"), Chips::new(500));
+        let seated = state.tables.get("cash").unwrap().call(|controller| controller.get_players().len()).await.unwrap();
+        assert_eq!(seated, 0);
+    }
+
+    #[tokio::test]
+    async fn test_invite_a_friend_to_a_table() {
+        let state = test_state();
+        let routes = get_routes(state.clone(), &CorsConfig::default());
+        let admin_token = login(&routes, "/v1/admin/login", serde_json::json!({"admin_token": "host-token"})).await;
+        let create = warp::test::request()
+            .method("POST")
+            .path("/v1/tables")
+            .header("authorization", format!("Bearer {admin_token}"))
+            .json(&serde_json::json!({"table_id": "side"}))
+            .reply(&routes)
+            .await;
+        assert_eq!(create.status(), 200);
+        let alice = login(&routes, "/v1/login", serde_json::json!({"player_id": "1"})).await;
+        let bob = login(&routes, "/v1/login", serde_json::json!({"player_id": "2"})).await;
+        let request = |method: &str, path: &str, token: &str| {
+            warp::test::request().method(method).path(path).header("authorization", format!("Bearer {token}"))
+        };
+        let invite = || request("POST", "/v1/invite", &alice).json(&serde_json::json!({"friend_id": "2", "table_id": "side"}));
+
+        assert_eq!(request("PUT", "/v1/friends/2", &alice).reply(&routes).await.status(), 200);
+        assert_eq!(invite().reply(&routes).await.status(), 403); // Bob hasn't added Alice back
+        let response = request("PUT", "/v1/friends/1", &bob).reply(&routes).await;
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(body["friends"], serde_json::json!([{"player_id": "1", "mutual": true}]));
+
+        let response = invite().reply(&routes).await;
+        assert_eq!(response.status(), 200);
+        let id = serde_json::from_slice::<serde_json::Value>(response.body()).unwrap()["id"].as_u64().unwrap();
+        assert_eq!(invite().reply(&routes).await.status(), 409);
+        let response = request("GET", "/v1/invitations", &bob).reply(&routes).await;
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!((body["invitations"][0]["id"].as_u64(), body["invitations"][0]["from"].as_str()), (Some(id), Some("1")));
+
+        let accept = |token: &str| request("POST", &format!("/v1/invitations/{id}/accept"), token).json(&serde_json::json!({"display_name": "Bob", "chip_stack": 1000}));
+        assert_eq!(accept(&alice).reply(&routes).await.status(), 403);
+        assert_eq!(accept(&bob).reply(&routes).await.status(), 200);
+        assert_eq!(accept(&bob).reply(&routes).await.status(), 400); // Already answered
+        let table = state.tables.get("side").unwrap();
+        let seated = table.call(|controller| controller.get_players().iter().map(|player| player.player_id.to_string()).collect::<Vec<_>>()).await.unwrap();
+        assert_eq!(seated, ["2"]);
+    }
+
+    #[tokio::test]
+    async fn test_audit_log_export() {
+        let routes = get_routes(test_state(), &CorsConfig::default());
+        warp::test::request().path("/v1/deal_hole").reply(&routes).await;
+        let admin_token = login(&routes, "/v1/admin/login", serde_json::json!({"admin_token": "host-token"})).await;
+
+        let response = warp::test::request()
+            .path("/v1/admin/tables/main/audit")
+            .header("authorization", format!("Bearer {admin_token}"))
+            .reply(&routes)
+            .await;
+        assert_eq!(response.status(), 200);
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(body["intact"], true);
+        let kinds: Vec<&str> = body["entries"].as_array().unwrap().iter().map(|entry| entry["type"].as_str().unwrap()).collect();
+        assert_eq!(kinds, ["deck_shuffled", "hole_cards", "hole_cards"]);
+        assert_eq!(body["entries"][1]["prev_hash"], body["entries"][0]["hash"]);
+    }
+
+    #[tokio::test]
+    async fn test_ledger_listing() {
+        let routes = get_routes(test_state(), &CorsConfig::default());
+        let admin_token = login(&routes, "/v1/admin/login", serde_json::json!({"admin_token": "host-token"})).await;
+        let ledger = |path: &str| warp::test::request().path(path).header("authorization", format!("Bearer {admin_token}")).reply(&routes);
+
+        let response = ledger("/v1/admin/tables/main/ledger?player_id=1").await;
+        assert_eq!(response.status(), 200);
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(body["reconciled"], true);
+        assert_eq!(body["transactions"][0]["kind"], "seated");
+        assert_eq!(body["transactions"][0]["from"], "cashier");
+        assert_eq!(body["transactions"][0]["to"], serde_json::json!({"player": "1"}));
+        assert_eq!(ledger("/v1/admin/tables/nowhere/ledger").await.status(), 404);
+    }
+
+    #[tokio::test]
+    async fn test_collusion_report() {
+        let routes = get_routes(test_state(), &CorsConfig::default());
+        let player_token = login(&routes, "/v1/login", serde_json::json!({"player_id": "1"})).await;
+        let admin_token = login(&routes, "/v1/admin/login", serde_json::json!({"admin_token": "host-token"})).await;
+        let report = |token: &str, path: &str| {
+            warp::test::request().path(path).header("authorization", format!("Bearer {token}")).reply(&routes)
+        };
+
+        assert_eq!(report(&player_token, "/v1/admin/tables/main/collusion").await.status(), 403);
+        assert_eq!(report(&admin_token, "/v1/admin/tables/nowhere/collusion").await.status(), 404);
+        let response = report(&admin_token, "/v1/admin/tables/main/collusion").await;
+        assert_eq!(response.status(), 200);
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!((body["hands_analyzed"].as_u64(), body["flags"].as_array().map(Vec::len)), (Some(0), Some(0)));
+    }
+
+    #[tokio::test]
+    async fn test_outs_analysis() {
+        let routes = get_routes(test_state(), &CorsConfig::default());
+
+        let response = warp::test::request().path("/v1/analysis/outs?hole_cards=9h,Th&board=Jh,Qc,2h").reply(&routes).await;
+        assert_eq!(response.status(), 200);
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(body["hand"], "HighCard");
+        assert_eq!(body["draws"], serde_json::json!(["flush_draw", "open_ended"]));
+        assert_eq!(body["out_count"], 29);
+
+        let response = warp::test::request().path("/v1/analysis/outs?hole_cards=9h,Th&board=Jh,9h,2h").reply(&routes).await;
+        assert_eq!(response.status(), 400);
+    }
+
+    #[tokio::test]
+    async fn test_equity_vs_range() {
+        let routes = get_routes(test_state(), &CorsConfig::default());
+        let request = |body: serde_json::Value| warp::test::request().method("POST").path("/v1/equity_vs_range").json(&body);
+
+        let response = request(serde_json::json!({"hero": "Ah Kh", "range": "AA, KK, QQ", "board": "Ad 7c 2s 9d Tc"})).reply(&routes).await;
+        assert_eq!(response.status(), 200);
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(body["combo_count"], 10); // The hero's cards leave one pair of aces and three of kings
+        assert_eq!(body["equity"], 0.9);
+        assert_eq!(body["combos"][0]["cards"][0]["rank"], "Ace");
+        assert_eq!(body["combos"][0]["equity"], 0.0);
+
+        let preflop = request(serde_json::json!({"hero": "Ah Kh", "range": "22+", "trials": 200})).reply(&routes).await;
+        assert_eq!(preflop.status(), 200);
+        assert_eq!(request(serde_json::json!({"hero": "Ah Kh", "range": "AKx"})).reply(&routes).await.status(), 400);
+        assert_eq!(request(serde_json::json!({"hero": "Ah Kh", "range": "AA", "trials": 1_000_000})).reply(&routes).await.status(), 400);
+    }
+
+    /// Checks every hand out of position half the time and bets the rest.
+    struct HalfAndHalf;
+
+    impl SolverBackend for HalfAndHalf {
+        fn name(&self) -> &str {
+            "half_and_half"
+        }
+
+        fn solve(&self, spot: &SolverSpot) -> Result<SolverStrategy, GameError> {
+            let combos = spot.live_combos(0).into_iter().map(|cards| ComboStrategy { cards, frequencies: vec![0.5, 0.5] }).collect();
+            Ok(SolverStrategy::new(vec!["check".to_string(), "bet 75".to_string()], combos).unwrap())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_solver_strategy() {
+        let request = |body: serde_json::Value| warp::test::request().method("POST").path("/v1/analysis/solve").json(&body);
+        let spot = serde_json::json!({"ranges": ["AA, KK", "AK"], "board": "Ah Kd 2c", "pot": 100, "effective_stack": 900});
+
+        let routes = get_routes(test_state(), &CorsConfig::default());
+        assert_eq!(request(spot.clone()).reply(&routes).await.status(), 403);
+
+        let state = Arc::new(AppState { solver: Some(Arc::new(HalfAndHalf)), ..Arc::into_inner(test_state()).unwrap() });
+        let routes = get_routes(state, &CorsConfig::default());
+        let response = request(spot).reply(&routes).await;
+        assert_eq!(response.status(), 200);
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!((&body["type"], &body["solver"]), (&serde_json::json!("solver_strategy"), &serde_json::json!("half_and_half")));
+        assert_eq!(body["actions"], serde_json::json!([{"action": "check", "frequency": 0.5}, {"action": "bet 75", "frequency": 0.5}]));
+        assert_eq!(body["combos"].as_array().unwrap().len(), 6); // The board blocks half the aces and kings
+
+        let one_range = serde_json::json!({"ranges": ["AA"], "board": "Ah Kd 2c", "pot": 100, "effective_stack": 900});
+        assert_eq!(request(one_range).reply(&routes).await.status(), 400);
+        let preflop = serde_json::json!({"ranges": ["AA", "KK"], "board": "", "pot": 100, "effective_stack": 900});
+        assert_eq!(request(preflop).reply(&routes).await.status(), 400);
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "debug-routes")]
+    async fn test_debug_routes() {
+        let routes = get_routes(test_state(), &CorsConfig::default());
+        let admin_token = login(&routes, "/v1/admin/login", serde_json::json!({"admin_token": "host-token"})).await;
+        let stack = |cards: &str| {
+            warp::test::request()
+                .method("POST")
+                .path("/v1/debug/set_deck")
+                .header("authorization", format!("Bearer {admin_token}"))
+                .json(&serde_json::json!({"cards": cards}))
+        };
+        assert_eq!(stack("Ah Ah").reply(&routes).await.status(), 400);
+        assert_eq!(stack("Ah Ad 7c 2d").reply(&routes).await.status(), 200);
+
+        let response = warp::test::request().path("/v1/deal_hole").reply(&routes).await;
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(body["players"][1]["hole_cards"][0], serde_json::json!({"rank": "Seven", "suit": "Clubs"}));
+
+        let response = warp::test::request()
+            .path("/v1/debug/invariants")
+            .header("authorization", format!("Bearer {admin_token}"))
+            .reply(&routes)
+            .await;
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(body["ok"], true, "{body}");
+    }
+
+    #[tokio::test]
+    async fn test_add_bot_seats_a_computer_player() {
+        let state = test_state();
+        let routes = get_routes(state.clone(), &CorsConfig::default());
+        let admin_token = login(&routes, "/v1/admin/login", serde_json::json!({"admin_token": "host-token"})).await;
+        let add = |player_id: &str| {
+            warp::test::request()
+                .method("POST")
+                .path("/add_bot")
+                .header("authorization", format!("Bearer {admin_token}"))
+                .json(&serde_json::json!({"player_id": player_id, "display_name": "Robo", "chip_stack": 500, "difficulty": "hard"}))
+        };
+
+        let response = add("bot").reply(&routes).await;
+        assert_eq!(response.status(), 200);
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(body["chip_stack"], 500);
+        assert_eq!(add("bot").reply(&routes).await.status(), 400);
+
+        let is_bot = state.tables.default_table().call(|controller| controller.bot("bot").map(|bot| bot.name())).await.unwrap();
+        assert_eq!(is_bot, Some("rule_based"));
+    }
+
+    #[tokio::test]
+    async fn test_login_then_access_own_seat() {
+        let routes = get_routes(test_state(), &CorsConfig::default());
+
+        let response = warp::test::request().path("/v1/me").reply(&routes).await;
+        assert_eq!(response.status(), 401);
+
+        let token = login(&routes, "/v1/login", serde_json::json!({"player_id": "2"})).await;
+
+        let response = warp::test::request()
+            .path("/v1/me")
+            .header("authorization", format!("Bearer {token}"))
+            .reply(&routes)
+            .await;
+        assert_eq!(response.status(), 200);
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(body["name"], "Bob");
+        assert_eq!((&body["chips_won"], &body["ev_chips_won"]), (&serde_json::json!(0), &serde_json::json!(0.0)));
+        assert!(body.get("action").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_own_seat_shows_the_price_of_acting() {
+        let state = test_state();
+        let routes = get_routes(state.clone(), &CorsConfig::default());
+        state.tables.default_table().call(|controller| controller.start_hand(Chips::new(5), Chips::new(10))).await.unwrap().unwrap();
+        let token = login(&routes, "/v1/login", serde_json::json!({"player_id": "1"})).await;
+
+        let response = warp::test::request()
+            .path("/v1/me")
+            .header("authorization", format!("Bearer {token}"))
+            .reply(&routes)
+            .await;
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(body["action"], serde_json::json!({"to_call": 5, "pot": 15, "pot_odds": 0.25, "effective_stack": 990, "min_raise_to": 20}));
+    }
+
+    #[tokio::test]
+    async fn test_hints_at_training_tables() {
+        let state = test_state();
+        let routes = get_routes(state.clone(), &CorsConfig::default());
+        state.tables.default_table().call(|controller| controller.start_hand(Chips::new(5), Chips::new(10))).await.unwrap().unwrap();
+        let (alice, bob) = (
+            login(&routes, "/v1/login", serde_json::json!({"player_id": "1"})).await,
+            login(&routes, "/v1/login", serde_json::json!({"player_id": "2"})).await,
+        );
+        let hint = |token: &str, player_id: &str| {
+            warp::test::request()
+                .path(&format!("/v1/hint?player_id={player_id}"))
+                .header("authorization", format!("Bearer {token}"))
+        };
+
+        assert_eq!(hint(&alice, "1").reply(&routes).await.status(), 403);
+        state.tables.default_table().call(|controller| controller.set_training_mode(true)).await.unwrap();
+        let response = hint(&alice, "1").reply(&routes).await;
+        assert_eq!(response.status(), 200);
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!((&body["type"], &body["position"], &body["pot_odds"]), (&serde_json::json!("hint"), &serde_json::json!("button"), &serde_json::json!(0.25)));
+        assert!(!body["rationale"].as_str().unwrap().is_empty());
+
+        assert_eq!(hint(&alice, "2").reply(&routes).await.status(), 403); // Someone else's seat
+        assert_eq!(hint(&bob, "2").reply(&routes).await.status(), 409);  // Not Bob's turn
+    }
+
+    #[tokio::test]
+    async fn test_hand_history_export() {
+        let state = test_state();
+        let routes = get_routes(state.clone(), &CorsConfig::default());
+        state.tables.default_table().try_call(|controller| {
+            controller.start_hand(Chips::new(5), Chips::new(10))?;
+            controller.act("1", PlayerAction::Fold)
+        }).await.unwrap();
+        let token = login(&routes, "/v1/login", serde_json::json!({"player_id": "2"})).await;
+        let export = |path: &str| warp::test::request().path(path).header("authorization", format!("Bearer {token}"));
+
+        let response = export("/v1/history/1/export?format=stars").reply(&routes).await;
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.headers()["content-type"], "text/plain; charset=utf-8");
+        let text = std::str::from_utf8(response.body()).unwrap();
+        assert!(text.starts_with("PokerStars Hand #1: Hold'em No Limit (5/10)"), "{text}");
+        assert!(text.contains("Dealt to ") && text.contains("Alice: folds"), "{text}");
+
+        let response = export("/v1/history/main:1/export?format=phh").reply(&routes).await;
+        assert_eq!(response.headers()["content-type"], "application/toml; charset=utf-8");
+        assert!(std::str::from_utf8(response.body()).unwrap().contains(r#"variant = "NT""#));
+
+        assert_eq!(export("/v1/history/2/export?format=stars").reply(&routes).await.status(), 404);
+        assert_eq!(export("/v1/history/side:1/export?format=stars").reply(&routes).await.status(), 400);
+        assert_eq!(export("/v1/history/1/export?format=ipoker").reply(&routes).await.status(), 400);
+        assert_eq!(warp::test::request().path("/v1/history/1/export?format=stars").reply(&routes).await.status(), 401);
+
+        let response = export("/v1/history/export?dataset=results&columns=player_id,net").reply(&routes).await;
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.headers()["content-type"], "text/csv; charset=utf-8");
+        assert_eq!(response.headers()["content-disposition"], "attachment; filename=\"results.csv\"");
+        assert_eq!(response.body(), "player_id,net\r\n1,-5\r\n2,5\r\n");
+        // Nothing had finished before the epoch
+        assert_eq!(export("/v1/history/export?dataset=hands&columns=hand_number&to=1").reply(&routes).await.body(), "hand_number\r\n");
+        assert_eq!(export("/v1/history/export?dataset=actions&columns=rake").reply(&routes).await.status(), 400);
+
+        let response = export("/v1/history?player_id=2&max_pot=10").reply(&routes).await;
+        assert_eq!(response.status(), 200);
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(body["type"], "hand_history");
+        assert_eq!(body["hands"][0]["hand_number"], 1);
+        assert_eq!(body["hands"][0]["pot"], 10);
+        assert_eq!(body["hands"][0]["winners"], serde_json::json!(["2"]));
+        assert!(body.get("next_cursor").is_none());
+        for query in ["min_hand_rank=HighCard", "variant=omaha", "min_pot=11", "cursor=1"] {
+            let body: serde_json::Value = serde_json::from_slice(export(&format!("/v1/history?{query}")).reply(&routes).await.body()).unwrap();
+            assert_eq!(body["hands"], serde_json::json!([]), "{query}");
+        }
+        assert_eq!(export("/v1/history?limit=500").reply(&routes).await.status(), 400);
+
+        let response = export("/v1/sessions/2").reply(&routes).await;
+        assert_eq!(response.status(), 200);
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(body["type"], "sessions");
+        assert_eq!(body["sessions"][0]["hands_played"], 1);
+        assert_eq!(body["sessions"][0]["net"], 5);
+        assert_eq!(body["sessions"][0]["biggest_pots"][0]["pot"], 10);
+        assert_eq!(export("/v1/sessions/9").reply(&routes).await.body(), r#"{"type":"sessions","player_id":"9","sessions":[]}"#);
+    }
+
+    #[tokio::test]
+    async fn test_hand_history_import() {
+        let routes = get_routes(test_state(), &CorsConfig::default());
+        let import = |body: &str| warp::test::request().method("POST").path("/v1/history/import").body(body);
+        let history = "PokerStars Hand #7: Hold'em No Limit (5/10) - 2024/05/01 13:45:00 ET
+Table 'Elsewhere' 6-max Seat #1 is the button
+Seat 1: Carol (500 in chips)
+Seat 2: Dave (500 in chips)
+Carol: posts small blind 5
+Dave: posts big blind 10
+*** HOLE CARDS ***
+Dealt to Carol [Qs Qd]
+Carol: raises 20 to 30
+Dave: folds
+Uncalled bet (20) returned to Carol
+Carol collected 20 from pot
+*** SUMMARY ***
+";
+        let response = import(history).reply(&routes).await;
+        assert_eq!(response.status(), 200);
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(body["type"], "hand_history_import");
+        let hand = &body["hands"][0];
+        assert_eq!((&hand["hand_number"], &hand["hole_cards"][0]["name"]), (&serde_json::json!(7), &serde_json::json!("Carol")));
+        let kinds: Vec<&str> = hand["events"].as_array().unwrap().iter().map(|event| event["type"].as_str().unwrap()).collect();
+        assert_eq!(kinds, ["hand_started", "player_acted", "player_acted", "hand_finished"]);
+
+        assert_eq!(import("Carol: folds").reply(&routes).await.status(), 400);
+
+        let phh = "variant = 'NT'\nblinds_or_straddles = [5, 10]\nmin_bet = 10\nstarting_stacks = [500, 500]\nactions = ['p2 f']\n";
+        let response = warp::test::request().method("POST").path("/v1/history/import?format=phh").body(phh).reply(&routes).await;
+        assert_eq!(response.status(), 200);
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(body["hands"][0]["events"][1]["action"], "fold");
+    }
+
+    #[tokio::test]
+    async fn test_buy_in_within_limits() {
+        let routes = get_routes(test_state(), &CorsConfig::default());
+        let token = login(&routes, "/v1/login", serde_json::json!({"player_id": "1"})).await;
+        let buy = |body: serde_json::Value| {
+            warp::test::request()
+                .method("POST")
+                .path("/v1/buyin")
+                .header("authorization", format!("Bearer {token}"))
+                .json(&body)
+        };
+
+        let response = buy(serde_json::json!({"amount": 500})).reply(&routes).await;
+        assert_eq!(response.status(), 200);
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(body["chip_stack"], 1500);
+        assert_eq!(buy(serde_json::json!({"amount": 1000})).reply(&routes).await.status(), 400); // Over the maximum
+        assert_eq!(buy(serde_json::json!({"kind": "rebuy", "amount": 500})).reply(&routes).await.status(), 403); // Not busted
+        assert_eq!(buy(serde_json::json!({"kind": "add_on", "amount": 500})).reply(&routes).await.status(), 403); // No add-on
+    }
+
+    #[tokio::test]
+    async fn test_waiting_list_seats_players_as_seats_open() {
+        let state = test_state();
+        let routes = get_routes(state.clone(), &CorsConfig::default());
+        let table = state.tables.default_table();
+        table.call(|controller| controller.set_max_seats(2)).await.unwrap();
+        let carol = login(&routes, "/v1/login", serde_json::json!({"player_id": "3"})).await;
+        let token = login(&routes, "/v1/login", serde_json::json!({"player_id": "4"})).await;
+        let join = |token: &str| {
+            warp::test::request()
+                .method("POST")
+                .path("/v1/tables/main/join")
+                .header("authorization", format!("Bearer {token}"))
+                .json(&serde_json::json!({"display_name": "Dana", "chip_stack": 1000}))
+        };
+
+        let response = join(&carol).reply(&routes).await;
+        assert_eq!(response.status(), 200);
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!((&body["status"], &body["position"]), (&serde_json::json!("waiting"), &serde_json::json!(1)));
+        assert_eq!(join(&carol).reply(&routes).await.status(), 400);
+        join(&token).reply(&routes).await;
+
+        let response = warp::test::request().path("/v1/tables/main/waitlist").reply(&routes).await;
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(body["players"].as_array().unwrap().len(), 2);
+        assert_eq!((&body["seats_taken"], &body["max_seats"]), (&serde_json::json!(2), &serde_json::json!(2)));
+
+        // Waiting players can give up their place
+        let response = warp::test::request()
+            .method("DELETE")
+            .path("/v1/tables/main/waitlist")
+            .header("authorization", format!("Bearer {token}"))
+            .reply(&routes)
+            .await;
+        assert_eq!(response.status(), 200);
+
+        // Watchers hear about the open seat and who took it
+        let mut client = warp::test::ws().path("/v1/tables/main/spectate/ws").handshake(routes.clone()).await.unwrap();
+        table.try_call(|controller| controller.remove_player("1")).await.unwrap();
+        let mut kinds = Vec::new();
+        for _ in 0..3 {
+            let event: serde_json::Value = serde_json::from_str(client.recv().await.unwrap().to_str().unwrap()).unwrap();
+            kinds.push(event["type"].as_str().unwrap().to_string());
+        }
+        assert_eq!(kinds, ["player_removed", "seat_opened", "player_seated"]);
+        let seated = table.call(|controller| controller.find_player("3").map(|player| player.table_position)).await.unwrap();
+        assert_eq!(seated, Ok(0));
+    }
+
+    #[tokio::test]
+    async fn test_tournament_registration() {
+        use poker_core::tournament::TournamentRules;
+
+        let state = test_state();
+        let routes = get_routes(state.clone(), &CorsConfig::default());
+        let register = warp::test::request()
+            .method("POST")
+            .path("/v1/tables/main/register")
+            .json(&serde_json::json!({"player_id": "3", "display_name": "Cal"}));
+        assert_eq!(register.reply(&routes).await.status(), 403); // A cash table
+
+        let rules = TournamentRules { late_registration_until: 3, ..TournamentRules::default() };
+        state.tables.default_table().call(move |controller| controller.set_tournament_rules(Some(rules))).await.unwrap();
+        let response = warp::test::request()
+            .method("POST")
+            .path("/v1/tables/main/register")
+            .json(&serde_json::json!({"player_id": "3", "display_name": "Cal"}))
+            .reply(&routes)
+            .await;
+        assert_eq!(response.status(), 200);
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!((&body["status"], &body["entries"], &body["prize_pool"]), (&serde_json::json!("seated"), &serde_json::json!(1), &serde_json::json!(100)));
+
+        let response = warp::test::request().path("/v1/tables/main/tournament").reply(&routes).await;
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!((&body["registration_open"], &body["rules"]["starting_stack"]), (&serde_json::json!(true), &serde_json::json!(1500)));
+
+        // Cal still has chips, so there is nothing to re-enter
+        let token = login(&routes, "/v1/login", serde_json::json!({"player_id": "3"})).await;
+        let response = warp::test::request()
+            .method("POST")
+            .path("/v1/tables/main/reenter")
+            .header("authorization", format!("Bearer {token}"))
+            .reply(&routes)
+            .await;
+        assert_eq!(response.status(), 403);
+    }
+
+    #[tokio::test]
+    async fn test_spectator_view_hides_hole_cards() {
+        let state = test_state();
+        let routes = get_routes(state.clone(), &CorsConfig::default());
+        state.tables.default_table().try_call(|controller| controller.deal_hole_cards()).await.unwrap();
+
+        let response = warp::test::request().path("/v1/tables/main/spectate").reply(&routes).await;
+        assert_eq!(response.status(), 200);
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(body["players"][0]["has_cards"], true);
+        assert!(body["players"][0].get("hole_cards").is_none());
+
+        let response = warp::test::request().path("/v1/tables/other/spectate").reply(&routes).await;
+        assert_eq!(response.status(), 404);
+    }
+
+    #[tokio::test]
+    async fn test_spectator_websocket_receives_events() {
+        let state = test_state();
+        let routes = get_routes(state.clone(), &CorsConfig::default());
+
+        let mut client = warp::test::ws().path("/v1/tables/main/spectate/ws").handshake(routes).await.unwrap();
+        assert_eq!(state.tables.default_table().spectators.load(std::sync::atomic::Ordering::Relaxed), 1);
+
+        state.tables.default_table().call(|controller| controller.pause()).await.unwrap();
+        let message = client.recv().await.unwrap();
+        let event: serde_json::Value = serde_json::from_str(message.to_str().unwrap()).unwrap();
+        assert_eq!(event, serde_json::json!({"seq": 1, "table_id": "main", "type": "table_paused"}));
+    }
+
+    #[tokio::test]
+    async fn test_spectator_websocket_resumes_from_last_seq() {
+        let state = test_state();
+        let routes = get_routes(state.clone(), &CorsConfig::default());
+        state.tables.default_table().call(|controller| {
+            controller.pause();
+            controller.resume();
+        }).await.unwrap();
+
+        let mut client = warp::test::ws().path("/v1/tables/main/spectate/ws?last_seq=1").handshake(routes.clone()).await.unwrap();
+        state.tables.default_table().call(|controller| controller.pause()).await.unwrap();
+        for (seq, kind) in [(2, "table_resumed"), (3, "table_paused")] {
+            let event: serde_json::Value = serde_json::from_str(client.recv().await.unwrap().to_str().unwrap()).unwrap();
+            assert_eq!((&event["seq"], &event["type"]), (&serde_json::json!(seq), &serde_json::json!(kind)));
+        }
+
+        // A sequence number from before a restart can't be resumed from
+        let mut client = warp::test::ws().path("/v1/tables/main/spectate/ws?last_seq=90").handshake(routes).await.unwrap();
+        let notice: serde_json::Value = serde_json::from_str(client.recv().await.unwrap().to_str().unwrap()).unwrap();
+        assert_eq!(notice, serde_json::json!({"type": "resync", "last_seq": 3}));
+    }
+
+    #[tokio::test]
+    async fn test_spectator_websocket_tracks_presence() {
+        let state = test_state();
+        let routes = get_routes(state.clone(), &CorsConfig::default());
+        let presence = |routes| async move {
+            let response = warp::test::request().path("/v1/tables/main/spectate").reply(routes).await;
+            let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+            body["players"].as_array().unwrap().iter().map(|seat| seat["presence"].as_str().unwrap().to_string()).collect::<Vec<_>>()
+        };
+        assert_eq!(presence(&routes).await, ["disconnected", "disconnected"]);
+
+        let token = login(&routes, "/v1/login", serde_json::json!({"player_id": "1"})).await;
+        let client = warp::test::ws().path(&format!("/v1/tables/main/spectate/ws?token={token}")).handshake(routes.clone()).await.unwrap();
+        assert_eq!(presence(&routes).await, ["connected", "disconnected"]);
+        drop(client);
+        while state.tables.default_table().spectators.load(std::sync::atomic::Ordering::Relaxed) > 0 {
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(presence(&routes).await, ["disconnected", "disconnected"]);
+
+        let rejected = warp::test::ws().path("/v1/tables/main/spectate/ws?token=forged").handshake(routes).await;
+        assert!(rejected.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_invalid_body_lists_each_field() {
+        let routes = get_routes(test_state(), &CorsConfig::default());
+        let response = warp::test::request()
+            .method("POST")
+            .path("/v1/equity_vs_range")
+            .json(&serde_json::json!({"hero": "Ah Zz", "range": "QQ+", "board": "Kd Kd", "trials": 0}))
+            .reply(&routes)
+            .await;
+        assert_eq!(response.status(), 400);
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(body["message"], "Invalid request body");
+        let fields: Vec<&str> = body["errors"].as_array().unwrap().iter().map(|error| error["field"].as_str().unwrap()).collect();
+        assert_eq!(fields, ["hero", "board", "trials"]);
+    }
+
+    #[tokio::test]
+    async fn test_compression_and_etags() {
+        use std::io::Read;
+
+        let state = test_state();
+        let routes = get_routes(state.clone(), &CorsConfig::default());
+        let plain = warp::test::request().path("/v1/openapi.json").reply(&routes).await;
+        assert!(plain.headers().get("content-encoding").is_none());
+        let response = warp::test::request().path("/v1/openapi.json").header("accept-encoding", "gzip").reply(&routes).await;
+        assert_eq!(response.headers()["content-encoding"], "gzip");
+        let mut unzipped = Vec::new();
+        flate2::read::GzDecoder::new(&response.body()[..]).read_to_end(&mut unzipped).unwrap();
+        assert_eq!(unzipped, plain.body().to_vec());
+        assert_eq!(response.headers()["etag"], plain.headers()["etag"]);
+
+        // Unchanged state isn't sent again; once the table moves on it is
+        let response = warp::test::request().path("/v1/tables/main/spectate").reply(&routes).await;
+        let etag = response.headers()["etag"].clone();
+        let fetch = || warp::test::request().path("/v1/tables/main/spectate").header("if-none-match", etag.clone());
+        let response = fetch().reply(&routes).await;
+        assert_eq!(response.status(), 304);
+        assert!(response.body().is_empty());
+        state.tables.default_table().call(|controller| controller.pause()).await.unwrap();
+        assert_eq!(fetch().reply(&routes).await.status(), 200);
+    }
+
+    #[tokio::test]
+    async fn test_long_poll() {
+        let state = test_state();
+        let routes = get_routes(state.clone(), &CorsConfig::default());
+        let poll = |path: &str| {
+            let (routes, path) = (routes.clone(), path.to_string());
+            async move {
+                let response = warp::test::request().path(&path).reply(&routes).await;
+                assert_eq!(response.status(), 200, "{path}");
+                serde_json::from_slice::<serde_json::Value>(response.body()).unwrap()
+            }
+        };
+        state.tables.default_table().call(|controller| controller.pause()).await.unwrap();
+
+        // Events already there come back at once; nothing new waits out the timeout
+        let body = poll("/v1/poll?since_seq=0").await;
+        assert_eq!((&body["events"][0]["type"], &body["last_seq"], &body["resync"]), (&serde_json::json!("table_paused"), &serde_json::json!(1), &serde_json::json!(false)));
+        let body = poll("/v1/poll?since_seq=1&timeout_secs=0").await;
+        assert_eq!((&body["events"], &body["last_seq"]), (&serde_json::json!([]), &serde_json::json!(1)));
+
+        // A waiting poll is answered by the next event (or finds it already there if it starts late)
+        let waiting = tokio::spawn(poll("/v1/poll?since_seq=1"));
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        state.tables.default_table().call(|controller| controller.resume()).await.unwrap();
+        let body = waiting.await.unwrap();
+        assert_eq!((&body["events"][0]["seq"], &body["last_seq"]), (&serde_json::json!(2), &serde_json::json!(2)));
+
+        assert_eq!(poll("/v1/poll?since_seq=7").await["resync"], true);
+        let response = warp::test::request().path("/v1/poll?timeout_secs=600").reply(&routes).await;
+        assert_eq!(response.status(), 400);
+    }
+
+    #[tokio::test]
+    async fn test_event_stream_resumes_from_last_event_id() {
+        use warp::Reply;
+
+        let state = test_state();
+        let routes = get_routes(state.clone(), &CorsConfig::default());
+        state.tables.default_table().call(|controller| {
+            controller.pause();
+            controller.resume();
+        }).await.unwrap();
+
+        // The stream stays open, so read just its first chunk
+        let response = warp::test::request()
+            .path("/v1/events")
+            .header("last-event-id", "1")
+            .filter(&routes)
+            .await
+            .unwrap()
+            .into_response();
+        assert_eq!(response.headers()["content-type"], "text/event-stream");
+        let mut body = response.into_body();
+        let chunk = warp::hyper::body::HttpBody::data(&mut body).await.unwrap().unwrap();
+        let text = String::from_utf8(chunk.to_vec()).unwrap();
+        assert!(text.contains("event:table_resumed"), "{text}");
+        assert!(text.contains("id:2"), "{text}");
+    }
 }
@@ -0,0 +1,197 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use poker_core::card_dealer::Card;
+use poker_core::events::DealRecord;
+use poker_core::player_id::PlayerId;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::warn;
+use utoipa::ToSchema;
+
+/// `prev_hash` of the first entry in a log.
+const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// A dealing step as the audit log keeps it. The shuffled deck is kept as a
+/// hash, which commits to its order without storing it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AuditEvent {
+    DeckShuffled { hand_number: u64, deck_hash: String }, // SHA-256 of the deck in dealing order, e.g. "AhKd..."
+    HoleCards { hand_number: u64, player_id: PlayerId, cards: Vec<Card> },
+    CommunityCards { hand_number: u64, board: usize, cards: Vec<Card> },
+    RabbitHunt { hand_number: u64, player_id: PlayerId, cards: Vec<Card> }, // Shown, not dealt
+}
+
+impl From<DealRecord> for AuditEvent {
+    fn from(record: DealRecord) -> Self {
+        match record {
+            DealRecord::DeckShuffled { hand_number, deck } => {
+                let order: String = deck.iter().map(Card::to_string).collect();
+                AuditEvent::DeckShuffled { hand_number, deck_hash: hex::encode(Sha256::digest(order.as_bytes())) }
+            }
+            DealRecord::HoleCards { hand_number, player_id, cards } => AuditEvent::HoleCards { hand_number, player_id, cards },
+            DealRecord::CommunityCards { hand_number, board, cards } => AuditEvent::CommunityCards { hand_number, board, cards },
+            DealRecord::RabbitHunt { hand_number, player_id, cards } => AuditEvent::RabbitHunt { hand_number, player_id, cards },
+        }
+    }
+}
+
+/// One link of the hash chain.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct AuditEntry {
+    pub seq: u64,           // Starts at 1 and increases by one per entry
+    pub recorded_at: u64,   // Milliseconds since the Unix epoch
+    #[serde(flatten)]
+    pub event: AuditEvent,
+    pub prev_hash: String,  // `hash` of the entry before, or all zeroes for the first
+    pub hash: String,       // SHA-256 of this entry with `hash` left empty
+}
+
+impl AuditEntry {
+    fn compute_hash(&self) -> String {
+        let unsigned = AuditEntry { hash: String::new(), ..self.clone() };
+        let json = serde_json::to_vec(&unsigned).expect("audit entries serialize");
+        hex::encode(Sha256::digest(json))
+    }
+}
+
+/// Checks that every entry's hash matches its contents and links to the entry
+/// before. Returns the `seq` of the first entry that doesn't.
+pub fn verify_chain(entries: &[AuditEntry]) -> Result<(), u64> {
+    let mut prev_hash = GENESIS_HASH;
+    for (entry, seq) in entries.iter().zip(1..) {
+        if entry.seq != seq || entry.prev_hash != prev_hash || entry.hash != entry.compute_hash() {
+            return Err(seq);
+        }
+        prev_hash = &entry.hash;
+    }
+    Ok(())
+}
+
+/// A table's append-only log of every card dealt, each entry chained to the
+/// one before by its hash, so editing, dropping or reordering entries after
+/// the fact shows up in `verify_chain`.
+///
+/// Entries are kept in memory and, once `persist_to` is called, appended to a
+/// JSON-lines file as well.
+#[derive(Default)]
+pub struct AuditLog {
+    entries: Vec<AuditEntry>,
+    file: Option<File>,
+}
+
+impl AuditLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn entries(&self) -> &[AuditEntry] {
+        &self.entries
+    }
+
+    /// Appends the log to `path` from now on, continuing the chain already
+    /// in the file. Fails if the file's chain is broken, rather than
+    /// extending a log that has been tampered with.
+    #[cfg_attr(not(feature = "persistence"), allow(dead_code))]
+    pub fn persist_to(&mut self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut entries = Vec::new();
+        if path.exists() {
+            for line in BufReader::new(File::open(path)?).lines() {
+                entries.push(serde_json::from_str(&line?).map_err(io::Error::other)?);
+            }
+        }
+        verify_chain(&entries).map_err(|seq| io::Error::other(format!("{} is broken at entry {seq}", path.display())))?;
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        let previous = std::mem::replace(&mut self.entries, entries);
+        for entry in previous {
+            self.append_to(&mut file, entry.event, entry.recorded_at)?;
+        }
+        self.file = Some(file);
+        Ok(())
+    }
+
+    /// Chains a dealing step onto the log.
+    pub fn record(&mut self, record: DealRecord) {
+        let recorded_at = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_millis() as u64);
+        let event = AuditEvent::from(record);
+        let result = match self.file.take() {
+            Some(mut file) => {
+                let result = self.append_to(&mut file, event, recorded_at);
+                self.file = Some(file);
+                result
+            }
+            None => {
+                self.push(event, recorded_at);
+                Ok(())
+            }
+        };
+        if let Err(err) = result {
+            warn!(%err, "could not write the deal audit log");
+        }
+    }
+
+    fn append_to(&mut self, file: &mut File, event: AuditEvent, recorded_at: u64) -> io::Result<()> {
+        let entry = self.push(event, recorded_at);
+        let mut line = serde_json::to_vec(entry).map_err(io::Error::other)?;
+        line.push(b'\n');
+        file.write_all(&line)
+    }
+
+    fn push(&mut self, event: AuditEvent, recorded_at: u64) -> &AuditEntry {
+        let prev_hash = self.entries.last().map_or(GENESIS_HASH.to_string(), |entry| entry.hash.clone());
+        let mut entry = AuditEntry { seq: self.entries.len() as u64 + 1, recorded_at, event, prev_hash, hash: String::new() };
+        entry.hash = entry.compute_hash();
+        self.entries.push(entry);
+        self.entries.last().expect("just pushed")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use poker_core::card_dealer::parse_cards;
+
+    fn hole_cards(cards: &str) -> DealRecord {
+        DealRecord::HoleCards { hand_number: 1, player_id: "1".into(), cards: parse_cards(cards).unwrap() }
+    }
+
+    #[test]
+    fn test_tampering_breaks_the_chain() {
+        let mut log = AuditLog::new();
+        log.record(DealRecord::DeckShuffled { hand_number: 1, deck: parse_cards("Ah Kd").unwrap() });
+        log.record(hole_cards("Ah Kd"));
+        log.record(DealRecord::CommunityCards { hand_number: 1, board: 1, cards: parse_cards("2c 3c 4c").unwrap() });
+        assert_eq!(verify_chain(log.entries()), Ok(()));
+
+        let mut edited = log.entries().to_vec();
+        edited[1].event = AuditEvent::from(hole_cards("As Ks"));
+        assert_eq!(verify_chain(&edited), Err(2));
+        let mut dropped = log.entries().to_vec();
+        dropped.remove(1);
+        assert_eq!(verify_chain(&dropped), Err(2));
+    }
+
+    #[test]
+    fn test_persisted_log_continues_the_chain() {
+        let path = std::env::temp_dir().join(format!("card_dealer_audit_{}.jsonl", std::process::id()));
+        let mut log = AuditLog::new();
+        log.record(hole_cards("Ah Kd"));
+        log.persist_to(&path).unwrap();
+        log.record(hole_cards("2c 2d"));
+
+        let mut reopened = AuditLog::new();
+        reopened.persist_to(&path).unwrap();
+        reopened.record(hole_cards("7h 2s"));
+        assert_eq!(reopened.entries().len(), 3);
+        assert_eq!(reopened.entries()[..2], log.entries()[..]);
+        assert_eq!(verify_chain(reopened.entries()), Ok(()));
+
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,118 @@
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use poker_core::events::GameEvent;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::time::{sleep, timeout};
+use tracing::{debug, info};
+
+use crate::events::SequencedEvent;
+use crate::table_registry::TableHandle;
+
+/// How long a table that can't deal waits before trying again, for changes
+/// that announce no event, such as a hand released during hand-for-hand play.
+const RETRY_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Keeps `table` dealing: starts a hand with the table's blinds, and the next
+/// one `pause` after each hand finishes.
+///
+/// Within a hand the engine already deals each street as the betting closes,
+/// shows down and pays the pots; this only starts the hands. While the table
+/// can't deal, e.g. paused, on a break or short of players, it waits for an
+/// event that may change that. It stops once the table is gone.
+pub fn spawn_auto_dealer(table: &Arc<TableHandle>, pause: Duration) {
+    info!(table_id = %table.id, pause_secs = pause.as_secs_f64(), "dealing hands automatically");
+    tokio::spawn(run_auto_dealer(Arc::downgrade(table), table.subscribe(), pause));
+}
+
+async fn run_auto_dealer(table: Weak<TableHandle>, mut events: broadcast::Receiver<Arc<SequencedEvent>>, pause: Duration) {
+    loop {
+        let Some(handle) = table.upgrade() else { return };
+        let started = handle.try_call(|controller| {
+            if controller.is_hand_in_progress() {
+                return Ok(false);
+            }
+            controller.start_next_hand().map(|()| true)
+        }).await;
+        match started {
+            Ok(true) => debug!(table_id = %handle.id, "hand dealt"),
+            Ok(false) => {}
+            Err(err) => debug!(%err, table_id = %handle.id, "no hand dealt"),
+        }
+        drop(handle);
+
+        // Leave the table a moment between hands, so players see how the last one ended
+        match timeout(RETRY_INTERVAL, next_opening(&mut events)).await {
+            Ok(Some(true)) => sleep(pause).await,
+            Ok(Some(false)) | Err(_) => {}
+            Ok(None) => return,
+        }
+    }
+}
+
+/// Waits for an event after which a hand may be dealt; `true` when it ended
+/// a hand. `None` once the table is gone.
+async fn next_opening(events: &mut broadcast::Receiver<Arc<SequencedEvent>>) -> Option<bool> {
+    loop {
+        match events.recv().await {
+            Ok(event) => match event.event {
+                GameEvent::HandFinished { .. } | GameEvent::HandCancelled { .. } => return Some(true),
+                GameEvent::TableResumed
+                | GameEvent::BreakEnded
+                | GameEvent::HandForHandEnded
+                | GameEvent::PlayerSeated { .. }
+                | GameEvent::ChipsBought { .. }
+                | GameEvent::ChipsAdjusted { .. } => return Some(false),
+                _ => continue,
+            },
+            Err(RecvError::Lagged(_)) => return Some(false), // Check the table rather than guess what was missed
+            Err(RecvError::Closed) => return None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use poker_core::bots::{Difficulty, RuleBasedBot};
+    use poker_core::chips::Chips;
+    use poker_core::game_controller::GameController;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[tokio::test]
+    async fn test_hands_are_dealt_one_after_another() {
+        let mut controller = GameController::new();
+        controller.initialize_players(vec![
+            ("1".to_string(), "Alice".to_string(), 0, Chips::new(1000)),
+            ("2".to_string(), "Bob".to_string(), 1, Chips::new(1000)),
+        ]);
+        for player_id in ["1", "2"] {
+            controller.set_bot(player_id, Box::new(RuleBasedBot::with_difficulty(Difficulty::default(), StdRng::seed_from_u64(7)))).unwrap();
+        }
+        controller.set_blinds(Chips::new(5), Chips::new(10)).unwrap();
+        controller.pause();
+        let table = TableHandle::new("auto", controller);
+        spawn_auto_dealer(&table, Duration::from_millis(20));
+
+        // Nothing is dealt while the table is paused; resuming sets it going
+        sleep(Duration::from_millis(50)).await;
+        assert_eq!(table.call(|controller| controller.hand_number()).await.unwrap(), 0);
+        table.call(|controller| controller.resume()).await.unwrap();
+        let mut hands = 0;
+        for _ in 0..100 {
+            sleep(Duration::from_millis(50)).await;
+            hands = table.call(|controller| controller.hand_number()).await.unwrap();
+            if hands >= 3 {
+                break;
+            }
+        }
+        assert!(hands >= 3, "only {hands} hands dealt");
+
+        table.call(|controller| controller.pause()).await.unwrap();
+        sleep(Duration::from_millis(50)).await;
+        let paused_at = table.call(|controller| controller.hand_number()).await.unwrap();
+        sleep(Duration::from_millis(100)).await;
+        assert_eq!(table.call(|controller| controller.hand_number()).await.unwrap(), paused_at);
+    }
+}
@@ -0,0 +1,103 @@
+//! Player balances for tracked-currency tables: the chips a player holds
+//! away from any table. Sitting down or buying in at a tracked table draws
+//! on the balance, leaving pays the stack back into it.
+
+use std::collections::BTreeMap;
+use std::io;
+use std::path::PathBuf;
+
+use poker_core::chips::Chips;
+use poker_core::currency::Bank;
+use poker_core::game_error::GameError;
+use poker_core::player_id::PlayerId;
+
+use crate::json_store::JsonStore;
+
+/// Every player's balance, optionally saved to a JSON file on each change.
+#[derive(Debug, Default)]
+pub struct Bankroll {
+    balances: JsonStore<PlayerId, Chips>,
+}
+
+impl Bankroll {
+    /// A bankroll kept in memory only.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A bankroll saved to `path`, starting from the balances already there.
+    #[cfg_attr(not(feature = "persistence"), allow(dead_code))]
+    pub fn open(path: impl Into<PathBuf>) -> io::Result<Self> {
+        Ok(Self { balances: JsonStore::open(path)? })
+    }
+
+    /// The balance of `player_id`; nothing for a player never credited.
+    pub fn balance(&self, player_id: &str) -> Chips {
+        self.balances.lock().get(player_id).copied().unwrap_or_default()
+    }
+
+    /// Adds `delta` chips to the balance of `player_id` (takes them away when
+    /// negative) and returns the new balance.
+    pub fn adjust(&self, player_id: &str, delta: i64) -> Result<Chips, GameError> {
+        let mut balances = self.balances.lock();
+        let previous = balances.get(player_id).copied().unwrap_or_default();
+        let balance = previous.checked_add_signed(delta)
+            .ok_or_else(|| GameError::InvalidRequest(format!("balance would become {}", i128::from(previous.get()) + i128::from(delta))))?;
+        balances.insert(PlayerId::from(player_id), balance);
+        if let Err(err) = self.save(&balances) {
+            balances.insert(PlayerId::from(player_id), previous);
+            return Err(err);
+        }
+        Ok(balance)
+    }
+
+    fn save(&self, balances: &BTreeMap<PlayerId, Chips>) -> Result<(), GameError> {
+        self.balances.save(balances).map_err(|err| GameError::Internal(format!("could not save balances: {err}")))
+    }
+}
+
+impl Bank for Bankroll {
+    fn withdraw(&self, player_id: &str, amount: Chips) -> Result<(), GameError> {
+        let mut balances = self.balances.lock();
+        let previous = balances.get(player_id).copied().unwrap_or_default();
+        let balance = previous.checked_sub(amount).ok_or(GameError::InsufficientChips)?;
+        balances.insert(PlayerId::from(player_id), balance);
+        if let Err(err) = self.save(&balances) {
+            balances.insert(PlayerId::from(player_id), previous);
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    /// Chips paid back are already off the table, so a failed save keeps
+    /// them in memory rather than losing them.
+    fn deposit(&self, player_id: &str, amount: Chips) {
+        let mut balances = self.balances.lock();
+        *balances.entry(PlayerId::from(player_id)).or_default() += amount;
+        if let Err(err) = self.save(&balances) {
+            tracing::warn!(player_id, %amount, %err, "balance credited in memory only");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_balances_survive_a_restart() {
+        let path = std::env::temp_dir().join(format!("card_dealer_bankroll_{}.json", std::process::id()));
+        let bankroll = Bankroll::open(&path).unwrap();
+        assert_eq!(bankroll.adjust("1", 1000), Ok(Chips::new(1000)));
+        assert!(matches!(bankroll.adjust("1", -1001), Err(GameError::InvalidRequest(_))));
+        assert_eq!(bankroll.withdraw("1", Chips::new(600)), Ok(()));
+        assert_eq!(bankroll.withdraw("1", Chips::new(600)), Err(GameError::InsufficientChips));
+        bankroll.deposit("2", Chips::new(50));
+
+        let reopened = Bankroll::open(&path).unwrap();
+        assert_eq!(reopened.balance("1"), Chips::new(400));
+        assert_eq!(reopened.balance("2"), Chips::new(50));
+        assert_eq!(reopened.balance("3"), Chips::ZERO);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
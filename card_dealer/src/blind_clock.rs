@@ -0,0 +1,86 @@
+use std::sync::{Arc, Weak};
+
+use poker_core::blind_schedule::{BlindSchedule, ClockState};
+use tokio::time::{sleep, Instant};
+use tracing::{info, warn};
+
+use crate::table_registry::TableHandle;
+
+/// A table's tournament clock, started when the first level began.
+#[derive(Debug)]
+pub struct BlindClock {
+    schedule: BlindSchedule,
+    started: Instant,
+}
+
+impl BlindClock {
+    /// Where the clock stands right now.
+    pub fn state(&self) -> Option<ClockState> {
+        self.schedule.at(self.started.elapsed())
+    }
+}
+
+/// Starts `table`'s clock at the first level of `schedule`.
+///
+/// The clock task hands the table each level's blinds as the level starts, so
+/// they apply from the next hand dealt, and holds new hands during breaks.
+/// It stops at the last level, or once the table is gone.
+pub fn spawn_blind_clock(table: &Arc<TableHandle>, schedule: BlindSchedule) {
+    let clock = BlindClock { schedule, started: Instant::now() };
+    if table.clock.set(clock).is_err() {
+        warn!(table_id = %table.id, "the table's clock is already running");
+        return;
+    }
+    tokio::spawn(run_clock(Arc::downgrade(table)));
+}
+
+async fn run_clock(table: Weak<TableHandle>) {
+    loop {
+        let Some(handle) = table.upgrade() else { return };
+        let Some(state) = handle.clock.get().and_then(BlindClock::state) else { return };
+        let applied = handle.try_call(move |controller| {
+            controller.set_blinds(state.small_blind, state.big_blind)?;
+            controller.set_big_blind_ante(state.ante);
+            controller.set_on_break(state.on_break);
+            Ok(())
+        }).await;
+        if let Err(err) = applied {
+            warn!(%err, table_id = %handle.id, "stopping the blind clock");
+            return;
+        }
+        info!(table_id = %handle.id, level = state.level, on_break = state.on_break, "blind clock");
+        drop(handle);
+        let Some(remaining) = state.remaining else { return };
+        sleep(remaining).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use poker_core::blind_schedule::BlindLevel;
+    use poker_core::chips::Chips;
+    use poker_core::game_controller::GameController;
+
+    #[tokio::test]
+    async fn test_clock_raises_blinds_and_holds_breaks() {
+        let level = |small_blind, duration_secs, break_secs| BlindLevel {
+            small_blind: Chips::new(small_blind),
+            big_blind: Chips::new(small_blind * 2),
+            duration_secs,
+            break_secs,
+            ante: Chips::new(small_blind * 2),
+        };
+        let table = TableHandle::new("clock", GameController::new());
+        spawn_blind_clock(&table, BlindSchedule { levels: vec![level(10, 1, 3600), level(25, 60, 0)] });
+        sleep(Duration::from_millis(100)).await;
+        let blinds = table.call(|controller| (controller.blinds(), controller.big_blind_ante(), controller.is_on_break())).await.unwrap();
+        assert_eq!(blinds, (Some((Chips::new(10), Chips::new(20))), Chips::new(20), false));
+
+        sleep(Duration::from_secs(1)).await;
+        let state = table.clock.get().and_then(BlindClock::state).unwrap();
+        assert!(state.on_break && state.remaining.is_some_and(|remaining| remaining > Duration::from_secs(3500)));
+        assert!(table.call(|controller| controller.is_on_break()).await.unwrap());
+    }
+}
@@ -0,0 +1,879 @@
+use std::collections::HashMap;
+use std::io;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+
+use clap::{Parser, Subcommand};
+use poker_core::blind_schedule::BlindSchedule;
+use poker_core::bounty::BountyRules;
+use poker_core::jackpot::JackpotRules;
+use poker_core::buy_in::BuyInRules;
+use poker_core::chips::Chips;
+use poker_core::currency::CurrencyMode;
+use poker_core::entropy::{EntropySource, FileSource, LoggedSeedSource, OsSource, ThreadSource};
+use poker_core::game_controller::DEFAULT_MAX_SEATS;
+use poker_core::rake::RakeRules;
+use poker_core::table_config::TableConfig;
+use poker_core::tournament::TournamentRules;
+use poker_core::variant::GameVariant;
+use poker_core::wild_cards::WildCards;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "monitor")]
+use crate::monitor::MonitorOptions;
+use crate::play::PlayOptions;
+use crate::simulate::SimulateOptions;
+
+/// Server settings.
+///
+/// Values are layered: built-in defaults, then the TOML file given by
+/// `--config`, then `CARD_DEALER_*` environment variables, then CLI flags.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ServerConfig {
+    pub listen_addr: SocketAddr,         // Address the HTTP API binds to
+    pub grpc_addr: SocketAddr,           // Address the gRPC service binds to (grpc feature)
+    pub small_blind: Chips,              // Default small blind for new tables
+    pub big_blind: Chips,                // Default big blind for new tables
+    pub starting_stack: Chips,           // Chips each seeded player starts with
+    pub players: Vec<String>,            // Display names of the players seated at startup
+    pub action_timeout_secs: u64,        // Seconds a player has to act before the table checks or folds for them; 0 for no limit
+    pub persistence_dir: PathBuf,        // Where game state and logs are written
+    pub shutdown_grace_secs: u64,        // How long a running hand may continue after a shutdown signal
+    pub auto_deal_pause_secs: Option<u64>, // Deal hands one after another, this long apart; hands are only started by hand when unset
+    pub tls_cert_path: Option<PathBuf>,  // PEM certificate chain; TLS is enabled when set with the key
+    pub tls_key_path: Option<PathBuf>,   // PEM private key matching `tls_cert_path`
+    pub cors: CorsConfig,                // Cross-origin access for browser UIs
+    pub rate_limit: RateLimitConfig,     // Per-IP request budget for the game routes
+    pub auth: AuthConfig,                // Session tokens for player-scoped routes
+    pub webhooks: WebhookConfig,         // URLs notified of game events
+    pub redis: RedisConfig,              // Redis pub/sub event publishing (redis feature)
+    pub postgres: PostgresConfig,        // Game state shared between instances in PostgreSQL (postgres feature)
+    pub nats: NatsConfig,                // Event streaming to NATS (nats feature)
+    pub kafka: KafkaConfig,              // Event streaming to Kafka (kafka feature)
+    pub mqtt: MqttConfig,                // Event publishing to MQTT for small devices (mqtt feature)
+    pub acpc: AcpcConfig,                // ACPC protocol endpoint for research bots (acpc feature)
+    pub solver: SolverConfig,            // External solver behind /v1/analysis/solve
+    pub buy_in: BuyInRules,              // Buy-in limits, rebuys and add-on at the tables
+    pub max_seats: usize,                // Seats per table; further players join the waiting list
+    pub bounty: Option<BountyRules>,     // Makes the tables knockout tournaments when set
+    pub tournament: Option<TournamentRules>, // Entry fee, starting stack and late registration for tournament tables
+    pub blind_schedule: Option<BlindSchedule>, // Blind levels raised on the clock; replaces the fixed blinds when set
+    pub variant: GameVariant,            // The game dealt at the tables
+    pub wild_cards: WildCards,           // Jokers or deuces wild, for home-game rules
+    pub jackpot: Option<JackpotRules>,   // Bad beat jackpot funded by a drop from each pot, when set
+    pub seven_deuce_bonus: Option<Chips>, // Paid to whoever wins a pot with 7-2 offsuit by each other player dealt in
+    pub entropy: EntropyConfig,          // Where the randomness shuffling the decks comes from
+    pub decks: usize,                    // Standard decks shuffled together into the shoe
+    pub continuous_shuffle: bool,        // Shuffle each hand's cards back into the shoe instead of a fresh deck per hand
+    pub training_mode: bool,             // Players may ask /v1/hint what to do on their turn
+    pub max_runs: usize,                 // Most times players all in before the river may agree to run the board
+    pub insurance: bool,                 // The favourite of a heads-up all-in may insure their share of the pot
+    pub big_blind_ante: Chips,           // Posted by the big blind for the whole table; zero for no ante
+    pub rake: RakeRules,                 // The house's share of each pot; none by default
+    pub straddles: bool,                 // The player under the gun may straddle
+    pub preflop_chart: Option<PathBuf>,  // Starting-hand chart file for hints; the built-in chart when unset
+}
+
+/// Cross-origin resource sharing policy.
+///
+/// CORS is off when `allowed_origins` is empty; `"*"` allows any origin.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CorsConfig {
+    pub allowed_origins: Vec<String>,    // e.g. "https://table.example.com"
+    pub allowed_methods: Vec<String>,    // HTTP methods cross-origin callers may use
+    pub allowed_headers: Vec<String>,    // Request headers cross-origin callers may send
+    pub max_age_secs: u64,               // How long browsers may cache a preflight response
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        Self {
+            allowed_origins: Vec::new(),
+            allowed_methods: vec!["GET".to_string(), "POST".to_string(), "PUT".to_string(), "DELETE".to_string()],
+            allowed_headers: vec!["content-type".to_string(), "authorization".to_string()],
+            max_age_secs: 600,
+        }
+    }
+}
+
+impl CorsConfig {
+    pub fn is_enabled(&self) -> bool {
+        !self.allowed_origins.is_empty()
+    }
+
+    pub fn allows_any_origin(&self) -> bool {
+        self.allowed_origins.iter().any(|origin| origin == "*")
+    }
+
+    /// Checks the values up front, since warp panics on malformed ones.
+    fn validate(&self) -> Result<(), String> {
+        for origin in &self.allowed_origins {
+            if origin != "*" && !(origin.starts_with("http://") || origin.starts_with("https://")) {
+                return Err(format!("Invalid CORS origin '{}': expected '*' or a http(s):// origin", origin));
+            }
+        }
+        for method in &self.allowed_methods {
+            if warp::http::Method::from_bytes(method.as_bytes()).is_err() {
+                return Err(format!("Invalid CORS method '{}'", method));
+            }
+        }
+        for header in &self.allowed_headers {
+            if warp::http::header::HeaderName::from_bytes(header.as_bytes()).is_err() {
+                return Err(format!("Invalid CORS header '{}'", header));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Token-bucket settings applied per client IP.
+///
+/// Setting `requests_per_sec` to zero turns rate limiting off.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RateLimitConfig {
+    pub requests_per_sec: f64,           // Sustained rate each client is allowed
+    pub burst: u32,                      // Requests a client may make back to back
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self { requests_per_sec: 5.0, burst: 20 }
+    }
+}
+
+/// Session token settings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuthConfig {
+    pub secret: Option<String>,              // HMAC key for signing tokens; random per run when unset
+    pub session_ttl_secs: u64,               // How long a token stays valid after login or refresh
+    pub passwords: HashMap<String, String>,  // player_id -> password; players not listed can't log in unless `open_login` is set
+    pub open_login: bool,                    // Lets players without a configured password log in by id alone; trusted networks only
+    pub admin_token: Option<String>,         // Exchanged at /v1/admin/login for an admin session; admin is disabled when unset
+}
+
+impl Default for AuthConfig {
+    fn default() -> Self {
+        Self { secret: None, session_ttl_secs: 3600, passwords: HashMap::new(), open_login: false, admin_token: None }
+    }
+}
+
+/// Outgoing webhook settings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebhookConfig {
+    pub urls: Vec<String>,               // Endpoints each event is POSTed to; webhooks are off when empty
+    pub events: Vec<String>,             // Event types to send, e.g. "hand_finished"
+    pub secret: Option<String>,          // Signs each body into the X-Webhook-Signature header
+    pub max_retries: u32,                // Retries after the first failed attempt
+    pub initial_backoff_ms: u64,         // Wait before the first retry; doubles on each retry
+    pub timeout_secs: u64,               // Per-attempt request timeout
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            urls: Vec::new(),
+            events: vec!["hand_finished".to_string(), "pot_awarded".to_string(), "player_busted".to_string()],
+            secret: None,
+            max_retries: 5,
+            initial_backoff_ms: 500,
+            timeout_secs: 5,
+        }
+    }
+}
+
+/// Where the randomness shuffling the decks comes from.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "source", rename_all = "snake_case")]
+pub enum EntropyConfig {
+    #[default]
+    Os,                      // The operating system's RNG
+    Thread,                  // The thread-local CSPRNG, reseeded from the OS
+    Chacha,                  // A ChaCha CSPRNG seeded afresh for each deck, logging every seed
+    File { path: PathBuf },  // A hardware RNG device such as /dev/hwrng, or a pipe from a remote feed
+}
+
+impl EntropyConfig {
+    /// Opens the configured source.
+    pub fn open(&self) -> io::Result<Box<dyn EntropySource>> {
+        Ok(match self {
+            EntropyConfig::Os => Box::new(OsSource),
+            EntropyConfig::Thread => Box::new(ThreadSource),
+            EntropyConfig::Chacha => Box::new(LoggedSeedSource::new()),
+            EntropyConfig::File { path } => Box::new(FileSource::open(path)?),
+        })
+    }
+}
+
+/// Redis pub/sub settings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RedisConfig {
+    pub url: Option<String>,             // e.g. "redis://127.0.0.1/"; publishing is off when unset
+    pub channel_prefix: String,          // Events go to "<prefix>:table:<table_id>:events"
+}
+
+impl Default for RedisConfig {
+    fn default() -> Self {
+        Self { url: None, channel_prefix: "poker".to_string() }
+    }
+}
+
+/// NATS event streaming settings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NatsConfig {
+    pub url: Option<String>,             // e.g. "nats://127.0.0.1:4222"; streaming is off when unset
+    pub subject_prefix: String,          // Events go to "<prefix>.table.<table_id>.events"
+}
+
+impl Default for NatsConfig {
+    fn default() -> Self {
+        Self { url: None, subject_prefix: "poker".to_string() }
+    }
+}
+
+/// Kafka event streaming settings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KafkaConfig {
+    pub brokers: Option<String>,         // Comma-separated bootstrap servers, e.g. "kafka1:9092,kafka2:9092"; streaming is off when unset
+    pub topic_prefix: String,            // Events go to "<prefix>.table.<table_id>.events", keyed by table id
+}
+
+impl Default for KafkaConfig {
+    fn default() -> Self {
+        Self { brokers: None, topic_prefix: "poker".to_string() }
+    }
+}
+
+/// MQTT publishing settings, for devices such as a scoreboard that only
+/// speak MQTT.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MqttConfig {
+    pub host: Option<String>,            // Broker host name or address; publishing is off when unset
+    pub port: u16,
+    pub client_id: String,               // Must be unique among the broker's clients
+    pub username: Option<String>,        // Sent with `password` when both are set
+    pub password: Option<String>,
+    pub topic_prefix: String,            // Events go to "<prefix>/table/<table_id>/events/<type>"
+    pub retain: bool,                    // Have the broker keep each topic's last event for devices connecting later
+}
+
+impl Default for MqttConfig {
+    fn default() -> Self {
+        Self {
+            host: None,
+            port: 1883,
+            client_id: "card-dealer".to_string(),
+            username: None,
+            password: None,
+            topic_prefix: "poker".to_string(),
+            retain: false,
+        }
+    }
+}
+
+/// PostgreSQL storage settings. When a URL is set, game state is saved in
+/// the database instead of `persistence_dir`, so several server instances
+/// can share it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PostgresConfig {
+    pub url: Option<String>,             // e.g. "postgres://dealer:secret@db/poker"; files are used when unset
+    pub pool_size: usize,                // Most connections kept open at once
+}
+
+impl Default for PostgresConfig {
+    fn default() -> Self {
+        Self { url: None, pool_size: 8 }
+    }
+}
+
+/// Annual Computer Poker Competition protocol settings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AcpcConfig {
+    pub listen_addr: Option<SocketAddr>, // TCP address ACPC clients connect to; off when unset
+    pub seats: Vec<String>,              // Player ids ACPC clients play, given out in connection order
+}
+
+impl Default for AcpcConfig {
+    fn default() -> Self {
+        Self { listen_addr: None, seats: vec!["1".to_string(), "2".to_string()] }
+    }
+}
+
+/// External solver settings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SolverConfig {
+    pub command: Vec<String>,            // Program and arguments started for each spot; solving is off when empty
+    pub timeout_secs: u64,               // Time the solver gets before it is killed
+}
+
+impl Default for SolverConfig {
+    fn default() -> Self {
+        Self { command: Vec::new(), timeout_secs: 60 }
+    }
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            listen_addr: ([127, 0, 0, 1], 3030).into(),
+            grpc_addr: ([127, 0, 0, 1], 50051).into(),
+            small_blind: Chips::new(5),
+            big_blind: Chips::new(10),
+            starting_stack: Chips::new(1000),
+            players: vec!["Alice".to_string(), "Bob".to_string(), "Charlie".to_string()],
+            action_timeout_secs: 30,
+            persistence_dir: PathBuf::from("data"),
+            shutdown_grace_secs: 30,
+            auto_deal_pause_secs: None,
+            tls_cert_path: None,
+            tls_key_path: None,
+            cors: CorsConfig::default(),
+            rate_limit: RateLimitConfig::default(),
+            auth: AuthConfig::default(),
+            webhooks: WebhookConfig::default(),
+            redis: RedisConfig::default(),
+            postgres: PostgresConfig::default(),
+            nats: NatsConfig::default(),
+            kafka: KafkaConfig::default(),
+            mqtt: MqttConfig::default(),
+            acpc: AcpcConfig::default(),
+            solver: SolverConfig::default(),
+            buy_in: BuyInRules::default(),
+            max_seats: DEFAULT_MAX_SEATS,
+            bounty: None,
+            tournament: None,
+            blind_schedule: None,
+            variant: GameVariant::default(),
+            wild_cards: WildCards::default(),
+            jackpot: None,
+            seven_deuce_bonus: None,
+            entropy: EntropyConfig::default(),
+            decks: 1,
+            continuous_shuffle: false,
+            training_mode: false,
+            max_runs: 1,
+            insurance: false,
+            big_blind_ante: Chips::ZERO,
+            rake: RakeRules::default(),
+            straddles: false,
+            preflop_chart: None,
+        }
+    }
+}
+
+/// Command-line flags; each one can also be set through its environment variable.
+#[derive(Debug, Default, Parser)]
+#[command(name = "card_dealer", about = "Texas Hold'em dealer server")]
+pub struct CliArgs {
+    /// Run something other than the server
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Path to a TOML config file
+    #[arg(long, env = "CARD_DEALER_CONFIG")]
+    pub config: Option<PathBuf>,
+
+    /// Address the HTTP API binds to
+    #[arg(long, env = "CARD_DEALER_LISTEN_ADDR")]
+    pub listen_addr: Option<SocketAddr>,
+
+    /// Address the gRPC service binds to
+    #[arg(long, env = "CARD_DEALER_GRPC_ADDR")]
+    pub grpc_addr: Option<SocketAddr>,
+
+    /// Default small blind
+    #[arg(long, env = "CARD_DEALER_SMALL_BLIND")]
+    pub small_blind: Option<Chips>,
+
+    /// Default big blind
+    #[arg(long, env = "CARD_DEALER_BIG_BLIND")]
+    pub big_blind: Option<Chips>,
+
+    /// Chips each seeded player starts with
+    #[arg(long, env = "CARD_DEALER_STARTING_STACK")]
+    pub starting_stack: Option<Chips>,
+
+    /// Comma-separated display names of the players seated at startup
+    #[arg(long, env = "CARD_DEALER_PLAYERS", value_delimiter = ',')]
+    pub players: Option<Vec<String>>,
+
+    /// Seconds a player has to act before the table checks or folds for them; 0 for no limit
+    #[arg(long, env = "CARD_DEALER_ACTION_TIMEOUT_SECS")]
+    pub action_timeout_secs: Option<u64>,
+
+    /// Directory for persisted game state
+    #[arg(long, env = "CARD_DEALER_PERSISTENCE_DIR")]
+    pub persistence_dir: Option<PathBuf>,
+
+    /// Seconds a running hand may continue after a shutdown signal
+    #[arg(long, env = "CARD_DEALER_SHUTDOWN_GRACE_SECS")]
+    pub shutdown_grace_secs: Option<u64>,
+
+    /// Deal hands automatically, this many seconds apart
+    #[arg(long, env = "CARD_DEALER_AUTO_DEAL_PAUSE_SECS")]
+    pub auto_deal_pause_secs: Option<u64>,
+
+    /// PEM certificate chain for serving HTTPS
+    #[arg(long, env = "CARD_DEALER_TLS_CERT")]
+    pub tls_cert_path: Option<PathBuf>,
+
+    /// PEM private key for serving HTTPS
+    #[arg(long, env = "CARD_DEALER_TLS_KEY")]
+    pub tls_key_path: Option<PathBuf>,
+
+    /// Comma-separated origins allowed to call the API from a browser ("*" for any)
+    #[arg(long, env = "CARD_DEALER_CORS_ORIGINS", value_delimiter = ',')]
+    pub cors_origins: Option<Vec<String>>,
+
+    /// Sustained requests per second allowed per client IP (0 disables rate limiting)
+    #[arg(long, env = "CARD_DEALER_RATE_LIMIT_PER_SEC")]
+    pub rate_limit_per_sec: Option<f64>,
+
+    /// Requests a client IP may make back to back before being throttled
+    #[arg(long, env = "CARD_DEALER_RATE_LIMIT_BURST")]
+    pub rate_limit_burst: Option<u32>,
+
+    /// Secret used to sign session tokens
+    #[arg(long, env = "CARD_DEALER_AUTH_SECRET", hide_env_values = true)]
+    pub auth_secret: Option<String>,
+
+    /// Seconds a session token stays valid
+    #[arg(long, env = "CARD_DEALER_SESSION_TTL_SECS")]
+    pub session_ttl_secs: Option<u64>,
+
+    /// Let players without a configured password log in by id alone
+    #[arg(long, env = "CARD_DEALER_OPEN_LOGIN")]
+    pub open_login: Option<bool>,
+
+    /// Token the table host exchanges for an admin session
+    #[arg(long, env = "CARD_DEALER_ADMIN_TOKEN", hide_env_values = true)]
+    pub admin_token: Option<String>,
+
+    /// Comma-separated URLs notified of game events
+    #[arg(long, env = "CARD_DEALER_WEBHOOK_URLS", value_delimiter = ',')]
+    pub webhook_urls: Option<Vec<String>>,
+
+    /// Redis URL to publish game events to (redis feature)
+    #[arg(long, env = "CARD_DEALER_REDIS_URL")]
+    pub redis_url: Option<String>,
+
+    /// NATS URL to stream game events to (nats feature)
+    #[arg(long, env = "CARD_DEALER_NATS_URL")]
+    pub nats_url: Option<String>,
+
+    /// Comma-separated Kafka brokers to stream game events to (kafka feature)
+    #[arg(long, env = "CARD_DEALER_KAFKA_BROKERS")]
+    pub kafka_brokers: Option<String>,
+
+    /// MQTT broker host to publish game events to (mqtt feature)
+    #[arg(long, env = "CARD_DEALER_MQTT_HOST")]
+    pub mqtt_host: Option<String>,
+
+    /// PostgreSQL URL to keep game state in instead of files (postgres feature)
+    #[arg(long, env = "CARD_DEALER_POSTGRES_URL", hide_env_values = true)]
+    pub postgres_url: Option<String>,
+
+    /// Address ACPC protocol clients connect to (acpc feature)
+    #[arg(long, env = "CARD_DEALER_ACPC_ADDR")]
+    pub acpc_addr: Option<SocketAddr>,
+
+    /// Comma-separated player ids ACPC clients are seated as
+    #[arg(long, env = "CARD_DEALER_ACPC_SEATS", value_delimiter = ',')]
+    pub acpc_seats: Option<Vec<String>>,
+
+    /// Seats per table before players join the waiting list
+    #[arg(long, env = "CARD_DEALER_MAX_SEATS")]
+    pub max_seats: Option<usize>,
+}
+
+/// Modes besides serving the API.
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Play hot-seat hands in the terminal against the local engine
+    Play(PlayOptions),
+    /// Play many hands between built-in bots and report statistics
+    Simulate(SimulateOptions),
+    /// Watch a running server's default table in a terminal dashboard
+    #[cfg(feature = "monitor")]
+    Monitor(MonitorOptions),
+}
+
+impl ServerConfig {
+    /// Builds the configuration from already-parsed arguments.
+    pub fn from_args(args: CliArgs) -> Result<Self, String> {
+        let mut config = match &args.config {
+            Some(path) => Self::from_file(path)?,
+            None => Self::default(),
+        };
+        config.apply_overrides(args);
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Reads a TOML file; missing keys keep their defaults.
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|err| format!("Failed to read config file {}: {}", path.display(), err))?;
+        toml::from_str(&contents)
+            .map_err(|err| format!("Failed to parse config file {}: {}", path.display(), err))
+    }
+
+    /// Replaces every setting given on the command line or in the environment.
+    fn apply_overrides(&mut self, args: CliArgs) {
+        if let Some(listen_addr) = args.listen_addr { self.listen_addr = listen_addr; }
+        if let Some(grpc_addr) = args.grpc_addr { self.grpc_addr = grpc_addr; }
+        if let Some(small_blind) = args.small_blind { self.small_blind = small_blind; }
+        if let Some(big_blind) = args.big_blind { self.big_blind = big_blind; }
+        if let Some(starting_stack) = args.starting_stack { self.starting_stack = starting_stack; }
+        if let Some(players) = args.players { self.players = players; }
+        if let Some(action_timeout_secs) = args.action_timeout_secs { self.action_timeout_secs = action_timeout_secs; }
+        if let Some(persistence_dir) = args.persistence_dir { self.persistence_dir = persistence_dir; }
+        if let Some(shutdown_grace_secs) = args.shutdown_grace_secs { self.shutdown_grace_secs = shutdown_grace_secs; }
+        if let Some(auto_deal_pause_secs) = args.auto_deal_pause_secs { self.auto_deal_pause_secs = Some(auto_deal_pause_secs); }
+        if let Some(tls_cert_path) = args.tls_cert_path { self.tls_cert_path = Some(tls_cert_path); }
+        if let Some(tls_key_path) = args.tls_key_path { self.tls_key_path = Some(tls_key_path); }
+        if let Some(cors_origins) = args.cors_origins { self.cors.allowed_origins = cors_origins; }
+        if let Some(rate_limit_per_sec) = args.rate_limit_per_sec { self.rate_limit.requests_per_sec = rate_limit_per_sec; }
+        if let Some(rate_limit_burst) = args.rate_limit_burst { self.rate_limit.burst = rate_limit_burst; }
+        if let Some(auth_secret) = args.auth_secret { self.auth.secret = Some(auth_secret); }
+        if let Some(session_ttl_secs) = args.session_ttl_secs { self.auth.session_ttl_secs = session_ttl_secs; }
+        if let Some(open_login) = args.open_login { self.auth.open_login = open_login; }
+        if let Some(admin_token) = args.admin_token { self.auth.admin_token = Some(admin_token); }
+        if let Some(webhook_urls) = args.webhook_urls { self.webhooks.urls = webhook_urls; }
+        if let Some(redis_url) = args.redis_url { self.redis.url = Some(redis_url); }
+        if let Some(postgres_url) = args.postgres_url { self.postgres.url = Some(postgres_url); }
+        if let Some(nats_url) = args.nats_url { self.nats.url = Some(nats_url); }
+        if let Some(kafka_brokers) = args.kafka_brokers { self.kafka.brokers = Some(kafka_brokers); }
+        if let Some(mqtt_host) = args.mqtt_host { self.mqtt.host = Some(mqtt_host); }
+        if let Some(acpc_addr) = args.acpc_addr { self.acpc.listen_addr = Some(acpc_addr); }
+        if let Some(acpc_seats) = args.acpc_seats { self.acpc.seats = acpc_seats; }
+        if let Some(max_seats) = args.max_seats { self.max_seats = max_seats; }
+    }
+
+    /// Rejects settings the game can't run with.
+    fn validate(&self) -> Result<(), String> {
+        if self.small_blind.is_zero() || self.big_blind < self.small_blind {
+            return Err("Blinds must be positive and the big blind at least the small blind".to_string());
+        }
+        if self.starting_stack < self.big_blind {
+            return Err("Starting stack must cover at least one big blind".to_string());
+        }
+        if self.players.len() < 2 {
+            return Err("At least two players are required".to_string());
+        }
+        if self.players.len() > self.max_seats {
+            return Err(format!("{} players don't fit in {} seats", self.players.len(), self.max_seats));
+        }
+        if self.tls_cert_path.is_some() != self.tls_key_path.is_some() {
+            return Err("TLS needs both a certificate and a private key".to_string());
+        }
+        if !(self.rate_limit.requests_per_sec >= 0.0 && self.rate_limit.requests_per_sec.is_finite()) {
+            return Err("Rate limit must be a non-negative number of requests per second".to_string());
+        }
+        if self.rate_limit.requests_per_sec > 0.0 && self.rate_limit.burst == 0 {
+            return Err("Rate limit burst must allow at least one request".to_string());
+        }
+        if self.auth.session_ttl_secs == 0 {
+            return Err("Session lifetime must be at least one second".to_string());
+        }
+        if self.auth.secret.as_deref() == Some("") {
+            return Err("Auth secret must not be empty".to_string());
+        }
+        if self.auth.admin_token.as_deref() == Some("") {
+            return Err("Admin token must not be empty".to_string());
+        }
+        if let Some(url) = self.webhooks.urls.iter().find(|url| !(url.starts_with("http://") || url.starts_with("https://"))) {
+            return Err(format!("Invalid webhook URL '{}': expected a http(s):// URL", url));
+        }
+        if self.buy_in.min_buy_in > self.buy_in.max_buy_in {
+            return Err("Minimum buy-in must not exceed the maximum".to_string());
+        }
+        if self.bounty.is_some_and(|rules| rules.bounty.is_zero()) {
+            return Err("Bounties must be at least one chip".to_string());
+        }
+        if self.tournament.is_some_and(|rules| rules.max_entries == 0 || rules.starting_stack < self.big_blind) {
+            return Err("Tournaments need at least one entry per player and a starting stack of a big blind".to_string());
+        }
+        if let Some(schedule) = &self.blind_schedule {
+            schedule.validate()?;
+        }
+        if let Some(rules) = &self.jackpot {
+            rules.validate()?;
+        }
+        if self.decks == 0 {
+            return Err("At least one deck is needed".to_string());
+        }
+        if self.acpc.listen_addr.is_some() && self.acpc.seats.is_empty() {
+            return Err("ACPC needs at least one seat for clients".to_string());
+        }
+        if self.postgres.url.is_some() && self.postgres.pool_size == 0 {
+            return Err("The PostgreSQL pool needs at least one connection".to_string());
+        }
+        if self.mqtt.host.is_some() && self.mqtt.client_id.is_empty() {
+            return Err("MQTT needs a client id".to_string());
+        }
+        if !self.solver.command.is_empty() && self.solver.timeout_secs == 0 {
+            return Err("The solver needs a timeout above zero".to_string());
+        }
+        self.cors.validate()
+    }
+
+    /// Certificate and key paths, if HTTPS is configured.
+    pub fn tls(&self) -> Option<(&Path, &Path)> {
+        match (&self.tls_cert_path, &self.tls_key_path) {
+            (Some(cert), Some(key)) => Some((cert, key)),
+            _ => None,
+        }
+    }
+
+    /// How the table opened at startup is set up.
+    pub fn table_config(&self) -> TableConfig {
+        TableConfig {
+            variant: self.variant,
+            small_blind: self.small_blind,
+            big_blind: self.big_blind,
+            buy_in: self.buy_in.clone(),
+            max_seats: self.max_seats,
+            wild_cards: self.wild_cards,
+            decks: self.decks,
+            continuous_shuffle: self.continuous_shuffle,
+            training_mode: self.training_mode,
+            max_runs: self.max_runs,
+            insurance: self.insurance,
+            big_blind_ante: self.big_blind_ante,
+            rake: self.rake.clone(),
+            straddles: self.straddles,
+            action_timeout_secs: (self.action_timeout_secs > 0).then_some(self.action_timeout_secs),
+            currency: CurrencyMode::PlayMoney, // Its players are seated from the config, not drawn from balances
+        }
+    }
+
+    /// Seed data for `GameController::initialize_players`.
+    pub fn initial_players(&self) -> Vec<(String, String, usize, Chips)> {
+        self.players
+            .iter()
+            .enumerate()
+            .map(|(seat, name)| ((seat + 1).to_string(), name.clone(), seat, self.starting_stack))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_values_and_overrides() {
+        let mut config: ServerConfig = toml::from_str(
+            r#"
+            listen_addr = "0.0.0.0:8080"
+            starting_stack = 500
+            players = ["Dana", "Eve"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.listen_addr, "0.0.0.0:8080".parse().unwrap());
+        assert_eq!(config.big_blind, Chips::new(10)); // Missing keys fall back to the defaults
+
+        config.apply_overrides(CliArgs {
+            starting_stack: Some(Chips::new(2000)),
+            ..CliArgs::default()
+        });
+        assert_eq!(config.starting_stack, Chips::new(2000));
+        assert_eq!(
+            config.initial_players(),
+            vec![
+                ("1".to_string(), "Dana".to_string(), 0, Chips::new(2000)),
+                ("2".to_string(), "Eve".to_string(), 1, Chips::new(2000)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bounty_from_file() {
+        let config: ServerConfig = toml::from_str(
+            r#"
+            [bounty]
+            bounty = 250
+            format = "progressive"
+            "#,
+        )
+        .unwrap();
+        let rules = config.bounty.unwrap();
+        assert_eq!((rules.bounty, rules.format), (Chips::new(250), poker_core::bounty::BountyFormat::Progressive));
+        assert!(ServerConfig::default().bounty.is_none());
+    }
+
+    #[test]
+    fn test_blind_schedule_from_file() {
+        let config: ServerConfig = toml::from_str(
+            r#"
+            [[blind_schedule.levels]]
+            small_blind = 10
+            big_blind = 20
+            duration_secs = 900
+            break_secs = 300
+
+            [[blind_schedule.levels]]
+            small_blind = 25
+            big_blind = 50
+            duration_secs = 900
+            "#,
+        )
+        .unwrap();
+        let schedule = config.blind_schedule.as_ref().unwrap();
+        assert_eq!((schedule.levels.len(), schedule.levels[0].break_secs, schedule.levels[1].break_secs), (2, 300, 0));
+        assert_eq!(config.validate(), Ok(()));
+
+        let empty = ServerConfig { blind_schedule: Some(BlindSchedule::default()), ..ServerConfig::default() };
+        assert!(empty.validate().is_err());
+    }
+
+    #[test]
+    fn test_variant_from_file() {
+        let config: ServerConfig = toml::from_str(r#"variant = "double_board_hold_em""#).unwrap();
+        assert_eq!(config.variant, GameVariant::DoubleBoardHoldEm);
+        let config: ServerConfig = toml::from_str(r#"variant = "courchevel""#).unwrap();
+        assert_eq!(config.variant, GameVariant::Courchevel);
+        assert_eq!(ServerConfig::default().variant, GameVariant::HoldEm);
+        let config: ServerConfig = toml::from_str(r#"wild_cards = "joker""#).unwrap();
+        assert_eq!((config.variant, config.wild_cards), (GameVariant::HoldEm, WildCards::Joker));
+    }
+
+    #[test]
+    fn test_jackpot_from_file() {
+        let config: ServerConfig = toml::from_str(
+            r#"
+            [jackpot]
+            drop = 2
+            qualifying_hand = "FullHouse"
+            "#,
+        )
+        .unwrap();
+        let rules = config.jackpot.as_ref().unwrap();
+        assert_eq!((rules.drop, rules.qualifying_hand.clone(), rules.loser_share), (Chips::new(2), poker_core::HandRank::FullHouse, 50));
+        assert_eq!(config.validate(), Ok(()));
+
+        let greedy = ServerConfig { jackpot: Some(JackpotRules { loser_share: 90, ..JackpotRules::default() }), ..ServerConfig::default() };
+        assert!(greedy.validate().is_err());
+    }
+
+    #[test]
+    fn test_entropy_from_file() {
+        let config: ServerConfig = toml::from_str("[entropy]\nsource = \"chacha\"").unwrap();
+        assert_eq!(config.entropy, EntropyConfig::Chacha);
+        assert_eq!(config.entropy.open().unwrap().name(), "chacha");
+
+        let config: ServerConfig = toml::from_str("[entropy]\nsource = \"file\"\npath = \"/no/such/device\"").unwrap();
+        assert_eq!(config.entropy, EntropyConfig::File { path: PathBuf::from("/no/such/device") });
+        assert!(config.entropy.open().is_err());
+        assert_eq!(ServerConfig::default().entropy, EntropyConfig::Os);
+    }
+
+    #[test]
+    fn test_shoe_from_file() {
+        let config: ServerConfig = toml::from_str("decks = 6\ncontinuous_shuffle = true").unwrap();
+        assert_eq!((config.decks, config.continuous_shuffle), (6, true));
+        assert_eq!(config.validate(), Ok(()));
+        assert!(ServerConfig { decks: 0, ..ServerConfig::default() }.validate().is_err());
+    }
+
+    #[test]
+    fn test_seven_deuce_bonus_from_file() {
+        let config: ServerConfig = toml::from_str("seven_deuce_bonus = 50").unwrap();
+        assert_eq!(config.seven_deuce_bonus, Some(Chips::new(50)));
+        assert_eq!(ServerConfig::default().seven_deuce_bonus, None);
+    }
+
+    #[test]
+    fn test_training_mode_from_file() {
+        let config: ServerConfig = toml::from_str("training_mode = true\npreflop_chart = \"charts/tight.txt\"").unwrap();
+        assert_eq!((config.training_mode, config.preflop_chart), (true, Some(PathBuf::from("charts/tight.txt"))));
+        assert!(!ServerConfig::default().training_mode);
+    }
+
+    #[test]
+    fn test_auto_deal_from_file() {
+        let config: ServerConfig = toml::from_str("auto_deal_pause_secs = 3").unwrap();
+        assert_eq!(config.auto_deal_pause_secs, Some(3));
+        assert_eq!(ServerConfig::default().auto_deal_pause_secs, None);
+    }
+
+    #[test]
+    fn test_solver_from_file() {
+        let config: ServerConfig = toml::from_str("[solver]\ncommand = [\"python3\", \"solve.py\"]\ntimeout_secs = 30").unwrap();
+        assert_eq!(config.solver, SolverConfig { command: vec!["python3".to_string(), "solve.py".to_string()], timeout_secs: 30 });
+        assert_eq!(config.validate(), Ok(()));
+        let no_time = SolverConfig { timeout_secs: 0, ..config.solver };
+        assert!(ServerConfig { solver: no_time, ..ServerConfig::default() }.validate().is_err());
+    }
+
+    #[test]
+    fn test_postgres_from_file_and_env() {
+        let config: ServerConfig = toml::from_str("[postgres]\nurl = \"postgres://dealer@db/poker\"\npool_size = 4").unwrap();
+        assert_eq!(config.postgres, PostgresConfig { url: Some("postgres://dealer@db/poker".to_string()), pool_size: 4 });
+        assert_eq!(config.validate(), Ok(()));
+        assert!(ServerConfig { postgres: PostgresConfig { pool_size: 0, ..config.postgres }, ..ServerConfig::default() }.validate().is_err());
+
+        let args = CliArgs { postgres_url: Some("postgres://other/poker".to_string()), ..CliArgs::default() };
+        assert_eq!(ServerConfig::from_args(args).unwrap().postgres.url.as_deref(), Some("postgres://other/poker"));
+    }
+
+    #[test]
+    fn test_event_streaming_from_file() {
+        let config: ServerConfig = toml::from_str("[nats]\nurl = \"nats://127.0.0.1:4222\"\n[kafka]\nbrokers = \"kafka1:9092\"\ntopic_prefix = \"casino\"").unwrap();
+        assert_eq!(config.nats, NatsConfig { url: Some("nats://127.0.0.1:4222".to_string()), subject_prefix: "poker".to_string() });
+        assert_eq!(config.kafka, KafkaConfig { brokers: Some("kafka1:9092".to_string()), topic_prefix: "casino".to_string() });
+        assert_eq!(ServerConfig::default().kafka.brokers, None);
+
+        let config: ServerConfig = toml::from_str("[mqtt]\nhost = \"scoreboard.local\"\nretain = true").unwrap();
+        assert_eq!((config.mqtt.host.as_deref(), config.mqtt.port, config.mqtt.retain), (Some("scoreboard.local"), 1883, true));
+        assert_eq!(config.validate(), Ok(()));
+        assert!(ServerConfig { mqtt: MqttConfig { client_id: String::new(), ..config.mqtt }, ..ServerConfig::default() }.validate().is_err());
+    }
+
+    #[test]
+    fn test_invalid_blinds_rejected() {
+        let args = CliArgs {
+            small_blind: Some(Chips::new(20)),
+            big_blind: Some(Chips::new(10)),
+            ..CliArgs::default()
+        };
+        assert!(ServerConfig::from_args(args).is_err());
+    }
+
+    #[test]
+    fn test_cors_origins_validated() {
+        let args = CliArgs {
+            cors_origins: Some(vec!["table.example.com".to_string()]),
+            ..CliArgs::default()
+        };
+        assert!(ServerConfig::from_args(args).is_err());
+
+        let args = CliArgs {
+            cors_origins: Some(vec!["https://table.example.com".to_string()]),
+            ..CliArgs::default()
+        };
+        assert!(ServerConfig::from_args(args).unwrap().cors.is_enabled());
+    }
+
+    #[test]
+    fn test_tls_requires_cert_and_key() {
+        let args = CliArgs {
+            tls_cert_path: Some(PathBuf::from("cert.pem")),
+            ..CliArgs::default()
+        };
+        assert!(ServerConfig::from_args(args).is_err());
+    }
+}
@@ -0,0 +1,214 @@
+//! Streams every table's events to Kafka or NATS, one topic per table, so
+//! analytics pipelines and fraud-detection consumers can follow the game
+//! without talking to the server, and to MQTT, one topic per kind of event,
+//! for small devices such as a scoreboard on a Raspberry Pi. Events are
+//! JSON, as sent to webhooks.
+
+use std::future::Future;
+use std::sync::Arc;
+#[cfg(any(feature = "kafka", feature = "mqtt"))]
+use std::time::Duration;
+
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::{info, warn};
+
+#[cfg(feature = "kafka")]
+use crate::config::KafkaConfig;
+#[cfg(feature = "mqtt")]
+use crate::config::MqttConfig;
+#[cfg(feature = "nats")]
+use crate::config::NatsConfig;
+use crate::events::SequencedEvent;
+
+/// How long Kafka may take to acknowledge an event before it is dropped.
+#[cfg(feature = "kafka")]
+const KAFKA_DELIVERY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Events waiting for the MQTT connection before further ones are dropped.
+#[cfg(feature = "mqtt")]
+const MQTT_QUEUE_LEN: usize = 64;
+
+/// Wait before reconnecting after the MQTT broker becomes unreachable.
+#[cfg(feature = "mqtt")]
+const MQTT_RECONNECT_DELAY: Duration = Duration::from_secs(2);
+
+/// Topic or subject a table's events go to, e.g. `poker.table.main.events`.
+#[cfg(any(feature = "nats", feature = "kafka", test))]
+fn topic_name(prefix: &str, table_id: &str) -> String {
+    format!("{prefix}.table.{table_id}.events")
+}
+
+/// Hands each event to `publish`, with its JSON, in order, until the table stops.
+/// Events `publish` fails on are logged and dropped; consumers can spot the
+/// gap in the sequence numbers.
+async fn forward<F, Fut>(sink: &'static str, mut events: broadcast::Receiver<Arc<SequencedEvent>>, mut publish: F)
+where
+    F: FnMut(&SequencedEvent, String) -> Fut,
+    Fut: Future<Output = Result<(), String>>,
+{
+    loop {
+        let event = match events.recv().await {
+            Ok(event) => event,
+            Err(RecvError::Lagged(missed)) => {
+                warn!(sink, missed, "event stream fell behind, events skipped");
+                continue;
+            }
+            Err(RecvError::Closed) => break,
+        };
+        let payload = match serde_json::to_string(&*event) {
+            Ok(payload) => payload,
+            Err(err) => {
+                warn!(sink, %err, seq = event.seq, "failed to serialize event for streaming");
+                continue;
+            }
+        };
+        if let Err(err) = publish(&event, payload).await {
+            warn!(sink, %err, seq = event.seq, "failed to stream event, event dropped");
+        }
+    }
+}
+
+/// Starts publishing every event to NATS on `<prefix>.table.<table_id>.events`.
+/// The client reconnects by itself; does nothing when no URL is configured.
+#[cfg(feature = "nats")]
+pub fn spawn_nats_sink(config: NatsConfig, table_id: String, events: broadcast::Receiver<Arc<SequencedEvent>>) {
+    let Some(url) = config.url else { return };
+    let subject = topic_name(&config.subject_prefix, &table_id);
+    info!(%subject, "streaming events to NATS");
+
+    tokio::spawn(async move {
+        let client = match async_nats::ConnectOptions::new().retry_on_initial_connect().connect(url.as_str()).await {
+            Ok(client) => client,
+            Err(err) => {
+                warn!(%err, "invalid NATS URL, event streaming disabled");
+                return;
+            }
+        };
+        forward("nats", events, |_, payload| {
+            let (client, subject) = (client.clone(), subject.clone());
+            async move { client.publish(subject, payload.into()).await.map_err(|err| err.to_string()) }
+        })
+        .await;
+    });
+}
+
+/// Starts producing every event to Kafka on `<prefix>.table.<table_id>.events`,
+/// keyed by the table id. Does nothing when no brokers are configured.
+#[cfg(feature = "kafka")]
+pub fn spawn_kafka_sink(config: KafkaConfig, table_id: String, events: broadcast::Receiver<Arc<SequencedEvent>>) {
+    use rdkafka::producer::{FutureProducer, FutureRecord};
+    use rdkafka::ClientConfig;
+
+    let Some(brokers) = config.brokers else { return };
+    let producer: FutureProducer = match ClientConfig::new()
+        .set("bootstrap.servers", &brokers)
+        .set("message.timeout.ms", KAFKA_DELIVERY_TIMEOUT.as_millis().to_string())
+        .create()
+    {
+        Ok(producer) => producer,
+        Err(err) => {
+            warn!(%err, "invalid Kafka settings, event streaming disabled");
+            return;
+        }
+    };
+    let topic = topic_name(&config.topic_prefix, &table_id);
+    info!(%topic, %brokers, "streaming events to Kafka");
+
+    tokio::spawn(async move {
+        forward("kafka", events, |_, payload| {
+            let (producer, topic, key) = (producer.clone(), topic.clone(), table_id.clone());
+            async move {
+                let record = FutureRecord::to(&topic).key(&key).payload(&payload);
+                producer.send(record, Duration::ZERO).await.map(|_| ()).map_err(|(err, _)| err.to_string())
+            }
+        })
+        .await;
+    });
+}
+
+/// MQTT topic of one kind of event, e.g. `poker/table/main/events/hand_finished`,
+/// so a device can subscribe to just what it shows.
+#[cfg(any(feature = "mqtt", test))]
+fn mqtt_topic(prefix: &str, table_id: &str, kind: &str) -> String {
+    format!("{prefix}/table/{table_id}/events/{kind}")
+}
+
+/// Starts publishing every event to the configured MQTT broker, at most
+/// once, on a topic per kind of event. With `retain` set, the broker keeps
+/// each topic's last event for devices that connect later. Does nothing
+/// when no host is configured.
+#[cfg(feature = "mqtt")]
+pub fn spawn_mqtt_sink(config: MqttConfig, table_id: String, events: broadcast::Receiver<Arc<SequencedEvent>>) {
+    use rumqttc::{AsyncClient, MqttOptions, QoS};
+
+    let Some(host) = config.host else { return };
+    let mut options = MqttOptions::new(config.client_id, host.as_str(), config.port);
+    options.set_keep_alive(Duration::from_secs(30));
+    if let (Some(username), Some(password)) = (config.username, config.password) {
+        options.set_credentials(username, password);
+    }
+    let (client, mut connection) = AsyncClient::new(options, MQTT_QUEUE_LEN);
+    info!(%host, port = config.port, topics = %mqtt_topic(&config.topic_prefix, &table_id, "#"), "publishing events to MQTT");
+
+    // The connection only makes progress while it is polled; it reconnects on the next poll after an error
+    tokio::spawn(async move {
+        loop {
+            if let Err(err) = connection.poll().await {
+                warn!(%err, "MQTT broker unreachable");
+                tokio::time::sleep(MQTT_RECONNECT_DELAY).await;
+            }
+        }
+    });
+    tokio::spawn(async move {
+        let prefix = config.topic_prefix;
+        forward("mqtt", events, |event, payload| {
+            // Queued for the connection task rather than awaited, so a broker that's away doesn't hold up the table's events
+            let result = client
+                .try_publish(mqtt_topic(&prefix, &table_id, event.event.kind()), QoS::AtMostOnce, config.retain, payload)
+                .map_err(|err| err.to_string());
+            async move { result }
+        })
+        .await;
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    use poker_core::events::GameEvent;
+
+    use crate::events::EventBus;
+
+    #[test]
+    fn test_topic_name() {
+        assert_eq!(topic_name("poker", "main"), "poker.table.main.events");
+        assert_eq!(mqtt_topic("poker", "main", "hand_finished"), "poker/table/main/events/hand_finished");
+    }
+
+    #[tokio::test]
+    async fn test_events_are_forwarded_in_order() {
+        let mut bus = EventBus::new("main");
+        let events = bus.sender().subscribe();
+        bus.publish(GameEvent::TablePaused);
+        bus.publish(GameEvent::HandCancelled { hand_number: 4 });
+        bus.publish(GameEvent::TableResumed);
+        drop(bus);
+
+        // The second event fails to send and is dropped; the others still go out
+        let sent = Mutex::new(Vec::new());
+        forward("test", events, |_, payload| {
+            let failed = payload.contains("hand_cancelled");
+            if !failed {
+                sent.lock().unwrap().push(serde_json::from_str::<serde_json::Value>(&payload).unwrap());
+            }
+            async move { if failed { Err("broker down".to_string()) } else { Ok(()) } }
+        })
+        .await;
+        let sent = sent.into_inner().unwrap();
+        assert_eq!(sent.len(), 2);
+        assert_eq!(sent[0], serde_json::json!({"table_id": "main", "seq": 1, "type": "table_paused"}));
+        assert_eq!(sent[1]["seq"], 3);
+    }
+}
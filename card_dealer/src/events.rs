@@ -0,0 +1,185 @@
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use poker_core::events::GameEvent;
+use poker_core::game_error::GameError;
+use serde::Serialize;
+use tokio::sync::broadcast;
+use utoipa::ToSchema;
+
+/// How many events a slow subscriber may fall behind before it starts missing them.
+const CHANNEL_CAPACITY: usize = 256;
+
+/// How many recent events are kept for clients resuming from a sequence number.
+const HISTORY_LEN: usize = 1024;
+
+/// Id of hand `hand_number` at `table_id`, e.g. `main:42`, by which logs,
+/// histories, webhooks and support tickets can refer to it unambiguously.
+pub fn hand_id(table_id: &str, hand_number: u64) -> String {
+    format!("{table_id}:{hand_number}")
+}
+
+/// The number of the hand at `table_id` that `id` names, given either as a
+/// hand id or as the bare hand number.
+pub fn parse_hand_id(table_id: &str, id: &str) -> Result<u64, GameError> {
+    let number = match id.rsplit_once(':') {
+        Some((table, number)) if table == table_id => number,
+        Some(_) => return Err(GameError::InvalidRequest(format!("hand {id} was not played at table {table_id}"))),
+        None => id,
+    };
+    number.parse().map_err(|_| GameError::InvalidRequest(format!("'{id}' is not a hand id")))
+}
+
+/// A `GameEvent` stamped with its table, its position in the table's event
+/// stream, and the ids of the hand and action it belongs to.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct SequencedEvent {
+    pub seq: u64, // Starts at 1 and increases by one per event
+    #[schema(example = "main")]
+    pub table_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = "main:42")]
+    pub hand_id: Option<String>,   // Events of a hand only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = "main:42:3")]
+    pub action_id: Option<String>, // `player_acted` only: the hand id and the action's number in the hand
+    #[serde(flatten)]
+    pub event: GameEvent,
+}
+
+/// Numbers events and broadcasts them to every subscriber.
+///
+/// Owned by a table's task, which publishes the events the `GameController`
+/// queued after each command, so they arrive in the order they happened.
+pub struct EventBus {
+    table_id: String,
+    sender: broadcast::Sender<Arc<SequencedEvent>>,
+    history: VecDeque<Arc<SequencedEvent>>, // The last `HISTORY_LEN` events, oldest first
+    last_seq: u64,
+    actions: (u64, u32), // Hand number of the last action published, and how many that hand has had
+}
+
+impl EventBus {
+    pub fn new(table_id: impl Into<String>) -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self { table_id: table_id.into(), sender, history: VecDeque::new(), last_seq: 0, actions: (0, 0) }
+    }
+
+    /// Publishes an event; it is dropped if nobody is listening.
+    pub fn publish(&mut self, event: GameEvent) {
+        self.last_seq += 1;
+        let hand_id = event.hand_number().map(|hand_number| hand_id(&self.table_id, hand_number));
+        let action_id = match (&event, &hand_id) {
+            (GameEvent::PlayerActed { hand_number, .. }, Some(hand_id)) => {
+                if self.actions.0 != *hand_number {
+                    self.actions = (*hand_number, 0);
+                }
+                self.actions.1 += 1;
+                Some(format!("{hand_id}:{}", self.actions.1))
+            }
+            _ => None,
+        };
+        let event = Arc::new(SequencedEvent { seq: self.last_seq, table_id: self.table_id.clone(), hand_id, action_id, event });
+        if self.history.len() == HISTORY_LEN {
+            self.history.pop_front();
+        }
+        self.history.push_back(event.clone());
+        let _ = self.sender.send(event);
+    }
+
+    /// A sender others can `subscribe` through without access to the bus itself.
+    pub fn sender(&self) -> broadcast::Sender<Arc<SequencedEvent>> {
+        self.sender.clone()
+    }
+
+    /// Subscribes and returns the retained events numbered after `seq`, so a
+    /// client resuming from `seq` sees every later event exactly once (as far
+    /// back as the history reaches).
+    pub fn subscribe_after(&self, seq: u64) -> (Vec<Arc<SequencedEvent>>, broadcast::Receiver<Arc<SequencedEvent>>) {
+        let missed = self.history.iter().filter(|event| event.seq > seq).cloned().collect();
+        (missed, self.sender.subscribe())
+    }
+
+    /// Whether the retained events reach back to just after `seq`, so a
+    /// client resuming from it misses nothing. A `seq` beyond the latest
+    /// event, e.g. from before a server restart, can't be resumed either.
+    pub fn can_resume_from(&self, seq: u64) -> bool {
+        seq <= self.last_seq && self.last_seq - seq <= self.history.len() as u64
+    }
+
+    /// Sequence number of the latest event (0 before the first one).
+    pub fn last_seq(&self) -> u64 {
+        self.last_seq
+    }
+
+    pub fn table_id(&self) -> &str {
+        &self.table_id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_events_are_numbered_and_tagged() {
+        let mut bus = EventBus::new("main");
+        let mut receiver = bus.sender().subscribe();
+        bus.publish(GameEvent::TablePaused);
+        bus.publish(GameEvent::HandCancelled { hand_number: 4 });
+
+        assert_eq!(receiver.try_recv().unwrap().seq, 1);
+        let event = receiver.try_recv().unwrap();
+        assert_eq!(
+            serde_json::to_value(&*event).unwrap(),
+            serde_json::json!({"seq": 2, "table_id": "main", "hand_id": "main:4", "type": "hand_cancelled", "hand_number": 4})
+        );
+        assert_eq!(bus.last_seq(), 2);
+        assert_eq!(event.event.kind(), "hand_cancelled");
+    }
+
+    #[test]
+    fn test_actions_are_numbered_within_their_hand() {
+        use poker_core::player::PlayerAction;
+
+        let mut bus = EventBus::new("main");
+        let mut receiver = bus.sender().subscribe();
+        let acted = |hand_number| GameEvent::PlayerActed { hand_number, player_id: "1".into(), action: PlayerAction::Call };
+        bus.publish(acted(7));
+        bus.publish(acted(7));
+        bus.publish(GameEvent::TablePaused);
+        bus.publish(acted(8));
+        let action_ids: Vec<Option<String>> = std::iter::from_fn(|| receiver.try_recv().ok()).map(|event| event.action_id.clone()).collect();
+        assert_eq!(action_ids, [Some("main:7:1".to_string()), Some("main:7:2".to_string()), None, Some("main:8:1".to_string())]);
+
+        assert_eq!(parse_hand_id("main", "main:7"), Ok(7));
+        assert_eq!(parse_hand_id("main", "7"), Ok(7));
+        assert!(matches!(parse_hand_id("main", "side:7"), Err(GameError::InvalidRequest(_))));
+        assert!(matches!(parse_hand_id("main", "main:seven"), Err(GameError::InvalidRequest(_))));
+    }
+
+    #[test]
+    fn test_resume_after_sequence_number() {
+        let mut bus = EventBus::new("main");
+        bus.publish(GameEvent::TablePaused);
+        bus.publish(GameEvent::TableResumed);
+
+        let (missed, mut receiver) = bus.subscribe_after(1);
+        assert_eq!(missed.iter().map(|event| event.seq).collect::<Vec<_>>(), vec![2]);
+
+        bus.publish(GameEvent::TablePaused);
+        assert_eq!(receiver.try_recv().unwrap().seq, 3);
+        assert!(bus.can_resume_from(0) && bus.can_resume_from(3));
+        assert!(!bus.can_resume_from(4));
+    }
+
+    #[test]
+    fn test_resume_beyond_history() {
+        let mut bus = EventBus::new("main");
+        for _ in 0..=HISTORY_LEN {
+            bus.publish(GameEvent::TablePaused);
+        }
+        assert!(!bus.can_resume_from(0));
+        assert!(bus.can_resume_from(1));
+    }
+}
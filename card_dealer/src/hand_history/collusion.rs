@@ -0,0 +1,308 @@
+//! Signs of collusion in a table's finished hands, for an admin to look
+//! into: chips handed over by folding after a big investment, players who
+//! never stand up to one particular opponent, and players who logged in
+//! from the same address. A flag is a lead, not a verdict, and only the
+//! hands the history still keeps are looked at.
+//!
+//! Each pattern is a `CollusionCheck`; `analyze` runs any set of them.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::net::IpAddr;
+use std::sync::Mutex;
+
+use poker_core::chips::Chips;
+use poker_core::player::PlayerAction;
+use poker_core::player_id::PlayerId;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use super::{contributions, HandHistory, RecordedHand};
+
+/// The addresses each player has logged in from.
+pub type Addresses = HashMap<PlayerId, BTreeSet<IpAddr>>;
+
+/// Addresses kept per player; older ones are forgotten first.
+const MAX_ADDRESSES_PER_PLAYER: usize = 32;
+
+/// Something about a pair of players that looks like they work together.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CollusionFlag {
+    /// `from` folded after putting in a lot, in pots `to` took alone.
+    ChipDumping { from: PlayerId, to: PlayerId, hands: Vec<u64>, chips: Chips },
+    /// `player_id` folded to nearly every bet or raise of `towards`, far
+    /// more often than to anyone else's. `fold_rate_vs_others` is none if
+    /// they never faced anyone else's bet.
+    SoftPlay { player_id: PlayerId, towards: PlayerId, spots: usize, folds: usize, fold_rate_vs_others: Option<f64> },
+    /// Players dealt into the same hands who logged in from the same address.
+    SharedAddress { players: Vec<PlayerId>, addresses: Vec<String>, hands_together: usize },
+}
+
+/// One pattern looked for in the hands a table keeps.
+pub trait CollusionCheck: Send + Sync {
+    fn check(&self, hands: &[&RecordedHand], addresses: &Addresses) -> Vec<CollusionFlag>;
+}
+
+/// Flags a player who, more than once, folded to a pot one other player
+/// then won alone after putting in at least `min_big_blinds` big blinds.
+#[derive(Debug, Clone, Copy)]
+pub struct ChipDumpingCheck {
+    pub min_big_blinds: u64,
+    pub min_hands: usize,
+}
+
+impl Default for ChipDumpingCheck {
+    fn default() -> Self {
+        Self { min_big_blinds: 20, min_hands: 2 }
+    }
+}
+
+impl CollusionCheck for ChipDumpingCheck {
+    fn check(&self, hands: &[&RecordedHand], _: &Addresses) -> Vec<CollusionFlag> {
+        let mut dumped: BTreeMap<(&PlayerId, &PlayerId), (Vec<u64>, Chips)> = BTreeMap::new();
+        for hand in hands {
+            let log = &hand.log;
+            let winners = hand.winners();
+            let [winner] = winners[..] else { continue };
+            let put_in = contributions(log);
+            let folded = log.actions.iter().filter(|action| action.action == PlayerAction::Fold).map(|action| &action.player_id);
+            for loser in folded.filter(|loser| *loser != winner) {
+                let invested = put_in.get(loser).copied().unwrap_or_default();
+                if invested >= log.big_blind * self.min_big_blinds && !invested.is_zero() {
+                    let (hands, chips) = dumped.entry((loser, winner)).or_default();
+                    hands.push(log.hand_number);
+                    *chips += invested;
+                }
+            }
+        }
+        dumped
+            .into_iter()
+            .filter(|(_, (hands, _))| hands.len() >= self.min_hands)
+            .map(|((from, to), (hands, chips))| CollusionFlag::ChipDumping { from: from.clone(), to: to.clone(), hands, chips })
+            .collect()
+    }
+}
+
+/// Flags a player who folded to at least `min_fold_rate` of the bets and
+/// raises of one opponent, over at least `min_spots` of them, and to at
+/// least `min_gap` more of them than of everyone else's.
+#[derive(Debug, Clone, Copy)]
+pub struct SoftPlayCheck {
+    pub min_spots: usize,
+    pub min_fold_rate: f64,
+    pub min_gap: f64,
+}
+
+impl Default for SoftPlayCheck {
+    fn default() -> Self {
+        Self { min_spots: 5, min_fold_rate: 0.9, min_gap: 0.5 }
+    }
+}
+
+impl CollusionCheck for SoftPlayCheck {
+    fn check(&self, hands: &[&RecordedHand], _: &Addresses) -> Vec<CollusionFlag> {
+        let mut faced: BTreeMap<(&PlayerId, &PlayerId), (usize, usize)> = BTreeMap::new(); // Spots and folds, by player and bettor
+        let mut totals: HashMap<&PlayerId, (usize, usize)> = HashMap::new();
+        for hand in hands {
+            let mut street = None;
+            let mut aggressor: Option<&PlayerId> = None; // Last to bet or raise on the street
+            for action in &hand.log.actions {
+                if street != Some(action.street) {
+                    street = Some(action.street);
+                    aggressor = None;
+                }
+                if let Some(bettor) = aggressor.filter(|bettor| **bettor != action.player_id) {
+                    let folded = usize::from(action.action == PlayerAction::Fold);
+                    for (spots, folds) in [faced.entry((&action.player_id, bettor)).or_default(), totals.entry(&action.player_id).or_default()] {
+                        *spots += 1;
+                        *folds += folded;
+                    }
+                }
+                if matches!(action.action, PlayerAction::Bet(_) | PlayerAction::Raise(_)) {
+                    aggressor = Some(&action.player_id);
+                }
+            }
+        }
+        faced
+            .into_iter()
+            .filter_map(|((player_id, towards), (spots, folds))| {
+                let fold_rate = folds as f64 / spots as f64;
+                let (all_spots, all_folds) = totals[player_id];
+                let fold_rate_vs_others = (all_spots > spots).then(|| (all_folds - folds) as f64 / (all_spots - spots) as f64);
+                let flagged = spots >= self.min_spots
+                    && fold_rate >= self.min_fold_rate
+                    && fold_rate_vs_others.is_none_or(|others| fold_rate - others >= self.min_gap);
+                flagged.then(|| CollusionFlag::SoftPlay { player_id: player_id.clone(), towards: towards.clone(), spots, folds, fold_rate_vs_others })
+            })
+            .collect()
+    }
+}
+
+/// Flags players dealt into the same hands who logged in from an address in common.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SharedAddressCheck;
+
+impl CollusionCheck for SharedAddressCheck {
+    fn check(&self, hands: &[&RecordedHand], addresses: &Addresses) -> Vec<CollusionFlag> {
+        let mut together: BTreeMap<(&PlayerId, &PlayerId), usize> = BTreeMap::new();
+        for hand in hands {
+            let mut players: Vec<&PlayerId> = hand.log.seats.iter().collect();
+            players.sort();
+            for (index, first) in players.iter().enumerate() {
+                for second in &players[index + 1..] {
+                    *together.entry((*first, *second)).or_default() += 1;
+                }
+            }
+        }
+        together
+            .into_iter()
+            .filter_map(|((first, second), hands_together)| {
+                let shared: Vec<String> = addresses.get(first)?.intersection(addresses.get(second)?).map(IpAddr::to_string).collect();
+                (!shared.is_empty()).then(|| CollusionFlag::SharedAddress {
+                    players: vec![first.clone(), second.clone()],
+                    addresses: shared,
+                    hands_together,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Every built-in check, with its default thresholds.
+pub fn default_checks() -> Vec<Box<dyn CollusionCheck>> {
+    vec![Box::new(ChipDumpingCheck::default()), Box::new(SoftPlayCheck::default()), Box::new(SharedAddressCheck)]
+}
+
+/// Runs `checks` over the finished hands `history` keeps, in order; the
+/// flags come back check by check. Returns the number of hands looked at too.
+pub fn analyze(history: &HandHistory, addresses: &Addresses, checks: &[Box<dyn CollusionCheck>]) -> (usize, Vec<CollusionFlag>) {
+    let hands: Vec<&RecordedHand> = history.hands.iter().filter(|hand| hand.log.finished).collect();
+    let flags = checks.iter().flat_map(|check| check.check(&hands, addresses)).collect();
+    (hands.len(), flags)
+}
+
+/// The addresses players have logged in from, for `SharedAddressCheck`.
+#[derive(Debug, Default)]
+pub struct LoginAddresses {
+    players: Mutex<HashMap<PlayerId, Vec<IpAddr>>>, // Oldest first; only locked briefly, never across an await
+}
+
+impl LoginAddresses {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Notes that `player_id` logged in from `address`.
+    pub fn record(&self, player_id: &str, address: IpAddr) {
+        let mut players = self.players.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let seen = players.entry(player_id.into()).or_default();
+        seen.retain(|known| *known != address);
+        if seen.len() == MAX_ADDRESSES_PER_PLAYER {
+            seen.remove(0);
+        }
+        seen.push(address);
+    }
+
+    /// Every player's addresses as they stand now.
+    pub fn snapshot(&self) -> Addresses {
+        let players = self.players.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        players.iter().map(|(player_id, seen)| (player_id.clone(), seen.iter().copied().collect())).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use poker_core::betting::{HandLog, LoggedAction, LoggedPlayer, Street};
+    use poker_core::variant::GameVariant;
+
+    /// A three-handed hand at 5/10, the button "1", with `actions` given as
+    /// each player's total put in once they acted, and `winner` taking the pot.
+    fn hand(hand_number: u64, actions: &[(&str, PlayerAction, u64)], winner: &str) -> RecordedHand {
+        let ids = ["1", "2", "3"];
+        let log = HandLog {
+            hand_number,
+            variant: GameVariant::HoldEm,
+            seats: ids.iter().map(|id| PlayerId::from(*id)).collect(),
+            players: ids.iter().enumerate().map(|(table_position, id)| LoggedPlayer {
+                player_id: PlayerId::from(*id),
+                display_name: id.to_string(),
+                table_position,
+                stack: Chips::new(1000),
+            }).collect(),
+            small_blind: Chips::new(5),
+            big_blind: Chips::new(10),
+            ante: Chips::ZERO,
+            straddle: None,
+            hole_cards: HashMap::new(),
+            actions: actions.iter().map(|(player_id, action, hand_total)| LoggedAction {
+                street: Street::Preflop,
+                player_id: PlayerId::from(*player_id),
+                action: action.clone(),
+                hand_total: Chips::new(*hand_total),
+            }).collect(),
+            board: Vec::new(),
+            extra_boards: Vec::new(),
+            eliminations: Vec::new(),
+            all_in: Vec::new(),
+            payouts: vec![(PlayerId::from(winner), Chips::new(100))],
+            rake: Chips::ZERO,
+            streets: Vec::new(),
+            showdown: Vec::new(),
+            shown: Vec::new(),
+            finished: true,
+        };
+        RecordedHand { log, finished_at: 0, max_seats: 6 }
+    }
+
+    #[test]
+    fn test_flags() {
+        let mut history = HandHistory::new();
+        // "1" raises big and folds to "3" twice, then folds to every raise of "3" but calls "2"
+        for hand_number in 1..=2 {
+            history.hands.push_back(hand(hand_number, &[
+                ("1", PlayerAction::Raise(Chips::new(300)), 300),
+                ("2", PlayerAction::Fold, 5),
+                ("3", PlayerAction::Raise(Chips::new(1000)), 1000),
+                ("1", PlayerAction::Fold, 300),
+            ], "3"));
+        }
+        for hand_number in 3..=5 {
+            history.hands.push_back(hand(hand_number, &[
+                ("1", PlayerAction::Call, 10),
+                ("2", PlayerAction::Call, 10),
+                ("3", PlayerAction::Raise(Chips::new(40)), 40),
+                ("1", PlayerAction::Fold, 10),
+                ("2", PlayerAction::Fold, 10),
+            ], "3"));
+        }
+        history.hands.push_back(hand(6, &[
+            ("1", PlayerAction::Call, 10),
+            ("2", PlayerAction::Raise(Chips::new(40)), 40),
+            ("3", PlayerAction::Fold, 10),
+            ("1", PlayerAction::Call, 40),
+        ], "1"));
+
+        let logins = LoginAddresses::new();
+        logins.record("1", [10, 0, 0, 1].into());
+        logins.record("3", [10, 0, 0, 1].into());
+        logins.record("2", [10, 0, 0, 2].into());
+
+        let (hands, flags) = analyze(&history, &logins.snapshot(), &default_checks());
+        assert_eq!(hands, 6);
+        assert_eq!(flags, vec![
+            CollusionFlag::ChipDumping { from: "1".into(), to: "3".into(), hands: vec![1, 2], chips: Chips::new(600) },
+            CollusionFlag::SoftPlay { player_id: "1".into(), towards: "3".into(), spots: 5, folds: 5, fold_rate_vs_others: Some(0.0) },
+            CollusionFlag::SharedAddress { players: vec!["1".into(), "3".into()], addresses: vec!["10.0.0.1".to_string()], hands_together: 6 },
+        ]);
+
+        // "2" folded to every raise of "3" too, but just as readily to "1"
+        let soft_play = SoftPlayCheck { min_spots: 3, ..SoftPlayCheck::default() };
+        let hands: Vec<&RecordedHand> = history.hands.iter().collect();
+        let flagged: Vec<_> = soft_play.check(&hands, &Addresses::new()).into_iter().map(|flag| match flag {
+            CollusionFlag::SoftPlay { player_id, .. } => player_id,
+            other => panic!("unexpected {other:?}"),
+        }).collect();
+        assert_eq!(flagged, [PlayerId::from("1")]);
+    }
+}
@@ -0,0 +1,201 @@
+//! Bulk CSV exports of finished hands for offline analysis, e.g. in pandas
+//! or a spreadsheet: one row per hand, per action, or per player per hand.
+//! Hole cards are only included where they were shown at showdown.
+
+use poker_core::betting::Street;
+use poker_core::game_error::GameError;
+use poker_core::player::PlayerAction;
+use poker_core::player_id::PlayerId;
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+use super::{cards, contributions, winnings, RecordedHand};
+
+/// What each row of an export describes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Dataset {
+    Hands,      // One row per hand
+    Actions,    // One row per action, in the order taken
+    Results,    // One row per player dealt into each hand
+}
+
+impl Dataset {
+    /// Every column, in the order exported when no selection is given.
+    pub fn columns(self) -> &'static [&'static str] {
+        match self {
+            Dataset::Hands => &["hand_number", "played_at", "variant", "small_blind", "big_blind", "players", "board", "pot", "winners", "showdown"],
+            Dataset::Actions => &["hand_number", "played_at", "sequence", "street", "player_id", "action", "amount", "hand_total"],
+            Dataset::Results => &["hand_number", "played_at", "player_id", "display_name", "seat", "starting_stack", "invested", "won", "net", "shown_cards"],
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Dataset::Hands => "hands",
+            Dataset::Actions => "actions",
+            Dataset::Results => "results",
+        }
+    }
+
+    /// The rows `hand` contributes, with a field for every column.
+    fn rows(self, hand: &RecordedHand) -> Vec<Vec<String>> {
+        let log = &hand.log;
+        let (hand_number, played_at) = (log.hand_number.to_string(), hand.finished_at.to_string());
+        let put_in = contributions(log);
+        match self {
+            Dataset::Hands => {
+                let winners: Vec<String> = hand.winners().into_iter().map(PlayerId::to_string).collect();
+                vec![vec![
+                    hand_number,
+                    played_at,
+                    serde_json::to_value(log.variant).ok().and_then(|name| name.as_str().map(str::to_string)).unwrap_or_default(),
+                    log.small_blind.to_string(),
+                    log.big_blind.to_string(),
+                    log.players.len().to_string(),
+                    cards(&log.board),
+                    hand.pot().to_string(),
+                    winners.join(" "),
+                    (!log.showdown.is_empty()).to_string(),
+                ]]
+            }
+            Dataset::Actions => log.actions.iter().enumerate()
+                .map(|(index, action)| {
+                    let (name, amount) = match action.action {
+                        PlayerAction::Fold | PlayerAction::SitOut => ("fold", None),
+                        PlayerAction::Check => ("check", None),
+                        PlayerAction::Call => ("call", None),
+                        PlayerAction::Bet(amount) => ("bet", Some(amount)),
+                        PlayerAction::Raise(amount) => ("raise", Some(amount)),
+                    };
+                    vec![
+                        hand_number.clone(),
+                        played_at.clone(),
+                        (index + 1).to_string(),
+                        street_column(action.street).to_string(),
+                        action.player_id.to_string(),
+                        name.to_string(),
+                        amount.map_or_else(String::new, |amount| amount.to_string()),
+                        action.hand_total.to_string(),
+                    ]
+                })
+                .collect(),
+            Dataset::Results => log.players.iter()
+                .map(|player| {
+                    let invested = put_in.get(&player.player_id).copied().unwrap_or_default();
+                    let won = winnings(log, &player.player_id);
+                    let net = i128::from(won.get()) - i128::from(invested.get());
+                    let shown = log.hole_cards.get(&player.player_id).filter(|_| log.showdown.contains(&player.player_id));
+                    vec![
+                        hand_number.clone(),
+                        played_at.clone(),
+                        player.player_id.to_string(),
+                        player.display_name.clone(),
+                        (player.table_position + 1).to_string(),
+                        player.stack.to_string(),
+                        invested.to_string(),
+                        won.to_string(),
+                        net.to_string(),
+                        shown.map_or_else(String::new, |shown| cards(shown)),
+                    ]
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Writes `hands` as CSV with a header row, keeping only `columns`, in the
+/// order given, or every column when there's no selection.
+pub fn to_csv<'a>(hands: impl IntoIterator<Item = &'a RecordedHand>, dataset: Dataset, columns: Option<&[String]>) -> Result<String, GameError> {
+    let all = dataset.columns();
+    let selected: Vec<usize> = match columns {
+        None => (0..all.len()).collect(),
+        Some(columns) => columns.iter()
+            .map(|column| {
+                all.iter().position(|candidate| candidate == column).ok_or_else(|| {
+                    GameError::InvalidRequest(format!("unknown {} column {column:?}, expected one of {}", dataset.name(), all.join(", ")))
+                })
+            })
+            .collect::<Result<_, _>>()?,
+    };
+    if selected.is_empty() {
+        return Err(GameError::InvalidRequest("select at least one column".to_string()));
+    }
+    let mut text = String::new();
+    let mut write_row = |fields: Vec<&str>| {
+        text.push_str(&fields.into_iter().map(quoted).collect::<Vec<_>>().join(","));
+        text.push_str("\r\n");
+    };
+    write_row(selected.iter().map(|&index| all[index]).collect());
+    for hand in hands {
+        for row in dataset.rows(hand) {
+            write_row(selected.iter().map(|&index| row[index].as_str()).collect());
+        }
+    }
+    Ok(text)
+}
+
+/// A field as RFC 4180 writes it: quoted, with quotes doubled, when it holds
+/// a comma, a quote or a line break.
+fn quoted(field: &str) -> String {
+    if field.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn street_column(street: Street) -> &'static str {
+    match street {
+        Street::Preflop => "preflop",
+        Street::Flop => "flop",
+        Street::Turn => "turn",
+        Street::River => "river",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use poker_core::card_dealer::parse_cards;
+    use poker_core::chips::Chips;
+    use poker_core::game_controller::GameController;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use crate::hand_history::HandHistory;
+
+    #[test]
+    fn test_csv_datasets() {
+        let mut controller = GameController::with_rng(StdRng::seed_from_u64(1));
+        controller.initialize_players(vec![
+            ("1".to_string(), "Smith, \"Al\"".to_string(), 0, Chips::new(1000)),
+            ("2".to_string(), "Bob".to_string(), 1, Chips::new(1000)),
+        ]);
+        controller.stack_deck(parse_cards("Ah Ad Kc Kd 2c 7h 9s Jd 3c").unwrap()).unwrap();
+        controller.start_hand(Chips::new(5), Chips::new(10)).unwrap();
+        controller.act("1", PlayerAction::Raise(Chips::new(30))).unwrap();
+        controller.act("2", PlayerAction::Fold).unwrap();
+        let mut history = HandHistory::new();
+        for log in controller.take_finished_hands() {
+            history.record(log, 6);
+        }
+        let hands: Vec<&RecordedHand> = history.between(None, None).collect();
+        let select = |columns: &[&str]| columns.iter().map(|column| column.to_string()).collect::<Vec<_>>();
+
+        // The uncalled 30 goes back to Alice and isn't part of the pot
+        let text = to_csv(hands.iter().copied(), Dataset::Hands, Some(&select(&["hand_number", "variant", "pot", "winners", "showdown"]))).unwrap();
+        assert_eq!(text, "hand_number,variant,pot,winners,showdown\r\n1,hold_em,20,1,false\r\n");
+
+        let text = to_csv(hands.iter().copied(), Dataset::Actions, Some(&select(&["sequence", "street", "player_id", "action", "amount", "hand_total"]))).unwrap();
+        assert_eq!(text, "sequence,street,player_id,action,amount,hand_total\r\n1,preflop,1,raise,30,40\r\n2,preflop,2,fold,,10\r\n");
+
+        let text = to_csv(hands.iter().copied(), Dataset::Results, Some(&select(&["display_name", "seat", "invested", "won", "net", "shown_cards"]))).unwrap();
+        assert_eq!(text, "display_name,seat,invested,won,net,shown_cards\r\n\"Smith, \"\"Al\"\"\",1,40,50,10,\r\nBob,2,10,0,-10,\r\n");
+
+        let everything = to_csv(hands.iter().copied(), Dataset::Results, None).unwrap();
+        assert!(everything.starts_with(&format!("{}\r\n", Dataset::Results.columns().join(","))), "{everything}");
+        assert!(matches!(to_csv(hands.iter().copied(), Dataset::Hands, Some(&select(&["rake"]))), Err(GameError::InvalidRequest(_))));
+        assert!(matches!(to_csv(hands.iter().copied(), Dataset::Hands, Some(&[])), Err(GameError::InvalidRequest(_))));
+    }
+}
@@ -0,0 +1,927 @@
+//! The hands finished at a table, kept for replaying and exporting, and the
+//! PokerStars text format that tracking software such as PokerTracker and
+//! Hold'em Manager imports. Hands played elsewhere can be read back from
+//! PokerStars or GGPoker text, so they can be analysed like our own. The
+//! vendor-neutral PHH format lives in `phh`.
+
+use std::collections::{HashMap, VecDeque};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use poker_core::betting::{HandLog, LoggedAction, LoggedPlayer, Street, StreetBets};
+use poker_core::card_dealer::Card;
+use poker_core::chips::Chips;
+use poker_core::game_error::GameError;
+use poker_core::player::PlayerAction;
+use poker_core::player_id::PlayerId;
+use poker_core::variant::GameVariant;
+
+pub mod collusion;
+pub mod csv;
+pub mod phh;
+pub mod query;
+pub mod sessions;
+
+/// Finished hands a table keeps; older ones are dropped.
+const HISTORY_LEN: usize = 200;
+
+/// A finished hand and when it ended.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedHand {
+    pub log: HandLog,
+    pub finished_at: u64,   // Milliseconds since the Unix epoch
+    pub max_seats: usize,   // Seats at the table when the hand was played
+}
+
+impl RecordedHand {
+    /// Chips in the pots at the end of the hand, leaving out a bet nobody
+    /// called.
+    pub fn pot(&self) -> Chips {
+        pot(&self.log)
+    }
+
+    /// Players the pots paid, each once, the main pot's winners first.
+    pub fn winners(&self) -> Vec<&PlayerId> {
+        let mut winners: Vec<&PlayerId> = Vec::new();
+        for (winner, _) in &self.log.payouts {
+            if !winners.contains(&winner) {
+                winners.push(winner);
+            }
+        }
+        winners
+    }
+}
+
+/// A table's most recent finished hands, oldest first.
+#[derive(Debug, Default)]
+pub struct HandHistory {
+    hands: VecDeque<RecordedHand>,
+    seat_changes: Vec<(PlayerId, u64)>, // Players seated or unseated, with the last hand finished before
+}
+
+impl HandHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a hand that has just finished. A hand already kept, recorded
+    /// again once a winner showed their cards, replaces the earlier entry.
+    pub fn record(&mut self, log: HandLog, max_seats: usize) {
+        if let Some(hand) = self.hands.iter_mut().find(|hand| hand.log.hand_number == log.hand_number) {
+            hand.log = log;
+            return;
+        }
+        let finished_at = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_millis() as u64);
+        if self.hands.len() == HISTORY_LEN {
+            self.hands.pop_front();
+            let oldest = self.hands.front().map_or(0, |hand| hand.log.hand_number);
+            self.seat_changes.retain(|(_, after)| *after >= oldest);
+        }
+        self.hands.push_back(RecordedHand { log, finished_at, max_seats });
+    }
+
+    /// Notes that `player_id` sat down at or left the table, which starts or
+    /// ends one of their sessions. Record the hands finished before first.
+    pub fn record_seat_change(&mut self, player_id: PlayerId) {
+        let after = self.hands.back().map_or(0, |hand| hand.log.hand_number);
+        self.seat_changes.push((player_id, after));
+    }
+
+    /// The hands that finished from `from` up to, not including, `to`, in
+    /// milliseconds since the Unix epoch; oldest first.
+    pub fn between(&self, from: Option<u64>, to: Option<u64>) -> impl Iterator<Item = &RecordedHand> {
+        self.hands.iter().filter(move |hand| from.is_none_or(|from| hand.finished_at >= from) && to.is_none_or(|to| hand.finished_at < to))
+    }
+
+    /// The hand numbered `hand_number`, if it is still kept.
+    pub fn get(&self, hand_number: u64) -> Result<&RecordedHand, GameError> {
+        self.hands.iter().find(|hand| hand.log.hand_number == hand_number).ok_or(GameError::HandNotFound(hand_number))
+    }
+}
+
+/// Renders `hand` as a PokerStars hand history, as seen by `viewer`: their
+/// own hole cards are dealt to them, everyone else's only show if they
+/// reach showdown. Double-board hands have no PokerStars equivalent.
+pub fn to_pokerstars(hand: &RecordedHand, table_id: &str, viewer: Option<&str>) -> Result<String, GameError> {
+    let log = &hand.log;
+    let game = match log.variant {
+        GameVariant::HoldEm => "Hold'em No Limit",
+        GameVariant::Omaha => "Omaha No Limit",
+        GameVariant::FiveCardOmaha => "5 Card Omaha No Limit",
+        GameVariant::Courchevel => "Courchevel No Limit",
+        GameVariant::DoubleBoardHoldEm => {
+            return Err(GameError::InvalidRequest("double-board hands can't be exported in PokerStars format".to_string()));
+        }
+    };
+    let players: HashMap<&PlayerId, &LoggedPlayer> = log.players.iter().map(|player| (&player.player_id, player)).collect();
+    let name = |id: &PlayerId| players.get(id).map_or_else(|| id.to_string(), |player| player.display_name.clone());
+    let stack = |id: &PlayerId| players.get(id).map_or(Chips::ZERO, |player| player.stack);
+    let hole_cards = |id: &PlayerId| cards(log.hole_cards.get(id).map_or(&[][..], Vec::as_slice));
+
+    let mut lines = vec![
+        format!("PokerStars Hand #{}: {game} ({}/{}) - {} UTC", log.hand_number, log.small_blind, log.big_blind, timestamp(hand.finished_at)),
+        format!("Table '{table_id}' {}-max Seat #{} is the button", hand.max_seats, players.get(&log.seats[0]).map_or(0, |player| player.table_position + 1)),
+    ];
+    for player in &log.players {
+        lines.push(format!("Seat {}: {} ({} in chips)", player.table_position + 1, player.display_name, player.stack));
+    }
+
+    let [(small, _), (big, big_blind)] = blinds(log);
+    let mut put_in: HashMap<&PlayerId, Chips> = HashMap::new();
+    let mut street_start = HashMap::new();  // What each player had put in before the street, the ante counting as before preflop
+    if let Some((id, ante)) = ante(log) {
+        put_in.insert(id, ante);
+        street_start.insert(id, ante);
+        lines.push(format!("{}: posts the ante {ante}", name(id)));
+    }
+    for ((id, posted), label) in blinds(log).into_iter().zip(["small", "big"]) {
+        *put_in.entry(id).or_default() += posted;
+        lines.push(format!("{}: posts {label} blind {posted}", name(id)));
+    }
+    if let Some((id, straddle)) = &log.straddle {
+        *put_in.entry(id).or_default() += *straddle;
+        lines.push(format!("{}: posts straddle {straddle}", name(id)));
+    }
+    lines.push("*** HOLE CARDS ***".to_string());
+    if let Some(viewer) = viewer.map(PlayerId::from).filter(|viewer| log.hole_cards.contains_key(viewer)) {
+        lines.push(format!("Dealt to {} [{}]", name(&viewer), hole_cards(&viewer)));
+    }
+
+    let mut street = Street::Preflop;
+    let mut to_match = log.straddle.as_ref().map_or(big_blind, |(_, straddle)| big_blind.max(*straddle)); // The street's biggest total so far
+    let mut folded: HashMap<&PlayerId, Street> = HashMap::new();
+    for action in &log.actions {
+        if action.street != street {
+            announce_streets(log, &mut street, action.street, &mut lines);
+            street_start = put_in.clone();
+            to_match = Chips::ZERO;
+        }
+        let who = name(&action.player_id);
+        let before = put_in.get(&action.player_id).copied().unwrap_or_default();
+        let street_total = action.hand_total - street_start.get(&action.player_id).copied().unwrap_or_default();
+        let all_in = if action.hand_total >= stack(&action.player_id) { " and is all-in" } else { "" };
+        lines.push(match action.action {
+            PlayerAction::Fold | PlayerAction::SitOut => {
+                folded.insert(&action.player_id, street);
+                format!("{who}: folds")
+            }
+            PlayerAction::Check => format!("{who}: checks"),
+            PlayerAction::Call => format!("{who}: calls {}{all_in}", action.hand_total - before),
+            PlayerAction::Bet(_) => format!("{who}: bets {street_total}{all_in}"),
+            PlayerAction::Raise(_) => format!("{who}: raises {} to {street_total}{all_in}", street_total - to_match.min(street_total)),
+        });
+        to_match = to_match.max(street_total);
+        put_in.insert(&action.player_id, action.hand_total);
+    }
+    // Streets dealt out after everyone was all-in
+    announce_streets(log, &mut street, Street::River, &mut lines);
+
+    // The engine leaves a bet nobody called in a pot of its own; trackers expect it handed back
+    let uncalled = uncalled_bet(log, &put_in);
+    let mut collected: Vec<(&PlayerId, Chips)> = Vec::new();
+    for (id, amount) in &log.payouts {
+        match collected.iter_mut().find(|(winner, _)| *winner == id) {
+            Some((_, total)) => *total += *amount,
+            None => collected.push((id, *amount)),
+        }
+    }
+    if let Some((id, excess)) = uncalled {
+        lines.push(format!("Uncalled bet ({excess}) returned to {}", name(id)));
+        if let Some((_, total)) = collected.iter_mut().find(|(winner, _)| *winner == id) {
+            *total = total.checked_sub(excess).unwrap_or_default();
+        }
+    }
+    collected.retain(|(_, amount)| !amount.is_zero());
+    if !log.showdown.is_empty() {
+        lines.push("*** SHOW DOWN ***".to_string());
+        for id in &log.showdown {
+            lines.push(format!("{}: shows [{}]", name(id), hole_cards(id)));
+        }
+    }
+    for (id, amount) in &collected {
+        lines.push(format!("{} collected {amount} from pot", name(id)));
+    }
+    for (id, shown) in &log.shown {
+        lines.push(format!("{}: shows [{}]", name(id), cards(shown)));
+    }
+
+    lines.push("*** SUMMARY ***".to_string());
+    let total: Chips = put_in.values().copied().sum::<Chips>() - uncalled.map_or(Chips::ZERO, |(_, excess)| excess);
+    lines.push(format!("Total pot {total} | Rake {}", log.rake));
+    if !log.board.is_empty() {
+        lines.push(format!("Board [{}]", cards(&log.board)));
+    }
+    for player in &log.players {
+        let id = &player.player_id;
+        let roles: String = [("button", &log.seats[0]), ("small blind", small), ("big blind", big)].into_iter()
+            .filter(|(_, holder)| *holder == id)
+            .map(|(role, _)| format!(" ({role})"))
+            .collect();
+        let won = collected.iter().find(|(winner, _)| *winner == id).map(|(_, amount)| *amount);
+        let outcome = match (folded.get(id), won) {
+            (Some(Street::Preflop), _) if !put_in.contains_key(id) => "folded before Flop (didn't bet)".to_string(),
+            (Some(Street::Preflop), _) => "folded before Flop".to_string(),
+            (Some(street), _) => format!("folded on the {}", street_name(*street)),
+            (None, Some(amount)) if log.showdown.contains(id) => format!("showed [{}] and won ({amount})", hole_cards(id)),
+            (None, None) if log.showdown.contains(id) => format!("showed [{}] and lost", hole_cards(id)),
+            (None, Some(amount)) => format!("collected ({amount})"),
+            (None, None) => "mucked".to_string(),
+        };
+        lines.push(format!("Seat {}: {}{roles} {outcome}", player.table_position + 1, player.display_name));
+    }
+    lines.push(String::new());
+    Ok(lines.join("\n"))
+}
+
+/// Reads PokerStars or GGPoker hand histories, one hand after another, as
+/// the site wrote them. Players are known by their screen names, which
+/// become their ids; amounts in a currency are counted in cents. Antes and
+/// straddles count towards what a player put in without being logged as
+/// actions, and rake is left out of the payouts. Eliminations and all-in
+/// equities aren't reconstructed.
+pub fn parse_hand_histories(text: &str) -> Result<Vec<RecordedHand>, GameError> {
+    let mut hands = Vec::new();
+    let mut current: Option<HandParser> = None;
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim_start_matches('\u{feff}').trim();
+        let invalid = |reason: String| GameError::InvalidRequest(format!("hand history line {}: {reason}", number + 1));
+        if line.starts_with("PokerStars ") || line.starts_with("Poker Hand #") {
+            if let Some(parser) = current.take() {
+                hands.push(parser.finish()?);
+            }
+            current = Some(HandParser::new(line).map_err(invalid)?);
+        } else if let Some(parser) = &mut current {
+            parser.read(line).map_err(invalid)?;
+        } else if !line.is_empty() {
+            return Err(invalid("expected a PokerStars or GGPoker hand header".to_string()));
+        }
+    }
+    if let Some(parser) = current {
+        hands.push(parser.finish()?);
+    }
+    if hands.is_empty() {
+        return Err(GameError::InvalidRequest("no hands found".to_string()));
+    }
+    Ok(hands)
+}
+
+/// Where in a hand history the parser is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Section {
+    Seats,      // Seats and blinds, before the hole cards
+    Play,       // Actions, streets and the pots being collected
+    Showdown,
+    Summary,    // Repeats what came before, so it's skipped
+}
+
+/// One hand being read, line by line.
+struct HandParser {
+    log: HandLog,
+    finished_at: u64,
+    max_seats: usize,
+    button: usize,                      // Seat number, counting from 1
+    section: Section,
+    street: Street,
+    put_in: HashMap<PlayerId, Chips>,   // Chips each player has put in so far
+    street_start: HashMap<PlayerId, Chips>, // What they had put in before the street, antes counting as before preflop
+    uncalled: Option<(PlayerId, Chips)>,
+}
+
+impl HandParser {
+    /// Starts a hand from its header, e.g.
+    /// `PokerStars Hand #1: Hold'em No Limit ($0.01/$0.02 USD) - 2024/05/01 13:45:00 ET`.
+    fn new(header: &str) -> Result<Self, String> {
+        let (_, rest) = header.split_once("Hand #").ok_or("expected `Hand #` in the header")?;
+        let (id, game) = rest.split_once(':').ok_or("expected `:` after the hand number")?;
+        let hand_number = id.chars().filter(char::is_ascii_digit).collect::<String>().parse::<u64>()
+            .map_err(|_| format!("no hand number in {id:?}"))?;
+        let variant = match () {
+            _ if game.contains("Hold'em") => GameVariant::HoldEm,
+            _ if game.contains("Hi/Lo") => return Err("hi/lo games can't be imported".to_string()),
+            _ if game.contains("5 Card Omaha") => GameVariant::FiveCardOmaha,
+            _ if game.contains("Card Omaha") => return Err("only four and five card Omaha can be imported".to_string()),
+            _ if game.contains("Omaha") => GameVariant::Omaha,
+            _ if game.contains("Courchevel") => GameVariant::Courchevel,
+            _ => return Err(format!("unsupported game in {game:?}")),
+        };
+        let blinds = game.split('(').skip(1)
+            .filter_map(|group| group.split_once(')').map(|(inside, _)| inside))
+            .find(|inside| inside.contains('/'))
+            .ok_or("no blinds in the header")?;
+        let (small_blind, big_blind) = blinds.split_whitespace().next().and_then(|blinds| blinds.split_once('/')).ok_or("no blinds in the header")?;
+        let (small_blind, big_blind) = (amount(small_blind)?, amount(big_blind)?);
+        let tokens: Vec<&str> = game.split_whitespace().collect();
+        let finished_at = tokens.windows(2).find_map(|pair| parse_timestamp(pair[0], pair[1])).unwrap_or(0);
+        Ok(Self {
+            log: HandLog {
+                hand_number,
+                variant,
+                seats: Vec::new(),
+                players: Vec::new(),
+                small_blind,
+                big_blind,
+                ante: Chips::ZERO,
+                straddle: None,
+                hole_cards: HashMap::new(),
+                actions: Vec::new(),
+                board: Vec::new(),
+                extra_boards: Vec::new(),
+                eliminations: Vec::new(),
+                all_in: Vec::new(),
+                payouts: Vec::new(),
+                rake: Chips::ZERO,
+                streets: Vec::new(),
+                showdown: Vec::new(),
+                shown: Vec::new(),
+                finished: true,
+            },
+            finished_at,
+            max_seats: 0,
+            button: 1,
+            section: Section::Seats,
+            street: Street::Preflop,
+            put_in: HashMap::new(),
+            street_start: HashMap::new(),
+            uncalled: None,
+        })
+    }
+
+    /// Reads the next line. Lines that don't change the hand, such as chat,
+    /// are skipped.
+    fn read(&mut self, line: &str) -> Result<(), String> {
+        if let Some(marker) = line.strip_prefix("*** ") {
+            return self.marker(marker);
+        }
+        if self.section == Section::Summary {
+            // e.g. `Total pot 380 | Rake 3`
+            if let Some(rake) = line.split(" | ").find_map(|part| part.strip_prefix("Rake ")) {
+                self.log.rake = amount(rake)?;
+            }
+            return Ok(());
+        }
+        if let Some(table) = line.strip_prefix("Table '") {
+            let (_, rest) = table.rsplit_once('\'').ok_or("unterminated table name")?;
+            self.max_seats = rest.split_whitespace().find_map(|token| token.strip_suffix("-max")?.parse().ok()).unwrap_or(0);
+            if let Some((_, button)) = rest.split_once("Seat #") {
+                self.button = button.split_whitespace().next().and_then(|seat| seat.parse().ok()).ok_or("unreadable button seat")?;
+            }
+            return Ok(());
+        }
+        if self.section == Section::Seats && line.starts_with("Seat ") {
+            return self.seat(line);
+        }
+        if let Some((amount_text, name)) = line.strip_prefix("Uncalled bet (").and_then(|rest| rest.split_once(") returned to ")) {
+            self.uncalled = Some((PlayerId::from(name), amount(amount_text)?));
+            return Ok(());
+        }
+        if let Some(dealt) = line.strip_prefix("Dealt to ") {
+            // GGPoker lists every player, without the cards of anyone but the hero
+            if let Some((name, cards)) = dealt.strip_suffix(']').and_then(|dealt| dealt.split_once(" [")) {
+                self.log.hole_cards.insert(PlayerId::from(name), parse_cards(cards)?);
+            }
+            return Ok(());
+        }
+        if let Some((name, rest)) = line.split_once(" collected ") {
+            if let Some(player_id) = self.player(name) {
+                let collected = rest.split_whitespace().next().ok_or("expected an amount collected")?;
+                self.log.payouts.push((player_id, amount(collected)?));
+            }
+            return Ok(());
+        }
+        let Some((player_id, rest)) = self.log.players.iter()
+            .filter_map(|player| Some((player.player_id.clone(), line.strip_prefix(player.display_name.as_str())?.strip_prefix(": ")?)))
+            .max_by_key(|(_, rest)| std::cmp::Reverse(rest.len())) // The longest name that fits, for names that prefix others
+        else {
+            return Ok(());
+        };
+        self.player_line(player_id, rest)
+    }
+
+    /// A `*** ... ***` line starting a new part of the hand.
+    fn marker(&mut self, marker: &str) -> Result<(), String> {
+        let (name, cards) = marker.split_once(" ***").ok_or("unterminated `***` marker")?;
+        let street = match name {
+            "HOLE CARDS" | "PRE-FLOP" => {
+                self.section = Section::Play;
+                return Ok(());
+            }
+            "SHOW DOWN" | "SHOWDOWN" => {
+                self.section = Section::Showdown;
+                return Ok(());
+            }
+            "SUMMARY" => {
+                self.section = Section::Summary;
+                return Ok(());
+            }
+            "FLOP" => Street::Flop,
+            "TURN" => Street::Turn,
+            "RIVER" => Street::River,
+            _ if name.starts_with("FIRST") || name.starts_with("SECOND") => return Err("hands run more than once can't be imported".to_string()),
+            _ => return Ok(()),
+        };
+        // Each bracket adds to the board, e.g. `[Kd 7c 2h] [9s]` on the turn
+        let board: String = cards.split(['[', ']']).skip(1).step_by(2).collect::<Vec<_>>().join(" ");
+        self.log.board = parse_cards(&board)?;
+        if self.log.board.len() != street.board_cards() {
+            return Err(format!("expected {} board cards by the {}", street.board_cards(), street_name(street)));
+        }
+        self.close_street();
+        self.section = Section::Play;
+        self.street = street;
+        self.street_start = self.put_in.clone();
+        Ok(())
+    }
+
+    /// A seat line, e.g. `Seat 1: Alice (1000 in chips)` or `Seat 2: Bob ($2.13 in chips) is sitting out`.
+    fn seat(&mut self, line: &str) -> Result<(), String> {
+        let (seat, rest) = line["Seat ".len()..].split_once(": ").ok_or("expected `Seat N: name (stack in chips)`")?;
+        let seat: usize = seat.parse().map_err(|_| format!("unreadable seat {seat:?}"))?;
+        let (player, status) = rest.split_once(" in chips").ok_or("expected `(stack in chips)`")?;
+        if status.contains("sitting out") || status.contains("out of hand") {
+            return Ok(());
+        }
+        let (name, stack) = player.rsplit_once(" (").ok_or("expected `(stack in chips)`")?;
+        self.log.players.push(LoggedPlayer {
+            player_id: PlayerId::from(name),
+            display_name: name.to_string(),
+            table_position: seat.checked_sub(1).ok_or("seats count from 1")?,
+            stack: amount(stack)?,
+        });
+        Ok(())
+    }
+
+    /// What a player did, the part of the line after `name: `.
+    fn player_line(&mut self, player_id: PlayerId, rest: &str) -> Result<(), String> {
+        let rest = rest.strip_suffix(" and is all-in").unwrap_or(rest);
+        let mut words = rest.split_whitespace();
+        let verb = words.next().unwrap_or_default();
+        let put_in = self.put_in.get(&player_id).copied().unwrap_or_default();
+        let street_start = self.street_start.get(&player_id).copied().unwrap_or_default();
+        let last = |rest: &str| rest.split_whitespace().last().ok_or("expected an amount").and_then(|text| amount(text).map_err(|_| "unreadable amount"));
+        let action = match verb {
+            "posts" => {
+                let posted = last(rest)?;
+                self.put_in.insert(player_id.clone(), put_in + posted);
+                if rest.contains("straddle") {
+                    self.log.straddle = Some((player_id.clone(), posted));
+                }
+                if rest.contains("ante") {
+                    // Antes go in before the betting, so they don't count towards a preflop raise
+                    self.street_start.insert(player_id, street_start + posted);
+                }
+                return Ok(());
+            }
+            "shows" => {
+                let (_, shown) = rest.split_once('[').ok_or("expected the cards shown")?;
+                let (cards, _) = shown.split_once(']').ok_or("expected the cards shown")?;
+                let cards = parse_cards(cards)?;
+                if self.section == Section::Showdown {
+                    self.log.hole_cards.insert(player_id.clone(), cards);
+                    if !self.log.showdown.contains(&player_id) {
+                        self.log.showdown.push(player_id);
+                    }
+                } else {
+                    // Shown by choice after winning uncalled, perhaps only one card
+                    self.log.hole_cards.entry(player_id.clone()).or_insert_with(|| cards.clone());
+                    self.log.shown.push((player_id, cards));
+                }
+                return Ok(());
+            }
+            "folds" => PlayerAction::Fold,
+            "checks" => PlayerAction::Check,
+            "calls" => {
+                self.put_in.insert(player_id.clone(), put_in + last(rest)?);
+                PlayerAction::Call
+            }
+            "bets" => {
+                let bet = last(rest)?;
+                self.put_in.insert(player_id.clone(), street_start + bet);
+                PlayerAction::Bet(bet)
+            }
+            "raises" => {
+                let (by, to) = words.next().zip(words.nth(1)).ok_or("expected `raises X to Y`")?;
+                self.put_in.insert(player_id.clone(), street_start + amount(to)?);
+                PlayerAction::Raise(amount(by)?)
+            }
+            _ => return Ok(()), // Mucks, sits out, says something
+        };
+        if self.section != Section::Play {
+            return Err("an action outside the betting".to_string());
+        }
+        let hand_total = self.put_in.get(&player_id).copied().unwrap_or_default();
+        self.log.actions.push(LoggedAction { street: self.street, player_id, action, hand_total });
+        Ok(())
+    }
+
+    /// Logs what each player put in on the street that just ended, blinds
+    /// and antes counting towards preflop.
+    fn close_street(&mut self) {
+        let before = |id: &PlayerId| if self.street == Street::Preflop { Chips::ZERO } else { self.street_start.get(id).copied().unwrap_or_default() };
+        let bets = self.log.players.iter()
+            .map(|player| (player.player_id.clone(), self.put_in.get(&player.player_id).copied().unwrap_or_default() - before(&player.player_id)))
+            .filter(|(_, amount)| !amount.is_zero())
+            .collect();
+        self.log.streets.push(StreetBets { street: self.street, bets });
+    }
+
+    /// The id of the player named `name`, if they were dealt in.
+    fn player(&self, name: &str) -> Option<PlayerId> {
+        self.log.players.iter().find(|player| player.display_name == name).map(|player| player.player_id.clone())
+    }
+
+    /// Puts the seats in dealing order, button first, and hands back the
+    /// uncalled bet as a pot of its own.
+    fn finish(mut self) -> Result<RecordedHand, GameError> {
+        let invalid = |reason: &str| GameError::InvalidRequest(format!("hand #{}: {reason}", self.log.hand_number));
+        if self.log.players.len() < 2 {
+            return Err(invalid("fewer than two players were dealt in"));
+        }
+        if self.log.big_blind.is_zero() {
+            return Err(invalid("the big blind is zero"));
+        }
+        // A dead button deals from the first seat after it
+        let button = self.button.saturating_sub(1);
+        let first = self.log.players.iter().position(|player| player.table_position >= button).unwrap_or(0);
+        self.log.seats = self.log.players.iter().cycle().skip(first).take(self.log.players.len()).map(|player| player.player_id.clone()).collect();
+        self.close_street();
+        if let Some(uncalled) = self.uncalled.take() {
+            self.log.payouts.push(uncalled);
+        }
+        let max_seats = if self.max_seats == 0 { self.log.players.len() } else { self.max_seats };
+        Ok(RecordedHand { log: self.log, finished_at: self.finished_at, max_seats })
+    }
+}
+
+/// An amount as a site writes it: chips, e.g. `1,500`, or money, e.g.
+/// `$0.25`, counted in cents.
+fn amount(text: &str) -> Result<Chips, String> {
+    let unreadable = || format!("unreadable amount {text:?}");
+    let digits = text.replace(',', "");
+    let Some(money) = digits.strip_prefix(['$', '€', '£']) else {
+        return digits.parse().map(Chips::new).map_err(|_| unreadable());
+    };
+    let (whole, cents) = money.split_once('.').unwrap_or((money, "0"));
+    if cents.is_empty() || cents.len() > 2 {
+        return Err(unreadable());
+    }
+    let whole: u64 = whole.parse().map_err(|_| unreadable())?;
+    let cents: u64 = format!("{cents:0<2}").parse().map_err(|_| unreadable())?;
+    Ok(Chips::new(whole * 100 + cents))
+}
+
+fn parse_cards(text: &str) -> Result<Vec<Card>, String> {
+    poker_core::card_dealer::parse_cards(text).map_err(|err| err.to_string())
+}
+
+/// Who posted the small and the big blind, and how much, short stacks
+/// posting what they had. Heads-up the button posts the small blind, as in
+/// `BettingState::blinds`.
+fn blinds(log: &HandLog) -> [(&PlayerId, Chips); 2] {
+    let (small, big) = if log.seats.len() == 2 { (&log.seats[0], &log.seats[1]) } else { (&log.seats[1], &log.seats[2]) };
+    let stack = |id: &PlayerId| log.players.iter().find(|player| &player.player_id == id).map_or(Chips::ZERO, |player| player.stack);
+    [(small, log.small_blind.min(stack(small))), (big, log.big_blind.min(stack(big)))]
+}
+
+/// Who posted the big blind ante and how much, if the hand had one: what
+/// the big blind had left after the blind, at most.
+fn ante(log: &HandLog) -> Option<(&PlayerId, Chips)> {
+    let [_, (big, posted)] = blinds(log);
+    let stack = log.players.iter().find(|player| &player.player_id == big).map_or(Chips::ZERO, |player| player.stack);
+    Some((big, log.ante.min(stack - posted))).filter(|(_, ante)| !ante.is_zero())
+}
+
+/// Chips each player had put into the hand by its end, blinds and ante included.
+fn contributions(log: &HandLog) -> HashMap<&PlayerId, Chips> {
+    let mut put_in: HashMap<&PlayerId, Chips> = blinds(log).into_iter().collect();
+    if let Some((big, ante)) = ante(log) {
+        *put_in.entry(big).or_default() += ante;
+    }
+    for action in &log.actions {
+        put_in.insert(&action.player_id, action.hand_total);
+    }
+    put_in
+}
+
+/// Chips in the pots at the end of the hand, leaving out a bet nobody called.
+fn pot(log: &HandLog) -> Chips {
+    let put_in = contributions(log);
+    put_in.values().copied().sum::<Chips>() - uncalled_bet(log, &put_in).map_or(Chips::ZERO, |(_, excess)| excess)
+}
+
+/// What the pots paid `player_id`, an uncalled bet handed back included.
+fn winnings(log: &HandLog, player_id: &PlayerId) -> Chips {
+    log.payouts.iter().filter(|(winner, _)| winner == player_id).map(|(_, amount)| *amount).sum()
+}
+
+/// Who put in more than anyone else could match, and by how much, given
+/// what each player put into the hand. The ante is dead money, so it is
+/// never handed back.
+fn uncalled_bet<'a>(log: &HandLog, put_in: &HashMap<&'a PlayerId, Chips>) -> Option<(&'a PlayerId, Chips)> {
+    let ante = ante(log);
+    let live = |id: &PlayerId, chips: Chips| match ante {
+        Some((big, ante)) if big == id => chips - ante,
+        _ => chips,
+    };
+    let mut totals: Vec<Chips> = put_in.iter().map(|(id, chips)| live(id, *chips)).collect();
+    totals.sort_unstable_by(|a, b| b.cmp(a));
+    match totals[..] {
+        [most, next, ..] if most > next => put_in.iter().find(|(id, chips)| live(id, **chips) == most).map(|(id, _)| (*id, most - next)),
+        _ => None,
+    }
+}
+
+/// Announces each street after `street` up to `until` that has its board
+/// cards dealt, e.g. `*** TURN *** [Jh Qc 2h] [7d]`.
+fn announce_streets(log: &HandLog, street: &mut Street, until: Street, lines: &mut Vec<String>) {
+    for next in [Street::Flop, Street::Turn, Street::River] {
+        if next <= *street || next > until || next.board_cards() > log.board.len() {
+            continue;
+        }
+        let (shown, new) = log.board[..next.board_cards()].split_at(next.board_cards() - new_cards(next));
+        lines.push(match next {
+            Street::Flop => format!("*** FLOP *** [{}]", cards(new)),
+            _ => format!("*** {} *** [{}] [{}]", street_name(next).to_uppercase(), cards(shown), cards(new)),
+        });
+        *street = next;
+    }
+}
+
+fn cards(cards: &[Card]) -> String {
+    cards.iter().map(Card::to_string).collect::<Vec<_>>().join(" ")
+}
+
+/// Board cards first dealt on `street`.
+fn new_cards(street: Street) -> usize {
+    match street {
+        Street::Preflop => 0,
+        Street::Flop => 3,
+        Street::Turn | Street::River => 1,
+    }
+}
+
+fn street_name(street: Street) -> &'static str {
+    match street {
+        Street::Preflop => "Preflop",
+        Street::Flop => "Flop",
+        Street::Turn => "Turn",
+        Street::River => "River",
+    }
+}
+
+/// `2024/05/01 13:45:00` for milliseconds since the Unix epoch, in UTC.
+fn timestamp(millis: u64) -> String {
+    let secs = millis / 1000;
+    let (days, time) = (secs / 86_400, secs % 86_400);
+    // Howard Hinnant's days-to-civil algorithm, on eras of 400 years
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{year}/{month:02}/{day:02} {:02}:{:02}:{:02}", time / 3600, time % 3600 / 60, time % 60)
+}
+
+/// Milliseconds since the Unix epoch for `2024/05/01` and `13:45:00`, read
+/// as UTC; `None` if they aren't a date and a time.
+fn parse_timestamp(date: &str, time: &str) -> Option<u64> {
+    let numbers = |text: &str, separator: char| text.split(separator).map(|part| part.parse::<i64>().ok()).collect::<Option<Vec<_>>>();
+    let (date, time) = (numbers(date, '/')?, numbers(time, ':')?);
+    let (&[year, month, day], &[hours, minutes, seconds]) = (&date[..], &time[..]) else { return None };
+    if year < 1970 || !(1..=12).contains(&month) || !(1..=31).contains(&day) || hours > 23 || minutes > 59 || seconds > 60 {
+        return None;
+    }
+    // The inverse of `timestamp`, with years starting in March
+    let year = if month <= 2 { year - 1 } else { year };
+    let (era, year_of_era) = (year.div_euclid(400), year.rem_euclid(400));
+    let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+    u64::try_from((days * 86_400 + hours * 3600 + minutes * 60 + seconds) * 1000).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use poker_core::card_dealer::parse_cards;
+    use poker_core::events::GameEvent;
+    use poker_core::game_controller::GameController;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    /// Alice on the button and Bob in the big blind, with `deck` stacked.
+    fn heads_up(deck: &str) -> GameController {
+        let mut controller = GameController::with_rng(StdRng::seed_from_u64(1));
+        controller.initialize_players(vec![
+            ("1".to_string(), "Alice".to_string(), 0, Chips::new(1000)),
+            ("2".to_string(), "Bob".to_string(), 1, Chips::new(1000)),
+        ]);
+        controller.stack_deck(parse_cards(deck).unwrap()).unwrap();
+        controller.start_hand(Chips::new(5), Chips::new(10)).unwrap();
+        controller
+    }
+
+    fn play(controller: &mut GameController, actions: &[(&str, PlayerAction)]) -> RecordedHand {
+        for (player_id, action) in actions {
+            controller.act(player_id, action.clone()).unwrap();
+        }
+        let mut history = HandHistory::new();
+        for log in controller.take_finished_hands() {
+            history.record(log, 6);
+        }
+        history.get(1).unwrap().clone()
+    }
+
+    #[test]
+    fn test_showdown_in_pokerstars_format() {
+        let mut controller = heads_up("Ah Ad Kc Kd 2c 7h 9s Jd 3c");
+        let hand = play(&mut controller, &[
+            ("1", PlayerAction::Raise(Chips::new(30))),
+            ("2", PlayerAction::Call),
+            ("2", PlayerAction::Check),
+            ("1", PlayerAction::Bet(Chips::new(50))),
+            ("2", PlayerAction::Call),
+            ("2", PlayerAction::Check),
+            ("1", PlayerAction::Check),
+            ("2", PlayerAction::Bet(Chips::new(100))),
+            ("1", PlayerAction::Call),
+        ]);
+        let text = to_pokerstars(&hand, "main", Some("2")).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert!(lines[0].starts_with("PokerStars Hand #1: Hold'em No Limit (5/10) - "), "{text}");
+        assert_eq!(lines[1..5], [
+            "Table 'main' 6-max Seat #1 is the button",
+            "Seat 1: Alice (1000 in chips)",
+            "Seat 2: Bob (1000 in chips)",
+            "Alice: posts small blind 5",
+        ]);
+        for expected in [
+            "Dealt to Bob [Kc Kd]",
+            "Alice: raises 30 to 40",
+            "*** FLOP *** [2c 7h 9s]",
+            "Alice: bets 50",
+            "*** TURN *** [2c 7h 9s] [Jd]",
+            "*** RIVER *** [2c 7h 9s Jd] [3c]",
+            "Bob: bets 100",
+            "Alice: calls 100",
+            "Alice: shows [Ah Ad]",
+            "Alice collected 380 from pot",
+            "Total pot 380 | Rake 0",
+            "Seat 1: Alice (button) (small blind) showed [Ah Ad] and won (380)",
+            "Seat 2: Bob (big blind) showed [Kc Kd] and lost",
+        ] {
+            assert!(lines.contains(&expected), "missing {expected:?} in\n{text}");
+        }
+        assert!(!text.contains("Dealt to Alice"));
+
+        // Reading the export back gives the same hand, screen names standing in for ids
+        let imported = parse_hand_histories(&text).unwrap();
+        assert_eq!(imported.len(), 1);
+        assert_eq!(to_pokerstars(&imported[0], "main", Some("Bob")).unwrap(), text);
+        assert_eq!(imported[0].log.actions.len(), hand.log.actions.len());
+        assert_eq!(imported[0].log.events().len(), hand.log.events().len());
+        let totals = |log: &HandLog| log.streets.iter().map(|street| (street.street, street.total())).collect::<Vec<_>>();
+        assert_eq!(totals(&imported[0].log), [(Street::Preflop, Chips::new(80)), (Street::Flop, Chips::new(100)), (Street::Turn, Chips::ZERO), (Street::River, Chips::new(200))]);
+        assert_eq!(totals(&imported[0].log), totals(&hand.log));
+    }
+
+    #[test]
+    fn test_uncalled_bet_is_returned() {
+        let mut controller = heads_up("Ah Ad Kc Kd 2c 7h 9s Jd 3c");
+        let hand = play(&mut controller, &[("1", PlayerAction::Raise(Chips::new(30))), ("2", PlayerAction::Fold)]);
+        let text = to_pokerstars(&hand, "main", None).unwrap();
+        for expected in [
+            "Uncalled bet (30) returned to Alice",
+            "Alice collected 20 from pot",
+            "Total pot 20 | Rake 0",
+            "Seat 2: Bob (big blind) folded before Flop",
+        ] {
+            assert!(text.lines().any(|line| line == expected), "missing {expected:?} in\n{text}");
+        }
+        assert!(!text.contains("Dealt to") && !text.contains("SHOW DOWN") && !text.contains("FLOP"));
+
+        assert_eq!(to_pokerstars(&parse_hand_histories(&text).unwrap()[0], "main", None).unwrap(), text);
+
+        assert!(matches!(HandHistory::new().get(1), Err(GameError::HandNotFound(1))));
+        assert_eq!(timestamp(1_714_571_100_000), "2024/05/01 13:45:00");
+        assert_eq!(parse_timestamp("2024/05/01", "13:45:00"), Some(1_714_571_100_000));
+    }
+
+    #[test]
+    fn test_card_shown_after_the_hand_replaces_its_record() {
+        let mut controller = heads_up("Ah Ad Kc Kd 2c 7h 9s Jd 3c");
+        controller.act("1", PlayerAction::Raise(Chips::new(30))).unwrap();
+        controller.act("2", PlayerAction::Fold).unwrap();
+        let mut history = HandHistory::new();
+        for log in controller.take_finished_hands() {
+            history.record(log, 6);
+        }
+        controller.show_cards("1", &parse_cards("Ah").unwrap()).unwrap();
+        for log in controller.take_finished_hands() {
+            history.record(log, 6);
+        }
+
+        assert_eq!(history.between(None, None).count(), 1);
+        let text = to_pokerstars(history.get(1).unwrap(), "main", None).unwrap();
+        assert!(text.lines().any(|line| line == "Alice: shows [Ah]"), "{text}");
+        let imported = parse_hand_histories(&text).unwrap();
+        assert_eq!(imported[0].log.shown, [(PlayerId::from("Alice"), parse_cards("Ah").unwrap())]);
+        assert_eq!(to_pokerstars(&imported[0], "main", None).unwrap(), text);
+    }
+
+    const GGPOKER_HAND: &str = "\
+Poker Hand #RC1800000001: Hold'em No Limit ($0.01/$0.02) - 2024/05/01 13:45:00
+Table 'RushAndCash1' 6-max Seat #1 is the button
+Seat 1: 4f2a9c ($2 in chips)
+Seat 2: Hero ($2.13 in chips)
+Seat 3: 7b1d0e ($1.50 in chips)
+Hero: posts small blind $0.01
+7b1d0e: posts big blind $0.02
+*** HOLE CARDS ***
+Dealt to 4f2a9c
+Dealt to Hero [Ah Kh]
+Dealt to 7b1d0e
+4f2a9c: folds
+Hero: raises $0.04 to $0.06
+7b1d0e: calls $0.04
+*** FLOP *** [Kd 7c 2h]
+Hero: bets $0.05
+7b1d0e: raises $0.10 to $0.15
+Hero: calls $0.10
+*** TURN *** [Kd 7c 2h] [9s]
+Hero: checks
+7b1d0e: bets $1.29 and is all-in
+Hero: calls $1.29
+*** RIVER *** [Kd 7c 2h 9s] [3c]
+*** SHOWDOWN ***
+7b1d0e: shows [7d 7h] (Three of a kind, Sevens)
+Hero: shows [Ah Kh] (a Pair of Kings)
+7b1d0e collected $2.96 from pot
+*** SUMMARY ***
+Total pot $3 | Rake $0.04 | Jackpot $0 | Bingo $0
+Board [Kd 7c 2h 9s 3c]
+Seat 1: 4f2a9c (button) folded before Flop (didn't bet)
+Seat 2: Hero (small blind) showed [Ah Kh] and lost with a Pair of Kings
+Seat 3: 7b1d0e (big blind) showed [7d 7h] and won ($2.96) with Three of a kind, Sevens
+";
+
+    #[test]
+    fn test_big_blind_ante_in_pokerstars_format() {
+        let mut controller = GameController::with_rng(StdRng::seed_from_u64(1));
+        controller.initialize_players(vec![
+            ("1".to_string(), "Alice".to_string(), 0, Chips::new(1000)),
+            ("2".to_string(), "Bob".to_string(), 1, Chips::new(1000)),
+        ]);
+        controller.set_big_blind_ante(Chips::new(10));
+        controller.start_hand(Chips::new(5), Chips::new(10)).unwrap();
+        let hand = play(&mut controller, &[("1", PlayerAction::Raise(Chips::new(30))), ("2", PlayerAction::Fold)]);
+        let text = to_pokerstars(&hand, "main", None).unwrap();
+        for expected in [
+            "Bob: posts the ante 10",
+            "Alice: raises 30 to 40",
+            "Uncalled bet (30) returned to Alice", // The dead ante doesn't cover any of the raise
+            "Alice collected 30 from pot",
+            "Total pot 30 | Rake 0",
+        ] {
+            assert!(text.lines().any(|line| line == expected), "missing {expected:?} in\n{text}");
+        }
+        assert_eq!(hand.pot(), Chips::new(30));
+    }
+
+    #[test]
+    fn test_ggpoker_import() {
+        let hands = parse_hand_histories(GGPOKER_HAND).unwrap();
+        let log = &hands[0].log;
+        assert_eq!((log.hand_number, hands[0].finished_at, hands[0].max_seats), (1_800_000_001, 1_714_571_100_000, 6));
+        assert_eq!((log.small_blind, log.big_blind), (Chips::new(1), Chips::new(2)));
+        assert_eq!(log.seats, [PlayerId::from("4f2a9c"), PlayerId::from("Hero"), PlayerId::from("7b1d0e")]);
+        assert_eq!(log.players.iter().map(|player| player.stack).collect::<Vec<_>>(), [Chips::new(200), Chips::new(213), Chips::new(150)]);
+        assert_eq!(log.actions.len(), 9);
+        assert_eq!((&log.actions[1].action, log.actions[1].hand_total), (&PlayerAction::Raise(Chips::new(4)), Chips::new(6)));
+        assert_eq!((log.actions[8].street, log.actions[8].hand_total), (Street::Turn, Chips::new(150)));
+        assert_eq!(log.board, parse_cards("Kd 7c 2h 9s 3c").unwrap());
+        assert_eq!(log.hole_cards.len(), 2);
+        assert_eq!(log.showdown, [PlayerId::from("7b1d0e"), PlayerId::from("Hero")]);
+        assert_eq!(log.payouts, [(PlayerId::from("7b1d0e"), Chips::new(296))]);
+
+        // The board is dealt between the streets' actions, and the winner ends the hand
+        let events = log.events();
+        assert_eq!(events.len(), 14);
+        assert!(matches!(&events[4], GameEvent::BoardDealt { cards, .. } if cards.len() == 3));
+        assert!(matches!(&events[13], GameEvent::HandFinished { winners, .. } if winners == &[PlayerId::from("7b1d0e")]));
+
+        let two_hands = format!("{GGPOKER_HAND}\n\n{}", GGPOKER_HAND.replace("RC1800000001", "RC1800000002"));
+        assert_eq!(parse_hand_histories(&two_hands).unwrap().len(), 2);
+        for broken in [
+            "",
+            "Table 'RushAndCash1' 6-max Seat #1 is the button",
+            "PokerStars Hand #1: Omaha Hi/Lo Pot Limit (5/10) - 2024/05/01 13:45:00 ET",
+            &GGPOKER_HAND.replace("$0.04 to", "lots to"),
+            &GGPOKER_HAND.replace("[Kd 7c 2h] [9s]", "[Kd 7c 2h]"),
+        ] {
+            assert!(matches!(parse_hand_histories(broken), Err(GameError::InvalidRequest(_))), "{broken}");
+        }
+    }
+}
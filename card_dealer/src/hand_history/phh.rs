@@ -0,0 +1,490 @@
+//! The Poker Hand History (PHH) format: one hand per TOML document, with
+//! the players listed from the seat after the button round to the button
+//! and every action written as a short command, e.g.
+//!
+//! ```text
+//! variant = "NT"
+//! ante_trimming_status = false
+//! antes = [0, 0]
+//! blinds_or_straddles = [10, 5]
+//! min_bet = 10
+//! starting_stacks = [1000, 1000]
+//! actions = ["d dh p1 KcKd", "d dh p2 ????", "p2 cbr 40", "p1 f"]
+//! players = ["Bob", "Alice"]
+//! ```
+//!
+//! `d dh` deals hole cards, `d db` the board, `f` folds, `cc` checks or
+//! calls, `cbr` bets or raises to a street total and `sm` shows. Only no-limit
+//! Hold'em (`NT`) is read and written; a `.phhs` file of several hands, each
+//! under its own `[name]` table, is read as well.
+
+use std::collections::HashMap;
+
+use poker_core::betting::{HandLog, LoggedAction, LoggedPlayer, Street, StreetBets};
+use poker_core::card_dealer::Card;
+use poker_core::chips::Chips;
+use poker_core::game_error::GameError;
+use poker_core::player::PlayerAction;
+use poker_core::player_id::PlayerId;
+use poker_core::variant::GameVariant;
+use serde::{Deserialize, Serialize};
+use toml::value::Datetime;
+
+use super::{parse_timestamp, timestamp, RecordedHand};
+
+/// One hand, as its TOML document.
+#[derive(Debug, Serialize, Deserialize)]
+struct PhhHand {
+    variant: String,
+    #[serde(default)]
+    ante_trimming_status: bool,
+    #[serde(default)]
+    antes: Vec<Chips>,
+    blinds_or_straddles: Vec<Chips>,
+    min_bet: Chips,
+    starting_stacks: Vec<Chips>,
+    actions: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    players: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    finishing_stacks: Vec<Chips>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    seats: Vec<usize>,          // Seat numbers, counting from 1
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seat_count: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    table: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hand: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    year: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    month: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    day: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    time: Option<Datetime>,     // A local time, e.g. `13:45:00`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    time_zone: Option<String>,
+}
+
+/// Renders `hand` as a PHH document, as seen by `viewer`: their own hole
+/// cards are dealt face up, everyone else's only if they reach showdown.
+pub fn to_phh(hand: &RecordedHand, table_id: &str, viewer: Option<&str>) -> Result<String, GameError> {
+    let log = &hand.log;
+    if log.variant != GameVariant::HoldEm {
+        return Err(GameError::InvalidRequest("only Hold'em hands can be exported in PHH format".to_string()));
+    }
+    // The seat after the button first, the button last
+    let order: Vec<&PlayerId> = log.seats.iter().cycle().skip(1).take(log.seats.len()).collect();
+    let number = |id: &PlayerId| order.iter().position(|candidate| *candidate == id).map_or(0, |index| index + 1);
+    let player = |id: &PlayerId| log.players.iter().find(|player| &player.player_id == id);
+    let stack = |id: &PlayerId| player(id).map_or(Chips::ZERO, |player| player.stack);
+
+    // Heads-up the button posts the small blind, and comes second
+    let mut blinds: Vec<Chips> = if order.len() == 2 {
+        vec![log.big_blind, log.small_blind]
+    } else {
+        (0..order.len()).map(|index| [log.small_blind, log.big_blind].get(index).copied().unwrap_or_default()).collect()
+    };
+    // A straddle is the third blind, posted under the gun
+    if let Some((id, straddle)) = &log.straddle {
+        if let Some(index) = order.iter().position(|candidate| *candidate == id) {
+            blinds[index] = *straddle;
+        }
+    }
+    let mut put_in: HashMap<&PlayerId, Chips> = order.iter().zip(&blinds).map(|(id, blind)| (*id, (*blind).min(stack(id)))).collect();
+    // The big blind, first heads-up and second otherwise, antes what the blind leaves of their stack, at most
+    let big = usize::from(order.len() > 2);
+    let antes: Vec<Chips> = order.iter().enumerate()
+        .map(|(index, id)| if index == big { log.ante.min(stack(id) - put_in[id]) } else { Chips::ZERO })
+        .collect();
+    for (id, ante) in order.iter().zip(&antes) {
+        *put_in.entry(id).or_default() += *ante;
+    }
+
+    let viewer = viewer.map(PlayerId::from);
+    let mut actions: Vec<String> = order.iter()
+        .map(|id| {
+            let shown = viewer.as_ref() == Some(*id) || log.showdown.contains(id);
+            let cards = log.hole_cards.get(*id).filter(|_| shown).map_or_else(|| "????".to_string(), |cards| concatenated(cards));
+            format!("d dh p{} {cards}", number(id))
+        })
+        .collect();
+    let mut street = Street::Preflop;
+    let mut street_start: HashMap<&PlayerId, Chips> = order.iter().copied().zip(antes.iter().copied()).collect();
+    for action in &log.actions {
+        if action.street != street {
+            deal_board(log, &mut street, action.street, &mut actions);
+            street_start = put_in.clone();
+        }
+        let command = match action.action {
+            PlayerAction::Fold | PlayerAction::SitOut => "f".to_string(),
+            PlayerAction::Check | PlayerAction::Call => "cc".to_string(),
+            PlayerAction::Bet(_) | PlayerAction::Raise(_) => {
+                format!("cbr {}", action.hand_total - street_start.get(&action.player_id).copied().unwrap_or_default())
+            }
+        };
+        actions.push(format!("p{} {command}", number(&action.player_id)));
+        put_in.insert(&action.player_id, action.hand_total);
+    }
+    deal_board(log, &mut street, Street::River, &mut actions);
+    for id in &log.showdown {
+        let cards = log.hole_cards.get(id).map_or_else(String::new, |cards| concatenated(cards));
+        actions.push(format!("p{} sm {cards}", number(id)));
+    }
+
+    let finishing_stacks = if log.finished {
+        order.iter()
+            .map(|id| {
+                let won: Chips = log.payouts.iter().filter(|(winner, _)| winner == *id).map(|(_, amount)| *amount).sum();
+                stack(id) - put_in.get(id).copied().unwrap_or_default() + won
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let (date, time) = timestamp(hand.finished_at).split_once(' ').map(|(date, time)| (date.to_string(), time.to_string())).unwrap_or_default();
+    let date: Vec<i64> = date.split('/').filter_map(|part| part.parse().ok()).collect();
+    let document = PhhHand {
+        variant: "NT".to_string(),
+        ante_trimming_status: false,
+        antes,
+        blinds_or_straddles: blinds,
+        min_bet: log.big_blind,
+        starting_stacks: order.iter().map(|id| stack(id)).collect(),
+        actions,
+        players: order.iter().map(|id| player(id).map_or_else(|| id.to_string(), |player| player.display_name.clone())).collect(),
+        finishing_stacks,
+        seats: order.iter().filter_map(|id| player(id)).map(|player| player.table_position + 1).collect(),
+        seat_count: Some(hand.max_seats),
+        table: Some(table_id.to_string()),
+        hand: Some(log.hand_number),
+        year: date.first().copied(),
+        month: date.get(1).copied(),
+        day: date.get(2).copied(),
+        time: time.parse().ok(),
+        time_zone: Some("UTC".to_string()),
+    };
+    toml::to_string(&document).map_err(|err| GameError::Internal(format!("could not write PHH: {err}")))
+}
+
+/// Reads a `.phh` document of one hand, or a `.phhs` document of several.
+/// Players without names are called `p1`, `p2` and so on, and their names
+/// become their ids. Payouts come from the finishing stacks, when the file
+/// has them.
+pub fn parse_phh(text: &str) -> Result<Vec<RecordedHand>, GameError> {
+    let invalid = |reason: String| GameError::InvalidRequest(format!("PHH: {reason}"));
+    let document: toml::Table = text.parse().map_err(|err: toml::de::Error| invalid(err.message().to_string()))?;
+    // Each hand is read from text again: times don't survive reading from a `toml::Value`
+    let hands: Vec<(String, String)> = if document.contains_key("actions") {
+        vec![(String::new(), text.to_string())]
+    } else {
+        document.into_iter()
+            .filter_map(|(name, value)| Some((name, toml::to_string(value.as_table()?).ok()?)))
+            .collect()
+    };
+    if hands.is_empty() {
+        return Err(invalid("no hands found".to_string()));
+    }
+    hands.into_iter()
+        .map(|(name, text)| {
+            let context = if name.is_empty() { String::new() } else { format!("[{name}] ") };
+            let hand: PhhHand = toml::from_str(&text).map_err(|err: toml::de::Error| invalid(format!("{context}{}", err.message())))?;
+            read_hand(hand).map_err(|reason| invalid(format!("{context}{reason}")))
+        })
+        .collect()
+}
+
+/// Replays one hand's actions into a `HandLog`.
+fn read_hand(hand: PhhHand) -> Result<RecordedHand, String> {
+    if hand.variant != "NT" {
+        return Err(format!("unsupported variant {:?}, only no-limit Hold'em (NT) is read", hand.variant));
+    }
+    let count = hand.starting_stacks.len();
+    if count < 2 {
+        return Err("fewer than two players".to_string());
+    }
+    if hand.blinds_or_straddles.len() != count || (!hand.antes.is_empty() && hand.antes.len() != count) {
+        return Err("blinds and antes need one entry per player".to_string());
+    }
+    let names: Vec<String> = if hand.players.is_empty() {
+        (1..=count).map(|number| format!("p{number}")).collect()
+    } else if hand.players.len() == count {
+        hand.players.clone()
+    } else {
+        return Err("players need one name each".to_string());
+    };
+    let ids: Vec<PlayerId> = names.iter().map(PlayerId::from).collect();
+    let mut players: Vec<LoggedPlayer> = names.iter().zip(&hand.starting_stacks).enumerate()
+        .map(|(index, (name, stack))| LoggedPlayer {
+            player_id: PlayerId::from(name),
+            display_name: name.clone(),
+            table_position: hand.seats.get(index).map_or(index, |seat| seat.saturating_sub(1)),
+            stack: *stack,
+        })
+        .collect();
+    players.sort_by_key(|player| player.table_position);
+    // Heads-up the button, second, posts the small blind
+    let (small_blind, big_blind) = if count == 2 {
+        (hand.blinds_or_straddles[1], hand.blinds_or_straddles[0])
+    } else {
+        (hand.blinds_or_straddles[0], hand.blinds_or_straddles[1])
+    };
+
+    let antes = |index: usize| hand.antes.get(index).copied().unwrap_or_default();
+    let mut put_in: Vec<Chips> = (0..count).map(|index| antes(index) + hand.blinds_or_straddles[index]).collect();
+    if put_in.iter().zip(&hand.starting_stacks).any(|(posted, stack)| posted > stack) {
+        return Err("blinds and antes above a starting stack".to_string());
+    }
+    let mut street_start: Vec<Chips> = (0..count).map(antes).collect();
+    let mut to_match = hand.blinds_or_straddles.iter().copied().max().unwrap_or_default(); // The street's biggest total so far
+    let mut street = Street::Preflop;
+    let mut log = HandLog {
+        hand_number: hand.hand.unwrap_or(0),
+        variant: GameVariant::HoldEm,
+        seats: ids.iter().cycle().skip(count - 1).take(count).cloned().collect(),
+        players,
+        small_blind,
+        big_blind,
+        ante: Chips::ZERO,
+        straddle: hand.blinds_or_straddles.get(2).filter(|straddle| !straddle.is_zero()).map(|straddle| (ids[2].clone(), *straddle)),
+        hole_cards: HashMap::new(),
+        actions: Vec::new(),
+        board: Vec::new(),
+        extra_boards: Vec::new(),
+        eliminations: Vec::new(),
+        all_in: Vec::new(),
+        payouts: Vec::new(),
+        rake: Chips::ZERO,
+        streets: Vec::new(),
+        showdown: Vec::new(),
+        shown: Vec::new(),
+        finished: !hand.finishing_stacks.is_empty(),
+    };
+    for (number, line) in hand.actions.iter().enumerate() {
+        let command = line.split('#').next().unwrap_or_default().trim();
+        let invalid = |reason: &str| format!("action {} {line:?}: {reason}", number + 1);
+        let words: Vec<&str> = command.split_whitespace().collect();
+        let player = |word: &str| {
+            word.strip_prefix('p').and_then(|number| number.parse::<usize>().ok()).filter(|number| (1..=count).contains(number)).map(|number| number - 1)
+        };
+        match words[..] {
+            ["d", "dh", seat, cards] => {
+                let seat = player(seat).ok_or_else(|| invalid("unknown player"))?;
+                if !cards.contains('?') {
+                    log.hole_cards.insert(ids[seat].clone(), split_cards(cards).map_err(|err| invalid(&err))?);
+                }
+            }
+            ["d", "db", cards] => {
+                log.board.extend(split_cards(cards).map_err(|err| invalid(&err))?);
+                let next = match log.board.len() {
+                    3 => Street::Flop,
+                    4 => Street::Turn,
+                    5 => Street::River,
+                    _ => return Err(invalid("the board doesn't make a flop, turn or river")),
+                };
+                log.streets.push(street_bets(street, &ids, &put_in, &street_start));
+                street = next;
+                street_start = put_in.clone();
+                to_match = Chips::ZERO;
+            }
+            [seat, "sm", ref shown @ ..] => {
+                let seat = player(seat).ok_or_else(|| invalid("unknown player"))?;
+                if let [cards] = shown {
+                    log.hole_cards.insert(ids[seat].clone(), split_cards(cards).map_err(|err| invalid(&err))?);
+                    log.showdown.push(ids[seat].clone());
+                }
+            }
+            [seat, verb, ref amount @ ..] => {
+                let seat = player(seat).ok_or_else(|| invalid("unknown player"))?;
+                let street_total = put_in[seat] - street_start[seat];
+                let behind = hand.starting_stacks[seat] - put_in[seat];
+                let action = match (verb, amount) {
+                    ("f", []) => PlayerAction::Fold,
+                    ("cc", []) if street_total >= to_match => PlayerAction::Check,
+                    ("cc", []) => {
+                        put_in[seat] += (to_match - street_total).min(behind);
+                        PlayerAction::Call
+                    }
+                    ("cbr", [amount]) => {
+                        let to: Chips = amount.parse::<u64>().map(Chips::new).map_err(|_| invalid("unreadable amount"))?;
+                        if to <= street_total || to - street_total > behind {
+                            return Err(invalid("a bet must be above the player's bet and within their stack"));
+                        }
+                        put_in[seat] = street_start[seat] + to;
+                        let action = if to_match.is_zero() { PlayerAction::Bet(to) } else { PlayerAction::Raise(to - to_match.min(to)) };
+                        to_match = to_match.max(to);
+                        action
+                    }
+                    _ => return Err(invalid("unsupported action")),
+                };
+                log.actions.push(LoggedAction { street, player_id: ids[seat].clone(), action, hand_total: put_in[seat] });
+            }
+            _ => return Err(invalid("unsupported action")),
+        }
+    }
+
+    log.streets.push(street_bets(street, &ids, &put_in, &street_start));
+    if log.finished {
+        if hand.finishing_stacks.len() != count {
+            return Err("finishing stacks need one entry per player".to_string());
+        }
+        for (index, finishing) in hand.finishing_stacks.iter().enumerate() {
+            let kept = hand.starting_stacks[index] - put_in[index];
+            if *finishing > kept {
+                log.payouts.push((ids[index].clone(), *finishing - kept));
+            }
+        }
+    }
+    let date = hand.year.zip(hand.month).zip(hand.day).map(|((year, month), day)| format!("{year}/{month:02}/{day:02}"));
+    let finished_at = date.zip(hand.time).and_then(|(date, time)| parse_timestamp(&date, &time.to_string())).unwrap_or(0);
+    let max_seats = hand.seat_count.unwrap_or(count);
+    Ok(RecordedHand { log, finished_at, max_seats })
+}
+
+/// What each player put in on `street`, given their totals before it and
+/// now; blinds and antes count towards preflop.
+fn street_bets(street: Street, ids: &[PlayerId], put_in: &[Chips], street_start: &[Chips]) -> StreetBets {
+    let bets = ids.iter().zip(put_in.iter().zip(street_start))
+        .map(|(id, (&now, &before))| (id.clone(), if street == Street::Preflop { now } else { now - before }))
+        .filter(|(_, amount)| !amount.is_zero())
+        .collect();
+    StreetBets { street, bets }
+}
+
+/// Adds a `d db` line for each street after `street` up to `until` that has
+/// its board cards dealt.
+fn deal_board(log: &HandLog, street: &mut Street, until: Street, actions: &mut Vec<String>) {
+    while let Some(next) = street.next().filter(|next| *next <= until && next.board_cards() <= log.board.len()) {
+        actions.push(format!("d db {}", concatenated(&log.board[street.board_cards()..next.board_cards()])));
+        *street = next;
+    }
+}
+
+/// Cards written without spaces, e.g. `AcKs`.
+fn concatenated(cards: &[Card]) -> String {
+    cards.iter().map(Card::to_string).collect()
+}
+
+/// Reads cards written without spaces.
+fn split_cards(text: &str) -> Result<Vec<Card>, String> {
+    if !text.len().is_multiple_of(2) || !text.is_ascii() {
+        return Err(format!("unreadable cards {text:?}"));
+    }
+    (0..text.len()).step_by(2).map(|start| text[start..start + 2].parse::<Card>().map_err(|_| format!("unreadable card {:?}", &text[start..start + 2]))).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use poker_core::card_dealer::parse_cards;
+    use poker_core::game_controller::GameController;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use crate::hand_history::{to_pokerstars, HandHistory};
+
+    const THREE_HANDED: &str = "
+variant = 'NT'
+ante_trimming_status = true
+antes = [1, 1, 1]
+blinds_or_straddles = [5, 10, 0]
+min_bet = 10
+starting_stacks = [500, 500, 500]
+actions = [
+  'd dh p1 ????',
+  'd dh p2 ????',
+  'd dh p3 AcAd',
+  'p3 cbr 30  # Opens from the button',
+  'p1 f',
+  'p2 cc',
+  'd db Jc3d5c',
+  'p2 cc',
+  'p3 cbr 40',
+  'p2 f',
+]
+finishing_stacks = [494, 469, 537]
+";
+
+    #[test]
+    fn test_phh_round_trip() {
+        let mut controller = GameController::with_rng(StdRng::seed_from_u64(1));
+        controller.initialize_players(vec![
+            ("1".to_string(), "Alice".to_string(), 0, Chips::new(1000)),
+            ("2".to_string(), "Bob".to_string(), 1, Chips::new(1000)),
+        ]);
+        controller.stack_deck(parse_cards("Ah Ad Kc Kd 2c 7h 9s Jd 3c").unwrap()).unwrap();
+        controller.start_hand(Chips::new(5), Chips::new(10)).unwrap();
+        for (player_id, action) in [
+            ("1", PlayerAction::Raise(Chips::new(30))),
+            ("2", PlayerAction::Call),
+            ("2", PlayerAction::Check),
+            ("1", PlayerAction::Bet(Chips::new(50))),
+            ("2", PlayerAction::Call),
+            ("2", PlayerAction::Check),
+            ("1", PlayerAction::Check),
+            ("2", PlayerAction::Bet(Chips::new(100))),
+            ("1", PlayerAction::Call),
+        ] {
+            controller.act(player_id, action).unwrap();
+        }
+        let mut history = HandHistory::new();
+        for log in controller.take_finished_hands() {
+            history.record(log, 6);
+        }
+        let hand = history.get(1).unwrap();
+
+        // Heads-up the big blind is listed first and the button second
+        let text = to_phh(hand, "main", Some("2")).unwrap();
+        for expected in [
+            r#"blinds_or_straddles = [10, 5]"#,
+            r#"players = ["Bob", "Alice"]"#,
+            r#"finishing_stacks = [810, 1190]"#,
+        ] {
+            assert!(text.contains(expected), "missing {expected:?} in\n{text}");
+        }
+        let document: PhhHand = toml::from_str(&text).unwrap();
+        assert_eq!(document.actions[..4], ["d dh p1 KcKd", "d dh p2 AhAd", "p2 cbr 40", "p1 cc"]);
+        assert!(document.actions.contains(&"d db 2c7h9s".to_string()) && document.actions.contains(&"p2 sm AhAd".to_string()));
+
+        let imported = parse_phh(&text).unwrap();
+        assert_eq!(to_phh(&imported[0], "main", Some("Bob")).unwrap(), text);
+        assert_eq!(to_pokerstars(&imported[0], "main", Some("Bob")).unwrap(), to_pokerstars(hand, "main", Some("2")).unwrap());
+        let totals = |log: &HandLog| log.streets.iter().map(|street| (street.street, street.total())).collect::<Vec<_>>();
+        assert_eq!(totals(&imported[0].log), totals(&hand.log));
+    }
+
+    #[test]
+    fn test_phh_import() {
+        let hands = parse_phh(THREE_HANDED).unwrap();
+        let log = &hands[0].log;
+        assert_eq!(log.seats, [PlayerId::from("p3"), PlayerId::from("p1"), PlayerId::from("p2")]);
+        assert_eq!((log.small_blind, log.big_blind), (Chips::new(5), Chips::new(10)));
+        assert_eq!(log.hole_cards.keys().collect::<Vec<_>>(), [&PlayerId::from("p3")]);
+        let actions: Vec<(&PlayerAction, Chips)> = log.actions.iter().map(|action| (&action.action, action.hand_total)).collect();
+        assert_eq!(actions, [
+            (&PlayerAction::Raise(Chips::new(20)), Chips::new(31)),
+            (&PlayerAction::Fold, Chips::new(6)),
+            (&PlayerAction::Call, Chips::new(31)),
+            (&PlayerAction::Check, Chips::new(31)),
+            (&PlayerAction::Bet(Chips::new(40)), Chips::new(71)),
+            (&PlayerAction::Fold, Chips::new(31)),
+        ]);
+        assert_eq!(log.board.len(), 3);
+        assert_eq!(log.payouts, [(PlayerId::from("p3"), Chips::new(108))]);
+
+        let several = format!("[first]{THREE_HANDED}\n[second]{THREE_HANDED}");
+        assert_eq!(parse_phh(&several).unwrap().len(), 2);
+        for broken in [
+            THREE_HANDED.replace("'NT'", "'FT'"),
+            THREE_HANDED.replace("p1 f", "p4 f"),
+            THREE_HANDED.replace("p1 f", "p1 sd"),
+            THREE_HANDED.replace("Jc3d5c", "Jc3d"),
+            THREE_HANDED.replace("cbr 40", "cbr 4000"),
+            "actions = [".to_string(),
+        ] {
+            assert!(matches!(parse_phh(&broken), Err(GameError::InvalidRequest(_))), "{broken}");
+        }
+    }
+}
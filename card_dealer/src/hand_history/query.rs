@@ -0,0 +1,144 @@
+//! Searching a table's hand history, newest hand first, a page at a time.
+//! A page ends with a cursor: the number of its last hand, from which the
+//! next page carries on.
+
+use poker_core::chips::Chips;
+use poker_core::game_error::GameError;
+use poker_core::player_id::PlayerId;
+use poker_core::poker_hand::{Hand, HandRank};
+use poker_core::variant::GameVariant;
+
+use super::{pot, HandHistory, RecordedHand};
+
+/// Hands on a page when the caller doesn't say.
+pub const DEFAULT_PAGE_LEN: usize = 50;
+
+/// Most hands one page holds.
+pub const MAX_PAGE_LEN: usize = 200;
+
+/// Which hands a search returns; every hand when left at its default.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HistoryFilter {
+    pub player_id: Option<String>,      // Dealt into the hand
+    pub min_hand_rank: Option<HandRank>, // Shown down by the player, or by anyone without one
+    pub min_pot: Option<Chips>,
+    pub max_pot: Option<Chips>,
+    pub from: Option<u64>,              // Finished at or after, in milliseconds since the Unix epoch
+    pub to: Option<u64>,                // Finished before
+    pub variant: Option<GameVariant>,
+}
+
+impl HistoryFilter {
+    pub fn matches(&self, hand: &RecordedHand) -> bool {
+        let log = &hand.log;
+        let pot = pot(log);
+        self.player_id.as_ref().is_none_or(|id| log.players.iter().any(|player| player.player_id == **id))
+            && self.min_pot.is_none_or(|min| pot >= min)
+            && self.max_pot.is_none_or(|max| pot <= max)
+            && self.from.is_none_or(|from| hand.finished_at >= from)
+            && self.to.is_none_or(|to| hand.finished_at < to)
+            && self.variant.is_none_or(|variant| log.variant == variant)
+            && self.min_hand_rank.as_ref().is_none_or(|min| {
+                shown_hands(hand).iter()
+                    .filter(|(id, _)| self.player_id.as_ref().is_none_or(|player_id| **id == **player_id))
+                    .any(|(_, best)| best.rank >= *min)
+            })
+    }
+}
+
+/// The best hand each player made who showed down, on the first board.
+pub fn shown_hands(hand: &RecordedHand) -> Vec<(&PlayerId, Hand)> {
+    let log = &hand.log;
+    log.showdown.iter()
+        .filter_map(|id| Some((id, log.variant.best_hand(log.hole_cards.get(id)?, &log.board)?)))
+        .collect()
+}
+
+/// One page of a search, newest hand first.
+#[derive(Debug)]
+pub struct HistoryPage<'a> {
+    pub hands: Vec<&'a RecordedHand>,
+    pub next_cursor: Option<u64>, // Set when more hands match
+}
+
+/// Up to `limit` hands matching `filter`, starting after the hand numbered
+/// `cursor`, or with the newest hand.
+pub fn search<'a>(history: &'a HandHistory, filter: &HistoryFilter, cursor: Option<u64>, limit: usize) -> Result<HistoryPage<'a>, GameError> {
+    if !(1..=MAX_PAGE_LEN).contains(&limit) {
+        return Err(GameError::InvalidRequest(format!("limit must be between 1 and {MAX_PAGE_LEN}")));
+    }
+    if filter.min_pot.zip(filter.max_pot).is_some_and(|(min, max)| min > max) {
+        return Err(GameError::InvalidRequest("min_pot is above max_pot".to_string()));
+    }
+    let mut matching = history.hands.iter().rev()
+        .filter(|hand| cursor.is_none_or(|cursor| hand.log.hand_number < cursor))
+        .filter(|hand| filter.matches(hand));
+    let hands: Vec<&RecordedHand> = matching.by_ref().take(limit).collect();
+    let next_cursor = match matching.next() {
+        Some(_) => hands.last().map(|hand| hand.log.hand_number),
+        None => None,
+    };
+    Ok(HistoryPage { hands, next_cursor })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use poker_core::card_dealer::parse_cards;
+    use poker_core::game_controller::GameController;
+    use poker_core::player::PlayerAction;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_history_search() {
+        let mut controller = GameController::with_rng(StdRng::seed_from_u64(1));
+        controller.initialize_players(vec![
+            ("1".to_string(), "Alice".to_string(), 0, Chips::new(1000)),
+            ("2".to_string(), "Bob".to_string(), 1, Chips::new(1000)),
+        ]);
+        let mut history = HandHistory::new();
+        // Five folded hands, then Alice's full house beats Bob's kings
+        for _ in 0..5 {
+            controller.start_hand(Chips::new(5), Chips::new(10)).unwrap();
+            let first = controller.betting().unwrap().seats[0].to_string();
+            controller.act(&first, PlayerAction::Fold).unwrap();
+        }
+        controller.stack_deck(parse_cards("Ah Ad Kc Kd As 7h 7s Jd 3c").unwrap()).unwrap();
+        controller.start_hand(Chips::new(5), Chips::new(10)).unwrap();
+        for (player_id, action) in [("2", PlayerAction::Call), ("1", PlayerAction::Check)] {
+            controller.act(player_id, action).unwrap();
+        }
+        for _ in 0..3 {
+            controller.act("1", PlayerAction::Check).unwrap();
+            controller.act("2", PlayerAction::Check).unwrap();
+        }
+        for log in controller.take_finished_hands() {
+            history.record(log, 6);
+        }
+
+        let page = search(&history, &HistoryFilter::default(), None, 4).unwrap();
+        assert_eq!(page.hands.iter().map(|hand| hand.log.hand_number).collect::<Vec<_>>(), [6, 5, 4, 3]);
+        assert_eq!(page.next_cursor, Some(3));
+        let page = search(&history, &HistoryFilter::default(), page.next_cursor, 4).unwrap();
+        assert_eq!(page.hands.iter().map(|hand| hand.log.hand_number).collect::<Vec<_>>(), [2, 1]);
+        assert_eq!(page.next_cursor, None);
+
+        let full_houses = HistoryFilter { min_hand_rank: Some(HandRank::FullHouse), ..HistoryFilter::default() };
+        assert_eq!(search(&history, &full_houses, None, 10).unwrap().hands.len(), 1);
+        let bobs = HistoryFilter { player_id: Some("2".to_string()), ..full_houses.clone() };
+        assert!(search(&history, &bobs, None, 10).unwrap().hands.is_empty());
+        let big_pots = HistoryFilter { min_pot: Some(Chips::new(20)), max_pot: Some(Chips::new(20)), ..HistoryFilter::default() };
+        assert_eq!(search(&history, &big_pots, None, 10).unwrap().hands[0].log.hand_number, 6);
+        let omaha = HistoryFilter { variant: Some(GameVariant::Omaha), ..HistoryFilter::default() };
+        assert!(search(&history, &omaha, None, 10).unwrap().hands.is_empty());
+        let later = HistoryFilter { from: Some(u64::MAX), ..HistoryFilter::default() };
+        assert!(search(&history, &later, None, 10).unwrap().hands.is_empty());
+
+        let shown = shown_hands(history.get(6).unwrap());
+        assert_eq!(shown.iter().map(|(id, best)| (id.to_string(), best.rank.clone())).collect::<Vec<_>>(), [("1".to_string(), HandRank::FullHouse), ("2".to_string(), HandRank::TwoPair)]);
+        assert!(matches!(search(&history, &HistoryFilter::default(), None, 0), Err(GameError::InvalidRequest(_))));
+        let backwards = HistoryFilter { min_pot: Some(Chips::new(30)), max_pot: Some(Chips::new(20)), ..HistoryFilter::default() };
+        assert!(matches!(search(&history, &backwards, None, 10), Err(GameError::InvalidRequest(_))));
+    }
+}
@@ -0,0 +1,166 @@
+//! A player's hands grouped into sessions. A session starts when they sit
+//! down and ends when they leave the table, or when they go this long
+//! without finishing a hand.
+
+use poker_core::chips::Chips;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use super::{contributions, pot, uncalled_bet, winnings, HandHistory, RecordedHand};
+
+/// A player who hasn't finished a hand for this long has ended the session.
+const SESSION_GAP_MS: u64 = 30 * 60 * 1000;
+
+/// Pots listed in each session's summary.
+const BIGGEST_POTS: usize = 3;
+
+/// One of the biggest pots of a session.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct SessionPot {
+    pub hand_number: u64,
+    pub pot: Chips,
+    pub net: i64,           // What the player won or lost in the hand
+}
+
+/// How a player did in one session.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct SessionSummary {
+    pub first_hand: u64,
+    pub last_hand: u64,
+    pub started_at: u64,    // When the first hand finished, in milliseconds since the Unix epoch
+    pub ended_at: u64,      // When the last hand finished
+    pub hands_played: usize,
+    pub net: i64,           // Chips won less chips put in, over the session
+    pub biggest_pots: Vec<SessionPot>, // Largest first
+    pub showdowns: usize,
+    pub showdowns_won: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub showdown_win_rate: Option<f64>, // Share of showdowns that won chips; none without a showdown
+}
+
+impl SessionSummary {
+    fn new(hand: &RecordedHand) -> Self {
+        Self {
+            first_hand: hand.log.hand_number,
+            last_hand: hand.log.hand_number,
+            started_at: hand.finished_at,
+            ended_at: hand.finished_at,
+            hands_played: 0,
+            net: 0,
+            biggest_pots: Vec::new(),
+            showdowns: 0,
+            showdowns_won: 0,
+            showdown_win_rate: None,
+        }
+    }
+
+    /// Counts a hand `player_id` was dealt into.
+    fn add(&mut self, hand: &RecordedHand, player_id: &str) {
+        let log = &hand.log;
+        let Some(player) = log.players.iter().find(|player| player.player_id == *player_id) else { return };
+        let put_in = contributions(log);
+        let invested = put_in.get(&player.player_id).copied().unwrap_or_default();
+        let won = winnings(log, &player.player_id);
+        let net = i64::try_from(won.get()).unwrap_or(i64::MAX) - i64::try_from(invested.get()).unwrap_or(i64::MAX);
+
+        self.last_hand = log.hand_number;
+        self.ended_at = hand.finished_at;
+        self.hands_played += 1;
+        self.net += net;
+        self.biggest_pots.push(SessionPot { hand_number: log.hand_number, pot: pot(log), net });
+        self.biggest_pots.sort_by_key(|pot| std::cmp::Reverse(pot.pot));
+        self.biggest_pots.truncate(BIGGEST_POTS);
+        if log.showdown.contains(&player.player_id) {
+            // Getting back a bet the others couldn't cover isn't winning the showdown
+            let returned = uncalled_bet(log, &put_in).filter(|(id, _)| **id == player.player_id).map_or(Chips::ZERO, |(_, excess)| excess);
+            self.showdowns += 1;
+            self.showdowns_won += usize::from(won > returned);
+            self.showdown_win_rate = Some(self.showdowns_won as f64 / self.showdowns as f64);
+        }
+    }
+}
+
+/// The sessions of `player_id` among the hands `history` keeps, oldest
+/// first.
+pub fn sessions(history: &HandHistory, player_id: &str) -> Vec<SessionSummary> {
+    let mut sessions: Vec<SessionSummary> = Vec::new();
+    for hand in &history.hands {
+        if !hand.log.players.iter().any(|player| player.player_id == *player_id) {
+            continue;
+        }
+        let continues = sessions.last().is_some_and(|session| {
+            let left = history.seat_changes.iter()
+                .any(|(id, after)| *id == *player_id && (session.last_hand..hand.log.hand_number).contains(after));
+            !left && hand.finished_at.saturating_sub(session.ended_at) <= SESSION_GAP_MS
+        });
+        if !continues {
+            sessions.push(SessionSummary::new(hand));
+        }
+        sessions.last_mut().expect("a session was just started").add(hand, player_id);
+    }
+    sessions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use poker_core::card_dealer::parse_cards;
+    use poker_core::game_controller::GameController;
+    use poker_core::player::PlayerAction;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn play(controller: &mut GameController, history: &mut HandHistory, actions: &[(&str, PlayerAction)]) {
+        controller.stack_deck(parse_cards("Ah Ad Kc Kd 2c 7h 9s Jd 3c").unwrap()).unwrap();
+        controller.start_hand(Chips::new(5), Chips::new(10)).unwrap();
+        for (player_id, action) in actions {
+            controller.act(player_id, action.clone()).unwrap();
+        }
+        for log in controller.take_finished_hands() {
+            history.record(log, 6);
+        }
+    }
+
+    #[test]
+    fn test_sessions() {
+        let mut controller = GameController::with_rng(StdRng::seed_from_u64(1));
+        controller.initialize_players(vec![
+            ("1".to_string(), "Alice".to_string(), 0, Chips::new(1000)),
+            ("2".to_string(), "Bob".to_string(), 1, Chips::new(1000)),
+        ]);
+        let mut history = HandHistory::new();
+
+        // Alice's aces hold at showdown, then Bob steals the blinds
+        play(&mut controller, &mut history, &[
+            ("1", PlayerAction::Call),
+            ("2", PlayerAction::Check),
+            ("2", PlayerAction::Check),
+            ("1", PlayerAction::Check),
+            ("2", PlayerAction::Check),
+            ("1", PlayerAction::Check),
+            ("2", PlayerAction::Check),
+            ("1", PlayerAction::Check),
+        ]);
+        play(&mut controller, &mut history, &[("2", PlayerAction::Raise(Chips::new(20))), ("1", PlayerAction::Fold)]);
+        let summaries = sessions(&history, "1");
+        assert_eq!(summaries.len(), 1);
+        let session = &summaries[0];
+        assert_eq!((session.first_hand, session.last_hand, session.hands_played), (1, 2, 2));
+        assert_eq!(session.net, 10 - 10);
+        assert_eq!((session.showdowns, session.showdowns_won, session.showdown_win_rate), (1, 1, Some(1.0)));
+        assert_eq!(session.biggest_pots[0], SessionPot { hand_number: 1, pot: Chips::new(20), net: 10 });
+        assert_eq!(sessions(&history, "2")[0].net, 0);
+        assert!(sessions(&history, "3").is_empty());
+
+        // Leaving the table ends the session, and so does a long break
+        history.record_seat_change("1".into());
+        play(&mut controller, &mut history, &[("1", PlayerAction::Fold)]);
+        history.hands.back_mut().unwrap().finished_at += SESSION_GAP_MS + 1;
+        play(&mut controller, &mut history, &[("2", PlayerAction::Fold)]);
+        history.hands.back_mut().unwrap().finished_at += 2 * (SESSION_GAP_MS + 1);
+        let summaries = sessions(&history, "1");
+        assert_eq!(summaries.iter().map(|session| (session.first_hand, session.last_hand)).collect::<Vec<_>>(), [(1, 2), (3, 3), (4, 4)]);
+        assert_eq!(sessions(&history, "2").len(), 3);
+        assert_eq!(summaries[1].showdown_win_rate, None);
+    }
+}
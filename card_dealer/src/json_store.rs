@@ -0,0 +1,87 @@
+//! Small keyed stores, such as player profiles and balances, kept in memory
+//! and optionally saved to a JSON file on each change.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Entries by key, optionally saved to a JSON file whenever they change. The
+/// default store is kept in memory only.
+#[derive(Debug)]
+pub struct JsonStore<K, V> {
+    entries: Mutex<BTreeMap<K, V>>, // Only locked briefly, never across an await
+    path: Option<PathBuf>,
+}
+
+impl<K, V> Default for JsonStore<K, V> {
+    fn default() -> Self {
+        Self { entries: Mutex::new(BTreeMap::new()), path: None }
+    }
+}
+
+impl<K: Ord + Serialize + DeserializeOwned, V: Serialize + DeserializeOwned> JsonStore<K, V> {
+    /// A store saved to `path`, starting from the entries already there.
+    #[cfg_attr(not(feature = "persistence"), allow(dead_code))]
+    pub fn open(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+        let entries = match fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes).map_err(io::Error::other)?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(err) => return Err(err),
+        };
+        Ok(Self { entries: Mutex::new(entries), path: Some(path) })
+    }
+
+    /// The entries, still usable after a panic while they were locked.
+    pub fn lock(&self) -> MutexGuard<'_, BTreeMap<K, V>> {
+        self.entries.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Writes `entries`, as held under `lock`, to the file if there is one.
+    pub fn save(&self, entries: &BTreeMap<K, V>) -> io::Result<()> {
+        match &self.path {
+            Some(path) => write_json(path, entries),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Writes `value` to `path` as JSON, creating the directory if needed.
+///
+/// The file is written next to its final location and renamed into place so
+/// a crash mid-write never leaves half a file behind.
+pub fn write_json(path: &Path, value: &impl Serialize) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut tmp_path = path.as_os_str().to_owned();
+    tmp_path.push(".tmp");
+    fs::write(&tmp_path, serde_json::to_vec_pretty(value).map_err(io::Error::other)?)?;
+    fs::rename(&tmp_path, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entries_survive_a_restart() {
+        let dir = std::env::temp_dir().join(format!("card_dealer_json_store_{}", std::process::id()));
+        let path = dir.join("nested").join("store.json");
+        let store: JsonStore<String, u32> = JsonStore::open(&path).unwrap();
+        let mut entries = store.lock();
+        entries.insert("a".to_string(), 1);
+        store.save(&entries).unwrap();
+        drop(entries);
+
+        let reopened: JsonStore<String, u32> = JsonStore::open(&path).unwrap();
+        assert_eq!(reopened.lock().get("a"), Some(&1));
+        assert!(!dir.join("nested").join("store.json.tmp").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,33 +1,290 @@
-mod card_dealer;
-mod game_controller;
-mod poker_hand;
-mod player;
-mod table;
+#[cfg(feature = "acpc")]
+mod acpc;
+mod audit_log;
+mod auto_dealer;
+mod bankroll;
+mod blind_clock;
+mod config;
+mod events;
+#[cfg(any(feature = "nats", feature = "kafka", feature = "mqtt"))]
+mod event_stream;
+mod hand_history;
+mod json_store;
+#[cfg(feature = "persistence")]
+mod persistence;
+#[cfg(feature = "monitor")]
+mod monitor;
+mod play;
+mod presence;
+mod profiles;
+#[cfg(feature = "postgres")]
+mod postgres_store;
+#[cfg(feature = "redis")]
+mod redis_publisher;
+mod shutdown;
+mod simulate;
+mod social;
+mod solver;
+mod table_registry;
+mod tournament_coordinator;
+#[cfg(feature = "webhooks")]
+mod webhooks;
 mod api; // New module for API
 
-use warp::Filter;
-use std::sync::{Arc, Mutex};
-use game_controller::GameController;
-use api::{AppState, get_routes};
+use std::sync::Arc;
+use std::time::Duration;
+use poker_core::game_controller::GameController;
+use poker_core::preflop_chart::PreflopChart;
+use poker_core::solver::SolverBackend;
+use api::{AppState, Authenticator, IdempotencyCache, RateLimiter, get_routes};
+use bankroll::Bankroll;
+use clap::Parser;
+use config::{CliArgs, Command, ServerConfig};
+use hand_history::collusion::LoginAddresses;
+use profiles::ProfileStore;
+use social::Social;
+use table_registry::{TableHandle, TableRegistry, DEFAULT_TABLE_ID};
+use tracing::info;
+use tracing_subscriber::EnvFilter;
+
+/// Installs the global tracing subscriber.
+///
+/// `RUST_LOG` sets the filter (default `info`) and `LOG_FORMAT=json` switches
+/// from human-readable output to JSON lines for log shippers.
+fn init_tracing() {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    let subscriber = tracing_subscriber::fmt().with_env_filter(filter);
+    match std::env::var("LOG_FORMAT").as_deref() {
+        Ok("json") => subscriber.json().init(),
+        _ => subscriber.pretty().init(),
+    }
+}
+
+/// Where game state is saved: PostgreSQL when a URL is configured, so
+/// instances can share it, otherwise the persistence directory.
+#[cfg(feature = "persistence")]
+async fn open_store(config: &ServerConfig) -> Arc<dyn persistence::SnapshotStore> {
+    #[cfg(feature = "postgres")]
+    if config.postgres.url.is_some() {
+        match postgres_store::PostgresStore::connect(&config.postgres).await {
+            Ok(store) => return Arc::new(store),
+            Err(err) => {
+                // Carrying on with files would fork the state other instances share
+                tracing::error!(%err, "could not open the PostgreSQL store");
+                std::process::exit(2);
+            }
+        }
+    }
+    #[cfg(not(feature = "postgres"))]
+    if config.postgres.url.is_some() {
+        tracing::warn!("a PostgreSQL URL is configured but this build lacks the postgres feature");
+    }
+    Arc::new(persistence::FileStore::new(&config.persistence_dir))
+}
 
 #[tokio::main]
 async fn main() {
+    let mut args = CliArgs::parse();
+    let outcome = match args.command.take() {
+        Some(Command::Play(options)) => Some(play::run(&options, std::io::stdin().lock(), std::io::stdout())),
+        Some(Command::Simulate(options)) => Some(simulate::run(&options, std::io::stdout())),
+        #[cfg(feature = "monitor")]
+        Some(Command::Monitor(options)) => Some(monitor::run(&options).await),
+        None => None,
+    };
+    if let Some(outcome) = outcome {
+        if let Err(err) = outcome {
+            eprintln!("{err}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let config = match ServerConfig::from_args(args) {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("{err}");
+            std::process::exit(2);
+        }
+    };
+    init_tracing();
+
+    // Initialize players, picking up where the last shutdown left off if possible
+    let mut controller = match config.entropy.open() {
+        Ok(source) => GameController::with_entropy(source),
+        Err(err) => {
+            tracing::error!(%err, "could not open the entropy source");
+            std::process::exit(2);
+        }
+    };
+    #[cfg(feature = "persistence")]
+    let store = open_store(&config).await;
+    #[cfg(feature = "persistence")]
+    let restored = match store.load(DEFAULT_TABLE_ID).await {
+        Ok(Some(snapshot)) => {
+            info!(hand_number = snapshot.hand_number, store = %store.describe(), "restored game state");
+            controller.restore(snapshot);
+            true
+        }
+        Ok(None) => false,
+        Err(err) => {
+            tracing::warn!(%err, "could not read saved game state, starting fresh");
+            false
+        }
+    };
+    #[cfg(not(feature = "persistence"))]
+    let restored = false;
+    if !restored {
+        controller.initialize_players(config.initial_players());
+    }
+    if let Err(err) = controller.configure(&config.table_config()) {
+        tracing::warn!(%err, "could not set up the table as configured");
+    }
+    controller.set_bounty_rules(config.bounty);
+    controller.set_tournament_rules(config.tournament);
+    if let Err(err) = controller.set_jackpot_rules(config.jackpot.clone()) {
+        tracing::warn!(%err, "could not set the configured jackpot");
+    }
+    controller.set_seven_deuce_bonus(config.seven_deuce_bonus);
+    if let Some(path) = &config.preflop_chart {
+        match PreflopChart::load(path) {
+            Ok(chart) => controller.set_preflop_chart(chart),
+            Err(err) => tracing::warn!(%err, path = %path.display(), "could not load the preflop chart, using the built-in one"),
+        }
+    }
+    let table = TableHandle::new(DEFAULT_TABLE_ID, controller);
+    #[cfg(feature = "persistence")]
+    {
+        let path = persistence::audit_log_path(&config.persistence_dir, &table.id);
+        if let Err(err) = table.audit.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).persist_to(&path) {
+            tracing::warn!(%err, "could not open the deal audit log, keeping it in memory only");
+        }
+    }
+    if let Some(schedule) = config.blind_schedule.clone() {
+        blind_clock::spawn_blind_clock(&table, schedule);
+    }
+    if let Some(pause_secs) = config.auto_deal_pause_secs {
+        auto_dealer::spawn_auto_dealer(&table, Duration::from_secs(pause_secs));
+    }
+    #[cfg(feature = "webhooks")]
+    webhooks::spawn_webhooks(config.webhooks.clone(), table.id.clone(), table.subscribe());
+    #[cfg(not(feature = "webhooks"))]
+    if !config.webhooks.urls.is_empty() {
+        tracing::warn!("webhook URLs are configured but this build lacks the webhooks feature");
+    }
+    #[cfg(feature = "redis")]
+    redis_publisher::spawn_redis_publisher(config.redis.clone(), table.id.clone(), table.subscribe());
+    #[cfg(not(feature = "redis"))]
+    if config.redis.url.is_some() {
+        tracing::warn!("a Redis URL is configured but this build lacks the redis feature");
+    }
+    #[cfg(feature = "nats")]
+    event_stream::spawn_nats_sink(config.nats.clone(), table.id.clone(), table.subscribe());
+    #[cfg(not(feature = "nats"))]
+    if config.nats.url.is_some() {
+        tracing::warn!("a NATS URL is configured but this build lacks the nats feature");
+    }
+    #[cfg(feature = "kafka")]
+    event_stream::spawn_kafka_sink(config.kafka.clone(), table.id.clone(), table.subscribe());
+    #[cfg(not(feature = "kafka"))]
+    if config.kafka.brokers.is_some() {
+        tracing::warn!("Kafka brokers are configured but this build lacks the kafka feature");
+    }
+    #[cfg(feature = "mqtt")]
+    event_stream::spawn_mqtt_sink(config.mqtt.clone(), table.id.clone(), table.subscribe());
+    #[cfg(not(feature = "mqtt"))]
+    if config.mqtt.host.is_some() {
+        tracing::warn!("an MQTT host is configured but this build lacks the mqtt feature");
+    }
+
+    #[cfg(feature = "acpc")]
+    {
+        let (acpc_config, table) = (config.acpc.clone(), table.clone());
+        tokio::spawn(async move {
+            if let Err(err) = acpc::serve(acpc_config, table).await {
+                tracing::error!(%err, "ACPC server stopped");
+            }
+        });
+    }
+    #[cfg(not(feature = "acpc"))]
+    if config.acpc.listen_addr.is_some() {
+        tracing::warn!("an ACPC address is configured but this build lacks the acpc feature");
+    }
+
+    if config.auth.passwords.is_empty() && !config.auth.open_login {
+        tracing::warn!("no player passwords are configured and open login is off, so no player can log in");
+    }
+
+    #[cfg(feature = "persistence")]
+    let profiles = ProfileStore::open(persistence::profiles_path(&config.persistence_dir)).unwrap_or_else(|err| {
+        tracing::warn!(%err, "could not read player profiles, keeping them in memory only");
+        ProfileStore::new()
+    });
+    #[cfg(not(feature = "persistence"))]
+    let profiles = ProfileStore::new();
+    #[cfg(feature = "persistence")]
+    let bankroll = Bankroll::open(persistence::bankroll_path(&config.persistence_dir)).unwrap_or_else(|err| {
+        tracing::warn!(%err, "could not read player balances, keeping them in memory only");
+        Bankroll::new()
+    });
+    #[cfg(not(feature = "persistence"))]
+    let bankroll = Bankroll::new();
     let state = Arc::new(AppState {
-        game_controller: Mutex::new(GameController::new()),
+        tables: TableRegistry::new(table),
+        rate_limiter: RateLimiter::new(&config.rate_limit),
+        authenticator: Authenticator::new(&config.auth),
+        idempotency: IdempotencyCache::new(),
+        profiles,
+        bankroll: Arc::new(bankroll),
+        social: Social::new(),
+        logins: LoginAddresses::new(),
+        solver: solver::ProcessSolver::from_config(&config.solver).map(|solver| Arc::new(solver) as Arc<dyn SolverBackend>),
     });
+    if let Some(rules) = config.tournament.filter(|rules| rules.paid_places > 0) {
+        tournament_coordinator::spawn_coordinator(tournament_coordinator::TournamentCoordinator::new(state.tables.all(), rules));
+    }
 
-    // Initialize players
+    // Serve gRPC next to the HTTP API for non-browser clients
+    #[cfg(feature = "grpc")]
     {
-        let mut controller = state.game_controller.lock().unwrap();
-        controller.initialize_players(vec![
-            ("1".to_string(), "Alice".to_string(), 0, 1000),
-            ("2".to_string(), "Bob".to_string(), 1, 1000),
-            ("3".to_string(), "Charlie".to_string(), 2, 1000),
-        ]);
-    }
-
-    // Start the server with refactored routes
-    warp::serve(get_routes(state))
-        .run(([127, 0, 0, 1], 3030))
-        .await;
+        let grpc_state = state.clone();
+        let grpc_addr = config.grpc_addr;
+        tokio::spawn(async move {
+            if let Err(err) = api::serve_grpc(grpc_state, grpc_addr).await {
+                tracing::error!(%err, "gRPC server stopped");
+            }
+        });
+    }
+
+    // On SIGINT/SIGTERM let the current hand wind down and save state before the server stops
+    let graceful = {
+        let state = state.clone();
+        let grace = Duration::from_secs(config.shutdown_grace_secs);
+        async move {
+            shutdown::shutdown_signal().await;
+            shutdown::drain(&state, grace).await;
+            #[cfg(feature = "persistence")]
+            shutdown::save_state(&state, store.as_ref()).await;
+        }
+    };
+
+    // Start the server with refactored routes, over HTTPS when a certificate is configured
+    let server = warp::serve(get_routes(state, &config.cors));
+    match config.tls() {
+        Some((cert_path, key_path)) => {
+            let (addr, running) = server
+                .tls()
+                .cert_path(cert_path)
+                .key_path(key_path)
+                .bind_with_graceful_shutdown(config.listen_addr, graceful);
+            info!(%addr, "listening (TLS)");
+            running.await;
+        }
+        None => {
+            let (addr, running) = server.bind_with_graceful_shutdown(config.listen_addr, graceful);
+            info!(%addr, "listening");
+            running.await;
+        }
+    }
+    info!("server stopped");
 }
@@ -0,0 +1,350 @@
+//! `card_dealer monitor`: a terminal dashboard for a running server.
+//!
+//! Follows the default table's Server-Sent Events stream and, after each
+//! event, refetches the public table view, so operators can watch stacks,
+//! pots and the action log of a live game without a frontend.
+
+use std::collections::VecDeque;
+use std::io;
+use std::time::Duration;
+
+use clap::Args;
+use poker_core::card_dealer::Card;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, List, ListItem, Paragraph, Row, Table};
+use ratatui::{DefaultTerminal, Frame};
+use serde::Deserialize;
+use tokio::sync::mpsc;
+use tracing::debug;
+
+use crate::table_registry::DEFAULT_TABLE_ID;
+
+/// Events kept in the action log.
+const LOG_LEN: usize = 200;
+
+/// Wait before reconnecting to a dropped event stream.
+const RECONNECT_DELAY: Duration = Duration::from_secs(2);
+
+/// Settings for the monitor.
+#[derive(Debug, Clone, Args)]
+pub struct MonitorOptions {
+    /// Base URL of the server to watch
+    #[arg(long, default_value = "http://127.0.0.1:3030")]
+    pub server: String,
+}
+
+/// The public table state, as returned by `GET /v1/tables/{table_id}/spectate`.
+#[derive(Debug, Clone, Default, Deserialize)]
+struct TableView {
+    hand_number: u64,
+    hand_in_progress: bool,
+    paused: bool,
+    community_cards: Vec<Card>,
+    pots: Vec<u32>,
+    players: Vec<SeatView>,
+    spectators: usize,
+    last_event_seq: u64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct SeatView {
+    name: String,
+    table_position: usize,
+    chip_stack: u32,
+    is_in_play: bool,
+    is_sitting_out: bool,
+    has_cards: bool,
+}
+
+/// One message of an SSE stream.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct SseMessage {
+    id: Option<String>,
+    event: Option<String>,
+    data: String,
+}
+
+/// Splits an SSE byte stream into messages, whatever the chunk boundaries.
+#[derive(Default)]
+struct SseParser {
+    buffer: String,
+    message: SseMessage,
+}
+
+impl SseParser {
+    /// Feeds a chunk and returns the messages it completed.
+    fn push(&mut self, chunk: &str) -> Vec<SseMessage> {
+        self.buffer.push_str(chunk);
+        let mut messages = Vec::new();
+        while let Some(end) = self.buffer.find('\n') {
+            let line: String = self.buffer.drain(..=end).collect();
+            let line = line.trim_end_matches(['\n', '\r']);
+            if line.is_empty() {
+                let message = std::mem::take(&mut self.message);
+                if !message.data.is_empty() {
+                    messages.push(message);
+                }
+                continue;
+            }
+            let (field, value) = line.split_once(':').unwrap_or((line, ""));
+            let value = value.strip_prefix(' ').unwrap_or(value);
+            match field {
+                "id" => self.message.id = Some(value.to_string()),
+                "event" => self.message.event = Some(value.to_string()),
+                "data" => {
+                    if !self.message.data.is_empty() {
+                        self.message.data.push('\n');
+                    }
+                    self.message.data.push_str(value);
+                }
+                _ => {} // Comments (keep-alives) and retry hints
+            }
+        }
+        messages
+    }
+}
+
+/// What the network task tells the UI.
+enum Update {
+    View(TableView),
+    Event(String),
+    Status(String),
+}
+
+/// Everything the dashboard draws.
+struct Dashboard {
+    server: String,
+    view: TableView,
+    log: VecDeque<String>,
+    status: String,
+}
+
+impl Dashboard {
+    fn apply(&mut self, update: Update) {
+        match update {
+            Update::View(view) => self.view = view,
+            Update::Event(line) => {
+                if self.log.len() == LOG_LEN {
+                    self.log.pop_front();
+                }
+                self.log.push_back(line);
+            }
+            Update::Status(status) => self.status = status,
+        }
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let [header, body, log] =
+            Layout::vertical([Constraint::Length(3), Constraint::Min(8), Constraint::Length(12)]).areas(frame.area());
+        let [seats, board] = Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)]).areas(body);
+
+        let state = match (self.view.paused, self.view.hand_in_progress) {
+            (true, _) => "paused".yellow(),
+            (false, true) => "hand in progress".green(),
+            (false, false) => "between hands".into(),
+        };
+        let title = Line::from(vec![
+            format!("Hand #{}  ", self.view.hand_number).bold(),
+            state,
+            format!("  spectators {}  last event #{}  ", self.view.spectators, self.view.last_event_seq).into(),
+            self.status.clone().dim(),
+        ]);
+        frame.render_widget(Paragraph::new(title).block(Block::bordered().title(format!(" {} ", self.server))), header);
+
+        self.draw_seats(frame, seats);
+        self.draw_board(frame, board);
+
+        let height = usize::from(log.height.saturating_sub(2));
+        let items: Vec<ListItem> = self.log.iter().rev().take(height).rev().map(|line| ListItem::new(line.as_str())).collect();
+        frame.render_widget(List::new(items).block(Block::bordered().title(" Action log (q to quit) ")), log);
+    }
+
+    fn draw_seats(&self, frame: &mut Frame, area: Rect) {
+        let rows = self.view.players.iter().map(|seat| {
+            let status = if seat.is_sitting_out {
+                "sitting out"
+            } else if seat.has_cards && seat.is_in_play {
+                "in hand"
+            } else if seat.chip_stack == 0 {
+                "busted"
+            } else {
+                "waiting"
+            };
+            let style = if status == "in hand" { Style::default().fg(Color::Green) } else { Style::default() };
+            Row::new(vec![seat.table_position.to_string(), seat.name.clone(), seat.chip_stack.to_string(), status.to_string()])
+                .style(style)
+        });
+        let widths = [Constraint::Length(5), Constraint::Min(12), Constraint::Length(10), Constraint::Length(12)];
+        let table = Table::new(rows, widths)
+            .header(Row::new(vec!["Seat", "Player", "Stack", "Status"]).add_modifier(Modifier::BOLD))
+            .block(Block::bordered().title(" Players "));
+        frame.render_widget(table, area);
+    }
+
+    fn draw_board(&self, frame: &mut Frame, area: Rect) {
+        let board = if self.view.community_cards.is_empty() {
+            "-".to_string()
+        } else {
+            self.view.community_cards.iter().map(ToString::to_string).collect::<Vec<_>>().join(" ")
+        };
+        let mut lines = vec![Line::from(vec!["Board  ".bold(), board.into()])];
+        let total: u32 = self.view.pots.iter().sum();
+        lines.push(Line::from(vec!["Pots   ".bold(), format!("{total} total").into()]));
+        for (index, pot) in self.view.pots.iter().enumerate() {
+            let name = if index == 0 { "main".to_string() } else { format!("side {index}") };
+            lines.push(Line::from(format!("  {name}: {pot}")));
+        }
+        frame.render_widget(Paragraph::new(lines).block(Block::bordered().title(" Table ")), area);
+    }
+}
+
+/// Runs the dashboard until the operator presses `q` or Esc.
+pub async fn run(options: &MonitorOptions) -> io::Result<()> {
+    let server = options.server.trim_end_matches('/').to_string();
+    let (updates, receiver) = mpsc::unbounded_channel();
+    let follower = tokio::spawn(follow_events(server.clone(), updates));
+
+    let dashboard = Dashboard { server, view: TableView::default(), log: VecDeque::new(), status: "connecting".to_string() };
+    let result = tokio::task::spawn_blocking(move || {
+        let mut terminal = ratatui::init();
+        let result = ui_loop(&mut terminal, dashboard, receiver);
+        ratatui::restore();
+        result
+    })
+    .await
+    .map_err(io::Error::other)?;
+    follower.abort();
+    result
+}
+
+fn ui_loop(terminal: &mut DefaultTerminal, mut dashboard: Dashboard, mut updates: mpsc::UnboundedReceiver<Update>) -> io::Result<()> {
+    loop {
+        while let Ok(update) = updates.try_recv() {
+            dashboard.apply(update);
+        }
+        terminal.draw(|frame| dashboard.draw(frame))?;
+        if event::poll(Duration::from_millis(200))? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press && matches!(key.code, KeyCode::Char('q') | KeyCode::Esc) {
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+/// Streams events from the server, reconnecting with `Last-Event-ID` so none are missed.
+async fn follow_events(server: String, updates: mpsc::UnboundedSender<Update>) {
+    let client = reqwest::Client::new();
+    let mut last_seq: Option<String> = None;
+    loop {
+        let result = stream_once(&client, &server, &mut last_seq, &updates).await;
+        let status = match result {
+            Ok(()) => "stream ended, reconnecting".to_string(),
+            Err(err) => format!("disconnected ({err}), retrying"),
+        };
+        debug!(%status, "event stream interrupted");
+        if updates.send(Update::Status(status)).is_err() {
+            return; // The UI has gone away
+        }
+        tokio::time::sleep(RECONNECT_DELAY).await;
+    }
+}
+
+async fn stream_once(
+    client: &reqwest::Client,
+    server: &str,
+    last_seq: &mut Option<String>,
+    updates: &mpsc::UnboundedSender<Update>,
+) -> Result<(), reqwest::Error> {
+    let mut request = client.get(format!("{server}/v1/events"));
+    if let Some(seq) = last_seq.as_deref() {
+        request = request.header("Last-Event-ID", seq);
+    }
+    let mut response = request.send().await?.error_for_status()?;
+    let _ = updates.send(Update::Status("connected".to_string()));
+    refresh_view(client, server, updates).await?;
+
+    let mut parser = SseParser::default();
+    while let Some(chunk) = response.chunk().await? {
+        for message in parser.push(&String::from_utf8_lossy(&chunk)) {
+            if message.id.is_some() {
+                *last_seq = message.id.clone();
+            }
+            let _ = updates.send(Update::Event(describe(&message)));
+            refresh_view(client, server, updates).await?;
+        }
+    }
+    Ok(())
+}
+
+async fn refresh_view(client: &reqwest::Client, server: &str, updates: &mpsc::UnboundedSender<Update>) -> Result<(), reqwest::Error> {
+    let view = client
+        .get(format!("{server}/v1/tables/{DEFAULT_TABLE_ID}/spectate"))
+        .send()
+        .await?
+        .error_for_status()?
+        .json::<TableView>()
+        .await?;
+    let _ = updates.send(Update::View(view));
+    Ok(())
+}
+
+/// Formats an event for the action log, e.g. `#12 pot_awarded amount=30 pot=0 winners=["1"]`.
+fn describe(message: &SseMessage) -> String {
+    let seq = message.id.as_deref().unwrap_or("?");
+    let kind = message.event.as_deref().unwrap_or("message");
+    let fields = match serde_json::from_str::<serde_json::Value>(&message.data) {
+        Ok(serde_json::Value::Object(fields)) => fields
+            .iter()
+            .filter(|(name, _)| !matches!(name.as_str(), "seq" | "type"))
+            .map(|(name, value)| format!("{name}={value}"))
+            .collect::<Vec<_>>()
+            .join(" "),
+        _ => message.data.clone(),
+    };
+    format!("#{seq} {kind} {fields}").trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    #[test]
+    fn test_sse_messages_split_across_chunks() {
+        let mut parser = SseParser::default();
+        assert!(parser.push(": keep-alive\n\nid: 3\nevent: table_pau").is_empty());
+        let messages = parser.push("sed\ndata: {\"seq\":3,\"type\":\"table_paused\"}\n\n");
+        assert_eq!(messages, vec![SseMessage {
+            id: Some("3".to_string()),
+            event: Some("table_paused".to_string()),
+            data: "{\"seq\":3,\"type\":\"table_paused\"}".to_string(),
+        }]);
+        assert_eq!(describe(&messages[0]), "#3 table_paused");
+    }
+
+    #[test]
+    fn test_dashboard_renders_view_and_log() {
+        let view: TableView = serde_json::from_value(serde_json::json!({
+            "type": "spectate", "table_id": "main", "hand_number": 4, "hand_in_progress": true, "paused": false,
+            "community_cards": [{"rank": "Ace", "suit": "Spades"}], "pots": [120],
+            "players": [{"player_id": "1", "name": "Alice", "table_position": 0, "chip_stack": 940,
+                         "is_in_play": true, "is_sitting_out": false, "has_cards": true}],
+            "spectators": 2, "last_event_seq": 9,
+        })).unwrap();
+        let mut dashboard = Dashboard { server: "http://test".to_string(), view, log: VecDeque::new(), status: String::new() };
+        dashboard.apply(Update::Event("#9 hand_started hand_number=4".to_string()));
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+        terminal.draw(|frame| dashboard.draw(frame)).unwrap();
+        let screen: String = terminal.backend().buffer().content().iter().map(|cell| cell.symbol()).collect();
+        for expected in ["Hand #4", "Alice", "940", "in hand", "As", "120 total", "#9 hand_started"] {
+            assert!(screen.contains(expected), "missing {expected:?}");
+        }
+    }
+}
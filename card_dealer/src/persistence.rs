@@ -0,0 +1,120 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use futures_util::future::BoxFuture;
+use poker_core::snapshot::GameSnapshot;
+
+use crate::json_store::write_json;
+
+/// File name of the snapshot inside the persistence directory.
+const SNAPSHOT_FILE: &str = "state.json";
+
+/// Where a table's seats and stacks are kept between runs of the server.
+///
+/// The server saves a snapshot on shutdown and loads it on startup. There are
+/// two backends: `FileStore`, a JSON file for a single instance, and
+/// `PostgresStore` (postgres feature), shared by several instances and
+/// keeping each table's snapshot under its id. There is no SQLite backend.
+pub trait SnapshotStore: Send + Sync {
+    /// Short description for the logs, e.g. the directory or the database.
+    fn describe(&self) -> String;
+
+    /// The last snapshot saved for `table_id`, if any.
+    fn load<'a>(&'a self, table_id: &'a str) -> BoxFuture<'a, io::Result<Option<GameSnapshot>>>;
+
+    /// Saves `snapshot` for `table_id`, replacing the previous one.
+    fn save<'a>(&'a self, table_id: &'a str, snapshot: &'a GameSnapshot) -> BoxFuture<'a, io::Result<()>>;
+}
+
+/// Snapshots kept as a JSON file in the persistence directory. A directory
+/// holds one table's snapshot, so each instance needs its own.
+pub struct FileStore {
+    dir: PathBuf,
+}
+
+impl FileStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+}
+
+impl SnapshotStore for FileStore {
+    fn describe(&self) -> String {
+        self.dir.join(SNAPSHOT_FILE).display().to_string()
+    }
+
+    fn load<'a>(&'a self, _table_id: &'a str) -> BoxFuture<'a, io::Result<Option<GameSnapshot>>> {
+        Box::pin(async move { load_snapshot(&self.dir) })
+    }
+
+    fn save<'a>(&'a self, _table_id: &'a str, snapshot: &'a GameSnapshot) -> BoxFuture<'a, io::Result<()>> {
+        Box::pin(async move { save_snapshot(&self.dir, snapshot).map(|_| ()) })
+    }
+}
+
+/// Where the deal audit log of table `table_id` is kept inside `dir`.
+pub fn audit_log_path(dir: &Path, table_id: &str) -> PathBuf {
+    dir.join("audit").join(format!("{table_id}.jsonl"))
+}
+
+/// Where player profiles are kept inside `dir`.
+pub fn profiles_path(dir: &Path) -> PathBuf {
+    dir.join("profiles.json")
+}
+
+/// Where the balances of tracked-currency tables are kept inside `dir`.
+pub fn bankroll_path(dir: &Path) -> PathBuf {
+    dir.join("bankroll.json")
+}
+
+/// Writes the snapshot into `dir`, replacing any previous one without ever
+/// leaving a truncated snapshot behind.
+pub fn save_snapshot(dir: &Path, snapshot: &GameSnapshot) -> io::Result<PathBuf> {
+    let path = dir.join(SNAPSHOT_FILE);
+    write_json(&path, snapshot)?;
+    Ok(path)
+}
+
+/// Reads the snapshot from `dir`, if one has been saved.
+pub fn load_snapshot(dir: &Path) -> io::Result<Option<GameSnapshot>> {
+    let path = dir.join(SNAPSHOT_FILE);
+    match fs::read(&path) {
+        Ok(bytes) => serde_json::from_slice(&bytes).map(Some).map_err(io::Error::other),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use poker_core::chips::Chips;
+    use poker_core::snapshot::PlayerSnapshot;
+
+    #[tokio::test]
+    async fn test_snapshot_round_trip() {
+        let dir = std::env::temp_dir().join(format!("card_dealer_snapshot_{}", std::process::id()));
+        assert_eq!(load_snapshot(&dir).unwrap(), None);
+
+        let snapshot = GameSnapshot {
+            hand_number: 7,
+            players: vec![PlayerSnapshot {
+                player_id: "1".into(),
+                display_name: "Alice".to_string(),
+                table_position: 0,
+                chip_stack: Chips::new(1250),
+            }],
+        };
+        save_snapshot(&dir, &snapshot).unwrap();
+        assert_eq!(load_snapshot(&dir).unwrap(), Some(snapshot.clone()));
+
+        // The same file through the store interface
+        let store = FileStore::new(&dir);
+        let moved = GameSnapshot { hand_number: 8, ..snapshot };
+        store.save("main", &moved).await.unwrap();
+        assert_eq!(store.load("main").await.unwrap(), Some(moved));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
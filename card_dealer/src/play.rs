@@ -0,0 +1,313 @@
+//! `card_dealer play`: hot-seat hands in the terminal, dealt and bet through
+//! the same `GameController` the server uses. Handy for trying the engine
+//! without a frontend.
+
+use std::io::{self, BufRead, Write};
+
+use clap::Args;
+use poker_core::card_dealer::{Card, Suit};
+use poker_core::chips::Chips;
+use poker_core::events::GameEvent;
+use poker_core::game_controller::GameController;
+use poker_core::game_error::GameError;
+use poker_core::player::PlayerAction;
+use poker_core::player_id::PlayerId;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::simulate::BotKind;
+
+/// Settings for a local game.
+#[derive(Debug, Clone, Args)]
+pub struct PlayOptions {
+    /// Players seated at the table
+    #[arg(long, default_value_t = 4, value_parser = clap::value_parser!(u8).range(2..=10))]
+    pub players: u8,
+
+    /// Chips each player starts with
+    #[arg(long, default_value_t = Chips::new(1000))]
+    pub stack: Chips,
+
+    /// Small blind
+    #[arg(long, default_value_t = Chips::new(5))]
+    pub small_blind: Chips,
+
+    /// Big blind
+    #[arg(long, default_value_t = Chips::new(10))]
+    pub big_blind: Chips,
+
+    /// Seed for the shuffle, to replay the same cards
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    /// Stop after this many hands instead of playing until one player has every chip
+    #[arg(long)]
+    pub hands: Option<u64>,
+
+    /// Seats, counted from the last, played by bots
+    #[arg(long, default_value_t = 0)]
+    pub bots: u8,
+
+    /// Strategy the bots play
+    #[arg(long, value_enum, default_value_t = BotKind::TightAggressive)]
+    pub bot_strategy: BotKind,
+}
+
+impl Default for PlayOptions {
+    fn default() -> Self {
+        Self { players: 4, stack: Chips::new(1000), small_blind: Chips::new(5), big_blind: Chips::new(10), seed: None, hands: None, bots: 0, bot_strategy: BotKind::TightAggressive }
+    }
+}
+
+/// Plays until one player has every chip, the hand limit is reached, or the
+/// input ends (or says `q`).
+pub fn run(options: &PlayOptions, input: impl BufRead, output: impl Write) -> io::Result<()> {
+    let controller = match options.seed {
+        Some(seed) => GameController::with_rng(StdRng::seed_from_u64(seed)),
+        None => GameController::new(),
+    };
+    LocalGame { controller, options: options.clone(), input, output }.run()
+}
+
+struct LocalGame<R, W> {
+    controller: GameController,
+    options: PlayOptions,
+    input: R,
+    output: W,
+}
+
+impl<R: BufRead, W: Write> LocalGame<R, W> {
+    fn run(&mut self) -> io::Result<()> {
+        let humans = self.options.players.saturating_sub(self.options.bots);
+        let players = (1..=humans)
+            .map(|seat| (format!("p{seat}"), format!("Player {seat}"), usize::from(seat - 1), self.options.stack))
+            .collect();
+        self.controller.initialize_players(players);
+        for seat in humans + 1..=self.options.players {
+            let strategy = self.options.bot_strategy.strategy(self.options.seed.unwrap_or(0).wrapping_add(u64::from(seat)));
+            self.controller.seat_bot(&format!("p{seat}"), &format!("Bot {seat}"), self.options.stack, strategy).map_err(engine_error)?;
+        }
+        writeln!(self.output, "Blinds {}/{}. Actions: [f]old, [c]all, [k] check, [r]aise <total>, [a]ll-in, [q]uit.",
+            self.options.small_blind, self.options.big_blind)?;
+
+        let mut played = 0;
+        while self.controller.get_players().len() >= 2 && self.options.hands.is_none_or(|hands| played < hands) {
+            if !self.play_hand()? {
+                break;
+            }
+            played += 1;
+            self.remove_busted_players()?;
+        }
+
+        writeln!(self.output, "\nFinal stacks:")?;
+        for player in self.controller.get_players() {
+            writeln!(self.output, "  {:<10} {}", player.display_name, player.chip_stack)?;
+        }
+        Ok(())
+    }
+
+    /// Plays one hand; returns false if the players quit part way through.
+    fn play_hand(&mut self) -> io::Result<bool> {
+        self.controller.take_events(); // Only this hand's events are reported
+        self.controller.start_hand(self.options.small_blind, self.options.big_blind).map_err(engine_error)?;
+        let button = self.controller.betting().map(|betting| betting.seats[0].to_string()).unwrap_or_default();
+        writeln!(self.output, "\n=== Hand #{} — {} has the button ===", self.controller.hand_number(), self.name(&button))?;
+        writeln!(self.output, "-- Preflop")?;
+        self.report_events()?; // Bots before the first human may already have acted
+
+        while let Some(player_id) = self.controller.next_to_act().map(str::to_string) {
+            let Some(action) = self.prompt(&player_id)? else {
+                self.controller.cancel_hand();
+                writeln!(self.output, "\nHand abandoned, bets returned")?;
+                return Ok(false);
+            };
+            if let Err(err) = self.controller.act(&player_id, action) {
+                writeln!(self.output, "  {err}")?;
+            }
+            self.report_events()?;
+        }
+        Ok(true)
+    }
+
+    /// Reads an action for `player_id`; `None` means quit.
+    fn prompt(&mut self, player_id: &str) -> io::Result<Option<PlayerAction>> {
+        let player = self.controller.find_player(player_id).map_err(engine_error)?;
+        let Some(betting) = self.controller.betting() else {
+            return Ok(None);
+        };
+        let stack = player.chip_stack;
+        let street_bet = betting.street_bet(player_id);
+        let current_bet = betting.current_bet;
+        let pot: Chips = self.controller.get_table().pots.iter().map(|pot| pot.total).sum();
+        let hole = player.hole_cards.iter().map(pretty).collect::<Vec<_>>().join(" ");
+        // Bets and raises are entered as the street total to go to
+        let to_total = |total: Chips| match total {
+            total if total <= current_bet => PlayerAction::Call,
+            total if current_bet.is_zero() => PlayerAction::Bet(total),
+            total => PlayerAction::Raise(total - current_bet),
+        };
+        loop {
+            write!(self.output, "{} [{hole}] stack {stack}, pot {pot}, to call {} > ",
+                player.display_name, betting.owed(player_id).min(stack))?;
+            self.output.flush()?;
+            let mut line = String::new();
+            if self.input.read_line(&mut line)? == 0 {
+                return Ok(None);
+            }
+            let mut words = line.split_whitespace();
+            let action = match (words.next().map(str::to_ascii_lowercase).as_deref(), words.next()) {
+                (Some("q" | "quit"), _) => return Ok(None),
+                (Some("f" | "fold"), _) => PlayerAction::Fold,
+                (Some("c" | "call"), _) => PlayerAction::Call,
+                (Some("k" | "check"), _) => PlayerAction::Check,
+                (Some("a" | "allin" | "all-in"), _) => to_total(street_bet + stack),
+                (Some("r" | "raise" | "b" | "bet"), Some(amount)) => match amount.parse::<Chips>() {
+                    Ok(total) => to_total(total),
+                    Err(_) => {
+                        writeln!(self.output, "  {amount:?} is not a number of chips")?;
+                        continue;
+                    }
+                },
+                _ => {
+                    writeln!(self.output, "  enter f, c, k, r <total>, a or q")?;
+                    continue;
+                }
+            };
+            return Ok(Some(action));
+        }
+    }
+
+    /// Prints the actions, board, pot winners and busted players since the last call.
+    fn report_events(&mut self) -> io::Result<()> {
+        let showdown = self.controller.get_players().iter().filter(|player| player.is_in_play).count() > 1;
+        for event in self.controller.take_events() {
+            match event {
+                GameEvent::PlayerActed { player_id, action, .. } => {
+                    let done = match action {
+                        PlayerAction::Fold => "folds".to_string(),
+                        PlayerAction::Check => "checks".to_string(),
+                        PlayerAction::Call => "calls".to_string(),
+                        PlayerAction::Bet(amount) => format!("bets {amount}"),
+                        PlayerAction::Raise(amount) => format!("raises {amount}"),
+                        PlayerAction::SitOut => "sits out".to_string(),
+                    };
+                    writeln!(self.output, "{} {done}", self.name(&player_id))?;
+                }
+                GameEvent::BoardDealt { cards, .. } => {
+                    let street = match cards.len() {
+                        3 => "Flop",
+                        4 => "Turn",
+                        _ => "River",
+                    };
+                    writeln!(self.output, "-- {street}: {}", cards.iter().map(pretty).collect::<Vec<_>>().join(" "))?;
+                }
+                GameEvent::PotAwarded { amount, winners, .. } => {
+                    for winner in winners {
+                        let player = self.controller.find_player(&winner).map_err(engine_error)?;
+                        let hand = match (showdown, &player.best_hand) {
+                            (true, Some(hand)) => format!(" with {:?} ({})", hand.rank, hand.cards.iter().map(pretty).collect::<Vec<_>>().join(" ")),
+                            _ => String::new(),
+                        };
+                        writeln!(self.output, "{} wins {amount}{hand}", player.display_name)?;
+                    }
+                }
+                GameEvent::PlayerBusted { player_id } => writeln!(self.output, "{} is out of chips", self.name(&player_id))?,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn remove_busted_players(&mut self) -> io::Result<()> {
+        let busted: Vec<PlayerId> = self.controller.get_players().iter()
+            .filter(|player| player.chip_stack.is_zero())
+            .map(|player| player.player_id.clone())
+            .collect();
+        for player_id in busted {
+            self.controller.remove_player(&player_id).map_err(engine_error)?;
+        }
+        Ok(())
+    }
+
+    fn name(&self, player_id: &str) -> String {
+        self.controller.find_player(player_id).map_or_else(|_| player_id.to_string(), |player| player.display_name.clone())
+    }
+}
+
+/// Renders a card with its Unicode suit, e.g. `A♠`.
+fn pretty(card: &Card) -> String {
+    let symbol = match card.suit {
+        Suit::Hearts => '♥',
+        Suit::Diamonds => '♦',
+        Suit::Clubs => '♣',
+        Suit::Spades => '♠',
+        Suit::Joker => return "🃏".to_string(),
+    };
+    let notation = card.to_string();
+    format!("{}{symbol}", &notation[..notation.len() - 1])
+}
+
+fn engine_error(error: GameError) -> io::Error {
+    io::Error::other(error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn play(options: PlayOptions, input: &str) -> String {
+        let mut output = Vec::new();
+        run(&options, input.as_bytes(), &mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    fn heads_up(hands: u64) -> PlayOptions {
+        PlayOptions { players: 2, seed: Some(7), hands: Some(hands), ..PlayOptions::default() }
+    }
+
+    #[test]
+    fn test_fold_gives_blinds_to_big_blind() {
+        let output = play(heads_up(1), "f\n");
+        assert!(output.contains("Player 1 folds"));
+        assert!(output.contains("Player 2 wins 15"));
+        assert!(output.contains("Player 1   995"));
+        assert!(output.contains("Player 2   1005"));
+    }
+
+    #[test]
+    fn test_hand_checked_down_to_showdown_keeps_chips() {
+        // Button calls, big blind checks, then both check each street
+        let output = play(heads_up(1), "c\nk\nk\nk\nk\nk\nk\nk\n");
+        assert!(output.contains("-- River: "));
+        assert!(output.contains(" with "));
+        let stacks: u32 = output
+            .lines()
+            .skip_while(|line| !line.starts_with("Final stacks"))
+            .skip(1)
+            .map(|line| line.split_whitespace().last().unwrap().parse::<u32>().unwrap())
+            .sum();
+        assert_eq!(stacks, 2000);
+    }
+
+    #[test]
+    fn test_invalid_raise_prompts_again_and_quit_stops() {
+        let output = play(heads_up(5), "r 12\nq\n");
+        assert!(output.contains("bet at least 20"));
+        assert_eq!(output.matches("=== Hand").count(), 1);
+        assert!(output.contains("Player 1   1000"));
+        assert_eq!(pretty(&"Td".parse().unwrap()), "T♦");
+    }
+
+    #[test]
+    fn test_bots_fill_seats() {
+        let options = PlayOptions { bots: 1, bot_strategy: BotKind::CallingStation, ..heads_up(1) };
+        // The calling station checks behind every street
+        let output = play(options, "c
+k
+k
+k
+");
+        assert!(output.contains("Bot 2 checks"));
+        assert!(output.contains("-- River: "));
+    }
+}
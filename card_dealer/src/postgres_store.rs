@@ -0,0 +1,141 @@
+//! Game state kept in PostgreSQL, so several server instances behind a load
+//! balancer can share it. Connections come from a pool, and the schema is
+//! created and upgraded by the server itself on startup. This is the
+//! alternative to the JSON `FileStore`; there is no SQLite backend.
+
+use std::io;
+
+use deadpool_postgres::{Manager, ManagerConfig, Pool, RecyclingMethod};
+use futures_util::future::BoxFuture;
+use poker_core::snapshot::GameSnapshot;
+use tokio_postgres::NoTls;
+use tracing::info;
+
+use crate::config::PostgresConfig;
+use crate::persistence::SnapshotStore;
+
+/// Advisory lock held while migrating, so instances starting together
+/// don't both apply the same migration.
+const MIGRATION_LOCK: i64 = 0x0070_6f6b_6572; // "poker" in ASCII
+
+/// The schema, one step per entry. Applied steps are recorded in
+/// `schema_migrations`; only add to the end, never edit a step.
+const MIGRATIONS: &[&str] = &[
+    "CREATE TABLE table_snapshots (
+        table_id TEXT PRIMARY KEY,
+        hand_number BIGINT NOT NULL,
+        snapshot JSONB NOT NULL,
+        saved_at TIMESTAMPTZ NOT NULL DEFAULT now()
+    )",
+];
+
+/// Snapshots kept in PostgreSQL, one row per table.
+pub struct PostgresStore {
+    pool: Pool,
+    database: String,   // For the logs; never includes the password
+}
+
+impl PostgresStore {
+    /// Opens a connection pool to the configured database and brings its
+    /// schema up to date.
+    pub async fn connect(config: &PostgresConfig) -> io::Result<Self> {
+        let url = config.url.as_deref().ok_or_else(|| io::Error::other("no PostgreSQL URL is configured"))?;
+        let pg_config: tokio_postgres::Config = url.parse().map_err(io::Error::other)?;
+        let database = format!("postgres database {}", pg_config.get_dbname().unwrap_or("(default)"));
+        let manager = Manager::from_config(pg_config, NoTls, ManagerConfig { recycling_method: RecyclingMethod::Fast });
+        let pool = Pool::builder(manager).max_size(config.pool_size).build().map_err(io::Error::other)?;
+        let store = Self { pool, database };
+        store.migrate().await?;
+        Ok(store)
+    }
+
+    /// Applies the migrations the database hasn't seen yet, in one
+    /// transaction.
+    async fn migrate(&self) -> io::Result<()> {
+        let mut client = self.pool.get().await.map_err(io::Error::other)?;
+        let transaction = client.transaction().await.map_err(io::Error::other)?;
+        transaction.execute("SELECT pg_advisory_xact_lock($1)", &[&MIGRATION_LOCK]).await.map_err(io::Error::other)?;
+        transaction
+            .batch_execute("CREATE TABLE IF NOT EXISTS schema_migrations (version INTEGER PRIMARY KEY, applied_at TIMESTAMPTZ NOT NULL DEFAULT now())")
+            .await
+            .map_err(io::Error::other)?;
+        let row = transaction.query_one("SELECT COALESCE(MAX(version), 0) FROM schema_migrations", &[]).await.map_err(io::Error::other)?;
+        let applied = usize::try_from(row.get::<_, i32>(0)).unwrap_or(0);
+        for (index, migration) in MIGRATIONS.iter().enumerate().skip(applied) {
+            let version = i32::try_from(index + 1).expect("fewer than 2^31 migrations");
+            transaction.batch_execute(migration).await.map_err(io::Error::other)?;
+            transaction.execute("INSERT INTO schema_migrations (version) VALUES ($1)", &[&version]).await.map_err(io::Error::other)?;
+            info!(version, "applied database migration");
+        }
+        transaction.commit().await.map_err(io::Error::other)
+    }
+}
+
+impl SnapshotStore for PostgresStore {
+    fn describe(&self) -> String {
+        self.database.clone()
+    }
+
+    fn load<'a>(&'a self, table_id: &'a str) -> BoxFuture<'a, io::Result<Option<GameSnapshot>>> {
+        Box::pin(async move {
+            let client = self.pool.get().await.map_err(io::Error::other)?;
+            let row = client
+                .query_opt("SELECT snapshot::text FROM table_snapshots WHERE table_id = $1", &[&table_id])
+                .await
+                .map_err(io::Error::other)?;
+            row.map(|row| serde_json::from_str(row.get::<_, &str>(0)).map_err(io::Error::other)).transpose()
+        })
+    }
+
+    fn save<'a>(&'a self, table_id: &'a str, snapshot: &'a GameSnapshot) -> BoxFuture<'a, io::Result<()>> {
+        Box::pin(async move {
+            let json = serde_json::to_string(snapshot).map_err(io::Error::other)?;
+            let hand_number = i64::try_from(snapshot.hand_number).map_err(io::Error::other)?;
+            let client = self.pool.get().await.map_err(io::Error::other)?;
+            client
+                .execute(
+                    "INSERT INTO table_snapshots (table_id, hand_number, snapshot) VALUES ($1, $2, $3::text::jsonb)
+                     ON CONFLICT (table_id) DO UPDATE SET hand_number = EXCLUDED.hand_number, snapshot = EXCLUDED.snapshot, saved_at = now()",
+                    &[&table_id, &hand_number, &json],
+                )
+                .await
+                .map_err(io::Error::other)?;
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use poker_core::chips::Chips;
+    use poker_core::snapshot::PlayerSnapshot;
+
+    /// Needs a disposable database, e.g.
+    /// `CARD_DEALER_TEST_POSTGRES_URL=postgres://postgres@localhost/poker_test`;
+    /// skipped when the variable is unset.
+    #[tokio::test]
+    async fn test_postgres_round_trip() {
+        let Ok(url) = std::env::var("CARD_DEALER_TEST_POSTGRES_URL") else { return };
+        let config = PostgresConfig { url: Some(url), pool_size: 2 };
+        let store = PostgresStore::connect(&config).await.unwrap();
+        // Migrating again finds nothing left to do
+        let store_again = PostgresStore::connect(&config).await.unwrap();
+
+        let table_id = format!("test-{}", std::process::id());
+        assert_eq!(store.load(&table_id).await.unwrap(), None);
+        let snapshot = GameSnapshot {
+            hand_number: 7,
+            players: vec![PlayerSnapshot {
+                player_id: "1".into(),
+                display_name: "Alice".to_string(),
+                table_position: 0,
+                chip_stack: Chips::new(1250),
+            }],
+        };
+        store.save(&table_id, &snapshot).await.unwrap();
+        let moved = GameSnapshot { hand_number: 8, ..snapshot };
+        store_again.save(&table_id, &moved).await.unwrap();
+        assert_eq!(store.load(&table_id).await.unwrap(), Some(moved));
+    }
+}
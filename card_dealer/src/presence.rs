@@ -0,0 +1,111 @@
+//! Which players currently have a client connected to a table, judged by
+//! the heartbeats on their WebSocket connections. Disconnection protection
+//! and auto-sit-out can go by `PresenceTracker::status`.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// How often the server pings each WebSocket client.
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(15);
+
+/// A client that hasn't answered for this long is shown as idle.
+const IDLE_AFTER: Duration = Duration::from_secs(2 * HEARTBEAT_INTERVAL.as_secs());
+
+/// A connection that hasn't answered for this long is closed as dead.
+pub const HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(4 * HEARTBEAT_INTERVAL.as_secs());
+
+/// Whether a player's client is reachable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Presence {
+    Connected,      // Answered a heartbeat recently
+    Idle,           // Still connected, but missed the last heartbeats
+    Disconnected,   // No open connection
+}
+
+#[derive(Debug)]
+struct Connections {
+    open: usize,        // A player may have the table open in several tabs
+    last_seen: Instant, // Last message on any of them, pongs included
+}
+
+/// The players connected to one table.
+#[derive(Debug, Default)]
+pub struct PresenceTracker {
+    players: Mutex<HashMap<String, Connections>>, // Only locked briefly, never across an await
+}
+
+impl PresenceTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Counts a new connection of `player_id`.
+    pub fn connect(&self, player_id: &str) {
+        let mut players = self.players.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let connections = players.entry(player_id.to_string()).or_insert(Connections { open: 0, last_seen: Instant::now() });
+        connections.open += 1;
+        connections.last_seen = Instant::now();
+    }
+
+    /// Records that one of `player_id`'s connections is alive.
+    pub fn touch(&self, player_id: &str) {
+        let mut players = self.players.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(connections) = players.get_mut(player_id) {
+            connections.last_seen = Instant::now();
+        }
+    }
+
+    /// Forgets one connection of `player_id`, closed or timed out.
+    pub fn disconnect(&self, player_id: &str) {
+        let mut players = self.players.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(connections) = players.get_mut(player_id) {
+            connections.open -= 1;
+            if connections.open == 0 {
+                players.remove(player_id);
+            }
+        }
+    }
+
+    pub fn status(&self, player_id: &str) -> Presence {
+        self.status_at(player_id, Instant::now())
+    }
+
+    fn status_at(&self, player_id: &str, now: Instant) -> Presence {
+        let players = self.players.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        match players.get(player_id) {
+            None => Presence::Disconnected,
+            Some(connections) if now.saturating_duration_since(connections.last_seen) > IDLE_AFTER => Presence::Idle,
+            Some(_) => Presence::Connected,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_presence() {
+        let tracker = PresenceTracker::new();
+        assert_eq!(tracker.status("1"), Presence::Disconnected);
+
+        tracker.connect("1");
+        tracker.connect("1");
+        let now = Instant::now();
+        assert_eq!(tracker.status_at("1", now), Presence::Connected);
+        assert_eq!(tracker.status_at("1", now + IDLE_AFTER + Duration::from_secs(1)), Presence::Idle);
+
+        // The player stays connected until their last tab closes
+        tracker.disconnect("1");
+        assert_eq!(tracker.status("1"), Presence::Connected);
+        tracker.disconnect("1");
+        assert_eq!(tracker.status("1"), Presence::Disconnected);
+        tracker.touch("1");
+        assert_eq!(tracker.status("1"), Presence::Disconnected);
+    }
+}
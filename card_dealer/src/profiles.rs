@@ -0,0 +1,106 @@
+//! Player profiles: how a player presents themselves across tables and
+//! sessions, kept apart from the per-hand `Player` a table seats. A profile
+//! outlives any seat and is looked up by the player's id.
+
+use std::collections::BTreeMap;
+use std::io;
+use std::path::PathBuf;
+
+use poker_core::game_error::GameError;
+use poker_core::player_id::PlayerId;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::json_store::JsonStore;
+
+/// A player's profile, also the request body of
+/// `PUT /v1/players/{player_id}/profile`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct PlayerProfile {
+    #[schema(example = "Dana")]
+    pub display_name: String,
+    #[schema(example = "https://example.com/avatars/dana.png")]
+    pub avatar_url: Option<String>,   // Image shown next to the player's seat
+    #[schema(example = "DE")]
+    pub country: Option<String>,      // ISO 3166-1 alpha-2 code
+    #[serde(default)]
+    #[schema(value_type = Object, example = json!({"four_color_deck": true}))]
+    pub preferences: serde_json::Map<String, serde_json::Value>, // Client settings, stored as given
+}
+
+/// Every player's profile, optionally saved to a JSON file on each change.
+#[derive(Debug, Default)]
+pub struct ProfileStore {
+    profiles: JsonStore<PlayerId, PlayerProfile>,
+}
+
+impl ProfileStore {
+    /// A store kept in memory only.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A store saved to `path`, starting from the profiles already there.
+    #[cfg_attr(not(feature = "persistence"), allow(dead_code))]
+    pub fn open(path: impl Into<PathBuf>) -> io::Result<Self> {
+        Ok(Self { profiles: JsonStore::open(path)? })
+    }
+
+    pub fn get(&self, player_id: &str) -> Result<PlayerProfile, GameError> {
+        self.profiles.lock().get(player_id).cloned().ok_or_else(|| GameError::PlayerNotFound(player_id.to_string()))
+    }
+
+    /// Creates or replaces the profile of `player_id`; `true` when it is new.
+    pub fn put(&self, player_id: &str, profile: PlayerProfile) -> Result<bool, GameError> {
+        let mut profiles = self.profiles.lock();
+        let previous = profiles.insert(PlayerId::from(player_id), profile);
+        if let Err(err) = self.save(&profiles) {
+            match previous {
+                Some(previous) => profiles.insert(PlayerId::from(player_id), previous),
+                None => profiles.remove(player_id),
+            };
+            return Err(err);
+        }
+        Ok(previous.is_none())
+    }
+
+    /// Deletes the profile of `player_id` and returns it.
+    pub fn remove(&self, player_id: &str) -> Result<PlayerProfile, GameError> {
+        let mut profiles = self.profiles.lock();
+        let removed = profiles.remove(player_id).ok_or_else(|| GameError::PlayerNotFound(player_id.to_string()))?;
+        if let Err(err) = self.save(&profiles) {
+            profiles.insert(PlayerId::from(player_id), removed);
+            return Err(err);
+        }
+        Ok(removed)
+    }
+
+    fn save(&self, profiles: &BTreeMap<PlayerId, PlayerProfile>) -> Result<(), GameError> {
+        self.profiles.save(profiles).map_err(|err| GameError::Internal(format!("could not save player profiles: {err}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(display_name: &str) -> PlayerProfile {
+        PlayerProfile { display_name: display_name.to_string(), avatar_url: None, country: Some("NZ".to_string()), preferences: Default::default() }
+    }
+
+    #[test]
+    fn test_profiles_survive_a_restart() {
+        let path = std::env::temp_dir().join(format!("card_dealer_profiles_{}.json", std::process::id()));
+        let store = ProfileStore::open(&path).unwrap();
+        assert!(matches!(store.get("1"), Err(GameError::PlayerNotFound(_))));
+        assert_eq!(store.put("1", profile("Alice")), Ok(true));
+        assert_eq!(store.put("1", profile("Alicia")), Ok(false));
+        assert_eq!(store.put("2", profile("Bob")), Ok(true));
+        assert_eq!(store.remove("2").unwrap().display_name, "Bob");
+
+        let reopened = ProfileStore::open(&path).unwrap();
+        assert_eq!(reopened.get("1"), Ok(profile("Alicia")));
+        assert!(reopened.get("2").is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,84 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use redis::AsyncCommands;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::{info, warn};
+
+use crate::config::RedisConfig;
+use crate::events::SequencedEvent;
+
+/// Wait before reconnecting after Redis becomes unreachable.
+const RECONNECT_DELAY: Duration = Duration::from_secs(2);
+
+/// Channel a table's events are published on, e.g. `poker:table:main:events`.
+fn channel_name(prefix: &str, table_id: &str) -> String {
+    format!("{prefix}:table:{table_id}:events")
+}
+
+/// Starts publishing every event to Redis pub/sub.
+///
+/// Consumers `SUBSCRIBE` (or `PSUBSCRIBE poker:table:*:events`) without
+/// holding a connection to the game server. Events published while Redis is
+/// down are dropped; subscribers can catch up from the sequence numbers.
+/// Does nothing when no URL is configured.
+pub fn spawn_redis_publisher(config: RedisConfig, table_id: String, mut events: broadcast::Receiver<Arc<SequencedEvent>>) {
+    let Some(url) = config.url else { return };
+    let client = match redis::Client::open(url.as_str()) {
+        Ok(client) => client,
+        Err(err) => {
+            warn!(%err, "invalid Redis URL, Redis publishing disabled");
+            return;
+        }
+    };
+    let channel = channel_name(&config.channel_prefix, &table_id);
+    info!(%channel, "publishing events to Redis");
+
+    tokio::spawn(async move {
+        let mut connection = None;
+        loop {
+            let event = match events.recv().await {
+                Ok(event) => event,
+                Err(RecvError::Lagged(missed)) => {
+                    warn!(missed, "Redis publisher fell behind, events skipped");
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            };
+            let payload = match serde_json::to_string(&*event) {
+                Ok(payload) => payload,
+                Err(err) => {
+                    warn!(%err, seq = event.seq, "failed to serialize event for Redis");
+                    continue;
+                }
+            };
+
+            if connection.is_none() {
+                match client.get_multiplexed_async_connection().await {
+                    Ok(conn) => connection = Some(conn),
+                    Err(err) => {
+                        warn!(%err, seq = event.seq, "Redis unreachable, event dropped");
+                        tokio::time::sleep(RECONNECT_DELAY).await;
+                        continue;
+                    }
+                }
+            }
+            if let Some(conn) = connection.as_mut() {
+                if let Err(err) = conn.publish::<_, _, ()>(&channel, payload).await {
+                    warn!(%err, seq = event.seq, "failed to publish event to Redis");
+                    connection = None;
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_channel_name() {
+        assert_eq!(channel_name("poker", "main"), "poker:table:main:events");
+    }
+}
@@ -0,0 +1,85 @@
+use std::time::Duration;
+
+use tokio::time::{sleep, Instant};
+use tracing::{error, info, warn};
+
+use crate::api::AppState;
+#[cfg(feature = "persistence")]
+use crate::persistence::SnapshotStore;
+
+/// How often the drain loop checks whether the current hand has finished.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Resolves on SIGINT (Ctrl+C) or, on Unix, SIGTERM.
+pub async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(err) = tokio::signal::ctrl_c().await {
+            error!(%err, "failed to listen for Ctrl+C");
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(err) => {
+                error!(%err, "failed to listen for SIGTERM");
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => info!("received Ctrl+C"),
+        _ = terminate => info!("received SIGTERM"),
+    }
+}
+
+/// Brings every table to rest before the server exits.
+///
+/// New hands are refused immediately. A hand already in progress gets
+/// `grace` to reach showdown (the HTTP server keeps serving meanwhile);
+/// after that it is cancelled and every committed chip refunded.
+pub async fn drain(state: &AppState, grace: Duration) {
+    let tables = state.tables.all();
+    for table in &tables {
+        if let Err(err) = table.call(|controller| controller.stop_new_hands()).await {
+            error!(%err, table_id = %table.id, "failed to stop new hands");
+        }
+    }
+    info!(grace_secs = grace.as_secs(), "no longer starting new hands");
+
+    let deadline = Instant::now() + grace;
+    for table in &tables {
+        while table.call(|controller| controller.is_hand_in_progress()).await.unwrap_or(false) {
+            if Instant::now() >= deadline {
+                warn!(table_id = %table.id, "hand still in progress after the grace period, cancelling it");
+                let _ = table.call(|controller| controller.cancel_hand()).await;
+                break;
+            }
+            sleep(POLL_INTERVAL).await;
+        }
+    }
+}
+
+/// Saves the default table's seats and stacks to `store`.
+#[cfg(feature = "persistence")]
+pub async fn save_state(state: &AppState, store: &dyn SnapshotStore) {
+    let table = state.tables.default_table();
+    let snapshot = match table.call(|controller| controller.snapshot()).await {
+        Ok(snapshot) => snapshot,
+        Err(err) => {
+            error!(%err, "skipping the final snapshot");
+            return;
+        }
+    };
+    match store.save(&table.id, &snapshot).await {
+        Ok(()) => info!(store = %store.describe(), "game state saved"),
+        Err(err) => error!(%err, "failed to save game state"),
+    }
+}
@@ -0,0 +1,333 @@
+//! `card_dealer simulate`: plays many complete hands between built-in bots
+//! and reports aggregate statistics, for checking the engine at scale.
+//!
+//! Everything is seeded, so a run can be repeated exactly.
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use clap::{Args, ValueEnum};
+use poker_core::bots::{BotStrategy, CallingStation, Difficulty, RandomBot, RuleBasedBot, TightAggressive};
+use poker_core::chips::Chips;
+use poker_core::events::GameEvent;
+use poker_core::game_controller::GameController;
+use poker_core::game_error::GameError;
+use poker_core::player_id::PlayerId;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde::Serialize;
+
+/// Settings for a simulation run.
+#[derive(Debug, Clone, Args)]
+pub struct SimulateOptions {
+    /// Hands to play
+    #[arg(long, default_value_t = 1000)]
+    pub hands: u64,
+
+    /// Bots seated at the table
+    #[arg(long, default_value_t = 6, value_parser = clap::value_parser!(u8).range(2..=10))]
+    pub players: u8,
+
+    /// Chips each bot starts with, and rebuys for when it busts
+    #[arg(long, default_value_t = Chips::new(1000))]
+    pub stack: Chips,
+
+    /// Small blind
+    #[arg(long, default_value_t = Chips::new(5))]
+    pub small_blind: Chips,
+
+    /// Big blind
+    #[arg(long, default_value_t = Chips::new(10))]
+    pub big_blind: Chips,
+
+    /// Seed for the shuffle and the bots' choices
+    #[arg(long, default_value_t = 0)]
+    pub seed: u64,
+
+    /// Strategies to seat, repeated in turn around the table
+    #[arg(long, value_enum, value_delimiter = ',', default_value = "random")]
+    pub bots: Vec<BotKind>,
+
+    /// Report format
+    #[arg(long, value_enum, default_value_t = ReportFormat::Json)]
+    pub format: ReportFormat,
+
+    /// File to write the report to instead of stdout
+    #[arg(long)]
+    pub output: Option<PathBuf>,
+}
+
+impl Default for SimulateOptions {
+    fn default() -> Self {
+        Self { hands: 1000, players: 6, stack: Chips::new(1000), small_blind: Chips::new(5), big_blind: Chips::new(10), seed: 0, bots: vec![BotKind::Random], format: ReportFormat::Json, output: None }
+    }
+}
+
+/// The built-in bot strategies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum BotKind {
+    CallingStation,
+    Random,
+    TightAggressive,
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl BotKind {
+    /// A new bot of this kind; `seed` drives its choices if it makes random ones.
+    pub fn strategy(self, seed: u64) -> Box<dyn BotStrategy> {
+        match self {
+            BotKind::CallingStation => Box::new(CallingStation),
+            BotKind::Random => Box::new(RandomBot::new(StdRng::seed_from_u64(seed))),
+            BotKind::TightAggressive => Box::new(TightAggressive),
+            BotKind::Easy => Box::new(RuleBasedBot::with_difficulty(Difficulty::Easy, StdRng::seed_from_u64(seed))),
+            BotKind::Medium => Box::new(RuleBasedBot::with_difficulty(Difficulty::Medium, StdRng::seed_from_u64(seed))),
+            BotKind::Hard => Box::new(RuleBasedBot::with_difficulty(Difficulty::Hard, StdRng::seed_from_u64(seed))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ReportFormat {
+    Json,
+    Csv,
+}
+
+/// Aggregate results of a run.
+#[derive(Debug, Serialize)]
+pub struct SimulationReport {
+    pub hands: u64,
+    pub seed: u64,
+    pub showdowns: u64,                       // Hands that went to showdown rather than ending in folds
+    pub hand_ranks: BTreeMap<String, u64>,    // Best hand of every player reaching showdown
+    pub winning_ranks: BTreeMap<String, u64>, // Best hand of each showdown winner
+    pub pots: PotStats,
+    pub players: Vec<PlayerStats>,
+    pub chips_conserved: bool,                // Stacks plus rebuys always added up to the chips bought in
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct PotStats {
+    pub mean: f64,
+    pub median: Chips,
+    pub max: Chips,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PlayerStats {
+    pub player_id: PlayerId,
+    pub final_stack: Chips,
+    pub rebuys: u32,
+    pub net: i64,                 // Chips won or lost over the run, net of rebuys
+    pub ev_net: f64,              // `net` with every all-in before the river counted at the player's equity
+    pub mean_per_hand: f64,
+    pub variance_per_hand: f64,   // Variance of the chips won or lost per hand
+}
+
+/// Runs the simulation and writes the report.
+pub fn run(options: &SimulateOptions, stdout: impl Write) -> io::Result<()> {
+    let report = simulate(options).map_err(|err| io::Error::other(err.to_string()))?;
+    let mut output: Box<dyn Write> = match &options.output {
+        Some(path) => Box::new(File::create(path)?),
+        None => Box::new(stdout),
+    };
+    match options.format {
+        ReportFormat::Json => {
+            serde_json::to_writer_pretty(&mut output, &report)?;
+            writeln!(output)
+        }
+        ReportFormat::Csv => write_csv(&report, &mut output),
+    }
+}
+
+/// Plays `options.hands` hands between bots, topping busted bots back up to
+/// a full stack so every hand is played at the same table size.
+pub fn simulate(options: &SimulateOptions) -> Result<SimulationReport, GameError> {
+    let mut controller = GameController::with_rng(StdRng::seed_from_u64(options.seed));
+    let kinds = if options.bots.is_empty() { &[BotKind::Random][..] } else { &options.bots };
+    for (seat, kind) in (1..=options.players).zip(kinds.iter().cycle()) {
+        let strategy = kind.strategy(options.seed.wrapping_add(u64::from(seat)));
+        controller.seat_bot(&format!("bot{seat}"), &format!("Bot {seat}"), options.stack, strategy)?;
+    }
+    let ids: Vec<PlayerId> = controller.get_players().iter().map(|player| player.player_id.clone()).collect();
+    let bought_in = options.stack * ids.len() as u64;
+
+    let mut rebuys = vec![0u32; ids.len()];
+    let mut results: Vec<Vec<i64>> = vec![Vec::new(); ids.len()];
+    let mut pots = Vec::new();
+    let mut showdowns = 0;
+    let mut hand_ranks = BTreeMap::new();
+    let mut winning_ranks = BTreeMap::new();
+    let mut chips_conserved = true;
+
+    for _ in 0..options.hands {
+        for (index, id) in ids.iter().enumerate() {
+            if controller.find_player(id)?.chip_stack.is_zero() {
+                controller.award_chips(id, options.stack)?;
+                rebuys[index] += 1;
+            }
+        }
+        let before: Vec<Chips> = ids.iter().map(|id| controller.find_player(id).map(|player| player.chip_stack)).collect::<Result<_, _>>()?;
+
+        controller.take_events();
+        controller.start_hand(options.small_blind, options.big_blind)?; // Every seat is a bot, so this plays the whole hand
+
+        pots.push(controller.get_table().pots.iter().map(|pot| pot.total).sum::<Chips>());
+        let at_showdown: Vec<_> = controller.get_players().iter().filter(|player| player.is_in_play).collect();
+        if at_showdown.len() > 1 {
+            showdowns += 1;
+            for player in &at_showdown {
+                if let Some(rank) = &player.hand_strength {
+                    *hand_ranks.entry(format!("{rank:?}")).or_insert(0) += 1;
+                }
+            }
+            for event in controller.take_events() {
+                let GameEvent::HandFinished { winners, .. } = event else { continue };
+                for winner in winners {
+                    if let Some(rank) = &controller.find_player(&winner)?.hand_strength {
+                        *winning_ranks.entry(format!("{rank:?}")).or_insert(0) += 1;
+                    }
+                }
+            }
+        }
+
+        let mut chips = Chips::ZERO;
+        for (index, id) in ids.iter().enumerate() {
+            let stack = controller.find_player(id)?.chip_stack;
+            results[index].push(signed(stack) - signed(before[index]));
+            chips += stack;
+        }
+        let rebought: Chips = rebuys.iter().map(|&count| options.stack * u64::from(count)).sum();
+        chips_conserved &= chips == bought_in + rebought;
+    }
+
+    let players = ids
+        .iter()
+        .enumerate()
+        .map(|(index, id)| {
+            let (final_stack, luck) = controller.find_player(id)
+                .map(|player| (player.chip_stack, player.chips_won as f64 - player.ev_chips_won))
+                .unwrap_or_default();
+            let (mean_per_hand, variance_per_hand) = mean_and_variance(&results[index]);
+            let net = signed(final_stack) - signed(options.stack) * (1 + i64::from(rebuys[index]));
+            PlayerStats {
+                player_id: id.clone(),
+                final_stack,
+                rebuys: rebuys[index],
+                net,
+                ev_net: net as f64 - luck,
+                mean_per_hand,
+                variance_per_hand,
+            }
+        })
+        .collect();
+
+    Ok(SimulationReport {
+        hands: options.hands,
+        seed: options.seed,
+        showdowns,
+        hand_ranks,
+        winning_ranks,
+        pots: pot_stats(pots),
+        players,
+        chips_conserved,
+    })
+}
+
+fn mean_and_variance(values: &[i64]) -> (f64, f64) {
+    if values.is_empty() {
+        return (0.0, 0.0);
+    }
+    let count = values.len() as f64;
+    let mean = values.iter().sum::<i64>() as f64 / count;
+    let variance = values.iter().map(|&value| (value as f64 - mean).powi(2)).sum::<f64>() / count;
+    (mean, variance)
+}
+
+/// A stack as a signed number, for wins and losses.
+fn signed(chips: Chips) -> i64 {
+    i64::try_from(chips.get()).expect("chip count fits in an i64")
+}
+
+fn pot_stats(mut pots: Vec<Chips>) -> PotStats {
+    if pots.is_empty() {
+        return PotStats::default();
+    }
+    pots.sort_unstable();
+    PotStats {
+        mean: pots.iter().map(|pot| pot.get() as f64).sum::<f64>() / pots.len() as f64,
+        median: pots[pots.len() / 2],
+        max: pots[pots.len() - 1],
+    }
+}
+
+/// Writes the report as `section,key,value` rows.
+fn write_csv(report: &SimulationReport, output: &mut impl Write) -> io::Result<()> {
+    writeln!(output, "section,key,value")?;
+    writeln!(output, "run,hands,{}", report.hands)?;
+    writeln!(output, "run,seed,{}", report.seed)?;
+    writeln!(output, "run,showdowns,{}", report.showdowns)?;
+    writeln!(output, "run,chips_conserved,{}", report.chips_conserved)?;
+    for (rank, count) in &report.hand_ranks {
+        writeln!(output, "hand_ranks,{rank},{count}")?;
+    }
+    for (rank, count) in &report.winning_ranks {
+        writeln!(output, "winning_ranks,{rank},{count}")?;
+    }
+    writeln!(output, "pots,mean,{}", report.pots.mean)?;
+    writeln!(output, "pots,median,{}", report.pots.median)?;
+    writeln!(output, "pots,max,{}", report.pots.max)?;
+    for player in &report.players {
+        let id = &player.player_id;
+        writeln!(output, "{id},final_stack,{}", player.final_stack)?;
+        writeln!(output, "{id},rebuys,{}", player.rebuys)?;
+        writeln!(output, "{id},net,{}", player.net)?;
+        writeln!(output, "{id},ev_net,{}", player.ev_net)?;
+        writeln!(output, "{id},mean_per_hand,{}", player.mean_per_hand)?;
+        writeln!(output, "{id},variance_per_hand,{}", player.variance_per_hand)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options() -> SimulateOptions {
+        SimulateOptions { hands: 300, players: 4, seed: 3, ..SimulateOptions::default() }
+    }
+
+    #[test]
+    fn test_simulation_is_repeatable_and_conserves_chips() {
+        let report = simulate(&options()).unwrap();
+        assert!(report.chips_conserved);
+        assert!(report.showdowns > 0);
+        assert_eq!(report.players.iter().map(|player| player.net).sum::<i64>(), 0);
+        assert!(report.players.iter().map(|player| player.ev_net).sum::<f64>().abs() < 1e-6); // Equity moves chips between players too
+        assert!(report.players.iter().any(|player| player.ev_net != player.net as f64));
+        assert!(report.hand_ranks.values().sum::<u64>() >= 2 * report.showdowns);
+
+        let again = simulate(&options()).unwrap();
+        assert_eq!(serde_json::to_string(&report).unwrap(), serde_json::to_string(&again).unwrap());
+    }
+
+    #[test]
+    fn test_csv_report() {
+        let mut output = Vec::new();
+        run(&SimulateOptions { hands: 20, format: ReportFormat::Csv, ..options() }, &mut output).unwrap();
+        let csv = String::from_utf8(output).unwrap();
+        assert!(csv.starts_with("section,key,value\nrun,hands,20\n"));
+        assert!(csv.contains("bot4,net,"));
+    }
+
+    #[test]
+    fn test_mixed_strategies() {
+        let options = SimulateOptions { hands: 100, bots: vec![BotKind::CallingStation, BotKind::TightAggressive], ..options() };
+        let report = simulate(&options).unwrap();
+        assert!(report.chips_conserved);
+        assert_eq!(report.players.len(), 4);
+    }
+}
@@ -0,0 +1,197 @@
+//! Friends and table invitations. A player may invite a friend to a table;
+//! the table holds a seat for the friend until they accept, decline or let
+//! the invitation lapse.
+//!
+//! Friend lists are one-sided, like following: adding someone needs no
+//! consent. Invitations only go between mutual friends, so nobody can be
+//! sent them by a stranger.
+
+use std::collections::{BTreeSet, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use poker_core::game_error::GameError;
+use poker_core::player_id::PlayerId;
+use serde::Serialize;
+use tokio::sync::broadcast;
+use utoipa::ToSchema;
+
+/// How long an invitation holds its seat.
+pub const INVITATION_TTL: Duration = Duration::from_secs(60);
+
+/// Invitations a notification listener may fall behind by before missing some.
+const NOTIFICATION_CAPACITY: usize = 256;
+
+/// A player's friend, as listed by `GET /v1/friends`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct Friend {
+    #[schema(example = "2")]
+    pub player_id: PlayerId,
+    pub mutual: bool,             // They have added the player back, so either can invite the other
+}
+
+/// An invitation to take a seat at a table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct Invitation {
+    #[schema(example = 7)]
+    pub id: u64,
+    #[schema(example = "1")]
+    pub from: PlayerId,
+    #[schema(example = "2")]
+    pub to: PlayerId,
+    #[schema(example = "main")]
+    pub table_id: String,
+    pub expires_at: u64,          // Unix time (seconds) when the held seat is given up
+}
+
+/// Every player's friends and the invitations waiting for an answer.
+pub struct Social {
+    friends: Mutex<HashMap<PlayerId, BTreeSet<PlayerId>>>, // Only locked briefly, never across an await
+    invitations: Mutex<HashMap<u64, Invitation>>,            // By id; locked the same way
+    next_id: AtomicU64,
+    notifications: broadcast::Sender<Arc<Invitation>>,       // Each new invitation, for the invitee's listeners
+}
+
+impl Default for Social {
+    fn default() -> Self {
+        Self {
+            friends: Mutex::default(),
+            invitations: Mutex::default(),
+            next_id: AtomicU64::new(1),
+            notifications: broadcast::channel(NOTIFICATION_CAPACITY).0,
+        }
+    }
+}
+
+impl Social {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `friend_id` to the friends of `player_id`; `false` if already there.
+    pub fn add_friend(&self, player_id: &str, friend_id: &str) -> Result<bool, GameError> {
+        if player_id == friend_id {
+            return Err(GameError::InvalidRequest("players can't add themselves as a friend".to_string()));
+        }
+        Ok(lock(&self.friends).entry(player_id.into()).or_default().insert(friend_id.into()))
+    }
+
+    pub fn remove_friend(&self, player_id: &str, friend_id: &str) -> Result<(), GameError> {
+        let removed = lock(&self.friends).get_mut(player_id).is_some_and(|friends| friends.remove(friend_id));
+        if removed { Ok(()) } else { Err(GameError::PlayerNotFound(friend_id.to_string())) }
+    }
+
+    /// The friends of `player_id`, in id order.
+    pub fn friends(&self, player_id: &str) -> Vec<Friend> {
+        let friends = lock(&self.friends);
+        let Some(listed) = friends.get(player_id) else { return Vec::new() };
+        listed
+            .iter()
+            .map(|friend_id| Friend {
+                player_id: friend_id.clone(),
+                mutual: friends.get(friend_id).is_some_and(|theirs| theirs.contains(player_id)),
+            })
+            .collect()
+    }
+
+    fn are_mutual_friends(&self, a: &str, b: &str) -> bool {
+        let friends = lock(&self.friends);
+        let lists = |from: &str, to: &str| friends.get(from).is_some_and(|listed| listed.contains(to));
+        lists(a, b) && lists(b, a)
+    }
+
+    /// Checks `from` may invite `to` to `table_id`: they must be mutual
+    /// friends, and `to` not already invited there.
+    pub fn check_invite(&self, from: &str, to: &str, table_id: &str) -> Result<(), GameError> {
+        if !self.are_mutual_friends(from, to) {
+            return Err(GameError::NotAllowed(format!("player {to} and you must have added each other as friends")));
+        }
+        if lock(&self.invitations).values().any(|invitation| *invitation.to == *to && invitation.table_id == table_id) {
+            return Err(GameError::InvalidState(format!("player {to} is already invited to table {table_id}")));
+        }
+        Ok(())
+    }
+
+    /// Records an invitation whose seat is already held, and notifies the invitee.
+    pub fn invite(&self, from: &str, to: &str, table_id: &str) -> Invitation {
+        let invitation = Invitation {
+            id: self.next_id.fetch_add(1, Ordering::Relaxed),
+            from: from.into(),
+            to: to.into(),
+            table_id: table_id.to_string(),
+            expires_at: now_unix() + INVITATION_TTL.as_secs(),
+        };
+        lock(&self.invitations).insert(invitation.id, invitation.clone());
+        let _ = self.notifications.send(Arc::new(invitation.clone())); // Nobody may be listening
+        invitation
+    }
+
+    /// The open invitation `id`, checked to be addressed to `player_id`.
+    pub fn invitation(&self, id: u64, player_id: &str) -> Result<Invitation, GameError> {
+        match lock(&self.invitations).get(&id) {
+            Some(invitation) if *invitation.to == *player_id => Ok(invitation.clone()),
+            Some(_) => Err(GameError::NotAllowed("the invitation is for another player".to_string())),
+            None => Err(GameError::InvalidRequest(format!("no open invitation {id}"))),
+        }
+    }
+
+    /// Closes the invitation `id` once it is answered or lapses; `None` if
+    /// it already was.
+    pub fn close(&self, id: u64) -> Option<Invitation> {
+        lock(&self.invitations).remove(&id)
+    }
+
+    /// The open invitations sent to `player_id`, oldest first.
+    pub fn invitations(&self, player_id: &str) -> Vec<Invitation> {
+        let mut invitations: Vec<Invitation> = lock(&self.invitations).values().filter(|invitation| *invitation.to == *player_id).cloned().collect();
+        invitations.sort_by_key(|invitation| invitation.id);
+        invitations
+    }
+
+    /// Receives every invitation sent from now on, to anyone.
+    pub fn subscribe(&self) -> broadcast::Receiver<Arc<Invitation>> {
+        self.notifications.subscribe()
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn now_unix() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_mutual_friends_can_invite() {
+        let social = Social::new();
+        assert_eq!(social.add_friend("1", "2"), Ok(true));
+        assert_eq!(social.add_friend("1", "2"), Ok(false));
+        assert!(social.add_friend("1", "1").is_err());
+        assert_eq!(social.friends("1"), vec![Friend { player_id: "2".into(), mutual: false }]);
+        assert!(matches!(social.check_invite("1", "2", "main"), Err(GameError::NotAllowed(_))));
+
+        social.add_friend("2", "1").unwrap();
+        assert!(social.friends("1")[0].mutual);
+        let mut notifications = social.subscribe();
+        social.check_invite("1", "2", "main").unwrap();
+        let invitation = social.invite("1", "2", "main");
+        assert_eq!(*notifications.try_recv().unwrap(), invitation);
+        assert!(matches!(social.check_invite("1", "2", "main"), Err(GameError::InvalidState(_))));
+        assert_eq!(social.invitations("2"), vec![invitation.clone()]);
+
+        assert!(matches!(social.invitation(invitation.id, "1"), Err(GameError::NotAllowed(_))));
+        assert_eq!(social.invitation(invitation.id, "2"), Ok(invitation.clone()));
+        assert_eq!(social.close(invitation.id), Some(invitation.clone()));
+        assert!(social.invitation(invitation.id, "2").is_err());
+
+        social.remove_friend("2", "1").unwrap();
+        assert!(social.remove_friend("2", "1").is_err());
+        assert!(!social.friends("1")[0].mutual);
+    }
+}
@@ -0,0 +1,207 @@
+//! Runs an external solver process for `/v1/analysis/solve`.
+//!
+//! The configured command is started afresh for each spot. It reads one
+//! JSON object on stdin:
+//!
+//! ```text
+//! {"ranges": [["AsAd", "KsKh"], ["AsKc"]], "board": ["Ah", "Kd", "2c"],
+//!  "pot": 100, "effective_stack": 900, "bet_sizes": [33, 75]}
+//! ```
+//!
+//! with the out-of-position player's hands first and bet sizes in percent
+//! of the pot, and writes the strategy of that player as one JSON object on
+//! stdout before exiting:
+//!
+//! ```text
+//! {"actions": ["check", "bet 33", "bet 75"],
+//!  "combos": [{"cards": "AsAd", "frequencies": [0.2, 0.5, 0.3]}]}
+//! ```
+//!
+//! A small script can translate this to and from the input format of any
+//! solver.
+
+use std::io::{Read, Write};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use poker_core::card_dealer::Card;
+use poker_core::chips::Chips;
+use poker_core::game_error::GameError;
+use poker_core::solver::{ComboStrategy, SolverBackend, SolverSpot, SolverStrategy};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::config::SolverConfig;
+
+/// How often a running solver is checked for having exited.
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// What the solver reads on stdin.
+#[derive(Debug, Serialize)]
+struct SpotMessage {
+    ranges: [Vec<String>; 2],
+    board: Vec<String>,
+    pot: Chips,
+    effective_stack: Chips,
+    bet_sizes: Vec<u32>,
+}
+
+impl From<&SolverSpot> for SpotMessage {
+    fn from(spot: &SolverSpot) -> Self {
+        let hands = |player: usize| spot.live_combos(player).iter().map(|[first, second]| format!("{first}{second}")).collect();
+        Self {
+            ranges: [hands(0), hands(1)],
+            board: spot.board.iter().map(Card::to_string).collect(),
+            pot: spot.pot,
+            effective_stack: spot.effective_stack,
+            bet_sizes: spot.bet_sizes.clone(),
+        }
+    }
+}
+
+/// What the solver writes on stdout.
+#[derive(Debug, Deserialize)]
+struct StrategyMessage {
+    actions: Vec<String>,
+    combos: Vec<ComboMessage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ComboMessage {
+    cards: String,          // Four characters, e.g. `AsAd`
+    frequencies: Vec<f64>,
+}
+
+impl TryFrom<StrategyMessage> for SolverStrategy {
+    type Error = String;
+
+    fn try_from(message: StrategyMessage) -> Result<Self, String> {
+        let combos = message.combos.into_iter()
+            .map(|combo| {
+                let cards = combo.cards.get(..2).zip(combo.cards.get(2..))
+                    .and_then(|(first, second)| Some([first.parse().ok()?, second.parse().ok()?]))
+                    .ok_or_else(|| format!("unreadable hand {:?}", combo.cards))?;
+                Ok(ComboStrategy { cards, frequencies: combo.frequencies })
+            })
+            .collect::<Result<_, String>>()?;
+        SolverStrategy::new(message.actions, combos)
+    }
+}
+
+/// A solver run as a child process, one per spot.
+pub struct ProcessSolver {
+    command: Vec<String>, // The program, then its arguments
+    timeout: Duration,
+}
+
+impl ProcessSolver {
+    /// The solver `config` describes, or `None` when no command is set.
+    pub fn from_config(config: &SolverConfig) -> Option<Self> {
+        if config.command.is_empty() {
+            return None;
+        }
+        Some(Self { command: config.command.clone(), timeout: Duration::from_secs(config.timeout_secs) })
+    }
+
+    /// Runs the command with `input` on stdin, killing it after the timeout,
+    /// and returns what it wrote on stdout.
+    fn run(&self, input: Vec<u8>) -> Result<Vec<u8>, String> {
+        let (program, args) = self.command.split_first().expect("a solver always has a command");
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .map_err(|err| format!("could not start {program}: {err}"))?;
+
+        // Feed and drain the pipes on their own threads so a big spot or strategy can't deadlock them
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        let writer = thread::spawn(move || stdin.write_all(&input));
+        let mut stdout = child.stdout.take().expect("stdout is piped");
+        let reader = thread::spawn(move || {
+            let mut output = Vec::new();
+            stdout.read_to_end(&mut output).map(|_| output)
+        });
+
+        let deadline = Instant::now() + self.timeout;
+        let status = loop {
+            if let Some(status) = child.try_wait().map_err(|err| err.to_string())? {
+                break status;
+            }
+            if Instant::now() >= deadline {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!("no answer within {}s", self.timeout.as_secs()));
+            }
+            thread::sleep(POLL_INTERVAL);
+        };
+        // A solver may exit without reading all of its input, which is its own business
+        let _ = writer.join();
+        let output = reader.join().expect("the reader doesn't panic").map_err(|err| err.to_string())?;
+        if !status.success() {
+            return Err(format!("exited with {status}"));
+        }
+        Ok(output)
+    }
+}
+
+impl SolverBackend for ProcessSolver {
+    fn name(&self) -> &str {
+        &self.command[0]
+    }
+
+    fn solve(&self, spot: &SolverSpot) -> Result<SolverStrategy, GameError> {
+        let input = serde_json::to_vec(&SpotMessage::from(spot)).expect("spots always serialize");
+        let started = Instant::now();
+        let output = self.run(input).map_err(|err| GameError::Internal(format!("solver failed: {err}")))?;
+        debug!(elapsed_ms = started.elapsed().as_millis() as u64, "solver answered");
+        let message: StrategyMessage = serde_json::from_slice(&output)
+            .map_err(|err| GameError::Internal(format!("unreadable solver output: {err}")))?;
+        SolverStrategy::try_from(message).map_err(|err| GameError::Internal(format!("bad solver strategy: {err}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use poker_core::card_dealer::parse_cards;
+
+    fn spot() -> SolverSpot {
+        SolverSpot {
+            ranges: ["AA".parse().unwrap(), "AsKc".parse().unwrap()],
+            board: parse_cards("Ah Kd 2c").unwrap(),
+            pot: Chips::new(100),
+            effective_stack: Chips::new(900),
+            bet_sizes: vec![75],
+        }
+    }
+
+    fn shell(script: &str, timeout_secs: u64) -> ProcessSolver {
+        let command = vec!["sh".to_string(), "-c".to_string(), script.to_string()];
+        ProcessSolver::from_config(&SolverConfig { command, timeout_secs }).unwrap()
+    }
+
+    #[test]
+    fn test_process_solver_round_trip() {
+        let message = serde_json::to_value(SpotMessage::from(&spot())).unwrap();
+        assert_eq!(message["ranges"], serde_json::json!([["AdAc", "AdAs", "AcAs"], ["AsKc"]]));
+        assert_eq!(message["board"], serde_json::json!(["Ah", "Kd", "2c"]));
+
+        // Answers with a fixed strategy once it has read the spot
+        let solver = shell(r#"cat > /dev/null; echo '{"actions": ["check", "bet 75"], "combos": [{"cards": "AdAc", "frequencies": [0.25, 0.75]}]}'"#, 5);
+        let strategy = solver.solve(&spot()).unwrap();
+        assert_eq!(strategy.actions(), ["check", "bet 75"]);
+        assert_eq!(strategy.combos()[0].cards, [parse_cards("Ad").unwrap()[0], parse_cards("Ac").unwrap()[0]]);
+        assert_eq!(solver.name(), "sh");
+
+        for broken in ["exit 3", "echo 'not json'", r#"echo '{"actions": ["check"], "combos": [{"cards": "AdAc", "frequencies": [0.5]}]}'"#] {
+            assert!(matches!(shell(broken, 5).solve(&spot()), Err(GameError::Internal(_))), "{broken}");
+        }
+        let started = Instant::now();
+        assert!(matches!(shell("sleep 10", 1).solve(&spot()), Err(GameError::Internal(_))));
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(ProcessSolver::from_config(&SolverConfig::default()).is_none());
+    }
+}
@@ -0,0 +1,346 @@
+use std::collections::HashMap;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::AtomicUsize;
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use std::time::Duration;
+
+use poker_core::events::GameEvent;
+use poker_core::game_controller::GameController;
+use poker_core::game_error::GameError;
+use poker_core::player_id::PlayerId;
+use tokio::sync::{broadcast, mpsc, oneshot};
+use tokio::time::{self, Instant};
+use tracing::{debug, error};
+
+use crate::audit_log::AuditLog;
+use crate::blind_clock::BlindClock;
+use crate::events::{EventBus, SequencedEvent};
+use crate::hand_history::HandHistory;
+use crate::presence::PresenceTracker;
+
+/// Id of the table created at startup, which the unscoped routes act on.
+pub const DEFAULT_TABLE_ID: &str = "main";
+
+/// Commands a table queues before callers have to wait to send more.
+const COMMAND_QUEUE_LEN: usize = 64;
+
+/// A unit of work run against a table's game state on the table's own task.
+type Command = Box<dyn FnOnce(&mut GameController, &mut EventBus) + Send>;
+
+/// One hosted table: a handle to the task owning its game state, and who is watching it.
+///
+/// The `GameController` lives on a dedicated task and is only touched by the
+/// commands sent to it, one at a time and in arrival order, so there is no
+/// lock to contend on or poison. After each command the task publishes the
+/// events the controller recorded on the table's `EventBus`, and chains the
+/// cards it dealt onto the table's audit log, and keeps the hands it
+/// finished in the table's hand history.
+pub struct TableHandle {
+    pub id: String,
+    commands: mpsc::Sender<Command>,
+    events: broadcast::Sender<Arc<SequencedEvent>>,
+    pub spectators: AtomicUsize, // Open spectator WebSocket connections
+    pub presence: PresenceTracker, // Players following the table over a WebSocket
+    pub clock: OnceLock<BlindClock>, // Set once the table's blind clock is started
+    pub audit: Arc<Mutex<AuditLog>>, // Every card dealt at the table; only locked briefly, never across an await
+    pub history: Arc<Mutex<HandHistory>>, // The table's recent finished hands; locked the same way
+}
+
+impl TableHandle {
+    /// Spawns the table's task; it stops once the last handle is dropped.
+    pub fn new(id: impl Into<String>, controller: GameController) -> Arc<Self> {
+        let id = id.into();
+        let (commands, receiver) = mpsc::channel(COMMAND_QUEUE_LEN);
+        let bus = EventBus::new(id.clone());
+        let events = bus.sender();
+        let audit = Arc::new(Mutex::new(AuditLog::new()));
+        let history = Arc::new(Mutex::new(HandHistory::new()));
+        tokio::spawn(run_table(id.clone(), controller, bus, audit.clone(), history.clone(), receiver));
+        Arc::new(Self { id, commands, events, spectators: AtomicUsize::new(0), presence: PresenceTracker::new(), clock: OnceLock::new(), audit, history })
+    }
+
+    /// Runs `command` on the table's task and returns what it produced.
+    pub async fn call<R, F>(&self, command: F) -> Result<R, GameError>
+    where
+        R: Send + 'static,
+        F: FnOnce(&mut GameController) -> R + Send + 'static,
+    {
+        self.call_with_events(move |controller, _| command(controller)).await
+    }
+
+    /// Like `call`, with access to the table's event stream as of before the command.
+    pub async fn call_with_events<R, F>(&self, command: F) -> Result<R, GameError>
+    where
+        R: Send + 'static,
+        F: FnOnce(&mut GameController, &mut EventBus) -> R + Send + 'static,
+    {
+        let (reply, result) = oneshot::channel();
+        self.commands
+            .send(Box::new(move |controller: &mut GameController, events: &mut EventBus| {
+                let _ = reply.send(command(controller, events)); // The caller may have stopped waiting
+            }))
+            .await
+            .map_err(|_| GameError::Internal(format!("table {} is not running", self.id)))?;
+        // The reply is dropped unsent if the command panicked
+        result
+            .await
+            .map_err(|_| GameError::Internal(format!("table {} failed to run a command", self.id)))
+    }
+
+    /// Like `call`, for commands that can fail with a `GameError` themselves.
+    pub async fn try_call<R, F>(&self, command: F) -> Result<R, GameError>
+    where
+        R: Send + 'static,
+        F: FnOnce(&mut GameController) -> Result<R, GameError> + Send + 'static,
+    {
+        self.call(command).await?
+    }
+
+    /// Receives every event published from now on.
+    pub fn subscribe(&self) -> broadcast::Receiver<Arc<SequencedEvent>> {
+        self.events.subscribe()
+    }
+
+    /// Receives the retained events after `seq`, then every event published from now on.
+    pub async fn subscribe_after(
+        &self,
+        seq: u64,
+    ) -> Result<(Vec<Arc<SequencedEvent>>, broadcast::Receiver<Arc<SequencedEvent>>), GameError> {
+        self.call_with_events(move |_, events| events.subscribe_after(seq)).await
+    }
+}
+
+/// The table's task: applies commands until every handle is gone.
+///
+/// A panicking command is logged and skipped; the table keeps serving with
+/// whatever state the command left behind. When the table has an action
+/// timeout, a player who lets it run out on their turn is checked or folded
+/// for, just as if they had sent the action themselves.
+async fn run_table(
+    id: String,
+    mut controller: GameController,
+    mut events: EventBus,
+    audit: Arc<Mutex<AuditLog>>,
+    history: Arc<Mutex<HandHistory>>,
+    mut commands: mpsc::Receiver<Command>,
+) {
+    let mut turn = Turn::of(&controller, None);
+    loop {
+        let command = match &turn {
+            Some(turn) => tokio::select! {
+                command = commands.recv() => command,
+                () = time::sleep_until(turn.deadline) => Some(turn.time_out()),
+            },
+            None => commands.recv().await,
+        };
+        let Some(command) = command else { break };
+        if panic::catch_unwind(AssertUnwindSafe(|| command(&mut controller, &mut events))).is_err() {
+            error!(table_id = %id, "table command panicked");
+        }
+        publish(&mut controller, &mut events, &audit, &history);
+        turn = Turn::of(&controller, turn);
+    }
+    debug!(table_id = %id, "table stopped");
+}
+
+/// Hands the cards, hands and events a command left on the controller to
+/// the table's audit log, hand history and event stream.
+fn publish(controller: &mut GameController, events: &mut EventBus, audit: &Mutex<AuditLog>, history: &Mutex<HandHistory>) {
+    let records = controller.take_deal_records();
+    if !records.is_empty() {
+        let mut audit = audit.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        for record in records {
+            audit.record(record);
+        }
+    }
+    // Hands first, so a player leaving after the hand they just finished ends their session there
+    let hands = controller.take_finished_hands();
+    let table_events = controller.take_events();
+    let seat_changes: Vec<PlayerId> = table_events.iter()
+        .filter_map(|event| match event {
+            GameEvent::PlayerSeated { player_id, .. } | GameEvent::PlayerRemoved { player_id } => Some(player_id.clone()),
+            _ => None,
+        })
+        .collect();
+    if !hands.is_empty() || !seat_changes.is_empty() {
+        let mut history = history.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        for log in hands {
+            history.record(log, controller.max_seats());
+        }
+        for player_id in seat_changes {
+            history.record_seat_change(player_id);
+        }
+    }
+    for event in table_events {
+        events.publish(event);
+    }
+}
+
+/// The turn the table is waiting on, and when it stops waiting.
+struct Turn {
+    hand_number: u64,
+    player_id: PlayerId,
+    actions: usize, // Actions logged in the hand when the turn began, so a player's next turn gets a fresh clock
+    deadline: Instant,
+}
+
+impl Turn {
+    /// The turn now being waited on, if the table has an action timeout.
+    /// The deadline of `previous` carries over while it is the same turn.
+    fn of(controller: &GameController, previous: Option<Turn>) -> Option<Turn> {
+        let secs = controller.action_timeout_secs()?;
+        let player_id = controller.next_to_act()?;
+        let hand_number = controller.hand_number();
+        let actions = controller.hand_log().map_or(0, |log| log.actions.len());
+        if let Some(previous) = previous.filter(|turn| {
+            turn.hand_number == hand_number && turn.player_id == player_id && turn.actions == actions
+        }) {
+            return Some(previous);
+        }
+        Some(Turn { hand_number, player_id: player_id.into(), actions, deadline: Instant::now() + Duration::from_secs(secs) })
+    }
+
+    /// Acts for the player whose time ran out.
+    fn time_out(&self) -> Command {
+        let player_id = self.player_id.clone();
+        Box::new(move |controller: &mut GameController, _: &mut EventBus| {
+            if let Err(err) = controller.act_on_timeout(&player_id) {
+                error!(%player_id, %err, "could not act for a player who timed out");
+            }
+        })
+    }
+}
+
+/// Every table hosted by this server, by id.
+pub struct TableRegistry {
+    default_table: Arc<TableHandle>,
+    tables: RwLock<HashMap<String, Arc<TableHandle>>>, // Only held to clone a handle out, never across an await
+}
+
+impl TableRegistry {
+    pub fn new(default_table: Arc<TableHandle>) -> Self {
+        let tables = HashMap::from([(default_table.id.clone(), default_table.clone())]);
+        Self { default_table, tables: RwLock::new(tables) }
+    }
+
+    /// The table created at startup.
+    pub fn default_table(&self) -> Arc<TableHandle> {
+        self.default_table.clone()
+    }
+
+    pub fn get(&self, table_id: &str) -> Result<Arc<TableHandle>, GameError> {
+        self.tables
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get(table_id)
+            .cloned()
+            .ok_or_else(|| GameError::TableNotFound(table_id.to_string()))
+    }
+
+    /// Adds a table; refused when one with the same id is already open.
+    pub fn insert(&self, table: Arc<TableHandle>) -> Result<(), GameError> {
+        let mut tables = self.tables.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if tables.contains_key(&table.id) {
+            return Err(GameError::InvalidState(format!("table {} already exists", table.id)));
+        }
+        tables.insert(table.id.clone(), table);
+        Ok(())
+    }
+
+    pub fn all(&self) -> Vec<Arc<TableHandle>> {
+        self.tables
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .values()
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use poker_core::betting::Street;
+    use poker_core::chips::Chips;
+    use poker_core::player::PlayerAction;
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_tables_run_independently() {
+        let registry = TableRegistry::new(TableHandle::new(DEFAULT_TABLE_ID, GameController::new()));
+        registry.insert(TableHandle::new("second", GameController::new())).unwrap();
+        assert!(matches!(registry.insert(TableHandle::new("second", GameController::new())), Err(GameError::InvalidState(_))));
+
+        // A command stuck on the default table doesn't hold up another table
+        let (release, blocked) = std::sync::mpsc::channel::<()>();
+        let main = registry.default_table();
+        let stuck = tokio::spawn(async move { main.call(move |_| blocked.recv().is_ok()).await });
+
+        let second = registry.get("second").unwrap();
+        second.call(|controller| controller.pause()).await.unwrap();
+        assert!(second.call(|controller| controller.is_paused()).await.unwrap());
+        release.send(()).unwrap();
+        assert_eq!(stuck.await.unwrap(), Ok(true));
+
+        assert_eq!(registry.get("missing").err(), Some(GameError::TableNotFound("missing".to_string())));
+        assert_eq!(registry.all().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_panicking_command_leaves_table_running() {
+        let table = TableHandle::new(DEFAULT_TABLE_ID, GameController::new());
+        let result = table.call(|_| -> u32 { panic!("bad command") }).await;
+        assert!(matches!(result, Err(GameError::Internal(_))));
+
+        table.call(|controller| controller.pause()).await.unwrap();
+        assert!(table.call(|controller| controller.is_paused()).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_recorded_events_are_published_in_order() {
+        let table = TableHandle::new(DEFAULT_TABLE_ID, GameController::new());
+        let mut receiver = table.subscribe();
+        table.call(|controller| {
+            controller.pause();
+            controller.resume();
+        }).await.unwrap();
+
+        assert_eq!(receiver.recv().await.unwrap().seq, 1);
+        assert_eq!(receiver.recv().await.unwrap().event.kind(), "table_resumed");
+        let (missed, _) = table.subscribe_after(1).await.unwrap();
+        assert_eq!(missed.len(), 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_players_who_run_out_of_time_are_acted_for() {
+        let mut controller = GameController::new();
+        controller.initialize_players(vec![
+            ("1".to_string(), "Alice".to_string(), 0, Chips::new(1000)),
+            ("2".to_string(), "Bob".to_string(), 1, Chips::new(1000)),
+        ]);
+        controller.set_action_timeout(Some(30));
+        let table = TableHandle::new(DEFAULT_TABLE_ID, controller);
+        let mut receiver = table.subscribe();
+        table.try_call(|controller| controller.start_hand(Chips::new(5), Chips::new(10))).await.unwrap();
+        let next_to_act = || table.call(|controller| controller.next_to_act().map(str::to_string));
+
+        time::sleep(Duration::from_secs(29)).await;
+        table.try_call(|controller| controller.act("1", PlayerAction::Call)).await.unwrap();
+        // The big blind gets a clock of their own, then checks when it runs out
+        time::sleep(Duration::from_secs(29)).await;
+        assert_eq!(next_to_act().await.unwrap().as_deref(), Some("2"));
+        time::sleep(Duration::from_secs(2)).await;
+        assert_eq!(table.call(|controller| controller.betting().map(|betting| betting.street)).await.unwrap(), Some(Street::Flop));
+
+        // Owing a bet, a timed-out player folds
+        table.try_call(|controller| controller.act("2", PlayerAction::Bet(Chips::new(10)))).await.unwrap();
+        time::sleep(Duration::from_secs(31)).await;
+        assert_eq!(next_to_act().await.unwrap(), None);
+        let mut timed_out = Vec::new();
+        while let Ok(event) = receiver.try_recv() {
+            if let GameEvent::ActionTimedOut { player_id, action, .. } = &event.event {
+                timed_out.push((player_id.clone(), action.clone()));
+            }
+        }
+        assert_eq!(timed_out, vec![(PlayerId::from("2"), PlayerAction::Check), (PlayerId::from("1"), PlayerAction::Fold)]);
+    }
+}
@@ -0,0 +1,142 @@
+use std::sync::Arc;
+
+use poker_core::events::GameEvent;
+use poker_core::game_error::GameError;
+use poker_core::tournament::TournamentRules;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+use crate::table_registry::TableHandle;
+
+/// Keeps the tables of one tournament in step.
+///
+/// On the money bubble every table plays hand-for-hand: a table that has
+/// finished its hand deals no other until every table has finished theirs.
+pub struct TournamentCoordinator {
+    tables: Vec<Arc<TableHandle>>,
+    rules: TournamentRules,
+}
+
+impl TournamentCoordinator {
+    pub fn new(tables: Vec<Arc<TableHandle>>, rules: TournamentRules) -> Self {
+        Self { tables, rules }
+    }
+
+    /// Starts or ends hand-for-hand play from the players left across the
+    /// tables, and releases the next hand everywhere once no table is still
+    /// playing one. Returns whether the tables are on the bubble.
+    pub async fn sync(&self) -> Result<bool, GameError> {
+        let mut players_remaining = 0;
+        let mut hand_in_progress = false;
+        for table in &self.tables {
+            let (remaining, in_progress) = table
+                .call(|controller| (controller.players_remaining(), controller.is_hand_in_progress()))
+                .await?;
+            players_remaining += remaining;
+            hand_in_progress |= in_progress;
+        }
+
+        let on_bubble = self.rules.on_bubble(players_remaining);
+        for table in &self.tables {
+            table
+                .call(move |controller| {
+                    controller.set_hand_for_hand(on_bubble);
+                    if on_bubble && !hand_in_progress {
+                        controller.release_next_hand();
+                    }
+                })
+                .await?;
+        }
+        Ok(on_bubble)
+    }
+}
+
+/// Runs `coordinator` until its tables are gone, syncing the tables whenever
+/// a hand ends or players come and go.
+pub fn spawn_coordinator(coordinator: TournamentCoordinator) {
+    let (changed, mut changes) = mpsc::channel(coordinator.tables.len().max(1));
+    for table in &coordinator.tables {
+        let (mut events, changed, table_id) = (table.subscribe(), changed.clone(), table.id.clone());
+        tokio::spawn(async move {
+            loop {
+                match events.recv().await {
+                    Ok(event) if !changes_the_field(&event.event) => continue,
+                    Ok(_) | Err(RecvError::Lagged(_)) => {}
+                    Err(RecvError::Closed) => return,
+                }
+                if changed.send(table_id.clone()).await.is_err() {
+                    return;
+                }
+            }
+        });
+    }
+    drop(changed);
+
+    tokio::spawn(async move {
+        let mut on_bubble = false;
+        loop {
+            match coordinator.sync().await {
+                Ok(now_on_bubble) if now_on_bubble != on_bubble => {
+                    info!(on_bubble = now_on_bubble, "hand-for-hand play");
+                    on_bubble = now_on_bubble;
+                }
+                Ok(_) => {}
+                Err(err) => warn!(%err, "could not sync the tournament's tables"),
+            }
+            if changes.recv().await.is_none() {
+                return;
+            }
+        }
+    });
+}
+
+/// Events after which the bubble or the hands in progress may have changed.
+fn changes_the_field(event: &GameEvent) -> bool {
+    matches!(
+        event,
+        GameEvent::HandFinished { .. }
+            | GameEvent::HandCancelled { .. }
+            | GameEvent::PlayerSeated { .. }
+            | GameEvent::PlayerRemoved { .. }
+            | GameEvent::ChipsAdjusted { .. }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use poker_core::chips::Chips;
+    use poker_core::game_controller::GameController;
+
+    fn table(id: &str, players: &[&str]) -> Arc<TableHandle> {
+        let mut controller = GameController::new();
+        controller.initialize_players(players.iter().enumerate().map(|(seat, id)| (*id, id.to_string(), seat, Chips::new(1000))).collect());
+        TableHandle::new(id, controller)
+    }
+
+    #[tokio::test]
+    async fn test_bubble_plays_hand_for_hand() {
+        let (first, second) = (table("first", &["1", "2", "3"]), table("second", &["4", "5"]));
+        let rules = TournamentRules { paid_places: 3, ..TournamentRules::default() };
+        let coordinator = TournamentCoordinator::new(vec![first.clone(), second.clone()], rules);
+        assert_eq!(coordinator.sync().await, Ok(false)); // Five left, two off the money
+
+        first.try_call(|controller| controller.adjust_chips("3", -1000)).await.unwrap();
+        second.try_call(|controller| controller.start_hand(Chips::new(5), Chips::new(10))).await.unwrap();
+        assert_eq!(coordinator.sync().await, Ok(true));
+        let deal = || first.try_call(|controller| controller.start_hand(Chips::new(5), Chips::new(10)));
+        assert!(deal().await.is_err()); // The second table is still playing its hand
+
+        second.call(|controller| controller.cancel_hand()).await.unwrap();
+        coordinator.sync().await.unwrap();
+        deal().await.unwrap();
+        first.call(|controller| controller.cancel_hand()).await.unwrap();
+        assert!(deal().await.is_err()); // One hand per release
+
+        // The bubble bursts and the tables play on freely
+        second.try_call(|controller| controller.adjust_chips("5", -1000)).await.unwrap();
+        assert_eq!(coordinator.sync().await, Ok(false));
+        deal().await.unwrap();
+    }
+}
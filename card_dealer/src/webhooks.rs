@@ -0,0 +1,168 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::{debug, info, warn};
+
+use crate::config::WebhookConfig;
+use crate::events::SequencedEvent;
+
+/// Header carrying the hex HMAC-SHA256 of the body when a secret is configured.
+const SIGNATURE_HEADER: &str = "x-webhook-signature";
+
+/// Longest wait between two delivery attempts.
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Starts forwarding events to the configured webhook URLs.
+///
+/// Each delivery runs in its own task, so a slow or failing endpoint never
+/// holds up the others or the game. Does nothing when no URL is configured.
+pub fn spawn_webhooks(config: WebhookConfig, table_id: String, mut events: broadcast::Receiver<Arc<SequencedEvent>>) {
+    if config.urls.is_empty() {
+        return;
+    }
+    let client = match reqwest::Client::builder()
+        .timeout(Duration::from_secs(config.timeout_secs))
+        .build()
+    {
+        Ok(client) => client,
+        Err(err) => {
+            warn!(%err, "failed to build the webhook client, webhooks disabled");
+            return;
+        }
+    };
+    info!(%table_id, urls = config.urls.len(), events = ?config.events, "webhooks enabled");
+
+    let config = Arc::new(config);
+    tokio::spawn(async move {
+        loop {
+            let event = match events.recv().await {
+                Ok(event) => event,
+                Err(RecvError::Lagged(missed)) => {
+                    warn!(missed, "webhook dispatcher fell behind, events skipped");
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            };
+            if !config.events.iter().any(|kind| kind == event.event.kind()) {
+                continue;
+            }
+
+            let body = match serde_json::to_vec(&*event) {
+                Ok(body) => body,
+                Err(err) => {
+                    warn!(%err, seq = event.seq, "failed to serialize webhook event");
+                    continue;
+                }
+            };
+            let signature = config.secret.as_deref().map(|secret| sign(secret, &body));
+            for url in &config.urls {
+                tokio::spawn(deliver(
+                    client.clone(),
+                    url.clone(),
+                    body.clone(),
+                    signature.clone(),
+                    config.clone(),
+                ));
+            }
+        }
+    });
+}
+
+/// POSTs one event to one URL, retrying server errors, 429s and network
+/// failures with exponential backoff.
+async fn deliver(client: reqwest::Client, url: String, body: Vec<u8>, signature: Option<String>, config: Arc<WebhookConfig>) {
+    for attempt in 0..=config.max_retries {
+        if attempt > 0 {
+            tokio::time::sleep(backoff(attempt, Duration::from_millis(config.initial_backoff_ms))).await;
+        }
+
+        let mut request = client
+            .post(&url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body.clone());
+        if let Some(signature) = &signature {
+            request = request.header(SIGNATURE_HEADER, signature);
+        }
+
+        match request.send().await {
+            Ok(response) if response.status().is_success() => {
+                debug!(%url, attempt, "webhook delivered");
+                return;
+            }
+            Ok(response) if response.status().is_client_error() && response.status() != reqwest::StatusCode::TOO_MANY_REQUESTS => {
+                warn!(%url, status = response.status().as_u16(), "webhook rejected, not retrying");
+                return;
+            }
+            Ok(response) => warn!(%url, attempt, status = response.status().as_u16(), "webhook delivery failed"),
+            Err(err) => warn!(%url, attempt, %err, "webhook delivery failed"),
+        }
+    }
+    warn!(%url, retries = config.max_retries, "giving up on webhook delivery");
+}
+
+/// Wait before retry number `attempt` (1-based): `initial`, doubled each time, capped at `MAX_BACKOFF`.
+fn backoff(attempt: u32, initial: Duration) -> Duration {
+    initial.saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1))).min(MAX_BACKOFF)
+}
+
+fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use warp::Filter;
+    use crate::events::EventBus;
+    use poker_core::events::GameEvent;
+
+    #[test]
+    fn test_backoff_doubles_and_caps() {
+        let initial = Duration::from_millis(500);
+        assert_eq!(backoff(1, initial), Duration::from_millis(500));
+        assert_eq!(backoff(3, initial), Duration::from_secs(2));
+        assert_eq!(backoff(20, initial), MAX_BACKOFF);
+    }
+
+    #[tokio::test]
+    async fn test_failed_delivery_is_retried() {
+        // Endpoint that fails the first request and accepts the second
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let (received_tx, mut received_rx) = tokio::sync::mpsc::unbounded_channel();
+        let endpoint = {
+            let attempts = attempts.clone();
+            warp::post().and(warp::body::json()).map(move |body: serde_json::Value| {
+                if attempts.fetch_add(1, Ordering::SeqCst) == 0 {
+                    return warp::http::StatusCode::SERVICE_UNAVAILABLE;
+                }
+                received_tx.send(body).unwrap();
+                warp::http::StatusCode::OK
+            })
+        };
+        let (addr, server) = warp::serve(endpoint).bind_ephemeral(([127, 0, 0, 1], 0));
+        tokio::spawn(server);
+
+        let mut bus = EventBus::new("main");
+        let config = WebhookConfig {
+            urls: vec![format!("http://{addr}/hook")],
+            initial_backoff_ms: 10,
+            ..WebhookConfig::default()
+        };
+        spawn_webhooks(config, "main".to_string(), bus.sender().subscribe());
+
+        bus.publish(GameEvent::TablePaused); // Not subscribed to by default
+        bus.publish(GameEvent::PlayerBusted { player_id: "2".into() });
+
+        let body = received_rx.recv().await.unwrap();
+        assert_eq!(body["type"], "player_busted");
+        assert_eq!(body["table_id"], "main");
+        assert_eq!(body["seq"], 2);
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+    }
+}